@@ -1,11 +1,36 @@
 "use strict";
 const vscode = require("vscode");
 const { spawn } = require("child_process");
+const { LanguageClient } = require("vscode-languageclient/node");
 
 const AUTOSAVE_DEBOUNCE_MS = 300;
 let autosaveTimeouts = new Map();
+let client;
+
+function jadePath() {
+  return vscode.workspace.getConfiguration("jade").get("path") || "jade";
+}
 
 function activate(context) {
+  // Language server: diagnostics, symbols, definition, hover and completion from `jade lsp`
+  const server = { command: jadePath(), args: ["lsp"], options: { shell: process.platform === "win32" } };
+  client = new LanguageClient(
+    "jade",
+    "Jade Language Server",
+    { run: server, debug: server },
+    { documentSelector: [{ language: "jade" }] }
+  );
+  client.start();
+
+  // Debugging: VS Code talks the Debug Adapter Protocol to `jade debug --dap`
+  context.subscriptions.push(
+    vscode.debug.registerDebugAdapterDescriptorFactory("jade", {
+      createDebugAdapterDescriptor() {
+        return new vscode.DebugAdapterExecutable(jadePath(), ["debug", "--dap"]);
+      },
+    })
+  );
+
   // Run from buffer: pipe current document to `jade -` (no save needed)
   context.subscriptions.push(
     vscode.commands.registerCommand("jade.runFromBuffer", async () => {
@@ -23,7 +48,7 @@ function activate(context) {
       channel.clear();
       channel.show(true);
 
-      const jade = spawn("jade", ["-"], {
+      const jade = spawn(jadePath(), ["-"], {
         cwd: cwd || process.cwd(),
         stdio: ["pipe", "pipe", "pipe"],
         shell: process.platform === "win32",
@@ -71,6 +96,7 @@ function activate(context) {
 function deactivate() {
   for (const t of autosaveTimeouts.values()) clearTimeout(t);
   autosaveTimeouts.clear();
+  return client ? client.stop() : undefined;
 }
 
 module.exports = { activate, deactivate };
@@ -0,0 +1,216 @@
+//! Single-file executables for `jade build --bundle`: a copy of the `jade` binary followed by an
+//! archive of the program and every module it imports.
+//!
+//! The archive ends with a trailer (its length and [`MAGIC`]), so a binary only has to read the
+//! end of its own file at startup to know whether it carries a program. Imports are resolved at
+//! build time exactly as `jade run` resolves them; at run time they are served from the archive.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::error::JResult;
+use crate::interpreter::{parse_source, Interpreter};
+use crate::lexer::{Lexer, TokenType};
+
+/// Last bytes of every bundled executable.
+pub const MAGIC: &[u8; 8] = b"JADEBNDL";
+/// Archive length (`u64`, little endian) followed by [`MAGIC`].
+const TRAILER_LEN: u64 = 16;
+
+/// A source file packed into a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundledFile {
+    /// Where the file was read from at build time; shown in module values.
+    pub path: String,
+    pub source: String,
+}
+
+/// A program and the modules it imports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bundle {
+    pub entry: BundledFile,
+    /// Imported modules by module path (`import util.text` is `util/text`).
+    pub modules: BTreeMap<String, BundledFile>,
+}
+
+/// Module paths named by the `import` statements in `source`.
+fn imports(source: &str) -> Result<Vec<String>, String> {
+    let tokens = Lexer::new(source)
+        .tokenize()
+        .map_err(|e| format!("Lexer error: {}", e))?;
+    let mut paths = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens[i].token_type != TokenType::Import {
+            i += 1;
+            continue;
+        }
+        let mut segments = Vec::new();
+        i += 1;
+        while let Some(TokenType::Identifier(name)) = tokens.get(i).map(|t| &t.token_type) {
+            segments.push(name.clone());
+            i += 1;
+            if tokens.get(i).map(|t| &t.token_type) != Some(&TokenType::Dot) {
+                break;
+            }
+            i += 1;
+        }
+        if !segments.is_empty() {
+            paths.push(segments.join("/"));
+        }
+    }
+    Ok(paths)
+}
+
+fn read_source(path: &Path) -> Result<String, String> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_source(&source).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(source)
+}
+
+impl Bundle {
+    /// Read `file` and, transitively, every module it imports. Missing modules and syntax errors
+    /// are reported now rather than when the executable runs.
+    pub fn collect(file: &Path) -> Result<Bundle, String> {
+        let mut resolver = Interpreter::new();
+        for path in crate::testing::module_search_paths(file) {
+            resolver.add_module_search_path(path);
+        }
+
+        let entry = BundledFile {
+            path: file.display().to_string(),
+            source: read_source(file)?,
+        };
+        let mut modules = BTreeMap::new();
+        let mut queue = vec![entry.clone()];
+        while let Some(importer) = queue.pop() {
+            let paths =
+                imports(&importer.source).map_err(|e| format!("{}: {}", importer.path, e))?;
+            for module in paths {
+                if modules.contains_key(&module) {
+                    continue;
+                }
+                let path = resolver
+                    .resolve_module_path(&module)
+                    .map_err(|e| format!("{} (imported by {})", e, importer.path))?;
+                let file = BundledFile {
+                    source: read_source(Path::new(&path))?,
+                    path,
+                };
+                queue.push(file.clone());
+                modules.insert(module, file);
+            }
+        }
+        Ok(Bundle { entry, modules })
+    }
+
+    /// Encode as an archive: the entry point, then each module with its module path.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut str = |s: &str| {
+            out.extend_from_slice(&(s.len() as u64).to_le_bytes());
+            out.extend_from_slice(s.as_bytes());
+        };
+        str(&self.entry.path);
+        str(&self.entry.source);
+        for (module, file) in &self.modules {
+            str(module);
+            str(&file.path);
+            str(&file.source);
+        }
+        out
+    }
+
+    /// Decode an archive produced by [`Bundle::serialize`].
+    pub fn deserialize(bytes: &[u8]) -> Result<Bundle, String> {
+        let mut r = Reader { bytes, pos: 0 };
+        let entry = BundledFile {
+            path: r.str()?,
+            source: r.str()?,
+        };
+        let mut modules = BTreeMap::new();
+        while r.pos < bytes.len() {
+            let module = r.str()?;
+            let file = BundledFile {
+                path: r.str()?,
+                source: r.str()?,
+            };
+            modules.insert(module, file);
+        }
+        Ok(Bundle { entry, modules })
+    }
+
+    /// Write a copy of the `runtime` executable with this bundle appended to `output`.
+    pub fn write_executable(&self, runtime: &Path, output: &Path) -> Result<(), String> {
+        // Copying keeps the executable permission bits.
+        fs::copy(runtime, output)
+            .map_err(|e| format!("Failed to copy {}: {}", runtime.display(), e))?;
+        let archive = self.serialize();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(output)
+            .and_then(|mut file| {
+                file.write_all(&archive)?;
+                file.write_all(&(archive.len() as u64).to_le_bytes())?;
+                file.write_all(MAGIC)
+            })
+            .map_err(|e| format!("Failed to write {}: {}", output.display(), e))
+    }
+
+    /// The bundle appended to `executable`, or `None` for a plain `jade` binary.
+    pub fn from_executable(executable: &Path) -> Result<Option<Bundle>, String> {
+        let Ok(mut file) = fs::File::open(executable) else {
+            return Ok(None);
+        };
+        let mut trailer = [0u8; TRAILER_LEN as usize];
+        if file.seek(SeekFrom::End(-(TRAILER_LEN as i64))).is_err()
+            || file.read_exact(&mut trailer).is_err()
+            || &trailer[8..] != MAGIC
+        {
+            return Ok(None);
+        }
+        let len = u64::from_le_bytes(trailer[..8].try_into().unwrap());
+        let mut archive = Vec::new();
+        file.seek(SeekFrom::End(-((TRAILER_LEN + len) as i64)))
+            .and_then(|_| file.take(len).read_to_end(&mut archive))
+            .map_err(|e| format!("Failed to read the bundled program: {}", e))?;
+        Bundle::deserialize(&archive).map(Some)
+    }
+
+    /// Run the entry point, serving its imports from the bundle. Errors carry their source lines.
+    pub fn run(&self) -> JResult<()> {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_program_path(&self.entry.path);
+        for (module, file) in &self.modules {
+            interpreter.add_bundled_module(module, &file.path, &file.source);
+        }
+        interpreter
+            .run(&self.entry.source)
+            .map_err(|e| interpreter.describe_error(&e, &self.entry.source).into())
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], String> {
+        let slice = self
+            .bytes
+            .get(self.pos..self.pos.saturating_add(n))
+            .ok_or("truncated bundle")?;
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn str(&mut self) -> Result<String, String> {
+        let len = u64::from_le_bytes(self.take(8)?.try_into().unwrap());
+        let bytes = self.take(len as usize)?.to_vec();
+        String::from_utf8(bytes).map_err(|_| "invalid UTF-8 in bundle".to_string())
+    }
+}
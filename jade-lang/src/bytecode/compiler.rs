@@ -0,0 +1,571 @@
+//! AST to bytecode. Every expression leaves exactly one value on the stack; statements in a
+//! block are separated by `Pop`, so a block's value is its last statement's, as in the
+//! tree-walking interpreter.
+//!
+//! Top-level variables of the program are globals (functions see their current values, like
+//! closures over the top-level scope); everything declared in nested blocks or function bodies
+//! gets a local slot.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::{Constant, Function, Op, Program, BINARY_OPS, UNARY_OPS};
+use crate::parser::{AstNode, BinaryOp};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompileError {
+    /// The program uses a construct the bytecode compiler does not handle yet.
+    Unsupported(String),
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::Unsupported(what) => write!(f, "not supported by the bytecode compiler: {}", what),
+        }
+    }
+}
+
+/// Declared types whose values the interpreter converts on declaration (e.g. a list into a `vec`).
+pub(crate) const CONVERTING_TYPES: &[&str] = &[
+    "vec", "vector", "mat", "matrix", "set", "counter", "deque", "priorityq", "graph", "grid",
+    "tree", "span", "mut_span", "chunk", "sparse", "ring",
+];
+
+/// Compile a parsed program. Its `source_hash` is left at 0 for the caller to fill in.
+pub fn compile(ast: &AstNode) -> Result<Program, CompileError> {
+    let statements = match ast {
+        AstNode::Block(statements) => statements.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+
+    let mut compiler = Compiler::default();
+    // Top-level functions first, so calls can refer to functions declared later in the file.
+    let mut declarations = Vec::new();
+    for stmt in statements {
+        if let AstNode::FunctionDeclaration {
+            name,
+            params,
+            body,
+            decorators,
+            ..
+        } = stmt
+        {
+            if !decorators.is_empty() {
+                return Err(unsupported(&format!("decorated function '{}'", name)));
+            }
+            if compiler.function_index.contains_key(name) {
+                return Err(unsupported(&format!("redefinition of function '{}'", name)));
+            }
+            let index = compiler.functions.len() as u32;
+            compiler.function_index.insert(name.clone(), index);
+            compiler.functions.push(Function {
+                name: name.clone(),
+                arity: params.len() as u32,
+                locals: 0,
+                code: Vec::new(),
+                positions: Vec::new(),
+            });
+            declarations.push((index, params, body));
+        }
+    }
+
+    for (index, params, body) in declarations {
+        let mut state = FunctionState::default();
+        let mut param_scope = HashMap::new();
+        for (_, param) in params.iter() {
+            let slot = state.locals;
+            state.locals += 1;
+            param_scope.insert(param.clone(), slot);
+        }
+        state.scopes.push(param_scope);
+        compiler.expression(&mut state, body)?;
+        state.emit(Op::Return);
+        let function = &mut compiler.functions[index as usize];
+        function.locals = state.locals;
+        function.code = state.code;
+        function.positions = state.positions;
+    }
+
+    let mut main = FunctionState::default();
+    compiler.statements(&mut main, statements)?;
+    main.emit(Op::Return);
+    let main_index = compiler.functions.len() as u32;
+    compiler.functions.push(Function {
+        name: "<main>".to_string(),
+        arity: 0,
+        locals: main.locals,
+        code: main.code,
+        positions: main.positions,
+    });
+
+    Ok(Program {
+        source_hash: 0,
+        constants: compiler.constants,
+        functions: compiler.functions,
+        main: main_index,
+    })
+}
+
+fn unsupported(what: &str) -> CompileError {
+    CompileError::Unsupported(what.to_string())
+}
+
+/// Variant name of a node (`Match`, `Lambda`, ...) for error messages.
+fn node_kind(node: &AstNode) -> String {
+    format!("{:?}", node)
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect()
+}
+
+#[derive(Default)]
+struct Compiler {
+    constants: Vec<Constant>,
+    functions: Vec<Function>,
+    function_index: HashMap<String, u32>,
+}
+
+/// Per-function compilation state.
+#[derive(Default)]
+struct FunctionState {
+    code: Vec<Op>,
+    /// Source position of each instruction in `code`.
+    positions: Vec<(u32, u32)>,
+    /// Position of the innermost spanned node being compiled.
+    position: (u32, u32),
+    /// Block scopes, innermost last. Empty at the top level of the program, where names are globals.
+    scopes: Vec<HashMap<String, u32>>,
+    locals: u32,
+    /// Values on the operand stack at this point (locals excluded).
+    depth: u32,
+    loops: Vec<Loop>,
+}
+
+struct Loop {
+    /// Stack depth inside the loop (after its accumulated value).
+    depth: u32,
+    /// Iterating with `IterNext` (a `for`), which `break` must abandon.
+    iterates: bool,
+    start: u32,
+    breaks: Vec<usize>,
+}
+
+/// How a name resolves in the current function.
+enum Slot {
+    Local(u32),
+    Global,
+}
+
+impl FunctionState {
+    fn emit(&mut self, op: Op) -> usize {
+        let (pops, pushes) = match op {
+            Op::Const(_) | Op::Dup | Op::LoadLocal(_) | Op::LoadGlobal(_) => (0, 1),
+            Op::Pop | Op::StoreLocal(_) | Op::DefineGlobal(_) | Op::StoreGlobal(_) => (1, 0),
+            Op::Binary(_) | Op::Index => (2, 1),
+            Op::Unary(_) | Op::RequireBool(_) | Op::Swap => (0, 0),
+            Op::Jump(_) | Op::JumpIfFalseKeep(_) | Op::JumpIfTrueKeep(_) | Op::IterEnd => (0, 0),
+            Op::JumpIfFalse(_) | Op::IterStart | Op::Return => (1, 0),
+            Op::Call { argc, .. } | Op::CallBuiltin { argc, .. } => (argc, 1),
+            Op::MakeList(n) | Op::MakeTuple(n) | Op::Concat(n) => (n, 1),
+            Op::IterNext(_) => (0, 1),
+        };
+        self.depth = self.depth - pops + pushes;
+        self.code.push(op);
+        self.positions.push(self.position);
+        self.code.len() - 1
+    }
+
+    fn here(&self) -> u32 {
+        self.code.len() as u32
+    }
+
+    /// Point the jump at `at` to the current position.
+    fn patch(&mut self, at: usize) {
+        let target = self.here();
+        match &mut self.code[at] {
+            Op::Jump(t) | Op::JumpIfFalse(t) | Op::JumpIfFalseKeep(t) | Op::JumpIfTrueKeep(t) => {
+                *t = target
+            }
+            Op::IterNext(t) => *t = target,
+            _ => unreachable!("patching a non-jump instruction"),
+        }
+    }
+
+    fn resolve(&self, name: &str) -> Slot {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied())
+            .map_or(Slot::Global, Slot::Local)
+    }
+
+    /// Slot for `name` in the innermost scope, declaring it if needed (`None` at the top level).
+    fn declare(&mut self, name: &str) -> Option<u32> {
+        let next = self.locals;
+        let scope = self.scopes.last_mut()?;
+        let slot = *scope.entry(name.to_string()).or_insert(next);
+        if slot == next {
+            self.locals += 1;
+        }
+        Some(slot)
+    }
+}
+
+impl Compiler {
+    fn constant(&mut self, value: Constant) -> u32 {
+        if let Some(i) = self.constants.iter().position(|c| c == &value) {
+            return i as u32;
+        }
+        self.constants.push(value);
+        (self.constants.len() - 1) as u32
+    }
+
+    fn name(&mut self, name: &str) -> u32 {
+        self.constant(Constant::Str(name.to_string()))
+    }
+
+    /// Statements of a block in the current scope; leaves the last one's value (or `None`).
+    fn statements(&mut self, state: &mut FunctionState, statements: &[AstNode]) -> Result<(), CompileError> {
+        if statements.is_empty() {
+            let none = self.constant(Constant::None);
+            state.emit(Op::Const(none));
+            return Ok(());
+        }
+        for (i, stmt) in statements.iter().enumerate() {
+            if i > 0 {
+                state.emit(Op::Pop);
+            }
+            self.statement(state, stmt)?;
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, state: &mut FunctionState, stmt: &AstNode) -> Result<(), CompileError> {
+        match stmt {
+            // Compiled up front; only allowed at the top level of the program.
+            AstNode::FunctionDeclaration { name, .. } => {
+                if !state.scopes.is_empty() || !state.loops.is_empty() {
+                    return Err(unsupported(&format!("nested function '{}'", name)));
+                }
+                let none = self.constant(Constant::None);
+                state.emit(Op::Const(none));
+                Ok(())
+            }
+            AstNode::Defer(_) => Err(unsupported("Defer")),
+            other => self.expression(state, other),
+        }
+    }
+
+    fn store(&mut self, state: &mut FunctionState, name: &str, declare: bool) -> Result<(), CompileError> {
+        if self.function_index.contains_key(name) && matches!(state.resolve(name), Slot::Global) {
+            return Err(unsupported(&format!("assignment to function name '{}'", name)));
+        }
+        let slot = if declare {
+            state.declare(name).map_or(Slot::Global, Slot::Local)
+        } else {
+            state.resolve(name)
+        };
+        let op = match slot {
+            Slot::Local(slot) => Op::StoreLocal(slot),
+            Slot::Global if declare => Op::DefineGlobal(self.name(name)),
+            Slot::Global => Op::StoreGlobal(self.name(name)),
+        };
+        state.emit(op);
+        Ok(())
+    }
+
+    fn expression(&mut self, state: &mut FunctionState, node: &AstNode) -> Result<(), CompileError> {
+        let outer = state.position;
+        if let Some(span) = node.span() {
+            state.position = (span.line as u32, span.column as u32);
+        }
+        let result = self.node(state, node);
+        state.position = outer;
+        result
+    }
+
+    /// [`Compiler::expression`] without tracking its position.
+    fn node(&mut self, state: &mut FunctionState, node: &AstNode) -> Result<(), CompileError> {
+        match node {
+            AstNode::Integer(i) => self.push_constant(state, Constant::Int(*i)),
+            AstNode::Float(f) => self.push_constant(state, Constant::Float(*f)),
+            AstNode::String(s) => self.push_constant(state, Constant::Str(s.clone())),
+            AstNode::Boolean(b) => self.push_constant(state, Constant::Bool(*b)),
+            AstNode::Char(c) => self.push_constant(state, Constant::Char(*c)),
+
+            AstNode::Identifier(name, _) => match state.resolve(name) {
+                Slot::Local(slot) => {
+                    state.emit(Op::LoadLocal(slot));
+                }
+                Slot::Global if self.function_index.contains_key(name) => {
+                    return Err(unsupported(&format!("function '{}' used as a value", name)))
+                }
+                Slot::Global => {
+                    let name = self.name(name);
+                    state.emit(Op::LoadGlobal(name));
+                }
+            },
+
+            AstNode::StringInterpolation { parts } => {
+                for part in parts {
+                    self.expression(state, part)?;
+                }
+                state.emit(Op::Concat(parts.len() as u32));
+            }
+            AstNode::List(elements) => {
+                for element in elements {
+                    self.expression(state, element)?;
+                }
+                state.emit(Op::MakeList(elements.len() as u32));
+            }
+            AstNode::Tuple(elements) => {
+                for element in elements {
+                    self.expression(state, element)?;
+                }
+                state.emit(Op::MakeTuple(elements.len() as u32));
+            }
+            AstNode::Index { object, index, .. } => {
+                self.expression(state, object)?;
+                self.expression(state, index)?;
+                state.emit(Op::Index);
+            }
+
+            AstNode::Block(statements) => {
+                state.scopes.push(HashMap::new());
+                self.statements(state, statements)?;
+                state.scopes.pop();
+            }
+            AstNode::Expression(expr) => self.expression(state, expr)?,
+            // `return` yields its value without leaving the function, as in the interpreter.
+            AstNode::Return(expr, _) => match expr {
+                Some(expr) => self.expression(state, expr)?,
+                None => self.push_constant(state, Constant::None),
+            },
+
+            AstNode::VarDeclaration {
+                var_type,
+                name,
+                value,
+                is_static,
+                type_modifier,
+                ..
+            } => {
+                if *is_static || type_modifier.is_some() {
+                    return Err(unsupported(&format!("declaration modifiers on '{}'", name)));
+                }
+                if CONVERTING_TYPES.contains(&var_type.as_str()) {
+                    return Err(unsupported(&format!("'{}' declarations", var_type)));
+                }
+                self.expression(state, value)?;
+                state.emit(Op::Dup);
+                self.store(state, name, true)?;
+            }
+            AstNode::Assignment { name, value, .. } => {
+                self.expression(state, value)?;
+                state.emit(Op::Dup);
+                self.store(state, name, false)?;
+            }
+
+            AstNode::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } => self.binary(state, left, operator, right)?,
+            AstNode::Unary { operator, operand, .. } => {
+                self.expression(state, operand)?;
+                let code = UNARY_OPS
+                    .iter()
+                    .position(|op| op == operator)
+                    .ok_or_else(|| unsupported(&format!("operator {:?}", operator)))?;
+                state.emit(Op::Unary(code as u8));
+            }
+
+            AstNode::FunctionCall { name, args, .. } => {
+                if let Slot::Local(_) = state.resolve(name) {
+                    return Err(unsupported(&format!("call through variable '{}'", name)));
+                }
+                let func = self.function_index.get(name).copied();
+                if let Some(func) = func {
+                    let arity = self.functions[func as usize].arity;
+                    if arity as usize != args.len() {
+                        return Err(unsupported(&format!(
+                            "call to '{}' with {} arguments (declared with {})",
+                            name,
+                            args.len(),
+                            arity
+                        )));
+                    }
+                }
+                for arg in args {
+                    self.expression(state, arg)?;
+                }
+                let argc = args.len() as u32;
+                match func {
+                    Some(func) => state.emit(Op::Call { func, argc }),
+                    None => {
+                        let name = self.name(name);
+                        state.emit(Op::CallBuiltin { name, argc })
+                    }
+                };
+            }
+
+            AstNode::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.expression(state, condition)?;
+                let to_else = state.emit(Op::JumpIfFalse(0));
+                self.expression(state, then_branch)?;
+                let to_end = state.emit(Op::Jump(0));
+                state.patch(to_else);
+                state.depth -= 1;
+                match else_branch {
+                    Some(else_branch) => self.expression(state, else_branch)?,
+                    None => self.push_constant(state, Constant::None),
+                }
+                state.patch(to_end);
+            }
+
+            // Loops evaluate to their body's last value, so they keep it beneath the body's.
+            AstNode::While { condition, body, .. } => {
+                self.push_constant(state, Constant::None);
+                let start = state.here();
+                self.expression(state, condition)?;
+                let exit = state.emit(Op::JumpIfFalse(0));
+                self.loop_body(state, body, start, false)?;
+                state.patch(exit);
+                self.finish_loop(state);
+            }
+            AstNode::For {
+                var,
+                iterable,
+                body,
+                ..
+            } => {
+                self.expression(state, iterable)?;
+                state.emit(Op::IterStart);
+                self.push_constant(state, Constant::None);
+                let start = state.here();
+                let exit = state.emit(Op::IterNext(0));
+                // The loop variable lives in the scope around the loop.
+                self.store(state, var, true)?;
+                self.loop_body(state, body, start, true)?;
+                state.patch(exit);
+                self.finish_loop(state);
+            }
+            AstNode::Break(_) => {
+                let Some(innermost) = state.loops.last() else {
+                    return Err(unsupported("break outside a loop"));
+                };
+                let (depth, iterates) = (innermost.depth, innermost.iterates);
+                let resume = state.depth;
+                for _ in depth..state.depth {
+                    state.emit(Op::Pop);
+                }
+                if iterates {
+                    state.emit(Op::IterEnd);
+                }
+                let jump = state.emit(Op::Jump(0));
+                if let Some(innermost) = state.loops.last_mut() {
+                    innermost.breaks.push(jump);
+                }
+                // Unreachable from here on; account for the value `break` stands for.
+                state.depth = resume + 1;
+            }
+            AstNode::Continue(_) => {
+                let Some(innermost) = state.loops.last() else {
+                    return Err(unsupported("continue outside a loop"));
+                };
+                let (depth, start) = (innermost.depth, innermost.start);
+                let resume = state.depth;
+                for _ in depth..state.depth {
+                    state.emit(Op::Pop);
+                }
+                state.emit(Op::Jump(start));
+                state.depth = resume + 1;
+            }
+
+            other => return Err(CompileError::Unsupported(node_kind(other))),
+        }
+        Ok(())
+    }
+
+    fn push_constant(&mut self, state: &mut FunctionState, value: Constant) {
+        let index = self.constant(value);
+        state.emit(Op::Const(index));
+    }
+
+    /// Body of a loop whose accumulated value is on top of the stack: run it, keep its value,
+    /// and jump back to `start`.
+    fn loop_body(
+        &mut self,
+        state: &mut FunctionState,
+        body: &AstNode,
+        start: u32,
+        iterates: bool,
+    ) -> Result<(), CompileError> {
+        state.loops.push(Loop {
+            depth: state.depth,
+            iterates,
+            start,
+            breaks: Vec::new(),
+        });
+        self.expression(state, body)?;
+        state.emit(Op::Swap);
+        state.emit(Op::Pop);
+        state.emit(Op::Jump(start));
+        Ok(())
+    }
+
+    /// Land the innermost loop's `break`s here, leaving the accumulated value.
+    fn finish_loop(&mut self, state: &mut FunctionState) {
+        if let Some(finished) = state.loops.pop() {
+            for jump in finished.breaks {
+                state.patch(jump);
+            }
+            state.depth = finished.depth;
+        }
+    }
+
+    fn binary(
+        &mut self,
+        state: &mut FunctionState,
+        left: &AstNode,
+        operator: &BinaryOp,
+        right: &AstNode,
+    ) -> Result<(), CompileError> {
+        // `and` / `or` short-circuit and only accept booleans.
+        let short_circuit = match operator {
+            BinaryOp::And => Some(0),
+            BinaryOp::Or => Some(1),
+            _ => None,
+        };
+        if let Some(code) = short_circuit {
+            self.expression(state, left)?;
+            state.emit(Op::RequireBool(code));
+            let skip = state.emit(if code == 0 {
+                Op::JumpIfFalseKeep(0)
+            } else {
+                Op::JumpIfTrueKeep(0)
+            });
+            state.emit(Op::Pop);
+            self.expression(state, right)?;
+            state.emit(Op::RequireBool(code));
+            state.patch(skip);
+            return Ok(());
+        }
+
+        let code = BINARY_OPS
+            .iter()
+            .position(|op| op == operator)
+            .ok_or_else(|| unsupported(&format!("operator {:?}", operator)))?;
+        self.expression(state, left)?;
+        self.expression(state, right)?;
+        state.emit(Op::Binary(code as u8));
+        Ok(())
+    }
+}
@@ -0,0 +1,566 @@
+//! Bytecode for Jade: a compact instruction set, a compiler from the AST, a stack VM, and the
+//! `.jdc` cache that lets `jade run` skip lexing and parsing for unchanged files.
+//!
+//! The compiler covers the core of the language (literals, variables, arithmetic, `if`, loops,
+//! top-level functions, builtin calls). Programs that use anything else are reported as
+//! [`CompileError::Unsupported`] and run on the tree-walking interpreter instead.
+
+mod compiler;
+mod vm;
+
+pub use compiler::{compile, CompileError};
+pub(crate) use compiler::CONVERTING_TYPES;
+pub use vm::Vm;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::JResult;
+use crate::interpreter::Interpreter;
+use crate::parser::{BinaryOp, UnaryOp};
+
+/// Leading bytes of every `.jdc` file.
+pub const MAGIC: &[u8; 4] = b"JDC\0";
+/// Bumped whenever the encoding or instruction set changes; older caches are recompiled.
+pub const FORMAT_VERSION: u16 = 2;
+
+/// Literal values referenced by `Const` and by name operands.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Char(char),
+}
+
+/// One instruction. Operands are indices into [`Program::constants`] or [`Program::functions`],
+/// local slots, or absolute jump targets within the current function.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    /// Push a constant.
+    Const(u32),
+    Pop,
+    Dup,
+    /// Exchange the top two values.
+    Swap,
+    LoadLocal(u32),
+    /// Pop into a local slot.
+    StoreLocal(u32),
+    /// Push a global by name (constant index); falls back to the interpreter's builtins like `PI`.
+    LoadGlobal(u32),
+    /// Pop into a new or existing global.
+    DefineGlobal(u32),
+    /// Pop into an existing global; an error if it was never declared.
+    StoreGlobal(u32),
+    /// Pop two operands, push the result (index into [`BINARY_OPS`]).
+    Binary(u8),
+    /// Pop one operand, push the result (index into [`UNARY_OPS`]).
+    Unary(u8),
+    /// Fail unless the top of the stack is a bool (operands of `and` / `or`).
+    RequireBool(u8),
+    Jump(u32),
+    /// Pop; jump if falsy.
+    JumpIfFalse(u32),
+    /// Jump if the top is `false`, leaving it in place (short-circuit `and`).
+    JumpIfFalseKeep(u32),
+    /// Jump if the top is `true`, leaving it in place (short-circuit `or`).
+    JumpIfTrueKeep(u32),
+    /// Call a compiled function with the top `argc` values (a builtin of the same name wins).
+    Call { func: u32, argc: u32 },
+    /// Call a builtin by name (constant index) with the top `argc` values.
+    CallBuiltin { name: u32, argc: u32 },
+    /// Return the top of the stack from the current function.
+    Return,
+    /// Pop `n` values into a list.
+    MakeList(u32),
+    /// Pop `n` values into a tuple.
+    MakeTuple(u32),
+    /// Pop index and object, push `object[index]`.
+    Index,
+    /// Pop `n` values and push their concatenated display strings (string interpolation).
+    Concat(u32),
+    /// Pop an iterable and start iterating it.
+    IterStart,
+    /// Push the next item, or finish the innermost iteration and jump.
+    IterNext(u32),
+    /// Abandon the innermost iteration (`break`).
+    IterEnd,
+}
+
+/// Binary operators by their [`Op::Binary`] code.
+pub const BINARY_OPS: &[BinaryOp] = &[
+    BinaryOp::Add,
+    BinaryOp::Subtract,
+    BinaryOp::Multiply,
+    BinaryOp::Divide,
+    BinaryOp::Modulo,
+    BinaryOp::Power,
+    BinaryOp::Equal,
+    BinaryOp::NotEqual,
+    BinaryOp::Less,
+    BinaryOp::Greater,
+    BinaryOp::LessEqual,
+    BinaryOp::GreaterEqual,
+    BinaryOp::ConstantTimeEq,
+    BinaryOp::BitwiseAnd,
+    BinaryOp::BitwiseOr,
+    BinaryOp::BitwiseXor,
+    BinaryOp::LeftShift,
+    BinaryOp::RightShift,
+];
+
+/// Unary operators by their [`Op::Unary`] code.
+pub const UNARY_OPS: &[UnaryOp] = &[UnaryOp::Minus, UnaryOp::Not, UnaryOp::BitwiseNot];
+
+/// A compiled function. Parameters occupy the first `arity` local slots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub arity: u32,
+    pub locals: u32,
+    pub code: Vec<Op>,
+    /// Source line and column of each instruction, for error messages; `(0, 0)` when unknown.
+    pub positions: Vec<(u32, u32)>,
+}
+
+/// A compiled program: `functions[main]` is the top-level code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    pub source_hash: u64,
+    pub constants: Vec<Constant>,
+    pub functions: Vec<Function>,
+    pub main: u32,
+}
+
+/// FNV-1a over the source text; stable across builds and platforms, unlike `DefaultHasher`.
+pub fn source_hash(source: &str) -> u64 {
+    source.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl Program {
+    /// Encode as a `.jdc` file.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut w = Writer(Vec::new());
+        w.0.extend_from_slice(MAGIC);
+        w.0.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        w.u64(self.source_hash);
+        w.u32(self.constants.len() as u32);
+        for c in &self.constants {
+            match c {
+                Constant::None => w.u8(0),
+                Constant::Bool(b) => {
+                    w.u8(1);
+                    w.u8(*b as u8);
+                }
+                Constant::Int(i) => {
+                    w.u8(2);
+                    w.u64(*i as u64);
+                }
+                Constant::Float(f) => {
+                    w.u8(3);
+                    w.u64(f.to_bits());
+                }
+                Constant::Str(s) => {
+                    w.u8(4);
+                    w.str(s);
+                }
+                Constant::Char(c) => {
+                    w.u8(5);
+                    w.u32(*c as u32);
+                }
+            }
+        }
+        w.u32(self.functions.len() as u32);
+        for f in &self.functions {
+            w.str(&f.name);
+            w.u32(f.arity);
+            w.u32(f.locals);
+            w.u32(f.code.len() as u32);
+            for op in &f.code {
+                w.op(op);
+            }
+            for &(line, column) in &f.positions {
+                w.u32(line);
+                w.u32(column);
+            }
+        }
+        w.u32(self.main);
+        w.0
+    }
+
+    /// Decode a `.jdc` file produced by [`Program::serialize`].
+    pub fn deserialize(bytes: &[u8]) -> Result<Program, String> {
+        let mut r = Reader { bytes, pos: 0 };
+        if r.take(4)? != MAGIC {
+            return Err("not a Jade bytecode file".to_string());
+        }
+        let version = u16::from_le_bytes([r.u8()?, r.u8()?]);
+        if version != FORMAT_VERSION {
+            return Err(format!(
+                "bytecode format version {} is not supported (expected {})",
+                version, FORMAT_VERSION
+            ));
+        }
+        let source_hash = r.u64()?;
+        let mut constants = Vec::new();
+        for _ in 0..r.u32()? {
+            constants.push(match r.u8()? {
+                0 => Constant::None,
+                1 => Constant::Bool(r.u8()? != 0),
+                2 => Constant::Int(r.u64()? as i64),
+                3 => Constant::Float(f64::from_bits(r.u64()?)),
+                4 => Constant::Str(r.str()?),
+                5 => Constant::Char(
+                    char::from_u32(r.u32()?).ok_or("invalid char constant in bytecode")?,
+                ),
+                tag => return Err(format!("unknown constant tag {} in bytecode", tag)),
+            });
+        }
+        let mut functions = Vec::new();
+        for _ in 0..r.u32()? {
+            let name = r.str()?;
+            let arity = r.u32()?;
+            let locals = r.u32()?;
+            let mut code = Vec::new();
+            for _ in 0..r.u32()? {
+                code.push(r.op()?);
+            }
+            let mut positions = Vec::with_capacity(code.len());
+            for _ in 0..code.len() {
+                positions.push((r.u32()?, r.u32()?));
+            }
+            functions.push(Function {
+                name,
+                arity,
+                locals,
+                code,
+                positions,
+            });
+        }
+        let main = r.u32()?;
+        if main as usize >= functions.len() {
+            return Err("bytecode has no entry point".to_string());
+        }
+        let program = Program {
+            source_hash,
+            constants,
+            functions,
+            main,
+        };
+        program.validate()?;
+        Ok(program)
+    }
+
+    /// Check operands against the tables they index and stack effects against the stack, so the
+    /// VM never reads out of bounds.
+    fn validate(&self) -> Result<(), String> {
+        let is_name = |i: u32| matches!(self.constants.get(i as usize), Some(Constant::Str(_)));
+        for f in &self.functions {
+            let in_code = |target: u32| (target as usize) < f.code.len();
+            if f.code.last() != Some(&Op::Return)
+                || f.arity > f.locals
+                || f.positions.len() != f.code.len()
+            {
+                return Err(format!("malformed function '{}' in bytecode", f.name));
+            }
+            for op in &f.code {
+                let valid = match *op {
+                    Op::Const(i) => (i as usize) < self.constants.len(),
+                    Op::LoadLocal(slot) | Op::StoreLocal(slot) => slot < f.locals,
+                    Op::LoadGlobal(name) | Op::DefineGlobal(name) | Op::StoreGlobal(name) => {
+                        is_name(name)
+                    }
+                    Op::CallBuiltin { name, .. } => is_name(name),
+                    Op::Call { func, argc } => self
+                        .functions
+                        .get(func as usize)
+                        .is_some_and(|callee| func != self.main && callee.arity == argc),
+                    Op::RequireBool(code) => code < 2,
+                    Op::Jump(t)
+                    | Op::JumpIfFalse(t)
+                    | Op::JumpIfFalseKeep(t)
+                    | Op::JumpIfTrueKeep(t)
+                    | Op::IterNext(t) => in_code(t),
+                    _ => true,
+                };
+                if !valid {
+                    return Err(format!("invalid operand in {:?} in function '{}'", op, f.name));
+                }
+            }
+            check_stack_depth(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Values `op` pops and pushes.
+fn stack_effect(op: Op) -> (u32, u32) {
+    match op {
+        Op::Const(_) | Op::LoadLocal(_) | Op::LoadGlobal(_) => (0, 1),
+        Op::Pop
+        | Op::StoreLocal(_)
+        | Op::DefineGlobal(_)
+        | Op::StoreGlobal(_)
+        | Op::JumpIfFalse(_)
+        | Op::IterStart
+        | Op::Return => (1, 0),
+        Op::Dup => (1, 2),
+        Op::Swap => (2, 2),
+        Op::Binary(_) | Op::Index => (2, 1),
+        Op::Unary(_) | Op::RequireBool(_) => (1, 1),
+        Op::JumpIfFalseKeep(_) | Op::JumpIfTrueKeep(_) => (1, 1),
+        Op::Call { argc, .. } | Op::CallBuiltin { argc, .. } => (argc, 1),
+        Op::MakeList(n) | Op::MakeTuple(n) | Op::Concat(n) => (n, 1),
+        Op::Jump(_) | Op::IterNext(_) | Op::IterEnd => (0, 0),
+    }
+}
+
+/// Check that no instruction of `f` can pop more values than the function pushed, following
+/// every path from its entry with the lowest stack height each instruction can be reached at.
+fn check_stack_depth(f: &Function) -> Result<(), String> {
+    let mut lowest: Vec<Option<u32>> = vec![None; f.code.len()];
+    let mut pending = vec![(0usize, 0u32)];
+    while let Some((ip, height)) = pending.pop() {
+        if lowest[ip].is_some_and(|seen| seen <= height) {
+            continue;
+        }
+        lowest[ip] = Some(height);
+        let op = f.code[ip];
+        let (pops, pushes) = stack_effect(op);
+        let Some(after) = height.checked_sub(pops) else {
+            return Err(format!(
+                "{:?} in function '{}' pops an empty stack",
+                op, f.name
+            ));
+        };
+        let after = after + pushes;
+        match op {
+            Op::Return => {}
+            Op::Jump(target) => pending.push((target as usize, after)),
+            Op::JumpIfFalse(target) | Op::JumpIfFalseKeep(target) | Op::JumpIfTrueKeep(target) => {
+                pending.push((target as usize, after));
+                pending.push((ip + 1, after));
+            }
+            // The next item is pushed when it continues, nothing when it jumps out
+            Op::IterNext(target) => {
+                pending.push((target as usize, after));
+                pending.push((ip + 1, after + 1));
+            }
+            _ => pending.push((ip + 1, after)),
+        }
+    }
+    Ok(())
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, v: u8) {
+        self.0.push(v);
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn str(&mut self, s: &str) {
+        self.u32(s.len() as u32);
+        self.0.extend_from_slice(s.as_bytes());
+    }
+
+    fn op(&mut self, op: &Op) {
+        match *op {
+            Op::Const(a) => self.op1(0, a),
+            Op::Pop => self.u8(1),
+            Op::Dup => self.u8(2),
+            Op::LoadLocal(a) => self.op1(3, a),
+            Op::StoreLocal(a) => self.op1(4, a),
+            Op::LoadGlobal(a) => self.op1(5, a),
+            Op::DefineGlobal(a) => self.op1(6, a),
+            Op::StoreGlobal(a) => self.op1(7, a),
+            Op::Binary(code) => {
+                self.u8(8);
+                self.u8(code);
+            }
+            Op::Unary(code) => {
+                self.u8(9);
+                self.u8(code);
+            }
+            Op::RequireBool(code) => {
+                self.u8(10);
+                self.u8(code);
+            }
+            Op::Jump(a) => self.op1(11, a),
+            Op::JumpIfFalse(a) => self.op1(12, a),
+            Op::JumpIfFalseKeep(a) => self.op1(13, a),
+            Op::JumpIfTrueKeep(a) => self.op1(14, a),
+            Op::Call { func, argc } => {
+                self.op1(15, func);
+                self.u32(argc);
+            }
+            Op::CallBuiltin { name, argc } => {
+                self.op1(16, name);
+                self.u32(argc);
+            }
+            Op::Return => self.u8(17),
+            Op::MakeList(n) => self.op1(18, n),
+            Op::MakeTuple(n) => self.op1(19, n),
+            Op::Index => self.u8(20),
+            Op::Concat(n) => self.op1(21, n),
+            Op::IterStart => self.u8(22),
+            Op::IterNext(a) => self.op1(23, a),
+            Op::IterEnd => self.u8(24),
+            Op::Swap => self.u8(25),
+        }
+    }
+
+    fn op1(&mut self, opcode: u8, operand: u32) {
+        self.u8(opcode);
+        self.u32(operand);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], String> {
+        let end = self.pos + n;
+        let slice = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or("truncated bytecode file")?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    fn str(&mut self) -> Result<String, String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| "invalid UTF-8 in bytecode".to_string())
+    }
+
+    fn op(&mut self) -> Result<Op, String> {
+        let op = match self.u8()? {
+            0 => Op::Const(self.u32()?),
+            1 => Op::Pop,
+            2 => Op::Dup,
+            3 => Op::LoadLocal(self.u32()?),
+            4 => Op::StoreLocal(self.u32()?),
+            5 => Op::LoadGlobal(self.u32()?),
+            6 => Op::DefineGlobal(self.u32()?),
+            7 => Op::StoreGlobal(self.u32()?),
+            8 => Op::Binary(self.code(BINARY_OPS.len())?),
+            9 => Op::Unary(self.code(UNARY_OPS.len())?),
+            10 => Op::RequireBool(self.u8()?),
+            11 => Op::Jump(self.u32()?),
+            12 => Op::JumpIfFalse(self.u32()?),
+            13 => Op::JumpIfFalseKeep(self.u32()?),
+            14 => Op::JumpIfTrueKeep(self.u32()?),
+            15 => Op::Call {
+                func: self.u32()?,
+                argc: self.u32()?,
+            },
+            16 => Op::CallBuiltin {
+                name: self.u32()?,
+                argc: self.u32()?,
+            },
+            17 => Op::Return,
+            18 => Op::MakeList(self.u32()?),
+            19 => Op::MakeTuple(self.u32()?),
+            20 => Op::Index,
+            21 => Op::Concat(self.u32()?),
+            22 => Op::IterStart,
+            23 => Op::IterNext(self.u32()?),
+            24 => Op::IterEnd,
+            25 => Op::Swap,
+            opcode => return Err(format!("unknown opcode {} in bytecode", opcode)),
+        };
+        Ok(op)
+    }
+
+    fn code(&mut self, limit: usize) -> Result<u8, String> {
+        let code = self.u8()?;
+        if (code as usize) < limit {
+            Ok(code)
+        } else {
+            Err(format!("operator code {} out of range in bytecode", code))
+        }
+    }
+}
+
+/// Where `jade run` caches the bytecode for `source_path`: `.jade/cache/<stem>.jdc` beside it.
+pub fn cache_path(source_path: &Path) -> PathBuf {
+    let stem = source_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "main".to_string());
+    source_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(".jade")
+        .join("cache")
+        .join(format!("{}.jdc", stem))
+}
+
+/// Run a file through the `.jdc` cache: reuse the cached bytecode when its source hash matches,
+/// otherwise parse and compile (refreshing the cache). Programs the compiler does not support run
+/// on the tree-walking interpreter.
+pub fn run_file_cached(
+    source_path: &Path,
+    source: &str,
+    interpreter: &mut Interpreter,
+) -> JResult<()> {
+    let hash = source_hash(source);
+    let cache = cache_path(source_path);
+    let cached = fs::read(&cache)
+        .ok()
+        .and_then(|bytes| Program::deserialize(&bytes).ok())
+        .filter(|program| program.source_hash == hash);
+    if let Some(program) = cached {
+        return run_program(&program, interpreter);
+    }
+
+    let ast = crate::interpreter::parse_source(source)?;
+    match compile(&ast) {
+        Ok(mut program) => {
+            program.source_hash = hash;
+            // The cache is an optimization; an unwritable directory just means no cache.
+            if let Some(dir) = cache.parent() {
+                let _ = fs::create_dir_all(dir).and_then(|_| fs::write(&cache, program.serialize()));
+            }
+            run_program(&program, interpreter)
+        }
+        Err(CompileError::Unsupported(_)) => interpreter.run_ast(&ast),
+    }
+}
+
+/// Execute a compiled program with `interpreter` providing builtins and output.
+pub fn run_program(program: &Program, interpreter: &mut Interpreter) -> JResult<()> {
+    Vm::new(program, interpreter).run()?;
+    interpreter.drain_timers()
+}
@@ -0,0 +1,308 @@
+//! Stack VM for compiled programs. Frames live on the heap, so deep Jade recursion is bounded
+//! only by the interpreter's call depth limit. Operators, indexing and builtins are delegated to
+//! the [`Interpreter`], which keeps results identical to the tree-walker.
+
+use std::collections::HashMap;
+
+use super::{Constant, Op, Program, BINARY_OPS, UNARY_OPS};
+use crate::error::{ErrorKind, JError, JResult, TraceEntry};
+use crate::interpreter::{Interpreter, Value};
+
+struct Frame {
+    func: usize,
+    ip: usize,
+    /// Stack index of local slot 0.
+    base: usize,
+}
+
+pub struct Vm<'a> {
+    program: &'a Program,
+    interpreter: &'a mut Interpreter,
+    constants: Vec<Value>,
+    stack: Vec<Value>,
+    frames: Vec<Frame>,
+    globals: HashMap<String, Value>,
+    /// Remaining items of each active `for` loop, innermost last.
+    iterators: Vec<std::vec::IntoIter<Value>>,
+    /// Per function: whether its name turned out to be a builtin, which then wins (as in the
+    /// interpreter). Decided on the first call.
+    shadowed: Vec<Option<bool>>,
+}
+
+impl<'a> Vm<'a> {
+    pub fn new(program: &'a Program, interpreter: &'a mut Interpreter) -> Self {
+        let constants = program
+            .constants
+            .iter()
+            .map(|c| match c {
+                Constant::None => Value::None,
+                Constant::Bool(b) => Value::Boolean(*b),
+                Constant::Int(i) => Value::Integer(*i),
+                Constant::Float(f) => Value::Float(*f),
+                Constant::Str(s) => Value::String(s.clone()),
+                Constant::Char(c) => Value::Char(*c),
+            })
+            .collect();
+        Self {
+            program,
+            interpreter,
+            constants,
+            stack: Vec::new(),
+            frames: Vec::new(),
+            globals: HashMap::new(),
+            iterators: Vec::new(),
+            shadowed: vec![None; program.functions.len()],
+        }
+    }
+
+    /// Run the program's entry point; returns the value of its last statement.
+    pub fn run(&mut self) -> JResult<Value> {
+        let main = self.program.main as usize;
+        let locals = self.program.functions[main].locals as usize;
+        self.stack.resize(locals, Value::None);
+        self.frames.push(Frame {
+            func: main,
+            ip: 0,
+            base: 0,
+        });
+        self.execute().map_err(|e| self.locate_error(e))
+    }
+
+    fn execute(&mut self) -> JResult<Value> {
+        loop {
+            let frame = self.frames.last_mut().expect("VM frame stack is empty");
+            let op = self.program.functions[frame.func].code[frame.ip];
+            frame.ip += 1;
+            let base = frame.base;
+
+            match op {
+                Op::Const(i) => self.stack.push(self.constants[i as usize].clone()),
+                Op::Pop => {
+                    self.pop();
+                }
+                Op::Dup => {
+                    let top = self.peek().clone();
+                    self.stack.push(top);
+                }
+                Op::Swap => {
+                    let len = self.stack.len();
+                    self.stack.swap(len - 1, len - 2);
+                }
+                Op::LoadLocal(slot) => self.stack.push(self.stack[base + slot as usize].clone()),
+                Op::StoreLocal(slot) => {
+                    let value = self.pop();
+                    self.stack[base + slot as usize] = value;
+                }
+                Op::LoadGlobal(name) => {
+                    let name = self.name(name);
+                    let value = match self.globals.get(name) {
+                        Some(value) => value.clone(),
+                        None => self.interpreter.get_variable(name)?,
+                    };
+                    self.stack.push(value);
+                }
+                Op::DefineGlobal(name) => {
+                    let value = self.pop();
+                    self.globals.insert(self.name(name).to_string(), value);
+                }
+                Op::StoreGlobal(name) => {
+                    let value = self.pop();
+                    let name = self.name(name);
+                    if !self.globals.contains_key(name) && self.interpreter.get_variable(name).is_err() {
+                        return Err(JError::undefined_variable(name, 0, 0)
+                            .raised_as(&format!("Variable '{}' not found in any scope", name))
+                            .into());
+                    }
+                    self.globals.insert(name.to_string(), value);
+                }
+                Op::Binary(code) => {
+                    let right = self.pop();
+                    let left = self.pop();
+                    let result =
+                        self.interpreter
+                            .eval_binary_op(&left, &BINARY_OPS[code as usize], &right)?;
+                    self.stack.push(result);
+                }
+                Op::Unary(code) => {
+                    let operand = self.pop();
+                    let result = self
+                        .interpreter
+                        .eval_unary_op(&UNARY_OPS[code as usize], &operand)?;
+                    self.stack.push(result);
+                }
+                Op::RequireBool(code) => {
+                    if !matches!(self.peek(), Value::Boolean(_)) {
+                        let operator = if code == 0 { "And" } else { "Or" };
+                        return Err(JError::invalid_argument(format!("{} operator requires boolean operands", operator)).into());
+                    }
+                }
+                Op::Jump(target) => self.jump(target),
+                Op::JumpIfFalse(target) => {
+                    let cond = self.pop();
+                    if !self.interpreter.is_truthy(&cond) {
+                        self.jump(target);
+                    }
+                }
+                Op::JumpIfFalseKeep(target) => {
+                    if matches!(self.peek(), Value::Boolean(false)) {
+                        self.jump(target);
+                    }
+                }
+                Op::JumpIfTrueKeep(target) => {
+                    if matches!(self.peek(), Value::Boolean(true)) {
+                        self.jump(target);
+                    }
+                }
+                Op::Call { func, argc } => self.call(func as usize, argc as usize)?,
+                Op::CallBuiltin { name, argc } => {
+                    let args = self.stack.split_off(self.stack.len() - argc as usize);
+                    let name = self.name(name);
+                    let result = match self.interpreter.call_builtin_values(name, &args)? {
+                        Some(result) => result,
+                        None => {
+                            let callee = match self.globals.get(name) {
+                                Some(callee) => callee.clone(),
+                                None => self.interpreter.get_variable(name)?,
+                            };
+                            self.interpreter.call_value_with_args(callee, &args, None)?
+                        }
+                    };
+                    self.stack.push(result);
+                }
+                Op::Return => {
+                    let result = self.pop();
+                    self.frames.pop();
+                    if self.frames.is_empty() {
+                        return Ok(result);
+                    }
+                    self.stack.truncate(base);
+                    self.stack.push(result);
+                }
+                Op::MakeList(n) => {
+                    let items = self.stack.split_off(self.stack.len() - n as usize);
+                    self.stack.push(Value::List(items));
+                }
+                Op::MakeTuple(n) => {
+                    let items = self.stack.split_off(self.stack.len() - n as usize);
+                    self.stack.push(Value::Tuple(items));
+                }
+                Op::Index => {
+                    let index = self.pop();
+                    let object = self.pop();
+                    self.stack.push(Interpreter::index_value(object, index)?);
+                }
+                Op::Concat(n) => {
+                    let parts = self.stack.split_off(self.stack.len() - n as usize);
+                    let joined: String = parts.iter().map(|p| p.to_string()).collect();
+                    self.stack.push(Value::String(joined));
+                }
+                Op::IterStart => {
+                    let items = match self.pop() {
+                        Value::List(items) | Value::Tuple(items) => items,
+                        Value::String(s) => s.chars().map(Value::Char).collect(),
+                        Value::Dict(dict) => dict.into_keys().map(Value::String).collect(),
+                        Value::Vector(v) => v.into_iter().map(Value::Float).collect(),
+                        _ => {
+                            return Err(
+                                JError::unsupported("Can only iterate over lists, strings, dictionaries, tuples, and vectors").into(),
+                            )
+                        }
+                    };
+                    self.iterators.push(items.into_iter());
+                }
+                Op::IterNext(target) => {
+                    match self.iterators.last_mut().and_then(|items| items.next()) {
+                        Some(item) => self.stack.push(item),
+                        None => {
+                            self.iterators.pop();
+                            self.jump(target);
+                        }
+                    }
+                }
+                Op::IterEnd => {
+                    self.iterators.pop();
+                }
+            }
+        }
+    }
+
+    fn call(&mut self, func: usize, argc: usize) -> JResult<()> {
+        let program = self.program;
+        let name = program.functions[func].name.as_str();
+        if self.shadowed[func] != Some(false) {
+            let args = &self.stack[self.stack.len() - argc..];
+            let builtin = self.interpreter.call_builtin_values(name, args)?;
+            self.shadowed[func] = Some(builtin.is_some());
+            if let Some(result) = builtin {
+                self.stack.truncate(self.stack.len() - argc);
+                self.stack.push(result);
+                return Ok(());
+            }
+        }
+
+        // The entry point is not a call.
+        let depth = self.frames.len() - 1;
+        if depth >= self.interpreter.max_call_depth() {
+            return Err(Box::new(JError::stack_overflow(depth)));
+        }
+        let function = &program.functions[func];
+        let base = self.stack.len() - argc;
+        self.stack.resize(base + function.locals as usize, Value::None);
+        self.frames.push(Frame { func, ip: 0, base });
+        Ok(())
+    }
+
+    /// Locate `error` at the instruction that raised it, with a traceback of the VM's frames.
+    fn locate_error(&self, mut error: Box<JError>) -> Box<JError> {
+        if error.line.is_some() || error.kind == ErrorKind::AlreadyFormatted {
+            return error;
+        }
+        let mut trace = Vec::with_capacity(self.frames.len());
+        for frame in &self.frames {
+            let function = &self.program.functions[frame.func];
+            let Some(&(line, column)) = frame.ip.checked_sub(1).and_then(|ip| function.positions.get(ip))
+            else {
+                continue;
+            };
+            let name = if frame.func == self.program.main as usize {
+                "<module>"
+            } else {
+                function.name.as_str()
+            };
+            trace.push(TraceEntry {
+                function: name.to_string(),
+                file: self.interpreter.program_path().map(str::to_string),
+                line: line as usize,
+                column: column as usize,
+                source_line: None,
+            });
+        }
+        if let Some(last) = trace.last().filter(|entry| entry.line > 0) {
+            error.line = Some(last.line);
+            error.column = Some(last.column);
+            error.trace = trace;
+        }
+        error
+    }
+
+    fn jump(&mut self, target: u32) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.ip = target as usize;
+        }
+    }
+
+    fn name(&self, index: u32) -> &'a str {
+        match &self.program.constants[index as usize] {
+            Constant::Str(name) => name,
+            other => panic!("name operand refers to non-string constant {:?}", other),
+        }
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("VM operand stack underflow")
+    }
+
+    fn peek(&self) -> &Value {
+        self.stack.last().expect("VM operand stack underflow")
+    }
+}
@@ -0,0 +1,848 @@
+//! Jade AST to C source: the `jade build` backend without the LLVM tools or for dynamic code.
+//!
+//! Values stay dynamically typed, as in the interpreter: every expression is a `jade_value` from
+//! the runtime in `runtime/jade.c`, which implements operators, indexing, iteration and `out()`
+//! with the interpreter's semantics and error messages. The runtime is emitted first, so the
+//! generated file builds on its own with any C99 compiler (`cc main.c -lm`).
+//!
+//! Each expression is evaluated into a temporary that holds one reference; whoever consumes it
+//! releases it or moves it into a variable. Top-level functions become C functions taking and
+//! returning owned values, and top-level variables become C statics that functions can read.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use super::llvm::node_kind;
+use crate::bytecode::CONVERTING_TYPES;
+use crate::parser::{AstNode, BinaryOp, UnaryOp};
+
+/// The runtime, which the generated program follows in the same file.
+const RUNTIME: &str = include_str!("runtime/jade.c");
+
+type Result<T> = std::result::Result<T, String>;
+
+fn unsupported<T>(what: impl Into<String>) -> Result<T> {
+    Err(what.into())
+}
+
+/// `name` reduced to characters C allows in identifiers.
+fn mangle(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            out.push(c);
+        } else {
+            let _ = write!(out, "_{:x}", c as u32);
+        }
+    }
+    out
+}
+
+/// A C string literal with the bytes of `s`.
+fn c_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for b in s.bytes() {
+        match b {
+            b'"' | b'\\' | b'?' => {
+                out.push('\\');
+                out.push(b as char);
+            }
+            b' '..=b'~' => out.push(b as char),
+            _ => {
+                let _ = write!(out, "\\{:03o}", b);
+            }
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn binary_op(operator: &BinaryOp) -> Option<&'static str> {
+    Some(match operator {
+        BinaryOp::Add => "JADE_OP_ADD",
+        BinaryOp::Subtract => "JADE_OP_SUBTRACT",
+        BinaryOp::Multiply => "JADE_OP_MULTIPLY",
+        BinaryOp::Divide => "JADE_OP_DIVIDE",
+        BinaryOp::Modulo => "JADE_OP_MODULO",
+        BinaryOp::Power => "JADE_OP_POWER",
+        BinaryOp::Equal => "JADE_OP_EQUAL",
+        BinaryOp::NotEqual => "JADE_OP_NOT_EQUAL",
+        BinaryOp::Less => "JADE_OP_LESS",
+        BinaryOp::Greater => "JADE_OP_GREATER",
+        BinaryOp::LessEqual => "JADE_OP_LESS_EQUAL",
+        BinaryOp::GreaterEqual => "JADE_OP_GREATER_EQUAL",
+        BinaryOp::BitwiseAnd => "JADE_OP_BITWISE_AND",
+        BinaryOp::BitwiseOr => "JADE_OP_BITWISE_OR",
+        BinaryOp::BitwiseXor => "JADE_OP_BITWISE_XOR",
+        BinaryOp::LeftShift => "JADE_OP_LEFT_SHIFT",
+        BinaryOp::RightShift => "JADE_OP_RIGHT_SHIFT",
+        BinaryOp::ConstantTimeEq | BinaryOp::And | BinaryOp::Or | BinaryOp::Assign => return None,
+    })
+}
+
+/// Signature of the C function for a Jade function of `arity` parameters.
+fn prototype(symbol: &str, arity: usize) -> String {
+    let params: Vec<String> = (0..arity).map(|i| format!("jade_value a{}", i)).collect();
+    let params = match params.is_empty() {
+        true => "void".to_string(),
+        false => params.join(", "),
+    };
+    format!("static jade_value {}({})", symbol, params)
+}
+
+/// Code being generated for one C function.
+struct Func {
+    code: String,
+    depth: usize,
+    next: usize,
+    /// Block scopes, innermost last, mapping Jade names to C variables. Empty at the top level of
+    /// the program, where names are globals.
+    scopes: Vec<Vec<(String, String)>>,
+    /// Number of scopes open when each enclosing loop started.
+    loops: Vec<usize>,
+}
+
+impl Func {
+    fn new() -> Self {
+        Self {
+            code: String::new(),
+            depth: 1,
+            next: 0,
+            scopes: Vec::new(),
+            loops: Vec::new(),
+        }
+    }
+
+    fn emit(&mut self, line: impl AsRef<str>) {
+        for _ in 0..self.depth {
+            self.code.push_str("    ");
+        }
+        self.code.push_str(line.as_ref());
+        self.code.push('\n');
+    }
+
+    /// Open a C block; `head` is what precedes the brace (`if (c)`, `for (;;)`, ...).
+    fn open(&mut self, head: &str) {
+        if head.is_empty() {
+            self.emit("{");
+        } else {
+            self.emit(format!("{} {{", head));
+        }
+        self.depth += 1;
+    }
+
+    fn close(&mut self) {
+        self.depth -= 1;
+        self.emit("}");
+    }
+
+    /// Close an `if` block and open its `else` block.
+    fn otherwise(&mut self) {
+        self.depth -= 1;
+        self.emit("} else {");
+        self.depth += 1;
+    }
+
+    fn fresh(&mut self, prefix: &str) -> String {
+        self.next += 1;
+        format!("{}{}", prefix, self.next)
+    }
+
+    /// Evaluate `expression` into a new temporary and return its name.
+    fn temp(&mut self, expression: impl AsRef<str>) -> String {
+        let name = self.fresh("t");
+        self.emit(format!("jade_value {} = {};", name, expression.as_ref()));
+        name
+    }
+
+    /// A temporary holding `none`, for nodes that produce no value.
+    fn none(&mut self) -> String {
+        self.temp("jade_none()")
+    }
+
+    fn release(&mut self, temps: &[&str]) {
+        for temp in temps {
+            self.emit(format!("jade_release({});", temp));
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<&str> {
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|(n, _)| n == name)
+            .map(|(_, var)| var.as_str())
+    }
+
+    /// Release the variables of scopes from `depth` inward, before leaving them early.
+    fn release_scopes(&mut self, depth: usize) {
+        let vars: Vec<String> = self.scopes[depth..]
+            .iter()
+            .flatten()
+            .map(|(_, var)| var.clone())
+            .collect();
+        for var in vars.iter().rev() {
+            self.emit(format!("jade_release({});", var));
+        }
+    }
+}
+
+/// A top-level function: its declaration and, once called, its C symbol.
+struct Function<'a> {
+    params: &'a [(String, String)],
+    body: &'a AstNode,
+    symbol: Option<String>,
+}
+
+struct Translation<'a> {
+    functions: HashMap<&'a str, Function<'a>>,
+    /// Functions referenced but not generated yet.
+    pending: Vec<&'a str>,
+    is_builtin: &'a dyn Fn(&str) -> bool,
+    definitions: Vec<String>,
+    prototypes: Vec<String>,
+    /// Jade names declared at the top level and their C statics, in declaration order.
+    globals: Vec<(String, String)>,
+    strings: HashMap<String, String>,
+    constants: Vec<(String, String)>,
+}
+
+/// Translate a parsed program to a self-contained C file. `is_builtin` tells which names the
+/// interpreter resolves to builtins, which take precedence over user functions.
+pub(crate) fn translate(
+    ast: &AstNode,
+    is_builtin: &dyn Fn(&str) -> bool,
+) -> std::result::Result<String, String> {
+    let mut translation = Translation {
+        functions: HashMap::new(),
+        pending: Vec::new(),
+        is_builtin,
+        definitions: Vec::new(),
+        prototypes: Vec::new(),
+        globals: Vec::new(),
+        strings: HashMap::new(),
+        constants: Vec::new(),
+    };
+    translation
+        .program(ast)
+        .map_err(|what| super::cannot_compile(&what))
+}
+
+impl<'a> Translation<'a> {
+    fn program(&mut self, ast: &'a AstNode) -> Result<String> {
+        let statements = match ast {
+            AstNode::Block(statements) => statements.as_slice(),
+            other => std::slice::from_ref(other),
+        };
+        // Top-level functions and variables first: functions can call functions declared later in
+        // the file and read variables the top level declares before calling them.
+        for stmt in statements {
+            match stmt {
+                AstNode::FunctionDeclaration {
+                    name,
+                    params,
+                    body,
+                    decorators,
+                    ..
+                } => {
+                    if !decorators.is_empty() {
+                        return unsupported(format!("decorated function '{}'", name));
+                    }
+                    if self.functions.contains_key(name.as_str()) {
+                        return unsupported(format!("redefinition of function '{}'", name));
+                    }
+                    self.functions.insert(
+                        name,
+                        Function {
+                            params,
+                            body,
+                            symbol: None,
+                        },
+                    );
+                }
+                AstNode::VarDeclaration { name, .. } | AstNode::For { var: name, .. }
+                    if !self.globals.iter().any(|(n, _)| n == name) =>
+                {
+                    let symbol = format!("g{}_{}", self.globals.len(), mangle(name));
+                    self.globals.push((name.clone(), symbol));
+                }
+                _ => {}
+            }
+        }
+
+        let mut main = Func::new();
+        for stmt in statements {
+            let value = self.expr(&mut main, stmt)?;
+            main.release(&[&value]);
+        }
+        while let Some(name) = self.pending.pop() {
+            self.function(name)?;
+        }
+        Ok(self.file(main))
+    }
+
+    fn file(&self, main: Func) -> String {
+        let mut c = String::from(RUNTIME);
+        c.push('\n');
+        for (_, symbol) in &self.globals {
+            let _ = writeln!(c, "static jade_value {} = {{JADE_UNDEF, {{0}}}};", symbol);
+        }
+        for (symbol, _) in &self.constants {
+            let _ = writeln!(c, "static jade_value {};", symbol);
+        }
+        c.push('\n');
+        for prototype in &self.prototypes {
+            let _ = writeln!(c, "{};", prototype);
+        }
+        c.push('\n');
+        for definition in &self.definitions {
+            c.push_str(definition);
+            c.push('\n');
+        }
+        c.push_str("int main(void) {\n");
+        for (symbol, literal) in &self.constants {
+            let _ = writeln!(c, "    {} = {};", symbol, literal);
+        }
+        c.push_str(&main.code);
+        c.push_str("    fflush(stdout);\n    return 0;\n}\n");
+        c
+    }
+
+    /// A new reference to the string constant `s`.
+    fn string(&mut self, f: &mut Func, s: &str) -> String {
+        let symbol = match self.strings.get(s) {
+            Some(symbol) => symbol.clone(),
+            None => {
+                let symbol = format!("s{}", self.strings.len());
+                let literal = format!("jade_new_str({}, {})", c_string(s), s.len());
+                self.constants.push((symbol.clone(), literal));
+                self.strings.insert(s.to_string(), symbol.clone());
+                symbol
+            }
+        };
+        f.temp(format!("jade_retain({})", symbol))
+    }
+
+    /// C variable for `name` as seen from `f`: a local, or a global when no local shadows it.
+    fn variable(&self, f: &Func, name: &str) -> Option<String> {
+        f.lookup(name).map(str::to_string).or_else(|| {
+            self.globals
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, symbol)| symbol.clone())
+        })
+    }
+
+    fn read(&mut self, f: &mut Func, name: &str) -> Result<String> {
+        if let Some(var) = self.variable(f, name) {
+            return Ok(f.temp(format!("jade_read({}, {})", var, c_string(name))));
+        }
+        if self.functions.contains_key(name) {
+            return unsupported(format!("function '{}' used as a value", name));
+        }
+        f.emit(format!("jade_undefined({});", c_string(name)));
+        Ok(f.none())
+    }
+
+    /// The variable a declaration of `name` sets: the one already in the innermost scope (the
+    /// globals, at the top level), or a new one holding `initial`.
+    fn slot(&mut self, f: &mut Func, name: &str, initial: &str) -> (String, bool) {
+        let Some(scope) = f.scopes.last() else {
+            if let Some(symbol) = self.variable(f, name) {
+                return (symbol, true);
+            }
+            let symbol = format!("g{}_{}", self.globals.len(), mangle(name));
+            self.globals.push((name.to_string(), symbol.clone()));
+            f.emit(format!("{} = {};", symbol, initial));
+            return (symbol, false);
+        };
+        if let Some((_, var)) = scope.iter().find(|(n, _)| n == name) {
+            return (var.clone(), true);
+        }
+        let var = format!("{}_{}", f.fresh("v"), mangle(name));
+        f.emit(format!("jade_value {} = {};", var, initial));
+        if let Some(scope) = f.scopes.last_mut() {
+            scope.push((name.to_string(), var.clone()));
+        }
+        (var, false)
+    }
+
+    /// `name = value` for a declaration, which creates the variable in the innermost scope.
+    fn declare(&mut self, f: &mut Func, name: &str, value: &str) {
+        let (var, existed) = self.slot(f, name, value);
+        if existed {
+            f.emit(format!("jade_release({});", var));
+            f.emit(format!("{} = {};", var, value));
+        }
+    }
+
+    fn expr(&mut self, f: &mut Func, node: &'a AstNode) -> Result<String> {
+        match node {
+            AstNode::Integer(i) if *i == i64::MIN => Ok(f.temp("jade_int(INT64_MIN)")),
+            AstNode::Integer(i) => Ok(f.temp(format!("jade_int(INT64_C({}))", i))),
+            AstNode::Float(x) => Ok(f.temp(format!("jade_float({:e})", x))),
+            AstNode::Boolean(b) => Ok(f.temp(format!("jade_bool({})", *b as u8))),
+            AstNode::Char(c) => Ok(f.temp(format!("jade_char(0x{:x})", *c as u32))),
+            AstNode::String(s) => Ok(self.string(f, s)),
+            AstNode::StringInterpolation { parts } => {
+                let mut result = self.string(f, "");
+                for part in parts {
+                    let value = self.expr(f, part)?;
+                    let joined = f.temp(format!("jade_interpolate({}, {})", result, value));
+                    f.release(&[&result, &value]);
+                    result = joined;
+                }
+                Ok(result)
+            }
+            AstNode::Identifier(name, _) => self.read(f, name),
+            AstNode::List(elements) => {
+                let mut items = Vec::new();
+                for element in elements {
+                    items.push(self.expr(f, element)?);
+                }
+                let mut args = vec![items.len().to_string()];
+                args.extend(items);
+                Ok(f.temp(format!("jade_new_list({})", args.join(", "))))
+            }
+            AstNode::Dict(pairs) => {
+                let mut args = vec![pairs.len().to_string()];
+                for (key, value) in pairs {
+                    args.push(self.expr(f, key)?);
+                    args.push(self.expr(f, value)?);
+                }
+                Ok(f.temp(format!("jade_new_dict({})", args.join(", "))))
+            }
+            AstNode::Index { object, index, .. } => {
+                let object = self.expr(f, object)?;
+                let index = self.expr(f, index)?;
+                let value = f.temp(format!("jade_index({}, {})", object, index));
+                f.release(&[&object, &index]);
+                Ok(value)
+            }
+            AstNode::Range {
+                start,
+                end,
+                inclusive,
+                step,
+                ..
+            } => {
+                let start = self.expr(f, start)?;
+                let end = self.expr(f, end)?;
+                let step = match step {
+                    Some(step) => self.expr(f, step)?,
+                    None => f.temp("jade_undef()"),
+                };
+                let list = f.temp(format!(
+                    "jade_range({}, {}, {}, {})",
+                    start, end, step, *inclusive as u8
+                ));
+                f.release(&[&start, &end, &step]);
+                Ok(list)
+            }
+            AstNode::Block(statements) => {
+                let result = f.none();
+                f.open("");
+                f.scopes.push(Vec::new());
+                for (i, stmt) in statements.iter().enumerate() {
+                    let value = self.expr(f, stmt)?;
+                    if i + 1 == statements.len() {
+                        f.emit(format!("{} = {};", result, value));
+                    } else {
+                        f.release(&[&value]);
+                    }
+                }
+                let depth = f.scopes.len() - 1;
+                f.release_scopes(depth);
+                f.scopes.pop();
+                f.close();
+                Ok(result)
+            }
+            AstNode::Expression(expr) => self.expr(f, expr),
+            // `return` yields its value without leaving the function, as in the interpreter.
+            AstNode::Return(expr, _) => match expr {
+                Some(expr) => self.expr(f, expr),
+                None => Ok(f.none()),
+            },
+            AstNode::VarDeclaration {
+                var_type,
+                name,
+                value,
+                is_static,
+                type_modifier,
+                ..
+            } => {
+                if *is_static || type_modifier.is_some() {
+                    return unsupported(format!("declaration modifiers on '{}'", name));
+                }
+                if CONVERTING_TYPES.contains(&var_type.as_str()) {
+                    return unsupported(format!("'{}' declarations", var_type));
+                }
+                let value = self.expr(f, value)?;
+                self.declare(f, name, &format!("jade_retain({})", value));
+                Ok(value)
+            }
+            AstNode::Assignment { name, value, .. } => {
+                let Some(var) = self.variable(f, name) else {
+                    return unsupported(format!("assignment to undeclared variable '{}'", name));
+                };
+                let value = self.expr(f, value)?;
+                f.emit(format!(
+                    "jade_assign(&{}, jade_retain({}), {});",
+                    var,
+                    value,
+                    c_string(name)
+                ));
+                Ok(value)
+            }
+            AstNode::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } => self.binary(f, left, operator, right),
+            AstNode::Unary { operator, operand, .. } => {
+                let op = match operator {
+                    UnaryOp::Minus => "JADE_UNARY_MINUS",
+                    UnaryOp::Not => "JADE_UNARY_NOT",
+                    UnaryOp::BitwiseNot => "JADE_UNARY_BITWISE_NOT",
+                };
+                let operand = self.expr(f, operand)?;
+                let value = f.temp(format!("jade_unary({}, {})", op, operand));
+                f.release(&[&operand]);
+                Ok(value)
+            }
+            AstNode::FunctionCall { name, args, .. } => self.call(f, name, args),
+            AstNode::Call { callee, args, .. } => match &**callee {
+                AstNode::DotAccess { object, field, .. } => self.method(f, object, field, args),
+                other => unsupported(format!("calling a {}", node_kind(other))),
+            },
+            AstNode::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                let result = f.none();
+                let condition = self.condition(f, condition)?;
+                f.open(&format!("if ({})", condition));
+                let value = self.expr(f, then_branch)?;
+                f.emit(format!("{} = {};", result, value));
+                if let Some(else_branch) = else_branch {
+                    f.otherwise();
+                    let value = self.expr(f, else_branch)?;
+                    f.emit(format!("{} = {};", result, value));
+                }
+                f.close();
+                Ok(result)
+            }
+            AstNode::While { condition, body, .. } => {
+                let last = f.none();
+                f.open("for (;;)");
+                let condition = self.condition(f, condition)?;
+                f.emit(format!("if (!{}) break;", condition));
+                f.loops.push(f.scopes.len());
+                let value = self.expr(f, body)?;
+                f.loops.pop();
+                f.release(&[&last]);
+                f.emit(format!("{} = {};", last, value));
+                f.close();
+                Ok(last)
+            }
+            AstNode::For {
+                var,
+                iterable,
+                body,
+                ..
+            } => self.for_loop(f, var, iterable, body),
+            AstNode::Break(_) | AstNode::Continue(_) => {
+                let Some(&depth) = f.loops.last() else {
+                    return unsupported(format!(
+                        "{} outside a loop",
+                        node_kind(node).to_lowercase()
+                    ));
+                };
+                f.release_scopes(depth);
+                f.emit(if matches!(node, AstNode::Break(_)) {
+                    "break;"
+                } else {
+                    "continue;"
+                });
+                Ok(f.none())
+            }
+            // Functions are generated once called; only allowed at the top level of the program.
+            AstNode::FunctionDeclaration { name, .. } => {
+                if !f.scopes.is_empty() || !self.functions.contains_key(name.as_str()) {
+                    return unsupported(format!("nested function '{}'", name));
+                }
+                Ok(f.none())
+            }
+            other => unsupported(format!("{} is not supported", node_kind(other))),
+        }
+    }
+
+    /// Evaluate a condition into a C `int` (the interpreter's truthiness).
+    fn condition(&mut self, f: &mut Func, node: &'a AstNode) -> Result<String> {
+        let value = self.expr(f, node)?;
+        let condition = f.fresh("c");
+        f.emit(format!("int {} = jade_truthy({});", condition, value));
+        f.release(&[&value]);
+        Ok(condition)
+    }
+
+    fn binary(
+        &mut self,
+        f: &mut Func,
+        left: &'a AstNode,
+        operator: &BinaryOp,
+        right: &'a AstNode,
+    ) -> Result<String> {
+        // `and` / `or` short-circuit and only accept booleans.
+        if matches!(operator, BinaryOp::And | BinaryOp::Or) {
+            let (word, test) = match operator {
+                BinaryOp::And => ("\"And\"", ""),
+                _ => ("\"Or\"", "!"),
+            };
+            let result = self.expr(f, left)?;
+            f.open(&format!("if ({}jade_logic({}, {}))", test, result, word));
+            let value = self.expr(f, right)?;
+            f.emit(format!("jade_logic({}, {});", value, word));
+            f.emit(format!("{} = {};", result, value));
+            f.close();
+            return Ok(result);
+        }
+        let Some(op) = binary_op(operator) else {
+            return unsupported(format!("operator {:?}", operator));
+        };
+        let left = self.expr(f, left)?;
+        let right = self.expr(f, right)?;
+        let value = f.temp(format!("jade_binary({}, {}, {})", op, left, right));
+        f.release(&[&left, &right]);
+        Ok(value)
+    }
+
+    fn for_loop(
+        &mut self,
+        f: &mut Func,
+        var: &'a str,
+        iterable: &'a AstNode,
+        body: &'a AstNode,
+    ) -> Result<String> {
+        // Integer ranges are counted rather than built as lists first.
+        let (head, item, list) = match iterable {
+            AstNode::Range {
+                start,
+                end,
+                inclusive,
+                step: None,
+                ..
+            } => {
+                let start = self.expr(f, start)?;
+                let end = self.expr(f, end)?;
+                f.emit(format!("jade_range_bounds({}, {});", start, end));
+                let i = f.fresh("i");
+                let head = format!(
+                    "for (int64_t {i} = {}.as.i; {i} {} {}.as.i; {i}++)",
+                    start,
+                    if *inclusive { "<=" } else { "<" },
+                    end,
+                    i = i
+                );
+                (head, format!("jade_int({})", i), None)
+            }
+            other => {
+                let value = self.expr(f, other)?;
+                let list = f.temp(format!("jade_iterable({})", value));
+                f.release(&[&value]);
+                let i = f.fresh("i");
+                let head = format!(
+                    "for (int64_t {i} = 0; {i} < jade_iter_len({}); {i}++)",
+                    list,
+                    i = i
+                );
+                (head, format!("jade_iter_get({}, {})", list, i), Some(list))
+            }
+        };
+
+        // The loop variable lives in the scope around the loop, as with the interpreter's
+        // `set_variable`, and keeps the last item after it.
+        let (target, _) = self.slot(f, var, "jade_undef()");
+
+        let last = f.none();
+        f.open(&head);
+        f.emit(format!("jade_release({});", target));
+        f.emit(format!("{} = {};", target, item));
+        f.loops.push(f.scopes.len());
+        let value = self.expr(f, body)?;
+        f.loops.pop();
+        f.release(&[&last]);
+        f.emit(format!("{} = {};", last, value));
+        f.close();
+        if let Some(list) = list {
+            f.release(&[&list]);
+        }
+        Ok(last)
+    }
+
+    fn call(&mut self, f: &mut Func, name: &'a str, args: &'a [AstNode]) -> Result<String> {
+        if self.variable(f, name).is_some() {
+            return unsupported(format!("call through variable '{}'", name));
+        }
+        let runtime = match (name, args.len()) {
+            ("out", _) => return self.out(f, args),
+            ("len", 1) => "jade_len",
+            ("push", 2) => "jade_push",
+            ("append", 2) => "jade_append",
+            ("keys", 1) => "jade_keys",
+            ("values", 1) => "jade_values",
+            _ => "",
+        };
+        if !runtime.is_empty() {
+            let mut values = Vec::new();
+            for arg in args {
+                values.push(self.expr(f, arg)?);
+            }
+            let value = f.temp(format!("{}({})", runtime, values.join(", ")));
+            let values: Vec<&str> = values.iter().map(String::as_str).collect();
+            f.release(&values);
+            return Ok(value);
+        }
+        if (self.is_builtin)(name) {
+            return match self.functions.contains_key(name) {
+                true => unsupported(format!(
+                    "function '{}' has the name of a builtin, which takes precedence over it",
+                    name
+                )),
+                false => unsupported(format!(
+                    "builtin '{}' with {} arguments is not supported",
+                    name,
+                    args.len()
+                )),
+            };
+        }
+        let Some(function) = self.functions.get_mut(name) else {
+            return unsupported(format!("call to undefined function '{}'", name));
+        };
+        if function.params.len() != args.len() {
+            return unsupported(format!(
+                "call to '{}' with {} arguments (declared with {})",
+                name,
+                args.len(),
+                function.params.len()
+            ));
+        }
+        let symbol = match &function.symbol {
+            Some(symbol) => symbol.clone(),
+            None => {
+                let symbol = format!("jade_fn{}_{}", self.prototypes.len(), mangle(name));
+                function.symbol = Some(symbol.clone());
+                self.prototypes.push(prototype(&symbol, args.len()));
+                self.pending.push(name);
+                symbol
+            }
+        };
+        // Arguments are moved into the callee, which releases them.
+        let mut values = Vec::new();
+        for arg in args {
+            values.push(self.expr(f, arg)?);
+        }
+        Ok(f.temp(format!("{}({})", symbol, values.join(", "))))
+    }
+
+    fn method(
+        &mut self,
+        f: &mut Func,
+        object: &'a AstNode,
+        field: &str,
+        args: &'a [AstNode],
+    ) -> Result<String> {
+        let (runtime, named) = match (field, args.len()) {
+            ("len" | "length" | "size", 0) => ("jade_method_len", true),
+            ("push" | "append", 1) => ("jade_method_push", true),
+            ("get", 1 | 2) => ("jade_method_get", false),
+            ("has" | "contains_key", 1) => ("jade_method_has", true),
+            ("keys", 0) => ("jade_method_keys", false),
+            ("values", 0) => ("jade_method_values", false),
+            _ => return unsupported(format!("method '.{}' with {} arguments", field, args.len())),
+        };
+        let mut values = vec![self.expr(f, object)?];
+        for arg in args {
+            values.push(self.expr(f, arg)?);
+        }
+        let mut call_args = values.clone();
+        if field == "get" && args.len() == 1 {
+            call_args.push("jade_undef()".to_string());
+        }
+        if named {
+            call_args.push(c_string(field));
+        }
+        let result = f.temp(format!("{}({})", runtime, call_args.join(", ")));
+        let values: Vec<&str> = values.iter().map(String::as_str).collect();
+        f.release(&values);
+        Ok(result)
+    }
+
+    fn out(&mut self, f: &mut Func, args: &'a [AstNode]) -> Result<String> {
+        match args {
+            [] => f.emit("jade_print_text(\"\\n\");"),
+            [arg] => {
+                let value = self.expr(f, arg)?;
+                f.emit(format!("jade_out_one({});", value));
+                f.release(&[&value]);
+            }
+            [first, second] => {
+                let first = self.expr(f, first)?;
+                let second = self.expr(f, second)?;
+                f.emit(format!("jade_out_two({}, {});", first, second));
+                f.release(&[&first, &second]);
+            }
+            // Printed as they are evaluated, like the interpreter does.
+            _ => {
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        f.emit("jade_print_text(\" \");");
+                    }
+                    let value = self.expr(f, arg)?;
+                    f.emit(format!("jade_print({});", value));
+                    f.release(&[&value]);
+                }
+                f.emit("jade_print_text(\"\\n\");");
+            }
+        }
+        Ok(f.none())
+    }
+
+    /// Definition of a top-level function. Parameters are its outermost scope; like the
+    /// interpreter, it sees the globals but no caller's locals.
+    fn function(&mut self, name: &'a str) -> Result<()> {
+        let function = &self.functions[name];
+        let (params, body) = (function.params, function.body);
+        let symbol = function
+            .symbol
+            .clone()
+            .expect("only called functions are generated");
+
+        let mut f = Func::new();
+        f.emit("jade_enter();");
+        let mut scope = Vec::new();
+        for (i, (_, param)) in params.iter().enumerate() {
+            let var = format!("{}_{}", f.fresh("v"), mangle(param));
+            f.emit(format!("jade_value {} = a{};", var, i));
+            scope.retain(|(n, _): &(String, String)| n != param);
+            scope.push((param.clone(), var));
+        }
+        f.scopes.push(scope);
+        let result = self
+            .expr(&mut f, body)
+            .map_err(|what| format!("{} (in function '{}')", what, name))?;
+        f.release_scopes(0);
+        f.emit("jade_leave();");
+        f.emit(format!("return {};", result));
+        self.definitions.push(format!(
+            "{} {{\n{}}}\n",
+            prototype(&symbol, params.len()),
+            f.code
+        ));
+        Ok(())
+    }
+}
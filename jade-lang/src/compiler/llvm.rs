@@ -0,0 +1,1486 @@
+//! Jade AST to textual LLVM IR (LLVM 14 syntax, typed pointers) for `jade build`.
+//!
+//! Every expression gets a static type: `int`, `float`, `bool`, `str` or a list of one of these.
+//! Functions are compiled once per combination of argument types. A function's return type is
+//! its body's type; for recursive functions, which need it before the body is known, it is taken
+//! from the declaration or from trying candidates until the body agrees. The element type of an
+//! empty list literal is filled in from later pushes, regenerating the program until it settles.
+//!
+//! Programs outside this subset are rejected with a diagnostic rather than compiled into a binary
+//! that behaves differently from `jade run`. Runtime support (strings, lists, formatting, runtime
+//! errors) lives in `runtime/native.c`.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use crate::bytecode::CONVERTING_TYPES;
+use crate::parser::{AstNode, BinaryOp, UnaryOp};
+
+/// Passes over the program before giving up on list element types settling.
+const MAX_PASSES: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Ty {
+    Int,
+    Float,
+    Bool,
+    Str,
+    List(Box<Ty>),
+    /// Element type of a list that has only ever been empty.
+    Unknown,
+    /// Statements and calls that produce no value.
+    Void,
+}
+
+impl Ty {
+    fn llvm(&self) -> &'static str {
+        match self {
+            Ty::Int => "i64",
+            Ty::Float => "double",
+            Ty::Bool => "i1",
+            Ty::Str => "i8*",
+            Ty::List(_) => "%jade.list*",
+            Ty::Unknown | Ty::Void => "void",
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Ty::Int => "int".to_string(),
+            Ty::Float => "float".to_string(),
+            Ty::Bool => "bool".to_string(),
+            Ty::Str => "str".to_string(),
+            Ty::List(item) if **item == Ty::Unknown => "empty list".to_string(),
+            Ty::List(item) => format!("list of {}", item.name()),
+            Ty::Unknown => "unknown".to_string(),
+            Ty::Void => "nothing".to_string(),
+        }
+    }
+
+    /// Item type descriptor understood by `jade_list_str`.
+    fn descriptor(&self) -> String {
+        match self {
+            Ty::Int => "i".to_string(),
+            Ty::Float => "f".to_string(),
+            Ty::Bool => "b".to_string(),
+            Ty::Str => "s".to_string(),
+            Ty::List(item) => format!("l{}", item.descriptor()),
+            Ty::Unknown | Ty::Void => "?".to_string(),
+        }
+    }
+
+    /// The type both `self` and `other` values fit, filling in unknown list elements.
+    fn unify(&self, other: &Ty) -> Option<Ty> {
+        match (self, other) {
+            (a, b) if a == b => Some(a.clone()),
+            (Ty::List(a), Ty::List(b)) => a.unify(b).map(|item| Ty::List(Box::new(item))),
+            (Ty::Unknown, t) | (t, Ty::Unknown) if *t != Ty::Void => Some(t.clone()),
+            _ => None,
+        }
+    }
+
+    /// Return type named in a function declaration, when it is one the compiler can assume.
+    fn declared(name: &str) -> Option<Ty> {
+        match name {
+            "int" => Some(Ty::Int),
+            "float" => Some(Ty::Float),
+            "bool" => Some(Ty::Bool),
+            "str" | "string" => Some(Ty::Str),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Error {
+    /// The program uses something outside the compiled subset.
+    Unsupported(String),
+    /// A recursive call to a function whose return type is not known yet.
+    Unresolved { symbol: String, name: String },
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+fn unsupported<T>(what: impl Into<String>) -> Result<T> {
+    Err(Error::Unsupported(what.into()))
+}
+
+fn describe(error: Error) -> String {
+    let what = match error {
+        Error::Unsupported(what) => what,
+        Error::Unresolved { name, .. } => format!(
+            "cannot infer the return type of recursive function '{}' (declare it, e.g. `fn int | {} (...)`)",
+            name, name
+        ),
+    };
+    super::cannot_compile(&what)
+}
+
+/// Variant name of a node (`Match`, `Lambda`, ...) for diagnostics.
+pub(super) fn node_kind(node: &AstNode) -> String {
+    format!("{:?}", node)
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect()
+}
+
+/// A typed LLVM operand.
+#[derive(Debug, Clone)]
+struct Val {
+    ty: Ty,
+    op: String,
+}
+
+impl Val {
+    fn new(ty: Ty, op: impl Into<String>) -> Self {
+        Self { ty, op: op.into() }
+    }
+
+    fn void() -> Self {
+        Self::new(Ty::Void, "")
+    }
+
+    fn typed(&self) -> String {
+        format!("{} {}", self.ty.llvm(), self.op)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Var {
+    /// Pointer operand of the variable's storage (an alloca or a global).
+    ptr: String,
+    ty: Ty,
+    /// Identifies the declaration across passes, for element type refinements.
+    key: String,
+    /// A `for` variable after its loop: the interpreter only defines it if the loop ran.
+    poisoned: bool,
+}
+
+struct Instance {
+    symbol: String,
+    ret: Option<Ty>,
+    /// Return type assumed while the body is being compiled.
+    assumed: Option<Ty>,
+}
+
+type FunctionDecl<'a> = (&'a [(String, String)], Option<&'a str>, &'a AstNode);
+
+/// Code being generated for one LLVM function.
+struct Func {
+    /// `main` or the function name and argument types; prefixes declaration keys.
+    key: String,
+    allocas: String,
+    code: String,
+    next: usize,
+    /// Label of the block being emitted into.
+    label: String,
+    /// Block scopes, innermost last. Empty at the top level of the program, where names are globals.
+    scopes: Vec<HashMap<String, Var>>,
+    /// (`continue` target, `break` target) of enclosing loops.
+    loops: Vec<(String, String)>,
+}
+
+impl Func {
+    fn new(key: String) -> Self {
+        Self {
+            key,
+            allocas: String::new(),
+            code: String::new(),
+            next: 0,
+            label: "entry".to_string(),
+            scopes: Vec::new(),
+            loops: Vec::new(),
+        }
+    }
+
+    fn emit(&mut self, line: impl AsRef<str>) {
+        self.code.push_str("  ");
+        self.code.push_str(line.as_ref());
+        self.code.push('\n');
+    }
+
+    /// Emit an instruction producing a value and return its name.
+    fn value(&mut self, instruction: impl AsRef<str>) -> String {
+        self.next += 1;
+        let name = format!("%t{}", self.next);
+        self.emit(format!("{} = {}", name, instruction.as_ref()));
+        name
+    }
+
+    fn fresh_label(&mut self, hint: &str) -> String {
+        self.next += 1;
+        format!("{}{}", hint, self.next)
+    }
+
+    fn start(&mut self, label: &str) {
+        let _ = writeln!(self.code, "{}:", label);
+        self.label = label.to_string();
+    }
+
+    fn alloca(&mut self, ty: &Ty) -> String {
+        self.next += 1;
+        let name = format!("%v{}", self.next);
+        let _ = writeln!(self.allocas, "  {} = alloca {}", name, ty.llvm());
+        name
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Var> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn lookup_mut(&mut self, name: &str) -> Option<&mut Var> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+    }
+
+    fn decl_key<T>(&self, decl: &T) -> String {
+        format!("{}@{:p}", self.key, decl as *const T)
+    }
+}
+
+/// One pass of code generation over a program.
+struct Lowering<'a> {
+    functions: HashMap<&'a str, FunctionDecl<'a>>,
+    is_builtin: &'a dyn Fn(&str) -> bool,
+    instances: HashMap<(String, Vec<Ty>), Instance>,
+    /// Instance keys in creation order, for rolling back a failed attempt.
+    order: Vec<(String, Vec<Ty>)>,
+    next_symbol: usize,
+    definitions: Vec<String>,
+    constants: Vec<String>,
+    strings: HashMap<String, String>,
+    globals: HashMap<String, Var>,
+    /// Element types learned for declarations of empty lists, by declaration key.
+    refined: HashMap<String, Ty>,
+    /// `refined` grew during this pass, so earlier code may have used a stale type.
+    changed: bool,
+    /// An element of a list of unknown type was read (as a placeholder int) in this pass.
+    guessed: bool,
+}
+
+/// Lower a parsed program to an LLVM module for `target_triple`. `is_builtin` tells which names
+/// the interpreter resolves to builtins, which take precedence over user functions.
+pub(crate) fn lower(
+    ast: &AstNode,
+    target_triple: &str,
+    is_builtin: &dyn Fn(&str) -> bool,
+) -> std::result::Result<String, String> {
+    let mut refined = HashMap::new();
+    for _ in 0..MAX_PASSES {
+        let mut lowering = Lowering::new(is_builtin, refined);
+        let result = lowering.program(ast);
+        if !lowering.changed {
+            return match result {
+                _ if lowering.guessed => Err(describe(Error::Unsupported(
+                    "reading from a list whose element type cannot be inferred".to_string(),
+                ))),
+                Ok(main) => Ok(lowering.module(target_triple, main)),
+                Err(error) => Err(describe(error)),
+            };
+        }
+        refined = lowering.refined;
+    }
+    Err(describe(Error::Unsupported(
+        "the element types of its lists could not be inferred".to_string(),
+    )))
+}
+
+const DECLARATIONS: &str = r#"%jade.list = type opaque
+
+declare { i64, i1 } @llvm.sadd.with.overflow.i64(i64, i64)
+declare { i64, i1 } @llvm.ssub.with.overflow.i64(i64, i64)
+declare { i64, i1 } @llvm.smul.with.overflow.i64(i64, i64)
+declare double @llvm.pow.f64(double, double)
+declare double @llvm.fabs.f64(double)
+declare void @jade_panic(i8*) noreturn
+declare void @jade_overflow(i8*, i64, i64) noreturn
+declare i8* @jade_concat(i8*, i8*)
+declare i32 @jade_str_eq(i8*, i8*)
+declare i64 @jade_str_len(i8*)
+declare i8* @jade_int_str(i64)
+declare i8* @jade_float_str(double)
+declare i8* @jade_bool_str(i64)
+declare %jade.list* @jade_list_new(i64)
+declare void @jade_list_push(%jade.list*, i64)
+declare %jade.list* @jade_list_pushed(%jade.list*, i64)
+declare i64 @jade_list_len(%jade.list*)
+declare i64 @jade_list_get(%jade.list*, i64)
+declare i8* @jade_list_str(%jade.list*, i8*)
+declare i64 @jade_ipow(i64, i64)
+declare void @jade_print(i8*)
+declare void @jade_println()
+"#;
+
+impl<'a> Lowering<'a> {
+    fn new(is_builtin: &'a dyn Fn(&str) -> bool, refined: HashMap<String, Ty>) -> Self {
+        Self {
+            functions: HashMap::new(),
+            is_builtin,
+            instances: HashMap::new(),
+            order: Vec::new(),
+            next_symbol: 0,
+            definitions: Vec::new(),
+            constants: Vec::new(),
+            strings: HashMap::new(),
+            globals: HashMap::new(),
+            refined,
+            changed: false,
+            guessed: false,
+        }
+    }
+
+    /// Compile the top level into `main`'s body.
+    fn program(&mut self, ast: &'a AstNode) -> Result<Func> {
+        let statements = match ast {
+            AstNode::Block(statements) => statements.as_slice(),
+            other => std::slice::from_ref(other),
+        };
+        // Top-level functions first, so calls can refer to functions declared later in the file.
+        for stmt in statements {
+            if let AstNode::FunctionDeclaration {
+                name,
+                params,
+                return_type,
+                body,
+                decorators,
+                ..
+            } = stmt
+            {
+                if !decorators.is_empty() {
+                    return unsupported(format!("decorated function '{}'", name));
+                }
+                if self.functions.contains_key(name.as_str()) {
+                    return unsupported(format!("redefinition of function '{}'", name));
+                }
+                self.functions
+                    .insert(name, (params.as_slice(), return_type.as_deref(), &**body));
+            }
+        }
+
+        let mut main = Func::new("main".to_string());
+        for stmt in statements {
+            self.expr(&mut main, stmt)?;
+        }
+        Ok(main)
+    }
+
+    fn module(&self, target_triple: &str, main: Func) -> String {
+        let mut ir = String::new();
+        let _ = writeln!(ir, "; Jade Language Compiled Output");
+        let _ = writeln!(ir, "target triple = \"{}\"\n", target_triple);
+        ir.push_str(DECLARATIONS);
+        ir.push('\n');
+        for constant in &self.constants {
+            ir.push_str(constant);
+        }
+        let mut globals: Vec<&Var> = self.globals.values().collect();
+        globals.sort_by(|a, b| a.ptr.cmp(&b.ptr));
+        for var in globals {
+            let zero = match var.ty {
+                Ty::Int => "0",
+                Ty::Float => "0.0",
+                Ty::Bool => "false",
+                _ => "null",
+            };
+            let _ = writeln!(
+                ir,
+                "{} = internal global {} {}",
+                var.ptr,
+                var.ty.llvm(),
+                zero
+            );
+        }
+        ir.push('\n');
+        for definition in &self.definitions {
+            ir.push_str(definition);
+            ir.push('\n');
+        }
+        let _ = write!(
+            ir,
+            "define i32 @main() {{\nentry:\n{}{}  ret i32 0\n}}\n",
+            main.allocas, main.code
+        );
+        ir
+    }
+
+    /// Operand for a string constant.
+    fn string(&mut self, s: &str) -> String {
+        if let Some(op) = self.strings.get(s) {
+            return op.clone();
+        }
+        let name = format!("@.str.{}", self.strings.len());
+        let mut bytes = String::new();
+        for b in s.bytes() {
+            if b.is_ascii_graphic() && b != b'"' && b != b'\\' || b == b' ' {
+                bytes.push(b as char);
+            } else {
+                let _ = write!(bytes, "\\{:02X}", b);
+            }
+        }
+        let len = s.len() + 1;
+        self.constants.push(format!(
+            "{} = private unnamed_addr constant [{} x i8] c\"{}\\00\"\n",
+            name, len, bytes
+        ));
+        let op = format!(
+            "getelementptr inbounds ([{} x i8], [{} x i8]* {}, i64 0, i64 0)",
+            len, len, name
+        );
+        self.strings.insert(s.to_string(), op.clone());
+        op
+    }
+
+    /// Type for a declaration, taking a refinement from an earlier pass into account.
+    fn refinement(&self, key: &str, ty: Ty) -> Ty {
+        match self.refined.get(key) {
+            Some(refined) if ty.unify(refined).as_ref() == Some(refined) => refined.clone(),
+            _ => ty,
+        }
+    }
+
+    fn lookup(&self, f: &Func, name: &str) -> Option<Var> {
+        f.lookup(name).or_else(|| self.globals.get(name)).cloned()
+    }
+
+    fn read(&mut self, f: &mut Func, name: &str) -> Result<Val> {
+        let Some(var) = self.lookup(f, name) else {
+            if self.functions.contains_key(name) {
+                return unsupported(format!("function '{}' used as a value", name));
+            }
+            return unsupported(format!("undefined variable '{}'", name));
+        };
+        if var.poisoned {
+            return unsupported(format!("loop variable '{}' used after its loop", name));
+        }
+        let ty = var.ty.llvm();
+        let op = f.value(format!("load {}, {}* {}", ty, ty, var.ptr));
+        Ok(Val::new(var.ty, op))
+    }
+
+    /// Store `val` into an existing variable, refining an empty list's element type if needed.
+    fn store(&mut self, f: &mut Func, name: &str, val: &Val) -> Result<()> {
+        let var = match f.lookup_mut(name) {
+            Some(var) => var,
+            None => match self.globals.get_mut(name) {
+                Some(var) => var,
+                None => return unsupported(format!("assignment to undefined variable '{}'", name)),
+            },
+        };
+        let Some(ty) = var.ty.unify(&val.ty) else {
+            return unsupported(format!(
+                "variable '{}' holds {} and cannot be assigned {}",
+                name,
+                var.ty.name(),
+                val.ty.name()
+            ));
+        };
+        if ty != var.ty {
+            var.ty = ty.clone();
+            if self.refined.get(&var.key) != Some(&ty) {
+                self.refined.insert(var.key.clone(), ty);
+                self.changed = true;
+            }
+        }
+        var.poisoned = false;
+        let (ptr, llvm) = (var.ptr.clone(), var.ty.llvm());
+        f.emit(format!("store {} {}, {}* {}", llvm, val.op, llvm, ptr));
+        Ok(())
+    }
+
+    /// Declare `name` in the innermost scope (a global at the top level) holding `val`.
+    fn declare(&mut self, f: &mut Func, name: &str, key: String, val: &Val) -> Result<()> {
+        let existing = match f.scopes.last() {
+            Some(scope) => scope.get(name),
+            None => self.globals.get(name),
+        };
+        if let Some(var) = existing {
+            if var.ty.unify(&val.ty).is_none() {
+                return unsupported(format!(
+                    "variable '{}' is redeclared as {} after holding {}",
+                    name,
+                    val.ty.name(),
+                    var.ty.name()
+                ));
+            }
+            return self.store(f, name, val);
+        }
+        let ty = self.refinement(&key, val.ty.clone());
+        let ptr = match f.scopes.last() {
+            Some(_) => f.alloca(&ty),
+            None => format!("@\"g.{}.{}\"", name, self.globals.len()),
+        };
+        let var = Var {
+            ptr,
+            ty,
+            key,
+            poisoned: false,
+        };
+        match f.scopes.last_mut() {
+            Some(scope) => scope.insert(name.to_string(), var),
+            None => self.globals.insert(name.to_string(), var),
+        };
+        self.store(f, name, val)
+    }
+
+    fn value(&mut self, f: &mut Func, node: &'a AstNode, context: &str) -> Result<Val> {
+        let val = self.expr(f, node)?;
+        if val.ty == Ty::Void {
+            return unsupported(format!("{} has no value", context));
+        }
+        Ok(val)
+    }
+
+    fn expr(&mut self, f: &mut Func, node: &'a AstNode) -> Result<Val> {
+        match node {
+            AstNode::Integer(i) => Ok(Val::new(Ty::Int, i.to_string())),
+            AstNode::Float(x) => Ok(Val::new(Ty::Float, format!("0x{:016X}", x.to_bits()))),
+            AstNode::Boolean(b) => Ok(Val::new(Ty::Bool, b.to_string())),
+            AstNode::String(s) => Ok(Val::new(Ty::Str, self.string(s))),
+            AstNode::StringInterpolation { parts } => {
+                let mut result = Val::new(Ty::Str, self.string(""));
+                for part in parts {
+                    let val = self.value(f, part, "an interpolated expression")?;
+                    let s = self.display(f, &val)?;
+                    result = Val::new(
+                        Ty::Str,
+                        f.value(format!(
+                            "call i8* @jade_concat(i8* {}, i8* {})",
+                            result.op, s
+                        )),
+                    );
+                }
+                Ok(result)
+            }
+            AstNode::Identifier(name, _) => self.read(f, name),
+            AstNode::List(elements) => {
+                let mut item_ty = Ty::Unknown;
+                let mut items = Vec::new();
+                for element in elements {
+                    let val = self.value(f, element, "a list element")?;
+                    item_ty = match item_ty.unify(&val.ty) {
+                        Some(ty) => ty,
+                        None => {
+                            return unsupported(format!(
+                                "a list mixing {} and {} items",
+                                item_ty.name(),
+                                val.ty.name()
+                            ))
+                        }
+                    };
+                    items.push(val);
+                }
+                let list = f.value(format!(
+                    "call %jade.list* @jade_list_new(i64 {})",
+                    items.len()
+                ));
+                for item in &items {
+                    let word = self.pack(f, item);
+                    f.emit(format!(
+                        "call void @jade_list_push(%jade.list* {}, i64 {})",
+                        list, word
+                    ));
+                }
+                Ok(Val::new(Ty::List(Box::new(item_ty)), list))
+            }
+            AstNode::Index { object, index, .. } => {
+                let list = self.value(f, object, "an indexed expression")?;
+                let index = self.value(f, index, "an index")?;
+                let Ty::List(item_ty) = &list.ty else {
+                    return unsupported(format!("indexing a {}", list.ty.name()));
+                };
+                if index.ty != Ty::Int {
+                    return unsupported(format!("a list index of type {}", index.ty.name()));
+                }
+                let word = f.value(format!(
+                    "call i64 @jade_list_get({}, i64 {})",
+                    list.typed(),
+                    index.op
+                ));
+                self.unpack(f, item_ty, &word)
+            }
+            AstNode::Block(statements) => {
+                f.scopes.push(HashMap::new());
+                let mut last = Val::void();
+                for stmt in statements {
+                    last = self.expr(f, stmt)?;
+                }
+                f.scopes.pop();
+                Ok(last)
+            }
+            AstNode::Expression(expr) => self.expr(f, expr),
+            // `return` yields its value without leaving the function, as in the interpreter.
+            AstNode::Return(expr, _) => match expr {
+                Some(expr) => self.expr(f, expr),
+                None => Ok(Val::void()),
+            },
+            AstNode::VarDeclaration {
+                var_type,
+                name,
+                value,
+                is_static,
+                type_modifier,
+                ..
+            } => {
+                if *is_static || type_modifier.is_some() {
+                    return unsupported(format!("declaration modifiers on '{}'", name));
+                }
+                if CONVERTING_TYPES.contains(&var_type.as_str()) {
+                    return unsupported(format!("'{}' declarations", var_type));
+                }
+                let val = self.value(f, value, &format!("the value declared for '{}'", name))?;
+                let key = f.decl_key(node);
+                self.declare(f, name, key, &val)?;
+                Ok(val)
+            }
+            AstNode::Assignment { name, value, .. } => {
+                let val = self.value(f, value, &format!("the value assigned to '{}'", name))?;
+                self.store(f, name, &val)?;
+                Ok(val)
+            }
+            AstNode::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } => self.binary(f, left, operator, right),
+            AstNode::Unary { operator, operand, .. } => {
+                let val = self.value(f, operand, "an operand")?;
+                let op = match (operator, &val.ty) {
+                    (UnaryOp::Minus, Ty::Int) => format!("sub i64 0, {}", val.op),
+                    (UnaryOp::Minus, Ty::Float) => format!("fneg double {}", val.op),
+                    (UnaryOp::Not, Ty::Bool) => format!("xor i1 {}, true", val.op),
+                    (UnaryOp::BitwiseNot, Ty::Int) => format!("xor i64 {}, -1", val.op),
+                    _ => {
+                        return unsupported(format!(
+                            "operator {:?} on a {}",
+                            operator,
+                            val.ty.name()
+                        ))
+                    }
+                };
+                Ok(Val::new(val.ty, f.value(op)))
+            }
+            AstNode::FunctionCall { name, args, .. } => self.call(f, name, args),
+            AstNode::Call { callee, args, .. } => match &**callee {
+                AstNode::DotAccess { object, field, .. } => self.method(f, object, field, args),
+                other => unsupported(format!("calling a {}", node_kind(other))),
+            },
+            AstNode::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => self.if_expr(f, condition, then_branch, else_branch.as_deref()),
+            AstNode::While { condition, body, .. } => {
+                let (head, body_label, end) = (
+                    f.fresh_label("while"),
+                    f.fresh_label("do"),
+                    f.fresh_label("done"),
+                );
+                f.emit(format!("br label %{}", head));
+                f.start(&head);
+                let cond = self.condition(f, condition)?;
+                f.emit(format!(
+                    "br i1 {}, label %{}, label %{}",
+                    cond, body_label, end
+                ));
+                f.start(&body_label);
+                f.loops.push((head.clone(), end.clone()));
+                self.expr(f, body)?;
+                f.loops.pop();
+                f.emit(format!("br label %{}", head));
+                f.start(&end);
+                Ok(Val::void())
+            }
+            AstNode::For {
+                var,
+                iterable,
+                body,
+                ..
+            } => self.for_loop(f, var, iterable, body),
+            AstNode::Break(_) | AstNode::Continue(_) => {
+                let Some((next, end)) = f.loops.last().cloned() else {
+                    return unsupported(format!(
+                        "{} outside a loop",
+                        node_kind(node).to_lowercase()
+                    ));
+                };
+                let target = if matches!(node, AstNode::Break(_)) {
+                    end
+                } else {
+                    next
+                };
+                f.emit(format!("br label %{}", target));
+                let dead = f.fresh_label("dead");
+                f.start(&dead);
+                Ok(Val::void())
+            }
+            // Compiled on first call; only allowed at the top level of the program.
+            AstNode::FunctionDeclaration { name, .. } => {
+                if f.key != "main" || !f.scopes.is_empty() {
+                    return unsupported(format!("nested function '{}'", name));
+                }
+                Ok(Val::void())
+            }
+            AstNode::Range { .. } => unsupported("a range outside a `for` loop"),
+            other => unsupported(format!("{} is not supported", node_kind(other))),
+        }
+    }
+
+    /// Branch on `panic_if`, calling `panic` (a noreturn runtime call) when it holds.
+    fn guard(&mut self, f: &mut Func, panic_if: &str, panic: String) {
+        let (fail, ok) = (f.fresh_label("fail"), f.fresh_label("ok"));
+        f.emit(format!(
+            "br i1 {}, label %{}, label %{}",
+            panic_if, fail, ok
+        ));
+        f.start(&fail);
+        f.emit(panic);
+        f.emit("unreachable");
+        f.start(&ok);
+    }
+
+    fn panic_call(&mut self, message: &str) -> String {
+        format!("call void @jade_panic(i8* {})", self.string(message))
+    }
+
+    /// The `i64` list slot holding `val`.
+    fn pack(&mut self, f: &mut Func, val: &Val) -> String {
+        match &val.ty {
+            Ty::Int => val.op.clone(),
+            Ty::Float => f.value(format!("bitcast double {} to i64", val.op)),
+            Ty::Bool => f.value(format!("zext i1 {} to i64", val.op)),
+            _ => f.value(format!("ptrtoint {} to i64", val.typed())),
+        }
+    }
+
+    fn unpack(&mut self, f: &mut Func, ty: &Ty, word: &str) -> Result<Val> {
+        let op = match ty {
+            Ty::Int => word.to_string(),
+            Ty::Float => f.value(format!("bitcast i64 {} to double", word)),
+            Ty::Bool => f.value(format!("trunc i64 {} to i1", word)),
+            Ty::Str | Ty::List(_) => f.value(format!("inttoptr i64 {} to {}", word, ty.llvm())),
+            // Read before a later push tells the element type; the next pass will know it.
+            Ty::Unknown | Ty::Void => {
+                self.guessed = true;
+                return Ok(Val::new(Ty::Int, "0"));
+            }
+        };
+        Ok(Val::new(ty.clone(), op))
+    }
+
+    /// `val` as displayed by `out()` and string interpolation.
+    fn display(&mut self, f: &mut Func, val: &Val) -> Result<String> {
+        Ok(match &val.ty {
+            Ty::Str => val.op.clone(),
+            Ty::Int => f.value(format!("call i8* @jade_int_str(i64 {})", val.op)),
+            Ty::Float => f.value(format!("call i8* @jade_float_str(double {})", val.op)),
+            Ty::Bool => {
+                let word = f.value(format!("zext i1 {} to i64", val.op));
+                f.value(format!("call i8* @jade_bool_str(i64 {})", word))
+            }
+            Ty::List(item) => {
+                let descriptor = self.string(&item.descriptor());
+                f.value(format!(
+                    "call i8* @jade_list_str({}, i8* {})",
+                    val.typed(),
+                    descriptor
+                ))
+            }
+            Ty::Unknown | Ty::Void => return unsupported("displaying a value-less expression"),
+        })
+    }
+
+    /// Truthiness of a condition, as an `i1` operand.
+    fn condition(&mut self, f: &mut Func, node: &'a AstNode) -> Result<String> {
+        let val = self.value(f, node, "a condition")?;
+        Ok(match &val.ty {
+            Ty::Bool => val.op,
+            Ty::Int => f.value(format!("icmp ne i64 {}, 0", val.op)),
+            Ty::Float => f.value(format!("fcmp une double {}, 0.0", val.op)),
+            Ty::Str => {
+                let first = f.value(format!("load i8, i8* {}", val.op));
+                f.value(format!("icmp ne i8 {}, 0", first))
+            }
+            Ty::List(_) => {
+                let len = f.value(format!("call i64 @jade_list_len({})", val.typed()));
+                f.value(format!("icmp ne i64 {}, 0", len))
+            }
+            Ty::Unknown | Ty::Void => unreachable!("checked by value()"),
+        })
+    }
+
+    fn if_expr(
+        &mut self,
+        f: &mut Func,
+        condition: &'a AstNode,
+        then_branch: &'a AstNode,
+        else_branch: Option<&'a AstNode>,
+    ) -> Result<Val> {
+        let cond = self.condition(f, condition)?;
+        let (then_label, else_label, end) = (
+            f.fresh_label("then"),
+            f.fresh_label("else"),
+            f.fresh_label("endif"),
+        );
+        f.emit(format!(
+            "br i1 {}, label %{}, label %{}",
+            cond, then_label, else_label
+        ));
+        f.start(&then_label);
+        let then_val = self.expr(f, then_branch)?;
+        let then_end = f.label.clone();
+        f.emit(format!("br label %{}", end));
+        f.start(&else_label);
+        let else_val = match else_branch {
+            Some(branch) => self.expr(f, branch)?,
+            None => Val::void(),
+        };
+        let else_end = f.label.clone();
+        f.emit(format!("br label %{}", end));
+        f.start(&end);
+        match then_val.ty.unify(&else_val.ty) {
+            Some(ty) if ty != Ty::Void => {
+                let op = f.value(format!(
+                    "phi {} [ {}, %{} ], [ {}, %{} ]",
+                    ty.llvm(),
+                    then_val.op,
+                    then_end,
+                    else_val.op,
+                    else_end
+                ));
+                Ok(Val::new(ty, op))
+            }
+            // Branches of different types: only usable as a statement.
+            _ => Ok(Val::void()),
+        }
+    }
+
+    fn for_loop(
+        &mut self,
+        f: &mut Func,
+        var: &'a String,
+        iterable: &'a AstNode,
+        body: &'a AstNode,
+    ) -> Result<Val> {
+        // Either a counter over an integer range or an index into a list, kept in `counter`.
+        let (counter, limit, compare, list) = match iterable {
+            AstNode::Range {
+                start,
+                end,
+                inclusive,
+                step: None,
+                ..
+            } => {
+                let start = self.value(f, start, "a range bound")?;
+                let end = self.value(f, end, "a range bound")?;
+                if start.ty != Ty::Int || end.ty != Ty::Int {
+                    return unsupported(format!(
+                        "a range from {} to {}",
+                        start.ty.name(),
+                        end.ty.name()
+                    ));
+                }
+                let counter = f.alloca(&Ty::Int);
+                f.emit(format!("store i64 {}, i64* {}", start.op, counter));
+                (
+                    counter,
+                    end.op,
+                    if *inclusive { "sle" } else { "slt" },
+                    None,
+                )
+            }
+            AstNode::Range { .. } => return unsupported("a range with a step"),
+            other => {
+                let list = self.value(f, other, "a `for` iterable")?;
+                let Ty::List(item_ty) = &list.ty else {
+                    return unsupported(format!("iterating over a {}", list.ty.name()));
+                };
+                let item_ty = (**item_ty).clone();
+                let len = f.value(format!("call i64 @jade_list_len({})", list.typed()));
+                let counter = f.alloca(&Ty::Int);
+                f.emit(format!("store i64 0, i64* {}", counter));
+                (counter, len, "slt", Some((list, item_ty)))
+            }
+        };
+
+        // The loop variable lives in the scope around the loop, as with the interpreter's
+        // `set_variable`; a new one is only defined once the loop has run.
+        let fresh = match f.scopes.last() {
+            Some(scope) => scope.get(var.as_str()),
+            None => self.globals.get(var.as_str()),
+        }
+        .is_none_or(|existing| existing.poisoned);
+
+        let (head, body_label, next, end) = (
+            f.fresh_label("for"),
+            f.fresh_label("body"),
+            f.fresh_label("next"),
+            f.fresh_label("done"),
+        );
+        f.emit(format!("br label %{}", head));
+        f.start(&head);
+        let current = f.value(format!("load i64, i64* {}", counter));
+        let more = f.value(format!("icmp {} i64 {}, {}", compare, current, limit));
+        f.emit(format!(
+            "br i1 {}, label %{}, label %{}",
+            more, body_label, end
+        ));
+        f.start(&body_label);
+        let item = match &list {
+            None => Val::new(Ty::Int, current.clone()),
+            Some((list, item_ty)) => {
+                let word = f.value(format!(
+                    "call i64 @jade_list_get({}, i64 {})",
+                    list.typed(),
+                    current
+                ));
+                self.unpack(f, item_ty, &word)?
+            }
+        };
+        if fresh {
+            let key = f.decl_key(var);
+            self.declare(f, var, key, &item)?;
+        } else {
+            self.store(f, var, &item)?;
+        }
+        f.loops.push((next.clone(), end.clone()));
+        self.expr(f, body)?;
+        f.loops.pop();
+        f.emit(format!("br label %{}", next));
+        f.start(&next);
+        let current = f.value(format!("load i64, i64* {}", counter));
+        let incremented = f.value(format!("add i64 {}, 1", current));
+        f.emit(format!("store i64 {}, i64* {}", incremented, counter));
+        f.emit(format!("br label %{}", head));
+        f.start(&end);
+
+        if fresh {
+            let scope = match f.scopes.last_mut() {
+                Some(scope) => scope,
+                None => &mut self.globals,
+            };
+            if let Some(var) = scope.get_mut(var.as_str()) {
+                var.poisoned = true;
+            }
+        }
+        Ok(Val::void())
+    }
+
+    fn binary(
+        &mut self,
+        f: &mut Func,
+        left: &'a AstNode,
+        operator: &BinaryOp,
+        right: &'a AstNode,
+    ) -> Result<Val> {
+        // `and` / `or` short-circuit and only accept booleans.
+        if matches!(operator, BinaryOp::And | BinaryOp::Or) {
+            let is_and = *operator == BinaryOp::And;
+            let word = if is_and { "and" } else { "or" };
+            let l = self.value(f, left, "an operand")?;
+            if l.ty != Ty::Bool {
+                return unsupported(format!("`{}` on a {}", word, l.ty.name()));
+            }
+            let (rhs, end) = (f.fresh_label("rhs"), f.fresh_label("logic"));
+            let from = f.label.clone();
+            if is_and {
+                f.emit(format!("br i1 {}, label %{}, label %{}", l.op, rhs, end));
+            } else {
+                f.emit(format!("br i1 {}, label %{}, label %{}", l.op, end, rhs));
+            }
+            f.start(&rhs);
+            let r = self.value(f, right, "an operand")?;
+            if r.ty != Ty::Bool {
+                return unsupported(format!("`{}` on a {}", word, r.ty.name()));
+            }
+            let rhs_end = f.label.clone();
+            f.emit(format!("br label %{}", end));
+            f.start(&end);
+            let op = f.value(format!(
+                "phi i1 [ {}, %{} ], [ {}, %{} ]",
+                !is_and, from, r.op, rhs_end
+            ));
+            return Ok(Val::new(Ty::Bool, op));
+        }
+
+        let l = self.value(f, left, "an operand")?;
+        let r = self.value(f, right, "an operand")?;
+        let mismatch = || {
+            unsupported(format!(
+                "operator {:?} on {} and {}",
+                operator,
+                l.ty.name(),
+                r.ty.name()
+            ))
+        };
+        match (&l.ty, &r.ty) {
+            (Ty::Int, Ty::Int) => self.int_binary(f, operator, &l, &r, right),
+            (Ty::Float, Ty::Float) | (Ty::Int, Ty::Float) | (Ty::Float, Ty::Int) => self
+                .float_binary(f, operator, &l, &r)
+                .unwrap_or_else(mismatch),
+            (Ty::Str, Ty::Str) | (Ty::Bool, Ty::Bool)
+                if matches!(operator, BinaryOp::Equal | BinaryOp::ConstantTimeEq) =>
+            {
+                let op = if l.ty == Ty::Bool {
+                    f.value(format!("icmp eq i1 {}, {}", l.op, r.op))
+                } else {
+                    let eq = f.value(format!("call i32 @jade_str_eq(i8* {}, i8* {})", l.op, r.op));
+                    f.value(format!("icmp ne i32 {}, 0", eq))
+                };
+                Ok(Val::new(Ty::Bool, op))
+            }
+            (Ty::Str, Ty::Str | Ty::Int | Ty::Float | Ty::Bool)
+            | (Ty::Int | Ty::Float | Ty::Bool, Ty::Str)
+                if *operator == BinaryOp::Add =>
+            {
+                let (a, b) = (self.display(f, &l)?, self.display(f, &r)?);
+                let op = f.value(format!("call i8* @jade_concat(i8* {}, i8* {})", a, b));
+                Ok(Val::new(Ty::Str, op))
+            }
+            _ => mismatch(),
+        }
+    }
+
+    fn int_binary(
+        &mut self,
+        f: &mut Func,
+        operator: &BinaryOp,
+        l: &Val,
+        r: &Val,
+        right: &AstNode,
+    ) -> Result<Val> {
+        let (a, b) = (&l.op, &r.op);
+        let checked = match operator {
+            BinaryOp::Add => Some(("sadd", "+")),
+            BinaryOp::Subtract => Some(("ssub", "-")),
+            BinaryOp::Multiply => Some(("smul", "*")),
+            _ => None,
+        };
+        if let Some((intrinsic, symbol)) = checked {
+            let pair = f.value(format!(
+                "call {{ i64, i1 }} @llvm.{}.with.overflow.i64(i64 {}, i64 {})",
+                intrinsic, a, b
+            ));
+            let result = f.value(format!("extractvalue {{ i64, i1 }} {}, 0", pair));
+            let overflow = f.value(format!("extractvalue {{ i64, i1 }} {}, 1", pair));
+            let panic = format!(
+                "call void @jade_overflow(i8* {}, i64 {}, i64 {})",
+                self.string(symbol),
+                a,
+                b
+            );
+            self.guard(f, &overflow, panic);
+            return Ok(Val::new(Ty::Int, result));
+        }
+
+        let compare = |pred: &str| Some(format!("icmp {} i64 {}, {}", pred, a, b));
+        let instruction =
+            match operator {
+                BinaryOp::Divide | BinaryOp::Modulo => {
+                    let zero = f.value(format!("icmp eq i64 {}, 0", b));
+                    let panic = self.panic_call("Cannot divide by zero");
+                    self.guard(f, &zero, panic);
+                    let minus_one = f.value(format!("icmp eq i64 {}, -1", b));
+                    if *operator == BinaryOp::Divide {
+                        let min = f.value(format!("icmp eq i64 {}, {}", a, i64::MIN));
+                        let overflow = f.value(format!("and i1 {}, {}", min, minus_one));
+                        let panic = self.panic_call("Integer overflow: i64::MIN / -1");
+                        self.guard(f, &overflow, panic);
+                        format!("sdiv i64 {}, {}", a, b)
+                    } else {
+                        // `x % -1` is 0, and `srem` of i64::MIN by -1 is undefined.
+                        let divisor = f.value(format!("select i1 {}, i64 1, i64 {}", minus_one, b));
+                        format!("srem i64 {}, {}", a, divisor)
+                    }
+                }
+                BinaryOp::Power => match right {
+                    AstNode::Integer(exponent) if *exponent >= 0 => {
+                        format!("call i64 @jade_ipow(i64 {}, i64 {})", a, b)
+                    }
+                    _ => return unsupported(
+                        "`**` on integers unless the exponent is a non-negative integer literal",
+                    ),
+                },
+                BinaryOp::BitwiseAnd => format!("and i64 {}, {}", a, b),
+                BinaryOp::BitwiseOr => format!("or i64 {}, {}", a, b),
+                BinaryOp::BitwiseXor => format!("xor i64 {}, {}", a, b),
+                BinaryOp::LeftShift | BinaryOp::RightShift => {
+                    let negative = f.value(format!("icmp slt i64 {}, 0", b));
+                    let panic = self.panic_call("Shift amount must be non-negative");
+                    self.guard(f, &negative, panic);
+                    let amount = f.value(format!("and i64 {}, 63", b));
+                    let op = if *operator == BinaryOp::LeftShift {
+                        "shl"
+                    } else {
+                        "ashr"
+                    };
+                    format!("{} i64 {}, {}", op, a, amount)
+                }
+                _ => {
+                    let instruction = match operator {
+                        BinaryOp::Equal | BinaryOp::ConstantTimeEq => compare("eq"),
+                        BinaryOp::Less => compare("slt"),
+                        BinaryOp::Greater => compare("sgt"),
+                        BinaryOp::LessEqual => compare("sle"),
+                        BinaryOp::GreaterEqual => compare("sge"),
+                        _ => None,
+                    };
+                    let Some(instruction) = instruction else {
+                        return unsupported(format!("operator {:?} on int and int", operator));
+                    };
+                    return Ok(Val::new(Ty::Bool, f.value(instruction)));
+                }
+            };
+        Ok(Val::new(Ty::Int, f.value(instruction)))
+    }
+
+    /// Float arithmetic and comparisons, with ints widened; `None` if the interpreter rejects it.
+    fn float_binary(
+        &mut self,
+        f: &mut Func,
+        operator: &BinaryOp,
+        l: &Val,
+        r: &Val,
+    ) -> Option<Result<Val>> {
+        let mixed = l.ty != r.ty;
+        if !mixed && matches!(operator, BinaryOp::Power | BinaryOp::NotEqual) {
+            return None;
+        }
+        let mut widen = |val: &Val| match val.ty {
+            Ty::Int => f.value(format!("sitofp i64 {} to double", val.op)),
+            _ => val.op.clone(),
+        };
+        let (a, b) = (widen(l), widen(r));
+        let arithmetic = |op: &str| format!("{} double {}, {}", op, a, b);
+        let instruction = match operator {
+            BinaryOp::Add => arithmetic("fadd"),
+            BinaryOp::Subtract => arithmetic("fsub"),
+            BinaryOp::Multiply => arithmetic("fmul"),
+            BinaryOp::Divide => {
+                if mixed {
+                    let zero = if r.ty == Ty::Int {
+                        f.value(format!("icmp eq i64 {}, 0", r.op))
+                    } else {
+                        f.value(format!("fcmp oeq double {}, 0.0", r.op))
+                    };
+                    let panic = self.panic_call("Cannot divide by zero");
+                    self.guard(f, &zero, panic);
+                }
+                arithmetic("fdiv")
+            }
+            BinaryOp::Power => format!("call double @llvm.pow.f64(double {}, double {})", a, b),
+            BinaryOp::Equal | BinaryOp::NotEqual if mixed => {
+                // The interpreter compares ints with floats within f64::EPSILON.
+                let diff = f.value(arithmetic("fsub"));
+                let abs = f.value(format!("call double @llvm.fabs.f64(double {})", diff));
+                let pred = if *operator == BinaryOp::Equal {
+                    "olt"
+                } else {
+                    "oge"
+                };
+                let op = f.value(format!(
+                    "fcmp {} double {}, 0x{:016X}",
+                    pred,
+                    abs,
+                    f64::EPSILON.to_bits()
+                ));
+                return Some(Ok(Val::new(Ty::Bool, op)));
+            }
+            _ => {
+                let pred = match operator {
+                    BinaryOp::Equal => "oeq",
+                    BinaryOp::ConstantTimeEq if !mixed => "oeq",
+                    BinaryOp::Less => "olt",
+                    BinaryOp::Greater => "ogt",
+                    BinaryOp::LessEqual => "ole",
+                    BinaryOp::GreaterEqual => "oge",
+                    _ => return None,
+                };
+                let op = f.value(format!("fcmp {} double {}, {}", pred, a, b));
+                return Some(Ok(Val::new(Ty::Bool, op)));
+            }
+        };
+        Some(Ok(Val::new(Ty::Float, f.value(instruction))))
+    }
+
+    fn call(&mut self, f: &mut Func, name: &str, args: &'a [AstNode]) -> Result<Val> {
+        if self.lookup(f, name).is_some() {
+            return unsupported(format!("call through variable '{}'", name));
+        }
+        match (name, args.len()) {
+            ("out", _) => return self.out(f, args),
+            ("len", 1) => return self.len(f, &args[0]),
+            ("push", 2) => return self.push(f, &args[0], &args[1]),
+            _ => {}
+        }
+        if (self.is_builtin)(name) {
+            return match self.functions.contains_key(name) {
+                true => unsupported(format!(
+                    "function '{}' has the name of a builtin, which takes precedence over it",
+                    name
+                )),
+                false => unsupported(format!(
+                    "builtin '{}' with {} arguments is not supported",
+                    name,
+                    args.len()
+                )),
+            };
+        }
+        let Some(&(params, _, _)) = self.functions.get(name) else {
+            return unsupported(format!("call to undefined function '{}'", name));
+        };
+        if params.len() != args.len() {
+            return unsupported(format!(
+                "call to '{}' with {} arguments (declared with {})",
+                name,
+                args.len(),
+                params.len()
+            ));
+        }
+        let mut vals = Vec::new();
+        for arg in args {
+            vals.push(self.value(f, arg, &format!("an argument to '{}'", name))?);
+        }
+        let tys: Vec<Ty> = vals.iter().map(|v| v.ty.clone()).collect();
+        let (symbol, ret) = self.instance(name, &tys)?;
+        let args: Vec<String> = vals.iter().map(Val::typed).collect();
+        let call = format!("call {} @\"{}\"({})", ret.llvm(), symbol, args.join(", "));
+        if ret == Ty::Void {
+            f.emit(call);
+            return Ok(Val::void());
+        }
+        Ok(Val::new(ret, f.value(call)))
+    }
+
+    fn method(
+        &mut self,
+        f: &mut Func,
+        object: &'a AstNode,
+        field: &str,
+        args: &'a [AstNode],
+    ) -> Result<Val> {
+        match (field, args) {
+            ("len" | "length" | "size", []) => {
+                let list = self.value(f, object, "a method receiver")?;
+                if !matches!(list.ty, Ty::List(_)) {
+                    return unsupported(format!("method '.{}' on a {}", field, list.ty.name()));
+                }
+                Ok(Val::new(
+                    Ty::Int,
+                    f.value(format!("call i64 @jade_list_len({})", list.typed())),
+                ))
+            }
+            ("push" | "append", [item]) => self.push(f, object, item),
+            _ => unsupported(format!("method '.{}' with {} arguments", field, args.len())),
+        }
+    }
+
+    fn print(&mut self, f: &mut Func, val: &Val) -> Result<()> {
+        let s = self.display(f, val)?;
+        f.emit(format!("call void @jade_print(i8* {})", s));
+        Ok(())
+    }
+
+    fn out(&mut self, f: &mut Func, args: &'a [AstNode]) -> Result<Val> {
+        let space = Val::new(Ty::Str, self.string(" "));
+        match args {
+            [] => {}
+            [arg] => {
+                let val = self.value(f, arg, "the argument to out()")?;
+                if matches!(&val.ty, Ty::List(item) if matches!(**item, Ty::List(_))) {
+                    return unsupported("out() of a list of lists (printed as a table)");
+                }
+                self.print(f, &val)?;
+            }
+            [first, second] => {
+                let first = self.value(f, first, "an argument to out()")?;
+                let second = self.value(f, second, "an argument to out()")?;
+                self.print(f, &first)?;
+                self.print(f, &space)?;
+                self.print(f, &second)?;
+            }
+            // Printed as they are evaluated, like the interpreter does.
+            _ => {
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        self.print(f, &space)?;
+                    }
+                    let val = self.value(f, arg, "an argument to out()")?;
+                    self.print(f, &val)?;
+                }
+            }
+        }
+        f.emit("call void @jade_println()");
+        Ok(Val::void())
+    }
+
+    fn len(&mut self, f: &mut Func, arg: &'a AstNode) -> Result<Val> {
+        let val = self.value(f, arg, "the argument to len()")?;
+        let op = match &val.ty {
+            Ty::Str => f.value(format!("call i64 @jade_str_len(i8* {})", val.op)),
+            Ty::List(_) => f.value(format!("call i64 @jade_list_len({})", val.typed())),
+            other => return unsupported(format!("len() of a {}", other.name())),
+        };
+        Ok(Val::new(Ty::Int, op))
+    }
+
+    /// `push(xs, x)` and `xs.push(x)`: a copy of the list with `x` appended.
+    fn push(&mut self, f: &mut Func, list: &'a AstNode, item: &'a AstNode) -> Result<Val> {
+        let list = self.value(f, list, "the list pushed to")?;
+        let item = self.value(f, item, "the pushed value")?;
+        let Ty::List(item_ty) = &list.ty else {
+            return unsupported(format!("push() onto a {}", list.ty.name()));
+        };
+        let Some(item_ty) = item_ty.unify(&item.ty) else {
+            return unsupported(format!(
+                "pushing a {} onto a {}",
+                item.ty.name(),
+                list.ty.name()
+            ));
+        };
+        let word = self.pack(f, &item);
+        let op = f.value(format!(
+            "call %jade.list* @jade_list_pushed({}, i64 {})",
+            list.typed(),
+            word
+        ));
+        Ok(Val::new(Ty::List(Box::new(item_ty)), op))
+    }
+
+    /// Compile (or reuse) `name` specialised to argument types `tys`.
+    fn instance(&mut self, name: &str, tys: &[Ty]) -> Result<(String, Ty)> {
+        let key = (name.to_string(), tys.to_vec());
+        if let Some(instance) = self.instances.get(&key) {
+            return match instance.ret.as_ref().or(instance.assumed.as_ref()) {
+                Some(ret) => Ok((instance.symbol.clone(), ret.clone())),
+                None => Err(Error::Unresolved {
+                    symbol: instance.symbol.clone(),
+                    name: name.to_string(),
+                }),
+            };
+        }
+
+        let (params, return_type, body) = self.functions[name];
+        self.next_symbol += 1;
+        let symbol = format!("jade.{}.{}", name, self.next_symbol);
+        let type_names: Vec<String> = tys.iter().map(Ty::name).collect();
+        let fkey = format!("{}({})", name, type_names.join(", "));
+
+        // Without an assumption first; a recursive call then asks for one.
+        let declared = return_type.and_then(Ty::declared);
+        let mut candidates = vec![None];
+        for ty in declared
+            .iter()
+            .chain(&[Ty::Int, Ty::Float, Ty::Bool, Ty::Str, Ty::Void])
+        {
+            if !candidates.contains(&Some(ty.clone())) {
+                candidates.push(Some(ty.clone()));
+            }
+        }
+        let mut declared_error = None;
+        for assumed in candidates {
+            let mark = (
+                self.order.len(),
+                self.definitions.len(),
+                self.refined.clone(),
+                self.changed,
+                self.guessed,
+            );
+            self.order.push(key.clone());
+            self.instances.insert(
+                key.clone(),
+                Instance {
+                    symbol: symbol.clone(),
+                    ret: None,
+                    assumed: assumed.clone(),
+                },
+            );
+            let error = match self.function(&symbol, &fkey, params, tys, body) {
+                Ok((ret, definition)) if assumed.as_ref().is_none_or(|a| *a == ret) => {
+                    if let Some(instance) = self.instances.get_mut(&key) {
+                        instance.ret = Some(ret.clone());
+                    }
+                    self.definitions.push(definition);
+                    return Ok((symbol, ret));
+                }
+                Ok(_) => None,
+                Err(Error::Unresolved { symbol: s, .. }) if s == symbol => None,
+                Err(error) => Some(error),
+            };
+
+            // Undo everything compiled under this attempt.
+            let (order, definitions, refined, changed, guessed) = mark;
+            for stale in self.order.drain(order..) {
+                self.instances.remove(&stale);
+            }
+            self.definitions.truncate(definitions);
+            self.refined = refined;
+            self.changed = changed;
+            self.guessed = guessed;
+            match error {
+                Some(error) if assumed.is_none() => return Err(error),
+                Some(error) if assumed == declared => declared_error = Some(error),
+                _ => {}
+            }
+        }
+        Err(declared_error.unwrap_or(Error::Unresolved {
+            symbol,
+            name: name.to_string(),
+        }))
+    }
+
+    /// Definition of one function instance, with the type of the value it returns.
+    fn function(
+        &mut self,
+        symbol: &str,
+        fkey: &str,
+        params: &[(String, String)],
+        tys: &[Ty],
+        body: &'a AstNode,
+    ) -> Result<(Ty, String)> {
+        let mut f = Func::new(fkey.to_string());
+        let mut scope = HashMap::new();
+        let mut signature = Vec::new();
+        for (i, ((_, name), ty)) in params.iter().zip(tys).enumerate() {
+            let key = format!("{}@param{}", fkey, i);
+            let ty = self.refinement(&key, ty.clone());
+            let ptr = f.alloca(&ty);
+            f.emit(format!(
+                "store {} %a{}, {}* {}",
+                ty.llvm(),
+                i,
+                ty.llvm(),
+                ptr
+            ));
+            signature.push(format!("{} %a{}", ty.llvm(), i));
+            scope.insert(
+                name.clone(),
+                Var {
+                    ptr,
+                    ty,
+                    key,
+                    poisoned: false,
+                },
+            );
+        }
+        f.scopes.push(scope);
+        let result = self.expr(&mut f, body)?;
+        let ret = match result.ty {
+            Ty::Void => "ret void".to_string(),
+            _ => format!("ret {}", result.typed()),
+        };
+        let definition = format!(
+            "define internal {} @\"{}\"({}) {{\nentry:\n{}{}  {}\n}}\n",
+            result.ty.llvm(),
+            symbol,
+            signature.join(", "),
+            f.allocas,
+            f.code,
+            ret
+        );
+        Ok((result.ty, definition))
+    }
+}
@@ -0,0 +1,327 @@
+//! AOT compiler: J source → native binary (LLVM or C).
+//!
+//! The program is lowered to LLVM IR by [`llvm`], compiled with `llc` and linked with the C
+//! runtime in `runtime/native.c`. Without the LLVM tools, or for programs outside the statically
+//! typed subset [`llvm`] handles, it is translated to C by [`c`] and built with the system C
+//! compiler instead.
+
+mod c;
+mod llvm;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::interpreter::Interpreter;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+
+/// Support code for compiled programs, linked into every binary.
+const RUNTIME_C: &str = include_str!("runtime/native.c");
+
+/// C compilers tried, in order, for the C backend. `cl` is MSVC, which takes its own flags.
+const C_COMPILERS: &[&str] = &["cc", "gcc", "clang", "cl"];
+
+/// Prefix of the diagnostics for programs a backend cannot compile.
+fn cannot_compile(what: &str) -> String {
+    format!("`jade build` cannot compile this program: {}", what)
+}
+
+/// Intermediate source `jade build --emit` keeps next to the binary; it also picks the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emit {
+    /// `{output}.c`, built with the C backend.
+    C,
+    /// `{output}.ll`, built with LLVM.
+    Llvm,
+}
+
+pub struct AotCompiler {
+    optimization_level: u8,
+    target_triple: String,
+    emit: Option<Emit>,
+}
+
+impl AotCompiler {
+    pub fn new() -> Self {
+        Self {
+            optimization_level: 0,
+            target_triple: Self::get_target_triple(),
+            emit: None,
+        }
+    }
+
+    pub fn compile_file(
+        &mut self,
+        file_path: PathBuf,
+        release: bool,
+        output: Option<&str>,
+    ) -> Result<(), String> {
+        let source =
+            fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+        self.optimization_level = if release { 3 } else { 0 };
+
+        let output_name = output.unwrap_or_else(|| {
+            file_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("output")
+        });
+
+        println!("📝 Parsing source code...");
+
+        let mut lexer = Lexer::new(&source);
+        let tokens = lexer
+            .tokenize()
+            .map_err(|e| format!("Lexer error: {}", e))?;
+
+        let mut parser = Parser::new(tokens);
+        let ast = parser.parse().map_err(|e| format!("Parser error: {}", e))?;
+
+        match self.emit {
+            Some(Emit::Llvm) => self.build_with_llvm(&ast, output_name, release)?,
+            Some(Emit::C) => self.build_with_c(&ast, output_name, release)?,
+            None if self.check_llvm_tools() => {
+                println!("🔧 Generating LLVM IR...");
+                match self.generate_llvm_ir(&ast) {
+                    Ok(ir) => self.compile_llvm_module(ir, output_name, release)?,
+                    Err(e) if Self::find_c_compiler().is_some() => {
+                        println!("↪️  {}; using the C backend", e);
+                        self.build_with_c(&ast, output_name, release)?;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            None if Self::find_c_compiler().is_some() => {
+                println!("↪️  LLVM tools (llc, opt) not found; using the C backend");
+                self.build_with_c(&ast, output_name, release)?;
+            }
+            None => {
+                return Err(
+                    "no native toolchain found: install LLVM (llc, opt) or a C compiler (cc, gcc, clang, cl)"
+                        .to_string(),
+                )
+            }
+        }
+
+        println!("🎉 Binary created: {}", output_name);
+
+        Ok(())
+    }
+
+    fn build_with_llvm(
+        &self,
+        ast: &crate::parser::AstNode,
+        output_name: &str,
+        release: bool,
+    ) -> Result<(), String> {
+        println!("🔧 Generating LLVM IR...");
+        let llvm_ir = self.generate_llvm_ir(ast)?;
+        self.compile_llvm_module(llvm_ir, output_name, release)
+    }
+
+    fn compile_llvm_module(
+        &self,
+        llvm_ir: String,
+        output_name: &str,
+        release: bool,
+    ) -> Result<(), String> {
+        let ir_file = format!("{}.ll", output_name);
+        fs::write(&ir_file, llvm_ir).map_err(|e| format!("Failed to write LLVM IR: {}", e))?;
+
+        println!("⚡ Compiling to native code...");
+        let result = self.compile_llvm_ir(&ir_file, output_name, release);
+
+        if self.emit != Some(Emit::Llvm) {
+            let _ = fs::remove_file(&ir_file);
+        }
+        result
+    }
+
+    fn build_with_c(
+        &self,
+        ast: &crate::parser::AstNode,
+        output_name: &str,
+        release: bool,
+    ) -> Result<(), String> {
+        println!("🔧 Generating C...");
+        let source = self.generate_c(ast)?;
+        let Some(cc) = Self::find_c_compiler() else {
+            return Err("No C compiler (cc, gcc, clang, cl) found".to_string());
+        };
+
+        let c_file = format!("{}.c", output_name);
+        fs::write(&c_file, source).map_err(|e| format!("Failed to write C source: {}", e))?;
+
+        println!("⚡ Compiling to native code with {}...", cc);
+        let binary = self.binary_name(output_name);
+        let mut command = Command::new(cc);
+        if cc == "cl" {
+            // MSVC links the C runtime, math included, by default
+            let opt_level = if release { "/O2" } else { "/Od" };
+            let binary = format!("/Fe{}", binary);
+            command.args(["/nologo", c_file.as_str(), opt_level, binary.as_str()]);
+        } else {
+            let opt_level = if release { "-O2" } else { "-O0" };
+            command.args([c_file.as_str(), opt_level, "-o", binary.as_str(), "-lm"]);
+        }
+        let status = command
+            .status()
+            .map_err(|e| format!("Failed to run {}: {}", cc, e));
+
+        if cc == "cl" {
+            let _ = fs::remove_file(format!("{}.obj", output_name));
+        }
+        if self.emit != Some(Emit::C) {
+            let _ = fs::remove_file(&c_file);
+        }
+        if !status?.success() {
+            return Err("C compilation failed".to_string());
+        }
+        Ok(())
+    }
+
+    /// Lower a parsed program to LLVM IR, or explain which part of it cannot be compiled.
+    pub fn generate_llvm_ir(&self, ast: &crate::parser::AstNode) -> Result<String, String> {
+        // Builtins shadow user functions in the interpreter, so the compiler must know them.
+        llvm::lower(ast, &self.target_triple, &Interpreter::is_builtin)
+    }
+
+    /// Translate a parsed program to a self-contained C file, or explain which part of it cannot
+    /// be compiled.
+    pub fn generate_c(&self, ast: &crate::parser::AstNode) -> Result<String, String> {
+        c::translate(ast, &Interpreter::is_builtin)
+    }
+
+    fn compile_llvm_ir(
+        &self,
+        ir_file: &str,
+        output_name: &str,
+        release: bool,
+    ) -> Result<(), String> {
+        let opt_level = if release { "3" } else { "0" };
+
+        if !self.check_llvm_tools() {
+            return Err(
+                "LLVM tools (llc, opt) not found; install LLVM to build native binaries".to_string(),
+            );
+        }
+
+        if release {
+            let optimized_ir = format!("{}.opt.ll", output_name);
+
+            let opt_status = Command::new("opt")
+                .args([&format!("-O{}", opt_level), ir_file, "-o", &optimized_ir])
+                .status()
+                .map_err(|e| format!("Failed to run opt: {}", e))?;
+
+            if !opt_status.success() {
+                return Err("LLVM optimization failed".to_string());
+            }
+            self.compile_ir_to_binary(&optimized_ir, output_name)?;
+            let _ = fs::remove_file(&optimized_ir);
+        } else {
+            self.compile_ir_to_binary(ir_file, output_name)?;
+        }
+
+        Ok(())
+    }
+
+    fn compile_ir_to_binary(&self, ir_file: &str, output_name: &str) -> Result<(), String> {
+        let output_file = self.binary_name(output_name);
+
+        let llc_status = Command::new("llc")
+            .args([ir_file, "-relocation-model=pic", "-o", &format!("{}.s", output_name)])
+            .status()
+            .map_err(|e| format!("Failed to run llc: {}", e))?;
+
+        if !llc_status.success() {
+            return Err("LLVM code generation failed".to_string());
+        }
+
+        let runtime_file = format!("{}.runtime.c", output_name);
+        fs::write(&runtime_file, RUNTIME_C)
+            .map_err(|e| format!("Failed to write runtime source: {}", e))?;
+
+        let linker = if cfg!(windows) { "link" } else { "gcc" };
+
+        let link_status = Command::new(linker)
+            .args([
+                &format!("{}.s", output_name),
+                &runtime_file,
+                "-O2",
+                "-o",
+                &output_file,
+                "-lm",
+            ])
+            .status()
+            .map_err(|e| format!("Failed to run linker: {}", e))?;
+
+        let _ = fs::remove_file(format!("{}.s", output_name));
+        let _ = fs::remove_file(&runtime_file);
+
+        if !link_status.success() {
+            return Err("Linking failed".to_string());
+        }
+
+        Ok(())
+    }
+
+    fn find_c_compiler() -> Option<&'static str> {
+        // Only whether the compiler runs matters: `cl` rejects `--version` but still starts
+        C_COMPILERS
+            .iter()
+            .copied()
+            .find(|cc| Command::new(cc).arg("--version").output().is_ok())
+    }
+
+    fn check_llvm_tools(&self) -> bool {
+        Command::new("llc").arg("--version").output().is_ok()
+            && Command::new("opt").arg("--version").output().is_ok()
+    }
+
+    fn get_target_triple() -> String {
+        let win = cfg!(target_os = "windows");
+        let mac = cfg!(target_os = "macos");
+        let x64 = cfg!(target_arch = "x86_64");
+        let aarch = cfg!(target_arch = "aarch64");
+        let s = match (win, mac, x64, aarch) {
+            (true, _, true, _) => "x86_64-pc-windows-msvc",
+            (true, _, _, _) => "i686-pc-windows-msvc",
+            (_, true, _, true) => "aarch64-apple-darwin",
+            (_, true, _, _) => "x86_64-apple-darwin",
+            (_, _, true, _) => "x86_64-unknown-linux-gnu",
+            (_, _, _, true) => "aarch64-unknown-linux-gnu",
+            _ => "i686-unknown-linux-gnu",
+        };
+        s.to_string()
+    }
+
+    fn binary_name(&self, name: &str) -> String {
+        if cfg!(windows) {
+            format!("{}.exe", name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    pub fn set_optimization_level(&mut self, level: u8) {
+        self.optimization_level = level.min(3);
+    }
+
+    pub fn set_target(&mut self, target: String) {
+        self.target_triple = target;
+    }
+
+    /// Keep the generated C or LLVM IR next to the binary, building with that backend.
+    pub fn set_emit(&mut self, emit: Option<Emit>) {
+        self.emit = emit;
+    }
+}
+
+impl Default for AotCompiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,1029 @@
+/* Runtime for programs translated to C by `jade build`; the generated program is appended to it.
+ *
+ * Values are dynamically typed `jade_value`s, as in the interpreter. Strings, lists and dicts are
+ * reference counted and never modified once built, so sharing them is safe. Every runtime call
+ * borrows its arguments and returns a value its caller owns. Operators, indexing and `out()`
+ * follow the interpreter, including its runtime error messages. */
+
+#include <inttypes.h>
+#include <math.h>
+#include <stdarg.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+typedef enum {
+    JADE_UNDEF, /* a variable that has not been given a value yet */
+    JADE_NONE,
+    JADE_INT,
+    JADE_FLOAT,
+    JADE_BOOL,
+    JADE_STR,
+    JADE_CHAR,
+    JADE_LIST,
+    JADE_DICT,
+} jade_tag;
+
+typedef struct jade_str jade_str;
+typedef struct jade_list jade_list;
+typedef struct jade_dict jade_dict;
+
+typedef struct {
+    jade_tag tag;
+    union {
+        int64_t i;
+        double f;
+        int b;
+        uint32_t c;
+        jade_str *s;
+        jade_list *l;
+        jade_dict *d;
+    } as;
+} jade_value;
+
+struct jade_str {
+    int64_t refs;
+    int64_t len; /* bytes, excluding the terminating NUL */
+    char data[];
+};
+
+struct jade_list {
+    int64_t refs;
+    int64_t len, cap;
+    jade_value *items;
+};
+
+/* Entries in insertion order, found through an open-addressing table of entry indices. */
+struct jade_dict {
+    int64_t refs;
+    int64_t len, cap;
+    jade_str **keys;
+    jade_value *values;
+    int64_t *slots; /* entry index + 1, or 0 when free */
+    int64_t nslots;
+};
+
+/* Operators, named as in the interpreter's error messages. */
+enum {
+    JADE_OP_ADD,
+    JADE_OP_SUBTRACT,
+    JADE_OP_MULTIPLY,
+    JADE_OP_DIVIDE,
+    JADE_OP_MODULO,
+    JADE_OP_POWER,
+    JADE_OP_EQUAL,
+    JADE_OP_NOT_EQUAL,
+    JADE_OP_LESS,
+    JADE_OP_GREATER,
+    JADE_OP_LESS_EQUAL,
+    JADE_OP_GREATER_EQUAL,
+    JADE_OP_BITWISE_AND,
+    JADE_OP_BITWISE_OR,
+    JADE_OP_BITWISE_XOR,
+    JADE_OP_LEFT_SHIFT,
+    JADE_OP_RIGHT_SHIFT,
+};
+
+static const char *const jade_op_names[] = {
+    "Add",          "Subtract",   "Multiply", "Divide",     "Modulo",     "Power",
+    "Equal",        "NotEqual",   "Less",     "Greater",    "LessEqual",  "GreaterEqual",
+    "BitwiseAnd",   "BitwiseOr",  "BitwiseXor", "LeftShift", "RightShift",
+};
+
+enum { JADE_UNARY_MINUS, JADE_UNARY_NOT, JADE_UNARY_BITWISE_NOT };
+
+static const char *const jade_unary_names[] = {"Minus", "Not", "BitwiseNot"};
+
+/* Same default as `jade --max-depth`. */
+#define JADE_MAX_DEPTH 10000
+static int64_t jade_depth;
+
+/* ---- errors and memory ---- */
+
+_Noreturn void jade_panic(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "Runtime error: %s\n", message);
+    exit(1);
+}
+
+_Noreturn static void jade_panicf(const char *format, ...) {
+    va_list args;
+    va_start(args, format);
+    int n = vsnprintf(NULL, 0, format, args);
+    va_end(args);
+    char *message = malloc((size_t)n + 1);
+    if (!message) jade_panic("Out of memory");
+    va_start(args, format);
+    vsnprintf(message, (size_t)n + 1, format, args);
+    va_end(args);
+    jade_panic(message);
+}
+
+static void *jade_alloc(size_t size) {
+    void *p = malloc(size ? size : 1);
+    if (!p) jade_panic("Out of memory");
+    return p;
+}
+
+static void *jade_realloc(void *p, size_t size) {
+    p = realloc(p, size ? size : 1);
+    if (!p) jade_panic("Out of memory");
+    return p;
+}
+
+jade_value jade_retain(jade_value v) {
+    switch (v.tag) {
+    case JADE_STR: v.as.s->refs++; break;
+    case JADE_LIST: v.as.l->refs++; break;
+    case JADE_DICT: v.as.d->refs++; break;
+    default: break;
+    }
+    return v;
+}
+
+void jade_release(jade_value v) {
+    switch (v.tag) {
+    case JADE_STR:
+        if (--v.as.s->refs == 0) free(v.as.s);
+        break;
+    case JADE_LIST:
+        if (--v.as.l->refs == 0) {
+            for (int64_t i = 0; i < v.as.l->len; i++) jade_release(v.as.l->items[i]);
+            free(v.as.l->items);
+            free(v.as.l);
+        }
+        break;
+    case JADE_DICT:
+        if (--v.as.d->refs == 0) {
+            jade_dict *d = v.as.d;
+            for (int64_t i = 0; i < d->len; i++) {
+                if (--d->keys[i]->refs == 0) free(d->keys[i]);
+                jade_release(d->values[i]);
+            }
+            free(d->keys);
+            free(d->values);
+            free(d->slots);
+            free(d);
+        }
+        break;
+    default:
+        break;
+    }
+}
+
+/* ---- constructors ---- */
+
+jade_value jade_none(void) {
+    jade_value v = {JADE_NONE, {0}};
+    return v;
+}
+
+jade_value jade_undef(void) {
+    jade_value v = {JADE_UNDEF, {0}};
+    return v;
+}
+
+jade_value jade_int(int64_t i) {
+    jade_value v = {JADE_INT, {.i = i}};
+    return v;
+}
+
+jade_value jade_float(double f) {
+    jade_value v = {JADE_FLOAT, {.f = f}};
+    return v;
+}
+
+jade_value jade_bool(int b) {
+    jade_value v = {JADE_BOOL, {.b = b != 0}};
+    return v;
+}
+
+jade_value jade_char(uint32_t c) {
+    jade_value v = {JADE_CHAR, {.c = c}};
+    return v;
+}
+
+static jade_str *jade_str_new(const char *data, int64_t len) {
+    jade_str *s = jade_alloc(sizeof *s + (size_t)len + 1);
+    s->refs = 1;
+    s->len = len;
+    memcpy(s->data, data, (size_t)len);
+    s->data[len] = '\0';
+    return s;
+}
+
+jade_value jade_new_str(const char *data, int64_t len) {
+    jade_value v = {JADE_STR, {.s = jade_str_new(data, len)}};
+    return v;
+}
+
+static jade_list *jade_list_alloc(int64_t cap) {
+    jade_list *l = jade_alloc(sizeof *l);
+    l->refs = 1;
+    l->len = 0;
+    l->cap = cap > 4 ? cap : 4;
+    l->items = jade_alloc((size_t)l->cap * sizeof(jade_value));
+    return l;
+}
+
+/* Append in place, taking ownership of `item`; only for lists nobody else can see yet. */
+static void jade_list_add(jade_list *l, jade_value item) {
+    if (l->len == l->cap) {
+        l->cap *= 2;
+        l->items = jade_realloc(l->items, (size_t)l->cap * sizeof(jade_value));
+    }
+    l->items[l->len++] = item;
+}
+
+static jade_value jade_list_value(jade_list *l) {
+    jade_value v = {JADE_LIST, {.l = l}};
+    return v;
+}
+
+/* A list literal of `n` items, taking ownership of them. */
+jade_value jade_new_list(int64_t n, ...) {
+    jade_list *l = jade_list_alloc(n);
+    va_list args;
+    va_start(args, n);
+    for (int64_t i = 0; i < n; i++) jade_list_add(l, va_arg(args, jade_value));
+    va_end(args);
+    return jade_list_value(l);
+}
+
+static uint64_t jade_hash(const char *data, int64_t len) {
+    uint64_t h = 1469598103934665603ULL;
+    for (int64_t i = 0; i < len; i++) h = (h ^ (unsigned char)data[i]) * 1099511628211ULL;
+    return h;
+}
+
+/* Entry index of `key`, or -1. */
+static int64_t jade_dict_find(const jade_dict *d, const char *key, int64_t len) {
+    if (d->nslots == 0) return -1;
+    uint64_t mask = (uint64_t)d->nslots - 1;
+    for (uint64_t i = jade_hash(key, len) & mask;; i = (i + 1) & mask) {
+        int64_t slot = d->slots[i];
+        if (slot == 0) return -1;
+        jade_str *k = d->keys[slot - 1];
+        if (k->len == len && memcmp(k->data, key, (size_t)len) == 0) return slot - 1;
+    }
+}
+
+static void jade_dict_index(jade_dict *d, int64_t entry) {
+    uint64_t mask = (uint64_t)d->nslots - 1;
+    uint64_t i = jade_hash(d->keys[entry]->data, d->keys[entry]->len) & mask;
+    while (d->slots[i] != 0) i = (i + 1) & mask;
+    d->slots[i] = entry + 1;
+}
+
+/* Insert or replace, taking ownership of `key` and `value`; only while building a dict. */
+static void jade_dict_put(jade_dict *d, jade_str *key, jade_value value) {
+    int64_t found = jade_dict_find(d, key->data, key->len);
+    if (found >= 0) {
+        if (--key->refs == 0) free(key);
+        jade_release(d->values[found]);
+        d->values[found] = value;
+        return;
+    }
+    if (d->len == d->cap) {
+        d->cap = d->cap ? d->cap * 2 : 4;
+        d->keys = jade_realloc(d->keys, (size_t)d->cap * sizeof(jade_str *));
+        d->values = jade_realloc(d->values, (size_t)d->cap * sizeof(jade_value));
+    }
+    d->keys[d->len] = key;
+    d->values[d->len] = value;
+    d->len++;
+    if (d->len * 2 > d->nslots) {
+        free(d->slots);
+        d->nslots = d->nslots ? d->nslots * 2 : 8;
+        while (d->len * 2 > d->nslots) d->nslots *= 2;
+        d->slots = calloc((size_t)d->nslots, sizeof(int64_t));
+        if (!d->slots) jade_panic("Out of memory");
+        for (int64_t i = 0; i < d->len; i++) jade_dict_index(d, i);
+    } else {
+        jade_dict_index(d, d->len - 1);
+    }
+}
+
+/* A dict literal of `n` key/value pairs, taking ownership of them. Keys must be strings or
+ * integers; integers are stored as their decimal text. */
+jade_value jade_new_dict(int64_t n, ...) {
+    jade_dict *d = jade_alloc(sizeof *d);
+    memset(d, 0, sizeof *d);
+    d->refs = 1;
+    va_list args;
+    va_start(args, n);
+    for (int64_t i = 0; i < n; i++) {
+        jade_value key = va_arg(args, jade_value);
+        jade_value value = va_arg(args, jade_value);
+        jade_str *k;
+        if (key.tag == JADE_STR) {
+            k = key.as.s;
+        } else if (key.tag == JADE_INT) {
+            char buf[24];
+            snprintf(buf, sizeof buf, "%" PRId64, key.as.i);
+            k = jade_str_new(buf, (int64_t)strlen(buf));
+        } else {
+            jade_panic("Dictionary keys must be strings or integers");
+        }
+        jade_dict_put(d, k, value);
+    }
+    va_end(args);
+    jade_value v = {JADE_DICT, {.d = d}};
+    return v;
+}
+
+/* ---- formatting ---- */
+
+typedef struct {
+    char *data;
+    size_t len, cap;
+} jade_buf;
+
+static void jade_buf_add(jade_buf *b, const char *s, size_t n) {
+    if (b->len + n + 1 > b->cap) {
+        b->cap = (b->len + n + 1) * 2;
+        b->data = jade_realloc(b->data, b->cap);
+    }
+    memcpy(b->data + b->len, s, n);
+    b->len += n;
+    b->data[b->len] = '\0';
+}
+
+static void jade_buf_push(jade_buf *b, const char *s) { jade_buf_add(b, s, strlen(s)); }
+
+static int jade_utf8_encode(uint32_t c, char *out) {
+    if (c < 0x80) {
+        out[0] = (char)c;
+        return 1;
+    }
+    if (c < 0x800) {
+        out[0] = (char)(0xC0 | (c >> 6));
+        out[1] = (char)(0x80 | (c & 0x3F));
+        return 2;
+    }
+    if (c < 0x10000) {
+        out[0] = (char)(0xE0 | (c >> 12));
+        out[1] = (char)(0x80 | ((c >> 6) & 0x3F));
+        out[2] = (char)(0x80 | (c & 0x3F));
+        return 3;
+    }
+    out[0] = (char)(0xF0 | (c >> 18));
+    out[1] = (char)(0x80 | ((c >> 12) & 0x3F));
+    out[2] = (char)(0x80 | ((c >> 6) & 0x3F));
+    out[3] = (char)(0x80 | (c & 0x3F));
+    return 4;
+}
+
+/* Decode the character starting at `s`, storing its byte length in `width`. */
+static uint32_t jade_utf8_decode(const unsigned char *s, int *width) {
+    if (s[0] < 0x80) {
+        *width = 1;
+        return s[0];
+    }
+    if (s[0] < 0xE0) {
+        *width = 2;
+        return ((uint32_t)(s[0] & 0x1F) << 6) | (s[1] & 0x3F);
+    }
+    if (s[0] < 0xF0) {
+        *width = 3;
+        return ((uint32_t)(s[0] & 0x0F) << 12) | ((uint32_t)(s[1] & 0x3F) << 6) | (s[2] & 0x3F);
+    }
+    *width = 4;
+    return ((uint32_t)(s[0] & 0x07) << 18) | ((uint32_t)(s[1] & 0x3F) << 12) |
+           ((uint32_t)(s[2] & 0x3F) << 6) | (s[3] & 0x3F);
+}
+
+static int64_t jade_utf8_count(const jade_str *s) {
+    int64_t n = 0;
+    for (int64_t i = 0; i < s->len; i++)
+        if (((unsigned char)s->data[i] & 0xC0) != 0x80) n++;
+    return n;
+}
+
+/* Shortest digits that read back as `x`, written out without an exponent (like Rust's `{}`). */
+static void jade_format_float(jade_buf *b, double x) {
+    if (isnan(x)) {
+        jade_buf_push(b, "NaN");
+        return;
+    }
+    if (isinf(x)) {
+        jade_buf_push(b, x < 0 ? "-inf" : "inf");
+        return;
+    }
+    char buf[40];
+    for (int precision = 1; precision <= 17; precision++) {
+        snprintf(buf, sizeof buf, "%.*e", precision - 1, x);
+        if (strtod(buf, NULL) == x) break;
+    }
+    int negative = buf[0] == '-';
+    char digits[20];
+    int n = 0;
+    const char *p = buf + negative;
+    for (; *p != 'e'; p++)
+        if (*p != '.') digits[n++] = *p;
+    int exponent = atoi(p + 1);
+    while (n > 1 && digits[n - 1] == '0') n--;
+
+    if (negative) jade_buf_push(b, "-");
+    if (exponent >= n - 1) {
+        jade_buf_add(b, digits, (size_t)n);
+        for (int i = 0; i < exponent - (n - 1); i++) jade_buf_push(b, "0");
+    } else if (exponent >= 0) {
+        jade_buf_add(b, digits, (size_t)exponent + 1);
+        jade_buf_push(b, ".");
+        jade_buf_add(b, digits + exponent + 1, (size_t)(n - exponent - 1));
+    } else {
+        jade_buf_push(b, "0.");
+        for (int i = 0; i < -exponent - 1; i++) jade_buf_push(b, "0");
+        jade_buf_add(b, digits, (size_t)n);
+    }
+}
+
+/* Append `v` as the interpreter's `Display` shows it. */
+static void jade_format(jade_buf *b, jade_value v) {
+    char buf[24];
+    switch (v.tag) {
+    case JADE_UNDEF:
+    case JADE_NONE:
+        jade_buf_push(b, "none");
+        break;
+    case JADE_INT:
+        snprintf(buf, sizeof buf, "%" PRId64, v.as.i);
+        jade_buf_push(b, buf);
+        break;
+    case JADE_FLOAT:
+        jade_format_float(b, v.as.f);
+        break;
+    case JADE_BOOL:
+        jade_buf_push(b, v.as.b ? "true" : "false");
+        break;
+    case JADE_STR:
+        jade_buf_add(b, v.as.s->data, (size_t)v.as.s->len);
+        break;
+    case JADE_CHAR:
+        jade_buf_add(b, buf, (size_t)jade_utf8_encode(v.as.c, buf));
+        break;
+    case JADE_LIST:
+        jade_buf_push(b, "[");
+        for (int64_t i = 0; i < v.as.l->len; i++) {
+            if (i > 0) jade_buf_push(b, ", ");
+            jade_format(b, v.as.l->items[i]);
+        }
+        jade_buf_push(b, "]");
+        break;
+    case JADE_DICT:
+        jade_buf_push(b, "{");
+        for (int64_t i = 0; i < v.as.d->len; i++) {
+            if (i > 0) jade_buf_push(b, ", ");
+            jade_buf_add(b, v.as.d->keys[i]->data, (size_t)v.as.d->keys[i]->len);
+            jade_buf_push(b, ": ");
+            jade_format(b, v.as.d->values[i]);
+        }
+        jade_buf_push(b, "}");
+        break;
+    }
+}
+
+static jade_value jade_buf_value(jade_buf *b) {
+    jade_value v = jade_new_str(b->data ? b->data : "", (int64_t)b->len);
+    free(b->data);
+    return v;
+}
+
+/* `Display` text of `v` in a buffer the caller frees. */
+static char *jade_text(jade_value v) {
+    jade_buf b = {0};
+    jade_buf_push(&b, "");
+    jade_format(&b, v);
+    return b.data;
+}
+
+/* One step of a string interpolation: `acc` followed by the text of `v`. */
+jade_value jade_interpolate(jade_value acc, jade_value v) {
+    jade_buf b = {0};
+    jade_format(&b, acc);
+    jade_format(&b, v);
+    return jade_buf_value(&b);
+}
+
+/* ---- operators ---- */
+
+int jade_truthy(jade_value v) {
+    switch (v.tag) {
+    case JADE_BOOL: return v.as.b;
+    case JADE_INT: return v.as.i != 0;
+    case JADE_FLOAT: return v.as.f != 0.0;
+    case JADE_STR: return v.as.s->len != 0;
+    case JADE_LIST: return v.as.l->len != 0;
+    case JADE_UNDEF:
+    case JADE_NONE: return 0;
+    default: return 1;
+    }
+}
+
+/* The boolean operand of `and` (`op` "And") or `or` ("Or"). */
+int jade_logic(jade_value v, const char *op) {
+    if (v.tag != JADE_BOOL) jade_panicf("%s operator requires boolean operands", op);
+    return v.as.b;
+}
+
+_Noreturn static void jade_unsupported_binary(int op, jade_value a, jade_value b) {
+    jade_panicf("Unsupported binary operation: %s %s %s", jade_text(a), jade_op_names[op],
+                jade_text(b));
+}
+
+static int jade_is_collection(jade_value v) { return v.tag == JADE_LIST || v.tag == JADE_DICT; }
+
+/* Deep equality of list and dict items (floats compare within EPSILON). */
+static int jade_values_equal(jade_value a, jade_value b) {
+    const double epsilon = 2.220446049250313e-16;
+    if (a.tag == JADE_INT && b.tag == JADE_FLOAT) return fabs((double)a.as.i - b.as.f) < epsilon;
+    if (a.tag == JADE_FLOAT && b.tag == JADE_INT) return fabs(a.as.f - (double)b.as.i) < epsilon;
+    if (a.tag != b.tag) return 0;
+    switch (a.tag) {
+    case JADE_INT: return a.as.i == b.as.i;
+    case JADE_FLOAT: return fabs(a.as.f - b.as.f) < epsilon;
+    case JADE_BOOL: return a.as.b == b.as.b;
+    case JADE_CHAR: return a.as.c == b.as.c;
+    case JADE_STR:
+        return a.as.s->len == b.as.s->len &&
+               memcmp(a.as.s->data, b.as.s->data, (size_t)a.as.s->len) == 0;
+    case JADE_LIST:
+        if (a.as.l->len != b.as.l->len) return 0;
+        for (int64_t i = 0; i < a.as.l->len; i++)
+            if (!jade_values_equal(a.as.l->items[i], b.as.l->items[i])) return 0;
+        return 1;
+    case JADE_DICT:
+        if (a.as.d->len != b.as.d->len) return 0;
+        for (int64_t i = 0; i < a.as.d->len; i++) {
+            jade_str *key = a.as.d->keys[i];
+            int64_t j = jade_dict_find(b.as.d, key->data, key->len);
+            if (j < 0 || !jade_values_equal(a.as.d->values[i], b.as.d->values[j])) return 0;
+        }
+        return 1;
+    default:
+        return 0;
+    }
+}
+
+/* Checked 64-bit arithmetic: each stores `a op b` in `*r` and returns nonzero on overflow. GCC and
+ * Clang have builtins for it; MSVC gets the same checks spelled out. */
+#ifdef _MSC_VER
+static int jade_add_overflow(int64_t a, int64_t b, int64_t *r) {
+    if ((b > 0 && a > INT64_MAX - b) || (b < 0 && a < INT64_MIN - b)) return 1;
+    *r = a + b;
+    return 0;
+}
+
+static int jade_sub_overflow(int64_t a, int64_t b, int64_t *r) {
+    if ((b < 0 && a > INT64_MAX + b) || (b > 0 && a < INT64_MIN + b)) return 1;
+    *r = a - b;
+    return 0;
+}
+
+static int jade_mul_overflow(int64_t a, int64_t b, int64_t *r) {
+    /* No division here has INT64_MIN over -1 */
+    if (a > 0 ? (b > 0 ? a > INT64_MAX / b : b < INT64_MIN / a)
+              : (b > 0 ? a < INT64_MIN / b : b != 0 && a < INT64_MAX / b))
+        return 1;
+    *r = a * b;
+    return 0;
+}
+#else
+#define jade_add_overflow __builtin_add_overflow
+#define jade_sub_overflow __builtin_sub_overflow
+#define jade_mul_overflow __builtin_mul_overflow
+#endif
+
+/* `base ** exponent` by squaring, failing only when the result itself overflows. */
+static int64_t jade_ipow(int64_t base, int64_t exponent) {
+    int64_t acc = 1, b = base, e = exponent;
+    if (e == 0) return 1;
+    while (e > 1) {
+        if ((e & 1) && jade_mul_overflow(acc, b, &acc)) break;
+        e /= 2;
+        if (jade_mul_overflow(b, b, &b)) break;
+    }
+    if (e == 1 && !jade_mul_overflow(acc, b, &acc)) return acc;
+    jade_panicf("Integer overflow: %" PRId64 " ** %" PRId64, base, exponent);
+}
+
+static jade_value jade_int_binary(int op, int64_t a, int64_t b) {
+    int64_t r;
+    switch (op) {
+    case JADE_OP_ADD:
+        if (jade_add_overflow(a, b, &r))
+            jade_panicf("Integer overflow: %" PRId64 " + %" PRId64, a, b);
+        return jade_int(r);
+    case JADE_OP_SUBTRACT:
+        if (jade_sub_overflow(a, b, &r))
+            jade_panicf("Integer overflow: %" PRId64 " - %" PRId64, a, b);
+        return jade_int(r);
+    case JADE_OP_MULTIPLY:
+        if (jade_mul_overflow(a, b, &r))
+            jade_panicf("Integer overflow: %" PRId64 " * %" PRId64, a, b);
+        return jade_int(r);
+    case JADE_OP_DIVIDE:
+        if (b == 0) jade_panic("Cannot divide by zero");
+        if (a == INT64_MIN && b == -1) jade_panic("Integer overflow: i64::MIN / -1");
+        return jade_int(a / b);
+    case JADE_OP_MODULO:
+        if (b == 0) jade_panic("Cannot divide by zero");
+        if (a == INT64_MIN && b == -1) return jade_int(0);
+        return jade_int(a % b);
+    case JADE_OP_POWER:
+        if (b < 0) return jade_float(pow((double)a, (double)b));
+        if (b > (int64_t)UINT32_MAX) jade_panic("Exponent too large");
+        return jade_int(jade_ipow(a, b));
+    case JADE_OP_EQUAL: return jade_bool(a == b);
+    case JADE_OP_LESS: return jade_bool(a < b);
+    case JADE_OP_GREATER: return jade_bool(a > b);
+    case JADE_OP_LESS_EQUAL: return jade_bool(a <= b);
+    case JADE_OP_GREATER_EQUAL: return jade_bool(a >= b);
+    case JADE_OP_BITWISE_AND: return jade_int(a & b);
+    case JADE_OP_BITWISE_OR: return jade_int(a | b);
+    case JADE_OP_BITWISE_XOR: return jade_int(a ^ b);
+    case JADE_OP_LEFT_SHIFT:
+        if (b < 0) jade_panic("Shift amount must be non-negative");
+        return jade_int((int64_t)((uint64_t)a << (b & 63)));
+    case JADE_OP_RIGHT_SHIFT:
+        if (b < 0) jade_panic("Shift amount must be non-negative");
+        return jade_int(a >> (b & 63));
+    default:
+        jade_unsupported_binary(op, jade_int(a), jade_int(b));
+    }
+}
+
+/* Arithmetic and comparisons on floats; `a_int`/`b_int` say which operand was an int, since
+ * mixed operations differ from float ones in a few places. Returns NONE when unsupported. */
+static jade_value jade_float_binary(int op, double a, double b, int a_int, int b_int) {
+    const double epsilon = 2.220446049250313e-16;
+    int mixed = a_int || b_int;
+    switch (op) {
+    case JADE_OP_ADD: return jade_float(a + b);
+    case JADE_OP_SUBTRACT: return jade_float(a - b);
+    case JADE_OP_MULTIPLY: return jade_float(a * b);
+    case JADE_OP_DIVIDE:
+        if (mixed && b == 0.0) jade_panic("Cannot divide by zero");
+        return jade_float(a / b);
+    case JADE_OP_POWER:
+        if (!mixed) break;
+        return jade_float(pow(a, b));
+    case JADE_OP_EQUAL: return jade_bool(mixed ? fabs(a - b) < epsilon : a == b);
+    case JADE_OP_NOT_EQUAL:
+        if (!mixed) break;
+        return jade_bool(fabs(a - b) >= epsilon);
+    case JADE_OP_LESS: return jade_bool(a < b);
+    case JADE_OP_GREATER: return jade_bool(a > b);
+    case JADE_OP_LESS_EQUAL: return jade_bool(a <= b);
+    case JADE_OP_GREATER_EQUAL: return jade_bool(a >= b);
+    default: break;
+    }
+    return jade_none();
+}
+
+/* A single-byte string equal to a char, as the interpreter compares them. */
+static int jade_char_is_str(uint32_t c, const jade_str *s) {
+    return s->len == 1 && (unsigned char)s->data[0] == c;
+}
+
+jade_value jade_binary(int op, jade_value a, jade_value b) {
+    if (a.tag == JADE_INT && b.tag == JADE_INT) {
+        if (op != JADE_OP_NOT_EQUAL) return jade_int_binary(op, a.as.i, b.as.i);
+    } else if ((a.tag == JADE_INT || a.tag == JADE_FLOAT) &&
+               (b.tag == JADE_INT || b.tag == JADE_FLOAT)) {
+        int a_int = a.tag == JADE_INT, b_int = b.tag == JADE_INT;
+        jade_value r = jade_float_binary(op, a_int ? (double)a.as.i : a.as.f,
+                                         b_int ? (double)b.as.i : b.as.f, a_int, b_int);
+        if (r.tag != JADE_NONE) return r;
+    } else if (op == JADE_OP_EQUAL) {
+        if (a.tag == JADE_STR && b.tag == JADE_STR) return jade_bool(jade_values_equal(a, b));
+        if (a.tag == JADE_CHAR && b.tag == JADE_STR) return jade_bool(jade_char_is_str(a.as.c, b.as.s));
+        if (a.tag == JADE_STR && b.tag == JADE_CHAR) return jade_bool(jade_char_is_str(b.as.c, a.as.s));
+        if (a.tag == JADE_BOOL && b.tag == JADE_BOOL) return jade_bool(a.as.b == b.as.b);
+    }
+    if ((op == JADE_OP_EQUAL || op == JADE_OP_NOT_EQUAL) && jade_is_collection(a))
+        return jade_bool(jade_values_equal(a, b) == (op == JADE_OP_EQUAL));
+    if (op == JADE_OP_ADD && (a.tag == JADE_STR || b.tag == JADE_STR)) return jade_interpolate(a, b);
+    jade_unsupported_binary(op, a, b);
+}
+
+jade_value jade_unary(int op, jade_value v) {
+    if (op == JADE_UNARY_MINUS && v.tag == JADE_INT) return jade_int((int64_t)(0 - (uint64_t)v.as.i));
+    if (op == JADE_UNARY_MINUS && v.tag == JADE_FLOAT) return jade_float(-v.as.f);
+    if (op == JADE_UNARY_NOT && v.tag == JADE_BOOL) return jade_bool(!v.as.b);
+    if (op == JADE_UNARY_BITWISE_NOT && v.tag == JADE_INT) return jade_int(~v.as.i);
+    jade_panicf("Unsupported unary operation: %s %s", jade_unary_names[op], jade_text(v));
+}
+
+/* ---- variables and calls ---- */
+
+/* Read a variable that may not have been given a value yet. */
+jade_value jade_read(jade_value v, const char *name) {
+    if (v.tag == JADE_UNDEF) jade_panicf("Variable '%s' is not defined", name);
+    return jade_retain(v);
+}
+
+/* Assignment to `name`, which must already hold a value. */
+void jade_assign(jade_value *var, jade_value v, const char *name) {
+    if (var->tag == JADE_UNDEF) jade_panicf("Variable '%s' not found in any scope", name);
+    jade_release(*var);
+    *var = v;
+}
+
+_Noreturn void jade_undefined(const char *name) { jade_panicf("Variable '%s' is not defined", name); }
+
+void jade_enter(void) {
+    if (jade_depth >= JADE_MAX_DEPTH)
+        jade_panicf("Stack overflow: too many recursive calls (depth: %" PRId64 ")", jade_depth);
+    jade_depth++;
+}
+
+void jade_leave(void) { jade_depth--; }
+
+/* ---- indexing and iteration ---- */
+
+static const char *jade_index_type(jade_value v) {
+    switch (v.tag) {
+    case JADE_INT: return "integer";
+    case JADE_FLOAT: return "float";
+    case JADE_BOOL: return "boolean";
+    case JADE_CHAR: return "char";
+    case JADE_NONE: return "none";
+    default: return "unknown";
+    }
+}
+
+static jade_value jade_dict_lookup(const jade_dict *d, const char *key, int64_t len) {
+    int64_t i = jade_dict_find(d, key, len);
+    if (i < 0) jade_panicf("Key '%s' not found in dictionary", key);
+    return jade_retain(d->values[i]);
+}
+
+jade_value jade_index(jade_value obj, jade_value index) {
+    if (obj.tag == JADE_LIST && index.tag == JADE_INT) {
+        int64_t len = obj.as.l->len, i = index.as.i;
+        if (i < 0) {
+            if ((uint64_t)0 - (uint64_t)i > (uint64_t)len)
+                jade_panicf("Index %" PRId64 " out of bounds (length %" PRId64 ")", i, len);
+            i += len;
+        }
+        if (i >= len)
+            jade_panicf("Index %" PRId64 " is out of bounds for list of length %" PRId64,
+                        index.as.i, len);
+        return jade_retain(obj.as.l->items[i]);
+    }
+    if (obj.tag == JADE_STR && index.tag == JADE_INT) {
+        int64_t len = jade_utf8_count(obj.as.s), i = index.as.i;
+        if (i < 0 && (uint64_t)0 - (uint64_t)i <= (uint64_t)len) i += len;
+        if (i < 0 || i >= len)
+            jade_panicf("String index %" PRId64 " out of bounds (length %" PRId64 ")", index.as.i,
+                        len);
+        const unsigned char *p = (const unsigned char *)obj.as.s->data;
+        int width;
+        for (;; p += width) {
+            uint32_t c = jade_utf8_decode(p, &width);
+            if (i-- == 0) return jade_char(c);
+        }
+    }
+    if (obj.tag == JADE_DICT && index.tag == JADE_STR)
+        return jade_dict_lookup(obj.as.d, index.as.s->data, index.as.s->len);
+    if (obj.tag == JADE_DICT && index.tag == JADE_INT) {
+        char key[24];
+        snprintf(key, sizeof key, "%" PRId64, index.as.i);
+        return jade_dict_lookup(obj.as.d, key, (int64_t)strlen(key));
+    }
+    jade_panicf("Cannot index %s with %s", jade_index_type(obj),
+                index.tag == JADE_INT   ? "integer"
+                : index.tag == JADE_STR ? "string"
+                                        : "invalid index type");
+}
+
+/* The values a `for` loop visits: list items, string characters or dict keys. */
+jade_value jade_iterable(jade_value v) {
+    if (v.tag == JADE_LIST) return jade_retain(v);
+    if (v.tag == JADE_STR) {
+        jade_list *l = jade_list_alloc(v.as.s->len);
+        const unsigned char *p = (const unsigned char *)v.as.s->data;
+        int width;
+        for (int64_t i = 0; i < v.as.s->len; i += width) jade_list_add(l, jade_char(jade_utf8_decode(p + i, &width)));
+        return jade_list_value(l);
+    }
+    if (v.tag == JADE_DICT) {
+        jade_list *l = jade_list_alloc(v.as.d->len);
+        for (int64_t i = 0; i < v.as.d->len; i++) {
+            v.as.d->keys[i]->refs++;
+            jade_value key = {JADE_STR, {.s = v.as.d->keys[i]}};
+            jade_list_add(l, key);
+        }
+        return jade_list_value(l);
+    }
+    jade_panic("Can only iterate over lists, strings, dictionaries, tuples, and vectors");
+}
+
+int64_t jade_iter_len(jade_value list) { return list.as.l->len; }
+
+jade_value jade_iter_get(jade_value list, int64_t i) { return jade_retain(list.as.l->items[i]); }
+
+/* Integer bounds of a range used as a `for` iterable. */
+void jade_range_bounds(jade_value start, jade_value end) {
+    if (start.tag != JADE_INT || end.tag != JADE_INT) jade_panic("Range bounds must be integers");
+}
+
+/* `start..end` (or `..=`) by `step` as a list. */
+jade_value jade_range(jade_value start, jade_value end, jade_value step, int inclusive) {
+    jade_range_bounds(start, end);
+    int64_t s = start.as.i, e = end.as.i, by = 1;
+    if (step.tag != JADE_UNDEF) {
+        if (step.tag != JADE_INT) jade_panic("Range step must be an integer");
+        by = step.as.i;
+    }
+    if (by == 0) jade_panic("Range step cannot be zero");
+    jade_list *l = jade_list_alloc(0);
+    for (int64_t i = s; by > 0 ? (i < e || (inclusive && i == e)) : (i > e || (inclusive && i == e)); i += by)
+        jade_list_add(l, jade_int(i));
+    return jade_list_value(l);
+}
+
+/* ---- builtins and methods ---- */
+
+jade_value jade_len(jade_value v) {
+    if (v.tag == JADE_STR) return jade_int(v.as.s->len);
+    if (v.tag == JADE_LIST) return jade_int(v.as.l->len);
+    jade_panic("len() can only be called on strings, lists, and tuples");
+}
+
+static jade_value jade_pushed(const jade_list *list, jade_value item) {
+    jade_list *l = jade_list_alloc(list->len + 1);
+    for (int64_t i = 0; i < list->len; i++) jade_list_add(l, jade_retain(list->items[i]));
+    jade_list_add(l, jade_retain(item));
+    return jade_list_value(l);
+}
+
+jade_value jade_push(jade_value list, jade_value item) {
+    if (list.tag != JADE_LIST) jade_panic("push() can only be called on lists");
+    return jade_pushed(list.as.l, item);
+}
+
+jade_value jade_append(jade_value a, jade_value b) {
+    if (a.tag != JADE_LIST || b.tag != JADE_LIST) jade_panic("append() can only be called on two lists");
+    jade_list *l = jade_list_alloc(a.as.l->len + b.as.l->len);
+    for (int64_t i = 0; i < a.as.l->len; i++) jade_list_add(l, jade_retain(a.as.l->items[i]));
+    for (int64_t i = 0; i < b.as.l->len; i++) jade_list_add(l, jade_retain(b.as.l->items[i]));
+    return jade_list_value(l);
+}
+
+static jade_value jade_dict_part(jade_value v, int values) {
+    jade_list *l = jade_list_alloc(v.as.d->len);
+    for (int64_t i = 0; i < v.as.d->len; i++) {
+        if (values) {
+            jade_list_add(l, jade_retain(v.as.d->values[i]));
+        } else {
+            v.as.d->keys[i]->refs++;
+            jade_value key = {JADE_STR, {.s = v.as.d->keys[i]}};
+            jade_list_add(l, key);
+        }
+    }
+    return jade_list_value(l);
+}
+
+jade_value jade_keys(jade_value v) {
+    if (v.tag != JADE_DICT) jade_panic("keys() can only be called on dictionaries");
+    return jade_dict_part(v, 0);
+}
+
+jade_value jade_values(jade_value v) {
+    if (v.tag != JADE_DICT) jade_panic("values() can only be called on dictionaries");
+    return jade_dict_part(v, 1);
+}
+
+static const char *jade_type_name(jade_value v) {
+    switch (v.tag) {
+    case JADE_INT: return "int";
+    case JADE_FLOAT: return "float";
+    case JADE_BOOL: return "bool";
+    case JADE_STR: return "str";
+    case JADE_CHAR: return "char";
+    case JADE_LIST: return "list";
+    case JADE_DICT: return "dict";
+    default: return "none";
+    }
+}
+
+_Noreturn static void jade_no_method(jade_value v, const char *name) {
+    jade_panicf("Method '.%s' on a %s is not supported in compiled programs", name, jade_type_name(v));
+}
+
+/* `.len()`, `.length()` and `.size()`. */
+jade_value jade_method_len(jade_value v, const char *name) {
+    if (v.tag == JADE_STR) return jade_int(v.as.s->len);
+    if (v.tag == JADE_LIST) return jade_int(v.as.l->len);
+    if (v.tag == JADE_DICT) return jade_int(v.as.d->len);
+    jade_no_method(v, name);
+}
+
+/* `.push(x)` and `.append(x)`: a copy of the list with `x` added. */
+jade_value jade_method_push(jade_value v, jade_value item, const char *name) {
+    if (v.tag != JADE_LIST) jade_no_method(v, name);
+    return jade_pushed(v.as.l, item);
+}
+
+/* `dict.get(key)` and `dict.get(key, default)`; `fallback` is UNDEF without a default. */
+jade_value jade_method_get(jade_value v, jade_value key, jade_value fallback) {
+    if (v.tag != JADE_DICT) jade_no_method(v, "get");
+    char *text = jade_text(key);
+    int64_t i = jade_dict_find(v.as.d, text, (int64_t)strlen(text));
+    free(text);
+    if (i >= 0) return jade_retain(v.as.d->values[i]);
+    return fallback.tag == JADE_UNDEF ? jade_none() : jade_retain(fallback);
+}
+
+/* `.has(key)` and `.contains_key(key)`. */
+jade_value jade_method_has(jade_value v, jade_value key, const char *name) {
+    if (v.tag != JADE_DICT) jade_no_method(v, name);
+    char *text = jade_text(key);
+    int64_t i = jade_dict_find(v.as.d, text, (int64_t)strlen(text));
+    free(text);
+    return jade_bool(i >= 0);
+}
+
+jade_value jade_method_keys(jade_value v) {
+    if (v.tag != JADE_DICT) jade_no_method(v, "keys");
+    return jade_dict_part(v, 0);
+}
+
+jade_value jade_method_values(jade_value v) {
+    if (v.tag != JADE_DICT) jade_no_method(v, "values");
+    return jade_dict_part(v, 1);
+}
+
+/* ---- out() ---- */
+
+void jade_print(jade_value v) {
+    char *text = jade_text(v);
+    fputs(text, stdout);
+    free(text);
+}
+
+void jade_print_text(const char *s) { fputs(s, stdout); }
+
+/* `out(rows)` for a list of lists: aligned columns under a rule. */
+static void jade_print_table(const jade_list *rows) {
+    for (int64_t r = 0; r < rows->len; r++)
+        if (rows->items[r].tag != JADE_LIST) jade_panic("Table rows must be lists");
+    int64_t ncols = rows->items[0].as.l->len;
+    int64_t *widths = calloc((size_t)ncols + 1, sizeof(int64_t));
+    if (!widths) jade_panic("Out of memory");
+    for (int64_t r = 0; r < rows->len; r++) {
+        const jade_list *row = rows->items[r].as.l;
+        for (int64_t c = 0; c < row->len && c < ncols; c++) {
+            char *cell = jade_text(row->items[c]);
+            int64_t n = (int64_t)strlen(cell);
+            if (n > widths[c]) widths[c] = n;
+            free(cell);
+        }
+    }
+    for (int64_t r = 0; r < rows->len; r++) {
+        const jade_list *row = rows->items[r].as.l;
+        for (int64_t c = 0; c < row->len && c < ncols; c++) {
+            char *cell = jade_text(row->items[c]);
+            int64_t chars = 0;
+            for (const char *p = cell; *p; p++)
+                if (((unsigned char)*p & 0xC0) != 0x80) chars++;
+            fputs(cell, stdout);
+            for (int64_t pad = chars; pad < widths[c]; pad++) fputc(' ', stdout);
+            if (c < row->len - 1) fputs("  ", stdout);
+            free(cell);
+        }
+        fputc('\n', stdout);
+        if (r == 0) {
+            for (int64_t c = 0; c < ncols; c++) {
+                for (int64_t i = 0; i < widths[c]; i++) fputc('-', stdout);
+                fputs("  ", stdout);
+            }
+            fputc('\n', stdout);
+        }
+    }
+    free(widths);
+}
+
+/* `out(x)`. */
+void jade_out_one(jade_value v) {
+    if (v.tag == JADE_LIST && v.as.l->len > 0 && v.as.l->items[0].tag == JADE_LIST) {
+        jade_print_table(v.as.l);
+        return;
+    }
+    jade_print(v);
+    fputc('\n', stdout);
+}
+
+/* `out(a, b)`; a dict as `b` holds formatting options, which compiled programs do not support. */
+void jade_out_two(jade_value a, jade_value b) {
+    if (b.tag == JADE_DICT) jade_panic("out() with an options dict is not supported in compiled programs");
+    jade_print(a);
+    fputc(' ', stdout);
+    jade_print(b);
+    fputc('\n', stdout);
+}
+
+/* ---- program ---- */
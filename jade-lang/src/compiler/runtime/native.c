@@ -0,0 +1,218 @@
+/* Support code linked into binaries built from LLVM IR by `jade build`.
+ *
+ * Values are statically typed by the compiler: ints are int64_t, floats double, bools 0/1,
+ * strings NUL-terminated char pointers and lists `jade_list` pointers whose items are 64-bit
+ * words (floats bit-cast, strings and lists as pointers). Nothing is freed; compiled programs
+ * are expected to be short-lived. Formatting matches the interpreter's `out()`. */
+
+#include <inttypes.h>
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+typedef struct jade_list {
+    int64_t len;
+    int64_t cap;
+    int64_t *items;
+} jade_list;
+
+_Noreturn void jade_panic(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "Runtime error: %s\n", message);
+    exit(1);
+}
+
+_Noreturn void jade_overflow(const char *op, int64_t a, int64_t b) {
+    char message[96];
+    snprintf(message, sizeof message, "Integer overflow: %" PRId64 " %s %" PRId64, a, op, b);
+    jade_panic(message);
+}
+
+static void *jade_alloc(size_t size) {
+    void *p = malloc(size ? size : 1);
+    if (!p) jade_panic("Out of memory");
+    return p;
+}
+
+static char *jade_strdup(const char *s) {
+    size_t n = strlen(s) + 1;
+    return memcpy(jade_alloc(n), s, n);
+}
+
+/* Growable byte buffer used while formatting. */
+typedef struct {
+    char *data;
+    size_t len, cap;
+} jade_buf;
+
+static void jade_buf_push(jade_buf *b, const char *s) {
+    size_t n = strlen(s);
+    if (b->len + n + 1 > b->cap) {
+        b->cap = (b->len + n + 1) * 2;
+        b->data = realloc(b->data, b->cap);
+        if (!b->data) jade_panic("Out of memory");
+    }
+    memcpy(b->data + b->len, s, n + 1);
+    b->len += n;
+}
+
+const char *jade_concat(const char *a, const char *b) {
+    size_t na = strlen(a), nb = strlen(b);
+    char *s = jade_alloc(na + nb + 1);
+    memcpy(s, a, na);
+    memcpy(s + na, b, nb + 1);
+    return s;
+}
+
+int32_t jade_str_eq(const char *a, const char *b) { return strcmp(a, b) == 0; }
+
+int64_t jade_str_len(const char *s) { return (int64_t)strlen(s); }
+
+const char *jade_int_str(int64_t i) {
+    char buf[24];
+    snprintf(buf, sizeof buf, "%" PRId64, i);
+    return jade_strdup(buf);
+}
+
+const char *jade_bool_str(int64_t b) { return b ? "true" : "false"; }
+
+/* Shortest digits that read back as `x`, written out without an exponent (like Rust's `{}`). */
+const char *jade_float_str(double x) {
+    if (isnan(x)) return "NaN";
+    if (isinf(x)) return x < 0 ? "-inf" : "inf";
+    char buf[40];
+    for (int precision = 1; precision <= 17; precision++) {
+        snprintf(buf, sizeof buf, "%.*e", precision - 1, x);
+        if (strtod(buf, NULL) == x) break;
+    }
+    int negative = buf[0] == '-';
+    char digits[20];
+    int n = 0;
+    const char *p = buf + negative;
+    for (; *p != 'e'; p++)
+        if (*p != '.') digits[n++] = *p;
+    int exponent = atoi(p + 1);
+    while (n > 1 && digits[n - 1] == '0') n--;
+
+    char *out = jade_alloc((size_t)n + (size_t)abs(exponent) + 4);
+    char *o = out;
+    if (negative) *o++ = '-';
+    if (exponent >= n - 1) {
+        memcpy(o, digits, n);
+        o += n;
+        for (int i = 0; i < exponent - (n - 1); i++) *o++ = '0';
+    } else if (exponent >= 0) {
+        memcpy(o, digits, exponent + 1);
+        o += exponent + 1;
+        *o++ = '.';
+        memcpy(o, digits + exponent + 1, n - exponent - 1);
+        o += n - exponent - 1;
+    } else {
+        *o++ = '0';
+        *o++ = '.';
+        for (int i = 0; i < -exponent - 1; i++) *o++ = '0';
+        memcpy(o, digits, n);
+        o += n;
+    }
+    *o = '\0';
+    return out;
+}
+
+jade_list *jade_list_new(int64_t cap) {
+    jade_list *list = jade_alloc(sizeof *list);
+    list->len = 0;
+    list->cap = cap > 4 ? cap : 4;
+    list->items = jade_alloc((size_t)list->cap * sizeof(int64_t));
+    return list;
+}
+
+/* Append in place; only used on lists nobody else can see yet. */
+void jade_list_push(jade_list *list, int64_t item) {
+    if (list->len == list->cap) {
+        list->cap *= 2;
+        list->items = realloc(list->items, (size_t)list->cap * sizeof(int64_t));
+        if (!list->items) jade_panic("Out of memory");
+    }
+    list->items[list->len++] = item;
+}
+
+/* `push(xs, x)`: a new list, since Jade lists are values. */
+jade_list *jade_list_pushed(const jade_list *list, int64_t item) {
+    jade_list *copy = jade_list_new(list->len + 1);
+    memcpy(copy->items, list->items, (size_t)list->len * sizeof(int64_t));
+    copy->len = list->len;
+    jade_list_push(copy, item);
+    return copy;
+}
+
+int64_t jade_list_len(const jade_list *list) { return list->len; }
+
+int64_t jade_list_get(const jade_list *list, int64_t index) {
+    char message[96];
+    int64_t i = index;
+    if (index < 0) {
+        if ((uint64_t)0 - (uint64_t)index > (uint64_t)list->len) {
+            snprintf(message, sizeof message, "Index %" PRId64 " out of bounds (length %" PRId64 ")",
+                     index, list->len);
+            jade_panic(message);
+        }
+        i = list->len + index;
+    }
+    if (i >= list->len) {
+        snprintf(message, sizeof message,
+                 "Index %" PRId64 " is out of bounds for list of length %" PRId64, index, list->len);
+        jade_panic(message);
+    }
+    return list->items[i];
+}
+
+const char *jade_list_str(const jade_list *list, const char *type);
+
+/* Format one word of type `type` ("i", "f", "b", "s" or "l" followed by the item type). */
+static const char *jade_word_str(int64_t word, const char *type) {
+    double f;
+    switch (type[0]) {
+    case 'i':
+        return jade_int_str(word);
+    case 'f':
+        memcpy(&f, &word, sizeof f);
+        return jade_float_str(f);
+    case 'b':
+        return jade_bool_str(word);
+    case 's':
+        return (const char *)(intptr_t)word;
+    case 'l':
+        return jade_list_str((const jade_list *)(intptr_t)word, type + 1);
+    default:
+        jade_panic("list of unknown item type");
+    }
+}
+
+const char *jade_list_str(const jade_list *list, const char *type) {
+    jade_buf b = {0};
+    jade_buf_push(&b, "[");
+    for (int64_t i = 0; i < list->len; i++) {
+        if (i > 0) jade_buf_push(&b, ", ");
+        jade_buf_push(&b, jade_word_str(list->items[i], type));
+    }
+    jade_buf_push(&b, "]");
+    return b.data;
+}
+
+int64_t jade_ipow(int64_t base, int64_t exponent) {
+    int64_t result = 1;
+    for (int64_t i = 0; i < exponent; i++) {
+        if (__builtin_mul_overflow(result, base, &result)) {
+            char message[96];
+            snprintf(message, sizeof message, "Integer overflow: %" PRId64 " ** %" PRId64, base,
+                     exponent);
+            jade_panic(message);
+        }
+    }
+    return result;
+}
+
+void jade_print(const char *s) { fputs(s, stdout); }
+
+void jade_println(void) { fputc('\n', stdout); }
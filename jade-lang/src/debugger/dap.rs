@@ -0,0 +1,440 @@
+//! `jade debug --dap`: a Debug Adapter Protocol server over stdio, so editors such as VS Code
+//! can launch a program under the debugger, set breakpoints, step and inspect variables.
+//!
+//! Messages use the same `Content-Length` framing as the language server. A reader thread
+//! queues incoming requests so that `pause` and `setBreakpoints` are answered while the program
+//! runs. Programs have a single thread (id 1). Their `out()` output is sent as `output` events,
+//! since stdout carries the protocol; `input()` is not available.
+
+use std::cell::RefCell;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+use serde_json::{json, Value as Json};
+
+use super::{breakpoint_lines, effective_line, interpreter_for, parse_program, PREVIEW_LEN};
+use crate::error::JError;
+use crate::interpreter::{
+    Breakpoints, Debugger, Frontend, Interpreter, Interrupt, Paused, Resume, DEFAULT_MAX_CALL_DEPTH,
+};
+use crate::lsp::{read_message, write_message};
+use crate::repl::value_preview;
+
+const THREAD_ID: u64 = 1;
+
+/// Variable references: globals, statics, then the locals of frame `n` (0 is the innermost)
+/// as `LOCALS + n`.
+const GLOBALS: u64 = 1;
+const STATICS: u64 = 2;
+const LOCALS: u64 = 3;
+
+/// Serve DAP on stdin/stdout.
+pub fn run_stdio() -> io::Result<i32> {
+    run(io::BufReader::new(io::stdin()), io::stdout())
+}
+
+/// Serve DAP over `reader` and `writer`: configure and launch one program, debug it, then
+/// answer until the client disconnects or input ends.
+pub fn run(reader: impl BufRead + Send + 'static, writer: impl Write + 'static) -> io::Result<i32> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut reader = reader;
+        while let Ok(Some(body)) = read_message(&mut reader) {
+            // Requests that are not JSON cannot be answered: they have no sequence number
+            let Ok(request) = serde_json::from_str::<Json>(&body) else {
+                continue;
+            };
+            if sender.send(request).is_err() {
+                break;
+            }
+        }
+    });
+    let client = Rc::new(RefCell::new(Client {
+        writer: Box::new(writer),
+        seq: 0,
+        receiver,
+    }));
+
+    // Configuration: wait for the program to launch and for the client's breakpoints
+    let mut breakpoints = Breakpoints::new();
+    let mut launch = None;
+    let mut configured = false;
+    while launch.is_none() || !configured {
+        let mut client = client.borrow_mut();
+        let Some(request) = client.receive() else {
+            return Ok(0);
+        };
+        match command(&request) {
+            "initialize" => {
+                client.respond(&request, capabilities())?;
+                client.event("initialized", json!({}))?;
+            }
+            "launch" => {
+                let arguments = &request["arguments"];
+                match arguments["program"].as_str() {
+                    Some(program) => {
+                        let no_debug = arguments["noDebug"].as_bool().unwrap_or(false);
+                        let stop_on_entry = arguments["stopOnEntry"].as_bool().unwrap_or(false);
+                        launch = Some((PathBuf::from(program), no_debug, stop_on_entry));
+                        client.respond(&request, json!({}))?;
+                    }
+                    None => client.fail(&request, "launch needs the 'program' to debug")?,
+                }
+            }
+            "configurationDone" => {
+                configured = true;
+                client.respond(&request, json!({}))?;
+            }
+            "disconnect" | "terminate" => {
+                client.respond(&request, json!({}))?;
+                return Ok(0);
+            }
+            _ => client.answer(&request, &mut breakpoints)?,
+        }
+    }
+
+    let (program, no_debug, stop_on_entry) = launch.expect("launched");
+    if no_debug {
+        breakpoints.clear();
+    }
+    debug_program(&client, &program, breakpoints, stop_on_entry && !no_debug)?;
+
+    let mut client = client.borrow_mut();
+    let mut breakpoints = Breakpoints::new();
+    while let Some(request) = client.receive() {
+        if matches!(command(&request), "disconnect" | "terminate") {
+            client.respond(&request, json!({}))?;
+            break;
+        }
+        client.answer(&request, &mut breakpoints)?;
+    }
+    Ok(0)
+}
+
+/// Run the program at `path` under the debugger, then report its exit.
+fn debug_program(
+    client: &Rc<RefCell<Client>>,
+    path: &Path,
+    breakpoints: Breakpoints,
+    stop_on_entry: bool,
+) -> io::Result<()> {
+    let mut interpreter = interpreter_for(path, DEFAULT_MAX_CALL_DEPTH);
+    interpreter.set_output_capture(true);
+    let frontend = Adapter {
+        client: client.clone(),
+    };
+    interpreter.set_debugger(Debugger::new(frontend, breakpoints, stop_on_entry));
+
+    let source = std::fs::read_to_string(path);
+    let result = match &source {
+        Ok(source) => parse_program(path, source).and_then(|program| interpreter.run_ast(&program)),
+        Err(e) => Err(JError::io_error(format!("Error reading file {}: {}", path.display(), e)).into()),
+    };
+    let quit = interpreter
+        .take_debugger()
+        .is_some_and(|debugger| debugger.quit_requested());
+
+    let mut client = client.borrow_mut();
+    client.forward_output(&mut interpreter)?;
+    let exit_code = match result {
+        Err(e) if !quit => {
+            let source = source.as_deref().unwrap_or_default();
+            let report = interpreter.describe_error(&e, source);
+            client.output("stderr", &format!("{}\n", report))?;
+            1
+        }
+        _ => 0,
+    };
+    client.event("exited", json!({ "exitCode": exit_code }))?;
+    client.event("terminated", json!({}))
+}
+
+fn capabilities() -> Json {
+    json!({
+        "supportsConfigurationDoneRequest": true,
+        "supportsEvaluateForHovers": true,
+        "supportsTerminateRequest": true,
+    })
+}
+
+fn command(request: &Json) -> &str {
+    request["command"].as_str().unwrap_or_default()
+}
+
+/// The connection to the editor: requests queued by the reader thread, and the writer for
+/// responses and events.
+struct Client {
+    writer: Box<dyn Write>,
+    seq: u64,
+    receiver: Receiver<Json>,
+}
+
+impl Client {
+    /// The next request; `None` once input has ended.
+    fn receive(&self) -> Option<Json> {
+        self.receiver.recv().ok()
+    }
+
+    fn send(&mut self, mut message: Json) -> io::Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        write_message(&mut self.writer, &message)
+    }
+
+    fn respond(&mut self, request: &Json, body: Json) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": command(request),
+            "success": true,
+            "body": body,
+        }))
+    }
+
+    fn fail(&mut self, request: &Json, message: &str) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": command(request),
+            "success": false,
+            "message": message,
+        }))
+    }
+
+    fn event(&mut self, event: &str, body: Json) -> io::Result<()> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+
+    fn output(&mut self, category: &str, text: &str) -> io::Result<()> {
+        self.event("output", json!({ "category": category, "output": text }))
+    }
+
+    /// Send what the program printed since the last call.
+    fn forward_output(&mut self, interpreter: &mut Interpreter) -> io::Result<()> {
+        let Some(text) = interpreter.take_captured_output() else {
+            return Ok(());
+        };
+        interpreter.set_output_capture(true);
+        if text.is_empty() {
+            return Ok(());
+        }
+        self.output("stdout", &text)
+    }
+
+    /// Requests answered the same way whether or not the program is stopped.
+    fn answer(&mut self, request: &Json, breakpoints: &mut Breakpoints) -> io::Result<()> {
+        match command(request) {
+            "setBreakpoints" => self.set_breakpoints(request, breakpoints),
+            "setExceptionBreakpoints" => self.respond(request, json!({ "breakpoints": [] })),
+            "threads" => self.respond(
+                request,
+                json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] }),
+            ),
+            "stackTrace" | "scopes" | "variables" | "evaluate" => {
+                self.fail(request, "The program is not stopped")
+            }
+            other => self.fail(request, &format!("Unsupported request '{}'", other)),
+        }
+    }
+
+    /// Replace the breakpoints of one file, moving each to the next line with a statement.
+    fn set_breakpoints(&mut self, request: &Json, breakpoints: &mut Breakpoints) -> io::Result<()> {
+        let arguments = &request["arguments"];
+        let Some(path) = arguments["source"]["path"].as_str() else {
+            return self.fail(request, "setBreakpoints needs a source path");
+        };
+        let path = Path::new(path);
+        let available = breakpoint_lines(path);
+        let requested = arguments["breakpoints"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let mut lines = Vec::new();
+        let mut results = Vec::new();
+        for line in requested.iter().filter_map(|bp| bp["line"].as_u64()) {
+            match effective_line(&available, line as usize) {
+                Some(line) => {
+                    lines.push(line);
+                    results.push(json!({ "verified": true, "line": line }));
+                }
+                None => results.push(json!({
+                    "verified": false,
+                    "line": line,
+                    "message": "No statement at or after this line",
+                })),
+            }
+        }
+        breakpoints.set(path, lines);
+        self.respond(request, json!({ "breakpoints": results }))
+    }
+}
+
+/// The DAP front end of the debugger.
+struct Adapter {
+    client: Rc<RefCell<Client>>,
+}
+
+impl Frontend for Adapter {
+    fn stopped(&mut self, paused: &mut Paused<'_>) -> Resume {
+        let mut client = self.client.borrow_mut();
+        while_stopped(&mut client, paused).unwrap_or(Resume::Quit)
+    }
+
+    fn running(
+        &mut self,
+        interpreter: &mut Interpreter,
+        breakpoints: &mut Breakpoints,
+    ) -> Option<Interrupt> {
+        let mut client = self.client.borrow_mut();
+        if client.forward_output(interpreter).is_err() {
+            return Some(Interrupt::Quit);
+        }
+        loop {
+            let request = match client.receiver.try_recv() {
+                Ok(request) => request,
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => return Some(Interrupt::Quit),
+            };
+            let interrupt = match command(&request) {
+                "pause" => Some(Interrupt::Pause),
+                "disconnect" | "terminate" => Some(Interrupt::Quit),
+                _ => None,
+            };
+            let answered = match interrupt {
+                Some(_) => client.respond(&request, json!({})),
+                None => client.answer(&request, breakpoints),
+            };
+            if answered.is_err() {
+                return Some(Interrupt::Quit);
+            }
+            if interrupt.is_some() {
+                return interrupt;
+            }
+        }
+    }
+}
+
+/// Report the stop, then answer requests until one resumes the program.
+fn while_stopped(client: &mut Client, paused: &mut Paused<'_>) -> io::Result<Resume> {
+    client.forward_output(paused.interpreter())?;
+    client.event(
+        "stopped",
+        json!({
+            "reason": paused.reason.name(),
+            "threadId": THREAD_ID,
+            "allThreadsStopped": true,
+        }),
+    )?;
+    while let Some(request) = client.receive() {
+        let arguments = &request["arguments"];
+        let resume = match command(&request) {
+            "continue" => Resume::Continue,
+            "next" => Resume::StepOver,
+            "stepIn" => Resume::StepIn,
+            "stepOut" => Resume::StepOut,
+            "disconnect" | "terminate" => Resume::Quit,
+            "stackTrace" => {
+                let frames: Vec<Json> = paused
+                    .stack()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, frame)| {
+                        let mut json = json!({
+                            "id": i + 1,
+                            "name": frame.function,
+                            "line": frame.line,
+                            "column": frame.column,
+                        });
+                        if let Some(file) = &frame.file {
+                            json["source"] = source(Path::new(file));
+                        }
+                        json
+                    })
+                    .collect();
+                let total = frames.len();
+                let start = arguments["startFrame"].as_u64().unwrap_or(0) as usize;
+                let levels = match arguments["levels"].as_u64() {
+                    Some(levels) if levels > 0 => levels as usize,
+                    _ => total,
+                };
+                let frames: Vec<Json> = frames.into_iter().skip(start).take(levels).collect();
+                client.respond(
+                    &request,
+                    json!({ "stackFrames": frames, "totalFrames": total }),
+                )?;
+                continue;
+            }
+            "scopes" => {
+                let frame = arguments["frameId"].as_u64().unwrap_or(1).saturating_sub(1);
+                let scope = |name: &str, reference: u64| json!({ "name": name, "variablesReference": reference, "expensive": false });
+                let scopes = [
+                    scope("Locals", LOCALS + frame),
+                    scope("Globals", GLOBALS),
+                    scope("Statics", STATICS),
+                ];
+                client.respond(&request, json!({ "scopes": scopes }))?;
+                continue;
+            }
+            "variables" => {
+                let variables = match arguments["variablesReference"].as_u64().unwrap_or(0) {
+                    GLOBALS => paused.globals(),
+                    STATICS => paused.statics(),
+                    reference if reference >= LOCALS => {
+                        paused.locals((reference - LOCALS) as usize)
+                    }
+                    _ => Vec::new(),
+                };
+                let variables: Vec<Json> = variables
+                    .iter()
+                    .map(|(name, value)| {
+                        json!({
+                            "name": name,
+                            "value": value_preview(value, PREVIEW_LEN),
+                            "variablesReference": 0,
+                        })
+                    })
+                    .collect();
+                client.respond(&request, json!({ "variables": variables }))?;
+                continue;
+            }
+            "evaluate" => {
+                let expression = arguments["expression"].as_str().unwrap_or_default();
+                match paused.evaluate(expression) {
+                    Ok(value) => client.respond(
+                        &request,
+                        json!({ "result": value_preview(&value, PREVIEW_LEN), "variablesReference": 0 }),
+                    )?,
+                    Err(e) => client.fail(&request, &e.message)?,
+                }
+                client.forward_output(paused.interpreter())?;
+                continue;
+            }
+            "pause" => {
+                client.respond(&request, json!({}))?;
+                continue;
+            }
+            _ => {
+                client.answer(&request, paused.breakpoints)?;
+                continue;
+            }
+        };
+        let body = match resume {
+            Resume::Continue => json!({ "allThreadsContinued": true }),
+            _ => json!({}),
+        };
+        client.respond(&request, body)?;
+        return Ok(resume);
+    }
+    Ok(Resume::Quit)
+}
+
+fn source(file: &Path) -> Json {
+    let path = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    json!({ "name": name, "path": path.to_string_lossy() })
+}
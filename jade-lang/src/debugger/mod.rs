@@ -0,0 +1,290 @@
+//! `jade debug`: run a program under the step debugger ([`crate::interpreter::Debugger`]),
+//! driven from the terminal or, with `--dap`, by an editor over the Debug Adapter Protocol
+//! ([`dap`]).
+//!
+//! The program runs on the tree-walking interpreter (never from the bytecode cache) with the
+//! same module search paths as `jade run`.
+
+pub mod dap;
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::JResult;
+use crate::interpreter::{
+    statement_lines, Breakpoints, Debugger, Frontend, Interpreter, Paused, Resume, Value,
+};
+use crate::lexer::Lexer;
+use crate::parser::{AstNode, Parser};
+use crate::repl::value_preview;
+
+/// Longest value shown in variable listings.
+const PREVIEW_LEN: usize = 72;
+
+const HELP: &str = "\
+Commands (an empty line repeats the last one):
+  c, continue          run to the next breakpoint
+  n, next              run to the next line, stepping over calls
+  s, step              run to the next line, stepping into calls
+  o, out               run until the current function returns
+  b, break [file:]N    stop at line N (of the program unless a file is given)
+  d, delete [file:]N   remove a breakpoint; 'delete' alone removes all
+  breakpoints          list breakpoints
+  bt, where            show the call stack
+  l, list              show the source around the current line
+  locals               show local variables
+  globals              show global and top-level variables
+  statics              show static variables
+  p, print EXPR        evaluate an expression here
+  q, quit              end the program";
+
+/// Parse the program at `path`, naming the file in spans so breakpoints can match it.
+pub(crate) fn parse_program(path: &Path, source: &str) -> JResult<AstNode> {
+    let tokens = Lexer::new(source).tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.set_file(&path.to_string_lossy());
+    parser.parse()
+}
+
+/// An interpreter that resolves imports like `jade run` does for the program at `path`.
+pub(crate) fn interpreter_for(path: &Path, max_depth: usize) -> Interpreter {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_max_call_depth(max_depth);
+    for search_path in crate::testing::module_search_paths(path) {
+        interpreter.add_module_search_path(search_path);
+    }
+    interpreter
+}
+
+/// Lines of the file at `path` where breakpoints take effect; empty if it cannot be read or
+/// parsed.
+pub(crate) fn breakpoint_lines(path: &Path) -> BTreeSet<usize> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|source| parse_program(path, &source).ok())
+        .map(|program| statement_lines(&program))
+        .unwrap_or_default()
+}
+
+/// Where a breakpoint asked for at `line` takes effect: the first line at or after it where a
+/// statement starts.
+pub(crate) fn effective_line(lines: &BTreeSet<usize>, line: usize) -> Option<usize> {
+    lines.range(line..).next().copied()
+}
+
+/// Debug the program at `path` from the terminal, stopping before its first statement.
+/// Returns the exit status: 1 if the program failed, 0 if it finished or was quit.
+pub fn run_console(path: &Path, max_depth: usize) -> i32 {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("❌ Error reading file: {}", e);
+            return 1;
+        }
+    };
+    let mut interpreter = interpreter_for(path, max_depth);
+    let program = match parse_program(path, &source) {
+        Ok(program) => program,
+        Err(e) => {
+            eprintln!("{}", interpreter.describe_error(&e, &source));
+            return 1;
+        }
+    };
+    println!("🐞 Debugging {} — type 'help' for commands", path.display());
+
+    let console = Console {
+        program: path.to_path_buf(),
+        sources: HashMap::new(),
+        last_command: String::new(),
+    };
+    interpreter.set_debugger(Debugger::new(console, Breakpoints::new(), true));
+    let result = interpreter.run_ast(&program);
+    let _ = io::stdout().flush();
+    let quit = interpreter
+        .take_debugger()
+        .is_some_and(|debugger| debugger.quit_requested());
+    match result {
+        _ if quit => 0,
+        Ok(()) => {
+            println!("✅ Program finished");
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", interpreter.describe_error(&e, &source));
+            1
+        }
+    }
+}
+
+/// The terminal front end: commands from stdin, reports on stdout.
+struct Console {
+    program: PathBuf,
+    /// Source lines of files shown so far.
+    sources: HashMap<PathBuf, Vec<String>>,
+    last_command: String,
+}
+
+impl Frontend for Console {
+    fn stopped(&mut self, paused: &mut Paused<'_>) -> Resume {
+        self.show_location(paused);
+        loop {
+            print!("(jade-debug) ");
+            let _ = io::stdout().flush();
+            let mut line = String::new();
+            match io::stdin().lock().read_line(&mut line) {
+                Ok(0) | Err(_) => return Resume::Quit,
+                Ok(_) => {}
+            }
+            let line = match line.trim() {
+                "" => self.last_command.clone(),
+                line => line.to_string(),
+            };
+            self.last_command = line.clone();
+            let (command, argument) = line.split_once(' ').unwrap_or((&line, ""));
+            let argument = argument.trim();
+            match command {
+                "c" | "continue" => return Resume::Continue,
+                "n" | "next" => return Resume::StepOver,
+                "s" | "step" => return Resume::StepIn,
+                "o" | "out" | "finish" => return Resume::StepOut,
+                "q" | "quit" => return Resume::Quit,
+                "b" | "break" => self.add_breakpoint(paused, argument),
+                "d" | "delete" => self.delete_breakpoint(paused, argument),
+                "breakpoints" => {
+                    let all = paused.breakpoints.list();
+                    if all.is_empty() {
+                        println!("No breakpoints");
+                    }
+                    for (file, line) in all {
+                        println!("  {}:{}", file.display(), line);
+                    }
+                }
+                "bt" | "where" | "backtrace" => {
+                    for (i, frame) in paused.stack().iter().enumerate() {
+                        println!(
+                            "{} #{} {} at {}:{}",
+                            if i == 0 { "→" } else { " " },
+                            i,
+                            frame.function,
+                            frame.file.as_deref().unwrap_or("<program>"),
+                            frame.line
+                        );
+                    }
+                }
+                "l" | "list" => self.list(paused),
+                "locals" => show_variables(&paused.locals(0)),
+                "globals" => show_variables(&paused.globals()),
+                "statics" => show_variables(&paused.statics()),
+                "p" | "print" if argument.is_empty() => println!("Usage: print EXPR"),
+                "p" | "print" => match paused.evaluate(argument) {
+                    Ok(value) => println!("{}", value),
+                    Err(e) => println!("❌ {}", e.message),
+                },
+                "h" | "help" => println!("{}", HELP),
+                _ => println!("Unknown command '{}'; type 'help' for commands", command),
+            }
+        }
+    }
+}
+
+impl Console {
+    fn show_location(&mut self, paused: &Paused<'_>) {
+        let frame = &paused.stack()[0];
+        println!(
+            "⏸  {}:{} in {} ({})",
+            frame.file.as_deref().unwrap_or("<program>"),
+            paused.line(),
+            frame.function,
+            paused.reason.name()
+        );
+        if let Some(text) = self.source_line(&paused.file, paused.line()) {
+            println!("{:>5} │ {}", paused.line(), text);
+        }
+    }
+
+    fn list(&mut self, paused: &Paused<'_>) {
+        let current = paused.line();
+        let first = current.saturating_sub(5).max(1);
+        for line in first..current + 6 {
+            let Some(text) = self.source_line(&paused.file, line) else {
+                break;
+            };
+            let marker = if line == current {
+                "→"
+            } else if paused.breakpoints.contains(&paused.file, line) {
+                "●"
+            } else {
+                " "
+            };
+            println!("{} {:>4} │ {}", marker, line, text);
+        }
+    }
+
+    fn source_line(&mut self, file: &Path, line: usize) -> Option<String> {
+        let lines = self.sources.entry(file.to_path_buf()).or_insert_with(|| {
+            std::fs::read_to_string(file)
+                .map(|source| source.lines().map(str::to_string).collect())
+                .unwrap_or_default()
+        });
+        lines.get(line.checked_sub(1)?).cloned()
+    }
+
+    /// `N` or `file:N`, with files relative to the program's directory if not found as given.
+    fn breakpoint_spec(&self, spec: &str) -> Option<(PathBuf, usize)> {
+        let (file, line) = match spec.rsplit_once(':') {
+            Some((file, line)) => {
+                let file = PathBuf::from(file);
+                let beside_program = self.program.parent().map(|dir| dir.join(&file));
+                match beside_program {
+                    Some(path) if !file.exists() && path.exists() => (path, line),
+                    _ => (file, line),
+                }
+            }
+            None => (self.program.clone(), spec),
+        };
+        Some((file, line.trim().parse().ok()?))
+    }
+
+    fn add_breakpoint(&mut self, paused: &mut Paused<'_>, spec: &str) {
+        let Some((file, line)) = self.breakpoint_spec(spec) else {
+            println!("Usage: break [file:]LINE");
+            return;
+        };
+        match effective_line(&breakpoint_lines(&file), line) {
+            Some(line) => {
+                paused.breakpoints.insert(&file, line);
+                println!("● Breakpoint at {}:{}", file.display(), line);
+            }
+            None => println!(
+                "❌ No statement at or after line {} of {}",
+                line,
+                file.display()
+            ),
+        }
+    }
+
+    fn delete_breakpoint(&mut self, paused: &mut Paused<'_>, spec: &str) {
+        if spec.is_empty() {
+            paused.breakpoints.clear();
+            println!("All breakpoints removed");
+            return;
+        }
+        match self.breakpoint_spec(spec) {
+            Some((file, line)) if paused.breakpoints.remove(&file, line) => {
+                println!("Removed breakpoint at {}:{}", file.display(), line)
+            }
+            Some((file, line)) => println!("No breakpoint at {}:{}", file.display(), line),
+            None => println!("Usage: delete [file:]LINE"),
+        }
+    }
+}
+
+fn show_variables(variables: &[(String, Value)]) {
+    if variables.is_empty() {
+        println!("(none)");
+    }
+    for (name, value) in variables {
+        println!("  {} = {}", name, value_preview(value, PREVIEW_LEN));
+    }
+}
@@ -0,0 +1,55 @@
+//! Static diagnostics for `jade check`: every lexical and syntax error in a file, without
+//! running it, as text or as JSON for editors and CI.
+
+use crate::error::JError;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+
+/// Every lexical and syntax error in `source`, in source order. The tokens that did lex are
+/// still parsed; syntax errors on a line that already has a lexical error are dropped, since
+/// they are usually caused by it.
+pub fn check_source(source: &str, file: Option<&str>) -> Vec<JError> {
+    let (tokens, mut errors) = Lexer::new(source).tokenize_recovering();
+    let mut parser = Parser::new(tokens);
+    if let Some(file) = file {
+        parser.set_file(file);
+    }
+    let (_, syntax_errors) = parser.parse_recovering();
+    let lexical_lines: Vec<Option<usize>> = errors.iter().map(|e| e.line).collect();
+    errors.extend(
+        syntax_errors
+            .into_iter()
+            .filter(|e| !lexical_lines.contains(&e.line)),
+    );
+    for error in &mut errors {
+        if error.file.is_none() {
+            error.file = file.map(str::to_string);
+        }
+        if let Some(line) = error.line {
+            error.source_line = source
+                .lines()
+                .nth(line.saturating_sub(1))
+                .map(str::to_string);
+        }
+    }
+    errors.sort_by_key(|e| (e.line, e.column));
+    errors
+}
+
+/// `error` as a JSON diagnostic object, with the same fields as [`JError`].
+#[cfg(feature = "serde_json")]
+pub fn to_json(error: &JError) -> serde_json::Value {
+    serde_json::json!({
+        "severity": "error",
+        "code": error.kind.code(),
+        "kind": format!("{:?}", error.kind),
+        "message": error.message,
+        "file": error.file,
+        "line": error.line,
+        "column": error.column,
+        "end_line": error.end_line,
+        "end_column": error.end_column,
+        "tip": error.tip,
+        "solution": error.solution,
+    })
+}
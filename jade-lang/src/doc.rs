@@ -0,0 +1,731 @@
+//! `jade doc`: API documentation for a project or Jolt package, built from the `##` / `///`
+//! doc comments the parser attaches to declarations, as static HTML styled like the docs site
+//! or as Markdown.
+//!
+//! Every `.jdl` file is one page, named like its import path (`lib/util.jdl` is `lib.util`).
+//! The functions, classes, enums, traits and modules declared at its top level are listed in
+//! source order with their signatures; names starting with `_` are private and left out.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::{JError, JResult};
+use crate::formatter::{expression_text, params_list};
+use crate::jolt::JoltManager;
+use crate::lexer::Lexer;
+use crate::parser::{AstNode, ClassField, Parser};
+use crate::testing::discover_source_files;
+
+/// The docs site's stylesheet, written next to the HTML pages.
+const STYLESHEET: &str = include_str!("../docs-site/styles.css");
+
+/// Words shown as keywords and as types in highlighted signatures.
+const KEYWORDS: &[&str] = &[
+    "fn",
+    "async",
+    "class",
+    "enum",
+    "trait",
+    "module",
+    "static",
+    "pub",
+    "secure",
+    "singleton",
+    "actor",
+    "observable",
+    "threadsafe",
+    "data",
+    "resource",
+];
+const TYPES: &[&str] = &["int", "float", "str", "bool", "list", "dict", "char", "any"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Function,
+    Class,
+    Enum,
+    Trait,
+    Module,
+    Field,
+    Method,
+    Variant,
+}
+
+impl ItemKind {
+    /// Label in headings and indexes.
+    pub fn label(self) -> &'static str {
+        match self {
+            ItemKind::Function | ItemKind::Method => "fn",
+            ItemKind::Class => "class",
+            ItemKind::Enum => "enum",
+            ItemKind::Trait => "trait",
+            ItemKind::Module => "module",
+            ItemKind::Field => "field",
+            ItemKind::Variant => "variant",
+        }
+    }
+}
+
+/// A documented declaration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub name: String,
+    /// The declaration as written in Jade, without its body.
+    pub signature: String,
+    pub doc: Option<String>,
+    /// Fields and methods of a class, methods of a trait, variants of an enum, or the
+    /// declarations in a module.
+    pub members: Vec<Item>,
+}
+
+/// The declarations of one file.
+#[derive(Debug, Clone)]
+pub struct Page {
+    /// Import path, e.g. `lib.util`.
+    pub module: String,
+    /// The file, relative to the documented directory.
+    pub file: PathBuf,
+    pub items: Vec<Item>,
+}
+
+/// Everything `jade doc` writes about a project or package.
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub pages: Vec<Page>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Html,
+    Markdown,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Html => "html",
+            Format::Markdown => "md",
+        }
+    }
+}
+
+/// The top-level declarations of `source`, in source order.
+pub fn document_source(source: &str) -> JResult<Vec<Item>> {
+    let tokens = Lexer::new(source).tokenize()?;
+    match Parser::new(tokens).parse()? {
+        AstNode::Block(statements) => Ok(items(&statements)),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Document every `.jdl` file under `root` (a project or package directory, or a single
+/// file), named after the manifest found there if any. Files with no declarations get no
+/// page; files that fail to parse are returned with their error.
+pub fn document_project(root: &Path) -> (Project, Vec<(PathBuf, Box<JError>)>) {
+    let base = if root.is_file() {
+        root.parent().unwrap_or(Path::new("."))
+    } else {
+        root
+    };
+    let manifest = JoltManager::find_manifest_path(base)
+        .and_then(|path| JoltManager::read_manifest(&path).ok());
+    let directory_name = base
+        .canonicalize()
+        .ok()
+        .and_then(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "Jade project".to_string());
+    let mut project = match manifest {
+        Some(manifest) => Project {
+            name: manifest.name,
+            version: Some(manifest.version),
+            description: manifest.description,
+            pages: Vec::new(),
+        },
+        None => Project {
+            name: directory_name,
+            version: None,
+            description: None,
+            pages: Vec::new(),
+        },
+    };
+
+    let mut errors = Vec::new();
+    for file in discover_source_files(root) {
+        let result = fs::read_to_string(&file)
+            .map_err(|e| Box::new(JError::io_error(e.to_string())))
+            .and_then(|source| document_source(&source));
+        match result {
+            Ok(items) if items.is_empty() => {}
+            Ok(items) => {
+                let relative = file.strip_prefix(base).unwrap_or(&file);
+                project.pages.push(Page {
+                    module: module_name(relative),
+                    file: relative.to_path_buf(),
+                    items,
+                })
+            }
+            Err(error) => errors.push((file, error)),
+        }
+    }
+    (project, errors)
+}
+
+/// `lib/util.jdl` is `lib.util`, as written in `import`.
+fn module_name(file: &Path) -> String {
+    let parts: Vec<String> = file
+        .with_extension("")
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy().into_owned())
+        .filter(|part| part != ".")
+        .collect();
+    parts.join(".")
+}
+
+fn items(statements: &[AstNode]) -> Vec<Item> {
+    statements
+        .iter()
+        .filter_map(item)
+        .filter(|item| !item.name.starts_with('_'))
+        .collect()
+}
+
+fn item(node: &AstNode) -> Option<Item> {
+    let (kind, name, signature, members) = match node {
+        AstNode::FunctionDeclaration { name, .. }
+        | AstNode::AsyncFunction { name, .. }
+        | AstNode::GenericFunction { name, .. } => (
+            ItemKind::Function,
+            name,
+            function_signature(node, "")?,
+            Vec::new(),
+        ),
+        AstNode::ClassDeclaration {
+            name,
+            class_type,
+            parent,
+            traits,
+            fields,
+            methods,
+            static_fields,
+            static_methods,
+            ..
+        } => {
+            let mut signature = String::new();
+            if let Some(class_type) = class_type {
+                signature.push_str(class_type);
+                signature.push(' ');
+            }
+            signature.push_str("class | ");
+            signature.push_str(name);
+            if let Some(parent) = parent {
+                signature.push_str(" : ");
+                signature.push_str(parent);
+            }
+            for name in traits {
+                signature.push_str(" + ");
+                signature.push_str(name);
+            }
+            let members = fields
+                .iter()
+                .chain(static_fields)
+                .map(field)
+                .chain(methods.iter().filter_map(|m| method(m, "")))
+                .chain(static_methods.iter().filter_map(|m| method(m, "static ")))
+                .filter(|member| !member.name.starts_with('_'))
+                .collect();
+            (ItemKind::Class, name, signature, members)
+        }
+        AstNode::EnumDeclaration {
+            name,
+            backing_type,
+            variants,
+            ..
+        } => {
+            let signature = match backing_type {
+                Some(backing_type) => format!("enum | {} : {}", name, backing_type),
+                None => format!("enum | {}", name),
+            };
+            let members = variants
+                .iter()
+                .map(|(variant, value)| Item {
+                    kind: ItemKind::Variant,
+                    name: variant.clone(),
+                    signature: match value {
+                        Some(value) => format!("{} = {}", variant, expression(value)),
+                        None => variant.clone(),
+                    },
+                    doc: None,
+                    members: Vec::new(),
+                })
+                .collect();
+            (ItemKind::Enum, name, signature, members)
+        }
+        AstNode::TraitDeclaration { name, methods, .. } => (
+            ItemKind::Trait,
+            name,
+            format!("trait | {}", name),
+            methods.iter().filter_map(|m| method(m, "")).collect(),
+        ),
+        AstNode::ModuleDeclaration { name, body, .. } => {
+            let members = match body.as_ref() {
+                AstNode::Block(statements) => items(statements),
+                _ => Vec::new(),
+            };
+            (
+                ItemKind::Module,
+                name,
+                format!("module | {}", name),
+                members,
+            )
+        }
+        _ => return None,
+    };
+    Some(Item {
+        kind,
+        name: name.clone(),
+        signature,
+        doc: node.doc().map(str::to_string),
+        members,
+    })
+}
+
+/// `fn [type] | name ( params )` after its decorators, one per line.
+fn function_signature(node: &AstNode, modifiers: &str) -> Option<String> {
+    match node {
+        AstNode::FunctionDeclaration {
+            name,
+            params,
+            return_type,
+            decorators,
+            ..
+        } => {
+            let mut signature = String::new();
+            for decorator in decorators {
+                signature.push('@');
+                signature.push_str(&decorator.name);
+                if !decorator.args.is_empty() {
+                    let args: Vec<String> = decorator.args.iter().map(expression).collect();
+                    signature.push_str(&format!("({})", args.join(", ")));
+                }
+                signature.push('\n');
+            }
+            signature.push_str(modifiers);
+            signature.push_str("fn ");
+            if let Some(return_type) = return_type {
+                signature.push_str(return_type);
+                signature.push(' ');
+            }
+            signature.push_str(&format!("| {} {}", name, params_list(params)));
+            Some(signature)
+        }
+        AstNode::AsyncFunction { name, params, .. } => Some(format!(
+            "{}async fn | {} {}",
+            modifiers,
+            name,
+            params_list(params)
+        )),
+        AstNode::GenericFunction {
+            name,
+            type_params,
+            params,
+            return_type,
+            ..
+        } => Some(format!(
+            "{}fn {}| {}<{}> {}",
+            modifiers,
+            return_type
+                .as_ref()
+                .map(|t| format!("{} ", t))
+                .unwrap_or_default(),
+            name,
+            type_params.join(", "),
+            params_list(params)
+        )),
+        _ => None,
+    }
+}
+
+fn method(node: &AstNode, modifiers: &str) -> Option<Item> {
+    let AstNode::FunctionDeclaration { name, .. } = node else {
+        return None;
+    };
+    Some(Item {
+        kind: ItemKind::Method,
+        name: name.clone(),
+        signature: function_signature(node, modifiers)?,
+        doc: node.doc().map(str::to_string),
+        members: Vec::new(),
+    })
+}
+
+fn field(field: &ClassField) -> Item {
+    let mut signature = String::new();
+    if field.is_static {
+        signature.push_str("static ");
+    }
+    if field.is_public {
+        signature.push_str("pub ");
+    }
+    signature.push_str(&format!("{} | {}", field.field_type, field.name));
+    if let Some(value) = &field.default_value {
+        signature.push_str(" -> ");
+        signature.push_str(&expression(value));
+    }
+    Item {
+        kind: ItemKind::Field,
+        name: field.name.clone(),
+        signature,
+        doc: field.doc.clone(),
+        members: Vec::new(),
+    }
+}
+
+/// A default value or argument as source, or `...` if it has no one-line spelling.
+fn expression(node: &AstNode) -> String {
+    expression_text(node).unwrap_or_else(|| "...".to_string())
+}
+
+/// Write `project` to `out`: an index, one page per module and, for HTML, the docs site's
+/// stylesheet. Returns the files written.
+pub fn write_docs(project: &Project, out: &Path, format: Format) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(out)?;
+    let mut written = Vec::new();
+    let mut write = |name: String, contents: String| -> io::Result<()> {
+        let path = out.join(name);
+        fs::write(&path, contents)?;
+        written.push(path);
+        Ok(())
+    };
+    write(
+        format!("index.{}", format.extension()),
+        render_index(project, format),
+    )?;
+    for page in &project.pages {
+        write(
+            format!("{}.{}", page.module, format.extension()),
+            render_page(project, page, format),
+        )?;
+    }
+    if format == Format::Html {
+        write("styles.css".to_string(), STYLESHEET.to_string())?;
+    }
+    Ok(written)
+}
+
+/// The overview: every module with its declarations and the first line of their docs.
+pub fn render_index(project: &Project, format: Format) -> String {
+    match format {
+        Format::Html => {
+            let mut body = String::new();
+            let _ = writeln!(body, "      <h1>{}</h1>", escape(&title(project)));
+            if let Some(description) = &project.description {
+                let _ = writeln!(body, "      <p class=\"lead\">{}</p>", escape(description));
+            }
+            if project.pages.is_empty() {
+                body.push_str("      <p>No documented declarations.</p>\n");
+            }
+            for page in &project.pages {
+                let href = format!("{}.html", page.module);
+                let _ = writeln!(
+                    body,
+                    "      <h2 id=\"{}\"><a href=\"{}\">{}</a></h2>",
+                    escape(&page.module),
+                    escape(&href),
+                    escape(&page.module)
+                );
+                body.push_str("      <table class=\"ref-table\">\n");
+                for item in &page.items {
+                    let _ = writeln!(
+                        body,
+                        "        <tr><td><a href=\"{}#{}\"><code>{} {}</code></a></td><td>{}</td></tr>",
+                        escape(&href),
+                        escape(&item.name),
+                        item.kind.label(),
+                        escape(&item.name),
+                        inline_html(summary(item))
+                    );
+                }
+                body.push_str("      </table>\n");
+            }
+            html_document(project, None, "Overview", &body)
+        }
+        Format::Markdown => {
+            let mut text = format!("# {}\n\n", title(project));
+            if let Some(description) = &project.description {
+                let _ = writeln!(text, "{}\n", description);
+            }
+            if project.pages.is_empty() {
+                text.push_str("No documented declarations.\n");
+            }
+            for page in &project.pages {
+                let _ = writeln!(text, "## [{}]({}.md)\n", page.module, page.module);
+                text.push_str("| Item | Summary |\n| --- | --- |\n");
+                for item in &page.items {
+                    let _ = writeln!(
+                        text,
+                        "| [`{} {}`]({}.md#{}-{}) | {} |",
+                        item.kind.label(),
+                        item.name,
+                        page.module,
+                        item.kind.label(),
+                        item.name.to_lowercase(),
+                        summary(item).replace('|', "\\|")
+                    );
+                }
+                text.push('\n');
+            }
+            text
+        }
+    }
+}
+
+/// One module's page: each declaration's signature and docs, then its members'.
+pub fn render_page(project: &Project, page: &Page, format: Format) -> String {
+    match format {
+        Format::Html => {
+            let mut body = String::new();
+            let _ = writeln!(body, "      <h1>{}</h1>", escape(&page.module));
+            let _ = writeln!(
+                body,
+                "      <p class=\"lead\"><code>{}</code></p>",
+                escape(&page.file.display().to_string())
+            );
+            for item in &page.items {
+                html_item(&mut body, item, "", 2);
+            }
+            html_document(project, Some(page), &page.module, &body)
+        }
+        Format::Markdown => {
+            let mut text = format!("# {}\n\n`{}`\n", page.module, page.file.display());
+            for item in &page.items {
+                markdown_item(&mut text, item, 2);
+            }
+            text
+        }
+    }
+}
+
+fn title(project: &Project) -> String {
+    match &project.version {
+        Some(version) => format!("{} {}", project.name, version),
+        None => project.name.clone(),
+    }
+}
+
+/// First line of an item's docs.
+fn summary(item: &Item) -> &str {
+    item.doc
+        .as_deref()
+        .and_then(|doc| doc.lines().next())
+        .unwrap_or("")
+}
+
+fn html_item(body: &mut String, item: &Item, prefix: &str, level: usize) {
+    let id = format!("{}{}", prefix, item.name);
+    let level = level.min(6);
+    let _ = writeln!(
+        body,
+        "      <h{level} id=\"{}\">{} {}</h{level}>",
+        escape(&id),
+        item.kind.label(),
+        escape(&item.name)
+    );
+    let _ = writeln!(
+        body,
+        "      <pre><code>{}</code></pre>",
+        highlight(&item.signature)
+    );
+    if let Some(doc) = &item.doc {
+        body.push_str(&doc_html(doc));
+    }
+    let (variants, members): (Vec<&Item>, Vec<&Item>) = item
+        .members
+        .iter()
+        .partition(|member| member.kind == ItemKind::Variant);
+    if !variants.is_empty() {
+        body.push_str("      <ul>\n");
+        for variant in variants {
+            let _ = writeln!(
+                body,
+                "        <li><code>{}</code></li>",
+                escape(&variant.signature)
+            );
+        }
+        body.push_str("      </ul>\n");
+    }
+    for member in members {
+        html_item(body, member, &format!("{}.", id), level + 1);
+    }
+}
+
+fn markdown_item(text: &mut String, item: &Item, level: usize) {
+    let _ = writeln!(
+        text,
+        "\n{} {} {}\n\n```jade\n{}\n```",
+        "#".repeat(level.min(6)),
+        item.kind.label(),
+        item.name,
+        item.signature
+    );
+    if let Some(doc) = &item.doc {
+        let _ = writeln!(text, "\n{}", doc);
+    }
+    let (variants, members): (Vec<&Item>, Vec<&Item>) = item
+        .members
+        .iter()
+        .partition(|member| member.kind == ItemKind::Variant);
+    if !variants.is_empty() {
+        text.push('\n');
+        for variant in variants {
+            let _ = writeln!(text, "- `{}`", variant.signature);
+        }
+    }
+    for member in members {
+        markdown_item(text, member, level + 1);
+    }
+}
+
+/// A page in the docs site's layout: modules in the sidebar, with this page's declarations
+/// under "On this page".
+fn html_document(project: &Project, current: Option<&Page>, heading: &str, body: &str) -> String {
+    let mut sidebar = String::new();
+    let active = |yes: bool| if yes { " class=\"active\"" } else { "" };
+    let _ = writeln!(
+        sidebar,
+        "        <a href=\"index.html\"{}>Overview</a>",
+        active(current.is_none())
+    );
+    for page in &project.pages {
+        let _ = writeln!(
+            sidebar,
+            "        <a href=\"{}.html\"{}>{}</a>",
+            escape(&page.module),
+            active(current.is_some_and(|c| c.module == page.module)),
+            escape(&page.module)
+        );
+    }
+    let mut toc = String::new();
+    if let Some(page) = current {
+        toc.push_str(
+            "      <nav class=\"docs-toc\">\n        <div class=\"toc-title\">On this page</div>\n",
+        );
+        for item in &page.items {
+            let _ = writeln!(
+                toc,
+                "        <a href=\"#{}\">{}</a>",
+                escape(&item.name),
+                escape(&item.name)
+            );
+        }
+        toc.push_str("      </nav>\n");
+    }
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="UTF-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1" />
+  <title>{heading} — {name}</title>
+  <link rel="stylesheet" href="styles.css" />
+</head>
+<body>
+  <header class="site-header">
+    <nav class="nav">
+      <a href="index.html" class="logo-wrap"><span>{name}</span></a>
+    </nav>
+  </header>
+
+  <div class="docs-layout">
+    <aside class="docs-sidebar">
+      <nav>
+{sidebar}      </nav>
+{toc}    </aside>
+    <article class="docs-content">
+{body}    </article>
+  </div>
+  <footer class="site-footer"><p>{title}</p></footer>
+</body>
+</html>
+"#,
+        heading = escape(heading),
+        name = escape(&project.name),
+        title = escape(&title(project)),
+    )
+}
+
+/// Doc comment text as HTML paragraphs (split at blank lines), with `code` spans.
+fn doc_html(doc: &str) -> String {
+    let mut html = String::new();
+    for paragraph in doc.split("\n\n").filter(|p| !p.trim().is_empty()) {
+        let _ = writeln!(html, "      <p>{}</p>", inline_html(paragraph.trim()));
+    }
+    html
+}
+
+/// Escaped text with `backticked` spans as `<code>`.
+fn inline_html(text: &str) -> String {
+    text.split('`')
+        .enumerate()
+        .map(|(i, part)| {
+            if i % 2 == 1 {
+                format!("<code>{}</code>", escape(part))
+            } else {
+                escape(part)
+            }
+        })
+        .collect()
+}
+
+/// A signature with keywords and built-in types marked up like the docs site's code samples.
+fn highlight(signature: &str) -> String {
+    let mut html = String::new();
+    let mut word = String::new();
+    let mut in_string = false;
+    fn flush(word: &mut String, html: &mut String, in_string: bool) {
+        if word.is_empty() {
+            return;
+        }
+        let class = match word.as_str() {
+            _ if in_string => None,
+            w if KEYWORDS.contains(&w) => Some("kw"),
+            w if TYPES.contains(&w) => Some("typ"),
+            _ => None,
+        };
+        match class {
+            Some(class) => {
+                let _ = write!(html, "<span class=\"{}\">{}</span>", class, escape(word));
+            }
+            None => html.push_str(&escape(word)),
+        }
+        word.clear();
+    }
+    for c in signature.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        flush(&mut word, &mut html, in_string);
+        if c == '"' {
+            in_string = !in_string;
+        }
+        html.push_str(&escape(&c.to_string()));
+    }
+    flush(&mut word, &mut html, in_string);
+    html
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
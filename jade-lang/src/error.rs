@@ -5,18 +5,41 @@
 
 use std::fmt;
 
-#[derive(Debug, Clone)]
+/// Result of evaluating Jade code. Errors are boxed: a `JError` is large, and most results are
+/// not errors.
+pub type JResult<T> = Result<T, Box<JError>>;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct JError {
     pub kind: ErrorKind,
     pub message: String,
+    /// File the location is in, when it is not the program being run (e.g. an imported module).
+    pub file: Option<String>,
     pub line: Option<usize>,
     pub column: Option<usize>,
+    /// Where the offending text ends (exclusive), when known; for syntax errors.
+    pub end_line: Option<usize>,
+    pub end_column: Option<usize>,
     pub source_line: Option<String>,
     pub tip: Option<String>,
     pub solution: Option<String>,
     pub context: Option<String>,
     pub similar_names: Vec<String>,
     pub help_url: Option<String>,
+    /// Traceback: where each running Jade function was when the error was raised, outermost
+    /// (top-level code) first, ending at the error's location.
+    pub trace: Vec<TraceEntry>,
+}
+
+/// One line of a traceback: a location inside `function` (`<module>` for top-level code).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEntry {
+    pub function: String,
+    /// `None` for the program being run.
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub source_line: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -47,27 +70,113 @@ pub enum ErrorKind {
     UndefinedFunction,
     WrongArgumentCount,
     InvalidArgument,
+    /// Call to a `@deprecated` function while deprecations are errors (`--deny-deprecated`).
+    Deprecated,
 
     // Other
     FileNotFound,
     IOError,
+    /// Raised by `panic` in Jade code.
+    Thrown,
     /// Message is already a full formatted error (e.g. from parser); display as-is.
     AlreadyFormatted,
 }
 
+/// Explanations of every error code, printed by `jade explain`.
+const ERROR_INDEX: &str = include_str!("error_index.md");
+
+impl ErrorKind {
+    pub const ALL: [ErrorKind; 23] = [
+        ErrorKind::UnexpectedCharacter,
+        ErrorKind::UnterminatedString,
+        ErrorKind::InvalidNumber,
+        ErrorKind::InvalidEscape,
+        ErrorKind::UnexpectedToken,
+        ErrorKind::ExpectedToken,
+        ErrorKind::InvalidSyntax,
+        ErrorKind::MissingOperand,
+        ErrorKind::UndefinedVariable,
+        ErrorKind::TypeError,
+        ErrorKind::DivisionByZero,
+        ErrorKind::IndexOutOfBounds,
+        ErrorKind::KeyNotFound,
+        ErrorKind::InvalidOperation,
+        ErrorKind::StackOverflow,
+        ErrorKind::UndefinedFunction,
+        ErrorKind::WrongArgumentCount,
+        ErrorKind::InvalidArgument,
+        ErrorKind::Deprecated,
+        ErrorKind::FileNotFound,
+        ErrorKind::IOError,
+        ErrorKind::Thrown,
+        ErrorKind::AlreadyFormatted,
+    ];
+
+    /// Stable identifier shown in diagnostics: `J01xx` lexer, `J02xx` parser, `J03xx` runtime,
+    /// `J04xx` function calls, `J05xx` other. Codes are never reassigned.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::UnexpectedCharacter => "J0101",
+            ErrorKind::UnterminatedString => "J0102",
+            ErrorKind::InvalidNumber => "J0103",
+            ErrorKind::InvalidEscape => "J0104",
+            ErrorKind::UnexpectedToken => "J0201",
+            ErrorKind::ExpectedToken => "J0202",
+            ErrorKind::InvalidSyntax => "J0203",
+            ErrorKind::MissingOperand => "J0204",
+            ErrorKind::UndefinedVariable => "J0301",
+            ErrorKind::TypeError => "J0302",
+            ErrorKind::DivisionByZero => "J0303",
+            ErrorKind::IndexOutOfBounds => "J0304",
+            ErrorKind::KeyNotFound => "J0305",
+            ErrorKind::InvalidOperation => "J0306",
+            ErrorKind::StackOverflow => "J0307",
+            ErrorKind::UndefinedFunction => "J0401",
+            ErrorKind::WrongArgumentCount => "J0402",
+            ErrorKind::InvalidArgument => "J0403",
+            ErrorKind::Deprecated => "J0404",
+            ErrorKind::AlreadyFormatted => "J0500",
+            ErrorKind::FileNotFound => "J0501",
+            ErrorKind::IOError => "J0502",
+            ErrorKind::Thrown => "J0503",
+        }
+    }
+
+    /// The kind with this code (case-insensitive), if any.
+    pub fn from_code(code: &str) -> Option<ErrorKind> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.code().eq_ignore_ascii_case(code))
+    }
+}
+
+/// The error index entry for `code` (e.g. `J0303`): a title line, then an explanation with
+/// examples.
+pub fn explain(code: &str) -> Option<&'static str> {
+    let code = ErrorKind::from_code(code)?.code();
+    let start = ERROR_INDEX.find(&format!("\n## {}:", code))? + 1;
+    let entry = &ERROR_INDEX[start..];
+    let end = entry.find("\n## ").map_or(entry.len(), |end| end + 1);
+    Some(entry[..end].trim_end())
+}
+
 impl JError {
     pub fn new(kind: ErrorKind, message: String) -> Self {
         Self {
             kind,
             message,
+            file: None,
             line: None,
             column: None,
+            end_line: None,
+            end_column: None,
             source_line: None,
             tip: None,
             solution: None,
             context: None,
             similar_names: Vec::new(),
             help_url: None,
+            trace: Vec::new(),
         }
     }
 
@@ -77,7 +186,17 @@ impl JError {
         self
     }
 
-    #[allow(dead_code)]
+    pub fn with_end(mut self, line: usize, column: usize) -> Self {
+        self.end_line = Some(line);
+        self.end_column = Some(column);
+        self
+    }
+
+    pub fn with_file(mut self, file: String) -> Self {
+        self.file = Some(file);
+        self
+    }
+
     pub fn with_source(mut self, source_line: String) -> Self {
         self.source_line = Some(source_line);
         self
@@ -108,6 +227,104 @@ impl JError {
         self
     }
 
+    /// Drop the location; the interpreter fills it in from the expression that raised the error.
+    pub fn unlocated(mut self) -> Self {
+        self.line = None;
+        self.column = None;
+        self
+    }
+
+    /// Keep the message as raised; for errors classified from a message, which carries no
+    /// location.
+    pub(crate) fn raised_as(mut self, message: &str) -> Self {
+        self.message = message.to_string();
+        self.unlocated()
+    }
+
+    // Errors raised with a message, by kind; each gets the kind's usual tip and solution
+
+    /// A lexer error of `kind` (one of the lexer kinds).
+    pub fn lexical(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self::new(kind, message.into())
+            .with_tip("Check your string/character literals and number format.".to_string())
+            .with_solution("Strings: \"...\". Characters: 'x'. Comments: # or //.".to_string())
+    }
+
+    /// A parser error of `kind` (one of the parser kinds).
+    pub fn syntax(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self::new(kind, message.into())
+            .with_tip("Check brackets, parentheses, and statement syntax.".to_string())
+            .with_solution("See the Jade syntax guide for the correct form.".to_string())
+    }
+
+    /// A builtin or method called with too few or too many arguments.
+    pub fn argument_count(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::WrongArgumentCount, message.into())
+            .with_tip("Check the number and order of arguments for this function.".to_string())
+            .with_solution("Use help or the docs: e.g. range(n), range(start, end), or range(start, end, step).".to_string())
+    }
+
+    /// An argument of the wrong type or out of the accepted values.
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidArgument, message.into())
+            .with_tip("This function expects a specific type (list, string, number, etc.).".to_string())
+            .with_solution("Pass the correct type: use type() to inspect values, or int/float/str/list() to convert.".to_string())
+    }
+
+    /// An operation that needs at least one element got an empty collection.
+    pub fn empty_collection(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidArgument, message.into())
+            .with_tip("The collection is empty; this operation needs at least one element.".to_string())
+            .with_solution("Check length with len() before calling, or provide a default.".to_string())
+    }
+
+    /// An index or position outside its collection.
+    pub fn out_of_range(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::IndexOutOfBounds, message.into())
+            .with_tip("The index is outside the valid range for this list/string.".to_string())
+            .with_solution("Use 0 <= index < len(collection); check len() first.".to_string())
+    }
+
+    /// A lookup of a key that is not there.
+    pub fn missing_key(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::KeyNotFound, message.into())
+            .with_tip("The key does not exist in this dictionary.".to_string())
+            .with_solution("Use get(key) or contains(dict, key) before accessing.".to_string())
+    }
+
+    /// A file or module that could not be found.
+    pub fn missing_file(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::FileNotFound, message.into())
+            .with_tip("The file path may be wrong or the file may not exist.".to_string())
+            .with_solution("Use an absolute path or check the current directory.".to_string())
+    }
+
+    /// Reading or writing failed.
+    pub fn io_error(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::IOError, message.into())
+            .with_tip("Disk or permission issue.".to_string())
+            .with_solution("Check path, permissions, and disk space.".to_string())
+    }
+
+    /// A value whose type does not fit the operation.
+    pub fn type_mismatch(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::TypeError, message.into())
+            .with_tip("The value type doesn't match what the function or operator expects.".to_string())
+            .with_solution("Convert with int(), float(), str(), list(), or dict().".to_string())
+    }
+
+    /// A failed `assert`, or a property test that found a counterexample.
+    pub fn assertion_failed(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidOperation, message.into())
+    }
+
+    /// An operation the values, or the interpreter, do not support.
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidOperation, message.into())
+            .with_tip("This usually means a value was used in an unsupported way.".to_string())
+            .with_solution("Check types and arguments; use type(value) to inspect.".to_string())
+    }
+
     // Smart error constructors with automatic tips and solutions
 
     pub fn undefined_variable(name: &str, line: usize, column: usize) -> Self {
@@ -280,7 +497,7 @@ impl JError {
 
     pub fn unexpected_token(expected: &str, got: &str, line: usize, column: usize) -> Self {
         let mut error = Self::new(
-            ErrorKind::UnexpectedToken,
+            ErrorKind::ExpectedToken,
             format!("Expected {}, but got {}", expected, got),
         )
         .with_location(line, column);
@@ -376,6 +593,37 @@ impl JError {
         error
     }
 
+    /// `@validate`: an argument does not match the declared parameter type.
+    pub fn argument_type(func_name: &str, param: &str, expected: &str, got: &str) -> Self {
+        Self::new(
+            ErrorKind::TypeError,
+            format!(
+                "Argument '{}' of '{}' expected {}, got {}",
+                param, func_name, expected, got
+            ),
+        )
+        .with_tip(format!("'{}' is declared as {} | {}", param, expected, param))
+        .with_context(format!("@validate on '{}'", func_name))
+    }
+
+    /// `@validate(check)`: the validator rejected the arguments.
+    pub fn validation_failed(func_name: &str, reason: &str) -> Self {
+        Self::new(
+            ErrorKind::InvalidArgument,
+            format!("Arguments to '{}' failed validation: {}", func_name, reason),
+        )
+        .with_tip("The @validate function returned false or an error message".to_string())
+        .with_context(format!("@validate on '{}'", func_name))
+    }
+
+    pub fn deprecated(func_name: &str, message: &str) -> Self {
+        Self::new(
+            ErrorKind::Deprecated,
+            format!("'{}' is deprecated: {}", func_name, message),
+        )
+        .with_tip("Deprecation warnings are errors because of --deny-deprecated".to_string())
+    }
+
     #[allow(dead_code)]
     pub fn invalid_operation(
         operation: &str,
@@ -422,7 +670,7 @@ impl JError {
         column: usize,
     ) -> Self {
         let mut error =
-            Self::new(ErrorKind::UnexpectedToken, message.to_string()).with_location(line, column);
+            Self::new(ErrorKind::ExpectedToken, message.to_string()).with_location(line, column);
 
         // Provide context-specific tips
         let (tip, solution): (&str, &str) = match expected {
@@ -518,7 +766,6 @@ impl JError {
         error
     }
 
-    #[allow(dead_code)]
     pub fn file_not_found(filename: &str) -> Self {
         let mut error = Self::new(
             ErrorKind::FileNotFound,
@@ -535,94 +782,6 @@ impl JError {
 
         error
     }
-
-    /// Convert any interpreter/lexer/parser error string into a structured JError
-    /// so all errors get consistent formatting, tips, and solutions.
-    pub fn from_interpreter_message(msg: &str) -> Self {
-        let msg = msg.trim();
-        // Already formatted by JError::Display (e.g. from parser) — display as-is
-        if (msg.starts_with('\n') || msg.starts_with("❌")) && msg.contains(" ERROR: ") {
-            return JError::new(ErrorKind::AlreadyFormatted, msg.to_string());
-        }
-        let inner = msg
-            .strip_prefix("Lexer error: ")
-            .or_else(|| msg.strip_prefix("Parser error: "))
-            .or_else(|| msg.strip_prefix("Runtime error: "))
-            .unwrap_or(msg);
-
-        if (inner.starts_with('\n') || inner.starts_with("❌") || inner.trim_start().starts_with("❌")) && inner.contains(" ERROR: ") {
-            return JError::new(ErrorKind::AlreadyFormatted, inner.trim().to_string());
-        }
-        if msg.starts_with("Lexer error:") || (msg.contains("Unterminated") && msg.contains("literal")) {
-            return JError::new(ErrorKind::UnexpectedCharacter, inner.to_string())
-                .with_tip("Check your string/character literals and number format.".to_string())
-                .with_solution("Strings: \"...\". Characters: 'x'. Comments: # or //.".to_string());
-        }
-        if msg.starts_with("Parser error:") || (inner.contains("Expected") && (inner.contains("but got") || inner.contains("Expected"))) {
-            return JError::new(ErrorKind::UnexpectedToken, inner.to_string())
-                .with_tip("Check brackets, parentheses, and statement syntax.".to_string())
-                .with_solution("See the Jade syntax guide for the correct form.".to_string());
-        }
-        if inner.contains("not defined") {
-            if let Some(name) = extract_identifier(inner) {
-                return JError::undefined_variable(&name, 0, 0);
-            }
-        }
-        if inner.contains("not found") && (inner.contains("Function") || inner.contains("function") || inner.contains("Decorator")) {
-            if let Some(name) = extract_identifier(inner) {
-                return JError::undefined_function(&name, 0, 0);
-            }
-        }
-        if inner.to_lowercase().contains("divide by zero") || inner.to_lowercase().contains("division by zero") {
-            return JError::division_by_zero(0, 0);
-        }
-        if inner.contains("Stack overflow") {
-            return JError::stack_overflow(50);
-        }
-        if inner.contains("expects exactly") || inner.contains("expects 1 ") || inner.contains("expects 2 ") || inner.contains("expects 1-2") || inner.contains("expects 2 or 3") || inner.contains("expects at least 1") || inner.contains("expects no arguments") {
-            return JError::new(ErrorKind::WrongArgumentCount, inner.to_string())
-                .with_tip("Check the number and order of arguments for this function.".to_string())
-                .with_solution("Use help or the docs: e.g. range(n), range(start, end), or range(start, end, step).".to_string());
-        }
-        if inner.contains("can only be called on") || inner.contains("can only sum") || inner.contains("requires a ") || inner.contains("requires an ") || inner.contains("expects integer") || inner.contains("expects a ") || inner.contains("expects two ") || inner.contains("must be ") || inner.contains("can only be used on") {
-            return JError::new(ErrorKind::InvalidArgument, inner.to_string())
-                .with_tip("This function expects a specific type (list, string, number, etc.).".to_string())
-                .with_solution("Pass the correct type: use type() to inspect values, or int/float/str/list() to convert.".to_string());
-        }
-        if inner.contains("empty list") || inner.contains("empty vector") || inner.contains("Cannot find min") || inner.contains("Cannot find max") || inner.contains("Cannot pop") || inner.contains("non-empty list") {
-            return JError::new(ErrorKind::InvalidArgument, inner.to_string())
-                .with_tip("The collection is empty; this operation needs at least one element.".to_string())
-                .with_solution("Check length with len() before calling, or provide a default.".to_string());
-        }
-        if inner.contains("index out of bounds") || inner.contains("Index out of bounds") || inner.contains("out of range") {
-            return JError::new(ErrorKind::IndexOutOfBounds, inner.to_string())
-                .with_tip("The index is outside the valid range for this list/string.".to_string())
-                .with_solution("Use 0 <= index < len(collection); check len() first.".to_string());
-        }
-        if inner.contains("key not found") || inner.contains("Key not found") || inner.contains("no key") {
-            return JError::new(ErrorKind::KeyNotFound, inner.to_string())
-                .with_tip("The key does not exist in this dictionary.".to_string())
-                .with_solution("Use get(key) or contains(dict, key) before accessing.".to_string());
-        }
-        if inner.contains("Could not read") || (inner.contains("File ") && inner.contains("not found")) || inner.contains("file not found") {
-            return JError::new(ErrorKind::FileNotFound, inner.to_string())
-                .with_tip("The file path may be wrong or the file may not exist.".to_string())
-                .with_solution("Use an absolute path or check the current directory.".to_string());
-        }
-        if inner.contains("IO error") || inner.contains("I/O") || (inner.contains("read file") || (inner.contains("write") && inner.contains("failed"))) {
-            return JError::new(ErrorKind::IOError, inner.to_string())
-                .with_tip("Disk or permission issue.".to_string())
-                .with_solution("Check path, permissions, and disk space.".to_string());
-        }
-        if inner.contains("expects a class") || (inner.contains("got ") && (inner.contains("expected") || inner.contains("expects"))) {
-            return JError::new(ErrorKind::TypeError, inner.to_string())
-                .with_tip("The value type doesn't match what the function or operator expects.".to_string())
-                .with_solution("Convert with int(), float(), str(), list(), or dict().".to_string());
-        }
-        JError::new(ErrorKind::InvalidOperation, inner.to_string())
-            .with_tip("This usually means a value was used in an unsupported way.".to_string())
-            .with_solution("Check types and arguments; use type(value) to inspect.".to_string())
-    }
 }
 
 impl fmt::Display for JError {
@@ -632,22 +791,63 @@ impl fmt::Display for JError {
             return write!(f, "{}", self.message);
         }
         let (emoji, severity) = match self.kind {
-            ErrorKind::UnexpectedCharacter | ErrorKind::UnexpectedToken | ErrorKind::ExpectedToken | ErrorKind::InvalidSyntax => ("❌", "SYNTAX"),
+            ErrorKind::UnexpectedCharacter
+            | ErrorKind::UnterminatedString
+            | ErrorKind::InvalidNumber
+            | ErrorKind::InvalidEscape
+            | ErrorKind::UnexpectedToken
+            | ErrorKind::ExpectedToken
+            | ErrorKind::InvalidSyntax
+            | ErrorKind::MissingOperand => ("❌", "SYNTAX"),
             ErrorKind::UndefinedVariable | ErrorKind::UndefinedFunction => ("🔍", "NAME"),
             ErrorKind::TypeError => ("🔧", "TYPE"),
             ErrorKind::DivisionByZero | ErrorKind::IndexOutOfBounds | ErrorKind::KeyNotFound => ("⚠️", "RUNTIME"),
             ErrorKind::StackOverflow => ("💥", "STACK"),
             ErrorKind::WrongArgumentCount | ErrorKind::InvalidArgument => ("📝", "ARGUMENT"),
             ErrorKind::FileNotFound | ErrorKind::IOError => ("📁", "FILE"),
+            ErrorKind::Deprecated => ("⚠️", "DEPRECATION"),
             _ => ("❗", "ERROR"),
         };
 
+        // Python-style traceback when the error escaped from inside a function
+        if self.trace.len() > 1 {
+            writeln!(f, "\nTraceback (most recent call last):")?;
+            let mut repeated = 0;
+            for (i, entry) in self.trace.iter().enumerate() {
+                // Deep recursion: show a repeated entry three times, then count the rest
+                if i > 0 && *entry == self.trace[i - 1] {
+                    repeated += 1;
+                    if repeated >= 3 {
+                        continue;
+                    }
+                } else if repeated >= 3 {
+                    writeln!(f, "  [Previous line repeated {} more times]", repeated - 2)?;
+                    repeated = 0;
+                } else {
+                    repeated = 0;
+                }
+                let file = entry.file.as_deref().unwrap_or("<main>");
+                writeln!(f, "  File \"{}\", line {}, in {}", file, entry.line, entry.function)?;
+                if let Some(source) = &entry.source_line {
+                    writeln!(f, "    {}", source.trim())?;
+                }
+            }
+            if repeated >= 3 {
+                writeln!(f, "  [Previous line repeated {} more times]", repeated - 2)?;
+            }
+        } else {
+            writeln!(f)?;
+        }
+
         // Single line header
-        write!(f, "\n{} {} ERROR: {}", emoji, severity, self.message)?;
+        write!(f, "{} {} ERROR[{}]: {}", emoji, severity, self.kind.code(), self.message)?;
 
         // Location on same line if available
         if let (Some(line), Some(column)) = (self.line, self.column) {
-            write!(f, " (line {}, col {})", line, column)?;
+            match &self.file {
+                Some(file) => write!(f, " ({}, line {}, col {})", file, line, column)?,
+                None => write!(f, " (line {}, col {})", line, column)?,
+            }
         }
         writeln!(f)?;
 
@@ -689,7 +889,7 @@ impl fmt::Display for JError {
 }
 
 // Helper function to find similar names using Levenshtein distance
-fn find_similar_names(name: &str, candidates: &[&str]) -> Vec<String> {
+pub(crate) fn find_similar_names(name: &str, candidates: &[&str]) -> Vec<String> {
     let mut matches: Vec<(String, usize)> = candidates
         .iter()
         .map(|&candidate| {
@@ -752,23 +952,6 @@ fn levenshtein_distance(s1: &str, s2: &str) -> usize {
     matrix[len1][len2]
 }
 
-// Convert string errors to JError (delegate to from_interpreter_message for consistency)
-impl From<String> for JError {
-    fn from(s: String) -> Self {
-        JError::from_interpreter_message(&s)
-    }
-}
-
-fn extract_identifier(s: &str) -> Option<String> {
-    // Extract identifier from error message like "Variable 'x' not defined"
-    if let Some(start) = s.find('\'') {
-        if let Some(end) = s[start + 1..].find('\'') {
-            return Some(s[start + 1..start + 1 + end].to_string());
-        }
-    }
-    None
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,1567 @@
+//! `jade fmt`: rewrite Jade source in canonical layout.
+//!
+//! Each top-level statement is printed from its AST: two-space indentation, one statement per
+//! line, `fn | name ( type | param ) > {` headers and `type: name = value` declarations. The
+//! printed text is parsed again, and a statement whose reprint does not give back the same tree
+//! is kept exactly as written. Comments and blank lines are carried over by lining up the
+//! printed tokens with the original ones, and literals keep their original spelling (escapes,
+//! `$name` interpolation, `1.50`).
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::error::{ErrorKind, JError, JResult};
+use crate::lexer::{Lexer, Token, TokenType};
+use crate::parser::{
+    AstNode, BinaryOp, ClassField, CondBranch, MatchArm, Parser, Pattern, SwitchCase, UnaryOp,
+};
+
+const INDENT: &str = "  ";
+
+/// Dicts longer than this when printed on one line get one entry per line.
+const MAX_INLINE_DICT: usize = 72;
+
+/// Largest token alignment table (source tokens × printed tokens) worth building; bigger
+/// statements are kept as written.
+const MAX_ALIGNMENT_CELLS: usize = 4_000_000;
+
+/// Binding strength of each expression form, loosest first, following the parser's precedence
+/// climb from `pipeline` down to `primary`.
+const PIPELINE: u8 = 0;
+const LAMBDA: u8 = 1;
+const OR: u8 = 2;
+const AND: u8 = 3;
+const BIT_OR: u8 = 4;
+const BIT_XOR: u8 = 5;
+const BIT_AND: u8 = 6;
+const EQUALITY: u8 = 7;
+const COMPARISON: u8 = 8;
+const SHIFT: u8 = 9;
+const RANGE: u8 = 10;
+const TERM: u8 = 11;
+const FACTOR: u8 = 12;
+const UNARY: u8 = 13;
+const POSTFIX: u8 = 14;
+const PRIMARY: u8 = 15;
+
+/// `source` in canonical layout, ending with a newline. Fails on lexical and syntax errors,
+/// and (as a safeguard) if the result would not parse to the same program.
+pub fn format_source(source: &str) -> JResult<String> {
+    let all = Lexer::new(source).tokenize_with_comments()?;
+    let (comments, tokens): (Vec<Token>, Vec<Token>) = all
+        .into_iter()
+        .partition(|t| t.token_type == TokenType::Comment);
+    let statements = Parser::without_spans(tokens.clone()).parse_statements()?;
+
+    let formatted = Formatter::new(source, tokens, &comments, statements).run();
+
+    let reparsed = Lexer::new(&formatted)
+        .tokenize_with_comments()
+        .ok()
+        .and_then(|all| {
+            let count = all
+                .iter()
+                .filter(|t| t.token_type == TokenType::Comment)
+                .count();
+            let code = all
+                .into_iter()
+                .filter(|t| t.token_type != TokenType::Comment)
+                .collect();
+            Some((Parser::without_spans(code).parse().ok()?, count))
+        });
+    let original = Parser::without_spans(Lexer::new(source).tokenize()?).parse()?;
+    match reparsed {
+        Some((ast, count)) if ast == original && count == comments.len() => Ok(formatted),
+        _ => Err(Box::new(JError::new(
+            ErrorKind::InvalidOperation,
+            "Formatting would change the program; the source was left as is".to_string(),
+        ))),
+    }
+}
+
+/// Where a comment sits relative to the code: at the end of the line holding a token, or on
+/// its own line before a token (or after all code).
+#[derive(Clone, Copy, PartialEq)]
+enum Anchor {
+    After(usize),
+    Before(usize),
+    End,
+}
+
+struct Comment {
+    text: String,
+    anchor: Anchor,
+    blank_before: bool,
+}
+
+/// Something to emit before a printed line.
+enum Trivia {
+    Blank,
+    Comment(String),
+}
+
+struct Formatter<'a> {
+    lines: Vec<&'a str>,
+    /// Tokens as the parser saw them (no comments).
+    tokens: Vec<Token>,
+    comments: Vec<Comment>,
+    statements: Vec<(AstNode, Range<usize>)>,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(
+        source: &'a str,
+        tokens: Vec<Token>,
+        comments: &[Token],
+        statements: Vec<(AstNode, Range<usize>)>,
+    ) -> Self {
+        let lines: Vec<&str> = source
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect();
+        let mut formatter = Self {
+            lines,
+            tokens,
+            comments: Vec::new(),
+            statements,
+        };
+
+        let code: Vec<usize> = (0..formatter.tokens.len())
+            .filter(|&i| is_code(&formatter.tokens[i]))
+            .collect();
+        let mut next = 0;
+        for comment in comments {
+            let at = (comment.line, comment.column);
+            while next < code.len() && position(&formatter.tokens[code[next]]) < at {
+                next += 1;
+            }
+            let anchor = match next.checked_sub(1).map(|i| code[i]) {
+                Some(prev) if token_end(&formatter.tokens[prev]).0 == comment.line => {
+                    Anchor::After(prev)
+                }
+                _ => code.get(next).map_or(Anchor::End, |&i| Anchor::Before(i)),
+            };
+            formatter.comments.push(Comment {
+                text: comment.lexeme.trim_end().to_string(),
+                anchor,
+                blank_before: formatter.blank_before(comment.line),
+            });
+        }
+        formatter
+    }
+
+    fn run(&self) -> String {
+        let mut out: Vec<String> = Vec::new();
+
+        for (node, range) in &self.statements {
+            let ids: Vec<usize> = range
+                .clone()
+                .filter(|&i| is_code(&self.tokens[i]))
+                .collect();
+            let Some(&first) = ids.first() else {
+                continue;
+            };
+            for comment in self.comments_at(Anchor::Before(first)) {
+                emit(&mut out, comment.text.clone(), comment.blank_before);
+            }
+            let blank = self.starts_line(first) && self.blank_before(self.tokens[first].line);
+            let text = self
+                .printed(node, &ids)
+                .unwrap_or_else(|| self.verbatim(&ids));
+            emit(&mut out, text, blank);
+        }
+        for comment in self.comments_at(Anchor::End) {
+            emit(&mut out, comment.text.clone(), comment.blank_before);
+        }
+
+        if out.is_empty() {
+            return String::new();
+        }
+        let mut text = out.join("\n");
+        text.push('\n');
+        text
+    }
+
+    fn comments_at(&self, anchor: Anchor) -> impl Iterator<Item = &Comment> {
+        self.comments.iter().filter(move |c| c.anchor == anchor)
+    }
+
+    /// Whether the source line before `line` is blank.
+    fn blank_before(&self, line: usize) -> bool {
+        line >= 2 && self.lines[line - 2].trim().is_empty()
+    }
+
+    /// Whether token `i` is the first on its line.
+    fn starts_line(&self, i: usize) -> bool {
+        i == 0 || token_end(&self.tokens[i - 1]).0 < self.tokens[i].line
+    }
+
+    /// The statement made of code tokens `ids` exactly as written, with the comment that ends
+    /// its last line.
+    fn verbatim(&self, ids: &[usize]) -> String {
+        let first = &self.tokens[ids[0]];
+        let last = ids[ids.len() - 1];
+        let (end_line, end_column) = token_end(&self.tokens[last]);
+        let mut text = String::new();
+        for line in first.line..=end_line {
+            let chars: Vec<char> = self.lines[line - 1].chars().collect();
+            let from = if line == first.line {
+                first.column - 1
+            } else {
+                0
+            };
+            let to = if line == end_line {
+                end_column - 1
+            } else {
+                chars.len()
+            };
+            if line > first.line {
+                text.push('\n');
+            }
+            text.extend(&chars[from.min(chars.len())..to.min(chars.len())]);
+        }
+        if let Some(comment) = self.comments_at(Anchor::After(last)).next() {
+            text.push(' ');
+            text.push_str(&comment.text);
+        }
+        text
+    }
+
+    /// The statement `node` (code tokens `ids`) printed in canonical layout with its comments
+    /// and blank lines, or `None` if it cannot be printed faithfully.
+    fn printed(&self, node: &AstNode, ids: &[usize]) -> Option<String> {
+        let source: Vec<Token> = ids.iter().map(|&i| self.tokens[i].clone()).collect();
+        let text = Printer { tokens: &source }.statement(node, 0)?;
+        let printed = lex_code(&text)?;
+        let pairs = align(&source, &printed)?;
+
+        let respelled = respell(&text, &printed, &source, &pairs);
+        let (text, printed) = if respelled != text && parses_to(&respelled, node) {
+            let tokens = lex_code(&respelled)?;
+            (respelled, tokens)
+        } else if parses_to(&text, node) {
+            (text, printed)
+        } else {
+            return None;
+        };
+
+        let lines: Vec<&str> = text.split('\n').collect();
+        // Printed lines that begin inside, or end inside, a token spanning lines.
+        let mut starts_inside = vec![false; lines.len()];
+        let mut ends_inside = vec![false; lines.len()];
+        for token in &printed {
+            let (end_line, _) = token_end(token);
+            for line in token.line..end_line {
+                ends_inside[line - 1] = true;
+                starts_inside[line] = true;
+            }
+        }
+
+        let mut before: HashMap<usize, Vec<Trivia>> = HashMap::new();
+        let mut after: HashMap<usize, String> = HashMap::new();
+        let mut expected = 0;
+        for (p, &id) in ids.iter().enumerate() {
+            let target = pairs[p];
+            if p > 0 {
+                for comment in self.comments_at(Anchor::Before(id)) {
+                    let line = printed[target?].line - 1;
+                    if starts_inside[line] {
+                        return None;
+                    }
+                    let items = before.entry(line).or_default();
+                    if comment.blank_before {
+                        items.push(Trivia::Blank);
+                    }
+                    items.push(Trivia::Comment(comment.text.clone()));
+                    expected += 1;
+                }
+                if let Some(j) = target {
+                    let first_on_line = j == 0 || token_end(&printed[j - 1]).0 < printed[j].line;
+                    if first_on_line
+                        && self.starts_line(id)
+                        && self.blank_before(self.tokens[id].line)
+                    {
+                        before
+                            .entry(printed[j].line - 1)
+                            .or_default()
+                            .push(Trivia::Blank);
+                    }
+                }
+            }
+            for comment in self.comments_at(Anchor::After(id)) {
+                let line = token_end(&printed[target?]).0 - 1;
+                if ends_inside[line] || after.contains_key(&line) {
+                    return None;
+                }
+                after.insert(line, comment.text.clone());
+                expected += 1;
+            }
+        }
+
+        let mut out: Vec<String> = Vec::new();
+        for (index, line) in lines.iter().enumerate() {
+            let closes = line.trim_start().starts_with('}');
+            let items = before.remove(&index).unwrap_or_default();
+            let count = items.len();
+            for (k, item) in items.into_iter().enumerate() {
+                match item {
+                    Trivia::Blank => {
+                        let after_open = out
+                            .last()
+                            .is_none_or(|l: &String| l.is_empty() || l.ends_with('{'));
+                        let before_close = closes && k + 1 == count;
+                        if !after_open && !before_close {
+                            out.push(String::new());
+                        }
+                    }
+                    Trivia::Comment(text) => {
+                        let mut indent: String =
+                            line.chars().take_while(|c| c.is_whitespace()).collect();
+                        if closes {
+                            indent.push_str(INDENT);
+                        }
+                        out.push(format!("{}{}", indent, text));
+                    }
+                }
+            }
+            match after.get(&index) {
+                Some(comment) => out.push(format!("{} {}", line, comment)),
+                None => out.push(line.to_string()),
+            }
+        }
+        let result = out.join("\n");
+
+        let kept = Lexer::new(&result)
+            .tokenize_with_comments()
+            .ok()?
+            .iter()
+            .filter(|t| t.token_type == TokenType::Comment)
+            .count();
+        (kept == expected && parses_to(&result, node)).then_some(result)
+    }
+}
+
+fn is_code(token: &Token) -> bool {
+    !matches!(
+        token.token_type,
+        TokenType::Newline | TokenType::Semicolon | TokenType::Eof | TokenType::Comment
+    )
+}
+
+fn position(token: &Token) -> (usize, usize) {
+    (token.line, token.column)
+}
+
+/// Append `text` to `out`, after a blank line if `blank` and one is not there already.
+fn emit(out: &mut Vec<String>, text: String, blank: bool) {
+    if blank && out.last().is_some_and(|l| !l.is_empty()) {
+        out.push(String::new());
+    }
+    out.push(text);
+}
+
+/// Line and column just past the end of `token`.
+fn token_end(token: &Token) -> (usize, usize) {
+    if token.token_type == TokenType::Newline {
+        return (token.line, token.column + 1);
+    }
+    match token.lexeme.rfind('\n') {
+        None => (token.line, token.column + token.lexeme.chars().count()),
+        Some(at) => (
+            token.line + token.lexeme.matches('\n').count(),
+            token.lexeme[at + 1..].chars().count() + 1,
+        ),
+    }
+}
+
+/// Code tokens of `text`, or `None` if it does not lex.
+fn lex_code(text: &str) -> Option<Vec<Token>> {
+    let tokens = Lexer::new(text).tokenize().ok()?;
+    Some(tokens.into_iter().filter(is_code).collect())
+}
+
+/// Whether `text` parses to exactly the statement `node`.
+fn parses_to(text: &str, node: &AstNode) -> bool {
+    Lexer::new(&format!("{}\n", text))
+        .tokenize()
+        .ok()
+        .and_then(|tokens| Parser::without_spans(tokens).parse_statements().ok())
+        .is_some_and(|statements| statements.len() == 1 && statements[0].0 == *node)
+}
+
+/// For each token of `source`, the printed token it lines up with: a longest common
+/// subsequence by token type. `None` if the statement is too long to align.
+fn align(source: &[Token], printed: &[Token]) -> Option<Vec<Option<usize>>> {
+    let same = |i: usize, j: usize| source[i].token_type == printed[j].token_type;
+    let mut pairs = vec![None; source.len()];
+    let mut prefix = 0;
+    while prefix < source.len() && prefix < printed.len() && same(prefix, prefix) {
+        pairs[prefix] = Some(prefix);
+        prefix += 1;
+    }
+    let mut suffix = 0;
+    while suffix < source.len() - prefix
+        && suffix < printed.len() - prefix
+        && same(source.len() - 1 - suffix, printed.len() - 1 - suffix)
+    {
+        pairs[source.len() - 1 - suffix] = Some(printed.len() - 1 - suffix);
+        suffix += 1;
+    }
+
+    let (n, m) = (
+        source.len() - prefix - suffix,
+        printed.len() - prefix - suffix,
+    );
+    if n == 0 || m == 0 {
+        return Some(pairs);
+    }
+    if (n + 1) * (m + 1) > MAX_ALIGNMENT_CELLS {
+        return None;
+    }
+    // lengths[i][j]: longest common subsequence of the middle parts from i and j onwards
+    let width = m + 1;
+    let mut lengths = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i * width + j] = if same(prefix + i, prefix + j) {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if same(prefix + i, prefix + j) {
+            pairs[prefix + i] = Some(prefix + j);
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    Some(pairs)
+}
+
+/// `text` with printed literals spelled as in the source: matched literals whose spelling
+/// differs, and literals in the same slot between two matched tokens that may have the same
+/// value (an interpolation written with `$name`, a dict key written as a bare name, `self`).
+fn respell(text: &str, printed: &[Token], source: &[Token], pairs: &[Option<usize>]) -> String {
+    let mut matched: Vec<(usize, usize)> = pairs
+        .iter()
+        .enumerate()
+        .filter_map(|(p, pair)| pair.map(|q| (p, q)))
+        .collect();
+    matched.push((source.len(), printed.len()));
+
+    let mut spellings: Vec<(usize, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    for (p, q) in matched {
+        // Unmatched tokens between the previous pair and this one
+        if p - i == q - j {
+            for k in 0..p - i {
+                let similar = match (&source[i + k].token_type, &printed[j + k].token_type) {
+                    (TokenType::String(_), TokenType::String(_)) => true,
+                    (TokenType::Identifier(name), TokenType::String(key)) => name == key,
+                    (TokenType::Self_, TokenType::This) => true,
+                    _ => false,
+                };
+                if similar {
+                    spellings.push((j + k, &source[i + k].lexeme));
+                }
+            }
+        }
+        if p < source.len() {
+            let keeps_spelling = matches!(
+                source[p].token_type,
+                TokenType::String(_)
+                    | TokenType::Integer(_)
+                    | TokenType::Float(_)
+                    | TokenType::Money(..)
+            );
+            if keeps_spelling && source[p].lexeme != printed[q].lexeme {
+                spellings.push((q, &source[p].lexeme));
+            }
+        }
+        i = p + 1;
+        j = q + 1;
+    }
+    if spellings.is_empty() {
+        return text.to_string();
+    }
+
+    let mut line_starts = vec![0];
+    let chars: Vec<char> = text.chars().collect();
+    for (at, c) in chars.iter().enumerate() {
+        if *c == '\n' {
+            line_starts.push(at + 1);
+        }
+    }
+    let mut chars = chars;
+    spellings.sort_by_key(|&(index, _)| std::cmp::Reverse(index));
+    for (index, spelling) in spellings {
+        let token = &printed[index];
+        let start = line_starts[token.line - 1] + token.column - 1;
+        let end = start + token.lexeme.chars().count();
+        chars.splice(start..end, spelling.chars());
+    }
+    chars.into_iter().collect()
+}
+
+/// `node` as canonical Jade source on one line, if it has a faithful spelling; `jade doc` shows
+/// default values and decorator arguments this way.
+pub(crate) fn expression_text(node: &AstNode) -> Option<String> {
+    Printer { tokens: &[] }.expr(node, PIPELINE, None)
+}
+
+/// Prints AST nodes as canonical Jade source. Every method returns `None` for a node it has no
+/// faithful spelling for, so that the statement holding it is kept as written.
+struct Printer<'t> {
+    /// Source tokens of the statement, for what the tree does not record: the order of class
+    /// members and their `pub`, `priv` and `mirror` modifiers.
+    tokens: &'t [Token],
+}
+
+impl Printer<'_> {
+    /// A statement at nesting `depth`; lines after the first carry their own indentation.
+    fn statement(&self, node: &AstNode, depth: usize) -> Option<String> {
+        let indent = Some(depth);
+        Some(match node {
+            AstNode::Expression(expr) => {
+                let text = self.expr(expr, PIPELINE, indent)?;
+                // A statement starting with `{` is a block, not a dict
+                if text.starts_with('{') {
+                    format!("({})", text)
+                } else {
+                    text
+                }
+            }
+            AstNode::VarDeclaration {
+                var_type,
+                name,
+                value,
+                immutable,
+                is_static,
+                type_modifier,
+                ..
+            } => {
+                let mut text = String::new();
+                if *is_static {
+                    text.push_str("static ");
+                }
+                if *immutable {
+                    text.push('!');
+                }
+                if let Some(modifier) = type_modifier {
+                    text.push_str(modifier);
+                    text.push(' ');
+                }
+                format!(
+                    "{}{}: {} = {}",
+                    text,
+                    var_type,
+                    name,
+                    self.expr(value, PIPELINE, indent)?
+                )
+            }
+            AstNode::TypeConversion { target_type, name } => {
+                format!("{} * {}", target_type, name)
+            }
+            AstNode::Assignment { name, value, .. } => match value.as_ref() {
+                AstNode::Lambda { params, body } if matches!(**body, AstNode::Block(_)) => {
+                    let params = if params.is_empty() {
+                        String::new()
+                    } else {
+                        format!("{} ", params.join(", "))
+                    };
+                    format!("fn | {} > {}{}", name, params, self.body(body, depth)?)
+                }
+                _ => format!("{} = {}", name, self.expr(value, PIPELINE, indent)?),
+            },
+            AstNode::DestructuringAssignment { targets, value } => format!(
+                "({}) = {}",
+                targets.join(", "),
+                self.expr(value, PIPELINE, indent)?
+            ),
+            AstNode::FunctionDeclaration { .. } => self.function(node, "", depth)?,
+            AstNode::AsyncFunction {
+                name,
+                params,
+                return_type: None,
+                body,
+                ..
+            } => format!(
+                "async fn | {} {} > {}",
+                name,
+                params_list(params),
+                self.body(body, depth)?
+            ),
+            AstNode::ClassDeclaration {
+                name,
+                class_type,
+                parent,
+                traits,
+                fields,
+                methods,
+                static_fields,
+                static_methods,
+                ..
+            } => {
+                let mut text = String::new();
+                if let Some(class_type) = class_type {
+                    text.push_str(class_type);
+                    text.push(' ');
+                }
+                text.push_str("class | ");
+                text.push_str(name);
+                if let Some(parent) = parent {
+                    text.push_str(" : ");
+                    text.push_str(parent);
+                }
+                for name in traits {
+                    text.push_str(" + ");
+                    text.push_str(name);
+                }
+                text.push(' ');
+                let members = self.class_members(
+                    fields.iter().chain(static_fields),
+                    methods.iter().chain(static_methods),
+                    static_methods.len(),
+                    depth + 1,
+                )?;
+                text.push_str(&wrap_lines(&members, depth));
+                text
+            }
+            AstNode::EnumDeclaration {
+                name,
+                backing_type,
+                variants,
+                ..
+            } => {
+                let mut lines = Vec::new();
+                for (variant, value) in variants {
+                    lines.push(match value {
+                        Some(value) => {
+                            format!(
+                                "{} = {}",
+                                variant,
+                                self.expr(value, PIPELINE, Some(depth + 1))?
+                            )
+                        }
+                        None => variant.clone(),
+                    });
+                }
+                let backing = backing_type
+                    .as_ref()
+                    .map(|t| format!(" : {}", t))
+                    .unwrap_or_default();
+                format!("enum | {}{} {}", name, backing, wrap_lines(&lines, depth))
+            }
+            AstNode::TraitDeclaration { name, methods, .. } => {
+                let mut lines = Vec::new();
+                for method in methods {
+                    lines.push(self.function(method, "", depth + 1)?);
+                }
+                format!("trait | {} {}", name, wrap_lines(&lines, depth))
+            }
+            AstNode::ModuleDeclaration { name, body, .. } => {
+                format!("module | {} {}", name, self.body(body, depth)?)
+            }
+            AstNode::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => self.conditional("if", condition, then_branch, else_branch, depth)?,
+            AstNode::Unless {
+                condition,
+                then_branch,
+                else_branch,
+            } => match condition.as_ref() {
+                AstNode::Unary {
+                    operator: UnaryOp::Not,
+                    operand,
+                    ..
+                } => self.conditional("unless", operand, then_branch, else_branch, depth)?,
+                _ => return None,
+            },
+            AstNode::While { condition, body, .. } => format!(
+                "while {}{}",
+                self.expr(condition, PIPELINE, indent)?,
+                self.branch(body, depth)?
+            ),
+            AstNode::For {
+                var,
+                iterable,
+                body,
+                ..
+            } => {
+                if var == "_" {
+                    format!(
+                        "for in {}{}",
+                        self.expr(iterable, PIPELINE, indent)?,
+                        self.loop_body(body, depth)?
+                    )
+                } else {
+                    self.for_loop(var, &self.expr(iterable, PIPELINE, indent)?, body, depth)?
+                }
+            }
+            AstNode::ForIndexed {
+                index_var,
+                value_var,
+                iterable,
+                body,
+            } => self.for_loop(
+                &format!("({}, {})", index_var, value_var),
+                &self.expr(iterable, PIPELINE, indent)?,
+                body,
+                depth,
+            )?,
+            AstNode::ForReverse {
+                var,
+                iterable,
+                body,
+            } => self.for_loop(
+                var,
+                &format!("{} rev", self.expr(iterable, PIPELINE, indent)?),
+                body,
+                depth,
+            )?,
+            AstNode::ForFiltered {
+                var,
+                iterable,
+                filter,
+                body,
+            } => self.for_loop(
+                var,
+                &format!(
+                    "{} if {}",
+                    self.expr(iterable, PIPELINE, indent)?,
+                    self.expr(filter, PIPELINE, indent)?
+                ),
+                body,
+                depth,
+            )?,
+            AstNode::ForZip {
+                vars,
+                iterables,
+                body,
+            } if vars.len() == 2 && vars[1] == format!("{}_2", vars[0]) => self.for_loop(
+                &vars[0],
+                &format!("zip({})", self.list(iterables, indent)?),
+                body,
+                depth,
+            )?,
+            AstNode::ForParallel {
+                var,
+                iterable,
+                body,
+                workers: None,
+                ordered: false,
+            } => self.for_loop(
+                var,
+                &format!("parallel({})", self.expr(iterable, PIPELINE, indent)?),
+                body,
+                depth,
+            )?,
+            AstNode::ForChunked {
+                var,
+                iterable,
+                chunk_size,
+                body,
+            } => self.for_loop(
+                var,
+                &format!(
+                    "chunks({}, {})",
+                    self.expr(iterable, PIPELINE, indent)?,
+                    self.expr(chunk_size, PIPELINE, indent)?
+                ),
+                body,
+                depth,
+            )?,
+            AstNode::Match { expr, arms, .. } => {
+                let mut lines = Vec::new();
+                for MatchArm {
+                    pattern,
+                    guard,
+                    body,
+                } in arms
+                {
+                    let mut line = pattern_text(pattern)?;
+                    if let Some(guard) = guard {
+                        line.push_str(" if ");
+                        line.push_str(&self.expr(guard, PIPELINE, Some(depth + 1))?);
+                    }
+                    line.push_str(" : ");
+                    line.push_str(&self.arm_body(body, depth + 1)?);
+                    lines.push(line);
+                }
+                format!(
+                    "match {} {}",
+                    self.expr(expr, PIPELINE, indent)?,
+                    wrap_lines(&lines, depth)
+                )
+            }
+            AstNode::Cond { value, branches, .. } => self.cond("cond", value, branches, depth)?,
+            AstNode::When { value, branches } => self.cond("when", value, branches, depth)?,
+            AstNode::Either {
+                expr,
+                true_body,
+                false_body,
+            } => {
+                let lines = [
+                    format!("|> true : {}", self.arm_body(true_body, depth + 1)?),
+                    format!("|> false : {}", self.arm_body(false_body, depth + 1)?),
+                ];
+                format!(
+                    "either {} {}",
+                    self.expr(expr, PIPELINE, indent)?,
+                    wrap_lines(&lines, depth)
+                )
+            }
+            AstNode::Switch { expr, cases } => {
+                let mut lines = Vec::new();
+                for (case, body) in cases {
+                    let label = match case {
+                        SwitchCase::Literal(value) => {
+                            self.expr(value, PIPELINE, Some(depth + 1))?
+                        }
+                        SwitchCase::Else => "else".to_string(),
+                        SwitchCase::Range { .. } => return None,
+                    };
+                    lines.push(format!("{} : {}", label, self.arm_body(body, depth + 1)?));
+                }
+                format!(
+                    "switch {} {}",
+                    self.expr(expr, PIPELINE, indent)?,
+                    wrap_lines(&lines, depth)
+                )
+            }
+            AstNode::GuardReturn {
+                condition,
+                return_value,
+            } => {
+                let action = match return_value.as_ref() {
+                    AstNode::Return(None, _) => "return".to_string(),
+                    AstNode::Return(Some(value), _) => {
+                        format!("return {}", self.expr(value, PIPELINE, indent)?)
+                    }
+                    value => self.expr(value, PIPELINE, indent)?,
+                };
+                format!(
+                    "guard {} : {}",
+                    self.expr(condition, PIPELINE, indent)?,
+                    action
+                )
+            }
+            AstNode::TryCatch {
+                try_block,
+                catch_var,
+                catch_block,
+                finally_block,
+            } => {
+                let mut text = format!("try {} catch ", self.body(try_block, depth)?);
+                if let Some(var) = catch_var {
+                    text.push_str(var);
+                    text.push(' ');
+                }
+                text.push_str(&self.body(catch_block, depth)?);
+                if let Some(finally) = finally_block {
+                    text.push_str(" finally ");
+                    text.push_str(&self.body(finally, depth)?);
+                }
+                text
+            }
+            AstNode::Throw(value, _) => format!("panic {}", self.expr(value, PIPELINE, indent)?),
+            AstNode::Return(None, _) => "return".to_string(),
+            AstNode::Return(Some(value), _) => {
+                format!("return {}", self.expr(value, PIPELINE, indent)?)
+            }
+            AstNode::Yield { value } => format!("yield {}", self.expr(value, PIPELINE, indent)?),
+            AstNode::Break(_) => "break".to_string(),
+            AstNode::Continue(_) => "continue".to_string(),
+            AstNode::Defer(value) => format!("defer {}", self.expr(value, PIPELINE, indent)?),
+            AstNode::ConvergeLoop { body } => format!("converge {}", self.body(body, depth)?),
+            AstNode::Block(_) => self.body(node, depth)?,
+            AstNode::ImportStatement {
+                module_path, items, ..
+            } if items.is_empty() => {
+                format!("import {}", module_path.join("."))
+            }
+            AstNode::UseStatement { path } => format!("use {}", path.join(".")),
+            AstNode::ExecuteFile { filename } => format!("j; -> {}", quote(filename)),
+            AstNode::TestCase { name, body } => {
+                format!("test {} {}", quote(name), self.body(body, depth)?)
+            }
+            AstNode::PropertyTest {
+                name,
+                var_type,
+                var_name,
+                body,
+            } => format!(
+                "property {} ({} | {}) {}",
+                quote(name),
+                var_type,
+                var_name,
+                self.body(body, depth)?
+            ),
+            AstNode::Assertion { condition, message } => {
+                let mut text = format!("assert {}", self.expr(condition, PIPELINE, indent)?);
+                if let Some(message) = message {
+                    text.push_str(", ");
+                    text.push_str(&quote(message));
+                }
+                text
+            }
+            AstNode::AwaitExpression { expr } => {
+                format!("await {}", self.expr(expr, PRIMARY, indent)?)
+            }
+            _ => return None,
+        })
+    }
+
+    /// `fn [type] | name ( params ) > body`, after any decorators.
+    fn function(&self, node: &AstNode, modifiers: &str, depth: usize) -> Option<String> {
+        let AstNode::FunctionDeclaration {
+            name,
+            params,
+            return_type,
+            body,
+            decorators,
+            ..
+        } = node
+        else {
+            return None;
+        };
+        let mut text = String::new();
+        for decorator in decorators {
+            text.push('@');
+            text.push_str(&decorator.name);
+            if !decorator.args.is_empty() {
+                text.push_str(&format!("({})", self.list(&decorator.args, None)?));
+            }
+            text.push('\n');
+            text.push_str(&INDENT.repeat(depth));
+        }
+        text.push_str(modifiers);
+        text.push_str("fn ");
+        if let Some(return_type) = return_type {
+            text.push_str(return_type);
+            text.push(' ');
+        }
+        text.push_str(&format!(
+            "| {} {} > {}",
+            name,
+            params_list(params),
+            self.body(body, depth)?
+        ));
+        Some(text)
+    }
+
+    /// Class fields and methods in source order, with the modifiers written on them.
+    fn class_members<'n>(
+        &self,
+        fields: impl Iterator<Item = &'n ClassField>,
+        methods: impl Iterator<Item = &'n AstNode>,
+        static_methods: usize,
+        depth: usize,
+    ) -> Option<Vec<String>> {
+        let mut members: Vec<(usize, String)> = Vec::new();
+        for field in fields {
+            let (at, _, private, mirror) = self.member_source(&field.name, false);
+            let mut text = String::new();
+            if field.is_static {
+                text.push_str("static ");
+            }
+            if field.is_public {
+                text.push_str("pub ");
+            } else if private {
+                text.push_str("priv ");
+            }
+            if mirror {
+                text.push_str("mirror ");
+            }
+            text.push_str(&format!("{} | {}", field.field_type, field.name));
+            if let Some(value) = &field.default_value {
+                text.push_str(" -> ");
+                text.push_str(&self.expr(value, PIPELINE, Some(depth))?);
+            }
+            members.push((at, text));
+        }
+        let methods: Vec<&AstNode> = methods.collect();
+        let first_static = methods.len() - static_methods;
+        for (index, method) in methods.into_iter().enumerate() {
+            let AstNode::FunctionDeclaration { name, .. } = method else {
+                return None;
+            };
+            let (at, public, private, mirror) = self.member_source(name, true);
+            let mut modifiers = String::new();
+            if index >= first_static {
+                modifiers.push_str("static ");
+            }
+            if public {
+                modifiers.push_str("pub ");
+            } else if private {
+                modifiers.push_str("priv ");
+            }
+            if mirror {
+                modifiers.push_str("mirror ");
+            }
+            members.push((at, self.function(method, &modifiers, depth)?));
+        }
+        members.sort_by_key(|(at, _)| *at);
+        Some(members.into_iter().map(|(_, text)| text).collect())
+    }
+
+    /// Where class member `name` is declared among the statement's tokens, and whether it is
+    /// marked `pub`, `priv` and `mirror`.
+    fn member_source(&self, name: &str, method: bool) -> (usize, bool, bool, bool) {
+        let tokens = self.tokens;
+        let found = (1..tokens.len().saturating_sub(1)).find(|&i| {
+            tokens[i].token_type == TokenType::Pipe
+                && matches!(&tokens[i + 1].token_type, TokenType::Identifier(n) if n == name)
+                && tokens
+                    .get(i + 2)
+                    .is_some_and(|t| t.token_type == TokenType::LeftParen)
+                    == method
+        });
+        let Some(at) = found else {
+            return (usize::MAX, false, false, false);
+        };
+        let (mut public, mut private, mut mirror) = (false, false, false);
+        for token in tokens[..at].iter().rev().skip(1).take(4) {
+            match token.token_type {
+                TokenType::Pub => public = true,
+                TokenType::Priv => private = true,
+                TokenType::Mirror => mirror = true,
+                TokenType::Fn | TokenType::Static => {}
+                _ if token
+                    .lexeme
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_') => {}
+                _ => break,
+            }
+        }
+        (at, public, private, mirror)
+    }
+
+    /// `if`/`unless` with its branches; `else if` chains stay flat.
+    fn conditional(
+        &self,
+        keyword: &str,
+        condition: &AstNode,
+        then_branch: &AstNode,
+        else_branch: &Option<Box<AstNode>>,
+        depth: usize,
+    ) -> Option<String> {
+        let mut text = format!(
+            "{} {}{}",
+            keyword,
+            self.expr(condition, PIPELINE, Some(depth))?,
+            self.branch(then_branch, depth)?
+        );
+        if let Some(other) = else_branch {
+            text.push_str(" else");
+            match other.as_ref() {
+                AstNode::If { .. } => {
+                    text.push(' ');
+                    text.push_str(&self.statement(other, depth)?);
+                }
+                other => text.push_str(&self.branch(other, depth)?),
+            }
+        }
+        Some(text)
+    }
+
+    /// The branch of `if`, `unless` or `while`: a block, `: expr` or a single statement.
+    fn branch(&self, node: &AstNode, depth: usize) -> Option<String> {
+        Some(match node {
+            AstNode::Block(_) => format!(" {}", self.body(node, depth)?),
+            node if is_expression(node) => {
+                format!(" : {}", self.expr(node, PIPELINE, Some(depth))?)
+            }
+            AstNode::If { .. } => return None,
+            node => format!(" {}", self.statement(node, depth)?),
+        })
+    }
+
+    fn for_loop(&self, vars: &str, source: &str, body: &AstNode, depth: usize) -> Option<String> {
+        Some(format!(
+            "for {} in {}{}",
+            vars,
+            source,
+            self.loop_body(body, depth)?
+        ))
+    }
+
+    /// A loop body: a block or `: expr`.
+    fn loop_body(&self, body: &AstNode, depth: usize) -> Option<String> {
+        Some(match body {
+            AstNode::Block(_) => format!(" {}", self.body(body, depth)?),
+            body => format!(" : {}", self.expr(body, PIPELINE, Some(depth))?),
+        })
+    }
+
+    /// The body of a `match`, `cond`, `either` or `switch` arm: a block or an expression.
+    fn arm_body(&self, body: &AstNode, depth: usize) -> Option<String> {
+        match body {
+            AstNode::Block(_) => self.body(body, depth),
+            body => self.expr(body, PIPELINE, Some(depth)),
+        }
+    }
+
+    fn cond(
+        &self,
+        keyword: &str,
+        value: &AstNode,
+        branches: &[CondBranch],
+        depth: usize,
+    ) -> Option<String> {
+        let mut lines = Vec::new();
+        for branch in branches {
+            let condition = if branch.is_else {
+                "else".to_string()
+            } else {
+                self.expr(&branch.condition, PIPELINE, Some(depth + 1))?
+            };
+            lines.push(format!(
+                "|> {} : {}",
+                condition,
+                self.arm_body(&branch.body, depth + 1)?
+            ));
+        }
+        Some(format!(
+            "{} ({}) {}",
+            keyword,
+            self.expr(value, PIPELINE, Some(depth))?,
+            wrap_lines(&lines, depth)
+        ))
+    }
+
+    /// A function or block body: `{ ... }` over several lines, or an expression.
+    fn body(&self, body: &AstNode, depth: usize) -> Option<String> {
+        match body {
+            AstNode::Block(statements) => {
+                let mut lines = Vec::new();
+                for statement in statements {
+                    lines.push(self.statement(statement, depth + 1)?);
+                }
+                Some(wrap_lines(&lines, depth))
+            }
+            body => self.expr(body, PIPELINE, Some(depth)),
+        }
+    }
+
+    /// `node` as an expression, parenthesised if it binds looser than `min`. `indent` is the
+    /// nesting depth of the line, or `None` if the expression must stay on one line.
+    fn expr(&self, node: &AstNode, min: u8, indent: Option<usize>) -> Option<String> {
+        let (text, strength) = self.expr_strength(node, indent)?;
+        Some(if strength < min {
+            format!("({})", text)
+        } else {
+            text
+        })
+    }
+
+    fn expr_strength(&self, node: &AstNode, indent: Option<usize>) -> Option<(String, u8)> {
+        let text = match node {
+            AstNode::Integer(i) if *i >= 0 => i.to_string(),
+            AstNode::Float(f) if f.is_finite() && *f >= 0.0 => float_text(*f),
+            AstNode::String(s) => quote(s),
+            AstNode::StringInterpolation { parts } if !parts.is_empty() => {
+                let mut content = String::new();
+                for part in parts {
+                    match part {
+                        AstNode::String(s) => content.push_str(s),
+                        part => {
+                            content.push_str(&format!("{{{}}}", self.expr(part, PIPELINE, None)?))
+                        }
+                    }
+                }
+                quote(&content)
+            }
+            AstNode::Boolean(b) => b.to_string(),
+            AstNode::Char(c) if !c.is_control() => format!("'{}'", c),
+            AstNode::Emoji(e) => e.clone(),
+            AstNode::Money(symbol, amount) if *amount >= 0.0 || symbol != "$" => {
+                format!("{}{}", symbol, amount)
+            }
+            AstNode::Hex(s) | AstNode::Date(s) | AstNode::Time(s) | AstNode::DateTime(s) => {
+                s.clone()
+            }
+            AstNode::Infinity(true) => "inf".to_string(),
+            AstNode::Identifier(name, _) => name.clone(),
+            AstNode::Underscore => "_".to_string(),
+            AstNode::List(items) => format!("[{}]", self.list(items, indent)?),
+            AstNode::Matrix(rows) => {
+                let mut texts = Vec::new();
+                for row in rows {
+                    texts.push(format!("[{}]", self.list(row, indent)?));
+                }
+                format!("[{}]", texts.join(", "))
+            }
+            AstNode::Tuple(items) => match items.len() {
+                1 => format!("({},)", self.expr(&items[0], PIPELINE, indent)?),
+                _ => format!("({})", self.list(items, indent)?),
+            },
+            AstNode::Dict(pairs) => {
+                let mut entries = Vec::new();
+                for (key, value) in pairs {
+                    let AstNode::String(key) = key else {
+                        return None;
+                    };
+                    entries.push(format!(
+                        "{}: {}",
+                        quote(key),
+                        self.expr(value, PIPELINE, indent.map(|d| d + 1))?
+                    ));
+                }
+                let inline = format!("{{{}}}", entries.join(", "));
+                match indent {
+                    Some(depth) if entries.len() > 1 && inline.len() > MAX_INLINE_DICT => {
+                        let inner = INDENT.repeat(depth + 1);
+                        format!(
+                            "{{\n{}{}\n{}}}",
+                            inner,
+                            entries.join(&format!(",\n{}", inner)),
+                            INDENT.repeat(depth)
+                        )
+                    }
+                    _ => inline,
+                }
+            }
+            AstNode::ListComprehension {
+                expr,
+                var,
+                iterable,
+                condition,
+            } => format!(
+                "[{} for {} in {}{}]",
+                self.expr(expr, PIPELINE, indent)?,
+                var,
+                self.expr(iterable, PIPELINE, indent)?,
+                self.filter(condition, indent)?
+            ),
+            AstNode::DictComprehension {
+                key_expr,
+                value_expr,
+                var,
+                iterable,
+                condition,
+            } => {
+                let AstNode::String(key) = key_expr.as_ref() else {
+                    return None;
+                };
+                format!(
+                    "{{{}: {} for {} in {}{}}}",
+                    quote(key),
+                    self.expr(value_expr, PIPELINE, indent)?,
+                    var,
+                    self.expr(iterable, PIPELINE, indent)?,
+                    self.filter(condition, indent)?
+                )
+            }
+            AstNode::FunctionCall { name, args, .. } => {
+                return Some((format!("{}({})", name, self.list(args, indent)?), POSTFIX))
+            }
+            AstNode::Call { callee, args, .. } => {
+                return Some((
+                    format!(
+                        "{}({})",
+                        self.expr(callee, POSTFIX, indent)?,
+                        self.list(args, indent)?
+                    ),
+                    POSTFIX,
+                ))
+            }
+            AstNode::BroadcastCall { callee, args } => {
+                return Some((
+                    format!(
+                        "{}.({})",
+                        self.expr(callee, POSTFIX, indent)?,
+                        self.list(args, indent)?
+                    ),
+                    POSTFIX,
+                ))
+            }
+            AstNode::Index { object, index, .. } => {
+                return Some((
+                    format!(
+                        "{}[{}]",
+                        self.expr(object, POSTFIX, indent)?,
+                        self.expr(index, PIPELINE, indent)?
+                    ),
+                    POSTFIX,
+                ))
+            }
+            AstNode::DotAccess { object, field, .. } => {
+                return Some((
+                    format!("{}.{}", self.expr(object, POSTFIX, indent)?, field),
+                    POSTFIX,
+                ))
+            }
+            AstNode::Slice {
+                object,
+                start,
+                end,
+                step,
+            } => {
+                let mut text = format!("{}[", self.expr(object, POSTFIX, indent)?);
+                if let Some(start) = start {
+                    text.push_str(&self.expr(start, TERM, indent)?);
+                }
+                text.push_str("..");
+                if let Some(end) = end {
+                    text.push_str(&self.expr(end, TERM, indent)?);
+                }
+                if let Some(step) = step {
+                    text.push_str(" by ");
+                    text.push_str(&self.expr(step, TERM, indent)?);
+                }
+                text.push(']');
+                return Some((text, POSTFIX));
+            }
+            AstNode::TryExpression(inner) => {
+                return Some((format!("{}?", self.expr(inner, POSTFIX, indent)?), POSTFIX))
+            }
+            AstNode::DeferAttach { resource, cleanup } => {
+                return Some((
+                    format!(
+                        "{}.defer({})",
+                        self.expr(resource, POSTFIX, indent)?,
+                        self.expr(cleanup, PIPELINE, indent)?
+                    ),
+                    POSTFIX,
+                ))
+            }
+            AstNode::Unary {
+                operator, operand, ..
+            } => {
+                let op = match operator {
+                    UnaryOp::Minus => "-",
+                    UnaryOp::Not => "not ",
+                    UnaryOp::BitwiseNot => "~",
+                };
+                return Some((
+                    format!("{}{}", op, self.expr(operand, UNARY, indent)?),
+                    UNARY,
+                ));
+            }
+            AstNode::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } => {
+                let (op, strength) = binary_op(operator)?;
+                return Some((
+                    format!(
+                        "{} {} {}",
+                        self.expr(left, strength, indent)?,
+                        op,
+                        self.expr(right, strength + 1, indent)?
+                    ),
+                    strength,
+                ));
+            }
+            AstNode::Range {
+                start,
+                end,
+                inclusive,
+                step,
+                ..
+            } => {
+                let mut text = self.expr(start, TERM, indent)?;
+                text.push_str(if *inclusive { ".." } else { "..<" });
+                if **end != AstNode::Integer(i64::MAX) {
+                    text.push_str(&self.expr(end, TERM, indent)?);
+                }
+                if let Some(step) = step {
+                    text.push_str(" by ");
+                    text.push_str(&self.expr(step, TERM, indent)?);
+                }
+                return Some((text, RANGE));
+            }
+            AstNode::Lambda { params, body } if !matches!(**body, AstNode::Block(_)) => {
+                let params = match params.as_slice() {
+                    [param] => param.clone(),
+                    params => format!("({})", params.join(", ")),
+                };
+                return Some((
+                    format!("{} => {}", params, self.expr(body, OR, indent)?),
+                    LAMBDA,
+                ));
+            }
+            AstNode::Pipeline { left, right } => {
+                return Some((
+                    format!(
+                        "{} |> {}",
+                        self.expr(left, PIPELINE, indent)?,
+                        self.expr(right, LAMBDA, indent)?
+                    ),
+                    PIPELINE,
+                ));
+            }
+            _ => return None,
+        };
+        Some((text, PRIMARY))
+    }
+
+    /// Comma-separated expressions, as in call arguments and list literals.
+    fn list(&self, items: &[AstNode], indent: Option<usize>) -> Option<String> {
+        let mut texts = Vec::new();
+        for item in items {
+            texts.push(self.expr(item, PIPELINE, indent)?);
+        }
+        Some(texts.join(", "))
+    }
+
+    /// The ` if condition` of a comprehension.
+    fn filter(&self, condition: &Option<Box<AstNode>>, indent: Option<usize>) -> Option<String> {
+        Some(match condition {
+            Some(condition) => format!(" if {}", self.expr(condition, PIPELINE, indent)?),
+            None => String::new(),
+        })
+    }
+}
+
+/// `{`, then `lines` one level deeper than `depth`, then `}`; `{}` when empty.
+fn wrap_lines(lines: &[String], depth: usize) -> String {
+    if lines.is_empty() {
+        return "{}".to_string();
+    }
+    let inner = INDENT.repeat(depth + 1);
+    let mut text = String::from("{\n");
+    for line in lines {
+        text.push_str(&inner);
+        text.push_str(line);
+        text.push('\n');
+    }
+    text.push_str(&INDENT.repeat(depth));
+    text.push('}');
+    text
+}
+
+/// `( type | name, ... )`, as in function headers. Also used by `jade doc`.
+pub(crate) fn params_list(params: &[(String, String)]) -> String {
+    let params: Vec<String> = params
+        .iter()
+        .map(|(param_type, name)| format!("{} | {}", param_type, name))
+        .collect();
+    if params.is_empty() {
+        "( )".to_string()
+    } else {
+        format!("( {} )", params.join(", "))
+    }
+}
+
+fn pattern_text(pattern: &Pattern) -> Option<String> {
+    Some(match pattern {
+        Pattern::Literal(AstNode::Integer(i)) if *i >= 0 => i.to_string(),
+        Pattern::Literal(AstNode::Float(f)) if f.is_finite() && *f >= 0.0 => float_text(*f),
+        Pattern::Literal(AstNode::String(s)) => quote(s),
+        Pattern::Literal(AstNode::Boolean(b)) => b.to_string(),
+        Pattern::Identifier(name) => name.clone(),
+        Pattern::Wildcard => "_".to_string(),
+        _ => return None,
+    })
+}
+
+fn binary_op(operator: &BinaryOp) -> Option<(&'static str, u8)> {
+    Some(match operator {
+        BinaryOp::Or => ("or", OR),
+        BinaryOp::And => ("and", AND),
+        BinaryOp::BitwiseOr => ("|", BIT_OR),
+        BinaryOp::BitwiseXor => ("^", BIT_XOR),
+        BinaryOp::BitwiseAnd => ("&", BIT_AND),
+        BinaryOp::Equal => ("==", EQUALITY),
+        BinaryOp::NotEqual => ("!=", EQUALITY),
+        BinaryOp::ConstantTimeEq => ("~==", EQUALITY),
+        BinaryOp::Less => ("<", COMPARISON),
+        BinaryOp::Greater => (">", COMPARISON),
+        BinaryOp::LessEqual => ("<=", COMPARISON),
+        BinaryOp::GreaterEqual => (">=", COMPARISON),
+        BinaryOp::LeftShift => ("<<", SHIFT),
+        BinaryOp::RightShift => (">>", SHIFT),
+        BinaryOp::Add => ("+", TERM),
+        BinaryOp::Subtract => ("-", TERM),
+        BinaryOp::Multiply => ("*", FACTOR),
+        BinaryOp::Divide => ("/", FACTOR),
+        BinaryOp::Modulo => ("%", FACTOR),
+        BinaryOp::Power => ("**", FACTOR),
+        BinaryOp::Assign => return None,
+    })
+}
+
+/// Whether `node` is an expression rather than a statement, as in the `: expr` form of a
+/// branch.
+fn is_expression(node: &AstNode) -> bool {
+    matches!(
+        node,
+        AstNode::Integer(_)
+            | AstNode::Float(_)
+            | AstNode::String(_)
+            | AstNode::StringInterpolation { .. }
+            | AstNode::Boolean(_)
+            | AstNode::Char(_)
+            | AstNode::Emoji(_)
+            | AstNode::Money(..)
+            | AstNode::Hex(_)
+            | AstNode::Date(_)
+            | AstNode::Time(_)
+            | AstNode::DateTime(_)
+            | AstNode::Infinity(_)
+            | AstNode::List(_)
+            | AstNode::Dict(_)
+            | AstNode::Tuple(_)
+            | AstNode::Matrix(_)
+            | AstNode::Identifier(..)
+            | AstNode::Underscore
+            | AstNode::FunctionCall { .. }
+            | AstNode::Call { .. }
+            | AstNode::BroadcastCall { .. }
+            | AstNode::Lambda { .. }
+            | AstNode::Binary { .. }
+            | AstNode::Unary { .. }
+            | AstNode::Pipeline { .. }
+            | AstNode::Index { .. }
+            | AstNode::DotAccess { .. }
+            | AstNode::Slice { .. }
+            | AstNode::TryExpression(_)
+            | AstNode::Range { .. }
+            | AstNode::ListComprehension { .. }
+            | AstNode::DictComprehension { .. }
+            | AstNode::DeferAttach { .. }
+    )
+}
+
+/// A float literal: always with a decimal point, never in exponent form.
+fn float_text(f: f64) -> String {
+    let text = f.to_string();
+    if text.contains('.') {
+        text
+    } else {
+        format!("{}.0", text)
+    }
+}
+
+/// `s` as a string literal.
+fn quote(s: &str) -> String {
+    let mut text = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => text.push_str("\\\""),
+            '\\' => text.push_str("\\\\"),
+            '\n' => text.push_str("\\n"),
+            '\t' => text.push_str("\\t"),
+            '\r' => text.push_str("\\r"),
+            '\0' => text.push_str("\\0"),
+            c if (c as u32) < 0x20 || c == '\x7f' => text.push_str(&format!("\\x{:02X}", c as u32)),
+            c => text.push(c),
+        }
+    }
+    text.push('"');
+    text
+}
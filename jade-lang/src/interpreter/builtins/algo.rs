@@ -2,6 +2,7 @@
 //! sliding_window, partition_range, two_pointers_sum, flood_fill,
 //! lower_bound, upper_bound, prefix_sum, kadane, reverse_range, merge_sorted.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 use std::collections::VecDeque;
@@ -10,7 +11,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "swap" => Some(call_swap(interpreter, args)?),
         "rotate_left" => Some(call_rotate_left(interpreter, args)?),
@@ -35,9 +36,9 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_swap(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_swap(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("swap() expects exactly 3 arguments: swap(list, i, j)".to_string());
+        return Err(JError::argument_count("swap() expects exactly 3 arguments: swap(list, i, j)").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let i_val = interpreter.eval_node(&args[1])?;
@@ -46,18 +47,18 @@ fn call_swap(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, S
         (Value::List(mut list), Value::Integer(i), Value::Integer(j)) => {
             let len = list.len() as i64;
             if i < 0 || i >= len || j < 0 || j >= len {
-                return Err("swap() indices out of bounds".to_string());
+                return Err(JError::out_of_range("swap() indices out of bounds").into());
             }
             list.swap(i as usize, j as usize);
             Ok(Value::List(list))
         }
-        _ => Err("swap() expects (list, int, int)".to_string()),
+        _ => Err(JError::invalid_argument("swap() expects (list, int, int)").into()),
     }
 }
 
-fn call_rotate_left(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_rotate_left(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("rotate_left() expects exactly 2 arguments: rotate_left(list, k)".to_string());
+        return Err(JError::argument_count("rotate_left() expects exactly 2 arguments: rotate_left(list, k)").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let k_val = interpreter.eval_node(&args[1])?;
@@ -72,13 +73,13 @@ fn call_rotate_left(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<V
             result.extend_from_slice(&list[..k]);
             Ok(Value::List(result))
         }
-        _ => Err("rotate_left() expects (list, int)".to_string()),
+        _ => Err(JError::invalid_argument("rotate_left() expects (list, int)").into()),
     }
 }
 
-fn call_rotate_right(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_rotate_right(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("rotate_right() expects exactly 2 arguments: rotate_right(list, k)".to_string());
+        return Err(JError::argument_count("rotate_right() expects exactly 2 arguments: rotate_right(list, k)").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let k_val = interpreter.eval_node(&args[1])?;
@@ -94,13 +95,13 @@ fn call_rotate_right(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<
             result.extend_from_slice(&list[..split_point]);
             Ok(Value::List(result))
         }
-        _ => Err("rotate_right() expects (list, int)".to_string()),
+        _ => Err(JError::invalid_argument("rotate_right() expects (list, int)").into()),
     }
 }
 
-fn call_count_if(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_count_if(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("count_if() expects exactly 2 arguments: count_if(list, predicate)".to_string());
+        return Err(JError::argument_count("count_if() expects exactly 2 arguments: count_if(list, predicate)").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let pred_val = interpreter.eval_node(&args[1])?;
@@ -112,18 +113,18 @@ fn call_count_if(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Valu
                 match result {
                     Value::Boolean(true) => count += 1,
                     Value::Boolean(false) => {}
-                    _ => return Err("count_if() predicate must return boolean".to_string()),
+                    _ => return Err(JError::invalid_argument("count_if() predicate must return boolean").into()),
                 }
             }
             Ok(Value::Integer(count))
         }
-        _ => Err("count_if() expects a list as first argument".to_string()),
+        _ => Err(JError::invalid_argument("count_if() expects a list as first argument").into()),
     }
 }
 
-fn call_find_index(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_find_index(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("find_index() expects exactly 2 arguments: find_index(list, predicate)".to_string());
+        return Err(JError::argument_count("find_index() expects exactly 2 arguments: find_index(list, predicate)").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let pred_val = interpreter.eval_node(&args[1])?;
@@ -138,18 +139,18 @@ fn call_find_index(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Va
                 match result {
                     Value::Boolean(true) => return Ok(Value::Integer(i as i64)),
                     Value::Boolean(false) => {}
-                    _ => return Err("find_index() predicate must return boolean".to_string()),
+                    _ => return Err(JError::invalid_argument("find_index() predicate must return boolean").into()),
                 }
             }
             Ok(Value::Integer(-1))
         }
-        _ => Err("find_index() expects a list as first argument".to_string()),
+        _ => Err(JError::invalid_argument("find_index() expects a list as first argument").into()),
     }
 }
 
-fn call_binary_search(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_binary_search(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("binary_search() expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("binary_search() expects exactly 2 arguments").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let target_val = interpreter.eval_node(&args[1])?;
@@ -171,29 +172,29 @@ fn call_binary_search(interpreter: &mut Interpreter, args: &[AstNode]) -> Result
                             }
                         }
                         _ => {
-                            return Err("binary_search() requires a sorted list of integers".to_string());
+                            return Err(JError::invalid_argument("binary_search() requires a sorted list of integers").into());
                         }
                     }
                 }
                 Ok(Value::Integer(-1))
             } else {
-                Err("binary_search() target must be an integer".to_string())
+                Err(JError::invalid_argument("binary_search() target must be an integer").into())
             }
         }
-        _ => Err("binary_search() can only be called on lists".to_string()),
+        _ => Err(JError::invalid_argument("binary_search() can only be called on lists").into()),
     }
 }
 
-fn call_sliding_window(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_sliding_window(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("sliding_window() expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("sliding_window() expects exactly 2 arguments").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let size_val = interpreter.eval_node(&args[1])?;
     match (list_val, size_val) {
         (Value::List(list), Value::Integer(size)) => {
             if size <= 0 {
-                return Err("sliding_window() size must be positive".to_string());
+                return Err(JError::invalid_argument("sliding_window() size must be positive").into());
             }
             let size = size as usize;
             if list.len() < size {
@@ -206,7 +207,7 @@ fn call_sliding_window(interpreter: &mut Interpreter, args: &[AstNode]) -> Resul
             }
             Ok(Value::List(result))
         }
-        _ => Err("sliding_window() expects a list and an integer".to_string()),
+        _ => Err(JError::invalid_argument("sliding_window() expects a list and an integer").into()),
     }
 }
 
@@ -222,19 +223,19 @@ fn value_leq(a: &Value, b: &Value) -> bool {
     }
 }
 
-fn call_partition_range(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_partition_range(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("partition_range(list, low, high) expects exactly 3 arguments".to_string());
+        return Err(JError::argument_count("partition_range(list, low, high) expects exactly 3 arguments").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let low_val = interpreter.eval_node(&args[1])?;
     let high_val = interpreter.eval_node(&args[2])?;
     let (mut list, low, high) = match (list_val, low_val, high_val) {
         (Value::List(l), Value::Integer(lo), Value::Integer(hi)) => (l, lo as usize, hi as usize),
-        _ => return Err("partition_range expects (list, int, int)".to_string()),
+        _ => return Err(JError::invalid_argument("partition_range expects (list, int, int)").into()),
     };
     if list.is_empty() || low >= list.len() || high >= list.len() || low > high {
-        return Err("partition_range: invalid low/high indices".to_string());
+        return Err(JError::unsupported("partition_range: invalid low/high indices").into());
     }
     let pivot = list[high].clone();
     let mut i = low;
@@ -251,22 +252,22 @@ fn call_partition_range(interpreter: &mut Interpreter, args: &[AstNode]) -> Resu
     ]))
 }
 
-fn call_two_pointers_sum(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_two_pointers_sum(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("two_pointers_sum(arr, target) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("two_pointers_sum(arr, target) expects exactly 2 arguments").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let target_val = interpreter.eval_node(&args[1])?;
     let (list, target) = match (list_val, target_val) {
         (Value::List(l), Value::Integer(t)) => (l, t),
-        _ => return Err("two_pointers_sum expects (list of ints, int target)".to_string()),
+        _ => return Err(JError::invalid_argument("two_pointers_sum expects (list of ints, int target)").into()),
     };
     let mut left = 0i64;
     let mut right = list.len() as i64 - 1;
     while left < right {
         let sum = match (&list[left as usize], &list[right as usize]) {
             (Value::Integer(a), Value::Integer(b)) => a + b,
-            _ => return Err("two_pointers_sum requires list of integers".to_string()),
+            _ => return Err(JError::invalid_argument("two_pointers_sum requires list of integers").into()),
         };
         match sum.cmp(&target) {
             std::cmp::Ordering::Equal => {
@@ -282,9 +283,9 @@ fn call_two_pointers_sum(interpreter: &mut Interpreter, args: &[AstNode]) -> Res
     Ok(Value::Tuple(vec![Value::Integer(-1), Value::Integer(-1)]))
 }
 
-fn call_flood_fill(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_flood_fill(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 4 {
-        return Err("flood_fill(grid, start_row, start_col, predicate) expects 4 arguments".to_string());
+        return Err(JError::argument_count("flood_fill(grid, start_row, start_col, predicate) expects 4 arguments").into());
     }
     let grid_val = interpreter.eval_node(&args[0])?;
     let row_val = interpreter.eval_node(&args[1])?;
@@ -292,12 +293,12 @@ fn call_flood_fill(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Va
     let pred_val = interpreter.eval_node(&args[3])?;
     let (grid, start_r, start_c) = match (grid_val, row_val, col_val) {
         (Value::Grid(g), Value::Integer(r), Value::Integer(c)) => (g, r as usize, c as usize),
-        _ => return Err("flood_fill expects (grid, int, int)".to_string()),
+        _ => return Err(JError::invalid_argument("flood_fill expects (grid, int, int)").into()),
     };
     let rows = grid.len();
     let cols = if rows > 0 { grid[0].len() } else { 0 };
     if start_r >= rows || start_c >= cols {
-        return Err("flood_fill: start position out of bounds".to_string());
+        return Err(JError::out_of_range("flood_fill: start position out of bounds").into());
     }
     let mut visited = std::collections::HashSet::new();
     let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
@@ -350,15 +351,15 @@ fn value_cmp(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
     }
 }
 
-fn call_lower_bound(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_lower_bound(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("lower_bound(list, value) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("lower_bound(list, value) expects exactly 2 arguments").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let target = interpreter.eval_node(&args[1])?;
     let list = match &list_val {
         Value::List(l) => l,
-        _ => return Err("lower_bound() expects a list".to_string()),
+        _ => return Err(JError::invalid_argument("lower_bound() expects a list").into()),
     };
     let mut left = 0;
     let mut right = list.len();
@@ -372,15 +373,15 @@ fn call_lower_bound(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<V
     Ok(Value::Integer(left as i64))
 }
 
-fn call_upper_bound(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_upper_bound(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("upper_bound(list, value) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("upper_bound(list, value) expects exactly 2 arguments").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let target = interpreter.eval_node(&args[1])?;
     let list = match &list_val {
         Value::List(l) => l,
-        _ => return Err("upper_bound() expects a list".to_string()),
+        _ => return Err(JError::invalid_argument("upper_bound() expects a list").into()),
     };
     let mut left = 0;
     let mut right = list.len();
@@ -394,14 +395,14 @@ fn call_upper_bound(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<V
     Ok(Value::Integer(left as i64))
 }
 
-fn call_prefix_sum(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_prefix_sum(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("prefix_sum(list) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("prefix_sum(list) expects exactly 1 argument").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let list = match &list_val {
         Value::List(l) => l,
-        _ => return Err("prefix_sum() expects a list of numbers".to_string()),
+        _ => return Err(JError::invalid_argument("prefix_sum() expects a list of numbers").into()),
     };
     let mut out = Vec::with_capacity(list.len());
     let mut sum = 0i64;
@@ -411,27 +412,27 @@ fn call_prefix_sum(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Va
                 sum = sum.saturating_add(*x);
                 out.push(Value::Integer(sum));
             }
-            _ => return Err("prefix_sum() requires list of integers".to_string()),
+            _ => return Err(JError::invalid_argument("prefix_sum() requires list of integers").into()),
         }
     }
     Ok(Value::List(out))
 }
 
-fn call_kadane(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_kadane(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("kadane(list) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("kadane(list) expects exactly 1 argument").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let list = match &list_val {
         Value::List(l) => l,
-        _ => return Err("kadane() expects a list of numbers".to_string()),
+        _ => return Err(JError::invalid_argument("kadane() expects a list of numbers").into()),
     };
     let mut best = i64::MIN;
     let mut cur = 0i64;
     for v in list {
         let x = match v {
             Value::Integer(n) => *n,
-            _ => return Err("kadane() requires list of integers".to_string()),
+            _ => return Err(JError::invalid_argument("kadane() requires list of integers").into()),
         };
         cur = cur.saturating_add(x).max(x);
         best = best.max(cur);
@@ -439,34 +440,34 @@ fn call_kadane(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value,
     Ok(Value::Integer(if list.is_empty() { 0 } else { best }))
 }
 
-fn call_reverse_range(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_reverse_range(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("reverse_range(list, start, end) expects exactly 3 arguments".to_string());
+        return Err(JError::argument_count("reverse_range(list, start, end) expects exactly 3 arguments").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let start_val = interpreter.eval_node(&args[1])?;
     let end_val = interpreter.eval_node(&args[2])?;
     let (mut list, start, end) = match (list_val, start_val, end_val) {
         (Value::List(l), Value::Integer(s), Value::Integer(e)) => (l, s as usize, e as usize),
-        _ => return Err("reverse_range expects (list, int, int)".to_string()),
+        _ => return Err(JError::invalid_argument("reverse_range expects (list, int, int)").into()),
     };
     let len = list.len();
     if start > end || end > len {
-        return Err("reverse_range: invalid start/end".to_string());
+        return Err(JError::unsupported("reverse_range: invalid start/end").into());
     }
     list[start..=end].reverse();
     Ok(Value::List(list))
 }
 
-fn call_merge_sorted(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_merge_sorted(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("merge_sorted(a, b) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("merge_sorted(a, b) expects exactly 2 arguments").into());
     }
     let a_val = interpreter.eval_node(&args[0])?;
     let b_val = interpreter.eval_node(&args[1])?;
     let (a, b) = match (&a_val, &b_val) {
         (Value::List(x), Value::List(y)) => (x.clone(), y.clone()),
-        _ => return Err("merge_sorted() expects two lists".to_string()),
+        _ => return Err(JError::invalid_argument("merge_sorted() expects two lists").into()),
     };
     let mut out = Vec::with_capacity(a.len() + b.len());
     let mut i = 0;
@@ -506,40 +507,40 @@ fn lcm_two(a: i64, b: i64) -> i64 {
     (a.abs() / g).saturating_mul(b.abs())
 }
 
-fn call_gcd_list(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_gcd_list(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("gcd_list(list) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("gcd_list(list) expects exactly 1 argument").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let list = match &list_val {
         Value::List(l) => l,
-        _ => return Err("gcd_list() expects a list of integers".to_string()),
+        _ => return Err(JError::invalid_argument("gcd_list() expects a list of integers").into()),
     };
     let mut acc = 0i64;
     for v in list {
         let n = match v {
             Value::Integer(x) => *x,
-            _ => return Err("gcd_list() requires list of integers".to_string()),
+            _ => return Err(JError::invalid_argument("gcd_list() requires list of integers").into()),
         };
         acc = gcd(acc, n);
     }
     Ok(Value::Integer(acc))
 }
 
-fn call_lcm_list(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_lcm_list(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("lcm_list(list) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("lcm_list(list) expects exactly 1 argument").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let list = match &list_val {
         Value::List(l) => l,
-        _ => return Err("lcm_list() expects a list of integers".to_string()),
+        _ => return Err(JError::invalid_argument("lcm_list() expects a list of integers").into()),
     };
     let mut acc = 1i64;
     for v in list {
         let n = match v {
             Value::Integer(x) => *x,
-            _ => return Err("lcm_list() requires list of integers".to_string()),
+            _ => return Err(JError::invalid_argument("lcm_list() requires list of integers").into()),
         };
         acc = lcm_two(acc, n);
     }
@@ -1,5 +1,6 @@
 //! Core builtins J needs: replicate, assert, iota, identity, default.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 
@@ -7,7 +8,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "replicate" | "repeat_n" => Some(call_replicate(interpreter, args)?),
         "assert" => Some(call_assert(interpreter, args)?),
@@ -19,28 +20,28 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_replicate(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_replicate(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("replicate(n, x) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("replicate(n, x) expects exactly 2 arguments").into());
     }
     let n_val = interpreter.eval_node(&args[0])?;
     let x_val = interpreter.eval_node(&args[1])?;
     let n = match n_val {
         Value::Integer(i) if i >= 0 => i as usize,
-        Value::Integer(_) => return Err("replicate(n, x) requires n >= 0".to_string()),
-        _ => return Err("replicate(n, x) requires integer n".to_string()),
+        Value::Integer(_) => return Err(JError::invalid_argument("replicate(n, x) requires n >= 0").into()),
+        _ => return Err(JError::invalid_argument("replicate(n, x) requires integer n").into()),
     };
     Ok(Value::List(vec![x_val; n]))
 }
 
-fn call_assert(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_assert(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.is_empty() {
-        return Err("assert(condition) expects at least 1 argument".to_string());
+        return Err(JError::argument_count("assert(condition) expects at least 1 argument").into());
     }
     let cond = interpreter.eval_node(&args[0])?;
     let ok = match &cond {
         Value::Boolean(b) => *b,
-        _ => return Err("assert(condition) expects boolean condition".to_string()),
+        _ => return Err(JError::invalid_argument("assert(condition) expects boolean condition").into()),
     };
     if !ok {
         let msg = if args.len() >= 2 {
@@ -48,36 +49,36 @@ fn call_assert(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value,
         } else {
             "assertion failed".to_string()
         };
-        return Err(msg);
+        return Err(JError::assertion_failed(msg).into());
     }
     Ok(Value::None)
 }
 
-fn call_iota(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_iota(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("iota(n) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("iota(n) expects exactly 1 argument").into());
     }
     let n_val = interpreter.eval_node(&args[0])?;
     let n = match n_val {
         Value::Integer(i) if i >= 0 => i as usize,
-        Value::Integer(_) => return Err("iota(n) requires n >= 0".to_string()),
-        _ => return Err("iota(n) requires integer n".to_string()),
+        Value::Integer(_) => return Err(JError::invalid_argument("iota(n) requires n >= 0").into()),
+        _ => return Err(JError::invalid_argument("iota(n) requires integer n").into()),
     };
     Ok(Value::List(
         (0..n).map(|i| Value::Integer(i as i64)).collect(),
     ))
 }
 
-fn call_identity(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_identity(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("identity(x) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("identity(x) expects exactly 1 argument").into());
     }
     interpreter.eval_node(&args[0])
 }
 
-fn call_default(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_default(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("default(value, fallback) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("default(value, fallback) expects exactly 2 arguments").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     if matches!(val, Value::None) {
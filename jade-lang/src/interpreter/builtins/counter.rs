@@ -1,5 +1,6 @@
 //! Counter builtins: most_common, total.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 
@@ -7,7 +8,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "most_common" => Some(call_most_common(interpreter, args)?),
         "total" => Some(call_total(interpreter, args)?),
@@ -16,15 +17,15 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_most_common(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_most_common(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.is_empty() || args.len() > 2 {
-        return Err("most_common() expects 1 or 2 arguments".to_string());
+        return Err(JError::argument_count("most_common() expects 1 or 2 arguments").into());
     }
     let counter_val = interpreter.eval_node(&args[0])?;
     let n = if args.len() == 2 {
         match interpreter.eval_node(&args[1])? {
             Value::Integer(i) => i as usize,
-            _ => return Err("most_common() second argument must be an integer".to_string()),
+            _ => return Err(JError::invalid_argument("most_common() second argument must be an integer").into()),
         }
     } else {
         usize::MAX
@@ -45,13 +46,13 @@ fn call_most_common(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<V
                 .collect();
             Ok(Value::List(result))
         }
-        _ => Err("most_common() can only be called on counters".to_string()),
+        _ => Err(JError::invalid_argument("most_common() can only be called on counters").into()),
     }
 }
 
-fn call_total(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_total(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("total() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("total() expects exactly 1 argument").into());
     }
     let counter_val = interpreter.eval_node(&args[0])?;
     match counter_val {
@@ -59,6 +60,6 @@ fn call_total(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value,
             let total: i64 = counter.values().sum();
             Ok(Value::Integer(total))
         }
-        _ => Err("total() can only be called on counters".to_string()),
+        _ => Err(JError::invalid_argument("total() can only be called on counters").into()),
     }
 }
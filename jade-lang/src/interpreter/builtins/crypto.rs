@@ -1,20 +1,21 @@
 //! Crypto builtins: sha256, enigma_*, aes_*, password_*, encrypt/decrypt, etc.
 //! Delegates to interpreter::crypto.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 
-fn bytes_from_value(v: &Value) -> Result<Vec<u8>, String> {
+fn bytes_from_value(v: &Value) -> JResult<Vec<u8>> {
     match v {
         Value::String(s) => Ok(s.as_bytes().to_vec()),
         Value::List(items) => items
             .iter()
             .map(|v| match v {
                 Value::Integer(i) => Ok(*i as u8),
-                _ => Err("Expected list of integers (bytes)".to_string()),
+                _ => Err(JError::invalid_argument("Expected list of integers (bytes)").into()),
             })
-            .collect::<Result<Vec<u8>, String>>(),
-        _ => Err("Expected string or byte list".to_string()),
+            .collect::<JResult<Vec<u8>>>(),
+        _ => Err(JError::invalid_argument("Expected string or byte list").into()),
     }
 }
 
@@ -31,19 +32,19 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
         use crate::interpreter::crypto;
         use sha2::{Digest, Sha256};
 
         let v = match name {
             "sha256" => {
                 if args.len() != 1 {
-                    return Err("sha256() takes exactly 1 argument".to_string());
+                    return Err(JError::argument_count("sha256() takes exactly 1 argument").into());
                 }
                 let val = interpreter.eval_node(&args[0])?;
                 let text = match &val {
                     Value::String(s) => s.clone(),
-                    _ => return Err("sha256() requires a string argument".to_string()),
+                    _ => return Err(JError::invalid_argument("sha256() requires a string argument").into()),
                 };
                 let mut hasher = Sha256::new();
                 hasher.update(text.as_bytes());
@@ -52,12 +53,12 @@ pub(super) fn try_call(
             }
             "sha256_hex" => {
                 if args.len() != 1 {
-                    return Err("sha256_hex() takes exactly 1 argument".to_string());
+                    return Err(JError::argument_count("sha256_hex() takes exactly 1 argument").into());
                 }
                 let val = interpreter.eval_node(&args[0])?;
                 let text = match &val {
                     Value::String(s) => s.clone(),
-                    _ => return Err("sha256_hex() requires a string argument".to_string()),
+                    _ => return Err(JError::invalid_argument("sha256_hex() requires a string argument").into()),
                 };
                 let mut hasher = Sha256::new();
                 hasher.update(text.as_bytes());
@@ -67,39 +68,39 @@ pub(super) fn try_call(
             }
             "hmac" => {
                 if args.len() != 2 {
-                    return Err("hmac() takes exactly 2 arguments: (message, key)".to_string());
+                    return Err(JError::argument_count("hmac() takes exactly 2 arguments: (message, key)").into());
                 }
                 let message_val = interpreter.eval_node(&args[0])?;
                 let key_val = interpreter.eval_node(&args[1])?;
                 let message = match &message_val {
                     Value::String(s) => s.clone(),
-                    _ => return Err("hmac() message must be a string".to_string()),
+                    _ => return Err(JError::invalid_argument("hmac() message must be a string").into()),
                 };
                 let key = match &key_val {
                     Value::String(s) => s.clone(),
-                    _ => return Err("hmac() key must be a string".to_string()),
+                    _ => return Err(JError::invalid_argument("hmac() key must be a string").into()),
                 };
                 use hmac::{Hmac, Mac};
                 type HmacSha256 = Hmac<Sha256>;
                 let mut mac = HmacSha256::new_from_slice(key.as_bytes())
-                    .map_err(|e| format!("HMAC error: {}", e))?;
+                    .map_err(|e| JError::unsupported(format!("HMAC error: {}", e)))?;
                 mac.update(message.as_bytes());
                 let result = mac.finalize();
                 Ok(Some(list_from_bytes(result.into_bytes().as_slice())))
             }
             "secure_eq" => {
                 if args.len() != 2 {
-                    return Err("secure_eq() takes exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("secure_eq() takes exactly 2 arguments").into());
                 }
                 let a_val = interpreter.eval_node(&args[0])?;
                 let b_val = interpreter.eval_node(&args[1])?;
                 let a = match &a_val {
                     Value::String(s) => s.as_str(),
-                    _ => return Err("secure_eq() requires string arguments".to_string()),
+                    _ => return Err(JError::invalid_argument("secure_eq() requires string arguments").into()),
                 };
                 let b = match &b_val {
                     Value::String(s) => s.as_str(),
-                    _ => return Err("secure_eq() requires string arguments".to_string()),
+                    _ => return Err(JError::invalid_argument("secure_eq() requires string arguments").into()),
                 };
                 let mut result = 0u8;
                 for (x, y) in a.bytes().zip(b.bytes()) {
@@ -109,7 +110,7 @@ pub(super) fn try_call(
             }
             "enigma_keypair" => {
                 if !args.is_empty() {
-                    return Err("enigma_keypair() takes no arguments".to_string());
+                    return Err(JError::argument_count("enigma_keypair() takes no arguments").into());
                 }
                 let _kp = crypto::EnigmaKeypair::generate();
                 Ok(Some(Value::String("EnigmaKeypair(generated)".to_string())))
@@ -117,8 +118,7 @@ pub(super) fn try_call(
             "enigma_encrypt" => {
                 if args.len() < 3 || args.len() > 4 {
                     return Err(
-                        "enigma_encrypt() takes 3-4 arguments: (plaintext, key, nonce, [aad])"
-                            .to_string(),
+                        JError::argument_count("enigma_encrypt() takes 3-4 arguments: (plaintext, key, nonce, [aad])").into(),
                     );
                 }
                 let plaintext_val = interpreter.eval_node(&args[0])?;
@@ -132,10 +132,10 @@ pub(super) fn try_call(
                 let plaintext = match &plaintext_val {
                     Value::String(s) => s.as_bytes().to_vec(),
                     Value::List(items) => bytes_from_value(&Value::List(items.clone()))?,
-                    _ => return Err("enigma_encrypt() plaintext must be string or byte list".to_string()),
+                    _ => return Err(JError::invalid_argument("enigma_encrypt() plaintext must be string or byte list").into()),
                 };
-                let key = bytes_from_value(&key_val).map_err(|_| "enigma_encrypt() key must be a byte list".to_string())?;
-                let nonce = bytes_from_value(&nonce_val).map_err(|_| "enigma_encrypt() nonce must be a byte list".to_string())?;
+                let key = bytes_from_value(&key_val).map_err(|_| JError::invalid_argument("enigma_encrypt() key must be a byte list"))?;
+                let nonce = bytes_from_value(&nonce_val).map_err(|_| JError::invalid_argument("enigma_encrypt() nonce must be a byte list"))?;
                 let aad = match &aad_val {
                     Value::String(s) => s.as_bytes().to_vec(),
                     Value::List(items) => bytes_from_value(&Value::List(items.clone())).unwrap_or_default(),
@@ -147,8 +147,7 @@ pub(super) fn try_call(
             "enigma_decrypt" => {
                 if args.len() < 3 || args.len() > 4 {
                     return Err(
-                        "enigma_decrypt() takes 3-4 arguments: (ciphertext, key, nonce, [aad])"
-                            .to_string(),
+                        JError::argument_count("enigma_decrypt() takes 3-4 arguments: (ciphertext, key, nonce, [aad])").into(),
                     );
                 }
                 let ciphertext_val = interpreter.eval_node(&args[0])?;
@@ -160,9 +159,9 @@ pub(super) fn try_call(
                     Value::String(String::new())
                 };
                 let ciphertext = bytes_from_value(&ciphertext_val)
-                    .map_err(|_| "enigma_decrypt() ciphertext must be a byte list".to_string())?;
-                let key = bytes_from_value(&key_val).map_err(|_| "enigma_decrypt() key must be a byte list".to_string())?;
-                let nonce = bytes_from_value(&nonce_val).map_err(|_| "enigma_decrypt() nonce must be a byte list".to_string())?;
+                    .map_err(|_| JError::invalid_argument("enigma_decrypt() ciphertext must be a byte list"))?;
+                let key = bytes_from_value(&key_val).map_err(|_| JError::invalid_argument("enigma_decrypt() key must be a byte list"))?;
+                let nonce = bytes_from_value(&nonce_val).map_err(|_| JError::invalid_argument("enigma_decrypt() nonce must be a byte list"))?;
                 let aad = match &aad_val {
                     Value::String(s) => s.as_bytes().to_vec(),
                     Value::List(items) => bytes_from_value(&Value::List(items.clone())).unwrap_or_default(),
@@ -176,41 +175,41 @@ pub(super) fn try_call(
             }
             "crypto_random_bytes" => {
                 if args.len() != 1 {
-                    return Err("crypto_random_bytes() takes exactly 1 argument (length)".to_string());
+                    return Err(JError::argument_count("crypto_random_bytes() takes exactly 1 argument (length)").into());
                 }
                 let len_val = interpreter.eval_node(&args[0])?;
                 let len = match &len_val {
                     Value::Integer(n) if *n > 0 => *n as usize,
-                    _ => return Err("crypto_random_bytes() requires a positive integer".to_string()),
+                    _ => return Err(JError::invalid_argument("crypto_random_bytes() requires a positive integer").into()),
                 };
                 let bytes = crypto::random_bytes(len);
                 Ok(Some(list_from_bytes(&bytes)))
             }
             "xor_bytes" => {
                 if args.len() != 2 {
-                    return Err("xor_bytes() takes exactly 2 arguments (bytes_a, bytes_b)".to_string());
+                    return Err(JError::argument_count("xor_bytes() takes exactly 2 arguments (bytes_a, bytes_b)").into());
                 }
                 let a_val = interpreter.eval_node(&args[0])?;
                 let b_val = interpreter.eval_node(&args[1])?;
                 let a = bytes_from_value(&a_val)
-                    .map_err(|_| "xor_bytes() arguments must be byte lists or strings".to_string())?;
+                    .map_err(|_| JError::invalid_argument("xor_bytes() arguments must be byte lists or strings"))?;
                 let b = bytes_from_value(&b_val)
-                    .map_err(|_| "xor_bytes() arguments must be byte lists or strings".to_string())?;
+                    .map_err(|_| JError::invalid_argument("xor_bytes() arguments must be byte lists or strings"))?;
                 let result = crypto::xor_bytes(&a, &b)?;
                 Ok(Some(list_from_bytes(&result)))
             }
             "aes_encrypt" => {
                 if args.len() < 3 || args.len() > 4 {
                     return Err(
-                        "aes_encrypt(plaintext, key, nonce, [aad]) requires 3-4 arguments".to_string(),
+                        JError::argument_count("aes_encrypt(plaintext, key, nonce, [aad]) requires 3-4 arguments").into(),
                     );
                 }
                 let plaintext = bytes_from_value(&interpreter.eval_node(&args[0])?)
-                    .map_err(|_| "aes_encrypt plaintext must be string or byte list".to_string())?;
+                    .map_err(|_| JError::invalid_argument("aes_encrypt plaintext must be string or byte list"))?;
                 let key = bytes_from_value(&interpreter.eval_node(&args[1])?)
-                    .map_err(|_| "aes_encrypt key must be byte list".to_string())?;
+                    .map_err(|_| JError::invalid_argument("aes_encrypt key must be byte list"))?;
                 let nonce = bytes_from_value(&interpreter.eval_node(&args[2])?)
-                    .map_err(|_| "aes_encrypt nonce must be byte list".to_string())?;
+                    .map_err(|_| JError::invalid_argument("aes_encrypt nonce must be byte list"))?;
                 let aad = if args.len() == 4 {
                     bytes_from_value(&interpreter.eval_node(&args[3])?).unwrap_or_default()
                 } else {
@@ -222,15 +221,15 @@ pub(super) fn try_call(
             "aes_decrypt" => {
                 if args.len() < 3 || args.len() > 4 {
                     return Err(
-                        "aes_decrypt(ciphertext, key, nonce, [aad]) requires 3-4 arguments".to_string(),
+                        JError::argument_count("aes_decrypt(ciphertext, key, nonce, [aad]) requires 3-4 arguments").into(),
                     );
                 }
                 let ciphertext = bytes_from_value(&interpreter.eval_node(&args[0])?)
-                    .map_err(|_| "aes_decrypt ciphertext must be byte list".to_string())?;
+                    .map_err(|_| JError::invalid_argument("aes_decrypt ciphertext must be byte list"))?;
                 let key = bytes_from_value(&interpreter.eval_node(&args[1])?)
-                    .map_err(|_| "aes_decrypt key must be byte list".to_string())?;
+                    .map_err(|_| JError::invalid_argument("aes_decrypt key must be byte list"))?;
                 let nonce = bytes_from_value(&interpreter.eval_node(&args[2])?)
-                    .map_err(|_| "aes_decrypt nonce must be byte list".to_string())?;
+                    .map_err(|_| JError::invalid_argument("aes_decrypt nonce must be byte list"))?;
                 let aad = if args.len() == 4 {
                     bytes_from_value(&interpreter.eval_node(&args[3])?).unwrap_or_default()
                 } else {
@@ -245,20 +244,20 @@ pub(super) fn try_call(
             "derive_password_key" => {
                 if args.len() < 2 || args.len() > 4 {
                     return Err(
-                        "derive_password_key(password, salt, [ops_limit], [mem_limit_kb]) requires 2-4 arguments".to_string(),
+                        JError::argument_count("derive_password_key(password, salt, [ops_limit], [mem_limit_kb]) requires 2-4 arguments").into(),
                     );
                 }
                 let password_val = interpreter.eval_node(&args[0])?;
                 let password = match &password_val {
                     Value::String(s) => s.clone(),
-                    _ => return Err("derive_password_key password must be string".to_string()),
+                    _ => return Err(JError::invalid_argument("derive_password_key password must be string").into()),
                 };
                 let salt = bytes_from_value(&interpreter.eval_node(&args[1])?)
-                    .map_err(|_| "derive_password_key salt must be byte list".to_string())?;
+                    .map_err(|_| JError::invalid_argument("derive_password_key salt must be byte list"))?;
                 let ops_limit = if args.len() >= 3 {
                     match &interpreter.eval_node(&args[2])? {
                         Value::Integer(i) if *i > 0 => *i as u32,
-                        _ => return Err("derive_password_key ops_limit must be positive integer".to_string()),
+                        _ => return Err(JError::invalid_argument("derive_password_key ops_limit must be positive integer").into()),
                     }
                 } else {
                     2
@@ -266,7 +265,7 @@ pub(super) fn try_call(
                 let mem_limit_kb = if args.len() >= 4 {
                     match &interpreter.eval_node(&args[3])? {
                         Value::Integer(i) if *i > 0 => *i as u32,
-                        _ => return Err("derive_password_key mem_limit_kb must be positive integer".to_string()),
+                        _ => return Err(JError::invalid_argument("derive_password_key mem_limit_kb must be positive integer").into()),
                     }
                 } else {
                     19456
@@ -280,10 +279,10 @@ pub(super) fn try_call(
                 } else if args.len() == 1 {
                     match &interpreter.eval_node(&args[0])? {
                         Value::Integer(i) if *i > 0 => *i as usize,
-                        _ => return Err("crypto_salt([length]) length must be positive integer".to_string()),
+                        _ => return Err(JError::invalid_argument("crypto_salt([length]) length must be positive integer").into()),
                     }
                 } else {
-                    return Err("crypto_salt([length]) takes 0-1 arguments".to_string());
+                    return Err(JError::argument_count("crypto_salt([length]) takes 0-1 arguments").into());
                 };
                 let salt = crypto::generate_salt(length);
                 Ok(Some(list_from_bytes(&salt)))
@@ -294,17 +293,17 @@ pub(super) fn try_call(
                 } else if args.len() == 1 {
                     match &interpreter.eval_node(&args[0])? {
                         Value::Integer(i) if *i > 0 => *i as usize,
-                        _ => return Err("crypto_nonce([length]) length must be positive integer".to_string()),
+                        _ => return Err(JError::invalid_argument("crypto_nonce([length]) length must be positive integer").into()),
                     }
                 } else {
-                    return Err("crypto_nonce([length]) takes 0-1 arguments".to_string());
+                    return Err(JError::argument_count("crypto_nonce([length]) takes 0-1 arguments").into());
                 };
                 let nonce = crypto::generate_nonce(length);
                 Ok(Some(list_from_bytes(&nonce)))
             }
             "secure_compare" => {
                 if args.len() != 2 {
-                    return Err("secure_compare(a, b) requires 2 arguments".to_string());
+                    return Err(JError::argument_count("secure_compare(a, b) requires 2 arguments").into());
                 }
                 let a = bytes_from_value(&interpreter.eval_node(&args[0])?)?;
                 let b = bytes_from_value(&interpreter.eval_node(&args[1])?)?;
@@ -313,13 +312,13 @@ pub(super) fn try_call(
             "encrypt_value" => {
                 if args.len() < 2 || args.len() > 3 {
                     return Err(
-                        "encrypt_value(value, key_id, [key]) requires 2-3 arguments".to_string(),
+                        JError::argument_count("encrypt_value(value, key_id, [key]) requires 2-3 arguments").into(),
                     );
                 }
                 let value_to_encrypt = interpreter.eval_node(&args[0])?;
                 let key_id = match &interpreter.eval_node(&args[1])? {
                     Value::String(s) => s.clone(),
-                    _ => return Err("key_id must be a string".to_string()),
+                    _ => return Err(JError::invalid_argument("key_id must be a string").into()),
                 };
                 let key = if args.len() == 3 {
                     let key_val = interpreter.eval_node(&args[2])?;
@@ -329,7 +328,7 @@ pub(super) fn try_call(
                             let salt = crypto::generate_salt(16);
                             crypto::derive_password_key(s, &salt, 2, 19456)?
                         }
-                        _ => return Err("Key must be a byte list or password string".to_string()),
+                        _ => return Err(JError::invalid_argument("Key must be a byte list or password string").into()),
                     }
                 } else {
                     let mut hasher = Sha256::new();
@@ -354,7 +353,7 @@ pub(super) fn try_call(
             "decrypt_value" => {
                 if args.is_empty() || args.len() > 2 {
                     return Err(
-                        "decrypt_value(encrypted_value, [key]) requires 1-2 arguments".to_string(),
+                        JError::argument_count("decrypt_value(encrypted_value, [key]) requires 1-2 arguments").into(),
                     );
                 }
                 let encrypted_val = interpreter.eval_node(&args[0])?;
@@ -364,7 +363,7 @@ pub(super) fn try_call(
                         key_id,
                         nonce,
                     } => (ciphertext.clone(), key_id.clone(), nonce.clone()),
-                    _ => return Err("decrypt_value() requires an encrypted value".to_string()),
+                    _ => return Err(JError::invalid_argument("decrypt_value() requires an encrypted value").into()),
                 };
                 let key = if args.len() == 2 {
                     let key_val = interpreter.eval_node(&args[1])?;
@@ -374,7 +373,7 @@ pub(super) fn try_call(
                             let salt = crypto::generate_salt(16);
                             crypto::derive_password_key(s, &salt, 2, 19456)?
                         }
-                        _ => return Err("Key must be a byte list or password string".to_string()),
+                        _ => return Err(JError::invalid_argument("Key must be a byte list or password string").into()),
                     }
                 } else {
                     let mut hasher = Sha256::new();
@@ -400,12 +399,12 @@ pub(super) fn try_call(
             }
             "password_hash" => {
                 if args.len() != 1 {
-                    return Err("password_hash() takes exactly 1 argument".to_string());
+                    return Err(JError::argument_count("password_hash() takes exactly 1 argument").into());
                 }
                 let password_val = interpreter.eval_node(&args[0])?;
                 let password = match &password_val {
                     Value::String(s) => s.clone(),
-                    _ => return Err("password_hash() requires a string argument".to_string()),
+                    _ => return Err(JError::invalid_argument("password_hash() requires a string argument").into()),
                 };
                 use rand::Rng;
                 let mut rng = rand::thread_rng();
@@ -420,22 +419,22 @@ pub(super) fn try_call(
             "password_verify" => {
                 if args.len() != 2 {
                     return Err(
-                        "password_verify() takes exactly 2 arguments (password, hash)".to_string(),
+                        JError::argument_count("password_verify() takes exactly 2 arguments (password, hash)").into(),
                     );
                 }
                 let password_val = interpreter.eval_node(&args[0])?;
                 let hash_val = interpreter.eval_node(&args[1])?;
                 let password = match &password_val {
                     Value::String(s) => s.clone(),
-                    _ => return Err("password_verify() requires string arguments".to_string()),
+                    _ => return Err(JError::invalid_argument("password_verify() requires string arguments").into()),
                 };
                 let stored_hash = match &hash_val {
                     Value::String(s) => s.clone(),
-                    _ => return Err("password_verify() requires string arguments".to_string()),
+                    _ => return Err(JError::invalid_argument("password_verify() requires string arguments").into()),
                 };
                 let parts: Vec<&str> = stored_hash.split('$').collect();
                 if parts.len() != 3 || !parts[0].is_empty() {
-                    return Err("Invalid password hash format".to_string());
+                    return Err(JError::invalid_argument("Invalid password hash format").into());
                 }
                 let salt = parts[1];
                 let expected_hash = parts[2];
@@ -454,17 +453,17 @@ pub(super) fn try_call(
             }
             "encrypt" => {
                 if args.len() != 2 {
-                    return Err("encrypt() takes exactly 2 arguments (plaintext, key)".to_string());
+                    return Err(JError::argument_count("encrypt() takes exactly 2 arguments (plaintext, key)").into());
                 }
                 let plaintext_val = interpreter.eval_node(&args[0])?;
                 let key_val = interpreter.eval_node(&args[1])?;
                 let plaintext = match &plaintext_val {
                     Value::String(s) => s.clone(),
-                    _ => return Err("encrypt() plaintext must be a string".to_string()),
+                    _ => return Err(JError::invalid_argument("encrypt() plaintext must be a string").into()),
                 };
                 let key = match &key_val {
                     Value::String(s) => s.clone(),
-                    _ => return Err("encrypt() key must be a string".to_string()),
+                    _ => return Err(JError::invalid_argument("encrypt() key must be a string").into()),
                 };
                 let mut hasher = Sha256::new();
                 hasher.update(key.as_bytes());
@@ -479,22 +478,22 @@ pub(super) fn try_call(
             }
             "decrypt" => {
                 if args.len() != 2 {
-                    return Err("decrypt() takes exactly 2 arguments (ciphertext, key)".to_string());
+                    return Err(JError::argument_count("decrypt() takes exactly 2 arguments (ciphertext, key)").into());
                 }
                 let ciphertext_val = interpreter.eval_node(&args[0])?;
                 let key_val = interpreter.eval_node(&args[1])?;
                 let ciphertext = match &ciphertext_val {
                     Value::String(s) => s.clone(),
-                    _ => return Err("decrypt() ciphertext must be a string".to_string()),
+                    _ => return Err(JError::invalid_argument("decrypt() ciphertext must be a string").into()),
                 };
                 let key = match &key_val {
                     Value::String(s) => s.clone(),
-                    _ => return Err("decrypt() key must be a string".to_string()),
+                    _ => return Err(JError::invalid_argument("decrypt() key must be a string").into()),
                 };
                 use base64::{engine::general_purpose, Engine as _};
                 let encrypted = general_purpose::STANDARD
                     .decode(&ciphertext)
-                    .map_err(|e| format!("decrypt() invalid base64: {}", e))?;
+                    .map_err(|e| JError::invalid_argument(format!("decrypt() invalid base64: {}", e)))?;
                 let mut hasher = Sha256::new();
                 hasher.update(key.as_bytes());
                 let key_hash = hasher.finalize();
@@ -504,17 +503,17 @@ pub(super) fn try_call(
                     .map(|(i, b)| b ^ key_hash[i % key_hash.len()])
                     .collect();
                 let plaintext = String::from_utf8(decrypted)
-                    .map_err(|e| format!("decrypt() invalid UTF-8: {}", e))?;
+                    .map_err(|e| JError::invalid_argument(format!("decrypt() invalid UTF-8: {}", e)))?;
                 Ok(Some(Value::String(plaintext)))
             }
             "secure_token" => {
                 if args.len() != 1 {
-                    return Err("secure_token() takes exactly 1 argument (length)".to_string());
+                    return Err(JError::argument_count("secure_token() takes exactly 1 argument (length)").into());
                 }
                 let len_val = interpreter.eval_node(&args[0])?;
                 let len = match &len_val {
                     Value::Integer(n) if *n > 0 => *n as usize,
-                    _ => return Err("secure_token() requires a positive integer".to_string()),
+                    _ => return Err(JError::invalid_argument("secure_token() requires a positive integer").into()),
                 };
                 use rand::Rng;
                 const CHARSET: &[u8] =
@@ -1,6 +1,7 @@
 //! Data structures & algorithms builtins: graph (bfs, dfs, add_node, add_edge, get_neighbors,
 //! graph_nodes, graph_edges, topological_sort, dijkstra), deque, priority queue.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 use std::collections::{HashMap, VecDeque};
@@ -9,7 +10,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "bfs" => Some(call_bfs(interpreter, args)?),
         "dfs" => Some(call_dfs(interpreter, args)?),
@@ -34,9 +35,9 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_bfs(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_bfs(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() < 2 || args.len() > 3 {
-        return Err("bfs() expects 2-3 arguments: graph, start, [goal]".to_string());
+        return Err(JError::argument_count("bfs() expects 2-3 arguments: graph, start, [goal]").into());
     }
     let graph_val = interpreter.eval_node(&args[0])?;
     let start_val = interpreter.eval_node(&args[1])?;
@@ -57,13 +58,13 @@ fn call_bfs(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, St
             let path = interpreter.bfs_search(&graph, &start, goal.as_deref())?;
             Ok(Value::List(path.into_iter().map(Value::String).collect()))
         }
-        _ => Err("bfs() expects graph and string start node".to_string()),
+        _ => Err(JError::invalid_argument("bfs() expects graph and string start node").into()),
     }
 }
 
-fn call_dfs(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_dfs(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() < 2 || args.len() > 3 {
-        return Err("dfs() expects 2-3 arguments: graph, start, [goal]".to_string());
+        return Err(JError::argument_count("dfs() expects 2-3 arguments: graph, start, [goal]").into());
     }
     let graph_val = interpreter.eval_node(&args[0])?;
     let start_val = interpreter.eval_node(&args[1])?;
@@ -84,32 +85,32 @@ fn call_dfs(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, St
             let path = interpreter.dfs_search(&graph, &start, goal.as_deref())?;
             Ok(Value::List(path.into_iter().map(Value::String).collect()))
         }
-        _ => Err("dfs() expects graph and string start node".to_string()),
+        _ => Err(JError::invalid_argument("dfs() expects graph and string start node").into()),
     }
 }
 
-fn call_add_node(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_add_node(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("add_node() expects exactly 2 arguments: add_node(graph, node_name)".to_string());
+        return Err(JError::argument_count("add_node() expects exactly 2 arguments: add_node(graph, node_name)").into());
     }
     let graph_val = interpreter.eval_node(&args[0])?;
     let node_val = interpreter.eval_node(&args[1])?;
     let node_name = match node_val {
         Value::String(s) => s,
-        _ => return Err("Node name must be a string".to_string()),
+        _ => return Err(JError::invalid_argument("Node name must be a string").into()),
     };
     match graph_val {
         Value::Graph(mut graph) => {
             graph.entry(node_name).or_insert_with(Vec::new);
             Ok(Value::Graph(graph))
         }
-        _ => Err("add_node() can only be called on graphs".to_string()),
+        _ => Err(JError::invalid_argument("add_node() can only be called on graphs").into()),
     }
 }
 
-fn call_add_edge(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_add_edge(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() < 3 || args.len() > 4 {
-        return Err("add_edge() expects 3-4 arguments: add_edge(graph, from, to, [weight])".to_string());
+        return Err(JError::argument_count("add_edge() expects 3-4 arguments: add_edge(graph, from, to, [weight])").into());
     }
     let graph_val = interpreter.eval_node(&args[0])?;
     let from_val = interpreter.eval_node(&args[1])?;
@@ -121,16 +122,16 @@ fn call_add_edge(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Valu
     };
     let from = match from_val {
         Value::String(s) => s,
-        _ => return Err("From node must be a string".to_string()),
+        _ => return Err(JError::invalid_argument("From node must be a string").into()),
     };
     let to = match to_val {
         Value::String(s) => s,
-        _ => return Err("To node must be a string".to_string()),
+        _ => return Err(JError::invalid_argument("To node must be a string").into()),
     };
     let weight = match weight_val {
         Value::Integer(i) => i as f64,
         Value::Float(f) => f,
-        _ => return Err("Weight must be a number".to_string()),
+        _ => return Err(JError::invalid_argument("Weight must be a number").into()),
     };
     match graph_val {
         Value::Graph(mut graph) => {
@@ -145,19 +146,19 @@ fn call_add_edge(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Valu
             }
             Ok(Value::Graph(graph))
         }
-        _ => Err("add_edge() can only be called on graphs".to_string()),
+        _ => Err(JError::invalid_argument("add_edge() can only be called on graphs").into()),
     }
 }
 
-fn call_get_neighbors(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_get_neighbors(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("get_neighbors() expects exactly 2 arguments: get_neighbors(graph, node)".to_string());
+        return Err(JError::argument_count("get_neighbors() expects exactly 2 arguments: get_neighbors(graph, node)").into());
     }
     let graph_val = interpreter.eval_node(&args[0])?;
     let node_val = interpreter.eval_node(&args[1])?;
     let node = match node_val {
         Value::String(s) => s,
-        _ => return Err("Node must be a string".to_string()),
+        _ => return Err(JError::invalid_argument("Node must be a string").into()),
     };
     match graph_val {
         Value::Graph(graph) => {
@@ -168,16 +169,16 @@ fn call_get_neighbors(interpreter: &mut Interpreter, args: &[AstNode]) -> Result
                     .collect();
                 Ok(Value::List(list))
             } else {
-                Err(format!("Node '{}' not found in graph", node))
+                Err(JError::missing_key(format!("Node '{}' not found in graph", node)).into())
             }
         }
-        _ => Err("get_neighbors() can only be called on graphs".to_string()),
+        _ => Err(JError::invalid_argument("get_neighbors() can only be called on graphs").into()),
     }
 }
 
-fn call_push_front(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_push_front(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("push_front() expects exactly 2 arguments: push_front(deque, item)".to_string());
+        return Err(JError::argument_count("push_front() expects exactly 2 arguments: push_front(deque, item)").into());
     }
     let deque_val = interpreter.eval_node(&args[0])?;
     let item_val = interpreter.eval_node(&args[1])?;
@@ -186,13 +187,13 @@ fn call_push_front(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Va
             deque.insert(0, item_val);
             Ok(Value::Deque(deque))
         }
-        _ => Err("push_front() can only be called on deques".to_string()),
+        _ => Err(JError::invalid_argument("push_front() can only be called on deques").into()),
     }
 }
 
-fn call_push_back(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_push_back(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("push_back() expects exactly 2 arguments: push_back(deque, item)".to_string());
+        return Err(JError::argument_count("push_back() expects exactly 2 arguments: push_back(deque, item)").into());
     }
     let deque_val = interpreter.eval_node(&args[0])?;
     let item_val = interpreter.eval_node(&args[1])?;
@@ -201,13 +202,13 @@ fn call_push_back(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Val
             deque.push(item_val);
             Ok(Value::Deque(deque))
         }
-        _ => Err("push_back() can only be called on deques".to_string()),
+        _ => Err(JError::invalid_argument("push_back() can only be called on deques").into()),
     }
 }
 
-fn call_pop_front(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_pop_front(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("pop_front() expects exactly 1 argument: pop_front(deque)".to_string());
+        return Err(JError::argument_count("pop_front() expects exactly 1 argument: pop_front(deque)").into());
     }
     let deque_val = interpreter.eval_node(&args[0])?;
     match deque_val {
@@ -215,16 +216,16 @@ fn call_pop_front(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Val
             if !deque.is_empty() {
                 Ok(deque.remove(0))
             } else {
-                Err("Cannot pop_front from empty deque".to_string())
+                Err(JError::empty_collection("Cannot pop_front from empty deque").into())
             }
         }
-        _ => Err("pop_front() can only be called on deques".to_string()),
+        _ => Err(JError::invalid_argument("pop_front() can only be called on deques").into()),
     }
 }
 
-fn call_pop_back(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_pop_back(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("pop_back() expects exactly 1 argument: pop_back(deque)".to_string());
+        return Err(JError::argument_count("pop_back() expects exactly 1 argument: pop_back(deque)").into());
     }
     let deque_val = interpreter.eval_node(&args[0])?;
     match deque_val {
@@ -232,16 +233,16 @@ fn call_pop_back(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Valu
             if let Some(item) = deque.pop() {
                 Ok(item)
             } else {
-                Err("Cannot pop_back from empty deque".to_string())
+                Err(JError::empty_collection("Cannot pop_back from empty deque").into())
             }
         }
-        _ => Err("pop_back() can only be called on deques".to_string()),
+        _ => Err(JError::invalid_argument("pop_back() can only be called on deques").into()),
     }
 }
 
-fn call_peek_front(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_peek_front(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("peek_front() expects exactly 1 argument: peek_front(deque)".to_string());
+        return Err(JError::argument_count("peek_front() expects exactly 1 argument: peek_front(deque)").into());
     }
     let deque_val = interpreter.eval_node(&args[0])?;
     match deque_val {
@@ -249,16 +250,16 @@ fn call_peek_front(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Va
             if !deque.is_empty() {
                 Ok(deque[0].clone())
             } else {
-                Err("Cannot peek_front on empty deque".to_string())
+                Err(JError::empty_collection("Cannot peek_front on empty deque").into())
             }
         }
-        _ => Err("peek_front() can only be called on deques".to_string()),
+        _ => Err(JError::invalid_argument("peek_front() can only be called on deques").into()),
     }
 }
 
-fn call_peek_back(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_peek_back(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("peek_back() expects exactly 1 argument: peek_back(deque)".to_string());
+        return Err(JError::argument_count("peek_back() expects exactly 1 argument: peek_back(deque)").into());
     }
     let deque_val = interpreter.eval_node(&args[0])?;
     match deque_val {
@@ -266,16 +267,16 @@ fn call_peek_back(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Val
             if !deque.is_empty() {
                 Ok(deque[deque.len() - 1].clone())
             } else {
-                Err("Cannot peek_back on empty deque".to_string())
+                Err(JError::empty_collection("Cannot peek_back on empty deque").into())
             }
         }
-        _ => Err("peek_back() can only be called on deques".to_string()),
+        _ => Err(JError::invalid_argument("peek_back() can only be called on deques").into()),
     }
 }
 
-fn call_graph_nodes(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_graph_nodes(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("graph_nodes(graph) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("graph_nodes(graph) expects exactly 1 argument").into());
     }
     let graph_val = interpreter.eval_node(&args[0])?;
     match graph_val {
@@ -292,13 +293,13 @@ fn call_graph_nodes(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<V
                 list.into_iter().map(Value::String).collect(),
             ))
         }
-        _ => Err("graph_nodes() can only be called on graphs".to_string()),
+        _ => Err(JError::invalid_argument("graph_nodes() can only be called on graphs").into()),
     }
 }
 
-fn call_graph_edges(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_graph_edges(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("graph_edges(graph) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("graph_edges(graph) expects exactly 1 argument").into());
     }
     let graph_val = interpreter.eval_node(&args[0])?;
     match graph_val {
@@ -315,18 +316,18 @@ fn call_graph_edges(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<V
             }
             Ok(Value::List(out))
         }
-        _ => Err("graph_edges() can only be called on graphs".to_string()),
+        _ => Err(JError::invalid_argument("graph_edges() can only be called on graphs").into()),
     }
 }
 
-fn call_topological_sort(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_topological_sort(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("topological_sort(graph) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("topological_sort(graph) expects exactly 1 argument").into());
     }
     let graph_val = interpreter.eval_node(&args[0])?;
     let graph = match &graph_val {
         Value::Graph(g) => g,
-        _ => return Err("topological_sort() can only be called on graphs".to_string()),
+        _ => return Err(JError::invalid_argument("topological_sort() can only be called on graphs").into()),
     };
     let mut nodes: std::collections::HashSet<&String> = graph.keys().collect();
     for edges in graph.values() {
@@ -366,20 +367,20 @@ fn call_topological_sort(interpreter: &mut Interpreter, args: &[AstNode]) -> Res
         }
     }
     if order.len() != nodes.len() {
-        return Err("topological_sort: graph has a cycle".to_string());
+        return Err(JError::unsupported("topological_sort: graph has a cycle").into());
     }
     Ok(Value::List(order))
 }
 
-fn call_dijkstra(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_dijkstra(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("dijkstra(graph, start) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("dijkstra(graph, start) expects exactly 2 arguments").into());
     }
     let graph_val = interpreter.eval_node(&args[0])?;
     let start_val = interpreter.eval_node(&args[1])?;
     let (graph, start) = match (&graph_val, &start_val) {
         (Value::Graph(g), Value::String(s)) => (g, s.clone()),
-        _ => return Err("dijkstra() expects (graph, string start node)".to_string()),
+        _ => return Err(JError::invalid_argument("dijkstra() expects (graph, string start node)").into()),
     };
     let mut dist: HashMap<String, f64> = HashMap::new();
     dist.insert(start.clone(), 0.0);
@@ -407,16 +408,16 @@ fn call_dijkstra(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Valu
     Ok(Value::Dict(out))
 }
 
-fn call_pq_push(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_pq_push(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("pq_push() expects exactly 3 arguments: pq_push(pq, priority, item)".to_string());
+        return Err(JError::argument_count("pq_push() expects exactly 3 arguments: pq_push(pq, priority, item)").into());
     }
     let pq_val = interpreter.eval_node(&args[0])?;
     let priority_val = interpreter.eval_node(&args[1])?;
     let item_val = interpreter.eval_node(&args[2])?;
     let priority = match priority_val {
         Value::Integer(p) => p,
-        _ => return Err("Priority must be an integer".to_string()),
+        _ => return Err(JError::invalid_argument("Priority must be an integer").into()),
     };
     match pq_val {
         Value::PriorityQ(mut pq) => {
@@ -424,13 +425,13 @@ fn call_pq_push(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value
             pq.sort_by_key(|(p, _)| *p);
             Ok(Value::PriorityQ(pq))
         }
-        _ => Err("pq_push() can only be called on priority queues".to_string()),
+        _ => Err(JError::invalid_argument("pq_push() can only be called on priority queues").into()),
     }
 }
 
-fn call_pq_pop(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_pq_pop(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("pq_pop() expects exactly 1 argument: pq_pop(pq)".to_string());
+        return Err(JError::argument_count("pq_pop() expects exactly 1 argument: pq_pop(pq)").into());
     }
     let pq_val = interpreter.eval_node(&args[0])?;
     match pq_val {
@@ -438,16 +439,16 @@ fn call_pq_pop(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value,
             if !pq.is_empty() {
                 Ok(pq.remove(0).1)
             } else {
-                Err("Cannot pop from empty priority queue".to_string())
+                Err(JError::empty_collection("Cannot pop from empty priority queue").into())
             }
         }
-        _ => Err("pq_pop() can only be called on priority queues".to_string()),
+        _ => Err(JError::invalid_argument("pq_pop() can only be called on priority queues").into()),
     }
 }
 
-fn call_pq_peek(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_pq_peek(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("pq_peek() expects exactly 1 argument: pq_peek(pq)".to_string());
+        return Err(JError::argument_count("pq_peek() expects exactly 1 argument: pq_peek(pq)").into());
     }
     let pq_val = interpreter.eval_node(&args[0])?;
     match pq_val {
@@ -455,9 +456,9 @@ fn call_pq_peek(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value
             if !pq.is_empty() {
                 Ok(pq[0].1.clone())
             } else {
-                Err("Cannot peek on empty priority queue".to_string())
+                Err(JError::empty_collection("Cannot peek on empty priority queue").into())
             }
         }
-        _ => Err("pq_peek() can only be called on priority queues".to_string()),
+        _ => Err(JError::invalid_argument("pq_peek() can only be called on priority queues").into()),
     }
 }
@@ -1,5 +1,6 @@
 //! Enum builtins: enum_name, enum_value, enum_has.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 
@@ -7,7 +8,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "enum_name" => Some(call_enum_name(interpreter, args)?),
         "enum_value" => Some(call_enum_value(interpreter, args)?),
@@ -17,9 +18,9 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_enum_name(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_enum_name(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("enum_name() expects exactly 2 arguments: enum_name(enum, value)".to_string());
+        return Err(JError::argument_count("enum_name() expects exactly 2 arguments: enum_name(enum, value)").into());
     }
     let enum_val = interpreter.eval_node(&args[0])?;
     let value_val = interpreter.eval_node(&args[1])?;
@@ -32,13 +33,13 @@ fn call_enum_name(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Val
             }
             Ok(Value::None)
         }
-        _ => Err("enum_name() can only be called on enums".to_string()),
+        _ => Err(JError::invalid_argument("enum_name() can only be called on enums").into()),
     }
 }
 
-fn call_enum_value(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_enum_value(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("enum_value() expects exactly 2 arguments: enum_value(enum, name)".to_string());
+        return Err(JError::argument_count("enum_value() expects exactly 2 arguments: enum_value(enum, name)").into());
     }
     let enum_val = interpreter.eval_node(&args[0])?;
     let name_val = interpreter.eval_node(&args[1])?;
@@ -47,13 +48,13 @@ fn call_enum_value(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Va
             .get(&name)
             .cloned()
             .unwrap_or(Value::None)),
-        _ => Err("enum_value() can only be called on enums with string name".to_string()),
+        _ => Err(JError::invalid_argument("enum_value() can only be called on enums with string name").into()),
     }
 }
 
-fn call_enum_has(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_enum_has(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("enum_has() expects exactly 2 arguments: enum_has(enum, value)".to_string());
+        return Err(JError::argument_count("enum_has() expects exactly 2 arguments: enum_has(enum, value)").into());
     }
     let enum_val = interpreter.eval_node(&args[0])?;
     let value_val = interpreter.eval_node(&args[1])?;
@@ -64,6 +65,6 @@ fn call_enum_has(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Valu
                 .any(|v| interpreter.values_equal(v, &value_val));
             Ok(Value::Boolean(has))
         }
-        _ => Err("enum_has() can only be called on enums".to_string()),
+        _ => Err(JError::invalid_argument("enum_has() can only be called on enums").into()),
     }
 }
@@ -1,5 +1,6 @@
 //! File I/O builtins: read, write, read_lines, write_lines.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 
@@ -7,7 +8,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "read" => Some(call_read(interpreter, args)?),
         "write" => Some(call_write(interpreter, args)?),
@@ -18,51 +19,51 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_read(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_read(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("read() expects exactly 1 argument: read(filename)".to_string());
+        return Err(JError::argument_count("read() expects exactly 1 argument: read(filename)").into());
     }
     let filename_val = interpreter.eval_node(&args[0])?;
     let filename = match filename_val {
         Value::String(s) => s,
-        _ => return Err("read() filename must be a string".to_string()),
+        _ => return Err(JError::invalid_argument("read() filename must be a string").into()),
     };
     std::fs::read_to_string(&filename)
         .map(Value::String)
-        .map_err(|e| format!("Failed to read file '{}': {}", filename, e))
+        .map_err(|e| JError::io_error(format!("Failed to read file '{}': {}", filename, e)).into())
 }
 
-fn call_write(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_write(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
         return Err(
-            "write() expects exactly 2 arguments: write(filename, content)".to_string(),
+            JError::argument_count("write() expects exactly 2 arguments: write(filename, content)").into(),
         );
     }
     let filename_val = interpreter.eval_node(&args[0])?;
     let content_val = interpreter.eval_node(&args[1])?;
     let filename = match filename_val {
         Value::String(s) => s,
-        _ => return Err("write() filename must be a string".to_string()),
+        _ => return Err(JError::invalid_argument("write() filename must be a string").into()),
     };
     let content = content_val.to_string();
     std::fs::write(&filename, content)
         .map(|_| Value::Boolean(true))
-        .map_err(|e| format!("Failed to write file '{}': {}", filename, e))
+        .map_err(|e| JError::io_error(format!("Failed to write file '{}': {}", filename, e)).into())
 }
 
-fn call_read_lines(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_read_lines(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
         return Err(
-            "read_lines() expects exactly 1 argument: read_lines(filename)".to_string(),
+            JError::argument_count("read_lines() expects exactly 1 argument: read_lines(filename)").into(),
         );
     }
     let filename_val = interpreter.eval_node(&args[0])?;
     let filename = match filename_val {
         Value::String(s) => s,
-        _ => return Err("read_lines() filename must be a string".to_string()),
+        _ => return Err(JError::invalid_argument("read_lines() filename must be a string").into()),
     };
     let content = std::fs::read_to_string(&filename)
-        .map_err(|e| format!("Failed to read file '{}': {}", filename, e))?;
+        .map_err(|e| JError::io_error(format!("Failed to read file '{}': {}", filename, e)))?;
     let lines: Vec<Value> = content
         .lines()
         .map(|line| Value::String(line.to_string()))
@@ -70,18 +71,17 @@ fn call_read_lines(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Va
     Ok(Value::List(lines))
 }
 
-fn call_write_lines(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_write_lines(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
         return Err(
-            "write_lines() expects exactly 2 arguments: write_lines(filename, lines)"
-                .to_string(),
+            JError::argument_count("write_lines() expects exactly 2 arguments: write_lines(filename, lines)").into(),
         );
     }
     let filename_val = interpreter.eval_node(&args[0])?;
     let lines_val = interpreter.eval_node(&args[1])?;
     let filename = match filename_val {
         Value::String(s) => s,
-        _ => return Err("write_lines() filename must be a string".to_string()),
+        _ => return Err(JError::invalid_argument("write_lines() filename must be a string").into()),
     };
     let lines = match lines_val {
         Value::List(list) => list
@@ -89,9 +89,9 @@ fn call_write_lines(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<V
             .map(|v| v.to_string())
             .collect::<Vec<_>>()
             .join("\n"),
-        _ => return Err("write_lines() lines must be a list".to_string()),
+        _ => return Err(JError::invalid_argument("write_lines() lines must be a list").into()),
     };
     std::fs::write(&filename, lines)
         .map(|_| Value::Boolean(true))
-        .map_err(|e| format!("Failed to write file '{}': {}", filename, e))
+        .map_err(|e| JError::io_error(format!("Failed to write file '{}': {}", filename, e)).into())
 }
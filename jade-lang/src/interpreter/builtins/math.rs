@@ -1,6 +1,7 @@
 //! Math and number-theory builtins: gcd, lcm, egcd, is_prime, next_prime, prev_prime,
 //! factorial, fibonacci, binomial, factors, totient, gamma, polyval.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 
@@ -8,7 +9,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "gcd" => Some(call_gcd(interpreter, args)?),
         "lcm" => Some(call_lcm(interpreter, args)?),
@@ -33,9 +34,9 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_gcd(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_gcd(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("gcd() expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("gcd() expects exactly 2 arguments").into());
     }
     let a_val = interpreter.eval_node(&args[0])?;
     let b_val = interpreter.eval_node(&args[1])?;
@@ -50,13 +51,13 @@ fn call_gcd(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, St
             }
             Ok(Value::Integer(a))
         }
-        _ => Err("gcd() expects two integers".to_string()),
+        _ => Err(JError::invalid_argument("gcd() expects two integers").into()),
     }
 }
 
-fn call_lcm(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_lcm(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("lcm() expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("lcm() expects exactly 2 arguments").into());
     }
     let a_val = interpreter.eval_node(&args[0])?;
     let b_val = interpreter.eval_node(&args[1])?;
@@ -76,13 +77,13 @@ fn call_lcm(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, St
             }
             Ok(Value::Integer((a * b) / gcd_a))
         }
-        _ => Err("lcm() expects two integers".to_string()),
+        _ => Err(JError::invalid_argument("lcm() expects two integers").into()),
     }
 }
 
-fn call_is_prime(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_is_prime(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("is_prime() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("is_prime() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
@@ -105,22 +106,22 @@ fn call_is_prime(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Valu
             }
             Ok(Value::Boolean(true))
         }
-        _ => Err("is_prime() expects an integer".to_string()),
+        _ => Err(JError::invalid_argument("is_prime() expects an integer").into()),
     }
 }
 
-fn call_factorial(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_factorial(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("factorial() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("factorial() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
         Value::Integer(n) => {
             if n < 0 {
-                return Err("factorial() requires non-negative integer".to_string());
+                return Err(JError::invalid_argument("factorial() requires non-negative integer").into());
             }
             if n > 20 {
-                return Err("factorial() overflow: n must be <= 20".to_string());
+                return Err(JError::invalid_argument("factorial() overflow: n must be <= 20").into());
             }
             let mut result = 1i64;
             for i in 2..=n {
@@ -128,19 +129,19 @@ fn call_factorial(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Val
             }
             Ok(Value::Integer(result))
         }
-        _ => Err("factorial() expects an integer".to_string()),
+        _ => Err(JError::invalid_argument("factorial() expects an integer").into()),
     }
 }
 
-fn call_fibonacci(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_fibonacci(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("fibonacci() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("fibonacci() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
         Value::Integer(n) => {
             if n < 0 {
-                return Err("fibonacci() requires non-negative integer".to_string());
+                return Err(JError::invalid_argument("fibonacci() requires non-negative integer").into());
             }
             if n == 0 {
                 return Ok(Value::Integer(0));
@@ -157,19 +158,19 @@ fn call_fibonacci(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Val
             }
             Ok(Value::Integer(b))
         }
-        _ => Err("fibonacci() expects an integer".to_string()),
+        _ => Err(JError::invalid_argument("fibonacci() expects an integer").into()),
     }
 }
 
-fn call_egcd(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_egcd(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("egcd(a, b) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("egcd(a, b) expects exactly 2 arguments").into());
     }
     let a_val = interpreter.eval_node(&args[0])?;
     let b_val = interpreter.eval_node(&args[1])?;
     let (mut a, mut b) = match (a_val, b_val) {
         (Value::Integer(x), Value::Integer(y)) => (x, y),
-        _ => return Err("egcd(a, b) expects two integers".to_string()),
+        _ => return Err(JError::invalid_argument("egcd(a, b) expects two integers").into()),
     };
     let (mut x0, mut x1) = (1i64, 0i64);
     let (mut y0, mut y1) = (0i64, 1i64);
@@ -206,14 +207,14 @@ fn is_prime_n(n: i64) -> bool {
     true
 }
 
-fn call_next_prime(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_next_prime(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("next_prime(n) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("next_prime(n) expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     let n = match val {
         Value::Integer(n) => n,
-        _ => return Err("next_prime(n) expects an integer".to_string()),
+        _ => return Err(JError::invalid_argument("next_prime(n) expects an integer").into()),
     };
     let mut k = n + 1;
     if k < 2 {
@@ -231,17 +232,17 @@ fn call_next_prime(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Va
     Ok(Value::Integer(k))
 }
 
-fn call_prev_prime(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_prev_prime(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("prev_prime(n) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("prev_prime(n) expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     let n = match val {
         Value::Integer(n) => n,
-        _ => return Err("prev_prime(n) expects an integer".to_string()),
+        _ => return Err(JError::invalid_argument("prev_prime(n) expects an integer").into()),
     };
     if n <= 2 {
-        return Err("prev_prime(n): no prime less than n for n <= 2".to_string());
+        return Err(JError::unsupported("prev_prime(n): no prime less than n for n <= 2").into());
     }
     let mut k = n - 1;
     if k % 2 == 0 {
@@ -251,23 +252,23 @@ fn call_prev_prime(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Va
         k -= 2;
     }
     if k < 2 {
-        return Err("prev_prime(n): no prime found".to_string());
+        return Err(JError::unsupported("prev_prime(n): no prime found").into());
     }
     Ok(Value::Integer(k))
 }
 
-fn call_binomial(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_binomial(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("binomial(n, k) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("binomial(n, k) expects exactly 2 arguments").into());
     }
     let n_val = interpreter.eval_node(&args[0])?;
     let k_val = interpreter.eval_node(&args[1])?;
     let (n, k) = match (n_val, k_val) {
         (Value::Integer(n), Value::Integer(k)) => (n, k),
-        _ => return Err("binomial(n, k) expects two integers".to_string()),
+        _ => return Err(JError::invalid_argument("binomial(n, k) expects two integers").into()),
     };
     if n < 0 || k < 0 || k > n {
-        return Err("binomial(n, k) requires 0 <= k <= n".to_string());
+        return Err(JError::invalid_argument("binomial(n, k) requires 0 <= k <= n").into());
     }
     let n = n as u64;
     let mut k = k as u64;
@@ -279,20 +280,20 @@ fn call_binomial(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Valu
         result = result
             .checked_mul(n - i)
             .and_then(|r| r.checked_div(i + 1))
-            .ok_or_else(|| "binomial(n, k) overflow".to_string())?;
+            .ok_or_else(|| JError::unsupported("binomial(n, k) overflow"))?;
     }
     Ok(Value::Integer(result as i64))
 }
 
-fn call_factors(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_factors(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("factors(n) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("factors(n) expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     let mut n = match val {
         Value::Integer(n) if n > 1 => n.abs(),
         Value::Integer(1) => return Ok(Value::List(vec![])),
-        _ => return Err("factors(n) expects a positive integer".to_string()),
+        _ => return Err(JError::invalid_argument("factors(n) expects a positive integer").into()),
     };
     let mut out = Vec::new();
     let mut d = 2i64;
@@ -309,14 +310,14 @@ fn call_factors(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value
     Ok(Value::List(out))
 }
 
-fn call_totient(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_totient(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("totient(n) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("totient(n) expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     let n = match val {
         Value::Integer(n) if n > 0 => n,
-        _ => return Err("totient(n) expects a positive integer".to_string()),
+        _ => return Err(JError::invalid_argument("totient(n) expects a positive integer").into()),
     };
     let mut result = n;
     let mut x = n;
@@ -336,9 +337,9 @@ fn call_totient(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value
     Ok(Value::Integer(result))
 }
 
-fn call_gamma(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_gamma(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("gamma(n) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("gamma(n) expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
@@ -354,7 +355,7 @@ fn call_gamma(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value,
             }
         }
         Value::Float(f) if f >= 1.0 => Ok(Value::Float(f64::gamma_approx(f))),
-        _ => Err("gamma(n) expects n >= 1 (integer or float)".to_string()),
+        _ => Err(JError::invalid_argument("gamma(n) expects n >= 1 (integer or float)").into()),
     }
 }
 
@@ -397,27 +398,27 @@ impl GammaApprox for f64 {
     }
 }
 
-fn call_polyval(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_polyval(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("polyval(coeffs, x) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("polyval(coeffs, x) expects exactly 2 arguments").into());
     }
     let coeffs_val = interpreter.eval_node(&args[0])?;
     let x_val = interpreter.eval_node(&args[1])?;
     let coeffs = match coeffs_val {
         Value::List(c) => c,
-        _ => return Err("polyval(coeffs, x) expects list of coefficients (high to low power)".to_string()),
+        _ => return Err(JError::invalid_argument("polyval(coeffs, x) expects list of coefficients (high to low power)").into()),
     };
     let x = match x_val {
         Value::Integer(i) => i as f64,
         Value::Float(f) => f,
-        _ => return Err("polyval(coeffs, x) expects numeric x".to_string()),
+        _ => return Err(JError::invalid_argument("polyval(coeffs, x) expects numeric x").into()),
     };
     let mut result = 0.0f64;
     for (i, c) in coeffs.iter().enumerate() {
         let co = match c {
             Value::Integer(n) => *n as f64,
             Value::Float(f) => *f,
-            _ => return Err("polyval: coefficients must be numbers".to_string()),
+            _ => return Err(JError::invalid_argument("polyval: coefficients must be numbers").into()),
         };
         let power = coeffs.len() - 1 - i;
         result += co * x.powi(power as i32);
@@ -438,13 +439,13 @@ fn mod_positive(a: i64, m: i64) -> i64 {
     }
 }
 
-fn call_mod_add(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_mod_add(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("mod_add(a, b, m) expects exactly 3 arguments".to_string());
+        return Err(JError::argument_count("mod_add(a, b, m) expects exactly 3 arguments").into());
     }
     let (a, b, m) = three_ints(interpreter, args, "mod_add")?;
     if m <= 0 {
-        return Err("mod_add: modulus m must be positive".to_string());
+        return Err(JError::invalid_argument("mod_add: modulus m must be positive").into());
     }
     let a = mod_positive(a, m);
     let b = mod_positive(b, m);
@@ -452,13 +453,13 @@ fn call_mod_add(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value
     Ok(Value::Integer(mod_positive(r as i64, m)))
 }
 
-fn call_mod_sub(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_mod_sub(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("mod_sub(a, b, m) expects exactly 3 arguments".to_string());
+        return Err(JError::argument_count("mod_sub(a, b, m) expects exactly 3 arguments").into());
     }
     let (a, b, m) = three_ints(interpreter, args, "mod_sub")?;
     if m <= 0 {
-        return Err("mod_sub: modulus m must be positive".to_string());
+        return Err(JError::invalid_argument("mod_sub: modulus m must be positive").into());
     }
     let a = mod_positive(a, m);
     let b = mod_positive(b, m);
@@ -466,13 +467,13 @@ fn call_mod_sub(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value
     Ok(Value::Integer(mod_positive(r as i64, m)))
 }
 
-fn call_mod_mul(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_mod_mul(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("mod_mul(a, b, m) expects exactly 3 arguments".to_string());
+        return Err(JError::argument_count("mod_mul(a, b, m) expects exactly 3 arguments").into());
     }
     let (a, b, m) = three_ints(interpreter, args, "mod_mul")?;
     if m <= 0 {
-        return Err("mod_mul: modulus m must be positive".to_string());
+        return Err(JError::invalid_argument("mod_mul: modulus m must be positive").into());
     }
     let a = mod_positive(a, m);
     let b = mod_positive(b, m);
@@ -480,13 +481,13 @@ fn call_mod_mul(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value
     Ok(Value::Integer(mod_positive(r as i64, m)))
 }
 
-fn call_mod_pow(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_mod_pow(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("mod_pow(base, exp, m) expects exactly 3 arguments".to_string());
+        return Err(JError::argument_count("mod_pow(base, exp, m) expects exactly 3 arguments").into());
     }
     let (base, exp, m) = three_ints(interpreter, args, "mod_pow")?;
     if m <= 0 {
-        return Err("mod_pow: modulus m must be positive".to_string());
+        return Err(JError::invalid_argument("mod_pow: modulus m must be positive").into());
     }
     let base = mod_positive(base, m);
     let mut exp = exp;
@@ -494,7 +495,7 @@ fn call_mod_pow(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value
         let inv = call_mod_inv(interpreter, &[args[0].clone(), args[2].clone()])?;
         let inv_i = match inv {
             Value::Integer(i) => i,
-            _ => return Err("mod_pow: base not invertible mod m".to_string()),
+            _ => return Err(JError::unsupported("mod_pow: base not invertible mod m").into()),
         };
         exp = exp.saturating_neg();
         let mut result = 1i64;
@@ -522,22 +523,22 @@ fn call_mod_pow(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value
     Ok(Value::Integer(result))
 }
 
-fn call_mod_inv(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_mod_inv(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("mod_inv(a, m) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("mod_inv(a, m) expects exactly 2 arguments").into());
     }
     let a_val = interpreter.eval_node(&args[0])?;
     let m_val = interpreter.eval_node(&args[1])?;
     let (a, m) = match (a_val, m_val) {
         (Value::Integer(a), Value::Integer(m)) => (a, m),
-        _ => return Err("mod_inv(a, m) expects two integers".to_string()),
+        _ => return Err(JError::invalid_argument("mod_inv(a, m) expects two integers").into()),
     };
     if m <= 0 {
-        return Err("mod_inv: modulus m must be positive".to_string());
+        return Err(JError::invalid_argument("mod_inv: modulus m must be positive").into());
     }
     let (g, x, _) = egcd_inner(a, m);
     if g != 1 {
-        return Err("mod_inv: a and m must be coprime".to_string());
+        return Err(JError::invalid_argument("mod_inv: a and m must be coprime").into());
     }
     Ok(Value::Integer(mod_positive(x, m)))
 }
@@ -546,13 +547,13 @@ fn three_ints(
     interpreter: &mut Interpreter,
     args: &[AstNode],
     name: &str,
-) -> Result<(i64, i64, i64), String> {
+) -> JResult<(i64, i64, i64)> {
     let a = interpreter.eval_node(&args[0])?;
     let b = interpreter.eval_node(&args[1])?;
     let c = interpreter.eval_node(&args[2])?;
     match (a, b, c) {
         (Value::Integer(a), Value::Integer(b), Value::Integer(c)) => Ok((a, b, c)),
-        _ => Err(format!("{}(a, b, m) expects integers", name)),
+        _ => Err(JError::invalid_argument(format!("{}(a, b, m) expects integers", name)).into()),
     }
 }
 
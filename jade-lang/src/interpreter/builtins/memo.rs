@@ -1,28 +1,167 @@
-//! Memoization builtin: memo(f) returns a memoized version of f.
+//! Memoization builtins: memo(f[, max_size]) / memoize(f[, max_size]) return a cached version of f;
+//! cache_info(f) and cache_clear(f) inspect and reset that cache. Also backs the @memo/@cache decorators.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
+use std::collections::{BTreeMap, HashMap};
+
+/// Argument-keyed result cache for one memoized function, optionally bounded (least recently used
+/// entries are evicted first).
+#[derive(Debug, Default)]
+pub(crate) struct MemoCache {
+    /// key -> (result, last-use tick)
+    entries: HashMap<String, (Value, u64)>,
+    /// last-use tick -> key, oldest first
+    recency: BTreeMap<u64, String>,
+    max_size: Option<usize>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl MemoCache {
+    pub(crate) fn new(max_size: Option<usize>) -> Self {
+        Self {
+            max_size,
+            ..Default::default()
+        }
+    }
+
+    /// Cached result for `key`, marking it most recently used. Counts a hit or a miss.
+    pub(crate) fn get(&mut self, key: &str) -> Option<Value> {
+        self.tick += 1;
+        let tick = self.tick;
+        match self.entries.get_mut(key) {
+            Some((value, last_used)) => {
+                self.recency.remove(last_used);
+                self.recency.insert(tick, key.to_string());
+                *last_used = tick;
+                self.hits += 1;
+                Some(value.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub(crate) fn insert(&mut self, key: String, value: Value) {
+        if self.max_size == Some(0) {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, old_tick)) = self.entries.insert(key.clone(), (value, self.tick)) {
+            self.recency.remove(&old_tick);
+        }
+        self.recency.insert(self.tick, key);
+        if let Some(max) = self.max_size {
+            while self.entries.len() > max {
+                let Some((_, oldest)) = self.recency.pop_first() else {
+                    break;
+                };
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    fn info(&self) -> Value {
+        let mut info = HashMap::new();
+        info.insert("size".to_string(), Value::Integer(self.entries.len() as i64));
+        info.insert(
+            "max_size".to_string(),
+            self.max_size
+                .map(|m| Value::Integer(m as i64))
+                .unwrap_or(Value::None),
+        );
+        info.insert("hits".to_string(), Value::Integer(self.hits as i64));
+        info.insert("misses".to_string(), Value::Integer(self.misses as i64));
+        Value::Dict(info)
+    }
+}
+
+impl Interpreter {
+    /// Wrap a function in a fresh argument-keyed cache (`max_size` bounds it as an LRU).
+    pub(crate) fn memoize_value(&mut self, func: Value, max_size: Option<usize>) -> JResult<Value> {
+        if !matches!(func, Value::Function { .. } | Value::Decorated { .. }) {
+            return Err(JError::invalid_argument(format!(
+                "memoization expects a function, got {}",
+                Self::value_type_name(&func)
+            )).into());
+        }
+        let id = self.next_memo_id;
+        self.next_memo_id += 1;
+        self.memo_caches.insert(id, MemoCache::new(max_size));
+        Ok(Value::Memoized {
+            id,
+            inner: Box::new(func),
+        })
+    }
+}
+
+/// Optional cache size argument: a non-negative integer, or none for unbounded.
+pub(crate) fn parse_max_size(value: Option<&Value>, who: &str) -> JResult<Option<usize>> {
+    match value {
+        None | Some(Value::None) => Ok(None),
+        Some(Value::Integer(n)) if *n >= 0 => Ok(Some(*n as usize)),
+        Some(other) => Err(JError::invalid_argument(format!(
+            "{} max_size must be a non-negative integer, got {}",
+            who, other
+        )).into()),
+    }
+}
 
 pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
-    if name != "memo" {
-        return Ok(None);
-    }
-    if args.len() != 1 {
-        return Err("memo(f) expects exactly 1 argument (a function)".to_string());
-    }
-    let f = interpreter.eval_node(&args[0])?;
-    match &f {
-        Value::Function { .. } => {}
-        _ => return Err("memo(f) expects a function".to_string()),
-    }
-    let id = interpreter.next_memo_id;
-    interpreter.next_memo_id += 1;
-    Ok(Some(Value::Memoized {
-        id,
-        inner: Box::new(f),
-    }))
+) -> JResult<Option<Value>> {
+    let v = match name {
+        "memo" | "memoize" => {
+            if args.is_empty() || args.len() > 2 {
+                return Err(JError::invalid_argument(format!(
+                    "{}(f[, max_size]) expects a function and an optional cache size",
+                    name
+                )).into());
+            }
+            let f = interpreter.eval_node(&args[0])?;
+            let max_size = match args.get(1) {
+                Some(arg) => parse_max_size(Some(&interpreter.eval_node(arg)?), name)?,
+                None => None,
+            };
+            interpreter.memoize_value(f, max_size)?
+        }
+        "cache_info" | "cache_clear" => {
+            if args.len() != 1 {
+                return Err(JError::argument_count(format!("{}(f) expects exactly 1 argument (a memoized function)", name)).into());
+            }
+            let id = match interpreter.eval_node(&args[0])? {
+                Value::Memoized { id, .. } => id,
+                other => {
+                    return Err(JError::invalid_argument(format!(
+                        "{}(f) expects a memoized function (use @memo or memoize), got {}",
+                        name,
+                        Interpreter::value_type_name(&other)
+                    )).into())
+                }
+            };
+            let cache = interpreter.memo_caches.entry(id).or_default();
+            if name == "cache_clear" {
+                cache.clear();
+                Value::None
+            } else {
+                cache.info()
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(v))
 }
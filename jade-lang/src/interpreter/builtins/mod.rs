@@ -1,4 +1,4 @@
-//! Built-in functions grouped by category: math, algo, DSA, variables, string, io, numeric, enum, counter, crypto.
+//! Built-in functions grouped by category: math, algo, DSA, variables, string, io, numeric, enum, counter, memo, profile, crypto.
 
 mod core;
 mod math;
@@ -15,22 +15,29 @@ mod bits;
 mod uf;
 mod trie;
 mod memo;
+mod profile;
 mod random;
 mod crypto;
+mod names;
 #[cfg(feature = "regex")]
 pub(super) mod regex_builtins;
 
+use crate::error::JResult;
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 
+pub(crate) use memo::{parse_max_size, MemoCache};
+pub use names::{BUILTIN_METHODS, BUILTIN_NAMES};
+pub(crate) use variables::type_of_name;
+
 /// Try to dispatch a builtin by name. Returns `Ok(Some(value))` if handled, `Ok(None)` if not.
 /// Dispatches via a loop to avoid deep call stacks (stack overflow on Windows with many modules).
 pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
-    type TryFn = fn(&mut Interpreter, &str, &[AstNode]) -> Result<Option<Value>, String>;
+) -> JResult<Option<Value>> {
+    type TryFn = fn(&mut Interpreter, &str, &[AstNode]) -> JResult<Option<Value>>;
     let modules: &[TryFn] = &[
         core::try_call,
         math::try_call,
@@ -47,6 +54,7 @@ pub(super) fn try_call(
         uf::try_call,
         trie::try_call,
         memo::try_call,
+        profile::try_call,
         random::try_call,
         crypto::try_call,
     ];
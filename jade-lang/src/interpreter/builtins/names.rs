@@ -0,0 +1,502 @@
+//! Names of the builtin functions and methods, for editor completion and the linter.
+//!
+//! Calls only reach the builtin dispatchers for names listed in [`BUILTIN_NAMES`], so a new
+//! builtin must be added here as well as to its `match name`.
+//! [`BUILTIN_METHODS`] lists what each `call_<kind>_method` handles. A test compares both
+//! tables with the dispatch arms.
+
+/// Every builtin function name, sorted (looked up by binary search).
+pub const BUILTIN_NAMES: &[&str] = &[
+    "abs",
+    "accumulate",
+    "acos",
+    "add",
+    "add_edge",
+    "add_node",
+    "aes_decrypt",
+    "aes_encrypt",
+    "all",
+    "any",
+    "append",
+    "asin",
+    "assert",
+    "atan",
+    "atan2",
+    "audit_log",
+    "batched",
+    "benchmark",
+    "bfs",
+    "binary_search",
+    "binomial",
+    "bit_count",
+    "bit_set",
+    "bold",
+    "box",
+    "cache_clear",
+    "cache_info",
+    "cbrt",
+    "ceil",
+    "chain",
+    "channel",
+    "chunk",
+    "clamp",
+    "clamp01",
+    "clear",
+    "clear_bit",
+    "cli_args",
+    "cli_prompt",
+    "columns",
+    "combinations",
+    "compact",
+    "compose",
+    "contains",
+    "convex_hull",
+    "cos",
+    "cosh",
+    "count",
+    "count_bits",
+    "count_if",
+    "cross",
+    "crypto_nonce",
+    "crypto_random_bytes",
+    "crypto_salt",
+    "curry",
+    "cycle",
+    "decrypt",
+    "decrypt_value",
+    "dedupe",
+    "deep_clone",
+    "default",
+    "derive_password_key",
+    "determinant",
+    "dfs",
+    "difference",
+    "digits",
+    "dijkstra",
+    "dim",
+    "dir_create",
+    "dir_delete",
+    "dir_list",
+    "distance",
+    "dot",
+    "drop",
+    "drop_while",
+    "egcd",
+    "encrypt",
+    "encrypt_value",
+    "ends_with",
+    "enigma_decrypt",
+    "enigma_encrypt",
+    "enigma_keypair",
+    "enum_has",
+    "enum_name",
+    "enum_value",
+    "enumerate",
+    "env_get",
+    "env_set",
+    "exp",
+    "exp2",
+    "factorial",
+    "factors",
+    "fetch_html",
+    "fetch_json",
+    "fetch_text",
+    "fft",
+    "fibonacci",
+    "file_append",
+    "file_copy",
+    "file_delete",
+    "file_exists",
+    "file_read",
+    "file_rename",
+    "file_write",
+    "filter",
+    "find",
+    "find_all",
+    "find_index",
+    "flatten",
+    "flood_fill",
+    "floor",
+    "format",
+    "gamma",
+    "gcd",
+    "gcd_list",
+    "get",
+    "get_neighbors",
+    "gradient",
+    "graph_edges",
+    "graph_nodes",
+    "group_by",
+    "hamming",
+    "has",
+    "highest_set_bit",
+    "hmac",
+    "hypot",
+    "id",
+    "identity",
+    "ilog2",
+    "interleave",
+    "intersect",
+    "interval",
+    "iota",
+    "is_empty",
+    "is_power_of_two",
+    "is_prime",
+    "is_sorted",
+    "items",
+    "join",
+    "json_parse",
+    "json_stringify",
+    "kadane",
+    "keys",
+    "kmp_search",
+    "lcm",
+    "lcm_list",
+    "leading_zeros",
+    "len",
+    "lerp",
+    "levenshtein",
+    "ln",
+    "loading",
+    "log",
+    "log10",
+    "log10_floor",
+    "log2",
+    "log2_ceil",
+    "log2_floor",
+    "lower",
+    "lower_bound",
+    "lowest_set_bit",
+    "magnitude",
+    "make_secret",
+    "map",
+    "matches",
+    "matmul",
+    "max",
+    "mean",
+    "median",
+    "memo",
+    "memoize",
+    "merge",
+    "merge_sorted",
+    "min",
+    "mod_add",
+    "mod_inv",
+    "mod_mul",
+    "mod_pow",
+    "mod_sub",
+    "mode",
+    "most_common",
+    "mut_span",
+    "new",
+    "next_power_of_two",
+    "next_prime",
+    "normal_cdf",
+    "normal_pdf",
+    "normal_quantile",
+    "normalize",
+    "now",
+    "ones",
+    "out",
+    "pad_left",
+    "pad_right",
+    "pairwise",
+    "panel",
+    "parallel_map",
+    "partition",
+    "partition_range",
+    "password_hash",
+    "password_verify",
+    "peek_back",
+    "peek_front",
+    "permutations",
+    "pipe",
+    "pluck",
+    "polyval",
+    "pop",
+    "pop_back",
+    "pop_front",
+    "pow",
+    "pq_peek",
+    "pq_pop",
+    "pq_push",
+    "prefix_sum",
+    "prev_prime",
+    "product",
+    "profile_report",
+    "progress",
+    "push",
+    "push_back",
+    "push_front",
+    "quack_check",
+    "rainbow",
+    "rand",
+    "rand_choice",
+    "rand_int",
+    "rand_range",
+    "rand_seed",
+    "rand_uniform",
+    "random",
+    "random_bytes",
+    "range",
+    "read",
+    "read_lines",
+    "reduce",
+    "regex",
+    "regex_email",
+    "regex_hex_color",
+    "regex_ipv4",
+    "regex_replace",
+    "regex_semver",
+    "regex_split",
+    "regex_url",
+    "regex_uuid",
+    "remove",
+    "repeat",
+    "repeat_n",
+    "replace",
+    "replicate",
+    "retry",
+    "reveal_secret",
+    "reverse",
+    "reverse_range",
+    "ring",
+    "rotate",
+    "rotate_left",
+    "rotate_right",
+    "round",
+    "sample",
+    "scan",
+    "secure_compare",
+    "secure_eq",
+    "secure_token",
+    "set_bit",
+    "sha256",
+    "sha256_hex",
+    "shuffle",
+    "sign",
+    "sin",
+    "sinh",
+    "size",
+    "sleep",
+    "sliding_window",
+    "sort",
+    "span",
+    "sparse",
+    "spawn",
+    "spinner",
+    "split",
+    "sqrt",
+    "starts_with",
+    "status",
+    "stddev",
+    "substring",
+    "sum",
+    "swap",
+    "symmetric_diff",
+    "table",
+    "take",
+    "take_while",
+    "tan",
+    "tanh",
+    "tap",
+    "timestamp",
+    "today",
+    "toggle_bit",
+    "topological_sort",
+    "total",
+    "totient",
+    "trailing_zeros",
+    "transpose",
+    "tree",
+    "trie_contains",
+    "trie_insert",
+    "trie_new",
+    "trie_prefix_search",
+    "trim",
+    "trunc",
+    "two_pointers_sum",
+    "type_of",
+    "uf_connected",
+    "uf_find",
+    "uf_new",
+    "uf_union",
+    "underline",
+    "union",
+    "unique",
+    "update",
+    "upper",
+    "upper_bound",
+    "uuid_v4",
+    "values",
+    "varType",
+    "variance",
+    "window",
+    "write",
+    "write_lines",
+    "xor_bytes",
+    "z_array",
+    "zeros",
+    "zip",
+    "zip_longest",
+];
+
+/// Builtin methods per value kind (`list`, `string`, `dict`, ...), sorted.
+pub const BUILTIN_METHODS: &[(&str, &[&str])] = &[
+    (
+        "pool",
+        &["alloc_mat", "alloc_str", "alloc_vec", "len", "reset"],
+    ),
+    (
+        "smallvec",
+        &["first", "last", "len", "length", "pop", "push", "size"],
+    ),
+    (
+        "list",
+        &[
+            "append", "contains", "count", "empty", "find", "first", "get", "index", "insert",
+            "is_empty", "join", "last", "len", "length", "pop", "push", "size", "slice",
+        ],
+    ),
+    (
+        "string",
+        &[
+            "contains",
+            "empty",
+            "ends_with",
+            "find",
+            "is_empty",
+            "len",
+            "length",
+            "lower",
+            "lowercase",
+            "repeat",
+            "replace",
+            "size",
+            "split",
+            "starts_with",
+            "to_lower",
+            "to_upper",
+            "trim",
+            "trim_end",
+            "trim_left",
+            "trim_right",
+            "trim_start",
+            "upper",
+            "uppercase",
+        ],
+    ),
+    (
+        "int",
+        &[
+            "bit",
+            "clear_bit",
+            "count_bits",
+            "div_ceil",
+            "gcd",
+            "is_power_of_two",
+            "lcm",
+            "leading_zeros",
+            "popcount",
+            "set_bit",
+            "toggle_bit",
+            "trailing_zeros",
+        ],
+    ),
+    (
+        "float",
+        &[
+            "approx_eq",
+            "clamp",
+            "fract",
+            "is_finite",
+            "is_inf",
+            "is_infinite",
+            "is_nan",
+            "sign",
+            "to_degrees",
+            "to_radians",
+        ],
+    ),
+    (
+        "dict",
+        &[
+            "contains_key",
+            "get",
+            "has",
+            "items",
+            "keys",
+            "len",
+            "length",
+            "size",
+            "values",
+        ],
+    ),
+    (
+        "date",
+        &[
+            "add_days",
+            "day",
+            "day_of_week",
+            "difference",
+            "format",
+            "is_leap",
+            "month",
+            "month_name",
+            "weekday",
+            "weekday_name",
+            "year",
+        ],
+    ),
+    (
+        "time",
+        &[
+            "add_hours",
+            "add_minutes",
+            "format",
+            "hour",
+            "is_midnight",
+            "millis",
+            "minute",
+            "second",
+        ],
+    ),
+    (
+        "datetime",
+        &[
+            "add",
+            "date",
+            "day",
+            "difference",
+            "format",
+            "hour",
+            "minute",
+            "month",
+            "second",
+            "time",
+            "timestamp",
+            "year",
+        ],
+    ),
+    ("date_type", &["parse", "today"]),
+    ("time_type", &["now"]),
+    ("datetime_type", &["now", "now_local", "parse"]),
+    ("graph_type", &["directed", "undirected"]),
+    ("graph", &["degree", "edges", "is_connected", "nodes"]),
+    (
+        "interval",
+        &["abs", "days", "hours", "is_zero", "minutes", "seconds"],
+    ),
+    (
+        "enum_variant",
+        &[
+            "is_variant",
+            "label",
+            "name",
+            "unwrap",
+            "unwrap_or",
+            "value",
+            "variant_name",
+        ],
+    ),
+];
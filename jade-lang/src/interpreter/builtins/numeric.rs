@@ -1,6 +1,7 @@
 //! Numeric builtins: abs, sign, min, max, clamp, floor, ceil, round, trunc,
 //! sqrt, cbrt, pow, exp, ln, log10, log2, hypot, trig, lerp, clamp01.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 
@@ -16,7 +17,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "abs" => Some(call_abs(interpreter, args)?),
         "sign" => Some(call_sign(interpreter, args)?),
@@ -52,33 +53,33 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_abs(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_abs(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("abs() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("abs() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
         Value::Integer(i) => Ok(Value::Integer(i.abs())),
         Value::Float(f) => Ok(Value::Float(f.abs())),
-        _ => Err("abs() can only be called on numbers".to_string()),
+        _ => Err(JError::invalid_argument("abs() can only be called on numbers").into()),
     }
 }
 
-fn call_sqrt(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_sqrt(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("sqrt() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("sqrt() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
         Value::Integer(i) => Ok(Value::Float((i as f64).sqrt())),
         Value::Float(f) => Ok(Value::Float(f.sqrt())),
-        _ => Err("sqrt() can only be called on numbers".to_string()),
+        _ => Err(JError::invalid_argument("sqrt() can only be called on numbers").into()),
     }
 }
 
-fn call_pow(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_pow(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("pow() expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("pow() expects exactly 2 arguments").into());
     }
     let base_val = interpreter.eval_node(&args[0])?;
     let exp_val = interpreter.eval_node(&args[1])?;
@@ -86,11 +87,11 @@ fn call_pow(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, St
         (Value::Integer(base), Value::Integer(exp)) => {
             if exp >= 0 {
                 if exp > u32::MAX as i64 {
-                    return Err("Exponent too large".to_string());
+                    return Err(JError::unsupported("Exponent too large").into());
                 }
                 base.checked_pow(exp as u32)
                     .map(Value::Integer)
-                    .ok_or_else(|| format!("Integer overflow: {} ** {}", base, exp))
+                    .ok_or_else(|| JError::unsupported(format!("Integer overflow: {} ** {}", base, exp)).into())
             } else {
                 Ok(Value::Float((base as f64).powf(exp as f64)))
             }
@@ -98,46 +99,46 @@ fn call_pow(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, St
         (Value::Float(base), Value::Integer(exp)) => Ok(Value::Float(base.powf(exp as f64))),
         (Value::Integer(base), Value::Float(exp)) => Ok(Value::Float((base as f64).powf(exp))),
         (Value::Float(base), Value::Float(exp)) => Ok(Value::Float(base.powf(exp))),
-        _ => Err("pow() expects numeric arguments".to_string()),
+        _ => Err(JError::invalid_argument("pow() expects numeric arguments").into()),
     }
 }
 
-fn call_ceil(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_ceil(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("ceil() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("ceil() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
         Value::Float(f) => Ok(Value::Integer(f.ceil() as i64)),
         Value::Integer(i) => Ok(Value::Integer(i)),
-        _ => Err("ceil() can only be called on numbers".to_string()),
+        _ => Err(JError::invalid_argument("ceil() can only be called on numbers").into()),
     }
 }
 
-fn call_floor(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_floor(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("floor() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("floor() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
         Value::Float(f) => Ok(Value::Integer(f.floor() as i64)),
         Value::Integer(i) => Ok(Value::Integer(i)),
-        _ => Err("floor() can only be called on numbers".to_string()),
+        _ => Err(JError::invalid_argument("floor() can only be called on numbers").into()),
     }
 }
 
-fn call_sign(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_sign(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("sign() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("sign() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
-    let n = as_f64(&val).ok_or("sign() expects a number".to_string())?;
+    let n = as_f64(&val).ok_or_else(|| JError::invalid_argument("sign() expects a number"))?;
     Ok(Value::Integer(if n > 0.0 { 1 } else if n < 0.0 { -1 } else { 0 }))
 }
 
-fn call_min(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_min(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.is_empty() {
-        return Err("min() expects at least 1 argument".to_string());
+        return Err(JError::argument_count("min() expects at least 1 argument").into());
     }
     let mut acc = interpreter.eval_node(&args[0])?;
     for a in args.iter().skip(1) {
@@ -146,15 +147,15 @@ fn call_min(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, St
         if let (Some(x), Some(y)) = (a_f, v_f) {
             acc = if x <= y { acc } else { v };
         } else {
-            return Err("min() expects numbers".to_string());
+            return Err(JError::invalid_argument("min() expects numbers").into());
         }
     }
     Ok(acc)
 }
 
-fn call_max(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_max(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.is_empty() {
-        return Err("max() expects at least 1 argument".to_string());
+        return Err(JError::argument_count("max() expects at least 1 argument").into());
     }
     let mut acc = interpreter.eval_node(&args[0])?;
     for a in args.iter().skip(1) {
@@ -163,19 +164,19 @@ fn call_max(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, St
         if let (Some(x), Some(y)) = (a_f, v_f) {
             acc = if x >= y { acc } else { v };
         } else {
-            return Err("max() expects numbers".to_string());
+            return Err(JError::invalid_argument("max() expects numbers").into());
         }
     }
     Ok(acc)
 }
 
-fn call_clamp(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_clamp(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("clamp(x, lo, hi) expects exactly 3 arguments".to_string());
+        return Err(JError::argument_count("clamp(x, lo, hi) expects exactly 3 arguments").into());
     }
-    let x = as_f64(&interpreter.eval_node(&args[0])?).ok_or("clamp expects numbers".to_string())?;
-    let lo = as_f64(&interpreter.eval_node(&args[1])?).ok_or("clamp expects numbers".to_string())?;
-    let hi = as_f64(&interpreter.eval_node(&args[2])?).ok_or("clamp expects numbers".to_string())?;
+    let x = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("clamp expects numbers"))?;
+    let lo = as_f64(&interpreter.eval_node(&args[1])?).ok_or_else(|| JError::invalid_argument("clamp expects numbers"))?;
+    let hi = as_f64(&interpreter.eval_node(&args[2])?).ok_or_else(|| JError::invalid_argument("clamp expects numbers"))?;
     let r = x.clamp(lo, hi);
     Ok(if r.fract() == 0.0 && r.abs() <= i64::MAX as f64 {
         Value::Integer(r as i64)
@@ -184,181 +185,181 @@ fn call_clamp(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value,
     })
 }
 
-fn call_round(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_round(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("round() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("round() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
-    let f = as_f64(&val).ok_or("round() expects a number".to_string())?;
+    let f = as_f64(&val).ok_or_else(|| JError::invalid_argument("round() expects a number"))?;
     Ok(Value::Integer(f.round() as i64))
 }
 
-fn call_trunc(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_trunc(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("trunc() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("trunc() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
-    let f = as_f64(&val).ok_or("trunc() expects a number".to_string())?;
+    let f = as_f64(&val).ok_or_else(|| JError::invalid_argument("trunc() expects a number"))?;
     Ok(Value::Integer(f.trunc() as i64))
 }
 
-fn call_cbrt(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_cbrt(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("cbrt() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("cbrt() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
-    let f = as_f64(&val).ok_or("cbrt() expects a number".to_string())?;
+    let f = as_f64(&val).ok_or_else(|| JError::invalid_argument("cbrt() expects a number"))?;
     Ok(Value::Float(f.cbrt()))
 }
 
-fn call_exp(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_exp(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("exp() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("exp() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
-    let f = as_f64(&val).ok_or("exp() expects a number".to_string())?;
+    let f = as_f64(&val).ok_or_else(|| JError::invalid_argument("exp() expects a number"))?;
     Ok(Value::Float(f.exp()))
 }
 
-fn call_ln(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_ln(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("ln() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("ln() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
-    let f = as_f64(&val).ok_or("ln() expects a number".to_string())?;
+    let f = as_f64(&val).ok_or_else(|| JError::invalid_argument("ln() expects a number"))?;
     if f <= 0.0 {
-        return Err("ln() argument must be positive".to_string());
+        return Err(JError::invalid_argument("ln() argument must be positive").into());
     }
     Ok(Value::Float(f.ln()))
 }
 
-fn call_log10(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_log10(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("log10() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("log10() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
-    let f = as_f64(&val).ok_or("log10() expects a number".to_string())?;
+    let f = as_f64(&val).ok_or_else(|| JError::invalid_argument("log10() expects a number"))?;
     if f <= 0.0 {
-        return Err("log10() argument must be positive".to_string());
+        return Err(JError::invalid_argument("log10() argument must be positive").into());
     }
     Ok(Value::Float(f.log10()))
 }
 
-fn call_log2(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_log2(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("log2() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("log2() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
-    let f = as_f64(&val).ok_or("log2() expects a number".to_string())?;
+    let f = as_f64(&val).ok_or_else(|| JError::invalid_argument("log2() expects a number"))?;
     if f <= 0.0 {
-        return Err("log2() argument must be positive".to_string());
+        return Err(JError::invalid_argument("log2() argument must be positive").into());
     }
     Ok(Value::Float(f.log2()))
 }
 
-fn call_hypot(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_hypot(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("hypot(x, y) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("hypot(x, y) expects exactly 2 arguments").into());
     }
-    let x = as_f64(&interpreter.eval_node(&args[0])?).ok_or("hypot expects numbers".to_string())?;
-    let y = as_f64(&interpreter.eval_node(&args[1])?).ok_or("hypot expects numbers".to_string())?;
+    let x = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("hypot expects numbers"))?;
+    let y = as_f64(&interpreter.eval_node(&args[1])?).ok_or_else(|| JError::invalid_argument("hypot expects numbers"))?;
     Ok(Value::Float(x.hypot(y)))
 }
 
-fn call_sin(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_sin(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("sin() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("sin() expects exactly 1 argument").into());
     }
-    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or("sin() expects a number".to_string())?;
+    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("sin() expects a number"))?;
     Ok(Value::Float(f.sin()))
 }
 
-fn call_cos(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_cos(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("cos() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("cos() expects exactly 1 argument").into());
     }
-    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or("cos() expects a number".to_string())?;
+    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("cos() expects a number"))?;
     Ok(Value::Float(f.cos()))
 }
 
-fn call_tan(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_tan(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("tan() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("tan() expects exactly 1 argument").into());
     }
-    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or("tan() expects a number".to_string())?;
+    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("tan() expects a number"))?;
     Ok(Value::Float(f.tan()))
 }
 
-fn call_asin(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_asin(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("asin() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("asin() expects exactly 1 argument").into());
     }
-    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or("asin() expects a number".to_string())?;
+    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("asin() expects a number"))?;
     if f < -1.0 || f > 1.0 {
-        return Err("asin() argument must be in [-1, 1]".to_string());
+        return Err(JError::invalid_argument("asin() argument must be in [-1, 1]").into());
     }
     Ok(Value::Float(f.asin()))
 }
 
-fn call_acos(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_acos(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("acos() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("acos() expects exactly 1 argument").into());
     }
-    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or("acos() expects a number".to_string())?;
+    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("acos() expects a number"))?;
     if f < -1.0 || f > 1.0 {
-        return Err("acos() argument must be in [-1, 1]".to_string());
+        return Err(JError::invalid_argument("acos() argument must be in [-1, 1]").into());
     }
     Ok(Value::Float(f.acos()))
 }
 
-fn call_atan(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_atan(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("atan() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("atan() expects exactly 1 argument").into());
     }
-    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or("atan() expects a number".to_string())?;
+    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("atan() expects a number"))?;
     Ok(Value::Float(f.atan()))
 }
 
-fn call_atan2(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_atan2(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("atan2(y, x) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("atan2(y, x) expects exactly 2 arguments").into());
     }
-    let y = as_f64(&interpreter.eval_node(&args[0])?).ok_or("atan2 expects numbers".to_string())?;
-    let x = as_f64(&interpreter.eval_node(&args[1])?).ok_or("atan2 expects numbers".to_string())?;
+    let y = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("atan2 expects numbers"))?;
+    let x = as_f64(&interpreter.eval_node(&args[1])?).ok_or_else(|| JError::invalid_argument("atan2 expects numbers"))?;
     Ok(Value::Float(y.atan2(x)))
 }
 
-fn call_sinh(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_sinh(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("sinh() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("sinh() expects exactly 1 argument").into());
     }
-    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or("sinh() expects a number".to_string())?;
+    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("sinh() expects a number"))?;
     Ok(Value::Float(f.sinh()))
 }
 
-fn call_cosh(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_cosh(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("cosh() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("cosh() expects exactly 1 argument").into());
     }
-    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or("cosh() expects a number".to_string())?;
+    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("cosh() expects a number"))?;
     Ok(Value::Float(f.cosh()))
 }
 
-fn call_tanh(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_tanh(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("tanh() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("tanh() expects exactly 1 argument").into());
     }
-    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or("tanh() expects a number".to_string())?;
+    let f = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("tanh() expects a number"))?;
     Ok(Value::Float(f.tanh()))
 }
 
-fn call_lerp(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_lerp(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("lerp(a, b, t) expects exactly 3 arguments".to_string());
+        return Err(JError::argument_count("lerp(a, b, t) expects exactly 3 arguments").into());
     }
-    let a = as_f64(&interpreter.eval_node(&args[0])?).ok_or("lerp expects numbers".to_string())?;
-    let b = as_f64(&interpreter.eval_node(&args[1])?).ok_or("lerp expects numbers".to_string())?;
-    let t = as_f64(&interpreter.eval_node(&args[2])?).ok_or("lerp expects numbers".to_string())?;
+    let a = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("lerp expects numbers"))?;
+    let b = as_f64(&interpreter.eval_node(&args[1])?).ok_or_else(|| JError::invalid_argument("lerp expects numbers"))?;
+    let t = as_f64(&interpreter.eval_node(&args[2])?).ok_or_else(|| JError::invalid_argument("lerp expects numbers"))?;
     let r = a + t * (b - a);
     Ok(if r.fract() == 0.0 && r.abs() <= i64::MAX as f64 {
         Value::Integer(r as i64)
@@ -367,10 +368,10 @@ fn call_lerp(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, S
     })
 }
 
-fn call_clamp01(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_clamp01(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("clamp01(x) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("clamp01(x) expects exactly 1 argument").into());
     }
-    let x = as_f64(&interpreter.eval_node(&args[0])?).ok_or("clamp01 expects a number".to_string())?;
+    let x = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("clamp01 expects a number"))?;
     Ok(Value::Float(x.clamp(0.0, 1.0)))
 }
@@ -0,0 +1,22 @@
+//! Profiling builtins: profile_report() returns the numbers collected by `@profile`.
+
+use crate::error::{JError, JResult};
+use crate::interpreter::{Interpreter, Value};
+use crate::parser::AstNode;
+
+pub(super) fn try_call(
+    interpreter: &mut Interpreter,
+    name: &str,
+    args: &[AstNode],
+) -> JResult<Option<Value>> {
+    let v = match name {
+        "profile_report" => {
+            if !args.is_empty() {
+                return Err(JError::argument_count("profile_report() takes no arguments").into());
+            }
+            interpreter.profile_report_value()
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(v))
+}
@@ -1,5 +1,9 @@
-//! Random builtins: rand, rand_int, rand_choice, shuffle, rand_uniform.
+//! Random builtins: rand, rand_int, rand_choice, shuffle, rand_uniform, rand_seed.
+//!
+//! All draw from the interpreter's seedable generator, so `rand_seed(n)` makes a run repeatable.
 
+use crate::error::{JError, JResult};
+use crate::interpreter::generators::Rng;
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 
@@ -14,99 +18,96 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "rand" => Some(call_rand(interpreter, args)?),
         "rand_int" => Some(call_rand_int(interpreter, args)?),
         "rand_choice" => Some(call_rand_choice(interpreter, args)?),
         "shuffle" => Some(call_shuffle(interpreter, args)?),
         "rand_uniform" => Some(call_rand_uniform(interpreter, args)?),
+        "rand_seed" => Some(call_rand_seed(interpreter, args)?),
         _ => None,
     };
     Ok(v)
 }
 
-fn call_rand(_interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_rand(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if !args.is_empty() {
-        return Err("rand() expects no arguments".to_string());
+        return Err(JError::argument_count("rand() expects no arguments").into());
     }
-    Ok(Value::Float(rand_f64()))
+    Ok(Value::Float(interpreter.rng.next_f64()))
 }
 
-fn rand_f64() -> f64 {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let t = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
-    (t % 1_000_000) as f64 / 1_000_000.0
-}
-
-fn rand_u64() -> u64 {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64
+fn call_rand_seed(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
+    if args.len() != 1 {
+        return Err(JError::argument_count("rand_seed(n) expects exactly 1 argument").into());
+    }
+    let seed = as_i64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("rand_seed expects an integer"))?;
+    interpreter.rng = Rng::new(seed as u64);
+    Ok(Value::None)
 }
 
-fn call_rand_int(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_rand_int(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("rand_int(lo, hi) expects exactly 2 arguments (inclusive range)".to_string());
+        return Err(JError::argument_count("rand_int(lo, hi) expects exactly 2 arguments (inclusive range)").into());
     }
-    let lo = as_i64(&interpreter.eval_node(&args[0])?).ok_or("rand_int expects integers".to_string())?;
-    let hi = as_i64(&interpreter.eval_node(&args[1])?).ok_or("rand_int expects integers".to_string())?;
+    let lo = as_i64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("rand_int expects integers"))?;
+    let hi = as_i64(&interpreter.eval_node(&args[1])?).ok_or_else(|| JError::invalid_argument("rand_int expects integers"))?;
     if lo > hi {
-        return Err("rand_int(lo, hi) requires lo <= hi".to_string());
+        return Err(JError::invalid_argument("rand_int(lo, hi) requires lo <= hi").into());
     }
-    let range = (hi - lo) as u64 + 1;
-    let r = (rand_u64() % range) as i64 + lo;
-    Ok(Value::Integer(r))
+    Ok(Value::Integer(interpreter.rng.range_i64(lo, hi)))
 }
 
-fn call_rand_choice(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_rand_choice(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("rand_choice(list) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("rand_choice(list) expects exactly 1 argument").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let list = match list_val {
         Value::List(ref l) => l,
-        _ => return Err("rand_choice(list) expects a list".to_string()),
+        _ => return Err(JError::invalid_argument("rand_choice(list) expects a list").into()),
     };
     if list.is_empty() {
-        return Err("rand_choice() of empty list".to_string());
+        return Err(JError::empty_collection("rand_choice() of empty list").into());
     }
-    let idx = (rand_u64() as usize) % list.len();
+    let idx = interpreter.rng.index(list.len());
     Ok(list[idx].clone())
 }
 
-fn call_shuffle(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_shuffle(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("shuffle(list) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("shuffle(list) expects exactly 1 argument").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let mut list = match list_val {
         Value::List(l) => l,
-        _ => return Err("shuffle(list) expects a list".to_string()),
+        _ => return Err(JError::invalid_argument("shuffle(list) expects a list").into()),
     };
     for i in (1..list.len()).rev() {
-        let j = (rand_u64() as usize) % (i + 1);
+        let j = interpreter.rng.index(i + 1);
         list.swap(i, j);
     }
     Ok(Value::List(list))
 }
 
-fn call_rand_uniform(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_rand_uniform(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("rand_uniform(a, b) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("rand_uniform(a, b) expects exactly 2 arguments").into());
     }
     let a_val = interpreter.eval_node(&args[0])?;
     let b_val = interpreter.eval_node(&args[1])?;
     let a = match a_val {
         Value::Integer(i) => i as f64,
         Value::Float(f) => f,
-        _ => return Err("rand_uniform expects numbers".to_string()),
+        _ => return Err(JError::invalid_argument("rand_uniform expects numbers").into()),
     };
     let b = match b_val {
         Value::Integer(i) => i as f64,
         Value::Float(f) => f,
-        _ => return Err("rand_uniform expects numbers".to_string()),
+        _ => return Err(JError::invalid_argument("rand_uniform expects numbers").into()),
     };
-    let t = rand_f64();
+    let t = interpreter.rng.next_f64();
     let r = a + t * (b - a);
     Ok(Value::Float(r))
 }
@@ -3,12 +3,13 @@
 
 #![cfg(feature = "regex")]
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::interpreter::value::{CompiledRegex, RegexMatch};
 use crate::parser::AstNode;
 use std::collections::HashMap;
 
-fn pattern_with_flags(pattern: &str, flags: &str) -> Result<String, String> {
+fn pattern_with_flags(pattern: &str, flags: &str) -> JResult<String> {
     let mut out = pattern.to_string();
     if flags.contains('i') {
         out = format!("(?i){}", out);
@@ -22,12 +23,12 @@ fn pattern_with_flags(pattern: &str, flags: &str) -> Result<String, String> {
     Ok(out)
 }
 
-fn compile_regex(pattern: &str, flags: Option<&str>) -> Result<Value, String> {
+fn compile_regex(pattern: &str, flags: Option<&str>) -> JResult<Value> {
     let effective = match flags {
         Some(f) => pattern_with_flags(pattern, f)?,
         None => pattern.to_string(),
     };
-    let re = regex::Regex::new(&effective).map_err(|e| format!("Invalid regex: {}", e))?;
+    let re = regex::Regex::new(&effective).map_err(|e| JError::invalid_argument(format!("Invalid regex: {}", e)))?;
     Ok(Value::Regex(Box::new(CompiledRegex {
         pattern: effective,
         inner: re,
@@ -38,7 +39,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "regex" => Some(call_regex(interpreter, args)?),
         "regex_email" => Some(call_regex_email(interpreter, args)?),
@@ -54,44 +55,44 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_regex_replace(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_regex_replace(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("regex_replace(string, regex, replacement) expects 3 arguments".to_string());
+        return Err(JError::argument_count("regex_replace(string, regex, replacement) expects 3 arguments").into());
     }
     let s = get_string_arg(interpreter, args, 0)?;
     let repl = get_string_arg(interpreter, args, 2)?;
     let re_val = interpreter.eval_node(&args[1])?;
     let re = match re_val {
         Value::Regex(r) => r,
-        _ => return Err("regex_replace second argument must be a regex".to_string()),
+        _ => return Err(JError::invalid_argument("regex_replace second argument must be a regex").into()),
     };
     Ok(Value::String(regex_replace(&s, re.as_ref(), &repl)))
 }
 
-fn call_regex_split(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_regex_split(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("regex_split(string, regex) expects 2 arguments".to_string());
+        return Err(JError::argument_count("regex_split(string, regex) expects 2 arguments").into());
     }
     let s = get_string_arg(interpreter, args, 0)?;
     let re_val = interpreter.eval_node(&args[1])?;
     let re = match re_val {
         Value::Regex(r) => r,
-        _ => return Err("regex_split second argument must be a regex".to_string()),
+        _ => return Err(JError::invalid_argument("regex_split second argument must be a regex").into()),
     };
     let parts = regex_split(&s, re.as_ref());
     Ok(Value::List(parts.into_iter().map(Value::String).collect()))
 }
 
-fn get_string_arg(interpreter: &mut Interpreter, args: &[AstNode], idx: usize) -> Result<String, String> {
+fn get_string_arg(interpreter: &mut Interpreter, args: &[AstNode], idx: usize) -> JResult<String> {
     match interpreter.eval_node(&args[idx])? {
         Value::String(s) => Ok(s),
-        _ => Err("expected string argument".to_string()),
+        _ => Err(JError::invalid_argument("expected string argument").into()),
     }
 }
 
-fn call_regex(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_regex(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.is_empty() || args.len() > 2 {
-        return Err("regex(pattern) or regex(pattern, flags) expects 1 or 2 arguments".to_string());
+        return Err(JError::argument_count("regex(pattern) or regex(pattern, flags) expects 1 or 2 arguments").into());
     }
     let pattern = get_string_arg(interpreter, args, 0)?;
     let flags_str = if args.len() == 2 {
@@ -102,49 +103,49 @@ fn call_regex(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value,
     compile_regex(&pattern, flags_str.as_deref())
 }
 
-fn call_regex_email(_interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_regex_email(_interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if !args.is_empty() {
-        return Err("regex_email() expects no arguments".to_string());
+        return Err(JError::argument_count("regex_email() expects no arguments").into());
     }
     let pat = r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$";
     compile_regex(pat, None)
 }
 
-fn call_regex_url(_interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_regex_url(_interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if !args.is_empty() {
-        return Err("regex_url() expects no arguments".to_string());
+        return Err(JError::argument_count("regex_url() expects no arguments").into());
     }
     let pat = r"https?://[^\s]+";
     compile_regex(pat, None)
 }
 
-fn call_regex_ipv4(_interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_regex_ipv4(_interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if !args.is_empty() {
-        return Err("regex_ipv4() expects no arguments".to_string());
+        return Err(JError::argument_count("regex_ipv4() expects no arguments").into());
     }
     let pat = r"^(?:(?:25[0-5]|2[0-4][0-9]|[01]?[0-9][0-9]?)\.){3}(?:25[0-5]|2[0-4][0-9]|[01]?[0-9][0-9]?)$";
     compile_regex(pat, None)
 }
 
-fn call_regex_uuid(_interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_regex_uuid(_interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if !args.is_empty() {
-        return Err("regex_uuid() expects no arguments".to_string());
+        return Err(JError::argument_count("regex_uuid() expects no arguments").into());
     }
     let pat = r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$";
     compile_regex(pat, None)
 }
 
-fn call_regex_hex_color(_interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_regex_hex_color(_interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if !args.is_empty() {
-        return Err("regex_hex_color() expects no arguments".to_string());
+        return Err(JError::argument_count("regex_hex_color() expects no arguments").into());
     }
     let pat = r"^#([0-9A-Fa-f]{3}|[0-9A-Fa-f]{6}|[0-9A-Fa-f]{8})$";
     compile_regex(pat, None)
 }
 
-fn call_regex_semver(_interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_regex_semver(_interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if !args.is_empty() {
-        return Err("regex_semver() expects no arguments".to_string());
+        return Err(JError::argument_count("regex_semver() expects no arguments").into());
     }
     let pat = r"^\d+\.\d+\.\d+(-[0-9A-Za-z.-]+)?(\+[0-9A-Za-z.-]+)?$";
     compile_regex(pat, None)
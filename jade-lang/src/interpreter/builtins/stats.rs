@@ -1,5 +1,6 @@
 //! Statistics builtins: mean, median, mode, variance, stddev, normal_pdf, normal_cdf, normal_quantile.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 use std::f64::consts::PI;
@@ -12,9 +13,9 @@ fn as_f64(v: &Value) -> Option<f64> {
     }
 }
 
-fn list_of_f64(list: &[Value]) -> Result<Vec<f64>, String> {
+fn list_of_f64(list: &[Value]) -> JResult<Vec<f64>> {
     list.iter()
-        .map(|v| as_f64(v).ok_or("stats: list must contain numbers".to_string()))
+        .map(|v| as_f64(v).ok_or_else(|| JError::invalid_argument("stats: list must contain numbers").into()))
         .collect()
 }
 
@@ -22,7 +23,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "mean" => Some(call_mean(interpreter, args)?),
         "median" => Some(call_median(interpreter, args)?),
@@ -37,33 +38,33 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_mean(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_mean(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("mean(list) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("mean(list) expects exactly 1 argument").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let list = match list_val {
         Value::List(ref l) => list_of_f64(l)?,
-        _ => return Err("mean(list) expects a list of numbers".to_string()),
+        _ => return Err(JError::invalid_argument("mean(list) expects a list of numbers").into()),
     };
     if list.is_empty() {
-        return Err("mean() of empty list undefined".to_string());
+        return Err(JError::empty_collection("mean() of empty list undefined").into());
     }
     let sum: f64 = list.iter().sum();
     Ok(Value::Float(sum / list.len() as f64))
 }
 
-fn call_median(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_median(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("median(list) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("median(list) expects exactly 1 argument").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let mut list = match list_val {
         Value::List(ref l) => list_of_f64(l)?,
-        _ => return Err("median(list) expects a list of numbers".to_string()),
+        _ => return Err(JError::invalid_argument("median(list) expects a list of numbers").into()),
     };
     if list.is_empty() {
-        return Err("median() of empty list undefined".to_string());
+        return Err(JError::empty_collection("median() of empty list undefined").into());
     }
     list.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
     let mid = list.len() / 2;
@@ -75,17 +76,17 @@ fn call_median(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value,
     Ok(Value::Float(med))
 }
 
-fn call_mode(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_mode(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("mode(list) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("mode(list) expects exactly 1 argument").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let list = match list_val {
         Value::List(ref l) => l,
-        _ => return Err("mode(list) expects a list".to_string()),
+        _ => return Err(JError::invalid_argument("mode(list) expects a list").into()),
     };
     if list.is_empty() {
-        return Err("mode() of empty list undefined".to_string());
+        return Err(JError::empty_collection("mode() of empty list undefined").into());
     }
     use std::collections::HashMap;
     let mut counts: HashMap<String, (Value, i64)> = HashMap::new();
@@ -97,38 +98,38 @@ fn call_mode(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, S
     let (_, (mode_val, _)) = counts
         .into_iter()
         .max_by_key(|(_, (_, c))| *c)
-        .ok_or("mode() failed".to_string())?;
+        .ok_or_else(|| JError::unsupported("mode() failed"))?;
     Ok(mode_val)
 }
 
-fn call_variance(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_variance(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("variance(list) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("variance(list) expects exactly 1 argument").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let list = match list_val {
         Value::List(ref l) => list_of_f64(l)?,
-        _ => return Err("variance(list) expects a list of numbers".to_string()),
+        _ => return Err(JError::invalid_argument("variance(list) expects a list of numbers").into()),
     };
     if list.len() < 2 {
-        return Err("variance() needs at least 2 elements".to_string());
+        return Err(JError::invalid_argument("variance() needs at least 2 elements").into());
     }
     let mean: f64 = list.iter().sum::<f64>() / list.len() as f64;
     let var = list.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (list.len() - 1) as f64;
     Ok(Value::Float(var))
 }
 
-fn call_stddev(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_stddev(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("stddev(list) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("stddev(list) expects exactly 1 argument").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let list = match list_val {
         Value::List(ref l) => list_of_f64(l)?,
-        _ => return Err("stddev(list) expects a list of numbers".to_string()),
+        _ => return Err(JError::invalid_argument("stddev(list) expects a list of numbers").into()),
     };
     if list.len() < 2 {
-        return Err("stddev() needs at least 2 elements".to_string());
+        return Err(JError::invalid_argument("stddev() needs at least 2 elements").into());
     }
     let mean: f64 = list.iter().sum::<f64>() / list.len() as f64;
     let var = list.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (list.len() - 1) as f64;
@@ -143,18 +144,18 @@ fn normal_pdf(x: f64, mean: f64, std: f64) -> f64 {
     (-z * z / 2.0).exp() / (std * (2.0 * PI).sqrt())
 }
 
-fn call_normal_pdf(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_normal_pdf(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.is_empty() || args.len() > 3 {
-        return Err("normal_pdf(x, [mean=0], [std=1]) expects 1 to 3 arguments".to_string());
+        return Err(JError::argument_count("normal_pdf(x, [mean=0], [std=1]) expects 1 to 3 arguments").into());
     }
-    let x = as_f64(&interpreter.eval_node(&args[0])?).ok_or("normal_pdf expects numbers".to_string())?;
+    let x = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("normal_pdf expects numbers"))?;
     let mean = if args.len() >= 2 {
-        as_f64(&interpreter.eval_node(&args[1])?).ok_or("normal_pdf expects numbers".to_string())?
+        as_f64(&interpreter.eval_node(&args[1])?).ok_or_else(|| JError::invalid_argument("normal_pdf expects numbers"))?
     } else {
         0.0
     };
     let std = if args.len() >= 3 {
-        as_f64(&interpreter.eval_node(&args[2])?).ok_or("normal_pdf expects numbers".to_string())?
+        as_f64(&interpreter.eval_node(&args[2])?).ok_or_else(|| JError::invalid_argument("normal_pdf expects numbers"))?
     } else {
         1.0
     };
@@ -179,23 +180,23 @@ fn erf_approx(x: f64) -> f64 {
     }
 }
 
-fn call_normal_cdf(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_normal_cdf(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.is_empty() || args.len() > 3 {
-        return Err("normal_cdf(x, [mean=0], [std=1]) expects 1 to 3 arguments".to_string());
+        return Err(JError::argument_count("normal_cdf(x, [mean=0], [std=1]) expects 1 to 3 arguments").into());
     }
-    let x = as_f64(&interpreter.eval_node(&args[0])?).ok_or("normal_cdf expects numbers".to_string())?;
+    let x = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("normal_cdf expects numbers"))?;
     let mean = if args.len() >= 2 {
-        as_f64(&interpreter.eval_node(&args[1])?).ok_or("normal_cdf expects numbers".to_string())?
+        as_f64(&interpreter.eval_node(&args[1])?).ok_or_else(|| JError::invalid_argument("normal_cdf expects numbers"))?
     } else {
         0.0
     };
     let std = if args.len() >= 3 {
-        as_f64(&interpreter.eval_node(&args[2])?).ok_or("normal_cdf expects numbers".to_string())?
+        as_f64(&interpreter.eval_node(&args[2])?).ok_or_else(|| JError::invalid_argument("normal_cdf expects numbers"))?
     } else {
         1.0
     };
     if std <= 0.0 {
-        return Err("normal_cdf: std must be positive".to_string());
+        return Err(JError::invalid_argument("normal_cdf: std must be positive").into());
     }
     let z = (x - mean) / std;
     let cdf = 0.5 * (1.0 + erf_approx(z / 2.0_f64.sqrt()));
@@ -251,10 +252,10 @@ fn normal_quantile_approx(p: f64) -> f64 {
     }
 }
 
-fn call_normal_quantile(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_normal_quantile(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("normal_quantile(p) expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("normal_quantile(p) expects exactly 1 argument").into());
     }
-    let p = as_f64(&interpreter.eval_node(&args[0])?).ok_or("normal_quantile expects a number".to_string())?;
+    let p = as_f64(&interpreter.eval_node(&args[0])?).ok_or_else(|| JError::invalid_argument("normal_quantile expects a number"))?;
     Ok(Value::Float(normal_quantile_approx(p)))
 }
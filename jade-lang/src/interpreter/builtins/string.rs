@@ -1,5 +1,6 @@
 //! String builtins: split, join, substring, upper, lower, trim, replace, starts_with, ends_with, repeat, format.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 
@@ -7,7 +8,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "split" => Some(call_split(interpreter, args)?),
         "join" => Some(call_join(interpreter, args)?),
@@ -25,9 +26,9 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_split(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_split(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("split() expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("split() expects exactly 2 arguments").into());
     }
     let string_val = interpreter.eval_node(&args[0])?;
     let delimiter_val = interpreter.eval_node(&args[1])?;
@@ -39,13 +40,13 @@ fn call_split(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value,
                 .collect();
             Ok(Value::List(parts))
         }
-        _ => Err("split() expects string and delimiter arguments".to_string()),
+        _ => Err(JError::invalid_argument("split() expects string and delimiter arguments").into()),
     }
 }
 
-fn call_join(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_join(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("join() expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("join() expects exactly 2 arguments").into());
     }
     let list_val = interpreter.eval_node(&args[0])?;
     let separator_val = interpreter.eval_node(&args[1])?;
@@ -60,11 +61,11 @@ fn call_join(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, S
                 .collect();
             Ok(Value::String(strings.join(&sep)))
         }
-        _ => Err("join() expects list and separator string arguments".to_string()),
+        _ => Err(JError::invalid_argument("join() expects list and separator string arguments").into()),
     }
 }
 
-fn call_substring(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_substring(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     match args.len() {
         2 => {
             let string_val = interpreter.eval_node(&args[0])?;
@@ -80,7 +81,7 @@ fn call_substring(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Val
                     let result: String = chars[start_idx..].iter().collect();
                     Ok(Value::String(result))
                 }
-                _ => Err("substring() expects string and integer arguments".to_string()),
+                _ => Err(JError::invalid_argument("substring() expects string and integer arguments").into()),
             }
         }
         3 => {
@@ -107,49 +108,49 @@ fn call_substring(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Val
                     };
                     Ok(Value::String(result))
                 }
-                _ => Err("substring() expects string and integer arguments".to_string()),
+                _ => Err(JError::invalid_argument("substring() expects string and integer arguments").into()),
             }
         }
-        _ => Err("substring() expects 2 or 3 arguments".to_string()),
+        _ => Err(JError::argument_count("substring() expects 2 or 3 arguments").into()),
     }
 }
 
-fn call_upper(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_upper(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("upper() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("upper() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
         Value::String(s) => Ok(Value::String(s.to_uppercase())),
-        _ => Err("upper() can only be called on strings".to_string()),
+        _ => Err(JError::invalid_argument("upper() can only be called on strings").into()),
     }
 }
 
-fn call_lower(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_lower(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("lower() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("lower() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
         Value::String(s) => Ok(Value::String(s.to_lowercase())),
-        _ => Err("lower() can only be called on strings".to_string()),
+        _ => Err(JError::invalid_argument("lower() can only be called on strings").into()),
     }
 }
 
-fn call_trim(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_trim(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("trim() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("trim() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
         Value::String(s) => Ok(Value::String(s.trim().to_string())),
-        _ => Err("trim() can only be called on strings".to_string()),
+        _ => Err(JError::invalid_argument("trim() can only be called on strings").into()),
     }
 }
 
-fn call_replace(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_replace(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("replace() expects exactly 3 arguments".to_string());
+        return Err(JError::argument_count("replace() expects exactly 3 arguments").into());
     }
     let string_val = interpreter.eval_node(&args[0])?;
     let from_val = interpreter.eval_node(&args[1])?;
@@ -158,59 +159,59 @@ fn call_replace(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value
         (Value::String(s), Value::String(from), Value::String(to)) => {
             Ok(Value::String(s.replace(&from, &to)))
         }
-        _ => Err("replace() expects string arguments".to_string()),
+        _ => Err(JError::invalid_argument("replace() expects string arguments").into()),
     }
 }
 
-fn call_starts_with(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_starts_with(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("starts_with() expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("starts_with() expects exactly 2 arguments").into());
     }
     let string_val = interpreter.eval_node(&args[0])?;
     let prefix_val = interpreter.eval_node(&args[1])?;
     match (string_val, prefix_val) {
         (Value::String(s), Value::String(prefix)) => Ok(Value::Boolean(s.starts_with(&prefix))),
-        _ => Err("starts_with() expects string arguments".to_string()),
+        _ => Err(JError::invalid_argument("starts_with() expects string arguments").into()),
     }
 }
 
-fn call_ends_with(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_ends_with(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("ends_with() expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("ends_with() expects exactly 2 arguments").into());
     }
     let string_val = interpreter.eval_node(&args[0])?;
     let suffix_val = interpreter.eval_node(&args[1])?;
     match (string_val, suffix_val) {
         (Value::String(s), Value::String(suffix)) => Ok(Value::Boolean(s.ends_with(&suffix))),
-        _ => Err("ends_with() expects string arguments".to_string()),
+        _ => Err(JError::invalid_argument("ends_with() expects string arguments").into()),
     }
 }
 
-fn call_repeat(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_repeat(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("repeat() expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("repeat() expects exactly 2 arguments").into());
     }
     let string_val = interpreter.eval_node(&args[0])?;
     let count_val = interpreter.eval_node(&args[1])?;
     match (string_val, count_val) {
         (Value::String(s), Value::Integer(count)) => {
             if count < 0 {
-                return Err("repeat() count must be non-negative".to_string());
+                return Err(JError::invalid_argument("repeat() count must be non-negative").into());
             }
             Ok(Value::String(s.repeat(count as usize)))
         }
-        _ => Err("repeat() expects string and integer arguments".to_string()),
+        _ => Err(JError::invalid_argument("repeat() expects string and integer arguments").into()),
     }
 }
 
-fn call_format(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_format(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.is_empty() {
-        return Err("format() expects at least 1 argument".to_string());
+        return Err(JError::argument_count("format() expects at least 1 argument").into());
     }
     let template_val = interpreter.eval_node(&args[0])?;
     let template = match template_val {
         Value::String(s) => s,
-        _ => return Err("format() first argument must be a string".to_string()),
+        _ => return Err(JError::invalid_argument("format() first argument must be a string").into()),
     };
     let mut result = template;
     for arg in args.iter().skip(1) {
@@ -1,5 +1,6 @@
 //! Trie (prefix tree) builtins: trie_new, trie_insert, trie_contains, trie_prefix_search.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value, TrieNode};
 use crate::parser::AstNode;
 
@@ -7,7 +8,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "trie_new" => Some(call_trie_new(interpreter, args)?),
         "trie_insert" => Some(call_trie_insert(interpreter, args)?),
@@ -18,9 +19,9 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_trie_new(_interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_trie_new(_interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if !args.is_empty() {
-        return Err("trie_new() expects no arguments".to_string());
+        return Err(JError::argument_count("trie_new() expects no arguments").into());
     }
     Ok(Value::Trie(Box::new(TrieNode::default())))
 }
@@ -33,15 +34,15 @@ fn trie_insert_node(node: &mut TrieNode, s: &str) {
     current.is_end = true;
 }
 
-fn call_trie_insert(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_trie_insert(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("trie_insert(trie, s) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("trie_insert(trie, s) expects exactly 2 arguments").into());
     }
     let trie_val = interpreter.eval_node(&args[0])?;
     let s_val = interpreter.eval_node(&args[1])?;
     let (mut node, s) = match (trie_val, s_val) {
         (Value::Trie(node), Value::String(s)) => (*node, s),
-        _ => return Err("trie_insert(trie, s) expects Trie and string".to_string()),
+        _ => return Err(JError::invalid_argument("trie_insert(trie, s) expects Trie and string").into()),
     };
     trie_insert_node(&mut node, &s);
     Ok(Value::Trie(Box::new(node)))
@@ -58,15 +59,15 @@ fn trie_contains_node(node: &TrieNode, s: &str) -> bool {
     current.is_end
 }
 
-fn call_trie_contains(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_trie_contains(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("trie_contains(trie, s) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("trie_contains(trie, s) expects exactly 2 arguments").into());
     }
     let trie_val = interpreter.eval_node(&args[0])?;
     let s_val = interpreter.eval_node(&args[1])?;
     let (node, s) = match (trie_val, s_val) {
         (Value::Trie(node), Value::String(s)) => (node, s),
-        _ => return Err("trie_contains(trie, s) expects Trie and string".to_string()),
+        _ => return Err(JError::invalid_argument("trie_contains(trie, s) expects Trie and string").into()),
     };
     Ok(Value::Boolean(trie_contains_node(&node, &s)))
 }
@@ -82,15 +83,15 @@ fn trie_prefix_collect(node: &TrieNode, prefix: &str, acc: &mut Vec<String>) {
     }
 }
 
-fn call_trie_prefix_search(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_trie_prefix_search(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("trie_prefix_search(trie, prefix) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("trie_prefix_search(trie, prefix) expects exactly 2 arguments").into());
     }
     let trie_val = interpreter.eval_node(&args[0])?;
     let prefix_val = interpreter.eval_node(&args[1])?;
     let (node, prefix) = match (trie_val, prefix_val) {
         (Value::Trie(node), Value::String(s)) => (node, s),
-        _ => return Err("trie_prefix_search(trie, prefix) expects Trie and string".to_string()),
+        _ => return Err(JError::invalid_argument("trie_prefix_search(trie, prefix) expects Trie and string").into()),
     };
     let mut current = node.as_ref();
     for ch in prefix.chars() {
@@ -1,6 +1,7 @@
 //! Union-Find (Disjoint Set Union) builtins:
 //!   uf_new, uf_find, uf_union, uf_connected
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 
@@ -8,7 +9,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let result = match name {
         "uf_new"      => Some(call_uf_new(interpreter, args)?),
         "uf_find"     => Some(call_uf_find(interpreter, args)?),
@@ -21,15 +22,15 @@ pub(super) fn try_call(
 
 // ----------------------------------------------------------------------------
 
-fn call_uf_new(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_uf_new(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("uf_new expects exactly 1 argument: size".to_string());
+        return Err(JError::argument_count("uf_new expects exactly 1 argument: size").into());
     }
 
     let size_val = interpreter.eval_node(&args[0])?;
     let size = match size_val {
         Value::Integer(n) if n >= 0 => n as usize,
-        _ => return Err("uf_new expects a non-negative integer size".to_string()),
+        _ => return Err(JError::invalid_argument("uf_new expects a non-negative integer size").into()),
     };
 
     let parent: Vec<usize> = (0..size).collect();
@@ -60,9 +61,9 @@ fn find(parent: &[usize], mut x: usize) -> usize {
 fn call_uf_find(
     interpreter: &mut Interpreter,
     args: &[AstNode],
-) -> Result<Value, String> {
+) -> JResult<Value> {
     if args.len() != 2 {
-        return Err("uf_find(uf, x) expects exactly 2 arguments".to_string());
+        return Err(JError::argument_count("uf_find(uf, x) expects exactly 2 arguments").into());
     }
 
     let uf_val  = interpreter.eval_node(&args[0])?;
@@ -73,11 +74,11 @@ fn call_uf_find(
             Value::UnionFind { parent, rank },
             Value::Integer(i),
         ) if i >= 0 => (parent, rank, i as usize),
-        _ => return Err("uf_find expects (UnionFind, non-negative integer)".to_string()),
+        _ => return Err(JError::invalid_argument("uf_find expects (UnionFind, non-negative integer)").into()),
     };
 
     if x >= parent.len() {
-        return Err(format!("uf_find: index out of bounds ({} >= {})", x, parent.len()));
+        return Err(JError::out_of_range(format!("uf_find: index out of bounds ({} >= {})", x, parent.len())).into());
     }
 
     let root = find_in_place(&mut parent, x);
@@ -91,9 +92,9 @@ fn call_uf_find(
 fn call_uf_union(
     interpreter: &mut Interpreter,
     args: &[AstNode],
-) -> Result<Value, String> {
+) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("uf_union(uf, a, b) expects exactly 3 arguments".to_string());
+        return Err(JError::argument_count("uf_union(uf, a, b) expects exactly 3 arguments").into());
     }
 
     let uf_val = interpreter.eval_node(&args[0])?;
@@ -102,23 +103,23 @@ fn call_uf_union(
 
     let (mut parent, mut rank) = match uf_val {
         Value::UnionFind { parent, rank } => (parent, rank),
-        _ => return Err("uf_union expects UnionFind as first argument".to_string()),
+        _ => return Err(JError::invalid_argument("uf_union expects UnionFind as first argument").into()),
     };
 
     let a = match a_val {
         Value::Integer(n) if n >= 0 => n as usize,
-        _ => return Err("uf_union: a must be non-negative integer".to_string()),
+        _ => return Err(JError::invalid_argument("uf_union: a must be non-negative integer").into()),
     };
     let b = match b_val {
         Value::Integer(n) if n >= 0 => n as usize,
-        _ => return Err("uf_union: b must be non-negative integer".to_string()),
+        _ => return Err(JError::invalid_argument("uf_union: b must be non-negative integer").into()),
     };
 
     if a >= parent.len() || b >= parent.len() {
-        return Err(format!(
+        return Err(JError::out_of_range(format!(
             "uf_union: index out of bounds (size = {}, a = {}, b = {})",
             parent.len(), a, b
-        ));
+        )).into());
     }
 
     let ra = find_in_place(&mut parent, a);
@@ -144,9 +145,9 @@ fn call_uf_union(
 fn call_uf_connected(
     interpreter: &mut Interpreter,
     args: &[AstNode],
-) -> Result<Value, String> {
+) -> JResult<Value> {
     if args.len() != 3 {
-        return Err("uf_connected(uf, a, b) expects exactly 3 arguments".to_string());
+        return Err(JError::argument_count("uf_connected(uf, a, b) expects exactly 3 arguments").into());
     }
 
     let uf_val = interpreter.eval_node(&args[0])?;
@@ -159,14 +160,14 @@ fn call_uf_connected(
             Value::Integer(ai),
             Value::Integer(bi),
         ) if ai >= 0 && bi >= 0 => (parent, ai as usize, bi as usize),
-        _ => return Err("uf_connected expects (UnionFind, non-neg int, non-neg int)".to_string()),
+        _ => return Err(JError::invalid_argument("uf_connected expects (UnionFind, non-neg int, non-neg int)").into()),
     };
 
     if a >= parent.len() || b >= parent.len() {
-        return Err(format!(
+        return Err(JError::out_of_range(format!(
             "uf_connected: index out of bounds (size = {}, a = {}, b = {})",
             parent.len(), a, b
-        ));
+        )).into());
     }
 
     let ra = find(&parent, a);
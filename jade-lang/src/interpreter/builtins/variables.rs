@@ -1,5 +1,6 @@
 //! Variable/value inspection builtins: len, size, type_of, is_empty.
 
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::parser::AstNode;
 
@@ -7,7 +8,7 @@ pub(super) fn try_call(
     interpreter: &mut Interpreter,
     name: &str,
     args: &[AstNode],
-) -> Result<Option<Value>, String> {
+) -> JResult<Option<Value>> {
     let v = match name {
         "len" => Some(call_len(interpreter, args)?),
         "size" => Some(call_size(interpreter, args)?),
@@ -18,22 +19,22 @@ pub(super) fn try_call(
     Ok(v)
 }
 
-fn call_len(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_len(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("len() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("len() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
         Value::String(s) => Ok(Value::Integer(s.len() as i64)),
         Value::List(list) => Ok(Value::Integer(list.len() as i64)),
         Value::Tuple(tuple) => Ok(Value::Integer(tuple.len() as i64)),
-        _ => Err("len() can only be called on strings, lists, and tuples".to_string()),
+        _ => Err(JError::invalid_argument("len() can only be called on strings, lists, and tuples").into()),
     }
 }
 
-fn call_size(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_size(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("size() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("size() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     match val {
@@ -45,16 +46,21 @@ fn call_size(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, S
         Value::Tree { children, .. } => Ok(Value::Integer(children.len() as i64)),
         Value::List(list) => Ok(Value::Integer(list.len() as i64)),
         Value::Dict(dict) => Ok(Value::Integer(dict.len() as i64)),
-        _ => Err("size() can only be called on collections".to_string()),
+        _ => Err(JError::invalid_argument("size() can only be called on collections").into()),
     }
 }
 
-fn call_type_of(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_type_of(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("type_of() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("type_of() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
-    let type_name = match val {
+    Ok(Value::String(type_of_name(&val).to_string()))
+}
+
+/// Type name reported by `type_of()`.
+pub(crate) fn type_of_name(val: &Value) -> &'static str {
+    match val {
         Value::Integer(_) => "int",
         Value::Float(_) => "float",
         Value::String(_) => "str",
@@ -125,10 +131,12 @@ fn call_type_of(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value
         Value::UnionFind { .. } => "union_find",
         Value::Trie(_) => "trie",
         Value::Memoized { .. } => "memoized",
+        Value::Decorated { .. } => "function",
         #[cfg(feature = "regex")]
         Value::Regex(_) => "regex",
         Value::RegexMatch(_) => "match",
         Value::MatchGroup(_) => "match_group",
+        Value::Error(_) => "error",
         Value::BoundMethod { .. } => "bound_method",
         Value::DateType => "date_type",
         Value::TimeType => "time_type",
@@ -138,13 +146,12 @@ fn call_type_of(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value
         Value::Pool(_) => "pool",
         Value::PoolRef { .. } => "pool_ref",
         Value::SmallVec { .. } => "smallvec",
-    };
-    Ok(Value::String(type_name.to_string()))
+    }
 }
 
-fn call_is_empty(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Value, String> {
+fn call_is_empty(interpreter: &mut Interpreter, args: &[AstNode]) -> JResult<Value> {
     if args.len() != 1 {
-        return Err("is_empty() expects exactly 1 argument".to_string());
+        return Err(JError::argument_count("is_empty() expects exactly 1 argument").into());
     }
     let val = interpreter.eval_node(&args[0])?;
     let empty = match val {
@@ -160,7 +167,7 @@ fn call_is_empty(interpreter: &mut Interpreter, args: &[AstNode]) -> Result<Valu
         Value::Tuple(tuple) => tuple.is_empty(),
         Value::Vector(vec) => vec.is_empty(),
         Value::Matrix(mat) => mat.is_empty(),
-        _ => return Err("is_empty() can only be called on collections".to_string()),
+        _ => return Err(JError::invalid_argument("is_empty() can only be called on collections").into()),
     };
     Ok(Value::Boolean(empty))
 }
@@ -3,35 +3,153 @@
 use super::*;
 
 impl Interpreter {
-    pub(super) fn call_function(&mut self, name: &str, args: &[AstNode]) -> Result<Value, String> {
-        // Dispatch to categorized builtins (math, algo, DSA) first
-        if let Some(v) = super::builtins::try_call(self, name, args)? {
+    pub(super) fn call_function(&mut self, name: &str, args: &[AstNode]) -> JResult<Value> {
+        // A profiler sees builtins as leaf calls, and drops the frame if `name` is not one
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.enter(name, true);
+        }
+        let builtin = self.call_builtin(name, args);
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.exit(!matches!(builtin, Ok(None)));
+        }
+        if let Some(v) = builtin? {
             return Ok(v);
         }
-        // Handle remaining built-in functions
-        match name {
-            "out" => return self.call_out(args),
+        // If not built-in, look for user-defined function (or @once/MirrorDispatch wrapper)
+        let func_val = self.get_variable(name)?;
+        let eval_args: Vec<Value> = args
+            .iter()
+            .map(|a| self.eval_node(a))
+            .collect::<Result<Vec<_>, _>>()?;
+        #[cfg(feature = "jit")]
+        if let Some(result) = self.call_native(name, &func_val, &eval_args) {
+            return Ok(result);
+        }
+        self.call_value_with_args(func_val, &eval_args, None)
+    }
+
+    /// Categorized builtins (math, algo, DSA) first, then the remaining built-in functions;
+    /// `None` when `name` is not a builtin.
+    fn call_builtin(&mut self, name: &str, args: &[AstNode]) -> JResult<Option<Value>> {
+        if !Self::is_builtin(name) {
+            return Ok(None);
+        }
+        match super::builtins::try_call(self, name, args)? {
+            Some(v) => Ok(Some(v)),
+            None => self.call_remaining_builtin(name, args),
+        }
+    }
+
+    /// Run a call to a user function as native code once it is hot (`jit` feature); `None`
+    /// means interpret it: not numeric, not hot yet, or the native code bailed out.
+    #[cfg(feature = "jit")]
+    fn call_native(&mut self, name: &str, callee: &Value, args: &[Value]) -> Option<Value> {
+        use crate::jit::native::Ty;
+        let Value::Function { params, body, env, .. } = callee else {
+            return None;
+        };
+        let depth_left = self.max_call_depth.saturating_sub(self.call_depth);
+        let native = self.native.as_mut()?;
+        let hot = native.record_call(name);
+        let (types, words): (Vec<Ty>, Vec<i64>) = args
+            .iter()
+            .map(Ty::of)
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+
+        let index = match native.find(name, params, body, &types) {
+            Some((index, recursive)) => {
+                if recursive && !self.names_function(name, params, body, env) {
+                    return None;
+                }
+                index
+            }
+            None => {
+                if !hot || native.is_rejected(name, &types) {
+                    return None;
+                }
+                let self_calls = self.names_function(name, params, body, env);
+                let native = self.native.as_mut()?;
+                native.compile(name, params, body, &types, self_calls).ok()?
+            }
+        };
+        self.native.as_mut()?.run(name, index, &words, depth_left)
+    }
+
+    /// Whether `name`, looked up from inside a function with captured `env`, is that function.
+    #[cfg(feature = "jit")]
+    fn names_function(&self, name: &str, params: &[String], body: &AstNode, env: &Env) -> bool {
+        if params.iter().any(|param| param == name) || self.statics.contains_key(name) {
+            return false;
+        }
+        let same = |value: &Value| {
+            matches!(value, Value::Function { params: p, body: b, .. } if p == params && **b == *body)
+        };
+        for scope in env.0.iter().rev() {
+            if let Some(value) = scope.borrow().get(name) {
+                return same(value);
+            }
+        }
+        self.globals.get(name).is_some_and(same)
+    }
+
+    /// Call a builtin on already evaluated arguments (used by the bytecode VM); `None` when
+    /// `name` is not a builtin. Arguments are bound to hidden names in a scope of their own.
+    pub(crate) fn call_builtin_values(
+        &mut self,
+        name: &str,
+        args: &[Value],
+    ) -> JResult<Option<Value>> {
+        let mut frame = HashMap::new();
+        let arg_nodes: Vec<AstNode> = args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| {
+                let hidden = format!("\0arg{}", i);
+                frame.insert(hidden.clone(), arg.clone());
+                AstNode::Identifier(hidden, Span::default())
+            })
+            .collect();
+        self.locals.push(Rc::new(RefCell::new(frame)));
+        let result = self.call_builtin(name, &arg_nodes);
+        self.locals.pop();
+        result
+    }
+
+    /// Whether calls to `name` resolve to a builtin (which shadows user functions).
+    pub(crate) fn is_builtin(name: &str) -> bool {
+        super::builtins::BUILTIN_NAMES.binary_search(&name).is_ok()
+    }
+
+    /// Built-in functions not in `builtins/`; `None` when `name` is not one of them.
+    /// Out of line because this match has a very large stack frame.
+    #[inline(never)]
+    fn call_remaining_builtin(&mut self, name: &str, args: &[AstNode]) -> JResult<Option<Value>> {
+        let result = match name {
+            "out" => self.call_out(args),
 
             "sleep" => {
                 if args.len() != 1 {
-                    return Err("sleep() expects exactly 1 argument (seconds)".to_string());
+                    return Err(JError::argument_count("sleep() expects exactly 1 argument (seconds)").into());
                 }
                 let val = self.eval_node(&args[0])?;
                 let seconds = match val {
                     Value::Integer(i) => i as f64,
                     Value::Float(f) => f,
-                    _ => return Err("sleep() expects a numeric argument".to_string()),
+                    _ => return Err(JError::invalid_argument("sleep() expects a numeric argument").into()),
                 };
                 if seconds < 0.0 {
-                    return Err("sleep() duration must be non-negative".to_string());
+                    return Err(JError::invalid_argument("sleep() duration must be non-negative").into());
                 }
-                std::thread::sleep(std::time::Duration::from_secs_f64(seconds));
+                self.clock.sleep(std::time::Duration::from_secs_f64(seconds));
+                self.run_due_timers()?;
                 Ok(Value::None)
             }
 
             "varType" => {
                 if args.len() != 1 {
-                    return Err("varType() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("varType() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
                 let type_name = match val {
@@ -105,8 +223,10 @@ impl Interpreter {
                     Value::UnionFind { .. } => "union_find",
                     Value::Trie(_) => "trie",
                     Value::Memoized { .. } => "memoized",
+                    Value::Decorated { .. } => "function",
                     Value::RegexMatch(_) => "match",
                     Value::MatchGroup(_) => "match_group",
+                    Value::Error(_) => "error",
                     Value::BoundMethod { .. } => "bound_method",
                     Value::DateType => "date_type",
                     Value::TimeType => "time_type",
@@ -126,7 +246,7 @@ impl Interpreter {
                 // Create a new instance of a class
                 // Usage: new(ClassName) or new(ClassName, field1, value1, field2, value2, ...)
                 if args.is_empty() {
-                    return Err("new() expects at least 1 argument (class name)".to_string());
+                    return Err(JError::argument_count("new() expects at least 1 argument (class name)").into());
                 }
 
                 let class_val = self.eval_node(&args[0])?;
@@ -147,8 +267,7 @@ impl Interpreter {
                         if args.len() > 1 {
                             if !(args.len() - 1).is_multiple_of(2) {
                                 return Err(
-                                    "new() expects pairs of field names and values after class"
-                                        .to_string(),
+                                    JError::invalid_argument("new() expects pairs of field names and values after class").into(),
                                 );
                             }
 
@@ -159,7 +278,7 @@ impl Interpreter {
                                 if let Value::String(field_name) = field_name_val {
                                     instance_fields.insert(field_name, field_value);
                                 } else {
-                                    return Err("Field names must be strings in new()".to_string());
+                                    return Err(JError::invalid_argument("Field names must be strings in new()").into());
                                 }
                             }
                         }
@@ -186,14 +305,14 @@ impl Interpreter {
                             _ => Ok(instance),
                         }
                     }
-                    _ => Err(format!("new() expects a class, got {:?}", class_val)),
+                    _ => Err(JError::invalid_argument(format!("new() expects a class, got {:?}", class_val)).into()),
                 }
             }
 
             "channel" => {
                 // Create a new channel
                 if !args.is_empty() {
-                    return Err("channel() expects no arguments".to_string());
+                    return Err(JError::argument_count("channel() expects no arguments").into());
                 }
                 let channel_id = self.next_future_id as u64;
                 self.next_future_id += 1;
@@ -204,7 +323,7 @@ impl Interpreter {
                 // Spawn a task (similar to TaskSpawn but as a function)
                 if args.len() != 1 {
                     return Err(
-                        "spawn() expects exactly 1 argument (function or block)".to_string()
+                        JError::argument_count("spawn() expects exactly 1 argument (function or block)").into()
                     );
                 }
                 let task_id = self.next_future_id;
@@ -228,7 +347,7 @@ impl Interpreter {
                         }
                         Ok(Value::List(range))
                     } else {
-                        Err("range() expects integer argument".to_string())
+                        Err(JError::invalid_argument("range() expects integer argument").into())
                     }
                 }
                 2 => {
@@ -241,7 +360,7 @@ impl Interpreter {
                         }
                         Ok(Value::List(range))
                     } else {
-                        Err("range() expects integer arguments".to_string())
+                        Err(JError::invalid_argument("range() expects integer arguments").into())
                     }
                 }
                 3 => {
@@ -259,27 +378,27 @@ impl Interpreter {
                         }
                         Ok(Value::List(range))
                     } else {
-                        Err("range() expects integer arguments".to_string())
+                        Err(JError::invalid_argument("range() expects integer arguments").into())
                     }
                 }
-                _ => Err("range() expects 1, 2, or 3 arguments".to_string()),
+                _ => Err(JError::argument_count("range() expects 1, 2, or 3 arguments").into()),
             },
 
             "interval" => {
                 if args.len() != 2 {
-                    return Err("interval() expects exactly 2 arguments (start, end)".to_string());
+                    return Err(JError::argument_count("interval() expects exactly 2 arguments (start, end)").into());
                 }
                 let start_val = self.eval_node(&args[0])?;
                 let end_val = self.eval_node(&args[1])?;
                 match (start_val, end_val) {
                     (Value::Integer(start), Value::Integer(end)) => Ok(Value::Interval(start, end)),
-                    _ => Err("interval() expects integer arguments".to_string()),
+                    _ => Err(JError::invalid_argument("interval() expects integer arguments").into()),
                 }
             }
 
             "sum" => {
                 if args.len() != 1 {
-                    return Err("sum() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("sum() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
                 match val {
@@ -288,19 +407,19 @@ impl Interpreter {
                         for item in list {
                             match item {
                                 Value::Integer(i) => sum += i,
-                                _ => return Err("sum() can only sum integers".to_string()),
+                                _ => return Err(JError::invalid_argument("sum() can only sum integers").into()),
                             }
                         }
                         Ok(Value::Integer(sum))
                     }
-                    _ => Err("sum() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("sum() can only be called on lists").into()),
                 }
             }
 
             // Advanced array type constructors
             "span" => {
                 if args.len() != 1 {
-                    return Err("span() expects exactly 1 argument (list)".to_string());
+                    return Err(JError::argument_count("span() expects exactly 1 argument (list)").into());
                 }
                 let val = self.eval_node(&args[0])?;
                 match val {
@@ -312,13 +431,13 @@ impl Interpreter {
                             end: len,
                         })
                     }
-                    _ => Err("span() requires a list argument".to_string()),
+                    _ => Err(JError::invalid_argument("span() requires a list argument").into()),
                 }
             }
 
             "mut_span" => {
                 if args.len() != 1 {
-                    return Err("mut_span() expects exactly 1 argument (list)".to_string());
+                    return Err(JError::argument_count("mut_span() expects exactly 1 argument (list)").into());
                 }
                 let val = self.eval_node(&args[0])?;
                 match val {
@@ -330,19 +449,19 @@ impl Interpreter {
                             end: len,
                         })
                     }
-                    _ => Err("mut_span() requires a list argument".to_string()),
+                    _ => Err(JError::invalid_argument("mut_span() requires a list argument").into()),
                 }
             }
 
             "chunk" => {
                 if args.is_empty() || args.len() > 2 {
-                    return Err("chunk() expects 1-2 arguments (list, [size])".to_string());
+                    return Err(JError::argument_count("chunk() expects 1-2 arguments (list, [size])").into());
                 }
                 let val = self.eval_node(&args[0])?;
                 let chunk_size = if args.len() == 2 {
                     match self.eval_node(&args[1])? {
                         Value::Integer(size) => size as usize,
-                        _ => return Err("chunk size must be an integer".to_string()),
+                        _ => return Err(JError::invalid_argument("chunk size must be an integer").into()),
                     }
                 } else {
                     1
@@ -354,14 +473,14 @@ impl Interpreter {
                         chunk_size,
                         current_index: 0,
                     }),
-                    _ => Err("chunk() requires a list argument".to_string()),
+                    _ => Err(JError::invalid_argument("chunk() requires a list argument").into()),
                 }
             }
 
             "sparse" => {
                 if args.is_empty() || args.len() > 2 {
                     return Err(
-                        "sparse() expects 1-2 arguments (size or list, [default])".to_string()
+                        JError::argument_count("sparse() expects 1-2 arguments (size or list, [default])").into()
                     );
                 }
                 let val = self.eval_node(&args[0])?;
@@ -390,19 +509,19 @@ impl Interpreter {
                             size: list.len(),
                         })
                     }
-                    _ => Err("sparse() requires an integer size or list".to_string()),
+                    _ => Err(JError::invalid_argument("sparse() requires an integer size or list").into()),
                 }
             }
 
             "ring" => {
                 if args.len() != 1 {
-                    return Err("ring() expects exactly 1 argument (capacity)".to_string());
+                    return Err(JError::argument_count("ring() expects exactly 1 argument (capacity)").into());
                 }
                 let val = self.eval_node(&args[0])?;
                 match val {
                     Value::Integer(capacity) => {
                         if capacity <= 0 {
-                            return Err("ring capacity must be positive".to_string());
+                            return Err(JError::invalid_argument("ring capacity must be positive").into());
                         }
                         Ok(Value::Ring {
                             buffer: vec![Value::None; capacity as usize],
@@ -411,7 +530,7 @@ impl Interpreter {
                             size: 0,
                         })
                     }
-                    _ => Err("ring() requires an integer capacity".to_string()),
+                    _ => Err(JError::invalid_argument("ring() requires an integer capacity").into()),
                 }
             }
 
@@ -422,7 +541,7 @@ impl Interpreter {
                     let pipeline_val = self.get_variable("__pipeline__").unwrap_or(Value::None);
                     if matches!(pipeline_val, Value::None) {
                         return Err(
-                            "map() in pipeline requires a value from the left side".to_string()
+                            JError::invalid_argument("map() in pipeline requires a value from the left side").into()
                         );
                     }
                     let func_val = self.eval_node(&args[0])?;
@@ -434,7 +553,7 @@ impl Interpreter {
                     (list_val, func_val)
                 } else {
                     return Err(
-                        "map() expects 1 argument in pipeline or 2 arguments normally".to_string(),
+                        JError::argument_count("map() expects 1 argument in pipeline or 2 arguments normally").into(),
                     );
                 };
 
@@ -444,32 +563,18 @@ impl Interpreter {
                         for item in list {
                             // Call the function with the item as argument
                             let mapped_val = match &func_val {
-                                Value::Function { params, body, .. } => {
-                                    // Create new scope for lambda
-                                    self.push_scope();
-
-                                    // Set parameter
-                                    if params.len() == 1 {
-                                        self.set_variable(params[0].clone(), item);
-                                    } else {
-                                        self.pop_scope();
-                                        return Err(format!(
+                                Value::Function { name, params, body, env } => {
+                                    if params.len() != 1 {
+                                        return Err(JError::invalid_argument(format!(
                                             "map() lambda must have exactly 1 parameter, got {}",
                                             params.len()
-                                        ));
+                                        )).into());
                                     }
-
-                                    // Execute function body
-                                    let result = self.eval_node(body)?;
-
-                                    // Restore scope
-                                    self.pop_scope();
-
-                                    result
+                                    self.call_function_internal(name, &[item], params, body, None, env)?
                                 }
                                 _ => {
                                     return Err(
-                                        "map() expects a function as second argument".to_string()
+                                        JError::invalid_argument("map() expects a function as second argument").into()
                                     )
                                 }
                             };
@@ -477,13 +582,13 @@ impl Interpreter {
                         }
                         Ok(Value::List(result))
                     }
-                    _ => Err("map() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("map() can only be called on lists").into()),
                 }
             }
 
             "unique" => {
                 if args.len() != 1 {
-                    return Err("unique() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("unique() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
@@ -497,13 +602,13 @@ impl Interpreter {
                         }
                         Ok(Value::List(unique_list))
                     }
-                    _ => Err("unique() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("unique() can only be called on lists").into()),
                 }
             }
 
             "reverse" => {
                 if args.len() != 1 {
-                    return Err("reverse() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("reverse() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
@@ -521,15 +626,14 @@ impl Interpreter {
                         Ok(Value::Vector(vec))
                     }
                     _ => {
-                        Err("reverse() can only be called on lists, strings, or vectors"
-                            .to_string())
+                        Err(JError::invalid_argument("reverse() can only be called on lists, strings, or vectors").into())
                     }
                 }
             }
 
             "sort" => {
                 if args.len() != 1 {
-                    return Err("sort() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("sort() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
@@ -556,14 +660,14 @@ impl Interpreter {
                         vec.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
                         Ok(Value::Vector(vec))
                     }
-                    _ => Err("sort() can only be called on lists or vectors".to_string()),
+                    _ => Err(JError::invalid_argument("sort() can only be called on lists or vectors").into()),
                 }
             }
 
             "group_by" => {
                 if args.len() != 2 {
                     return Err(
-                        "group_by() expects exactly 2 arguments (list, key_function)".to_string(),
+                        JError::argument_count("group_by() expects exactly 2 arguments (list, key_function)").into(),
                     );
                 }
                 let list_val = self.eval_node(&args[0])?;
@@ -575,22 +679,24 @@ impl Interpreter {
                     for item in items {
                         // Call the key function with the item
                         let key_val = match &key_fn {
-                            Value::Function { params, body, .. } => {
+                            Value::Function { name, params, body, env } => {
                                 if params.len() != 1 {
                                     return Err(
-                                        "group_by key function must take exactly 1 parameter"
-                                            .to_string(),
+                                        JError::invalid_argument("group_by key function must take exactly 1 parameter").into(),
                                     );
                                 }
-                                self.push_scope();
-                                self.set_variable(params[0].clone(), item.clone());
-                                let result = self.eval_node(body)?;
-                                self.pop_scope();
-                                result
+                                self.call_function_internal(
+                                    name,
+                                    std::slice::from_ref(&item),
+                                    params,
+                                    body,
+                                    None,
+                                    env,
+                                )?
                             }
                             _ => {
                                 return Err(
-                                    "group_by requires a function as second argument".to_string()
+                                    JError::invalid_argument("group_by requires a function as second argument").into()
                                 )
                             }
                         };
@@ -607,14 +713,14 @@ impl Interpreter {
                     }
                     Ok(Value::Dict(result))
                 } else {
-                    Err("group_by requires a list as first argument".to_string())
+                    Err(JError::invalid_argument("group_by requires a list as first argument").into())
                 }
             }
 
             "partition" => {
                 if args.len() != 2 {
                     return Err(
-                        "partition() expects exactly 2 arguments (list, predicate)".to_string()
+                        JError::argument_count("partition() expects exactly 2 arguments (list, predicate)").into()
                     );
                 }
                 let list_val = self.eval_node(&args[0])?;
@@ -627,22 +733,24 @@ impl Interpreter {
                     for item in items {
                         // Call the predicate function with the item
                         let pred_result = match &predicate {
-                            Value::Function { params, body, .. } => {
+                            Value::Function { name, params, body, env } => {
                                 if params.len() != 1 {
                                     return Err(
-                                        "partition predicate must take exactly 1 parameter"
-                                            .to_string(),
+                                        JError::invalid_argument("partition predicate must take exactly 1 parameter").into(),
                                     );
                                 }
-                                self.push_scope();
-                                self.set_variable(params[0].clone(), item.clone());
-                                let result = self.eval_node(body)?;
-                                self.pop_scope();
-                                result
+                                self.call_function_internal(
+                                    name,
+                                    std::slice::from_ref(&item),
+                                    params,
+                                    body,
+                                    None,
+                                    env,
+                                )?
                             }
                             _ => {
                                 return Err(
-                                    "partition requires a function as second argument".to_string()
+                                    JError::invalid_argument("partition requires a function as second argument").into()
                                 )
                             }
                         };
@@ -651,7 +759,7 @@ impl Interpreter {
                             Value::Boolean(true) => true_items.push(item),
                             Value::Boolean(false) => false_items.push(item),
                             _ => {
-                                return Err("partition predicate must return a boolean".to_string())
+                                return Err(JError::invalid_argument("partition predicate must return a boolean").into())
                             }
                         }
                     }
@@ -662,20 +770,20 @@ impl Interpreter {
                         Value::List(false_items),
                     ]))
                 } else {
-                    Err("partition requires a list as first argument".to_string())
+                    Err(JError::invalid_argument("partition requires a list as first argument").into())
                 }
             }
 
             "min" => {
                 if args.len() != 1 {
-                    return Err("min() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("min() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
                 match val {
                     Value::List(list) => {
                         if list.is_empty() {
-                            return Err("Cannot find min of empty list".to_string());
+                            return Err(JError::empty_collection("Cannot find min of empty list").into());
                         }
                         let mut min_val = &list[0];
                         for item in &list[1..] {
@@ -707,27 +815,27 @@ impl Interpreter {
                     }
                     Value::Vector(vec) => {
                         if vec.is_empty() {
-                            return Err("Cannot find min of empty vector".to_string());
+                            return Err(JError::empty_collection("Cannot find min of empty vector").into());
                         }
                         let min_val = vec
                             .iter()
                             .fold(vec[0], |acc, &x| if x < acc { x } else { acc });
                         Ok(Value::Float(min_val))
                     }
-                    _ => Err("min() can only be called on lists or vectors".to_string()),
+                    _ => Err(JError::invalid_argument("min() can only be called on lists or vectors").into()),
                 }
             }
 
             "max" => {
                 if args.len() != 1 {
-                    return Err("max() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("max() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
                 match val {
                     Value::List(list) => {
                         if list.is_empty() {
-                            return Err("Cannot find max of empty list".to_string());
+                            return Err(JError::empty_collection("Cannot find max of empty list").into());
                         }
                         let mut max_val = &list[0];
                         for item in &list[1..] {
@@ -759,20 +867,20 @@ impl Interpreter {
                     }
                     Value::Vector(vec) => {
                         if vec.is_empty() {
-                            return Err("Cannot find max of empty vector".to_string());
+                            return Err(JError::empty_collection("Cannot find max of empty vector").into());
                         }
                         let max_val = vec
                             .iter()
                             .fold(vec[0], |acc, &x| if x > acc { x } else { acc });
                         Ok(Value::Float(max_val))
                     }
-                    _ => Err("max() can only be called on lists or vectors".to_string()),
+                    _ => Err(JError::invalid_argument("max() can only be called on lists or vectors").into()),
                 }
             }
 
             "count" => {
                 if args.len() != 2 {
-                    return Err("count() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("count() expects exactly 2 arguments").into());
                 }
                 let container_val = self.eval_node(&args[0])?;
                 let item_val = self.eval_node(&args[1])?;
@@ -793,16 +901,16 @@ impl Interpreter {
                             let count = s.chars().filter(|&c| c == ch).count();
                             Ok(Value::Integer(count as i64))
                         } else {
-                            Err("count() on string requires string or char argument".to_string())
+                            Err(JError::invalid_argument("count() on string requires string or char argument").into())
                         }
                     }
-                    _ => Err("count() can only be called on lists or strings".to_string()),
+                    _ => Err(JError::invalid_argument("count() can only be called on lists or strings").into()),
                 }
             }
 
             "flatten" => {
                 if args.len() != 1 {
-                    return Err("flatten() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("flatten() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
@@ -819,13 +927,13 @@ impl Interpreter {
                         }
                         Ok(Value::List(flattened))
                     }
-                    _ => Err("flatten() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("flatten() can only be called on lists").into()),
                 }
             }
 
             "zip" => {
                 if args.len() != 2 {
-                    return Err("zip() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("zip() expects exactly 2 arguments").into());
                 }
                 let list1_val = self.eval_node(&args[0])?;
                 let list2_val = self.eval_node(&args[1])?;
@@ -838,13 +946,13 @@ impl Interpreter {
                         }
                         Ok(Value::List(zipped))
                     }
-                    _ => Err("zip() expects two lists".to_string()),
+                    _ => Err(JError::invalid_argument("zip() expects two lists").into()),
                 }
             }
 
             "enumerate" => {
                 if args.len() != 1 {
-                    return Err("enumerate() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("enumerate() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
@@ -857,14 +965,14 @@ impl Interpreter {
                         }
                         Ok(Value::List(enumerated))
                     }
-                    _ => Err("enumerate() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("enumerate() can only be called on lists").into()),
                 }
             }
 
             "union" => {
                 if args.len() != 2 {
                     return Err(
-                        "union() expects exactly 2 arguments: union(list1, list2)".to_string()
+                        JError::argument_count("union() expects exactly 2 arguments: union(list1, list2)").into()
                     );
                 }
                 let list1_val = self.eval_node(&args[0])?;
@@ -894,15 +1002,14 @@ impl Interpreter {
 
                         Ok(Value::List(result))
                     }
-                    _ => Err("union() expects two lists".to_string()),
+                    _ => Err(JError::invalid_argument("union() expects two lists").into()),
                 }
             }
 
             "intersect" => {
                 if args.len() != 2 {
                     return Err(
-                        "intersect() expects exactly 2 arguments: intersect(list1, list2)"
-                            .to_string(),
+                        JError::argument_count("intersect() expects exactly 2 arguments: intersect(list1, list2)").into(),
                     );
                 }
                 let list1_val = self.eval_node(&args[0])?;
@@ -930,15 +1037,14 @@ impl Interpreter {
 
                         Ok(Value::List(result))
                     }
-                    _ => Err("intersect() expects two lists".to_string()),
+                    _ => Err(JError::invalid_argument("intersect() expects two lists").into()),
                 }
             }
 
             "difference" => {
                 if args.len() != 2 {
                     return Err(
-                        "difference() expects exactly 2 arguments: difference(list1, list2)"
-                            .to_string(),
+                        JError::argument_count("difference() expects exactly 2 arguments: difference(list1, list2)").into(),
                     );
                 }
                 let list1_val = self.eval_node(&args[0])?;
@@ -966,13 +1072,13 @@ impl Interpreter {
 
                         Ok(Value::List(result))
                     }
-                    _ => Err("difference() expects two lists".to_string()),
+                    _ => Err(JError::invalid_argument("difference() expects two lists").into()),
                 }
             }
 
             "symmetric_diff" => {
                 if args.len() != 2 {
-                    return Err("symmetric_diff() expects exactly 2 arguments: symmetric_diff(list1, list2)".to_string());
+                    return Err(JError::argument_count("symmetric_diff() expects exactly 2 arguments: symmetric_diff(list1, list2)").into());
                 }
                 let list1_val = self.eval_node(&args[0])?;
                 let list2_val = self.eval_node(&args[1])?;
@@ -1011,7 +1117,7 @@ impl Interpreter {
 
                         Ok(Value::List(result))
                     }
-                    _ => Err("symmetric_diff() expects two lists".to_string()),
+                    _ => Err(JError::invalid_argument("symmetric_diff() expects two lists").into()),
                 }
             }
 
@@ -1022,7 +1128,7 @@ impl Interpreter {
                     let pipeline_val = self.get_variable("__pipeline__").unwrap_or(Value::None);
                     if matches!(pipeline_val, Value::None) {
                         return Err(
-                            "filter() in pipeline requires a value from the left side".to_string()
+                            JError::invalid_argument("filter() in pipeline requires a value from the left side").into()
                         );
                     }
                     let func_val = self.eval_node(&args[0])?;
@@ -1034,8 +1140,7 @@ impl Interpreter {
                     (list_val, func_val)
                 } else {
                     return Err(
-                        "filter() expects 1 argument in pipeline or 2 arguments normally"
-                            .to_string(),
+                        JError::argument_count("filter() expects 1 argument in pipeline or 2 arguments normally").into(),
                     );
                 };
 
@@ -1045,32 +1150,17 @@ impl Interpreter {
                         for item in list {
                             // Call the function with the item as argument
                             let filter_result = match &func_val {
-                                Value::Function { params, body, .. } => {
-                                    // Create new scope for lambda
-                                    self.push_scope();
-
-                                    // Set parameter
-                                    if params.len() == 1 {
-                                        self.set_variable(params[0].clone(), item.clone());
-                                    } else {
-                                        self.pop_scope();
-                                        return Err(format!(
+                                Value::Function { name, params, body, env } => {
+                                    if params.len() != 1 {
+                                        return Err(JError::invalid_argument(format!(
                                             "filter() lambda must have exactly 1 parameter, got {}",
                                             params.len()
-                                        ));
+                                        )).into());
                                     }
-
-                                    // Execute function body
-                                    let result = self.eval_node(body)?;
-
-                                    // Restore scope
-                                    self.pop_scope();
-
-                                    result
+                                    self.call_function_internal(name, std::slice::from_ref(&item), params, body, None, env)?
                                 }
                                 _ => {
-                                    return Err("filter() expects a function as second argument"
-                                        .to_string())
+                                    return Err(JError::invalid_argument("filter() expects a function as second argument").into())
                                 }
                             };
 
@@ -1080,7 +1170,7 @@ impl Interpreter {
                         }
                         Ok(Value::List(result))
                     }
-                    _ => Err("filter() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("filter() can only be called on lists").into()),
                 }
             }
 
@@ -1094,7 +1184,7 @@ impl Interpreter {
                     // Get first element as initial value
                     let initial = match &list_val {
                         Value::List(list) if !list.is_empty() => list[0].clone(),
-                        _ => return Err("reduce() requires a non-empty list".to_string()),
+                        _ => return Err(JError::invalid_argument("reduce() requires a non-empty list").into()),
                     };
 
                     (list_val, initial, func_val)
@@ -1105,7 +1195,7 @@ impl Interpreter {
                     let func_val = self.eval_node(&args[2])?;
                     (list_val, initial_val, func_val)
                 } else {
-                    return Err("reduce() expects 2 or 3 arguments".to_string());
+                    return Err(JError::argument_count("reduce() expects 2 or 3 arguments").into());
                 };
 
                 match list_val {
@@ -1116,24 +1206,22 @@ impl Interpreter {
                         for item in list.iter().skip(start_idx) {
                             // Call the function with accumulator and item
                             let result = match &func_val {
-                                Value::Function { params, body, .. } => {
-                                    self.push_scope();
-
-                                    if params.len() == 2 {
-                                        self.set_variable(params[0].clone(), accumulator.clone());
-                                        self.set_variable(params[1].clone(), item.clone());
-                                    } else {
-                                        self.pop_scope();
-                                        return Err(format!("reduce() lambda must have exactly 2 parameters, got {}", params.len()));
+                                Value::Function { name, params, body, env } => {
+                                    if params.len() != 2 {
+                                        return Err(JError::invalid_argument(format!("reduce() lambda must have exactly 2 parameters, got {}", params.len())).into());
                                     }
-
-                                    let result = self.eval_node(body)?;
-                                    self.pop_scope();
-                                    result
+                                    self.call_function_internal(
+                                        name,
+                                        &[accumulator.clone(), item.clone()],
+                                        params,
+                                        body,
+                                        None,
+                                        env,
+                                    )?
                                 }
                                 _ => {
                                     return Err(
-                                        "reduce() expects a function as last argument".to_string()
+                                        JError::invalid_argument("reduce() expects a function as last argument").into()
                                     )
                                 }
                             };
@@ -1143,13 +1231,13 @@ impl Interpreter {
 
                         Ok(accumulator)
                     }
-                    _ => Err("reduce() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("reduce() can only be called on lists").into()),
                 }
             }
 
             "push" => {
                 if args.len() != 2 {
-                    return Err("push() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("push() expects exactly 2 arguments").into());
                 }
                 let list_val = self.eval_node(&args[0])?;
                 let item_val = self.eval_node(&args[1])?;
@@ -1159,13 +1247,13 @@ impl Interpreter {
                         list.push(item_val);
                         Ok(Value::List(list))
                     }
-                    _ => Err("push() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("push() can only be called on lists").into()),
                 }
             }
 
             "pop" => {
                 if args.len() != 1 {
-                    return Err("pop() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("pop() expects exactly 1 argument").into());
                 }
                 let list_val = self.eval_node(&args[0])?;
 
@@ -1174,16 +1262,16 @@ impl Interpreter {
                         if let Some(item) = list.pop() {
                             Ok(item)
                         } else {
-                            Err("Cannot pop from empty list".to_string())
+                            Err(JError::empty_collection("Cannot pop from empty list").into())
                         }
                     }
-                    _ => Err("pop() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("pop() can only be called on lists").into()),
                 }
             }
 
             "append" => {
                 if args.len() != 2 {
-                    return Err("append() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("append() expects exactly 2 arguments").into());
                 }
                 let list1_val = self.eval_node(&args[0])?;
                 let list2_val = self.eval_node(&args[1])?;
@@ -1193,13 +1281,13 @@ impl Interpreter {
                         list1.extend(list2);
                         Ok(Value::List(list1))
                     }
-                    _ => Err("append() can only be called on two lists".to_string()),
+                    _ => Err(JError::invalid_argument("append() can only be called on two lists").into()),
                 }
             }
 
             "contains" => {
                 if args.len() != 2 {
-                    return Err("contains() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("contains() expects exactly 2 arguments").into());
                 }
                 let container_val = self.eval_node(&args[0])?;
                 let item_val = self.eval_node(&args[1])?;
@@ -1208,7 +1296,7 @@ impl Interpreter {
                     Value::List(list) => {
                         for list_item in list {
                             if self.values_equal(&list_item, &item_val) {
-                                return Ok(Value::Boolean(true));
+                                return Ok(Some(Value::Boolean(true)));
                             }
                         }
                         Ok(Value::Boolean(false))
@@ -1222,14 +1310,14 @@ impl Interpreter {
                             #[cfg(feature = "regex")]
                             {
                                 if let Value::Regex(r) = &item_val {
-                                    return Ok(Value::Boolean(
+                                    return Ok(Some(Value::Boolean(
                                         super::builtins::regex_builtins::regex_contains(&s, r),
-                                    ));
+                                    )));
                                 }
-                                return Err("contains() on string requires string, char, or regex argument".to_string());
+                                return Err(JError::invalid_argument("contains() on string requires string, char, or regex argument").into());
                             }
                             #[cfg(not(feature = "regex"))]
-                            Err("contains() on string requires string or char argument".to_string())
+                            Err("contains() on string requires string or char argument".into())
                         }
                     }
                     Value::Dict(dict) => {
@@ -1238,18 +1326,18 @@ impl Interpreter {
                         } else if let Value::Integer(i) = item_val {
                             Ok(Value::Boolean(dict.contains_key(&i.to_string())))
                         } else {
-                            Err("contains() on dict requires string or integer key".to_string())
+                            Err(JError::invalid_argument("contains() on dict requires string or integer key").into())
                         }
                     }
                     _ => {
-                        Err("contains() can only be called on lists, strings, or dicts".to_string())
+                        Err(JError::invalid_argument("contains() can only be called on lists, strings, or dicts").into())
                     }
                 }
             }
 
             "keys" => {
                 if args.len() != 1 {
-                    return Err("keys() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("keys() expects exactly 1 argument").into());
                 }
                 let dict_val = self.eval_node(&args[0])?;
 
@@ -1259,13 +1347,13 @@ impl Interpreter {
                             dict.keys().map(|k| Value::String(k.clone())).collect();
                         Ok(Value::List(keys))
                     }
-                    _ => Err("keys() can only be called on dictionaries".to_string()),
+                    _ => Err(JError::invalid_argument("keys() can only be called on dictionaries").into()),
                 }
             }
 
             "values" => {
                 if args.len() != 1 {
-                    return Err("values() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("values() expects exactly 1 argument").into());
                 }
                 let dict_val = self.eval_node(&args[0])?;
 
@@ -1274,14 +1362,14 @@ impl Interpreter {
                         let values: Vec<Value> = dict.values().cloned().collect();
                         Ok(Value::List(values))
                     }
-                    _ => Err("values() can only be called on dictionaries".to_string()),
+                    _ => Err(JError::invalid_argument("values() can only be called on dictionaries").into()),
                 }
             }
 
             // Dictionary methods
             "items" => {
                 if args.len() != 1 {
-                    return Err("items() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("items() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
                 match val {
@@ -1292,14 +1380,14 @@ impl Interpreter {
                         }
                         Ok(Value::List(items))
                     }
-                    _ => Err("items() can only be called on dictionaries".to_string()),
+                    _ => Err(JError::invalid_argument("items() can only be called on dictionaries").into()),
                 }
             }
 
             "get" => {
                 if args.len() < 2 || args.len() > 3 {
                     return Err(
-                        "get() expects 2 or 3 arguments: get(dict, key, [default])".to_string()
+                        JError::argument_count("get() expects 2 or 3 arguments: get(dict, key, [default])").into()
                     );
                 }
                 let dict_val = self.eval_node(&args[0])?;
@@ -1315,7 +1403,7 @@ impl Interpreter {
                         let key = match key_val {
                             Value::String(s) => s,
                             Value::Integer(i) => i.to_string(),
-                            _ => return Err("get() key must be string or integer".to_string()),
+                            _ => return Err(JError::invalid_argument("get() key must be string or integer").into()),
                         };
 
                         if let Some(value) = dict.get(&key) {
@@ -1326,13 +1414,13 @@ impl Interpreter {
                             Ok(Value::None)
                         }
                     }
-                    _ => Err("get() can only be called on dictionaries".to_string()),
+                    _ => Err(JError::invalid_argument("get() can only be called on dictionaries").into()),
                 }
             }
 
             "has" => {
                 if args.len() != 2 {
-                    return Err("has() expects exactly 2 arguments: has(dict, key)".to_string());
+                    return Err(JError::argument_count("has() expects exactly 2 arguments: has(dict, key)").into());
                 }
                 let dict_val = self.eval_node(&args[0])?;
                 let key_val = self.eval_node(&args[1])?;
@@ -1342,18 +1430,18 @@ impl Interpreter {
                         let key = match key_val {
                             Value::String(s) => s,
                             Value::Integer(i) => i.to_string(),
-                            _ => return Err("has() key must be string or integer".to_string()),
+                            _ => return Err(JError::invalid_argument("has() key must be string or integer").into()),
                         };
                         Ok(Value::Boolean(dict.contains_key(&key)))
                     }
-                    _ => Err("has() can only be called on dictionaries".to_string()),
+                    _ => Err(JError::invalid_argument("has() can only be called on dictionaries").into()),
                 }
             }
 
             "remove" => {
                 if args.len() != 2 {
                     return Err(
-                        "remove() expects exactly 2 arguments: remove(dict, key)".to_string()
+                        JError::argument_count("remove() expects exactly 2 arguments: remove(dict, key)").into()
                     );
                 }
                 let dict_val = self.eval_node(&args[0])?;
@@ -1364,7 +1452,7 @@ impl Interpreter {
                         let key = match key_val {
                             Value::String(s) => s,
                             Value::Integer(i) => i.to_string(),
-                            _ => return Err("remove() key must be string or integer".to_string()),
+                            _ => return Err(JError::invalid_argument("remove() key must be string or integer").into()),
                         };
 
                         if let Some(value) = dict.remove(&key) {
@@ -1373,14 +1461,14 @@ impl Interpreter {
                             Ok(Value::None)
                         }
                     }
-                    _ => Err("remove() can only be called on dictionaries".to_string()),
+                    _ => Err(JError::invalid_argument("remove() can only be called on dictionaries").into()),
                 }
             }
 
             "merge" => {
                 if args.len() != 2 {
                     return Err(
-                        "merge() expects exactly 2 arguments: merge(dict1, dict2)".to_string()
+                        JError::argument_count("merge() expects exactly 2 arguments: merge(dict1, dict2)").into()
                     );
                 }
                 let dict1_val = self.eval_node(&args[0])?;
@@ -1393,14 +1481,14 @@ impl Interpreter {
                         }
                         Ok(Value::Dict(dict1))
                     }
-                    _ => Err("merge() can only be called on dictionaries".to_string()),
+                    _ => Err(JError::invalid_argument("merge() can only be called on dictionaries").into()),
                 }
             }
 
             "update" => {
                 if args.len() != 2 {
                     return Err(
-                        "update() expects exactly 2 arguments: update(dict1, dict2)".to_string()
+                        JError::argument_count("update() expects exactly 2 arguments: update(dict1, dict2)").into()
                     );
                 }
                 let dict1_val = self.eval_node(&args[0])?;
@@ -1413,18 +1501,18 @@ impl Interpreter {
                         }
                         Ok(Value::Dict(dict1))
                     }
-                    _ => Err("update() can only be called on dictionaries".to_string()),
+                    _ => Err(JError::invalid_argument("update() can only be called on dictionaries").into()),
                 }
             }
 
             "clear" => {
                 if args.len() != 1 {
-                    return Err("clear() expects exactly 1 argument: clear(dict)".to_string());
+                    return Err(JError::argument_count("clear() expects exactly 1 argument: clear(dict)").into());
                 }
                 let dict_val = self.eval_node(&args[0])?;
                 match dict_val {
                     Value::Dict(_) => Ok(Value::Dict(HashMap::new())),
-                    _ => Err("clear() can only be called on dictionaries".to_string()),
+                    _ => Err(JError::invalid_argument("clear() can only be called on dictionaries").into()),
                 }
             }
 
@@ -1432,7 +1520,7 @@ impl Interpreter {
             // Advanced printing functions
             "table" => {
                 if args.is_empty() {
-                    return Err("table() expects at least 1 argument".to_string());
+                    return Err(JError::argument_count("table() expects at least 1 argument").into());
                 }
                 let data_val = self.eval_node(&args[0])?;
 
@@ -1440,7 +1528,7 @@ impl Interpreter {
                     Value::List(rows) => {
                         // Print table from list of lists
                         if rows.is_empty() {
-                            return Ok(Value::None);
+                            return Ok(Some(Value::None));
                         }
 
                         // Convert all rows to strings
@@ -1486,20 +1574,20 @@ impl Interpreter {
                         println!("{}", "â”€".repeat(total_width));
                         Ok(Value::None)
                     }
-                    _ => Err("table() expects a list of lists".to_string()),
+                    _ => Err(JError::invalid_argument("table() expects a list of lists").into()),
                 }
             }
 
             "progress" => {
                 if args.is_empty() {
-                    return Err("progress() expects at least 1 argument (percentage)".to_string());
+                    return Err(JError::argument_count("progress() expects at least 1 argument (percentage)").into());
                 }
 
                 let percent_val = self.eval_node(&args[0])?;
                 let percent = match percent_val {
                     Value::Integer(i) => i as f64,
                     Value::Float(f) => f,
-                    _ => return Err("progress() percentage must be a number".to_string()),
+                    _ => return Err(JError::invalid_argument("progress() percentage must be a number").into()),
                 };
 
                 let width = if args.len() > 1 {
@@ -1541,7 +1629,7 @@ impl Interpreter {
 
             "rainbow" => {
                 if args.is_empty() {
-                    return Err("rainbow() expects 1 argument (text)".to_string());
+                    return Err(JError::argument_count("rainbow() expects 1 argument (text)").into());
                 }
 
                 let text_val = self.eval_node(&args[0])?;
@@ -1566,8 +1654,7 @@ impl Interpreter {
             "gradient" => {
                 if args.len() < 3 {
                     return Err(
-                        "gradient() expects 3 arguments: gradient(start_color, end_color, text)"
-                            .to_string(),
+                        JError::argument_count("gradient() expects 3 arguments: gradient(start_color, end_color, text)").into(),
                     );
                 }
 
@@ -1593,7 +1680,7 @@ impl Interpreter {
 
             "bold" => {
                 if args.is_empty() {
-                    return Err("bold() expects 1 argument (text)".to_string());
+                    return Err(JError::argument_count("bold() expects 1 argument (text)").into());
                 }
 
                 let text_val = self.eval_node(&args[0])?;
@@ -1607,7 +1694,7 @@ impl Interpreter {
 
             "underline" => {
                 if args.is_empty() {
-                    return Err("underline() expects 1 argument (text)".to_string());
+                    return Err(JError::argument_count("underline() expects 1 argument (text)").into());
                 }
 
                 let text_val = self.eval_node(&args[0])?;
@@ -1621,7 +1708,7 @@ impl Interpreter {
 
             "dim" => {
                 if args.is_empty() {
-                    return Err("dim() expects 1 argument (text)".to_string());
+                    return Err(JError::argument_count("dim() expects 1 argument (text)").into());
                 }
 
                 let text_val = self.eval_node(&args[0])?;
@@ -1636,7 +1723,7 @@ impl Interpreter {
             // Rich-like spinner and loading animations
             "spinner" => {
                 if args.is_empty() {
-                    return Err("spinner() expects at least 1 argument (style)".to_string());
+                    return Err(JError::argument_count("spinner() expects at least 1 argument (style)").into());
                 }
 
                 let style_val = self.eval_node(&args[0])?;
@@ -1670,7 +1757,7 @@ impl Interpreter {
 
             "loading" => {
                 if args.is_empty() {
-                    return Err("loading() expects at least 1 argument (message)".to_string());
+                    return Err(JError::argument_count("loading() expects at least 1 argument (message)").into());
                 }
 
                 let message_val = self.eval_node(&args[0])?;
@@ -1704,7 +1791,7 @@ impl Interpreter {
 
             "panel" => {
                 if args.is_empty() {
-                    return Err("panel() expects at least 1 argument (text)".to_string());
+                    return Err(JError::argument_count("panel() expects at least 1 argument (text)").into());
                 }
 
                 let text_val = self.eval_node(&args[0])?;
@@ -1737,7 +1824,7 @@ impl Interpreter {
 
             "box" => {
                 if args.is_empty() {
-                    return Err("box() expects at least 1 argument (text)".to_string());
+                    return Err(JError::argument_count("box() expects at least 1 argument (text)").into());
                 }
 
                 let text_val = self.eval_node(&args[0])?;
@@ -1761,7 +1848,7 @@ impl Interpreter {
 
             "status" => {
                 if args.len() < 2 {
-                    return Err("status() expects 2 arguments: status(type, message)".to_string());
+                    return Err(JError::argument_count("status() expects 2 arguments: status(type, message)").into());
                 }
 
                 let status_type_val = self.eval_node(&args[0])?;
@@ -1782,7 +1869,7 @@ impl Interpreter {
 
             "tree" => {
                 if args.is_empty() {
-                    return Err("tree() expects at least 1 argument (data)".to_string());
+                    return Err(JError::argument_count("tree() expects at least 1 argument (data)").into());
                 }
 
                 let data_val = self.eval_node(&args[0])?;
@@ -1801,7 +1888,7 @@ impl Interpreter {
 
             "columns" => {
                 if args.is_empty() {
-                    return Err("columns() expects at least 1 argument (list of texts)".to_string());
+                    return Err(JError::argument_count("columns() expects at least 1 argument (list of texts)").into());
                 }
 
                 let data_val = self.eval_node(&args[0])?;
@@ -1812,13 +1899,13 @@ impl Interpreter {
                         self.show_columns(&texts)?;
                         Ok(Value::None)
                     }
-                    _ => Err("columns() expects a list".to_string()),
+                    _ => Err(JError::invalid_argument("columns() expects a list").into()),
                 }
             }
 
             "find" => {
                 if args.len() != 2 {
-                    return Err("find() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("find() expects exactly 2 arguments").into());
                 }
                 let string_val = self.eval_node(&args[0])?;
                 let pattern_val = self.eval_node(&args[1])?;
@@ -1833,13 +1920,13 @@ impl Interpreter {
                         Ok(super::builtins::regex_builtins::regex_find(s, r)
                             .unwrap_or(Value::None))
                     }
-                    _ => Err("find() expects (string, string) or (string, regex)".to_string()),
+                    _ => Err(JError::invalid_argument("find() expects (string, string) or (string, regex)").into()),
                 }
             }
 
             "matches" => {
                 if args.len() != 2 {
-                    return Err("matches() expects exactly 2 arguments: matches(string, regex)".to_string());
+                    return Err(JError::argument_count("matches() expects exactly 2 arguments: matches(string, regex)").into());
                 }
                 let string_val = self.eval_node(&args[0])?;
                 let pattern_val = self.eval_node(&args[1])?;
@@ -1848,13 +1935,13 @@ impl Interpreter {
                     (Value::String(s), Value::Regex(r)) => {
                         Ok(Value::Boolean(super::builtins::regex_builtins::regex_matches(s, r)))
                     }
-                    _ => Err("matches() expects (string, regex)".to_string()),
+                    _ => Err(JError::invalid_argument("matches() expects (string, regex)").into()),
                 }
             }
 
             "find_all" => {
                 if args.len() != 2 {
-                    return Err("find_all() expects exactly 2 arguments: find_all(string, regex)".to_string());
+                    return Err(JError::argument_count("find_all() expects exactly 2 arguments: find_all(string, regex)").into());
                 }
                 let string_val = self.eval_node(&args[0])?;
                 let pattern_val = self.eval_node(&args[1])?;
@@ -1864,13 +1951,13 @@ impl Interpreter {
                         let list = super::builtins::regex_builtins::regex_find_all(s, r);
                         Ok(Value::List(list))
                     }
-                    _ => Err("find_all() expects (string, regex)".to_string()),
+                    _ => Err(JError::invalid_argument("find_all() expects (string, regex)").into()),
                 }
             }
 
             "pad_left" => {
                 if args.len() != 2 {
-                    return Err("pad_left() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("pad_left() expects exactly 2 arguments").into());
                 }
                 let string_val = self.eval_node(&args[0])?;
                 let width_val = self.eval_node(&args[1])?;
@@ -1878,18 +1965,18 @@ impl Interpreter {
                 match (string_val, width_val) {
                     (Value::String(s), Value::Integer(width)) => {
                         if width < 0 {
-                            return Err("pad_left() width must be non-negative".to_string());
+                            return Err(JError::invalid_argument("pad_left() width must be non-negative").into());
                         }
                         let result = format!("{:>width$}", s, width = width as usize);
                         Ok(Value::String(result))
                     }
-                    _ => Err("pad_left() expects string and integer arguments".to_string()),
+                    _ => Err(JError::invalid_argument("pad_left() expects string and integer arguments").into()),
                 }
             }
 
             "pad_right" => {
                 if args.len() != 2 {
-                    return Err("pad_right() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("pad_right() expects exactly 2 arguments").into());
                 }
                 let string_val = self.eval_node(&args[0])?;
                 let width_val = self.eval_node(&args[1])?;
@@ -1897,19 +1984,19 @@ impl Interpreter {
                 match (string_val, width_val) {
                     (Value::String(s), Value::Integer(width)) => {
                         if width < 0 {
-                            return Err("pad_right() width must be non-negative".to_string());
+                            return Err(JError::invalid_argument("pad_right() width must be non-negative").into());
                         }
                         let result = format!("{:<width$}", s, width = width as usize);
                         Ok(Value::String(result))
                     }
-                    _ => Err("pad_right() expects string and integer arguments".to_string()),
+                    _ => Err(JError::invalid_argument("pad_right() expects string and integer arguments").into()),
                 }
             }
 
             // Vector and Matrix functions
             "dot" => {
                 if args.len() != 2 {
-                    return Err("dot() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("dot() expects exactly 2 arguments").into());
                 }
                 let a_val = self.eval_node(&args[0])?;
                 let b_val = self.eval_node(&args[1])?;
@@ -1917,18 +2004,18 @@ impl Interpreter {
                 match (a_val, b_val) {
                     (Value::Vector(a), Value::Vector(b)) => {
                         if a.len() != b.len() {
-                            return Err("Vectors must have same length for dot product".to_string());
+                            return Err(JError::invalid_argument("Vectors must have same length for dot product").into());
                         }
                         let result: f64 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
                         Ok(Value::Float(result))
                     }
-                    _ => Err("dot() expects two vectors".to_string()),
+                    _ => Err(JError::invalid_argument("dot() expects two vectors").into()),
                 }
             }
 
             "magnitude" => {
                 if args.len() != 1 {
-                    return Err("magnitude() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("magnitude() expects exactly 1 argument").into());
                 }
                 let vec_val = self.eval_node(&args[0])?;
 
@@ -1937,13 +2024,13 @@ impl Interpreter {
                         let mag = v.iter().map(|x| x * x).sum::<f64>().sqrt();
                         Ok(Value::Float(mag))
                     }
-                    _ => Err("magnitude() expects a vector".to_string()),
+                    _ => Err(JError::invalid_argument("magnitude() expects a vector").into()),
                 }
             }
 
             "normalize" => {
                 if args.len() != 1 {
-                    return Err("normalize() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("normalize() expects exactly 1 argument").into());
                 }
                 let vec_val = self.eval_node(&args[0])?;
 
@@ -1951,25 +2038,25 @@ impl Interpreter {
                     Value::Vector(v) => {
                         let mag = v.iter().map(|x| x * x).sum::<f64>().sqrt();
                         if mag == 0.0 {
-                            return Err("Cannot normalize zero vector".to_string());
+                            return Err(JError::unsupported("Cannot normalize zero vector").into());
                         }
                         let normalized: Vec<f64> = v.iter().map(|x| x / mag).collect();
                         Ok(Value::Vector(normalized))
                     }
-                    _ => Err("normalize() expects a vector".to_string()),
+                    _ => Err(JError::invalid_argument("normalize() expects a vector").into()),
                 }
             }
 
             "transpose" => {
                 if args.len() != 1 {
-                    return Err("transpose() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("transpose() expects exactly 1 argument").into());
                 }
                 let mat_val = self.eval_node(&args[0])?;
 
                 match mat_val {
                     Value::Matrix(m) => {
                         if m.is_empty() || m[0].is_empty() {
-                            return Ok(Value::Matrix(Vec::new()));
+                            return Ok(Some(Value::Matrix(Vec::new())));
                         }
                         let rows = m.len();
                         let cols = m[0].len();
@@ -1982,13 +2069,13 @@ impl Interpreter {
                         }
                         Ok(Value::Matrix(transposed))
                     }
-                    _ => Err("transpose() expects a matrix".to_string()),
+                    _ => Err(JError::invalid_argument("transpose() expects a matrix").into()),
                 }
             }
 
             "matmul" => {
                 if args.len() != 2 {
-                    return Err("matmul() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("matmul() expects exactly 2 arguments").into());
                 }
                 let a_val = self.eval_node(&args[0])?;
                 let b_val = self.eval_node(&args[1])?;
@@ -1997,7 +2084,7 @@ impl Interpreter {
                     (Value::Matrix(a), Value::Matrix(b)) => {
                         if a.is_empty() || b.is_empty() || a[0].len() != b.len() {
                             return Err(
-                                "Matrix dimensions incompatible for multiplication".to_string()
+                                JError::unsupported("Matrix dimensions incompatible for multiplication").into()
                             );
                         }
 
@@ -2017,7 +2104,7 @@ impl Interpreter {
                     }
                     (Value::Matrix(m), Value::Vector(v)) => {
                         if m.is_empty() || m[0].len() != v.len() {
-                            return Err("Matrix and vector dimensions incompatible".to_string());
+                            return Err(JError::unsupported("Matrix and vector dimensions incompatible").into());
                         }
 
                         let mut result = Vec::new();
@@ -2028,14 +2115,14 @@ impl Interpreter {
                         }
                         Ok(Value::Vector(result))
                     }
-                    _ => Err("matmul() expects matrices or matrix and vector".to_string()),
+                    _ => Err(JError::invalid_argument("matmul() expects matrices or matrix and vector").into()),
                 }
             }
 
             // Set operations
             "add" => {
                 if args.len() != 2 {
-                    return Err("add() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("add() expects exactly 2 arguments").into());
                 }
                 let set_val = self.eval_node(&args[0])?;
                 let item_val = self.eval_node(&args[1])?;
@@ -2049,21 +2136,21 @@ impl Interpreter {
                             Value::Boolean(b) => b.to_string(),
                             _ => {
                                 return Err(
-                                    "Set elements must be convertible to strings".to_string()
+                                    JError::invalid_argument("Set elements must be convertible to strings").into()
                                 )
                             }
                         };
                         set.insert(key);
                         Ok(Value::Set(set))
                     }
-                    _ => Err("add() can only be called on sets".to_string()),
+                    _ => Err(JError::invalid_argument("add() can only be called on sets").into()),
                 }
             }
 
             // Counter operations
             "is_sorted" => {
                 if args.len() != 1 {
-                    return Err("is_sorted() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("is_sorted() expects exactly 1 argument").into());
                 }
                 let list_val = self.eval_node(&args[0])?;
 
@@ -2073,35 +2160,35 @@ impl Interpreter {
                             match (&list[i - 1], &list[i]) {
                                 (Value::Integer(a), Value::Integer(b)) => {
                                     if a > b {
-                                        return Ok(Value::Boolean(false));
+                                        return Ok(Some(Value::Boolean(false)));
                                     }
                                 }
                                 (Value::Float(a), Value::Float(b)) => {
                                     if a > b {
-                                        return Ok(Value::Boolean(false));
+                                        return Ok(Some(Value::Boolean(false)));
                                     }
                                 }
                                 (Value::String(a), Value::String(b)) => {
                                     if a > b {
-                                        return Ok(Value::Boolean(false));
+                                        return Ok(Some(Value::Boolean(false)));
                                     }
                                 }
                                 _ => {
                                     return Err(
-                                        "is_sorted() requires comparable elements".to_string()
+                                        JError::invalid_argument("is_sorted() requires comparable elements").into()
                                     )
                                 }
                             }
                         }
                         Ok(Value::Boolean(true))
                     }
-                    _ => Err("is_sorted() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("is_sorted() can only be called on lists").into()),
                 }
             }
 
             "shuffle" => {
                 if args.len() != 1 {
-                    return Err("shuffle() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("shuffle() expects exactly 1 argument").into());
                 }
                 let list_val = self.eval_node(&args[0])?;
 
@@ -2123,13 +2210,13 @@ impl Interpreter {
                         }
                         Ok(Value::List(list))
                     }
-                    _ => Err("shuffle() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("shuffle() can only be called on lists").into()),
                 }
             }
 
             "sample" => {
                 if args.len() != 2 {
-                    return Err("sample() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("sample() expects exactly 2 arguments").into());
                 }
                 let list_val = self.eval_node(&args[0])?;
                 let n_val = self.eval_node(&args[1])?;
@@ -2137,11 +2224,11 @@ impl Interpreter {
                 match (list_val, n_val) {
                     (Value::List(list), Value::Integer(n)) => {
                         if n < 0 {
-                            return Err("sample() size must be non-negative".to_string());
+                            return Err(JError::invalid_argument("sample() size must be non-negative").into());
                         }
                         let n = n as usize;
                         if n > list.len() {
-                            return Err("sample() size cannot be larger than list size".to_string());
+                            return Err(JError::invalid_argument("sample() size cannot be larger than list size").into());
                         }
 
                         // Simple sampling without replacement
@@ -2168,20 +2255,20 @@ impl Interpreter {
 
                         Ok(Value::List(result))
                     }
-                    _ => Err("sample() expects a list and an integer".to_string()),
+                    _ => Err(JError::invalid_argument("sample() expects a list and an integer").into()),
                 }
             }
 
             "accumulate" => {
                 if args.len() != 1 {
-                    return Err("accumulate() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("accumulate() expects exactly 1 argument").into());
                 }
                 let list_val = self.eval_node(&args[0])?;
 
                 match list_val {
                     Value::List(list) => {
                         if list.is_empty() {
-                            return Ok(Value::List(Vec::new()));
+                            return Ok(Some(Value::List(Vec::new())));
                         }
 
                         let mut result = Vec::new();
@@ -2202,21 +2289,21 @@ impl Interpreter {
                                 (Value::Float(a), Value::Integer(b)) => {
                                     acc = Value::Float(a + *b as f64);
                                 }
-                                _ => return Err("accumulate() requires numeric values".to_string()),
+                                _ => return Err(JError::invalid_argument("accumulate() requires numeric values").into()),
                             }
                             result.push(acc.clone());
                         }
 
                         Ok(Value::List(result))
                     }
-                    _ => Err("accumulate() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("accumulate() can only be called on lists").into()),
                 }
             }
 
             // Additional collection functions
             "take" => {
                 if args.len() != 2 {
-                    return Err("take() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("take() expects exactly 2 arguments").into());
                 }
                 let list_val = self.eval_node(&args[0])?;
                 let n_val = self.eval_node(&args[1])?;
@@ -2224,19 +2311,19 @@ impl Interpreter {
                 match (list_val, n_val) {
                     (Value::List(list), Value::Integer(n)) => {
                         if n < 0 {
-                            return Err("take() count must be non-negative".to_string());
+                            return Err(JError::invalid_argument("take() count must be non-negative").into());
                         }
                         let n = n as usize;
                         let result = list.into_iter().take(n).collect();
                         Ok(Value::List(result))
                     }
-                    _ => Err("take() expects list and integer arguments".to_string()),
+                    _ => Err(JError::invalid_argument("take() expects list and integer arguments").into()),
                 }
             }
 
             "drop" => {
                 if args.len() != 2 {
-                    return Err("drop() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("drop() expects exactly 2 arguments").into());
                 }
                 let list_val = self.eval_node(&args[0])?;
                 let n_val = self.eval_node(&args[1])?;
@@ -2244,84 +2331,84 @@ impl Interpreter {
                 match (list_val, n_val) {
                     (Value::List(list), Value::Integer(n)) => {
                         if n < 0 {
-                            return Err("drop() count must be non-negative".to_string());
+                            return Err(JError::invalid_argument("drop() count must be non-negative").into());
                         }
                         let n = n as usize;
                         let result = list.into_iter().skip(n).collect();
                         Ok(Value::List(result))
                     }
-                    _ => Err("drop() expects list and integer arguments".to_string()),
+                    _ => Err(JError::invalid_argument("drop() expects list and integer arguments").into()),
                 }
             }
 
             "sin" => {
                 if args.len() != 1 {
-                    return Err("sin() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("sin() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
                 match val {
                     Value::Integer(i) => Ok(Value::Float((i as f64).sin())),
                     Value::Float(f) => Ok(Value::Float(f.sin())),
-                    _ => Err("sin() can only be called on numbers".to_string()),
+                    _ => Err(JError::invalid_argument("sin() can only be called on numbers").into()),
                 }
             }
 
             "cos" => {
                 if args.len() != 1 {
-                    return Err("cos() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("cos() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
                 match val {
                     Value::Integer(i) => Ok(Value::Float((i as f64).cos())),
                     Value::Float(f) => Ok(Value::Float(f.cos())),
-                    _ => Err("cos() can only be called on numbers".to_string()),
+                    _ => Err(JError::invalid_argument("cos() can only be called on numbers").into()),
                 }
             }
 
             "tan" => {
                 if args.len() != 1 {
-                    return Err("tan() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("tan() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
                 match val {
                     Value::Integer(i) => Ok(Value::Float((i as f64).tan())),
                     Value::Float(f) => Ok(Value::Float(f.tan())),
-                    _ => Err("tan() can only be called on numbers".to_string()),
+                    _ => Err(JError::invalid_argument("tan() can only be called on numbers").into()),
                 }
             }
 
             "log" => {
                 if args.len() != 1 {
-                    return Err("log() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("log() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
                 match val {
                     Value::Integer(i) => Ok(Value::Float((i as f64).ln())),
                     Value::Float(f) => Ok(Value::Float(f.ln())),
-                    _ => Err("log() can only be called on numbers".to_string()),
+                    _ => Err(JError::invalid_argument("log() can only be called on numbers").into()),
                 }
             }
 
             "exp" => {
                 if args.len() != 1 {
-                    return Err("exp() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("exp() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
                 match val {
                     Value::Integer(i) => Ok(Value::Float((i as f64).exp())),
                     Value::Float(f) => Ok(Value::Float(f.exp())),
-                    _ => Err("exp() can only be called on numbers".to_string()),
+                    _ => Err(JError::invalid_argument("exp() can only be called on numbers").into()),
                 }
             }
 
             "clamp" => {
                 if args.len() != 3 {
-                    return Err("clamp() expects exactly 3 arguments".to_string());
+                    return Err(JError::argument_count("clamp() expects exactly 3 arguments").into());
                 }
                 let val = self.eval_node(&args[0])?;
                 let min_val = self.eval_node(&args[1])?;
@@ -2343,13 +2430,13 @@ impl Interpreter {
                         let max = max as f64;
                         Ok(Value::Float(v.max(min).min(max)))
                     }
-                    _ => Err("clamp() expects numeric arguments".to_string()),
+                    _ => Err(JError::invalid_argument("clamp() expects numeric arguments").into()),
                 }
             }
 
             "lerp" => {
                 if args.len() != 3 {
-                    return Err("lerp() expects exactly 3 arguments".to_string());
+                    return Err(JError::argument_count("lerp() expects exactly 3 arguments").into());
                 }
                 let a_val = self.eval_node(&args[0])?;
                 let b_val = self.eval_node(&args[1])?;
@@ -2358,19 +2445,19 @@ impl Interpreter {
                 let a = match a_val {
                     Value::Integer(i) => i as f64,
                     Value::Float(f) => f,
-                    _ => return Err("lerp() first argument must be numeric".to_string()),
+                    _ => return Err(JError::invalid_argument("lerp() first argument must be numeric").into()),
                 };
 
                 let b = match b_val {
                     Value::Integer(i) => i as f64,
                     Value::Float(f) => f,
-                    _ => return Err("lerp() second argument must be numeric".to_string()),
+                    _ => return Err(JError::invalid_argument("lerp() second argument must be numeric").into()),
                 };
 
                 let t = match t_val {
                     Value::Integer(i) => i as f64,
                     Value::Float(f) => f,
-                    _ => return Err("lerp() third argument must be numeric".to_string()),
+                    _ => return Err(JError::invalid_argument("lerp() third argument must be numeric").into()),
                 };
 
                 Ok(Value::Float(a + t * (b - a)))
@@ -2378,7 +2465,7 @@ impl Interpreter {
 
             "distance" => {
                 if args.len() != 2 {
-                    return Err("distance() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("distance() expects exactly 2 arguments").into());
                 }
                 let a_val = self.eval_node(&args[0])?;
                 let b_val = self.eval_node(&args[1])?;
@@ -2386,8 +2473,7 @@ impl Interpreter {
                 match (a_val, b_val) {
                     (Value::Vector(a), Value::Vector(b)) => {
                         if a.len() != b.len() {
-                            return Err("Vectors must have same length for distance calculation"
-                                .to_string());
+                            return Err(JError::invalid_argument("Vectors must have same length for distance calculation").into());
                         }
                         let dist_sq: f64 =
                             a.iter().zip(b.iter()).map(|(x, y)| (x - y).powi(2)).sum();
@@ -2396,7 +2482,7 @@ impl Interpreter {
                     (Value::Tuple(a), Value::Tuple(b)) => {
                         if a.len() != b.len() {
                             return Err(
-                                "Tuples must have same length for distance calculation".to_string()
+                                JError::invalid_argument("Tuples must have same length for distance calculation").into()
                             );
                         }
                         let mut dist_sq = 0.0;
@@ -2406,7 +2492,7 @@ impl Interpreter {
                                 Value::Float(f) => *f,
                                 _ => {
                                     return Err(
-                                        "Distance calculation requires numeric values".to_string()
+                                        JError::invalid_argument("Distance calculation requires numeric values").into()
                                     )
                                 }
                             };
@@ -2415,7 +2501,7 @@ impl Interpreter {
                                 Value::Float(f) => *f,
                                 _ => {
                                     return Err(
-                                        "Distance calculation requires numeric values".to_string()
+                                        JError::invalid_argument("Distance calculation requires numeric values").into()
                                     )
                                 }
                             };
@@ -2423,13 +2509,13 @@ impl Interpreter {
                         }
                         Ok(Value::Float(dist_sq.sqrt()))
                     }
-                    _ => Err("distance() expects two vectors or tuples".to_string()),
+                    _ => Err(JError::invalid_argument("distance() expects two vectors or tuples").into()),
                 }
             }
 
             "cross" => {
                 if args.len() != 2 {
-                    return Err("cross() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("cross() expects exactly 2 arguments").into());
                 }
                 let a_val = self.eval_node(&args[0])?;
                 let b_val = self.eval_node(&args[1])?;
@@ -2437,7 +2523,7 @@ impl Interpreter {
                 match (a_val, b_val) {
                     (Value::Vector(a), Value::Vector(b)) => {
                         if a.len() != 3 || b.len() != 3 {
-                            return Err("Cross product requires 3D vectors".to_string());
+                            return Err(JError::invalid_argument("Cross product requires 3D vectors").into());
                         }
                         let result = vec![
                             a[1] * b[2] - a[2] * b[1],
@@ -2446,20 +2532,20 @@ impl Interpreter {
                         ];
                         Ok(Value::Vector(result))
                     }
-                    _ => Err("cross() expects two 3D vectors".to_string()),
+                    _ => Err(JError::invalid_argument("cross() expects two 3D vectors").into()),
                 }
             }
 
             "determinant" => {
                 if args.len() != 1 {
-                    return Err("determinant() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("determinant() expects exactly 1 argument").into());
                 }
                 let mat_val = self.eval_node(&args[0])?;
 
                 match mat_val {
                     Value::Matrix(m) => {
                         if m.is_empty() || m.len() != m[0].len() {
-                            return Err("Determinant requires a square matrix".to_string());
+                            return Err(JError::invalid_argument("Determinant requires a square matrix").into());
                         }
 
                         let det =
@@ -2472,27 +2558,26 @@ impl Interpreter {
                                         + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
                                 }
                                 _ => return Err(
-                                    "Determinant only implemented for 1x1, 2x2, and 3x3 matrices"
-                                        .to_string(),
+                                    JError::unsupported("Determinant only implemented for 1x1, 2x2, and 3x3 matrices").into(),
                                 ),
                             };
 
                         Ok(Value::Float(det))
                     }
-                    _ => Err("determinant() expects a matrix".to_string()),
+                    _ => Err(JError::invalid_argument("determinant() expects a matrix").into()),
                 }
             }
 
             "identity" => {
                 if args.len() != 1 {
-                    return Err("identity() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("identity() expects exactly 1 argument").into());
                 }
                 let size_val = self.eval_node(&args[0])?;
 
                 match size_val {
                     Value::Integer(size) => {
                         if size <= 0 {
-                            return Err("Identity matrix size must be positive".to_string());
+                            return Err(JError::invalid_argument("Identity matrix size must be positive").into());
                         }
                         let size = size as usize;
                         let mut matrix = vec![vec![0.0; size]; size];
@@ -2501,7 +2586,7 @@ impl Interpreter {
                         }
                         Ok(Value::Matrix(matrix))
                     }
-                    _ => Err("identity() expects an integer size".to_string()),
+                    _ => Err(JError::invalid_argument("identity() expects an integer size").into()),
                 }
             }
 
@@ -2511,12 +2596,12 @@ impl Interpreter {
                     match size_val {
                         Value::Integer(size) => {
                             if size <= 0 {
-                                return Err("zeros() size must be positive".to_string());
+                                return Err(JError::invalid_argument("zeros() size must be positive").into());
                             }
                             let vec = vec![0.0; size as usize];
                             Ok(Value::Vector(vec))
                         }
-                        _ => Err("zeros() expects an integer size".to_string()),
+                        _ => Err(JError::invalid_argument("zeros() expects an integer size").into()),
                     }
                 }
                 2 => {
@@ -2525,15 +2610,15 @@ impl Interpreter {
                     match (rows_val, cols_val) {
                         (Value::Integer(rows), Value::Integer(cols)) => {
                             if rows <= 0 || cols <= 0 {
-                                return Err("zeros() dimensions must be positive".to_string());
+                                return Err(JError::invalid_argument("zeros() dimensions must be positive").into());
                             }
                             let matrix = vec![vec![0.0; cols as usize]; rows as usize];
                             Ok(Value::Matrix(matrix))
                         }
-                        _ => Err("zeros() expects integer dimensions".to_string()),
+                        _ => Err(JError::invalid_argument("zeros() expects integer dimensions").into()),
                     }
                 }
-                _ => Err("zeros() expects 1 or 2 arguments".to_string()),
+                _ => Err(JError::argument_count("zeros() expects 1 or 2 arguments").into()),
             },
 
             "ones" => match args.len() {
@@ -2542,12 +2627,12 @@ impl Interpreter {
                     match size_val {
                         Value::Integer(size) => {
                             if size <= 0 {
-                                return Err("ones() size must be positive".to_string());
+                                return Err(JError::invalid_argument("ones() size must be positive").into());
                             }
                             let vec = vec![1.0; size as usize];
                             Ok(Value::Vector(vec))
                         }
-                        _ => Err("ones() expects an integer size".to_string()),
+                        _ => Err(JError::invalid_argument("ones() expects an integer size").into()),
                     }
                 }
                 2 => {
@@ -2556,21 +2641,21 @@ impl Interpreter {
                     match (rows_val, cols_val) {
                         (Value::Integer(rows), Value::Integer(cols)) => {
                             if rows <= 0 || cols <= 0 {
-                                return Err("ones() dimensions must be positive".to_string());
+                                return Err(JError::invalid_argument("ones() dimensions must be positive").into());
                             }
                             let matrix = vec![vec![1.0; cols as usize]; rows as usize];
                             Ok(Value::Matrix(matrix))
                         }
-                        _ => Err("ones() expects integer dimensions".to_string()),
+                        _ => Err(JError::invalid_argument("ones() expects integer dimensions").into()),
                     }
                 }
-                _ => Err("ones() expects 1 or 2 arguments".to_string()),
+                _ => Err(JError::argument_count("ones() expects 1 or 2 arguments").into()),
             },
 
             // String algorithms
             "levenshtein" => {
                 if args.len() != 2 {
-                    return Err("levenshtein() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("levenshtein() expects exactly 2 arguments").into());
                 }
                 let s1_val = self.eval_node(&args[0])?;
                 let s2_val = self.eval_node(&args[1])?;
@@ -2580,13 +2665,13 @@ impl Interpreter {
                         let distance = self.levenshtein_distance(&s1, &s2);
                         Ok(Value::Integer(distance as i64))
                     }
-                    _ => Err("levenshtein() expects two strings".to_string()),
+                    _ => Err(JError::invalid_argument("levenshtein() expects two strings").into()),
                 }
             }
 
             "hamming" => {
                 if args.len() != 2 {
-                    return Err("hamming() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("hamming() expects exactly 2 arguments").into());
                 }
                 let s1_val = self.eval_node(&args[0])?;
                 let s2_val = self.eval_node(&args[1])?;
@@ -2594,19 +2679,19 @@ impl Interpreter {
                 match (s1_val, s2_val) {
                     (Value::String(s1), Value::String(s2)) => {
                         if s1.len() != s2.len() {
-                            return Err("hamming() requires strings of equal length".to_string());
+                            return Err(JError::invalid_argument("hamming() requires strings of equal length").into());
                         }
                         let distance = s1.chars().zip(s2.chars()).filter(|(a, b)| a != b).count();
                         Ok(Value::Integer(distance as i64))
                     }
-                    _ => Err("hamming() expects two strings".to_string()),
+                    _ => Err(JError::invalid_argument("hamming() expects two strings").into()),
                 }
             }
 
             // Advanced algorithm helpers
             "kmp_search" => {
                 if args.len() != 2 {
-                    return Err("kmp_search() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("kmp_search() expects exactly 2 arguments").into());
                 }
                 let text_val = self.eval_node(&args[0])?;
                 let pattern_val = self.eval_node(&args[1])?;
@@ -2621,13 +2706,13 @@ impl Interpreter {
                                 .collect(),
                         ))
                     }
-                    _ => Err("kmp_search() expects two strings".to_string()),
+                    _ => Err(JError::invalid_argument("kmp_search() expects two strings").into()),
                 }
             }
 
             "z_array" => {
                 if args.len() != 1 {
-                    return Err("z_array() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("z_array() expects exactly 1 argument").into());
                 }
                 let text_val = self.eval_node(&args[0])?;
 
@@ -2641,13 +2726,13 @@ impl Interpreter {
                                 .collect(),
                         ))
                     }
-                    _ => Err("z_array() expects a string".to_string()),
+                    _ => Err(JError::invalid_argument("z_array() expects a string").into()),
                 }
             }
 
             "convex_hull" => {
                 if args.len() != 1 {
-                    return Err("convex_hull() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("convex_hull() expects exactly 1 argument").into());
                 }
                 let points_val = self.eval_node(&args[0])?;
 
@@ -2656,13 +2741,13 @@ impl Interpreter {
                         let hull = self.convex_hull_2d(&points)?;
                         Ok(Value::List(hull))
                     }
-                    _ => Err("convex_hull() expects a list of (x, y) tuples".to_string()),
+                    _ => Err(JError::invalid_argument("convex_hull() expects a list of (x, y) tuples").into()),
                 }
             }
 
             "fft" => {
                 if args.len() != 1 {
-                    return Err("fft() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("fft() expects exactly 1 argument").into());
                 }
                 let signal_val = self.eval_node(&args[0])?;
 
@@ -2677,14 +2762,14 @@ impl Interpreter {
                         let fft_result = self.fft_transform(&signal_list)?;
                         Ok(Value::List(fft_result))
                     }
-                    _ => Err("fft() expects a list or vector of numbers".to_string()),
+                    _ => Err(JError::invalid_argument("fft() expects a list or vector of numbers").into()),
                 }
             }
 
             // More collection functions
             "all" => {
                 if args.len() != 1 {
-                    return Err("all() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("all() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
@@ -2693,13 +2778,13 @@ impl Interpreter {
                         let all_true = list.iter().all(|item| self.is_truthy(item));
                         Ok(Value::Boolean(all_true))
                     }
-                    _ => Err("all() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("all() can only be called on lists").into()),
                 }
             }
 
             "any" => {
                 if args.len() != 1 {
-                    return Err("any() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("any() expects exactly 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
@@ -2708,14 +2793,14 @@ impl Interpreter {
                         let any_true = list.iter().any(|item| self.is_truthy(item));
                         Ok(Value::Boolean(any_true))
                     }
-                    _ => Err("any() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("any() can only be called on lists").into()),
                 }
             }
 
             // Functional programming helpers
             "compose" => {
                 if args.len() != 2 {
-                    return Err("compose() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("compose() expects exactly 2 arguments").into());
                 }
                 // For now, just return a placeholder - full function composition would need more work
                 Ok(Value::String("composed_function".to_string()))
@@ -2723,7 +2808,7 @@ impl Interpreter {
 
             "curry" => {
                 if args.len() != 1 {
-                    return Err("curry() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("curry() expects exactly 1 argument").into());
                 }
                 // Placeholder for currying - would need more advanced function handling
                 Ok(Value::String("curried_function".to_string()))
@@ -2731,7 +2816,7 @@ impl Interpreter {
             // Date/time functions
             "now" => {
                 if !args.is_empty() {
-                    return Err("now() expects no arguments".to_string());
+                    return Err(JError::argument_count("now() expects no arguments").into());
                 }
                 use std::time::{SystemTime, UNIX_EPOCH};
                 let timestamp = SystemTime::now()
@@ -2743,7 +2828,7 @@ impl Interpreter {
 
             "today" => {
                 if !args.is_empty() {
-                    return Err("today() expects no arguments".to_string());
+                    return Err(JError::argument_count("today() expects no arguments").into());
                 }
                 let now = std::time::SystemTime::now();
                 let dur = now.duration_since(std::time::UNIX_EPOCH).unwrap();
@@ -2772,7 +2857,7 @@ impl Interpreter {
                     match n_val {
                         Value::Integer(n) => {
                             if n <= 0 {
-                                return Err("random() argument must be positive".to_string());
+                                return Err(JError::invalid_argument("random() argument must be positive").into());
                             }
                             use std::collections::hash_map::DefaultHasher;
                             use std::hash::{Hash, Hasher};
@@ -2783,16 +2868,16 @@ impl Interpreter {
                             let random_val = (seed % (n as u64)) as i64;
                             Ok(Value::Integer(random_val))
                         }
-                        _ => Err("random() expects an integer argument".to_string()),
+                        _ => Err(JError::invalid_argument("random() expects an integer argument").into()),
                     }
                 } else {
-                    Err("random() expects 0 or 1 arguments".to_string())
+                    Err(JError::argument_count("random() expects 0 or 1 arguments").into())
                 }
             }
 
             "chain" => {
                 if args.len() < 2 {
-                    return Err("chain() expects at least 2 arguments".to_string());
+                    return Err(JError::argument_count("chain() expects at least 2 arguments").into());
                 }
 
                 let mut result = Vec::new();
@@ -2818,7 +2903,7 @@ impl Interpreter {
 
             "cycle" => {
                 if args.len() != 2 {
-                    return Err("cycle() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("cycle() expects exactly 2 arguments").into());
                 }
                 let iterable_val = self.eval_node(&args[0])?;
                 let count_val = self.eval_node(&args[1])?;
@@ -2826,17 +2911,17 @@ impl Interpreter {
                 let count = match count_val {
                     Value::Integer(n) => {
                         if n < 0 {
-                            return Err("cycle() count must be non-negative".to_string());
+                            return Err(JError::invalid_argument("cycle() count must be non-negative").into());
                         }
                         n as usize
                     }
-                    _ => return Err("cycle() count must be an integer".to_string()),
+                    _ => return Err(JError::invalid_argument("cycle() count must be an integer").into()),
                 };
 
                 match iterable_val {
                     Value::List(list) => {
                         if list.is_empty() {
-                            return Ok(Value::List(Vec::new()));
+                            return Ok(Some(Value::List(Vec::new())));
                         }
                         let mut result = Vec::new();
                         for _ in 0..count {
@@ -2846,18 +2931,18 @@ impl Interpreter {
                     }
                     Value::String(s) => {
                         if s.is_empty() {
-                            return Ok(Value::String(String::new()));
+                            return Ok(Some(Value::String(String::new())));
                         }
                         let repeated = s.repeat(count);
                         Ok(Value::String(repeated))
                     }
-                    _ => Err("cycle() can only be called on lists and strings".to_string()),
+                    _ => Err(JError::invalid_argument("cycle() can only be called on lists and strings").into()),
                 }
             }
 
             "interleave" => {
                 if args.len() != 2 {
-                    return Err("interleave() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("interleave() expects exactly 2 arguments").into());
                 }
                 let list1_val = self.eval_node(&args[0])?;
                 let list2_val = self.eval_node(&args[1])?;
@@ -2877,13 +2962,13 @@ impl Interpreter {
                         }
                         Ok(Value::List(result))
                     }
-                    _ => Err("interleave() expects two lists".to_string()),
+                    _ => Err(JError::invalid_argument("interleave() expects two lists").into()),
                 }
             }
 
             "rotate" => {
                 if args.len() != 2 {
-                    return Err("rotate() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("rotate() expects exactly 2 arguments").into());
                 }
                 let list_val = self.eval_node(&args[0])?;
                 let n_val = self.eval_node(&args[1])?;
@@ -2891,7 +2976,7 @@ impl Interpreter {
                 match (list_val, n_val) {
                     (Value::List(mut list), Value::Integer(n)) => {
                         if list.is_empty() {
-                            return Ok(Value::List(list));
+                            return Ok(Some(Value::List(list)));
                         }
 
                         let len = list.len() as i64;
@@ -2901,34 +2986,33 @@ impl Interpreter {
                         list.rotate_left(n);
                         Ok(Value::List(list))
                     }
-                    _ => Err("rotate() expects a list and an integer".to_string()),
+                    _ => Err(JError::invalid_argument("rotate() expects a list and an integer").into()),
                 }
             }
 
             "permutations" => {
                 if args.len() != 1 {
-                    return Err("permutations() expects exactly 1 argument".to_string());
+                    return Err(JError::argument_count("permutations() expects exactly 1 argument").into());
                 }
                 let list_val = self.eval_node(&args[0])?;
 
                 match list_val {
                     Value::List(list) => {
                         if list.len() > 8 {
-                            return Err("permutations() limited to lists of 8 elements or fewer"
-                                .to_string());
+                            return Err(JError::unsupported("permutations() limited to lists of 8 elements or fewer").into());
                         }
 
                         let perms = self.generate_permutations(&list);
                         let result: Vec<Value> = perms.into_iter().map(Value::List).collect();
                         Ok(Value::List(result))
                     }
-                    _ => Err("permutations() can only be called on lists".to_string()),
+                    _ => Err(JError::invalid_argument("permutations() can only be called on lists").into()),
                 }
             }
 
             "combinations" => {
                 if args.len() != 2 {
-                    return Err("combinations() expects exactly 2 arguments".to_string());
+                    return Err(JError::argument_count("combinations() expects exactly 2 arguments").into());
                 }
                 let list_val = self.eval_node(&args[0])?;
                 let r_val = self.eval_node(&args[1])?;
@@ -2937,7 +3021,7 @@ impl Interpreter {
                     (Value::List(list), Value::Integer(r)) => {
                         if r < 0 || r > list.len() as i64 {
                             return Err(
-                                "combinations() r must be between 0 and list length".to_string()
+                                JError::invalid_argument("combinations() r must be between 0 and list length").into()
                             );
                         }
 
@@ -2945,13 +3029,13 @@ impl Interpreter {
                         let result: Vec<Value> = combs.into_iter().map(Value::List).collect();
                         Ok(Value::List(result))
                     }
-                    _ => Err("combinations() expects a list and an integer".to_string()),
+                    _ => Err(JError::invalid_argument("combinations() expects a list and an integer").into()),
                 }
             }
 
             "product" => {
                 if args.len() < 2 {
-                    return Err("product() expects at least 2 arguments".to_string());
+                    return Err(JError::argument_count("product() expects at least 2 arguments").into());
                 }
 
                 let mut lists = Vec::new();
@@ -2959,7 +3043,7 @@ impl Interpreter {
                     let val = self.eval_node(arg)?;
                     match val {
                         Value::List(list) => lists.push(list),
-                        _ => return Err("product() expects list arguments".to_string()),
+                        _ => return Err(JError::invalid_argument("product() expects list arguments").into()),
                     }
                 }
 
@@ -2972,8 +3056,7 @@ impl Interpreter {
             "parallel_map" => {
                 if args.len() != 2 {
                     return Err(
-                        "parallel_map() expects 2 arguments: parallel_map(list, function)"
-                            .to_string(),
+                        JError::argument_count("parallel_map() expects 2 arguments: parallel_map(list, function)").into(),
                     );
                 }
                 let list_val = self.eval_node(&args[0])?;
@@ -2990,27 +3073,16 @@ impl Interpreter {
                         }
                         Ok(Value::List(result))
                     }
-                    _ => Err("parallel_map() expects a list".to_string()),
+                    _ => Err(JError::invalid_argument("parallel_map() expects a list").into()),
                 }
             }
 
             // memoize - Automatically cache function results
-            "memoize" => {
-                if args.len() != 1 {
-                    return Err("memoize() expects 1 argument: a function".to_string());
-                }
-                let func_val = self.eval_node(&args[0])?;
-                // Return the function wrapped with memoization
-                // (Implementation would require cache storage)
-                Ok(func_val)
-            }
-
             // benchmark - Time function execution
             "benchmark" => {
                 if args.is_empty() {
                     return Err(
-                        "benchmark() expects at least 1 argument: function to benchmark"
-                            .to_string(),
+                        JError::argument_count("benchmark() expects at least 1 argument: function to benchmark").into(),
                     );
                 }
                 let func_val = self.eval_node(&args[0])?;
@@ -3046,7 +3118,7 @@ impl Interpreter {
             // tap - Debug helper: print value and return it (chainable)
             "tap" => {
                 if args.is_empty() {
-                    return Err("tap() expects at least 1 argument".to_string());
+                    return Err(JError::argument_count("tap() expects at least 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
@@ -3067,7 +3139,7 @@ impl Interpreter {
             "pipe" => {
                 if args.len() < 2 {
                     return Err(
-                        "pipe() expects at least 2 arguments: value, ...functions".to_string()
+                        JError::argument_count("pipe() expects at least 2 arguments: value, ...functions").into()
                     );
                 }
 
@@ -3085,23 +3157,23 @@ impl Interpreter {
             "retry" => {
                 if args.len() < 2 {
                     return Err(
-                        "retry() expects at least 2 arguments: function, max_attempts".to_string(),
+                        JError::argument_count("retry() expects at least 2 arguments: function, max_attempts").into(),
                     );
                 }
                 let func_val = self.eval_node(&args[0])?;
                 let max_attempts = if let Value::Integer(n) = self.eval_node(&args[1])? {
                     n as usize
                 } else {
-                    return Err("retry() max_attempts must be an integer".to_string());
+                    return Err(JError::invalid_argument("retry() max_attempts must be an integer").into());
                 };
 
                 let mut last_error = String::new();
 
                 for attempt in 1..=max_attempts {
                     match self.call_value_with_args(func_val.clone(), &[], None) {
-                        Ok(result) => return Ok(result),
+                        Ok(result) => return Ok(Some(result)),
                         Err(e) => {
-                            last_error = e;
+                            last_error = e.message;
                             if attempt < max_attempts {
                                 // Small delay between retries
                                 std::thread::sleep(std::time::Duration::from_millis(100));
@@ -3110,17 +3182,17 @@ impl Interpreter {
                     }
                 }
 
-                Err(format!(
+                Err(JError::unsupported(format!(
                     "retry() failed after {} attempts: {}",
                     max_attempts, last_error
-                ))
+                )).into())
             }
 
             // take_while - Take elements while predicate is true
             "take_while" => {
                 if args.len() != 2 {
                     return Err(
-                        "take_while() expects 2 arguments: take_while(list, predicate)".to_string(),
+                        JError::argument_count("take_while() expects 2 arguments: take_while(list, predicate)").into(),
                     );
                 }
                 let list_val = self.eval_node(&args[0])?;
@@ -3137,14 +3209,14 @@ impl Interpreter {
                                 Value::Boolean(false) => break,
                                 _ => {
                                     return Err(
-                                        "take_while() predicate must return boolean".to_string()
+                                        JError::invalid_argument("take_while() predicate must return boolean").into()
                                     )
                                 }
                             }
                         }
                         Ok(Value::List(result))
                     }
-                    _ => Err("take_while() expects a list".to_string()),
+                    _ => Err(JError::invalid_argument("take_while() expects a list").into()),
                 }
             }
 
@@ -3152,7 +3224,7 @@ impl Interpreter {
             "drop_while" => {
                 if args.len() != 2 {
                     return Err(
-                        "drop_while() expects 2 arguments: drop_while(list, predicate)".to_string(),
+                        JError::argument_count("drop_while() expects 2 arguments: drop_while(list, predicate)").into(),
                     );
                 }
                 let list_val = self.eval_node(&args[0])?;
@@ -3177,8 +3249,7 @@ impl Interpreter {
                                         result.push(item);
                                     }
                                     _ => {
-                                        return Err("drop_while() predicate must return boolean"
-                                            .to_string())
+                                        return Err(JError::invalid_argument("drop_while() predicate must return boolean").into())
                                     }
                                 }
                             } else {
@@ -3187,7 +3258,7 @@ impl Interpreter {
                         }
                         Ok(Value::List(result))
                     }
-                    _ => Err("drop_while() expects a list".to_string()),
+                    _ => Err(JError::invalid_argument("drop_while() expects a list").into()),
                 }
             }
 
@@ -3195,7 +3266,7 @@ impl Interpreter {
             "scan" => {
                 if args.len() != 3 {
                     return Err(
-                        "scan() expects 3 arguments: scan(list, initial, function)".to_string()
+                        JError::argument_count("scan() expects 3 arguments: scan(list, initial, function)").into()
                     );
                 }
                 let list_val = self.eval_node(&args[0])?;
@@ -3218,14 +3289,14 @@ impl Interpreter {
 
                         Ok(Value::List(result))
                     }
-                    _ => Err("scan() expects a list".to_string()),
+                    _ => Err(JError::invalid_argument("scan() expects a list").into()),
                 }
             }
 
             // window - Create overlapping windows (alias for sliding_window)
             "window" => {
                 if args.len() != 2 {
-                    return Err("window() expects 2 arguments: window(list, size)".to_string());
+                    return Err(JError::argument_count("window() expects 2 arguments: window(list, size)").into());
                 }
                 // Reuse sliding_window implementation
                 self.call_function("sliding_window", args)
@@ -3234,7 +3305,7 @@ impl Interpreter {
             // compact - Remove None/null values from list
             "compact" => {
                 if args.len() != 1 {
-                    return Err("compact() expects 1 argument".to_string());
+                    return Err(JError::argument_count("compact() expects 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
@@ -3246,21 +3317,21 @@ impl Interpreter {
                             .collect();
                         Ok(Value::List(result))
                     }
-                    _ => Err("compact() expects a list".to_string()),
+                    _ => Err(JError::invalid_argument("compact() expects a list").into()),
                 }
             }
 
             // pluck - Extract property from list of dicts/objects
             "pluck" => {
                 if args.len() != 2 {
-                    return Err("pluck() expects 2 arguments: pluck(list, key)".to_string());
+                    return Err(JError::argument_count("pluck() expects 2 arguments: pluck(list, key)").into());
                 }
                 let list_val = self.eval_node(&args[0])?;
                 let key_val = self.eval_node(&args[1])?;
 
                 let key = match key_val {
                     Value::String(s) => s,
-                    _ => return Err("pluck() key must be a string".to_string()),
+                    _ => return Err(JError::invalid_argument("pluck() key must be a string").into()),
                 };
 
                 match list_val {
@@ -3275,7 +3346,7 @@ impl Interpreter {
                         }
                         Ok(Value::List(result))
                     }
-                    _ => Err("pluck() expects a list".to_string()),
+                    _ => Err(JError::invalid_argument("pluck() expects a list").into()),
                 }
             }
 
@@ -3287,8 +3358,7 @@ impl Interpreter {
             "zip_longest" => {
                 if args.len() < 3 {
                     return Err(
-                        "zip_longest() expects at least 3 arguments: list1, list2, fillvalue"
-                            .to_string(),
+                        JError::argument_count("zip_longest() expects at least 3 arguments: list1, list2, fillvalue").into(),
                     );
                 }
 
@@ -3303,7 +3373,7 @@ impl Interpreter {
                             max_len = max_len.max(list.len());
                             lists.push(list);
                         }
-                        _ => return Err("zip_longest() expects list arguments".to_string()),
+                        _ => return Err(JError::invalid_argument("zip_longest() expects list arguments").into()),
                     }
                 }
 
@@ -3327,7 +3397,7 @@ impl Interpreter {
             "batched" => {
                 if args.len() != 2 {
                     return Err(
-                        "batched() expects 2 arguments: batched(list, batch_size)".to_string()
+                        JError::argument_count("batched() expects 2 arguments: batched(list, batch_size)").into()
                     );
                 }
                 let list_val = self.eval_node(&args[0])?;
@@ -3336,7 +3406,7 @@ impl Interpreter {
                 match (list_val, size_val) {
                     (Value::List(list), Value::Integer(size)) => {
                         if size <= 0 {
-                            return Err("batched() batch_size must be positive".to_string());
+                            return Err(JError::invalid_argument("batched() batch_size must be positive").into());
                         }
                         let size = size as usize;
                         let mut result = Vec::new();
@@ -3347,21 +3417,21 @@ impl Interpreter {
 
                         Ok(Value::List(result))
                     }
-                    _ => Err("batched() expects (list, int)".to_string()),
+                    _ => Err(JError::invalid_argument("batched() expects (list, int)").into()),
                 }
             }
 
             // pairwise - Return successive overlapping pairs
             "pairwise" => {
                 if args.len() != 1 {
-                    return Err("pairwise() expects 1 argument".to_string());
+                    return Err(JError::argument_count("pairwise() expects 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
                 match val {
                     Value::List(list) => {
                         if list.len() < 2 {
-                            return Ok(Value::List(Vec::new()));
+                            return Ok(Some(Value::List(Vec::new())));
                         }
 
                         let mut result = Vec::new();
@@ -3371,21 +3441,21 @@ impl Interpreter {
 
                         Ok(Value::List(result))
                     }
-                    _ => Err("pairwise() expects a list".to_string()),
+                    _ => Err(JError::invalid_argument("pairwise() expects a list").into()),
                 }
             }
 
             // dedupe - Remove consecutive duplicates
             "dedupe" => {
                 if args.len() != 1 {
-                    return Err("dedupe() expects 1 argument".to_string());
+                    return Err(JError::argument_count("dedupe() expects 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
 
                 match val {
                     Value::List(list) => {
                         if list.is_empty() {
-                            return Ok(Value::List(Vec::new()));
+                            return Ok(Some(Value::List(Vec::new())));
                         }
 
                         let mut result = vec![list[0].clone()];
@@ -3403,14 +3473,14 @@ impl Interpreter {
 
                         Ok(Value::List(result))
                     }
-                    _ => Err("dedupe() expects a list".to_string()),
+                    _ => Err(JError::invalid_argument("dedupe() expects a list").into()),
                 }
             }
 
             // deep_clone - Deep copy of nested structures
             "deep_clone" => {
                 if args.len() != 1 {
-                    return Err("deep_clone() expects 1 argument".to_string());
+                    return Err(JError::argument_count("deep_clone() expects 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
                 Ok(val.clone()) // Rust's clone is already deep
@@ -3419,7 +3489,7 @@ impl Interpreter {
             // json_parse - Parse JSON string
             "json_parse" => {
                 if args.len() != 1 {
-                    return Err("json_parse() expects 1 argument: JSON string".to_string());
+                    return Err(JError::argument_count("json_parse() expects 1 argument: JSON string").into());
                 }
                 let val = self.eval_node(&args[0])?;
                 match &val {
@@ -3427,23 +3497,23 @@ impl Interpreter {
                         #[cfg(feature = "serde_json")]
                         {
                             let parsed: serde_json::Value = serde_json::from_str(s)
-                                .map_err(|e| format!("json_parse() failed: {}", e))?;
+                                .map_err(|e| JError::invalid_argument(format!("json_parse() failed: {}", e)))?;
                             Ok(Self::json_value_to_jade(&parsed))
                         }
                         #[cfg(not(feature = "serde_json"))]
                         {
                             let _ = s;
-                            Err("json_parse() requires serde_json feature".to_string())
+                            Err("json_parse() requires serde_json feature".into())
                         }
                     }
-                    _ => Err("json_parse() expects a string".to_string()),
+                    _ => Err(JError::invalid_argument("json_parse() expects a string").into()),
                 }
             }
 
             // json_stringify - Convert to JSON string
             "json_stringify" => {
                 if args.len() != 1 {
-                    return Err("json_stringify() expects 1 argument".to_string());
+                    return Err(JError::argument_count("json_stringify() expects 1 argument").into());
                 }
                 let val = self.eval_node(&args[0])?;
                 #[cfg(feature = "serde_json")]
@@ -3455,7 +3525,7 @@ impl Interpreter {
                 #[cfg(not(feature = "serde_json"))]
                 {
                     let _ = val;
-                    Err("json_stringify() requires serde_json feature".to_string())
+                    Err("json_stringify() requires serde_json feature".into())
                 }
             }
 
@@ -3463,7 +3533,7 @@ impl Interpreter {
             // Only activates with exact parameters, otherwise silently returns None
             "quack_check" => {
                 if args.len() != 3 {
-                    return Ok(Value::None); // Silent fail
+                    return Ok(Some(Value::None)); // Silent fail
                 }
 
                 let sound = self.eval_node(&args[0])?;
@@ -3498,12 +3568,12 @@ impl Interpreter {
             // ===== CRYPTOGRAPHY (see builtins/crypto.rs) — sha256, enigma_*, aes_*, etc. =====
             "random_bytes" => {
                 if args.len() != 1 {
-                    return Err("random_bytes() takes exactly 1 argument (length)".to_string());
+                    return Err(JError::argument_count("random_bytes() takes exactly 1 argument (length)").into());
                 }
                 let len_val = self.eval_node(&args[0])?;
                 let len = match len_val {
                     Value::Integer(n) if n > 0 => n as usize,
-                    _ => return Err("random_bytes() requires a positive integer".to_string()),
+                    _ => return Err(JError::invalid_argument("random_bytes() requires a positive integer").into()),
                 };
 
                 use rand::Rng;
@@ -3517,22 +3587,22 @@ impl Interpreter {
 
             "rand_range" => {
                 if args.len() != 2 {
-                    return Err("rand_range() takes exactly 2 arguments (min, max)".to_string());
+                    return Err(JError::argument_count("rand_range() takes exactly 2 arguments (min, max)").into());
                 }
                 let min_val = self.eval_node(&args[0])?;
                 let max_val = self.eval_node(&args[1])?;
 
                 let min = match min_val {
                     Value::Integer(n) => n,
-                    _ => return Err("rand_range() requires integer arguments".to_string()),
+                    _ => return Err(JError::invalid_argument("rand_range() requires integer arguments").into()),
                 };
                 let max = match max_val {
                     Value::Integer(n) => n,
-                    _ => return Err("rand_range() requires integer arguments".to_string()),
+                    _ => return Err(JError::invalid_argument("rand_range() requires integer arguments").into()),
                 };
 
                 if min >= max {
-                    return Err("rand_range() requires min < max".to_string());
+                    return Err(JError::invalid_argument("rand_range() requires min < max").into());
                 }
 
                 use rand::Rng;
@@ -3544,7 +3614,7 @@ impl Interpreter {
 
             "uuid_v4" => {
                 if !args.is_empty() {
-                    return Err("uuid_v4() takes no arguments".to_string());
+                    return Err(JError::argument_count("uuid_v4() takes no arguments").into());
                 }
 
                 use uuid::Uuid;
@@ -3554,14 +3624,14 @@ impl Interpreter {
 
             "make_secret" => {
                 if args.len() != 1 {
-                    return Err("make_secret(value) requires 1 argument".to_string());
+                    return Err(JError::argument_count("make_secret(value) requires 1 argument").into());
                 }
 
                 let value = self.eval_node(&args[0])?;
                 let secret_str = match value {
                     Value::String(s) => s,
                     Value::Integer(i) => i.to_string(),
-                    _ => return Err("make_secret() requires a string or integer".to_string()),
+                    _ => return Err(JError::invalid_argument("make_secret() requires a string or integer").into()),
                 };
 
                 Ok(Value::Secret(secret_str))
@@ -3569,25 +3639,25 @@ impl Interpreter {
 
             "reveal_secret" => {
                 if args.len() != 1 {
-                    return Err("reveal_secret(secret) requires 1 argument".to_string());
+                    return Err(JError::argument_count("reveal_secret(secret) requires 1 argument").into());
                 }
 
                 let secret_val = self.eval_node(&args[0])?;
                 match secret_val {
                     Value::Secret(s) => Ok(Value::String(s)),
-                    _ => Err("reveal_secret() requires a secret value".to_string()),
+                    _ => Err(JError::invalid_argument("reveal_secret() requires a secret value").into()),
                 }
             }
 
             "audit_log" => {
                     if args.len() < 2 {
-                        return Err("audit_log(event, data) requires at least 2 arguments".to_string());
+                        return Err(JError::argument_count("audit_log(event, data) requires at least 2 arguments").into());
                     }
 
                     let event_val = self.eval_node(&args[0])?;
                     let event = match event_val {
                         Value::String(s) => s,
-                        _ => return Err("Event must be a string".to_string()),
+                        _ => return Err(JError::invalid_argument("Event must be a string").into()),
                     };
 
                     let data_val = self.eval_node(&args[1])?;
@@ -3626,92 +3696,92 @@ impl Interpreter {
             // ===== WEB SCRAPING FUNCTIONS =====
             "fetch_html" | "fetch_text" => {
                 if args.len() != 1 {
-                    return Err("fetch_text() takes exactly 1 argument (url)".to_string());
+                    return Err(JError::argument_count("fetch_text() takes exactly 1 argument (url)").into());
                 }
                 let url_val = self.eval_node(&args[0])?;
                 let url = match url_val {
                     Value::String(s) => s,
-                    _ => return Err("fetch_text() requires a string URL".to_string()),
+                    _ => return Err(JError::invalid_argument("fetch_text() requires a string URL").into()),
                 };
                 #[cfg(feature = "reqwest")]
                 {
                     let client = reqwest::blocking::Client::builder()
                         .user_agent("Jade-Scraper/1.0")
                         .build()
-                        .map_err(|e| format!("fetch_text() client error: {}", e))?;
+                        .map_err(|e| JError::io_error(format!("fetch_text() client error: {}", e)))?;
                     let body = client
                         .get(&url)
                         .send()
-                        .map_err(|e| format!("fetch_text() request failed: {}", e))?
+                        .map_err(|e| JError::io_error(format!("fetch_text() request failed: {}", e)))?
                         .text()
-                        .map_err(|e| format!("fetch_text() read failed: {}", e))?;
+                        .map_err(|e| JError::io_error(format!("fetch_text() read failed: {}", e)))?;
                     Ok(Value::String(body))
                 }
                 #[cfg(not(feature = "reqwest"))]
                 {
                     let _ = url;
-                    Err("fetch_text() requires reqwest feature (full build)".to_string())
+                    Err("fetch_text() requires reqwest feature (full build)".into())
                 }
             }
 
             "fetch_json" => {
                 if args.len() != 1 {
-                    return Err("fetch_json() takes exactly 1 argument (url)".to_string());
+                    return Err(JError::argument_count("fetch_json() takes exactly 1 argument (url)").into());
                 }
                 let url_val = self.eval_node(&args[0])?;
                 let url = match url_val {
                     Value::String(s) => s,
-                    _ => return Err("fetch_json() requires a string URL".to_string()),
+                    _ => return Err(JError::invalid_argument("fetch_json() requires a string URL").into()),
                 };
                 #[cfg(all(feature = "reqwest", feature = "serde_json"))]
                 {
                     let client = reqwest::blocking::Client::builder()
                         .user_agent("Jade-Scraper/1.0")
                         .build()
-                        .map_err(|e| format!("fetch_json() client error: {}", e))?;
+                        .map_err(|e| JError::io_error(format!("fetch_json() client error: {}", e)))?;
                     let json_val: serde_json::Value = client
                         .get(&url)
                         .send()
-                        .map_err(|e| format!("fetch_json() request failed: {}", e))?
+                        .map_err(|e| JError::io_error(format!("fetch_json() request failed: {}", e)))?
                         .json()
-                        .map_err(|e| format!("fetch_json() parse failed: {}", e))?;
+                        .map_err(|e| JError::invalid_argument(format!("fetch_json() parse failed: {}", e)))?;
                     Ok(Self::json_value_to_jade(&json_val))
                 }
                 #[cfg(not(all(feature = "reqwest", feature = "serde_json")))]
                 {
                     let _ = url;
-                    Err("fetch_json() requires reqwest and serde_json (full build)".to_string())
+                    Err("fetch_json() requires reqwest and serde_json (full build)".into())
                 }
             }
 
             // ===== CLI FUNCTIONS =====
             "cli_prompt" => {
                 if args.len() != 1 {
-                    return Err("cli_prompt() takes exactly 1 argument (prompt text)".to_string());
+                    return Err(JError::argument_count("cli_prompt() takes exactly 1 argument (prompt text)").into());
                 }
                 let prompt_val = self.eval_node(&args[0])?;
                 let prompt = match prompt_val {
                     Value::String(s) => s,
-                    _ => return Err("cli_prompt() requires a string".to_string()),
+                    _ => return Err(JError::invalid_argument("cli_prompt() requires a string").into()),
                 };
 
                 use std::io::{self, Write};
                 print!("{}", prompt);
                 io::stdout()
                     .flush()
-                    .map_err(|e| format!("cli_prompt() failed to flush output: {}", e))?;
+                    .map_err(|e| JError::io_error(format!("cli_prompt() failed to flush output: {}", e)))?;
 
                 let mut input = String::new();
                 io::stdin()
                     .read_line(&mut input)
-                    .map_err(|e| format!("cli_prompt() failed to read input: {}", e))?;
+                    .map_err(|e| JError::io_error(format!("cli_prompt() failed to read input: {}", e)))?;
 
                 Ok(Value::String(input.trim().to_string()))
             }
 
             "cli_args" => {
                 if !args.is_empty() {
-                    return Err("cli_args() takes no arguments".to_string());
+                    return Err(JError::argument_count("cli_args() takes no arguments").into());
                 }
 
                 // Get command line arguments
@@ -3725,12 +3795,12 @@ impl Interpreter {
 
             "env_get" => {
                 if args.len() != 1 {
-                    return Err("env_get() takes exactly 1 argument (variable name)".to_string());
+                    return Err(JError::argument_count("env_get() takes exactly 1 argument (variable name)").into());
                 }
                 let var_val = self.eval_node(&args[0])?;
                 let var_name = match var_val {
                     Value::String(s) => s,
-                    _ => return Err("env_get() requires a string".to_string()),
+                    _ => return Err(JError::invalid_argument("env_get() requires a string").into()),
                 };
 
                 match std::env::var(&var_name) {
@@ -3741,18 +3811,18 @@ impl Interpreter {
 
             "env_set" => {
                 if args.len() != 2 {
-                    return Err("env_set() takes exactly 2 arguments (name, value)".to_string());
+                    return Err(JError::argument_count("env_set() takes exactly 2 arguments (name, value)").into());
                 }
                 let name_val = self.eval_node(&args[0])?;
                 let value_val = self.eval_node(&args[1])?;
 
                 let name = match name_val {
                     Value::String(s) => s,
-                    _ => return Err("env_set() name must be a string".to_string()),
+                    _ => return Err(JError::invalid_argument("env_set() name must be a string").into()),
                 };
                 let value = match value_val {
                     Value::String(s) => s,
-                    _ => return Err("env_set() value must be a string".to_string()),
+                    _ => return Err(JError::invalid_argument("env_set() value must be a string").into()),
                 };
 
                 std::env::set_var(&name, &value);
@@ -3761,24 +3831,24 @@ impl Interpreter {
 
             "file_read" => {
                     if args.len() != 1 {
-                        return Err("file_read() takes exactly 1 argument (filename)".to_string());
+                        return Err(JError::argument_count("file_read() takes exactly 1 argument (filename)").into());
                     }
                     let filename_val = self.eval_node(&args[0])?;
                     let filename = match filename_val {
                         Value::String(s) => s,
-                        _ => return Err("file_read() requires a string filename".to_string()),
+                        _ => return Err(JError::invalid_argument("file_read() requires a string filename").into()),
                     };
 
                     match std::fs::read_to_string(&filename) {
                         Ok(content) => Ok(Value::String(content)),
-                        Err(e) => Err(format!("Failed to read file '{}': {}", filename, e)),
+                        Err(e) => Err(JError::io_error(format!("Failed to read file '{}': {}", filename, e)).into()),
                     }
             }
 
             "file_write" => {
                     if args.len() != 2 {
                         return Err(
-                            "file_write() takes exactly 2 arguments (filename, content)".to_string()
+                            JError::argument_count("file_write() takes exactly 2 arguments (filename, content)").into()
                         );
                     }
                     let filename_val = self.eval_node(&args[0])?;
@@ -3786,27 +3856,27 @@ impl Interpreter {
 
                     let filename = match filename_val {
                         Value::String(s) => s,
-                        _ => return Err("file_write() filename must be a string".to_string()),
+                        _ => return Err(JError::invalid_argument("file_write() filename must be a string").into()),
                     };
                     let content = match content_val {
                         Value::String(s) => s,
-                        _ => return Err("file_write() content must be a string".to_string()),
+                        _ => return Err(JError::invalid_argument("file_write() content must be a string").into()),
                     };
 
                     match std::fs::write(&filename, &content) {
                         Ok(_) => Ok(Value::None),
-                        Err(e) => Err(format!("Failed to write file '{}': {}", filename, e)),
+                        Err(e) => Err(JError::io_error(format!("Failed to write file '{}': {}", filename, e)).into()),
                     }
             }
 
             "file_exists" => {
                     if args.len() != 1 {
-                        return Err("file_exists() takes exactly 1 argument (filename)".to_string());
+                        return Err(JError::argument_count("file_exists() takes exactly 1 argument (filename)").into());
                     }
                     let filename_val = self.eval_node(&args[0])?;
                     let filename = match filename_val {
                         Value::String(s) => s,
-                        _ => return Err("file_exists() requires a string filename".to_string()),
+                        _ => return Err(JError::invalid_argument("file_exists() requires a string filename").into()),
                     };
 
                     Ok(Value::Boolean(std::path::Path::new(&filename).exists()))
@@ -3814,12 +3884,12 @@ impl Interpreter {
 
             "dir_list" => {
                     if args.len() != 1 {
-                        return Err("dir_list() takes exactly 1 argument (directory path)".to_string());
+                        return Err(JError::argument_count("dir_list() takes exactly 1 argument (directory path)").into());
                     }
                     let dir_val = self.eval_node(&args[0])?;
                     let dir_path = match dir_val {
                         Value::String(s) => s,
-                        _ => return Err("dir_list() requires a string path".to_string()),
+                        _ => return Err(JError::invalid_argument("dir_list() requires a string path").into()),
                     };
 
                     match std::fs::read_dir(&dir_path) {
@@ -3832,13 +3902,13 @@ impl Interpreter {
                                 .collect();
                             Ok(Value::List(files))
                         }
-                        Err(e) => Err(format!("Failed to read directory '{}': {}", dir_path, e)),
+                        Err(e) => Err(JError::io_error(format!("Failed to read directory '{}': {}", dir_path, e)).into()),
                     }
             }
 
             "timestamp" => {
                 if !args.is_empty() {
-                    return Err("timestamp() takes no arguments".to_string());
+                    return Err(JError::argument_count("timestamp() takes no arguments").into());
                 }
 
                 use std::time::{SystemTime, UNIX_EPOCH};
@@ -3849,24 +3919,24 @@ impl Interpreter {
 
             "file_delete" => {
                     if args.len() != 1 {
-                        return Err("file_delete() takes exactly 1 argument (filename)".to_string());
+                        return Err(JError::argument_count("file_delete() takes exactly 1 argument (filename)").into());
                     }
                     let filename_val = self.eval_node(&args[0])?;
                     let filename = match filename_val {
                         Value::String(s) => s,
-                        _ => return Err("file_delete() requires a string filename".to_string()),
+                        _ => return Err(JError::invalid_argument("file_delete() requires a string filename").into()),
                     };
 
                     match std::fs::remove_file(&filename) {
                         Ok(_) => Ok(Value::None),
-                        Err(e) => Err(format!("Failed to delete file '{}': {}", filename, e)),
+                        Err(e) => Err(JError::io_error(format!("Failed to delete file '{}': {}", filename, e)).into()),
                     }
             }
 
             "file_append" => {
                     if args.len() != 2 {
                         return Err(
-                            "file_append() takes exactly 2 arguments (filename, content)".to_string(),
+                            JError::argument_count("file_append() takes exactly 2 arguments (filename, content)").into(),
                         );
                     }
                     let filename_val = self.eval_node(&args[0])?;
@@ -3874,11 +3944,11 @@ impl Interpreter {
 
                     let filename = match filename_val {
                         Value::String(s) => s,
-                        _ => return Err("file_append() filename must be a string".to_string()),
+                        _ => return Err(JError::invalid_argument("file_append() filename must be a string").into()),
                     };
                     let content = match content_val {
                         Value::String(s) => s,
-                        _ => return Err("file_append() content must be a string".to_string()),
+                        _ => return Err(JError::invalid_argument("file_append() content must be a string").into()),
                     };
 
                     use std::fs::OpenOptions;
@@ -3887,52 +3957,52 @@ impl Interpreter {
                     match OpenOptions::new().create(true).append(true).open(&filename) {
                         Ok(mut file) => match file.write_all(content.as_bytes()) {
                             Ok(_) => Ok(Value::None),
-                            Err(e) => Err(format!("Failed to append to file '{}': {}", filename, e)),
+                            Err(e) => Err(JError::io_error(format!("Failed to append to file '{}': {}", filename, e)).into()),
                         },
-                        Err(e) => Err(format!("Failed to open file '{}': {}", filename, e)),
+                        Err(e) => Err(JError::io_error(format!("Failed to open file '{}': {}", filename, e)).into()),
                     }
             }
 
             "dir_create" => {
                     if args.len() != 1 {
                         return Err(
-                            "dir_create() takes exactly 1 argument (directory path)".to_string()
+                            JError::argument_count("dir_create() takes exactly 1 argument (directory path)").into()
                         );
                     }
                     let dir_val = self.eval_node(&args[0])?;
                     let dir_path = match dir_val {
                         Value::String(s) => s,
-                        _ => return Err("dir_create() requires a string path".to_string()),
+                        _ => return Err(JError::invalid_argument("dir_create() requires a string path").into()),
                     };
 
                     match std::fs::create_dir_all(&dir_path) {
                         Ok(_) => Ok(Value::None),
-                        Err(e) => Err(format!("Failed to create directory '{}': {}", dir_path, e)),
+                        Err(e) => Err(JError::io_error(format!("Failed to create directory '{}': {}", dir_path, e)).into()),
                     }
             }
 
             "dir_delete" => {
                     if args.len() != 1 {
                         return Err(
-                            "dir_delete() takes exactly 1 argument (directory path)".to_string()
+                            JError::argument_count("dir_delete() takes exactly 1 argument (directory path)").into()
                         );
                     }
                     let dir_val = self.eval_node(&args[0])?;
                     let dir_path = match dir_val {
                         Value::String(s) => s,
-                        _ => return Err("dir_delete() requires a string path".to_string()),
+                        _ => return Err(JError::invalid_argument("dir_delete() requires a string path").into()),
                     };
 
                     match std::fs::remove_dir_all(&dir_path) {
                         Ok(_) => Ok(Value::None),
-                        Err(e) => Err(format!("Failed to delete directory '{}': {}", dir_path, e)),
+                        Err(e) => Err(JError::io_error(format!("Failed to delete directory '{}': {}", dir_path, e)).into()),
                     }
             }
 
             "file_copy" => {
                     if args.len() != 2 {
                         return Err(
-                            "file_copy() takes exactly 2 arguments (source, destination)".to_string(),
+                            JError::argument_count("file_copy() takes exactly 2 arguments (source, destination)").into(),
                         );
                     }
                     let src_val = self.eval_node(&args[0])?;
@@ -3940,23 +4010,23 @@ impl Interpreter {
 
                     let src = match src_val {
                         Value::String(s) => s,
-                        _ => return Err("file_copy() source must be a string".to_string()),
+                        _ => return Err(JError::invalid_argument("file_copy() source must be a string").into()),
                     };
                     let dst = match dst_val {
                         Value::String(s) => s,
-                        _ => return Err("file_copy() destination must be a string".to_string()),
+                        _ => return Err(JError::invalid_argument("file_copy() destination must be a string").into()),
                     };
 
                     match std::fs::copy(&src, &dst) {
                         Ok(_) => Ok(Value::None),
-                        Err(e) => Err(format!("Failed to copy file '{}' to '{}': {}", src, dst, e)),
+                        Err(e) => Err(JError::io_error(format!("Failed to copy file '{}' to '{}': {}", src, dst, e)).into()),
                     }
             }
 
             "file_rename" => {
                     if args.len() != 2 {
                         return Err(
-                            "file_rename() takes exactly 2 arguments (old_name, new_name)".to_string(),
+                            JError::argument_count("file_rename() takes exactly 2 arguments (old_name, new_name)").into(),
                         );
                     }
                     let old_val = self.eval_node(&args[0])?;
@@ -3964,60 +4034,53 @@ impl Interpreter {
 
                     let old_name = match old_val {
                         Value::String(s) => s,
-                        _ => return Err("file_rename() old_name must be a string".to_string()),
+                        _ => return Err(JError::invalid_argument("file_rename() old_name must be a string").into()),
                     };
                     let new_name = match new_val {
                         Value::String(s) => s,
-                        _ => return Err("file_rename() new_name must be a string".to_string()),
+                        _ => return Err(JError::invalid_argument("file_rename() new_name must be a string").into()),
                     };
 
                     match std::fs::rename(&old_name, &new_name) {
                         Ok(_) => Ok(Value::None),
-                        Err(e) => Err(format!(
+                        Err(e) => Err(JError::unsupported(format!(
                             "Failed to rename file '{}' to '{}': {}",
                             old_name, new_name, e
-                        )),
+                        )).into()),
                     }
             }
 
-            _ => {
-                // If not built-in, look for user-defined function (or @once/MirrorDispatch wrapper)
-                let func_val = self.get_variable(name)?;
-                let eval_args: Vec<Value> = args
-                    .iter()
-                    .map(|a| self.eval_node(a))
-                    .collect::<Result<Vec<_>, _>>()?;
-                self.call_value_with_args(func_val, &eval_args, None)
-            }
-        }
+            _ => return Ok(None),
+        };
+        result.map(Some)
     }
 
-    pub(super) fn eval_binary_op(&self, left: &Value, op: &BinaryOp, right: &Value) -> Result<Value, String> {
+    pub(crate) fn eval_binary_op(&self, left: &Value, op: &BinaryOp, right: &Value) -> JResult<Value> {
         match (left, op, right) {
             (Value::Integer(a), BinaryOp::Add, Value::Integer(b)) => a
                 .checked_add(*b)
                 .map(Value::Integer)
-                .ok_or_else(|| format!("Integer overflow: {} + {}", a, b)),
+                .ok_or_else(|| JError::unsupported(format!("Integer overflow: {} + {}", a, b)).into()),
             (Value::Integer(a), BinaryOp::Subtract, Value::Integer(b)) => a
                 .checked_sub(*b)
                 .map(Value::Integer)
-                .ok_or_else(|| format!("Integer overflow: {} - {}", a, b)),
+                .ok_or_else(|| JError::unsupported(format!("Integer overflow: {} - {}", a, b)).into()),
             (Value::Integer(a), BinaryOp::Multiply, Value::Integer(b)) => a
                 .checked_mul(*b)
                 .map(Value::Integer)
-                .ok_or_else(|| format!("Integer overflow: {} * {}", a, b)),
+                .ok_or_else(|| JError::unsupported(format!("Integer overflow: {} * {}", a, b)).into()),
             (Value::Integer(a), BinaryOp::Divide, Value::Integer(b)) => {
                 if *b == 0 {
-                    Err(JError::division_by_zero(0, 0).to_string())
+                    Err(Box::new(JError::division_by_zero(0, 0).unlocated()))
                 } else if *a == i64::MIN && *b == -1 {
-                    Err("Integer overflow: i64::MIN / -1".to_string())
+                    Err(JError::unsupported("Integer overflow: i64::MIN / -1").into())
                 } else {
                     Ok(Value::Integer(a / b))
                 }
             }
             (Value::Integer(a), BinaryOp::Modulo, Value::Integer(b)) => {
                 if *b == 0 {
-                    Err(JError::division_by_zero(0, 0).to_string())
+                    Err(Box::new(JError::division_by_zero(0, 0).unlocated()))
                 } else if *a == i64::MIN && *b == -1 {
                     Ok(Value::Integer(0)) // Mathematically correct
                 } else {
@@ -4028,11 +4091,11 @@ impl Interpreter {
                 if *b < 0 {
                     Ok(Value::Float((*a as f64).powf(*b as f64)))
                 } else if *b > u32::MAX as i64 {
-                    Err("Exponent too large".to_string())
+                    Err(JError::unsupported("Exponent too large").into())
                 } else {
                     a.checked_pow(*b as u32)
                         .map(Value::Integer)
-                        .ok_or_else(|| format!("Integer overflow: {} ** {}", a, b))
+                        .ok_or_else(|| JError::unsupported(format!("Integer overflow: {} ** {}", a, b)).into())
                 }
             }
 
@@ -4061,14 +4124,14 @@ impl Interpreter {
             }
             (Value::Integer(a), BinaryOp::Divide, Value::Float(b)) => {
                 if *b == 0.0 {
-                    Err(JError::division_by_zero(0, 0).to_string())
+                    Err(Box::new(JError::division_by_zero(0, 0).unlocated()))
                 } else {
                     Ok(Value::Float(*a as f64 / b))
                 }
             }
             (Value::Float(a), BinaryOp::Divide, Value::Integer(b)) => {
                 if *b == 0 {
-                    Err(JError::division_by_zero(0, 0).to_string())
+                    Err(Box::new(JError::division_by_zero(0, 0).unlocated()))
                 } else {
                     Ok(Value::Float(a / *b as f64))
                 }
@@ -4142,13 +4205,13 @@ impl Interpreter {
             }
             (Value::Integer(a), BinaryOp::LeftShift, Value::Integer(b)) => {
                 if *b < 0 {
-                    return Err("Shift amount must be non-negative".to_string());
+                    return Err(JError::invalid_argument("Shift amount must be non-negative").into());
                 }
                 Ok(Value::Integer(a << b))
             }
             (Value::Integer(a), BinaryOp::RightShift, Value::Integer(b)) => {
                 if *b < 0 {
-                    return Err("Shift amount must be non-negative".to_string());
+                    return Err(JError::invalid_argument("Shift amount must be non-negative").into());
                 }
                 Ok(Value::Integer(a >> b))
             }
@@ -4165,14 +4228,14 @@ impl Interpreter {
             // Vector operations
             (Value::Vector(a), BinaryOp::Add, Value::Vector(b)) => {
                 if a.len() != b.len() {
-                    return Err("Vector dimensions must match for addition".to_string());
+                    return Err(JError::invalid_argument("Vector dimensions must match for addition").into());
                 }
                 let result: Vec<f64> = a.iter().zip(b.iter()).map(|(x, y)| x + y).collect();
                 Ok(Value::Vector(result))
             }
             (Value::Vector(a), BinaryOp::Subtract, Value::Vector(b)) => {
                 if a.len() != b.len() {
-                    return Err("Vector dimensions must match for subtraction".to_string());
+                    return Err(JError::invalid_argument("Vector dimensions must match for subtraction").into());
                 }
                 let result: Vec<f64> = a.iter().zip(b.iter()).map(|(x, y)| x - y).collect();
                 Ok(Value::Vector(result))
@@ -4197,7 +4260,7 @@ impl Interpreter {
             // Matrix operations
             (Value::Matrix(a), BinaryOp::Add, Value::Matrix(b)) => {
                 if a.len() != b.len() || (!a.is_empty() && a[0].len() != b[0].len()) {
-                    return Err("Matrix dimensions must match for addition".to_string());
+                    return Err(JError::invalid_argument("Matrix dimensions must match for addition").into());
                 }
                 let mut result = Vec::new();
                 for (row_a, row_b) in a.iter().zip(b.iter()) {
@@ -4209,7 +4272,7 @@ impl Interpreter {
             }
             (Value::Matrix(a), BinaryOp::Subtract, Value::Matrix(b)) => {
                 if a.len() != b.len() || (!a.is_empty() && a[0].len() != b[0].len()) {
-                    return Err("Matrix dimensions must match for subtraction".to_string());
+                    return Err(JError::invalid_argument("Matrix dimensions must match for subtraction").into());
                 }
                 let mut result = Vec::new();
                 for (row_a, row_b) in a.iter().zip(b.iter()) {
@@ -4436,6 +4499,14 @@ impl Interpreter {
             (Value::Boolean(a), BinaryOp::Or, Value::Boolean(b)) => Ok(Value::Boolean(*a || *b)),
             (_, BinaryOp::ConstantTimeEq, _) => Ok(Value::Boolean(self.values_equal(left, right))),
 
+            // Deep equality for collections
+            (Value::List(_) | Value::Tuple(_) | Value::Dict(_), BinaryOp::Equal, _) => {
+                Ok(Value::Boolean(self.values_equal(left, right)))
+            }
+            (Value::List(_) | Value::Tuple(_) | Value::Dict(_), BinaryOp::NotEqual, _) => {
+                Ok(Value::Boolean(!self.values_equal(left, right)))
+            }
+
             // String + any / any + string (concat); other types converted to string
             (Value::String(a), BinaryOp::Add, right) => {
                 Ok(Value::String(format!("{}{}", a, right)))
@@ -4444,25 +4515,25 @@ impl Interpreter {
                 Ok(Value::String(format!("{}{}", left, b)))
             }
 
-            _ => Err(format!(
+            _ => Err(JError::unsupported(format!(
                 "Unsupported binary operation: {} {:?} {}",
                 left, op, right
-            )),
+            )).into()),
         }
     }
 
-    pub(super) fn eval_unary_op(&self, op: &UnaryOp, operand: &Value) -> Result<Value, String> {
+    pub(crate) fn eval_unary_op(&self, op: &UnaryOp, operand: &Value) -> JResult<Value> {
         match (op, operand) {
             (UnaryOp::Minus, Value::Integer(i)) => Ok(Value::Integer(-i)),
             (UnaryOp::Minus, Value::Float(f)) => Ok(Value::Float(-f)),
             (UnaryOp::Minus, Value::Infinity(positive)) => Ok(Value::Infinity(!positive)),
             (UnaryOp::Not, Value::Boolean(b)) => Ok(Value::Boolean(!b)),
             (UnaryOp::BitwiseNot, Value::Integer(i)) => Ok(Value::Integer(!i)),
-            _ => Err(format!("Unsupported unary operation: {:?} {}", op, operand)),
+            _ => Err(JError::unsupported(format!("Unsupported unary operation: {:?} {}", op, operand)).into()),
         }
     }
 
-    pub(super) fn pattern_matches(&mut self, pattern: &Pattern, value: &Value) -> Result<bool, String> {
+    pub(super) fn pattern_matches(&mut self, pattern: &Pattern, value: &Value) -> JResult<bool> {
         match (pattern, value) {
             (Pattern::Wildcard, _) => Ok(true),
             (Pattern::Literal(lit_node), _) => {
@@ -4471,7 +4542,7 @@ impl Interpreter {
                     AstNode::Float(f) => Value::Float(*f),
                     AstNode::String(s) => Value::String(s.clone()),
                     AstNode::Boolean(b) => Value::Boolean(*b),
-                    _ => return Err("Invalid literal in pattern".to_string()),
+                    _ => return Err(JError::invalid_argument("Invalid literal in pattern").into()),
                 };
                 Ok(self.values_equal(&lit_val, value))
             }
@@ -4525,11 +4596,15 @@ impl Interpreter {
             (Value::Tuple(a), Value::Tuple(b)) => {
                 a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| self.values_equal(x, y))
             }
+            (Value::Dict(a), Value::Dict(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(k, x)| b.get(k).is_some_and(|y| self.values_equal(x, y)))
+            }
             _ => false,
         }
     }
 
-    pub(super) fn is_truthy(&self, value: &Value) -> bool {
+    pub(crate) fn is_truthy(&self, value: &Value) -> bool {
         match value {
             Value::Boolean(b) => *b,
             Value::Integer(i) => *i != 0,
@@ -4542,25 +4617,25 @@ impl Interpreter {
     }
 
     // CRITICAL FIX: Matrix validation to prevent ragged matrices
-    pub(super) fn validate_matrix(&self, rows: &[Vec<f64>]) -> Result<(), String> {
+    pub(super) fn validate_matrix(&self, rows: &[Vec<f64>]) -> JResult<()> {
         if rows.is_empty() {
             return Ok(());
         }
         let expected_len = rows[0].len();
         for (i, row) in rows.iter().enumerate() {
             if row.len() != expected_len {
-                return Err(format!(
+                return Err(JError::unsupported(format!(
                     "Matrix row {} has length {}, expected {} (ragged matrices not allowed)",
                     i,
                     row.len(),
                     expected_len
-                ));
+                )).into());
             }
         }
         Ok(())
     }
 
-    pub(super) fn get_property(&self, obj: &Value, field: &str) -> Result<Value, String> {
+    pub(super) fn get_property(&self, obj: &Value, field: &str) -> JResult<Value> {
         match obj {
             Value::Class {
                 name: class_name,
@@ -4575,10 +4650,10 @@ impl Interpreter {
                 } else if let Some(v) = static_methods.get(field) {
                     Ok(v.clone())
                 } else {
-                    Err(format!(
+                    Err(JError::unsupported(format!(
                         "Unknown static field or method '{}' on class '{}'",
                         field, class_name
-                    ))
+                    )).into())
                 }
             }
             Value::Instance { class_name, fields } => {
@@ -4604,7 +4679,7 @@ impl Interpreter {
                     "find_all" => Ok(Value::GridFindAll(Box::new(Value::Grid(grid.clone())))),
                     "row" => Ok(Value::GridRow(Box::new(Value::Grid(grid.clone())))),
                     "col" => Ok(Value::GridCol(Box::new(Value::Grid(grid.clone())))),
-                    _ => Err(format!("Grid method '{}' not found", field)),
+                    _ => Err(JError::unsupported(format!("Grid method '{}' not found", field)).into()),
                 }
             }
             Value::Matrix(mat) => {
@@ -4640,7 +4715,7 @@ impl Interpreter {
                     "col_sums" => Ok(Value::MatrixColSums(Box::new(Value::Matrix(mat.clone())))),
                     "row_means" => Ok(Value::MatrixRowMeans(Box::new(Value::Matrix(mat.clone())))),
                     "col_means" => Ok(Value::MatrixColMeans(Box::new(Value::Matrix(mat.clone())))),
-                    _ => Err(format!("Matrix method '{}' not found", field)),
+                    _ => Err(JError::unsupported(format!("Matrix method '{}' not found", field)).into()),
                 }
             }
             Value::Counter(counter) => match field {
@@ -4662,21 +4737,21 @@ impl Interpreter {
                 }
                 "total" => Ok(Value::Integer(counter.values().sum())),
                 "len" | "length" | "size" => Ok(Value::Integer(counter.len() as i64)),
-                _ => Err(format!("Counter method '{}' not found", field)),
+                _ => Err(JError::unsupported(format!("Counter method '{}' not found", field)).into()),
             },
             Value::Pool(_pool_id) => match field {
                 "alloc_str" | "alloc_vec" | "alloc_mat" | "reset" | "len" => Ok(Value::BoundMethod {
                     receiver: Box::new(obj.clone()),
                     method: field.to_string(),
                 }),
-                _ => Err(format!("Pool method '{}' not found", field)),
+                _ => Err(JError::unsupported(format!("Pool method '{}' not found", field)).into()),
             },
             Value::PoolRef { pool_id, index } => {
-                let storage = self.pools.get(pool_id).ok_or("Pool no longer valid (reset?)".to_string())?;
-                let val = storage.get(*index).cloned().ok_or("PoolRef index out of bounds".to_string())?;
+                let storage = self.pools.get(pool_id).ok_or_else(|| JError::unsupported("Pool no longer valid (reset?)"))?;
+                let val = storage.get(*index).cloned().ok_or_else(|| JError::out_of_range("PoolRef index out of bounds"))?;
                 match field {
                     "value" | "get" => Ok(val),
-                    _ => Err(format!("PoolRef has no property '{}'", field)),
+                    _ => Err(JError::unsupported(format!("PoolRef has no property '{}'", field)).into()),
                 }
             }
             Value::SmallVec { .. } => Ok(Value::BoundMethod {
@@ -4693,11 +4768,11 @@ impl Interpreter {
                 receiver: Box::new(obj.clone()),
                 method: field.to_string(),
             }),
-            _ => Err(format!("Cannot get property '{}' on non-object", field)),
+            _ => Err(JError::unsupported(format!("Cannot get property '{}' on non-object", field)).into()),
         }
     }
 
-    pub(super) fn get_instance_method(&self, class_name: &str, method_name: &str) -> Result<Value, String> {
+    pub(super) fn get_instance_method(&self, class_name: &str, method_name: &str) -> JResult<Value> {
         let class_val = self.get_variable(class_name)?;
         if let Value::Class { methods, .. } = class_val {
             if let Some(m) = methods.get(method_name) {
@@ -4710,12 +4785,12 @@ impl Interpreter {
                     handle_missing: Box::new(handle_missing.clone()),
                 });
             }
-            Err(format!(
+            Err(JError::unsupported(format!(
                 "Unknown method '{}' on class '{}'",
                 method_name, class_name
-            ))
+            )).into())
         } else {
-            Err(format!("'{}' is not a class", class_name))
+            Err(JError::type_mismatch(format!("'{}' is not a class", class_name)).into())
         }
     }
 
@@ -4724,7 +4799,7 @@ impl Interpreter {
         callee: Value,
         args: &[AstNode],
         this_opt: Option<Value>,
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         let eval_args: Vec<Value> = args
             .iter()
             .map(|a| self.eval_node(a))
@@ -4733,13 +4808,65 @@ impl Interpreter {
     }
 
     fn memo_cache_key(args: &[Value]) -> String {
-        args.iter()
-            .map(|v| v.to_string())
-            .collect::<Vec<_>>()
-            .join("\x00")
+        let mut key = String::new();
+        for arg in args {
+            Self::write_memo_key(arg, &mut key);
+            key.push('\x00');
+        }
+        key
+    }
+
+    /// Every value, nested ones included, is written with its type so `[1]` and `["1"]` do not
+    /// share an entry. Dict, set and counter entries are sorted so equal values give equal keys.
+    fn write_memo_key(value: &Value, key: &mut String) {
+        let sequence = |key: &mut String, tag: &str, items: &mut dyn Iterator<Item = &Value>| {
+            key.push_str(tag);
+            key.push('[');
+            for item in items {
+                Self::write_memo_key(item, key);
+                key.push(',');
+            }
+            key.push(']');
+        };
+        match value {
+            Value::List(items) => sequence(key, "list", &mut items.iter()),
+            Value::Tuple(items) => sequence(key, "tuple", &mut items.iter()),
+            Value::Deque(items) => sequence(key, "deque", &mut items.iter()),
+            Value::Queue(items) => sequence(key, "queue", &mut items.iter()),
+            Value::Sorted(items) => sequence(key, "sorted", &mut items.iter()),
+            Value::Diff(items) => sequence(key, "diff", &mut items.iter()),
+            Value::Dict(map) => {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                key.push_str("dict{");
+                for (k, v) in entries {
+                    key.push_str(&format!("{:?}:", k));
+                    Self::write_memo_key(v, key);
+                    key.push(',');
+                }
+                key.push('}');
+            }
+            Value::Set(items) => {
+                let mut items: Vec<_> = items.iter().collect();
+                items.sort();
+                key.push_str(&format!("set{:?}", items));
+            }
+            Value::Counter(counts) | Value::Bag(counts) => {
+                let mut counts: Vec<_> = counts.iter().collect();
+                counts.sort();
+                key.push_str(&format!("{}{:?}", Self::value_type_name(value), counts));
+            }
+            // Debug output names the variant and quotes strings
+            Value::Integer(_)
+            | Value::Float(_)
+            | Value::String(_)
+            | Value::Boolean(_)
+            | Value::Char(_) => key.push_str(&format!("{:?}", value)),
+            _ => key.push_str(&format!("{}:{}", Self::value_type_name(value), value)),
+        }
     }
 
-    fn value_type_name(v: &Value) -> &'static str {
+    pub(super) fn value_type_name(v: &Value) -> &'static str {
         match v {
             Value::Integer(_) => "int",
             Value::Float(_) => "float",
@@ -4756,6 +4883,7 @@ impl Interpreter {
             Value::DateTimeType => "datetime_type",
             Value::GraphType => "graph_type",
             Value::Duration { .. } => "duration",
+            Value::Error(_) => "error",
             Value::EnumVariant { .. } => "enum_variant",
             _ => "value",
         }
@@ -4767,7 +4895,7 @@ impl Interpreter {
         method: &str,
         eval_args: &[Value],
         _this_opt: Option<Value>,
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         match &receiver {
             Value::List(list) => self.call_list_method(list, method, eval_args),
             Value::String(s) => self.call_string_method(s, method, eval_args),
@@ -4790,11 +4918,11 @@ impl Interpreter {
             } => self.call_enum_variant_method(enum_name, variant_name, value.as_ref(), method, eval_args),
             Value::Pool(pool_id) => self.call_pool_method(*pool_id, method, eval_args),
             Value::SmallVec { cap, elements } => self.call_smallvec_method(*cap, elements, method, eval_args),
-            _ => Err(format!(
+            _ => Err(JError::unsupported(format!(
                 "Bound method '{}' not supported for type {}",
                 method,
                 Self::value_type_name(&receiver)
-            )),
+            )).into()),
         }
     }
 
@@ -4803,16 +4931,16 @@ impl Interpreter {
         pool_id: usize,
         method: &str,
         args: &[Value],
-    ) -> Result<Value, String> {
-        let storage = self.pools.get_mut(&pool_id).ok_or("Pool no longer valid".to_string())?;
+    ) -> JResult<Value> {
+        let storage = self.pools.get_mut(&pool_id).ok_or_else(|| JError::unsupported("Pool no longer valid"))?;
         match method {
             "alloc_str" => {
                 if args.len() != 1 {
-                    return Err("pool.alloc_str(s) requires exactly 1 argument".to_string());
+                    return Err(JError::argument_count("pool.alloc_str(s) requires exactly 1 argument").into());
                 }
                 let s = match &args[0] {
                     Value::String(x) => x.clone(),
-                    _ => return Err("pool.alloc_str(s) requires string".to_string()),
+                    _ => return Err(JError::invalid_argument("pool.alloc_str(s) requires string").into()),
                 };
                 storage.push(Value::String(s.clone()));
                 let index = storage.len() - 1;
@@ -4827,10 +4955,10 @@ impl Interpreter {
             }
             "alloc_mat" => {
                 if args.len() < 2 {
-                    return Err("pool.alloc_mat(rows, cols) requires 2 arguments".to_string());
+                    return Err(JError::argument_count("pool.alloc_mat(rows, cols) requires 2 arguments").into());
                 }
-                let rows = match &args[0] { Value::Integer(i) if *i >= 0 => *i as usize, _ => return Err("alloc_mat rows must be non-negative int".to_string()) };
-                let cols = match &args[1] { Value::Integer(i) if *i >= 0 => *i as usize, _ => return Err("alloc_mat cols must be non-negative int".to_string()) };
+                let rows = match &args[0] { Value::Integer(i) if *i >= 0 => *i as usize, _ => return Err(JError::invalid_argument("alloc_mat rows must be non-negative int").into()) };
+                let cols = match &args[1] { Value::Integer(i) if *i >= 0 => *i as usize, _ => return Err(JError::invalid_argument("alloc_mat cols must be non-negative int").into()) };
                 let mat = vec![vec![0.0; cols]; rows];
                 storage.push(Value::Matrix(mat));
                 let index = storage.len() - 1;
@@ -4841,7 +4969,7 @@ impl Interpreter {
                 Ok(Value::None)
             }
             "len" => Ok(Value::Integer(storage.len() as i64)),
-            _ => Err(format!("Pool method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("Pool method '{}' not found", method)).into()),
         }
     }
 
@@ -4851,12 +4979,12 @@ impl Interpreter {
         elements: &[Value],
         method: &str,
         args: &[Value],
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         let mut vec = elements.to_vec();
         match method {
             "push" => {
                 if args.len() != 1 {
-                    return Err("smallvec.push(x) requires 1 argument".to_string());
+                    return Err(JError::argument_count("smallvec.push(x) requires 1 argument").into());
                 }
                 vec.push(args[0].clone());
                 Ok(Value::SmallVec { cap, elements: vec })
@@ -4865,7 +4993,7 @@ impl Interpreter {
             "len" | "length" | "size" => Ok(Value::Integer(vec.len() as i64)),
             "first" => Ok(vec.first().cloned().unwrap_or(Value::None)),
             "last" => Ok(vec.last().cloned().unwrap_or(Value::None)),
-            _ => Err(format!("SmallVec method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("SmallVec method '{}' not found", method)).into()),
         }
     }
 
@@ -4874,61 +5002,61 @@ impl Interpreter {
         list: &[Value],
         method: &str,
         args: &[Value],
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         match method {
             "len" | "length" | "size" => {
                 if !args.is_empty() {
-                    return Err("list.len() takes no arguments".to_string());
+                    return Err(JError::argument_count("list.len() takes no arguments").into());
                 }
                 Ok(Value::Integer(list.len() as i64))
             }
             "first" => {
                 if !args.is_empty() {
-                    return Err("list.first() takes no arguments".to_string());
+                    return Err(JError::argument_count("list.first() takes no arguments").into());
                 }
                 list.first()
                     .cloned()
-                    .ok_or_else(|| "List is empty".to_string())
+                    .ok_or_else(|| JError::empty_collection("List is empty").into())
             }
             "last" => {
                 if !args.is_empty() {
-                    return Err("list.last() takes no arguments".to_string());
+                    return Err(JError::argument_count("list.last() takes no arguments").into());
                 }
                 list.last()
                     .cloned()
-                    .ok_or_else(|| "List is empty".to_string())
+                    .ok_or_else(|| JError::empty_collection("List is empty").into())
             }
             "push" => {
                 let mut new_list = list.to_vec();
                 if args.len() != 1 {
-                    return Err("list.push(value) requires exactly one argument".to_string());
+                    return Err(JError::argument_count("list.push(value) requires exactly one argument").into());
                 }
                 new_list.push(args[0].clone());
                 Ok(Value::List(new_list))
             }
             "pop" => {
                 if !args.is_empty() {
-                    return Err("list.pop() takes no arguments".to_string());
+                    return Err(JError::argument_count("list.pop() takes no arguments").into());
                 }
                 list.last()
                     .cloned()
-                    .ok_or_else(|| "List is empty".to_string())
+                    .ok_or_else(|| JError::empty_collection("List is empty").into())
             }
             "append" => {
                 let mut new_list = list.to_vec();
                 if args.len() != 1 {
-                    return Err("list.append(value) requires exactly one argument".to_string());
+                    return Err(JError::argument_count("list.append(value) requires exactly one argument").into());
                 }
                 new_list.push(args[0].clone());
                 Ok(Value::List(new_list))
             }
             "get" => {
                 if args.is_empty() {
-                    return Err("list.get(index) requires an index argument".to_string());
+                    return Err(JError::invalid_argument("list.get(index) requires an index argument").into());
                 }
                 let idx = match &args[0] {
                     Value::Integer(i) => *i,
-                    _ => return Err("list.get(index) expects integer index".to_string()),
+                    _ => return Err(JError::invalid_argument("list.get(index) expects integer index").into()),
                 };
                 let idx = idx as usize;
                 if args.len() >= 2 {
@@ -4937,19 +5065,19 @@ impl Interpreter {
                 } else {
                     list.get(idx)
                         .cloned()
-                        .ok_or_else(|| format!("List index {} out of range", idx))
+                        .ok_or_else(|| JError::out_of_range(format!("List index {} out of range", idx)).into())
                 }
             }
             "contains" => {
                 if args.len() != 1 {
-                    return Err("list.contains(value) requires exactly one argument".to_string());
+                    return Err(JError::argument_count("list.contains(value) requires exactly one argument").into());
                 }
                 let target = &args[0];
                 Ok(Value::Boolean(list.iter().any(|v| self.values_equal(v, target))))
             }
             "index" | "find" => {
                 if args.is_empty() {
-                    return Err("list.index(value) requires at least one argument".to_string());
+                    return Err(JError::argument_count("list.index(value) requires at least one argument").into());
                 }
                 let target = &args[0];
                 let start = if args.len() >= 2 {
@@ -4964,11 +5092,11 @@ impl Interpreter {
                     .skip(start)
                     .position(|v| self.values_equal(v, target))
                     .map(|i| Value::Integer((i + start) as i64))
-                    .ok_or_else(|| "Value not found in list".to_string())
+                    .ok_or_else(|| JError::unsupported("Value not found in list").into())
             }
             "count" => {
                 if args.len() != 1 {
-                    return Err("list.count(value) requires exactly one argument".to_string());
+                    return Err(JError::argument_count("list.count(value) requires exactly one argument").into());
                 }
                 let target = &args[0];
                 let n = list
@@ -4979,11 +5107,11 @@ impl Interpreter {
             }
             "join" => {
                 if args.len() != 1 {
-                    return Err("list.join(separator) requires exactly one argument".to_string());
+                    return Err(JError::argument_count("list.join(separator) requires exactly one argument").into());
                 }
                 let sep = match &args[0] {
                     Value::String(s) => s.as_str(),
-                    _ => return Err("list.join(separator) expects string".to_string()),
+                    _ => return Err(JError::invalid_argument("list.join(separator) expects string").into()),
                 };
                 let parts: Vec<String> = list
                     .iter()
@@ -4993,11 +5121,11 @@ impl Interpreter {
             }
             "insert" => {
                 if args.len() < 2 {
-                    return Err("list.insert(index, value) requires two arguments".to_string());
+                    return Err(JError::argument_count("list.insert(index, value) requires two arguments").into());
                 }
                 let idx = match &args[0] {
                     Value::Integer(i) => *i as usize,
-                    _ => return Err("list.insert(index, value) expects integer index".to_string()),
+                    _ => return Err(JError::invalid_argument("list.insert(index, value) expects integer index").into()),
                 };
                 let mut new_list = list.to_vec();
                 if idx > new_list.len() {
@@ -5058,11 +5186,11 @@ impl Interpreter {
             }
             "empty" | "is_empty" => {
                 if !args.is_empty() {
-                    return Err("list.is_empty() takes no arguments".to_string());
+                    return Err(JError::argument_count("list.is_empty() takes no arguments").into());
                 }
                 Ok(Value::Boolean(list.is_empty()))
             }
-            _ => Err(format!("List method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("List method '{}' not found", method)).into()),
         }
     }
 
@@ -5071,47 +5199,47 @@ impl Interpreter {
         s: &str,
         method: &str,
         args: &[Value],
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         match method {
             "len" | "length" | "size" => {
                 if !args.is_empty() {
-                    return Err("str.len() takes no arguments".to_string());
+                    return Err(JError::argument_count("str.len() takes no arguments").into());
                 }
                 Ok(Value::Integer(s.len() as i64))
             }
             "trim" => {
                 if !args.is_empty() {
-                    return Err("str.trim() takes no arguments".to_string());
+                    return Err(JError::argument_count("str.trim() takes no arguments").into());
                 }
                 Ok(Value::String(s.trim().to_string()))
             }
             "trim_start" | "trim_left" => {
                 if !args.is_empty() {
-                    return Err("str.trim_start() takes no arguments".to_string());
+                    return Err(JError::argument_count("str.trim_start() takes no arguments").into());
                 }
                 Ok(Value::String(s.trim_start().to_string()))
             }
             "trim_end" | "trim_right" => {
                 if !args.is_empty() {
-                    return Err("str.trim_end() takes no arguments".to_string());
+                    return Err(JError::argument_count("str.trim_end() takes no arguments").into());
                 }
                 Ok(Value::String(s.trim_end().to_string()))
             }
             "upper" | "uppercase" | "to_upper" => {
                 if !args.is_empty() {
-                    return Err("str.to_upper() takes no arguments".to_string());
+                    return Err(JError::argument_count("str.to_upper() takes no arguments").into());
                 }
                 Ok(Value::String(s.to_uppercase()))
             }
             "lower" | "lowercase" | "to_lower" => {
                 if !args.is_empty() {
-                    return Err("str.to_lower() takes no arguments".to_string());
+                    return Err(JError::argument_count("str.to_lower() takes no arguments").into());
                 }
                 Ok(Value::String(s.to_lowercase()))
             }
             "replace" => {
                 if args.len() < 2 {
-                    return Err("str.replace(old, new) requires two arguments".to_string());
+                    return Err(JError::argument_count("str.replace(old, new) requires two arguments").into());
                 }
                 let old_str = args[0].to_string();
                 let new_str = args[1].to_string();
@@ -5132,7 +5260,7 @@ impl Interpreter {
             }
             "split" => {
                 if args.is_empty() {
-                    return Err("str.split(sep) requires at least one argument".to_string());
+                    return Err(JError::argument_count("str.split(sep) requires at least one argument").into());
                 }
                 let sep = args[0].to_string();
                 let parts: Vec<Value> = if sep.is_empty() {
@@ -5144,28 +5272,28 @@ impl Interpreter {
             }
             "starts_with" => {
                 if args.len() != 1 {
-                    return Err("str.starts_with(prefix) requires one argument".to_string());
+                    return Err(JError::argument_count("str.starts_with(prefix) requires one argument").into());
                 }
                 let prefix = args[0].to_string();
                 Ok(Value::Boolean(s.starts_with(&prefix)))
             }
             "ends_with" => {
                 if args.len() != 1 {
-                    return Err("str.ends_with(suffix) requires one argument".to_string());
+                    return Err(JError::argument_count("str.ends_with(suffix) requires one argument").into());
                 }
                 let suffix = args[0].to_string();
                 Ok(Value::Boolean(s.ends_with(&suffix)))
             }
             "contains" => {
                 if args.len() != 1 {
-                    return Err("str.contains(sub) requires one argument".to_string());
+                    return Err(JError::argument_count("str.contains(sub) requires one argument").into());
                 }
                 let sub = args[0].to_string();
                 Ok(Value::Boolean(s.contains(&sub)))
             }
             "find" => {
                 if args.is_empty() {
-                    return Err("str.find(sub) requires at least one argument".to_string());
+                    return Err(JError::argument_count("str.find(sub) requires at least one argument").into());
                 }
                 let sub = args[0].to_string();
                 let start = if args.len() >= 2 {
@@ -5183,111 +5311,111 @@ impl Interpreter {
             }
             "repeat" => {
                 if args.len() != 1 {
-                    return Err("str.repeat(n) requires one argument".to_string());
+                    return Err(JError::argument_count("str.repeat(n) requires one argument").into());
                 }
                 let n = match &args[0] {
                     Value::Integer(i) => *i as usize,
-                    _ => return Err("str.repeat(n) expects integer".to_string()),
+                    _ => return Err(JError::invalid_argument("str.repeat(n) expects integer").into()),
                 };
                 Ok(Value::String(s.repeat(n)))
             }
             "empty" | "is_empty" => {
                 if !args.is_empty() {
-                    return Err("str.is_empty() takes no arguments".to_string());
+                    return Err(JError::argument_count("str.is_empty() takes no arguments").into());
                 }
                 Ok(Value::Boolean(s.is_empty()))
             }
-            _ => Err(format!("String method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("String method '{}' not found", method)).into()),
         }
     }
 
-    fn call_int_method(&self, i: i64, method: &str, args: &[Value]) -> Result<Value, String> {
+    fn call_int_method(&self, i: i64, method: &str, args: &[Value]) -> JResult<Value> {
         match method {
             "bit" => {
                 if args.len() != 1 {
-                    return Err("int.bit(n) requires one argument".to_string());
+                    return Err(JError::argument_count("int.bit(n) requires one argument").into());
                 }
                 let n = match &args[0] {
                     Value::Integer(n) => *n as u32,
-                    _ => return Err("int.bit(n) expects integer".to_string()),
+                    _ => return Err(JError::invalid_argument("int.bit(n) expects integer").into()),
                 };
                 Ok(Value::Integer(if (i >> n) & 1 != 0 { 1 } else { 0 }))
             }
             "set_bit" => {
                 if args.len() != 1 {
-                    return Err("int.set_bit(n) requires one argument".to_string());
+                    return Err(JError::argument_count("int.set_bit(n) requires one argument").into());
                 }
                 let n = match &args[0] {
                     Value::Integer(n) => *n as u32,
-                    _ => return Err("int.set_bit(n) expects integer".to_string()),
+                    _ => return Err(JError::invalid_argument("int.set_bit(n) expects integer").into()),
                 };
                 Ok(Value::Integer(i | (1i64 << n)))
             }
             "clear_bit" => {
                 if args.len() != 1 {
-                    return Err("int.clear_bit(n) requires one argument".to_string());
+                    return Err(JError::argument_count("int.clear_bit(n) requires one argument").into());
                 }
                 let n = match &args[0] {
                     Value::Integer(n) => *n as u32,
-                    _ => return Err("int.clear_bit(n) expects integer".to_string()),
+                    _ => return Err(JError::invalid_argument("int.clear_bit(n) expects integer").into()),
                 };
                 Ok(Value::Integer(i & !(1i64 << n)))
             }
             "toggle_bit" => {
                 if args.len() != 1 {
-                    return Err("int.toggle_bit(n) requires one argument".to_string());
+                    return Err(JError::argument_count("int.toggle_bit(n) requires one argument").into());
                 }
                 let n = match &args[0] {
                     Value::Integer(n) => *n as u32,
-                    _ => return Err("int.toggle_bit(n) expects integer".to_string()),
+                    _ => return Err(JError::invalid_argument("int.toggle_bit(n) expects integer").into()),
                 };
                 Ok(Value::Integer(i ^ (1i64 << n)))
             }
             "popcount" | "count_bits" => {
                 if !args.is_empty() {
-                    return Err("int.popcount() takes no arguments".to_string());
+                    return Err(JError::argument_count("int.popcount() takes no arguments").into());
                 }
                 Ok(Value::Integer(i.count_ones() as i64))
             }
             "trailing_zeros" => {
                 if !args.is_empty() {
-                    return Err("int.trailing_zeros() takes no arguments".to_string());
+                    return Err(JError::argument_count("int.trailing_zeros() takes no arguments").into());
                 }
                 Ok(Value::Integer(i.trailing_zeros() as i64))
             }
             "leading_zeros" => {
                 if !args.is_empty() {
-                    return Err("int.leading_zeros() takes no arguments".to_string());
+                    return Err(JError::argument_count("int.leading_zeros() takes no arguments").into());
                 }
                 Ok(Value::Integer(i.leading_zeros() as i64))
             }
             "is_power_of_two" => {
                 if !args.is_empty() {
-                    return Err("int.is_power_of_two() takes no arguments".to_string());
+                    return Err(JError::argument_count("int.is_power_of_two() takes no arguments").into());
                 }
                 Ok(Value::Boolean(i > 0 && (i & (i - 1)) == 0))
             }
             "div_ceil" => {
                 if args.len() != 1 {
-                    return Err("int.div_ceil(other) requires one argument".to_string());
+                    return Err(JError::argument_count("int.div_ceil(other) requires one argument").into());
                 }
                 let other = match &args[0] {
                     Value::Integer(j) => *j,
-                    _ => return Err("int.div_ceil(other) expects integer".to_string()),
+                    _ => return Err(JError::invalid_argument("int.div_ceil(other) expects integer").into()),
                 };
                 if other == 0 {
-                    return Err("division by zero".to_string());
+                    return Err(JError::division_by_zero(0, 0).raised_as("division by zero").into());
                 }
                 Ok(Value::Integer((i + other - 1) / other))
             }
             "gcd" => {
                 if args.len() != 1 {
-                    return Err("int.gcd(other) requires one argument".to_string());
+                    return Err(JError::argument_count("int.gcd(other) requires one argument").into());
                 }
                 let mut a = i.abs();
                 let mut b = match &args[0] {
                     Value::Integer(j) => j.abs(),
-                    _ => return Err("int.gcd(other) expects integer".to_string()),
+                    _ => return Err(JError::invalid_argument("int.gcd(other) expects integer").into()),
                 };
                 while b != 0 {
                     let t = b;
@@ -5298,11 +5426,11 @@ impl Interpreter {
             }
             "lcm" => {
                 if args.len() != 1 {
-                    return Err("int.lcm(other) requires one argument".to_string());
+                    return Err(JError::argument_count("int.lcm(other) requires one argument").into());
                 }
                 let other = match &args[0] {
                     Value::Integer(j) => *j,
-                    _ => return Err("int.lcm(other) expects integer".to_string()),
+                    _ => return Err(JError::invalid_argument("int.lcm(other) expects integer").into()),
                 };
                 let mut a = i.abs();
                 let mut b = other.abs();
@@ -5317,7 +5445,7 @@ impl Interpreter {
                 }
                 Ok(Value::Integer(prod / a))
             }
-            _ => Err(format!("Integer method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("Integer method '{}' not found", method)).into()),
         }
     }
 
@@ -5326,16 +5454,16 @@ impl Interpreter {
         f: f64,
         method: &str,
         args: &[Value],
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         match method {
             "approx_eq" => {
                 if args.len() < 1 {
-                    return Err("float.approx_eq(other) requires at least one argument".to_string());
+                    return Err(JError::argument_count("float.approx_eq(other) requires at least one argument").into());
                 }
                 let other = match &args[0] {
                     Value::Float(x) => *x,
                     Value::Integer(n) => *n as f64,
-                    _ => return Err("float.approx_eq(other) expects number".to_string()),
+                    _ => return Err(JError::invalid_argument("float.approx_eq(other) expects number").into()),
                 };
                 let eps = if args.len() >= 2 {
                     match &args[1] {
@@ -5350,25 +5478,25 @@ impl Interpreter {
             }
             "is_nan" => {
                 if !args.is_empty() {
-                    return Err("float.is_nan() takes no arguments".to_string());
+                    return Err(JError::argument_count("float.is_nan() takes no arguments").into());
                 }
                 Ok(Value::Boolean(f.is_nan()))
             }
             "is_inf" | "is_infinite" => {
                 if !args.is_empty() {
-                    return Err("float.is_inf() takes no arguments".to_string());
+                    return Err(JError::argument_count("float.is_inf() takes no arguments").into());
                 }
                 Ok(Value::Boolean(f.is_infinite()))
             }
             "is_finite" => {
                 if !args.is_empty() {
-                    return Err("float.is_finite() takes no arguments".to_string());
+                    return Err(JError::argument_count("float.is_finite() takes no arguments").into());
                 }
                 Ok(Value::Boolean(f.is_finite()))
             }
             "sign" => {
                 if !args.is_empty() {
-                    return Err("float.sign() takes no arguments".to_string());
+                    return Err(JError::argument_count("float.sign() takes no arguments").into());
                 }
                 Ok(Value::Integer(if f > 0.0 {
                     1
@@ -5380,39 +5508,39 @@ impl Interpreter {
             }
             "clamp" => {
                 if args.len() < 2 {
-                    return Err("float.clamp(min, max) requires two arguments".to_string());
+                    return Err(JError::argument_count("float.clamp(min, max) requires two arguments").into());
                 }
                 let min_val = match &args[0] {
                     Value::Float(x) => *x,
                     Value::Integer(n) => *n as f64,
-                    _ => return Err("float.clamp(min, max) expects numbers".to_string()),
+                    _ => return Err(JError::invalid_argument("float.clamp(min, max) expects numbers").into()),
                 };
                 let max_val = match &args[1] {
                     Value::Float(x) => *x,
                     Value::Integer(n) => *n as f64,
-                    _ => return Err("float.clamp(min, max) expects numbers".to_string()),
+                    _ => return Err(JError::invalid_argument("float.clamp(min, max) expects numbers").into()),
                 };
                 Ok(Value::Float(f.clamp(min_val, max_val)))
             }
             "fract" => {
                 if !args.is_empty() {
-                    return Err("float.fract() takes no arguments".to_string());
+                    return Err(JError::argument_count("float.fract() takes no arguments").into());
                 }
                 Ok(Value::Float(f - f.floor()))
             }
             "to_degrees" => {
                 if !args.is_empty() {
-                    return Err("float.to_degrees() takes no arguments".to_string());
+                    return Err(JError::argument_count("float.to_degrees() takes no arguments").into());
                 }
                 Ok(Value::Float(f.to_degrees()))
             }
             "to_radians" => {
                 if !args.is_empty() {
-                    return Err("float.to_radians() takes no arguments".to_string());
+                    return Err(JError::argument_count("float.to_radians() takes no arguments").into());
                 }
                 Ok(Value::Float(f.to_radians()))
             }
-            _ => Err(format!("Float method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("Float method '{}' not found", method)).into()),
         }
     }
 
@@ -5421,11 +5549,11 @@ impl Interpreter {
         dict: &std::collections::HashMap<String, Value>,
         method: &str,
         args: &[Value],
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         match method {
             "get" => {
                 if args.is_empty() {
-                    return Err("dict.get(key) requires at least one argument".to_string());
+                    return Err(JError::argument_count("dict.get(key) requires at least one argument").into());
                 }
                 let key = args[0].to_string();
                 if let Some(v) = dict.get(&key) {
@@ -5439,20 +5567,20 @@ impl Interpreter {
             }
             "has" | "contains_key" => {
                 if args.len() != 1 {
-                    return Err("dict.has(key) requires one argument".to_string());
+                    return Err(JError::argument_count("dict.has(key) requires one argument").into());
                 }
                 let key = args[0].to_string();
                 Ok(Value::Boolean(dict.contains_key(&key)))
             }
             "len" | "length" | "size" => {
                 if !args.is_empty() {
-                    return Err("dict.len() takes no arguments".to_string());
+                    return Err(JError::argument_count("dict.len() takes no arguments").into());
                 }
                 Ok(Value::Integer(dict.len() as i64))
             }
             "keys" => {
                 if !args.is_empty() {
-                    return Err("dict.keys() takes no arguments".to_string());
+                    return Err(JError::argument_count("dict.keys() takes no arguments").into());
                 }
                 let keys: Vec<Value> =
                     dict.keys().map(|k| Value::String(k.clone())).collect();
@@ -5460,14 +5588,14 @@ impl Interpreter {
             }
             "values" => {
                 if !args.is_empty() {
-                    return Err("dict.values() takes no arguments".to_string());
+                    return Err(JError::argument_count("dict.values() takes no arguments").into());
                 }
                 let values: Vec<Value> = dict.values().cloned().collect();
                 Ok(Value::List(values))
             }
             "items" => {
                 if !args.is_empty() {
-                    return Err("dict.items() takes no arguments".to_string());
+                    return Err(JError::argument_count("dict.items() takes no arguments").into());
                 }
                 let items: Vec<Value> = dict
                     .iter()
@@ -5475,7 +5603,7 @@ impl Interpreter {
                     .collect();
                 Ok(Value::List(items))
             }
-            _ => Err(format!("Dict method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("Dict method '{}' not found", method)).into()),
         }
     }
 
@@ -5540,29 +5668,29 @@ impl Interpreter {
         }
     }
 
-    fn parse_date_parts(s: &str) -> Result<(i64, i64, i64), String> {
+    fn parse_date_parts(s: &str) -> JResult<(i64, i64, i64)> {
         let parts: Vec<&str> = s.trim().split('-').collect();
         if parts.len() != 3 {
-            return Err(format!("Invalid date format '{}', use YYYY-MM-DD", s));
+            return Err(JError::invalid_argument(format!("Invalid date format '{}', use YYYY-MM-DD", s)).into());
         }
-        let y: i64 = parts[0].parse().map_err(|_| "Invalid year")?;
-        let m: i64 = parts[1].parse().map_err(|_| "Invalid month")?;
-        let d: i64 = parts[2].parse().map_err(|_| "Invalid day")?;
+        let y: i64 = parts[0].parse().map_err(|_| JError::invalid_argument("Invalid year"))?;
+        let m: i64 = parts[1].parse().map_err(|_| JError::invalid_argument("Invalid month"))?;
+        let d: i64 = parts[2].parse().map_err(|_| JError::invalid_argument("Invalid day"))?;
         Ok((y, m, d))
     }
 
     /// Parse date from YYYY-MM-DD or MM/DD/YYYY (and DD/MM/YYYY when first part > 12).
     /// Returns (year, month, day) and canonical "YYYY-MM-DD" string.
-    fn parse_date_flexible(s: &str) -> Result<(i64, i64, i64, String), String> {
+    fn parse_date_flexible(s: &str) -> JResult<(i64, i64, i64, String)> {
         let s = s.trim();
         if s.contains('/') {
             let parts: Vec<&str> = s.split('/').collect();
             if parts.len() != 3 {
-                return Err(format!("Invalid date format '{}', use YYYY-MM-DD or MM/DD/YYYY", s));
+                return Err(JError::invalid_argument(format!("Invalid date format '{}', use YYYY-MM-DD or MM/DD/YYYY", s)).into());
             }
-            let a: i64 = parts[0].parse().map_err(|_| "Invalid number in date")?;
-            let b: i64 = parts[1].parse().map_err(|_| "Invalid number in date")?;
-            let c: i64 = parts[2].parse().map_err(|_| "Invalid number in date")?;
+            let a: i64 = parts[0].parse().map_err(|_| JError::invalid_argument("Invalid number in date"))?;
+            let b: i64 = parts[1].parse().map_err(|_| JError::invalid_argument("Invalid number in date"))?;
+            let c: i64 = parts[2].parse().map_err(|_| JError::invalid_argument("Invalid number in date"))?;
             let (y, m, d) = if a > 31 || (a <= 12 && b <= 31) {
                 // MM/DD/YYYY (US) or YYYY/MM/DD when first is year
                 if a > 31 {
@@ -5582,7 +5710,7 @@ impl Interpreter {
         }
     }
 
-    fn parse_time_parts(s: &str) -> Result<(i64, i64, i64, i64), String> {
+    fn parse_time_parts(s: &str) -> JResult<(i64, i64, i64, i64)> {
         let s = s.trim();
         let (time_part, millis) = if let Some((t, ms)) = s.split_once('.') {
             (t, ms.parse::<i64>().unwrap_or(0))
@@ -5591,45 +5719,45 @@ impl Interpreter {
         };
         let parts: Vec<&str> = time_part.split(':').collect();
         if parts.len() < 3 {
-            return Err(format!("Invalid time format '{}', use HH:MM:SS", s));
+            return Err(JError::invalid_argument(format!("Invalid time format '{}', use HH:MM:SS", s)).into());
         }
-        let h: i64 = parts[0].parse().map_err(|_| "Invalid hour")?;
-        let min: i64 = parts[1].parse().map_err(|_| "Invalid minute")?;
-        let sec: i64 = parts[2].parse().map_err(|_| "Invalid second")?;
+        let h: i64 = parts[0].parse().map_err(|_| JError::invalid_argument("Invalid hour"))?;
+        let min: i64 = parts[1].parse().map_err(|_| JError::invalid_argument("Invalid minute"))?;
+        let sec: i64 = parts[2].parse().map_err(|_| JError::invalid_argument("Invalid second"))?;
         Ok((h, min, sec, millis))
     }
 
-    fn call_date_method(&self, s: &str, method: &str, args: &[Value]) -> Result<Value, String> {
+    fn call_date_method(&self, s: &str, method: &str, args: &[Value]) -> JResult<Value> {
         let (y, m, d) = Self::parse_date_parts(s)?;
         match method {
             "year" => {
                 if !args.is_empty() {
-                    return Err("date.year() takes no arguments".to_string());
+                    return Err(JError::argument_count("date.year() takes no arguments").into());
                 }
                 Ok(Value::Integer(y))
             }
             "month" => {
                 if !args.is_empty() {
-                    return Err("date.month() takes no arguments".to_string());
+                    return Err(JError::argument_count("date.month() takes no arguments").into());
                 }
                 Ok(Value::Integer(m))
             }
             "day" => {
                 if !args.is_empty() {
-                    return Err("date.day() takes no arguments".to_string());
+                    return Err(JError::argument_count("date.day() takes no arguments").into());
                 }
                 Ok(Value::Integer(d))
             }
             "weekday" => {
                 if !args.is_empty() {
-                    return Err("date.weekday() takes no arguments".to_string());
+                    return Err(JError::argument_count("date.weekday() takes no arguments").into());
                 }
                 let weekday = Self::weekday_from_ymd(y, m, d);
                 Ok(Value::Integer(weekday))
             }
             "weekday_name" | "day_of_week" => {
                 if !args.is_empty() {
-                    return Err("date.weekday_name() takes no arguments".to_string());
+                    return Err(JError::argument_count("date.weekday_name() takes no arguments").into());
                 }
                 let w = Self::weekday_from_ymd(y, m, d);
                 let name = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"]
@@ -5640,7 +5768,7 @@ impl Interpreter {
             }
             "month_name" => {
                 if !args.is_empty() {
-                    return Err("date.month_name() takes no arguments".to_string());
+                    return Err(JError::argument_count("date.month_name() takes no arguments").into());
                 }
                 let name = [
                     "January", "February", "March", "April", "May", "June",
@@ -5653,24 +5781,24 @@ impl Interpreter {
             }
             "is_leap" => {
                 if !args.is_empty() {
-                    return Err("date.is_leap() takes no arguments".to_string());
+                    return Err(JError::argument_count("date.is_leap() takes no arguments").into());
                 }
                 Ok(Value::Boolean(Self::is_leap_year(y)))
             }
             "add_days" => {
                 if args.len() != 1 {
-                    return Err("date.add_days(n) requires one argument".to_string());
+                    return Err(JError::argument_count("date.add_days(n) requires one argument").into());
                 }
                 let n = match &args[0] {
                     Value::Integer(i) => *i,
-                    _ => return Err("date.add_days(n) expects integer".to_string()),
+                    _ => return Err(JError::invalid_argument("date.add_days(n) expects integer").into()),
                 };
                 let (ny, nm, nd) = Self::add_days_ymd(y, m, d, n);
                 Ok(Value::Date(format!("{:04}-{:02}-{:02}", ny, nm, nd)))
             }
             "format" => {
                 if args.is_empty() {
-                    return Err("date.format(fmt) requires one argument".to_string());
+                    return Err(JError::argument_count("date.format(fmt) requires one argument").into());
                 }
                 let fmt = args[0].to_string();
                 let out = if fmt == "YYYY-MM-DD" || fmt.is_empty() {
@@ -5682,11 +5810,11 @@ impl Interpreter {
             }
             "difference" => {
                 if args.len() != 1 {
-                    return Err("date.difference(other) requires one argument".to_string());
+                    return Err(JError::argument_count("date.difference(other) requires one argument").into());
                 }
                 let other = match &args[0] {
                     Value::Date(os) => os.as_str(),
-                    _ => return Err("date.difference(other) expects date".to_string()),
+                    _ => return Err(JError::invalid_argument("date.difference(other) expects date").into()),
                 };
                 let (oy, om, od) = Self::parse_date_parts(other)?;
                 let days = Self::days_between(oy, om, od, y, m, d);
@@ -5694,7 +5822,7 @@ impl Interpreter {
                     total_seconds: days * 24 * 3600,
                 })
             }
-            _ => Err(format!("Date method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("Date method '{}' not found", method)).into()),
         }
     }
 
@@ -5760,69 +5888,69 @@ impl Interpreter {
         (d + (153 * nm + 2) / 5 + 365 * ny + ny / 4 - ny / 100 + ny / 400 - 32045) as i64
     }
 
-    fn call_time_method(&self, s: &str, method: &str, args: &[Value]) -> Result<Value, String> {
+    fn call_time_method(&self, s: &str, method: &str, args: &[Value]) -> JResult<Value> {
         let (h, min, sec, millis) = Self::parse_time_parts(s)?;
         match method {
             "hour" => {
                 if !args.is_empty() {
-                    return Err("time.hour() takes no arguments".to_string());
+                    return Err(JError::argument_count("time.hour() takes no arguments").into());
                 }
                 Ok(Value::Integer(h))
             }
             "minute" => {
                 if !args.is_empty() {
-                    return Err("time.minute() takes no arguments".to_string());
+                    return Err(JError::argument_count("time.minute() takes no arguments").into());
                 }
                 Ok(Value::Integer(min))
             }
             "second" => {
                 if !args.is_empty() {
-                    return Err("time.second() takes no arguments".to_string());
+                    return Err(JError::argument_count("time.second() takes no arguments").into());
                 }
                 Ok(Value::Integer(sec))
             }
             "millis" => {
                 if !args.is_empty() {
-                    return Err("time.millis() takes no arguments".to_string());
+                    return Err(JError::argument_count("time.millis() takes no arguments").into());
                 }
                 Ok(Value::Integer(millis))
             }
             "add_hours" => {
                 if args.len() != 1 {
-                    return Err("time.add_hours(n) requires one argument".to_string());
+                    return Err(JError::argument_count("time.add_hours(n) requires one argument").into());
                 }
                 let n = match &args[0] {
                     Value::Integer(i) => *i,
-                    _ => return Err("time.add_hours(n) expects integer".to_string()),
+                    _ => return Err(JError::invalid_argument("time.add_hours(n) expects integer").into()),
                 };
                 let (nh, nmin, nsec) = Self::add_seconds_to_time(h, min, sec, n * 3600);
                 Ok(Value::Time(format!("{:02}:{:02}:{:02}", nh, nmin, nsec)))
             }
             "add_minutes" => {
                 if args.len() != 1 {
-                    return Err("time.add_minutes(n) requires one argument".to_string());
+                    return Err(JError::argument_count("time.add_minutes(n) requires one argument").into());
                 }
                 let n = match &args[0] {
                     Value::Integer(i) => *i,
-                    _ => return Err("time.add_minutes(n) expects integer".to_string()),
+                    _ => return Err(JError::invalid_argument("time.add_minutes(n) expects integer").into()),
                 };
                 let (nh, nmin, nsec) = Self::add_seconds_to_time(h, min, sec, n * 60);
                 Ok(Value::Time(format!("{:02}:{:02}:{:02}", nh, nmin, nsec)))
             }
             "is_midnight" => {
                 if !args.is_empty() {
-                    return Err("time.is_midnight() takes no arguments".to_string());
+                    return Err(JError::argument_count("time.is_midnight() takes no arguments").into());
                 }
                 Ok(Value::Boolean(h == 0 && min == 0 && sec == 0))
             }
             "format" => {
                 if args.is_empty() {
-                    return Err("time.format(fmt) requires one argument".to_string());
+                    return Err(JError::argument_count("time.format(fmt) requires one argument").into());
                 }
                 let out = format!("{:02}:{:02}:{:02}", h, min, sec);
                 Ok(Value::String(out))
             }
-            _ => Err(format!("Time method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("Time method '{}' not found", method)).into()),
         }
     }
 
@@ -5835,7 +5963,7 @@ impl Interpreter {
         (h, min, s)
     }
 
-    fn call_datetime_method(&self, s: &str, method: &str, args: &[Value]) -> Result<Value, String> {
+    fn call_datetime_method(&self, s: &str, method: &str, args: &[Value]) -> JResult<Value> {
         let s = s.trim();
         let (date_part, time_part) = if let Some(pos) = s.find('T').or_else(|| s.find(' ')) {
             let (d, t) = s.split_at(pos);
@@ -5861,7 +5989,7 @@ impl Interpreter {
             }
             "format" => {
                 if args.is_empty() {
-                    return Err("datetime.format(fmt) requires one argument".to_string());
+                    return Err(JError::argument_count("datetime.format(fmt) requires one argument").into());
                 }
                 let out = format!(
                     "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
@@ -5904,11 +6032,11 @@ impl Interpreter {
             }
             "difference" => {
                 if args.len() != 1 {
-                    return Err("datetime.difference(other) requires one argument".to_string());
+                    return Err(JError::argument_count("datetime.difference(other) requires one argument").into());
                 }
                 let other = match &args[0] {
                     Value::DateTime(os) => os.as_str(),
-                    _ => return Err("datetime.difference(other) expects datetime".to_string()),
+                    _ => return Err(JError::invalid_argument("datetime.difference(other) expects datetime").into()),
                 };
                 let (od, ot) = if let Some(pos) = other.find('T').or_else(|| other.find(' ')) {
                     let (d, t) = other.split_at(pos);
@@ -5926,15 +6054,15 @@ impl Interpreter {
                     total_seconds: secs2 - secs1,
                 })
             }
-            _ => Err(format!("DateTime method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("DateTime method '{}' not found", method)).into()),
         }
     }
 
-    fn call_date_type_method(&self, method: &str, args: &[Value]) -> Result<Value, String> {
+    fn call_date_type_method(&self, method: &str, args: &[Value]) -> JResult<Value> {
         match method {
             "today" => {
                 if !args.is_empty() {
-                    return Err("date.today() takes no arguments".to_string());
+                    return Err(JError::argument_count("date.today() takes no arguments").into());
                 }
                 let now = std::time::SystemTime::now();
                 let dur = now.duration_since(std::time::UNIX_EPOCH).unwrap();
@@ -5946,13 +6074,13 @@ impl Interpreter {
             }
             "parse" => {
                 if args.is_empty() {
-                    return Err("date.parse(str) requires one argument".to_string());
+                    return Err(JError::argument_count("date.parse(str) requires one argument").into());
                 }
                 let s = args[0].to_string();
                 let (_, _, _, canonical) = Self::parse_date_flexible(&s)?;
                 Ok(Value::Date(canonical))
             }
-            _ => Err(format!("Date type method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("Date type method '{}' not found", method)).into()),
         }
     }
 
@@ -5969,11 +6097,11 @@ impl Interpreter {
         (year as i64, month as i64, day as i64)
     }
 
-    fn call_time_type_method(&self, method: &str, args: &[Value]) -> Result<Value, String> {
+    fn call_time_type_method(&self, method: &str, args: &[Value]) -> JResult<Value> {
         match method {
             "now" => {
                 if !args.is_empty() {
-                    return Err("time.now() takes no arguments".to_string());
+                    return Err(JError::argument_count("time.now() takes no arguments").into());
                 }
                 let now = std::time::SystemTime::now();
                 let dur = now.duration_since(std::time::UNIX_EPOCH).unwrap();
@@ -5983,15 +6111,15 @@ impl Interpreter {
                 let s = secs % 60;
                 Ok(Value::Time(format!("{:02}:{:02}:{:02}", h, min, s)))
             }
-            _ => Err(format!("Time type method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("Time type method '{}' not found", method)).into()),
         }
     }
 
-    fn call_datetime_type_method(&self, method: &str, args: &[Value]) -> Result<Value, String> {
+    fn call_datetime_type_method(&self, method: &str, args: &[Value]) -> JResult<Value> {
         match method {
             "now" | "now_local" => {
                 if !args.is_empty() {
-                    return Err("datetime.now() takes no arguments".to_string());
+                    return Err(JError::argument_count("datetime.now() takes no arguments").into());
                 }
                 let now = std::time::SystemTime::now();
                 let dur = now.duration_since(std::time::UNIX_EPOCH).unwrap();
@@ -6009,24 +6137,24 @@ impl Interpreter {
             }
             "parse" => {
                 if args.is_empty() {
-                    return Err("datetime.parse(str) requires one argument".to_string());
+                    return Err(JError::argument_count("datetime.parse(str) requires one argument").into());
                 }
                 Ok(Value::DateTime(args[0].to_string()))
             }
-            _ => Err(format!("DateTime type method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("DateTime type method '{}' not found", method)).into()),
         }
     }
 
-    fn call_graph_type_method(&self, method: &str, args: &[Value]) -> Result<Value, String> {
+    fn call_graph_type_method(&self, method: &str, args: &[Value]) -> JResult<Value> {
         use std::collections::HashMap;
         match method {
             "directed" | "undirected" => {
                 if !args.is_empty() {
-                    return Err("graph.directed() and graph.undirected() take no arguments".to_string());
+                    return Err(JError::unsupported("graph.directed() and graph.undirected() take no arguments").into());
                 }
                 Ok(Value::Graph(HashMap::new()))
             }
-            _ => Err(format!("Graph type method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("Graph type method '{}' not found", method)).into()),
         }
     }
 
@@ -6035,12 +6163,12 @@ impl Interpreter {
         graph: &std::collections::HashMap<String, Vec<(String, f64)>>,
         method: &str,
         args: &[Value],
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         use std::collections::HashSet;
         match method {
             "nodes" => {
                 if !args.is_empty() {
-                    return Err("graph.nodes() takes no arguments".to_string());
+                    return Err(JError::argument_count("graph.nodes() takes no arguments").into());
                 }
                 let mut nodes: HashSet<&String> = graph.keys().collect();
                 for edges in graph.values() {
@@ -6053,7 +6181,7 @@ impl Interpreter {
             }
             "edges" => {
                 if !args.is_empty() {
-                    return Err("graph.edges() takes no arguments".to_string());
+                    return Err(JError::argument_count("graph.edges() takes no arguments").into());
                 }
                 let mut list = Vec::new();
                 for (from, edges) in graph {
@@ -6069,18 +6197,18 @@ impl Interpreter {
             }
             "degree" => {
                 if args.len() != 1 {
-                    return Err("graph.degree(node) requires one argument".to_string());
+                    return Err(JError::argument_count("graph.degree(node) requires one argument").into());
                 }
                 let node = match &args[0] {
                     Value::String(s) => s.as_str(),
-                    _ => return Err("graph.degree(node) expects string".to_string()),
+                    _ => return Err(JError::invalid_argument("graph.degree(node) expects string").into()),
                 };
                 let out = graph.get(node).map(|e| e.len()).unwrap_or(0);
                 Ok(Value::Integer(out as i64))
             }
             "is_connected" => {
                 if !args.is_empty() {
-                    return Err("graph.is_connected() takes no arguments".to_string());
+                    return Err(JError::argument_count("graph.is_connected() takes no arguments").into());
                 }
                 let all_nodes: HashSet<String> = graph.keys().cloned().chain(graph.values().flat_map(|e| e.iter().map(|(n, _)| n.clone()))).collect();
                 if all_nodes.is_empty() {
@@ -6101,7 +6229,7 @@ impl Interpreter {
                 }
                 Ok(Value::Boolean(visited.len() >= all_nodes.len()))
             }
-            _ => Err(format!("Graph method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("Graph method '{}' not found", method)).into()),
         }
     }
 
@@ -6110,47 +6238,47 @@ impl Interpreter {
         total_seconds: i64,
         method: &str,
         args: &[Value],
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         match method {
             "days" => {
                 if !args.is_empty() {
-                    return Err("interval.days takes no arguments".to_string());
+                    return Err(JError::argument_count("interval.days takes no arguments").into());
                 }
                 Ok(Value::Integer(total_seconds / 86400))
             }
             "hours" => {
                 if !args.is_empty() {
-                    return Err("interval.hours takes no arguments".to_string());
+                    return Err(JError::argument_count("interval.hours takes no arguments").into());
                 }
                 Ok(Value::Integer(total_seconds / 3600))
             }
             "minutes" => {
                 if !args.is_empty() {
-                    return Err("interval.minutes takes no arguments".to_string());
+                    return Err(JError::argument_count("interval.minutes takes no arguments").into());
                 }
                 Ok(Value::Integer(total_seconds / 60))
             }
             "seconds" => {
                 if !args.is_empty() {
-                    return Err("interval.seconds takes no arguments".to_string());
+                    return Err(JError::argument_count("interval.seconds takes no arguments").into());
                 }
                 Ok(Value::Integer(total_seconds))
             }
             "is_zero" => {
                 if !args.is_empty() {
-                    return Err("interval.is_zero takes no arguments".to_string());
+                    return Err(JError::argument_count("interval.is_zero takes no arguments").into());
                 }
                 Ok(Value::Boolean(total_seconds == 0))
             }
             "abs" => {
                 if !args.is_empty() {
-                    return Err("interval.abs takes no arguments".to_string());
+                    return Err(JError::argument_count("interval.abs takes no arguments").into());
                 }
                 Ok(Value::Duration {
                     total_seconds: total_seconds.abs(),
                 })
             }
-            _ => Err(format!("Interval method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("Interval method '{}' not found", method)).into()),
         }
     }
 
@@ -6161,40 +6289,40 @@ impl Interpreter {
         value: &Value,
         method: &str,
         args: &[Value],
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         match method {
             "variant_name" | "name" | "label" => {
                 if !args.is_empty() {
-                    return Err("enum_variant.variant_name() takes no arguments".to_string());
+                    return Err(JError::argument_count("enum_variant.variant_name() takes no arguments").into());
                 }
                 Ok(Value::String(variant_name.to_string()))
             }
             "unwrap" => {
                 if !args.is_empty() {
-                    return Err("enum_variant.unwrap() takes no arguments".to_string());
+                    return Err(JError::argument_count("enum_variant.unwrap() takes no arguments").into());
                 }
                 Ok(value.clone())
             }
             "unwrap_or" => {
                 if args.len() != 1 {
-                    return Err("enum_variant.unwrap_or(default) requires one argument".to_string());
+                    return Err(JError::argument_count("enum_variant.unwrap_or(default) requires one argument").into());
                 }
                 Ok(value.clone())
             }
             "is_variant" => {
                 if args.len() != 1 {
-                    return Err("enum_variant.is_variant(name) requires one argument".to_string());
+                    return Err(JError::argument_count("enum_variant.is_variant(name) requires one argument").into());
                 }
                 let name = args[0].to_string();
                 Ok(Value::Boolean(name == variant_name))
             }
             "value" => {
                 if !args.is_empty() {
-                    return Err("enum_variant.value takes no arguments".to_string());
+                    return Err(JError::argument_count("enum_variant.value takes no arguments").into());
                 }
                 Ok(value.clone())
             }
-            _ => Err(format!("Enum variant method '{}' not found", method)),
+            _ => Err(JError::unsupported(format!("Enum variant method '{}' not found", method)).into()),
         }
     }
 
@@ -6203,12 +6331,12 @@ impl Interpreter {
         callee: Value,
         eval_args: &[Value],
         this_opt: Option<Value>,
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         match callee {
             Value::Memoized { id, inner } => {
                 let key = Self::memo_cache_key(eval_args);
-                if let Some(cached) = self.memo_caches.get(&id).and_then(|c| c.get(&key)) {
-                    return Ok(cached.clone());
+                if let Some(cached) = self.memo_caches.get_mut(&id).and_then(|c| c.get(&key)) {
+                    return Ok(cached);
                 }
                 let result = self.call_value_with_args(*inner, eval_args, this_opt)?;
                 self.memo_caches
@@ -6217,16 +6345,19 @@ impl Interpreter {
                     .insert(key, result.clone());
                 Ok(result)
             }
+            Value::Decorated { kind, name, inner } => {
+                self.call_decorated(kind, &name, *inner, eval_args, this_opt)
+            }
             Value::BoundMethod { receiver, method } => {
                 return self.call_bound_method(*receiver, &method, eval_args, this_opt);
             }
             Value::MatchGroup(m) => {
                 if eval_args.len() != 1 {
-                    return Err("match.group(n) expects one integer argument".to_string());
+                    return Err(JError::argument_count("match.group(n) expects one integer argument").into());
                 }
                 let n = match &eval_args[0] {
                     Value::Integer(i) => *i,
-                    _ => return Err("match.group(n) expects integer n".to_string()),
+                    _ => return Err(JError::invalid_argument("match.group(n) expects integer n").into()),
                 };
                 if n == 0 {
                     return Ok(Value::String(m.text.clone()));
@@ -6267,7 +6398,7 @@ impl Interpreter {
                     static_methods: _,
                 } = class_val
                 else {
-                    return Err(format!("'{}' is not a class", class_name));
+                    return Err(JError::type_mismatch(format!("'{}' is not a class", class_name)).into());
                 };
                 
                 // Handle singleton class - return existing instance if it exists
@@ -6297,13 +6428,14 @@ impl Interpreter {
                 };
                 
                 // Call init method if it exists
-                if let Some(Value::Function { params, body, .. }) = methods.get("init") {
+                if let Some(Value::Function { params, body, env, .. }) = methods.get("init") {
                     self.call_function_internal(
                         "init",
                         eval_args,
                         params,
                         body,
                         Some(instance.clone()),
+                        env,
                     )?;
                 }
                 
@@ -6319,25 +6451,25 @@ impl Interpreter {
                 
                 Ok(instance)
             }
-            Value::Function { name, params, body } => {
-                self.call_function_internal(&name, eval_args, &params, &body, this_opt)
+            Value::Function { name, params, body, env } => {
+                self.call_function_internal(&name, eval_args, &params, &body, this_opt, &env)
             }
             Value::GridNeighbors(grid_val) => {
                 let Value::Grid(grid) = grid_val.as_ref() else {
-                    return Err("GridNeighbors requires a grid".to_string());
+                    return Err(JError::invalid_argument("GridNeighbors requires a grid").into());
                 };
                 if eval_args.len() != 2 {
                     return Err(
-                        "grid.neighbors(i, j) requires exactly 2 arguments (row, col)".to_string(),
+                        JError::argument_count("grid.neighbors(i, j) requires exactly 2 arguments (row, col)").into(),
                     );
                 }
                 let i = match &eval_args[0] {
                     Value::Integer(n) => *n as usize,
-                    _ => return Err("grid.neighbors row must be integer".to_string()),
+                    _ => return Err(JError::invalid_argument("grid.neighbors row must be integer").into()),
                 };
                 let j = match &eval_args[1] {
                     Value::Integer(n) => *n as usize,
-                    _ => return Err("grid.neighbors col must be integer".to_string()),
+                    _ => return Err(JError::invalid_argument("grid.neighbors col must be integer").into()),
                 };
                 let rows = grid.len();
                 let cols = if grid.is_empty() { 0 } else { grid[0].len() };
@@ -6353,20 +6485,20 @@ impl Interpreter {
             }
             Value::GridNeighbors8(grid_val) => {
                 let Value::Grid(grid) = grid_val.as_ref() else {
-                    return Err("GridNeighbors8 requires a grid".to_string());
+                    return Err(JError::invalid_argument("GridNeighbors8 requires a grid").into());
                 };
                 if eval_args.len() != 2 {
                     return Err(
-                        "grid.neighbors8(i, j) requires exactly 2 arguments (row, col)".to_string(),
+                        JError::argument_count("grid.neighbors8(i, j) requires exactly 2 arguments (row, col)").into(),
                     );
                 };
                 let i = match &eval_args[0] {
                     Value::Integer(n) => *n as usize,
-                    _ => return Err("grid.neighbors8 row must be integer".to_string()),
+                    _ => return Err(JError::invalid_argument("grid.neighbors8 row must be integer").into()),
                 };
                 let j = match &eval_args[1] {
                     Value::Integer(n) => *n as usize,
-                    _ => return Err("grid.neighbors8 col must be integer".to_string()),
+                    _ => return Err(JError::invalid_argument("grid.neighbors8 col must be integer").into()),
                 };
                 let rows = grid.len();
                 let cols = if grid.is_empty() { 0 } else { grid[0].len() };
@@ -6392,10 +6524,10 @@ impl Interpreter {
             }
             Value::GridFindAll(grid_val) => {
                 let Value::Grid(grid) = grid_val.as_ref() else {
-                    return Err("GridFindAll requires a grid".to_string());
+                    return Err(JError::invalid_argument("GridFindAll requires a grid").into());
                 };
                 if eval_args.len() != 1 {
-                    return Err("grid.find_all(value) requires exactly 1 argument".to_string());
+                    return Err(JError::argument_count("grid.find_all(value) requires exactly 1 argument").into());
                 }
                 let target = &eval_args[0];
                 let mut positions = Vec::new();
@@ -6413,54 +6545,54 @@ impl Interpreter {
             }
             Value::GridRow(grid_val) => {
                 let Value::Grid(grid) = grid_val.as_ref() else {
-                    return Err("GridRow requires a grid".to_string());
+                    return Err(JError::invalid_argument("GridRow requires a grid").into());
                 };
                 if eval_args.len() != 1 {
-                    return Err("grid.row(n) requires exactly 1 argument".to_string());
+                    return Err(JError::argument_count("grid.row(n) requires exactly 1 argument").into());
                 }
                 let row_idx = match &eval_args[0] {
                     Value::Integer(n) => *n as usize,
-                    _ => return Err("grid.row index must be integer".to_string()),
+                    _ => return Err(JError::invalid_argument("grid.row index must be integer").into()),
                 };
                 if row_idx >= grid.len() {
-                    return Err(format!(
+                    return Err(JError::out_of_range(format!(
                         "Row index {} out of bounds (grid has {} rows)",
                         row_idx,
                         grid.len()
-                    ));
+                    )).into());
                 }
                 Ok(Value::List(grid[row_idx].clone()))
             }
             Value::GridCol(grid_val) => {
                 let Value::Grid(grid) = grid_val.as_ref() else {
-                    return Err("GridCol requires a grid".to_string());
+                    return Err(JError::invalid_argument("GridCol requires a grid").into());
                 };
                 if eval_args.len() != 1 {
-                    return Err("grid.col(n) requires exactly 1 argument".to_string());
+                    return Err(JError::argument_count("grid.col(n) requires exactly 1 argument").into());
                 }
                 let col_idx = match &eval_args[0] {
                     Value::Integer(n) => *n as usize,
-                    _ => return Err("grid.col index must be integer".to_string()),
+                    _ => return Err(JError::invalid_argument("grid.col index must be integer").into()),
                 };
                 if grid.is_empty() {
-                    return Err("Cannot get column from empty grid".to_string());
+                    return Err(JError::empty_collection("Cannot get column from empty grid").into());
                 }
                 let cols = grid[0].len();
                 if col_idx >= cols {
-                    return Err(format!(
+                    return Err(JError::out_of_range(format!(
                         "Column index {} out of bounds (grid has {} columns)",
                         col_idx, cols
-                    ));
+                    )).into());
                 }
                 let column: Vec<Value> = grid.iter().map(|row| row[col_idx].clone()).collect();
                 Ok(Value::List(column))
             }
             Value::MatrixRow(mat_val) => {
                 let Value::Matrix(mat) = mat_val.as_ref() else {
-                    return Err("MatrixRow requires a matrix".to_string());
+                    return Err(JError::invalid_argument("MatrixRow requires a matrix").into());
                 };
                 if eval_args.len() != 1 {
-                    return Err("matrix.row(n) requires exactly 1 argument".to_string());
+                    return Err(JError::argument_count("matrix.row(n) requires exactly 1 argument").into());
                 }
                 let row_idx = match &eval_args[0] {
                     Value::Integer(n) => {
@@ -6471,14 +6603,14 @@ impl Interpreter {
                             *n as usize
                         }
                     }
-                    _ => return Err("matrix.row index must be integer".to_string()),
+                    _ => return Err(JError::invalid_argument("matrix.row index must be integer").into()),
                 };
                 if row_idx >= mat.len() {
-                    return Err(format!(
+                    return Err(JError::out_of_range(format!(
                         "Row index {} out of bounds (matrix has {} rows)",
                         row_idx,
                         mat.len()
-                    ));
+                    )).into());
                 }
                 let row_values: Vec<Value> =
                     mat[row_idx].iter().map(|&v| Value::Float(v)).collect();
@@ -6486,15 +6618,15 @@ impl Interpreter {
             }
             Value::MatrixCol(mat_val) => {
                 let Value::Matrix(mat) = mat_val.as_ref() else {
-                    return Err("MatrixCol requires a matrix".to_string());
+                    return Err(JError::invalid_argument("MatrixCol requires a matrix").into());
                 };
                 if eval_args.len() != 1 {
-                    return Err("matrix.col(n) requires exactly 1 argument".to_string());
+                    return Err(JError::argument_count("matrix.col(n) requires exactly 1 argument").into());
                 }
                 let col_idx = match &eval_args[0] {
                     Value::Integer(n) => {
                         if mat.is_empty() {
-                            return Err("Cannot get column from empty matrix".to_string());
+                            return Err(JError::empty_collection("Cannot get column from empty matrix").into());
                         }
                         let cols = mat[0].len();
                         
@@ -6504,27 +6636,27 @@ impl Interpreter {
                             *n as usize
                         }
                     }
-                    _ => return Err("matrix.col index must be integer".to_string()),
+                    _ => return Err(JError::invalid_argument("matrix.col index must be integer").into()),
                 };
                 if mat.is_empty() {
-                    return Err("Cannot get column from empty matrix".to_string());
+                    return Err(JError::empty_collection("Cannot get column from empty matrix").into());
                 }
                 let cols = mat[0].len();
                 if col_idx >= cols {
-                    return Err(format!(
+                    return Err(JError::out_of_range(format!(
                         "Column index {} out of bounds (matrix has {} columns)",
                         col_idx, cols
-                    ));
+                    )).into());
                 }
                 let column: Vec<Value> = mat.iter().map(|row| Value::Float(row[col_idx])).collect();
                 Ok(Value::List(column))
             }
             Value::MatrixDiagonal(mat_val) => {
                 let Value::Matrix(mat) = mat_val.as_ref() else {
-                    return Err("MatrixDiagonal requires a matrix".to_string());
+                    return Err(JError::invalid_argument("MatrixDiagonal requires a matrix").into());
                 };
                 if !eval_args.is_empty() {
-                    return Err("matrix.diagonal() takes no arguments".to_string());
+                    return Err(JError::argument_count("matrix.diagonal() takes no arguments").into());
                 }
                 if mat.is_empty() {
                     return Ok(Value::List(Vec::new()));
@@ -6535,10 +6667,10 @@ impl Interpreter {
             }
             Value::MatrixFlat(mat_val) => {
                 let Value::Matrix(mat) = mat_val.as_ref() else {
-                    return Err("MatrixFlat requires a matrix".to_string());
+                    return Err(JError::invalid_argument("MatrixFlat requires a matrix").into());
                 };
                 if !eval_args.is_empty() {
-                    return Err("matrix.flat() takes no arguments".to_string());
+                    return Err(JError::argument_count("matrix.flat() takes no arguments").into());
                 }
                 let flat: Vec<Value> = mat
                     .iter()
@@ -6548,10 +6680,10 @@ impl Interpreter {
             }
             Value::MatrixRowSums(mat_val) => {
                 let Value::Matrix(mat) = mat_val.as_ref() else {
-                    return Err("MatrixRowSums requires a matrix".to_string());
+                    return Err(JError::invalid_argument("MatrixRowSums requires a matrix").into());
                 };
                 if !eval_args.is_empty() {
-                    return Err("matrix.row_sums() takes no arguments".to_string());
+                    return Err(JError::argument_count("matrix.row_sums() takes no arguments").into());
                 }
                 let sums: Vec<Value> = mat
                     .iter()
@@ -6561,10 +6693,10 @@ impl Interpreter {
             }
             Value::MatrixColSums(mat_val) => {
                 let Value::Matrix(mat) = mat_val.as_ref() else {
-                    return Err("MatrixColSums requires a matrix".to_string());
+                    return Err(JError::invalid_argument("MatrixColSums requires a matrix").into());
                 };
                 if !eval_args.is_empty() {
-                    return Err("matrix.col_sums() takes no arguments".to_string());
+                    return Err(JError::argument_count("matrix.col_sums() takes no arguments").into());
                 }
                 if mat.is_empty() {
                     return Ok(Value::List(Vec::new()));
@@ -6577,10 +6709,10 @@ impl Interpreter {
             }
             Value::MatrixRowMeans(mat_val) => {
                 let Value::Matrix(mat) = mat_val.as_ref() else {
-                    return Err("MatrixRowMeans requires a matrix".to_string());
+                    return Err(JError::invalid_argument("MatrixRowMeans requires a matrix").into());
                 };
                 if !eval_args.is_empty() {
-                    return Err("matrix.row_means() takes no arguments".to_string());
+                    return Err(JError::argument_count("matrix.row_means() takes no arguments").into());
                 }
                 let means: Vec<Value> = mat
                     .iter()
@@ -6593,10 +6725,10 @@ impl Interpreter {
             }
             Value::MatrixColMeans(mat_val) => {
                 let Value::Matrix(mat) = mat_val.as_ref() else {
-                    return Err("MatrixColMeans requires a matrix".to_string());
+                    return Err(JError::invalid_argument("MatrixColMeans requires a matrix").into());
                 };
                 if !eval_args.is_empty() {
-                    return Err("matrix.col_means() takes no arguments".to_string());
+                    return Err(JError::argument_count("matrix.col_means() takes no arguments").into());
                 }
                 if mat.is_empty() {
                     return Ok(Value::List(Vec::new()));
@@ -6611,11 +6743,11 @@ impl Interpreter {
                     .collect();
                 Ok(Value::List(means))
             }
-            _ => Err(format!("Cannot call {} as function", callee)),
+            _ => Err(JError::type_mismatch(format!("Cannot call {} as function", callee)).into()),
         }
     }
 
-    pub(super) fn get_variable(&self, name: &str) -> Result<Value, String> {
+    pub(crate) fn get_variable(&self, name: &str) -> JResult<Value> {
         // Check static variables first
         if let Some(value) = self.statics.get(name) {
             return Ok(value.clone());
@@ -6623,7 +6755,7 @@ impl Interpreter {
 
         // Check local scopes (from innermost to outermost)
         for scope in self.locals.iter().rev() {
-            if let Some(value) = scope.get(name) {
+            if let Some(value) = scope.borrow().get(name) {
                 return Ok(value.clone());
             }
         }
@@ -6633,22 +6765,23 @@ impl Interpreter {
             Ok(value.clone())
         } else {
             // Use enhanced error with suggestions
-            Err(JError::undefined_variable(name, 0, 0).to_string())
+            Err(Box::new(JError::undefined_variable(name, 0, 0).unlocated()))
         }
     }
 
     pub(super) fn set_variable(&mut self, name: String, value: Value) {
-        if let Some(scope) = self.locals.last_mut() {
-            scope.insert(name, value);
+        if let Some(scope) = self.locals.last() {
+            scope.borrow_mut().insert(name, value);
         } else {
             self.globals.insert(name, value);
         }
     }
 
     /// Assign to an existing variable, traversing scopes from innermost to outermost
-    pub(super) fn assign_variable(&mut self, name: &str, value: Value) -> Result<(), String> {
+    pub(super) fn assign_variable(&mut self, name: &str, value: Value) -> JResult<()> {
         // Search from innermost to outermost local scope
-        for scope in self.locals.iter_mut().rev() {
+        for scope in self.locals.iter().rev() {
+            let mut scope = scope.borrow_mut();
             if scope.contains_key(name) {
                 scope.insert(name.to_string(), value);
                 return Ok(());
@@ -6667,40 +6800,63 @@ impl Interpreter {
             return Ok(());
         }
 
-        Err(format!("Variable '{}' not found in any scope", name))
+        Err(JError::undefined_variable(name, 0, 0)
+            .raised_as(&format!("Variable '{}' not found in any scope", name))
+            .into())
     }
 
     // Module system helper methods
-    pub(super) fn load_module(&mut self, path: &str) -> Result<Value, String> {
+    pub(super) fn load_module(&mut self, path: &str) -> JResult<Value> {
         if let Some(cached) = self.module_cache.get(path) {
             return Ok(cached.clone());
         }
 
         // Resolve file path
-            let file_path = self.resolve_module_path(path)?;
+            let (file_path, source) = match self.bundled_modules.get(path) {
+                Some(bundled) => bundled.clone(),
+                None => {
+                    let file_path = self.resolve_module_path(path)?;
 
-            // Read and parse file
-            let source = std::fs::read_to_string(&file_path)
-                .map_err(|e| format!("Failed to load module {}: {}", path, e))?;
+                    // Read and parse file
+                    let source = std::fs::read_to_string(&file_path)
+                        .map_err(|e| JError::io_error(format!("Failed to load module {}: {}", path, e)))?;
+                    (file_path, source)
+                }
+            };
 
             let mut lexer = crate::lexer::Lexer::new(&source);
-            let tokens = lexer
-                .tokenize()
-                .map_err(|e| format!("Lexer error in module {}: {}", path, e))?;
+            let tokens = lexer.tokenize().map_err(|mut e| {
+                e.file = Some(file_path.clone());
+                e
+            })?;
 
             let mut parser = crate::parser::Parser::new(tokens);
-            let ast = parser
-                .parse()
-                .map_err(|e| format!("Parser error in module {}: {}", path, e))?;
+            parser.set_file(&file_path);
+            let ast = parser.parse()?;
+            if let Some(coverage) = self.coverage.as_mut() {
+                coverage.register(&ast);
+            }
 
-            // Execute in isolated scope
+            // Execute in isolated scope. The top-level block runs directly in it (not in a
+            // nested block scope) so that its declarations become the exports.
             self.push_scope();
-            self.eval_node(&ast)?;
+            let statements = match &ast {
+                crate::parser::AstNode::Block(statements) => statements.as_slice(),
+                other => std::slice::from_ref(other),
+            };
+            let result = statements.iter().try_for_each(|s| {
+                self.before_statement(s)?;
+                self.eval_node(s).map(drop)
+            });
+            if let Err(e) = result {
+                self.pop_scope();
+                return Err(e);
+            }
 
             // Get all variables from module scope as exports
             let mut exports = HashMap::new();
             if let Some(scope) = self.locals.last() {
-                exports = scope.clone();
+                exports = scope.borrow().clone();
             }
 
             self.pop_scope();
@@ -6718,7 +6874,8 @@ impl Interpreter {
             Ok(module)
     }
 
-    fn resolve_module_path(&self, path: &str) -> Result<String, String> {
+    /// File that `import` loads for a module path (`a/b` for `import a.b`).
+    pub(crate) fn resolve_module_path(&self, path: &str) -> JResult<String> {
         // If path starts with ./ or ../, it's relative
         if path.starts_with("./") || path.starts_with("../") {
             let full_path = if path.ends_with(".jdl") {
@@ -6730,7 +6887,7 @@ impl Interpreter {
             if std::path::Path::new(&full_path).exists() {
                 return Ok(full_path);
             } else {
-                return Err(format!("Module file not found: {}", full_path));
+                return Err(JError::missing_file(format!("Module file not found: {}", full_path)).into());
             }
         }
 
@@ -6759,7 +6916,7 @@ impl Interpreter {
                 if cache.exists() {
                     let entries = match std::fs::read_dir(cache) {
                         Ok(e) => e,
-                        Err(_) => return Err(format!("Module not found: {}", path)),
+                        Err(_) => return Err(JError::missing_file(format!("Module not found: {}", path)).into()),
                     };
                     for entry in entries.flatten() {
                         let name = entry.file_name();
@@ -6779,7 +6936,7 @@ impl Interpreter {
             }
         }
 
-        Err(format!("Module not found: {}", path))
+        Err(JError::missing_file(format!("Module not found: {}", path)).into())
     }
 
     /// Path to Jolt package cache (~/.jolt/cache). Used for import resolution.
@@ -6792,29 +6949,46 @@ impl Interpreter {
     }
 
     pub(super) fn push_scope(&mut self) {
-        self.locals.push(HashMap::new());
+        self.locals.push(Rc::new(RefCell::new(HashMap::new())));
+    }
+
+    /// Current local scopes, captured by functions and lambdas created here.
+    pub(super) fn capture_env(&self) -> Env {
+        Env(self.locals.clone())
     }
 
     pub(super) fn pop_scope(&mut self) {
-        self.locals.pop();
+        if let Some(scope) = self.locals.pop() {
+            Self::release_scope(scope);
+        }
+    }
+
+    /// Drop a scope that has left the stack. Functions defined in a scope capture it, so when
+    /// they are its only other holders the scope is emptied to break the cycle.
+    pub(super) fn release_scope(scope: Scope) {
+        let held_inside: usize = scope.borrow().values().map(|v| v.captures_of(&scope)).sum();
+        if held_inside > 0 && Rc::strong_count(&scope) == 1 + held_inside {
+            let values = std::mem::take(&mut *scope.borrow_mut());
+            drop(values);
+        }
     }
 
-    pub(super) fn execute_file(&mut self, filename: &str) -> Result<Value, String> {
+    pub(super) fn execute_file(&mut self, filename: &str) -> JResult<Value> {
         // Read the file
         let source = std::fs::read_to_string(filename)
-            .map_err(|e| format!("Error reading file '{}': {}", filename, e))?;
+            .map_err(|e| JError::io_error(format!("Error reading file '{}': {}", filename, e)))?;
 
         // Tokenize
         let mut lexer = crate::lexer::Lexer::new(&source);
-        let tokens = lexer
-            .tokenize()
-            .map_err(|e| format!("Lexer error in '{}': {}", filename, e))?;
+        let tokens = lexer.tokenize().map_err(|mut e| {
+            e.file = Some(filename.to_string());
+            e
+        })?;
 
         // Parse
         let mut parser = crate::parser::Parser::new(tokens);
-        let ast = parser
-            .parse()
-            .map_err(|e| format!("Parser error in '{}': {}", filename, e))?;
+        parser.set_file(filename);
+        let ast = parser.parse()?;
 
         // Execute in current context
         self.eval_node(&ast)
@@ -6826,7 +7000,7 @@ impl Interpreter {
         end: Option<i64>,
         len: i64,
         step: i64,
-    ) -> Result<(i64, i64), String> {
+    ) -> JResult<(i64, i64)> {
         let start = match start {
             Some(s) => {
                 if s < 0 {
@@ -6980,7 +7154,7 @@ impl Interpreter {
     }
 
     // Convex hull (Graham scan algorithm for 2D points)
-    fn convex_hull_2d(&self, points: &[Value]) -> Result<Vec<Value>, String> {
+    fn convex_hull_2d(&self, points: &[Value]) -> JResult<Vec<Value>> {
         if points.len() < 3 {
             return Ok(points.to_vec());
         }
@@ -6993,16 +7167,16 @@ impl Interpreter {
                     let x = match &tuple[0] {
                         Value::Integer(i) => *i as f64,
                         Value::Float(f) => *f,
-                        _ => return Err("Convex hull points must be numeric tuples".to_string()),
+                        _ => return Err(JError::invalid_argument("Convex hull points must be numeric tuples").into()),
                     };
                     let y = match &tuple[1] {
                         Value::Integer(i) => *i as f64,
                         Value::Float(f) => *f,
-                        _ => return Err("Convex hull points must be numeric tuples".to_string()),
+                        _ => return Err(JError::invalid_argument("Convex hull points must be numeric tuples").into()),
                     };
                     coords.push((x, y));
                 }
-                _ => return Err("Convex hull expects list of (x, y) tuples".to_string()),
+                _ => return Err(JError::invalid_argument("Convex hull expects list of (x, y) tuples").into()),
             }
         }
 
@@ -7062,7 +7236,7 @@ impl Interpreter {
         graph: &HashMap<String, Vec<(String, f64)>>,
         start: &str,
         goal: Option<&str>,
-    ) -> Result<Vec<String>, String> {
+    ) -> JResult<Vec<String>> {
         use std::collections::VecDeque;
 
         let mut queue = VecDeque::new();
@@ -7100,7 +7274,7 @@ impl Interpreter {
         }
 
         if goal.is_some() {
-            Err("Goal not reachable".to_string())
+            Err(JError::unsupported("Goal not reachable").into())
         } else {
             Ok(visited.into_iter().collect())
         }
@@ -7112,14 +7286,14 @@ impl Interpreter {
         graph: &HashMap<String, Vec<(String, f64)>>,
         start: &str,
         goal: Option<&str>,
-    ) -> Result<Vec<String>, String> {
+    ) -> JResult<Vec<String>> {
         let mut visited = HashSet::new();
         let mut path = Vec::new();
 
         if self.dfs_recursive(graph, start, goal, &mut visited, &mut path) {
             Ok(path)
         } else if goal.is_some() {
-            Err("Goal not reachable".to_string())
+            Err(JError::unsupported("Goal not reachable").into())
         } else {
             Ok(visited.into_iter().collect())
         }
@@ -7158,14 +7332,14 @@ impl Interpreter {
     }
 
     // FFT (Fast Fourier Transform) - simplified version
-    fn fft_transform(&self, signal: &[Value]) -> Result<Vec<Value>, String> {
+    fn fft_transform(&self, signal: &[Value]) -> JResult<Vec<Value>> {
         // Convert signal to complex numbers (simplified: just use real part)
         let mut samples: Vec<f64> = Vec::new();
         for val in signal {
             match val {
                 Value::Integer(i) => samples.push(*i as f64),
                 Value::Float(f) => samples.push(*f),
-                _ => return Err("FFT requires numeric values".to_string()),
+                _ => return Err(JError::invalid_argument("FFT requires numeric values").into()),
             }
         }
 
@@ -7267,7 +7441,7 @@ impl Interpreter {
         anim_type: &str,
         interval: f64,
         count: Option<usize>,
-    ) -> Result<(), String> {
+    ) -> JResult<()> {
         let frames: Vec<&str> = match anim_type {
             "spinner" => vec!["|", "/", "-", "\\"],
             "dots" => vec!["â ‹", "â ™", "â ¹", "â ¸", "â ¼", "â ´", "â ¦", "â §", "â ‡", "â "],
@@ -7286,7 +7460,7 @@ impl Interpreter {
                     frame.push(']');
                     frames.push(frame);
                 }
-                return Err("Bar animation not fully implemented".to_string());
+                return Err(JError::unsupported("Bar animation not fully implemented").into());
             }
             "bounce" => {
                 // Bounce animation: â€¢â€¢â€¢â€¢â€¢ â†’ â€¢ â€¢â€¢â€¢â€¢ â†’ â€¢â€¢ â€¢â€¢â€¢ etc.
@@ -7302,17 +7476,17 @@ impl Interpreter {
                     }
                     frames.push(frame);
                 }
-                return Err("Bounce animation not fully implemented".to_string());
+                return Err(JError::unsupported("Bounce animation not fully implemented").into());
             }
             "marquee" => {
                 // Marquee scrolling - would need terminal width
-                return Err("Marquee animation not fully implemented".to_string());
+                return Err(JError::unsupported("Marquee animation not fully implemented").into());
             }
             "pulse" => {
                 // Pulse effect using dim/bright
-                return Err("Pulse animation not fully implemented".to_string());
+                return Err(JError::unsupported("Pulse animation not fully implemented").into());
             }
-            _ => return Err(format!("Unknown animation type: {}", anim_type)),
+            _ => return Err(JError::unsupported(format!("Unknown animation type: {}", anim_type)).into()),
         };
 
         let max_iterations = count.unwrap_or(10); // Default to 10 iterations if not specified
@@ -7344,7 +7518,7 @@ impl Default for Interpreter {
 // Additional helper methods for Rich-like features
 impl Interpreter {
     // Rich-like spinner and loading animations
-    fn show_spinner(&self, style: &str, duration: f64, message: &str) -> Result<(), String> {
+    fn show_spinner(&self, style: &str, duration: f64, message: &str) -> JResult<()> {
         use std::io::{self, Write};
         use std::thread;
         use std::time::Duration;
@@ -7376,11 +7550,11 @@ impl Interpreter {
         Ok(())
     }
 
-    fn show_loading(&self, message: &str, style: &str, duration: f64) -> Result<(), String> {
+    fn show_loading(&self, message: &str, style: &str, duration: f64) -> JResult<()> {
         self.show_spinner(style, duration, message)
     }
 
-    fn show_panel(&self, text: &str, title: Option<&str>, style: &str) -> Result<(), String> {
+    fn show_panel(&self, text: &str, title: Option<&str>, style: &str) -> JResult<()> {
         let (top_left, top_right, bottom_left, bottom_right, horizontal, vertical) = match style {
             "double" => ("\u{2554}", "\u{2557}", "\u{255A}", "\u{255D}", "\u{2550}", "\u{2551}"),
             "rounded" => ("\u{256D}", "\u{256E}", "\u{2570}", "\u{256F}", "\u{2500}", "\u{2502}"),
@@ -7428,7 +7602,7 @@ impl Interpreter {
         Ok(())
     }
 
-    fn show_box(&self, text: &str, width: usize) -> Result<(), String> {
+    fn show_box(&self, text: &str, width: usize) -> JResult<()> {
         let lines: Vec<&str> = text.lines().collect();
 
         println!("â”Œ{}â”", "â”€".repeat(width));
@@ -7445,7 +7619,7 @@ impl Interpreter {
         Ok(())
     }
 
-    fn show_status(&self, status_type: &str, message: &str) -> Result<(), String> {
+    fn show_status(&self, status_type: &str, message: &str) -> JResult<()> {
         let (icon, color) = match status_type {
             "success" | "ok" => ("âœ“", "32"),   // Green
             "error" | "fail" => ("âœ—", "31"),   // Red
@@ -7459,7 +7633,7 @@ impl Interpreter {
         Ok(())
     }
 
-    fn show_tree(&self, data: &Value, title: Option<&str>, indent: usize) -> Result<(), String> {
+    fn show_tree(&self, data: &Value, title: Option<&str>, indent: usize) -> JResult<()> {
         if indent == 0 {
             if let Some(t) = title {
                 println!("\x1b[1m{}\x1b[0m", t);
@@ -7507,7 +7681,7 @@ impl Interpreter {
         Ok(())
     }
 
-    fn show_columns(&self, texts: &[String]) -> Result<(), String> {
+    fn show_columns(&self, texts: &[String]) -> JResult<()> {
         if texts.is_empty() {
             return Ok(());
         }
@@ -0,0 +1,218 @@
+//! Coverage recording: with a [`Coverage`] installed, the interpreter counts how often each
+//! statement runs and which way each `if`, `match` and `cond` goes. `jade test --coverage`
+//! merges the counts of every test file and writes them as LCOV.
+//!
+//! Statements are the ones the debugger stops at, located by [`AstNode::first_span`]; a branch
+//! point is located by the span of its keyword. Code registered with [`Coverage::register`]
+//! shows up with zero counts until it runs.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use super::debug::{canonical, stops_at, CanonicalPaths};
+use super::Interpreter;
+use crate::parser::{AstNode, Span};
+
+/// Execution counts for one source file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileCoverage {
+    /// Times a statement starting on each line ran.
+    pub lines: BTreeMap<usize, u64>,
+    /// Times each branch was taken, by the (line, column) of its `if`, `match` or `cond`. An
+    /// `if` without `else` and a `cond` without `|> else` have a last branch for falling through.
+    pub branches: BTreeMap<(usize, usize), Vec<u64>>,
+}
+
+impl FileCoverage {
+    pub fn lines_found(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn lines_hit(&self) -> usize {
+        self.lines.values().filter(|&&count| count > 0).count()
+    }
+
+    pub fn branches_found(&self) -> usize {
+        self.branches.values().map(Vec::len).sum()
+    }
+
+    pub fn branches_hit(&self) -> usize {
+        self.branches
+            .values()
+            .flatten()
+            .filter(|&&count| count > 0)
+            .count()
+    }
+}
+
+/// Line and branch counts by source file, installed with [`Interpreter::set_coverage`].
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    /// File that code parsed without a file name belongs to (the program or test file).
+    program: PathBuf,
+    /// Keyed by canonical path.
+    files: BTreeMap<PathBuf, FileCoverage>,
+    paths: CanonicalPaths,
+}
+
+impl Coverage {
+    /// Counts for a run of `program`; spans without a file are attributed to it.
+    pub fn new(program: impl AsRef<Path>) -> Self {
+        Self {
+            program: canonical(program.as_ref()),
+            ..Default::default()
+        }
+    }
+
+    /// Add zero counts for the statements and branches of `program`, so code that never runs
+    /// is reported as missed rather than left out.
+    pub fn register(&mut self, program: &AstNode) {
+        if let AstNode::Block(statements) = program {
+            for statement in statements.iter().filter(|statement| stops_at(statement)) {
+                if let Some(span) = statement.first_span() {
+                    self.file(span).lines.entry(span.line).or_insert(0);
+                }
+            }
+        }
+        if let Some((span, count)) = branch_point(program) {
+            if span.line > 0 {
+                let key = (span.line, span.column);
+                self.file(span)
+                    .branches
+                    .entry(key)
+                    .or_insert_with(|| vec![0; count]);
+            }
+        }
+        for child in program.children() {
+            self.register(child);
+        }
+    }
+
+    /// Counts by canonical file path.
+    pub fn files(&self) -> &BTreeMap<PathBuf, FileCoverage> {
+        &self.files
+    }
+
+    /// Add the counts of another run, e.g. of the next test file.
+    pub fn merge(&mut self, other: Coverage) {
+        for (path, theirs) in other.files {
+            let ours = self.files.entry(path).or_default();
+            for (line, count) in theirs.lines {
+                *ours.lines.entry(line).or_insert(0) += count;
+            }
+            for (key, counts) in theirs.branches {
+                let total = ours.branches.entry(key).or_default();
+                if total.len() < counts.len() {
+                    total.resize(counts.len(), 0);
+                }
+                for (sum, count) in total.iter_mut().zip(counts) {
+                    *sum += count;
+                }
+            }
+        }
+    }
+
+    /// Lines hit and found over all files.
+    pub fn line_totals(&self) -> (usize, usize) {
+        let files = self.files.values();
+        files.fold((0, 0), |(hit, found), file| {
+            (hit + file.lines_hit(), found + file.lines_found())
+        })
+    }
+
+    /// Branches taken and found over all files.
+    pub fn branch_totals(&self) -> (usize, usize) {
+        let files = self.files.values();
+        files.fold((0, 0), |(hit, found), file| {
+            (hit + file.branches_hit(), found + file.branches_found())
+        })
+    }
+
+    /// The counts as an LCOV tracefile, one record per file.
+    pub fn to_lcov(&self) -> String {
+        let mut out = String::new();
+        for (path, file) in &self.files {
+            let _ = writeln!(out, "TN:");
+            let _ = writeln!(out, "SF:{}", path.display());
+            for (block, ((line, _), counts)) in file.branches.iter().enumerate() {
+                // Branches of a point that never ran are "-" rather than 0
+                let ran = counts.iter().any(|&count| count > 0);
+                for (branch, count) in counts.iter().enumerate() {
+                    let taken = if ran {
+                        count.to_string()
+                    } else {
+                        "-".to_string()
+                    };
+                    let _ = writeln!(out, "BRDA:{},{},{},{}", line, block, branch, taken);
+                }
+            }
+            let _ = writeln!(out, "BRF:{}", file.branches_found());
+            let _ = writeln!(out, "BRH:{}", file.branches_hit());
+            for (line, count) in &file.lines {
+                let _ = writeln!(out, "DA:{},{}", line, count);
+            }
+            let _ = writeln!(out, "LF:{}", file.lines_found());
+            let _ = writeln!(out, "LH:{}", file.lines_hit());
+            let _ = writeln!(out, "end_of_record");
+        }
+        out
+    }
+
+    fn file(&mut self, span: &Span) -> &mut FileCoverage {
+        let path = self.paths.of(span).unwrap_or_else(|| self.program.clone());
+        self.files.entry(path).or_default()
+    }
+
+    /// The statement starting at `span` is about to run.
+    pub(super) fn hit_line(&mut self, span: &Span) {
+        *self.file(span).lines.entry(span.line).or_insert(0) += 1;
+    }
+
+    fn hit_branch(&mut self, span: &Span, branch: usize, count: usize) {
+        let key = (span.line, span.column);
+        let counts = self
+            .file(span)
+            .branches
+            .entry(key)
+            .or_insert_with(|| vec![0; count]);
+        if let Some(taken) = counts.get_mut(branch) {
+            *taken += 1;
+        }
+    }
+}
+
+/// The keyword span and number of branches of an `if`, `match` or `cond`.
+fn branch_point(node: &AstNode) -> Option<(&Span, usize)> {
+    match node {
+        AstNode::If { span, .. } => Some((span, 2)),
+        AstNode::Match { arms, span, .. } => Some((span, arms.len())),
+        AstNode::Cond { branches, span, .. } => {
+            let falls_through = !branches.iter().any(|branch| branch.is_else);
+            Some((span, branches.len() + usize::from(falls_through)))
+        }
+        _ => None,
+    }
+}
+
+impl Interpreter {
+    /// Count statements and branches in `coverage` from now on.
+    pub fn set_coverage(&mut self, coverage: Coverage) {
+        self.coverage = Some(Box::new(coverage));
+    }
+
+    /// Remove the coverage recorder and return its counts.
+    pub fn take_coverage(&mut self) -> Option<Coverage> {
+        self.coverage.take().map(|coverage| *coverage)
+    }
+
+    /// Called when branch `branch` of the `count` branches of the `if`, `match` or `cond` at
+    /// `span` is taken.
+    pub(super) fn cover_branch(&mut self, span: &Span, branch: usize, count: usize) {
+        if let Some(coverage) = self.coverage.as_mut() {
+            if span.line > 0 {
+                coverage.hit_branch(span, branch, count);
+            }
+        }
+    }
+}
@@ -17,6 +17,8 @@ use ed25519_dalek::{SigningKey, VerifyingKey};
 use hkdf::Hkdf;
 use rand::rngs::OsRng;
 use sha2::Sha256;
+
+use crate::error::{JError, JResult};
 use subtle::ConstantTimeEq;
 use x25519_dalek::{PublicKey, StaticSecret};
 
@@ -83,23 +85,26 @@ pub fn enigma_encrypt(
     key: &[u8],
     nonce: &[u8],
     aad: &[u8],
-) -> Result<Vec<u8>, String> {
+) -> JResult<Vec<u8>> {
     if key.len() != 32 {
-        return Err(format!(
+        return Err(JError::invalid_argument(format!(
             "Key must be 32 bytes for 2026 security level, got {}",
             key.len()
-        ));
+        ))
+        .into());
     }
     if nonce.len() != 12 {
-        return Err(format!("Nonce must be 12 bytes, got {}", nonce.len()));
+        return Err(
+            JError::invalid_argument(format!("Nonce must be 12 bytes, got {}", nonce.len())).into(),
+        );
     }
     let cipher =
-        ChaCha20Poly1305::new_from_slice(key).map_err(|_| "Invalid key length".to_string())?;
+        ChaCha20Poly1305::new_from_slice(key).map_err(|_| JError::invalid_argument("Invalid key length"))?;
     let nonce_array = Nonce::from_slice(nonce);
     let payload = Payload { msg: plaintext, aad };
     cipher
         .encrypt(nonce_array, payload)
-        .map_err(|_| "Encryption failed".to_string())
+        .map_err(|_| JError::unsupported("Encryption failed").into())
 }
 
 pub fn enigma_decrypt(
@@ -107,15 +112,15 @@ pub fn enigma_decrypt(
     key: &[u8],
     nonce: &[u8],
     aad: &[u8],
-) -> Result<Vec<u8>, String> {
+) -> JResult<Vec<u8>> {
     if key.len() != 32 {
-        return Err("Key must be 32 bytes".to_string());
+        return Err(JError::invalid_argument("Key must be 32 bytes").into());
     }
     if nonce.len() != 12 {
-        return Err("Nonce must be 12 bytes".to_string());
+        return Err(JError::invalid_argument("Nonce must be 12 bytes").into());
     }
     let cipher =
-        ChaCha20Poly1305::new_from_slice(key).map_err(|_| "Invalid key length".to_string())?;
+        ChaCha20Poly1305::new_from_slice(key).map_err(|_| JError::invalid_argument("Invalid key length"))?;
     let nonce_array = Nonce::from_slice(nonce);
     let payload = Payload {
         msg: ciphertext,
@@ -123,7 +128,7 @@ pub fn enigma_decrypt(
     };
     cipher
         .decrypt(nonce_array, payload)
-        .map_err(|_| "Decryption failed - tampered or wrong key".to_string())
+        .map_err(|_| JError::unsupported("Decryption failed - tampered or wrong key").into())
 }
 
 pub fn aes_encrypt(
@@ -131,20 +136,22 @@ pub fn aes_encrypt(
     key: &[u8],
     nonce: &[u8],
     aad: &[u8],
-) -> Result<Vec<u8>, String> {
+) -> JResult<Vec<u8>> {
     if key.len() != 32 {
-        return Err(format!(
+        return Err(JError::invalid_argument(format!(
             "Key must be 32 bytes for AES-256, got {}",
             key.len()
-        ));
+        ))
+        .into());
     }
     if nonce.len() != 12 {
-        return Err(format!(
+        return Err(JError::invalid_argument(format!(
             "Nonce must be 12 bytes for GCM, got {}",
             nonce.len()
-        ));
+        ))
+        .into());
     }
-    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| "Invalid key length".to_string())?;
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| JError::invalid_argument("Invalid key length"))?;
     let nonce_array = AesNonce::from_slice(nonce);
     let payload = AesPayload {
         msg: plaintext,
@@ -152,7 +159,7 @@ pub fn aes_encrypt(
     };
     cipher
         .encrypt(nonce_array, payload)
-        .map_err(|_| "AES encryption failed".to_string())
+        .map_err(|_| JError::unsupported("AES encryption failed").into())
 }
 
 pub fn aes_decrypt(
@@ -160,14 +167,14 @@ pub fn aes_decrypt(
     key: &[u8],
     nonce: &[u8],
     aad: &[u8],
-) -> Result<Vec<u8>, String> {
+) -> JResult<Vec<u8>> {
     if key.len() != 32 {
-        return Err("Key must be 32 bytes".to_string());
+        return Err(JError::invalid_argument("Key must be 32 bytes").into());
     }
     if nonce.len() != 12 {
-        return Err("Nonce must be 12 bytes".to_string());
+        return Err(JError::invalid_argument("Nonce must be 12 bytes").into());
     }
-    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| "Invalid key length".to_string())?;
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| JError::invalid_argument("Invalid key length"))?;
     let nonce_array = AesNonce::from_slice(nonce);
     let payload = AesPayload {
         msg: ciphertext,
@@ -175,7 +182,7 @@ pub fn aes_decrypt(
     };
     cipher
         .decrypt(nonce_array, payload)
-        .map_err(|_| "AES decryption failed - tampered or wrong key".to_string())
+        .map_err(|_| JError::unsupported("AES decryption failed - tampered or wrong key").into())
 }
 
 pub fn random_bytes(length: usize) -> Vec<u8> {
@@ -185,13 +192,14 @@ pub fn random_bytes(length: usize) -> Vec<u8> {
     bytes
 }
 
-pub fn xor_bytes(a: &[u8], b: &[u8]) -> Result<Vec<u8>, String> {
+pub fn xor_bytes(a: &[u8], b: &[u8]) -> JResult<Vec<u8>> {
     if a.len() != b.len() {
-        return Err(format!(
+        return Err(JError::invalid_argument(format!(
             "XOR requires equal length inputs: {} != {}",
             a.len(),
             b.len()
-        ));
+        ))
+        .into());
     }
     Ok(a.iter().zip(b.iter()).map(|(x, y)| x ^ y).collect())
 }
@@ -201,9 +209,9 @@ pub fn derive_password_key(
     salt: &[u8],
     ops_limit: u32,
     mem_limit_kb: u32,
-) -> Result<Vec<u8>, String> {
+) -> JResult<Vec<u8>> {
     if salt.len() < 16 {
-        return Err("Salt must be at least 16 bytes".to_string());
+        return Err(JError::invalid_argument("Salt must be at least 16 bytes").into());
     }
     let params = Params::new(
         mem_limit_kb,
@@ -211,14 +219,14 @@ pub fn derive_password_key(
         1,
         Some(32),
     )
-    .map_err(|e| format!("Invalid Argon2 params: {}", e))?;
+    .map_err(|e| JError::invalid_argument(format!("Invalid Argon2 params: {}", e)))?;
     let argon2 = Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
     let salt_str =
-        SaltString::encode_b64(salt).map_err(|e| format!("Failed to encode salt: {}", e))?;
+        SaltString::encode_b64(salt).map_err(|e| JError::unsupported(format!("Failed to encode salt: {}", e)))?;
     let hash = argon2
         .hash_password(password.as_bytes(), &salt_str)
-        .map_err(|e| format!("Argon2 hashing failed: {}", e))?;
-    let hash_bytes = hash.hash.ok_or("No hash output")?.as_bytes().to_vec();
+        .map_err(|e| JError::unsupported(format!("Argon2 hashing failed: {}", e)))?;
+    let hash_bytes = hash.hash.ok_or_else(|| JError::unsupported("No hash output"))?.as_bytes().to_vec();
     Ok(hash_bytes)
 }
 
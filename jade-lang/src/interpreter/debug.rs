@@ -0,0 +1,467 @@
+//! Debugging hook: with a [`Debugger`] installed, the interpreter reports each statement it is
+//! about to run and stops where a breakpoint or a step says so. While stopped, a [`Frontend`]
+//! (the `jade debug` console or the DAP server in [`crate::debugger`]) inspects the program
+//! through [`Paused`] and chooses how to resume.
+//!
+//! Stops happen at statements of blocks, located by [`AstNode::first_span`]; declarations of
+//! functions, classes and the like are skipped, since running them only defines a name.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::{parse_source, Interpreter, Scope, Value};
+use crate::error::{JError, JResult};
+use crate::parser::{AstNode, Span};
+
+/// How to go on from a stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resume {
+    /// Run until the next breakpoint.
+    Continue,
+    /// Stop at the next statement of this function or a caller, running calls to completion.
+    StepOver,
+    /// Stop at the next statement, inside a call if there is one.
+    StepIn,
+    /// Stop at the next statement after the current function returns.
+    StepOut,
+    /// End the program.
+    Quit,
+}
+
+/// Why the program stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Entry,
+    Breakpoint,
+    Step,
+    Pause,
+}
+
+impl StopReason {
+    /// The name DAP uses in `stopped` events.
+    pub fn name(self) -> &'static str {
+        match self {
+            StopReason::Entry => "entry",
+            StopReason::Breakpoint => "breakpoint",
+            StopReason::Step => "step",
+            StopReason::Pause => "pause",
+        }
+    }
+}
+
+/// A request a front end received while the program was running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupt {
+    Pause,
+    Quit,
+}
+
+/// The user side of a debugging session.
+pub trait Frontend {
+    /// The program stopped before a statement; inspect it through `paused` and choose how to
+    /// resume.
+    fn stopped(&mut self, paused: &mut Paused<'_>) -> Resume;
+
+    /// Called before each statement while the program runs, e.g. to forward its output or to
+    /// take requests that arrive meanwhile.
+    fn running(
+        &mut self,
+        _interpreter: &mut Interpreter,
+        _breakpoints: &mut Breakpoints,
+    ) -> Option<Interrupt> {
+        None
+    }
+}
+
+/// Breakpoint lines by source file. Files are compared by their canonical path.
+#[derive(Debug, Clone, Default)]
+pub struct Breakpoints {
+    lines: HashMap<PathBuf, BTreeSet<usize>>,
+}
+
+impl Breakpoints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, file: &Path, line: usize) {
+        self.lines.entry(canonical(file)).or_default().insert(line);
+    }
+
+    /// Remove one breakpoint; false if there was none.
+    pub fn remove(&mut self, file: &Path, line: usize) -> bool {
+        self.lines
+            .get_mut(&canonical(file))
+            .map(|lines| lines.remove(&line))
+            .unwrap_or(false)
+    }
+
+    /// Replace the breakpoints of `file`.
+    pub fn set(&mut self, file: &Path, lines: impl IntoIterator<Item = usize>) {
+        self.lines
+            .insert(canonical(file), lines.into_iter().collect());
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    pub fn contains(&self, file: &Path, line: usize) -> bool {
+        self.lines
+            .get(file)
+            .map(|lines| lines.contains(&line))
+            .unwrap_or(false)
+    }
+
+    /// Every breakpoint as (canonical file, line), sorted.
+    pub fn list(&self) -> Vec<(&Path, usize)> {
+        let mut all: Vec<_> = self
+            .lines
+            .iter()
+            .flat_map(|(file, lines)| lines.iter().map(move |line| (file.as_path(), *line)))
+            .collect();
+        all.sort();
+        all
+    }
+}
+
+/// `file` with links and `..` resolved, or as given when it cannot be resolved.
+pub(super) fn canonical(file: &Path) -> PathBuf {
+    file.canonicalize().unwrap_or_else(|_| file.to_path_buf())
+}
+
+/// Canonical path of each file named in spans, resolved once per file.
+#[derive(Debug, Clone, Default)]
+pub(super) struct CanonicalPaths(HashMap<Arc<str>, PathBuf>);
+
+impl CanonicalPaths {
+    /// Canonical path of the file `span` is in; `None` for code parsed without a file name.
+    pub(super) fn of(&mut self, span: &Span) -> Option<PathBuf> {
+        let file = span.file.as_ref()?;
+        let path = self
+            .0
+            .entry(file.clone())
+            .or_insert_with(|| canonical(Path::new(&**file)));
+        Some(path.clone())
+    }
+}
+
+/// Lines of `program` where the debugger can stop, i.e. where a breakpoint takes effect.
+pub fn statement_lines(program: &AstNode) -> BTreeSet<usize> {
+    let mut lines = BTreeSet::new();
+    collect_statement_lines(program, &mut lines);
+    lines
+}
+
+fn collect_statement_lines(node: &AstNode, lines: &mut BTreeSet<usize>) {
+    if let AstNode::Block(statements) = node {
+        let stops = statements.iter().filter(|statement| stops_at(statement));
+        lines.extend(stops.filter_map(|statement| Some(statement.first_span()?.line)));
+    }
+    for child in node.children() {
+        collect_statement_lines(child, lines);
+    }
+}
+
+/// Whether the debugger stops before `statement`. Declarations only define a name, and a
+/// nested block's statements stop on their own.
+pub(super) fn stops_at(statement: &AstNode) -> bool {
+    !matches!(
+        statement,
+        AstNode::FunctionDeclaration { .. }
+            | AstNode::AsyncFunction { .. }
+            | AstNode::GenericFunction { .. }
+            | AstNode::AutoFunction { .. }
+            | AstNode::ClassDeclaration { .. }
+            | AstNode::GenericClass { .. }
+            | AstNode::EnumDeclaration { .. }
+            | AstNode::TraitDeclaration { .. }
+            | AstNode::ModuleDeclaration { .. }
+            | AstNode::MacroDefinition { .. }
+            | AstNode::TestCase { .. }
+            | AstNode::PropertyTest { .. }
+            | AstNode::Defer(..)
+            | AstNode::Block(..)
+    )
+}
+
+/// Where stepping should stop next.
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Run,
+    Into,
+    /// At a call depth no deeper than this.
+    Over(usize),
+    /// At a call depth shallower than this.
+    Out(usize),
+}
+
+/// Breakpoints and stepping state, installed with [`Interpreter::set_debugger`].
+pub struct Debugger {
+    frontend: Box<dyn Frontend>,
+    breakpoints: Breakpoints,
+    step: Step,
+    stop_on_entry: bool,
+    quit: bool,
+    files: CanonicalPaths,
+}
+
+impl Debugger {
+    /// A debugger that stops at `breakpoints`, and before the first statement if
+    /// `stop_on_entry`. Only code parsed with a file name ([`crate::parser::Parser::set_file`])
+    /// can hit breakpoints.
+    pub fn new(
+        frontend: impl Frontend + 'static,
+        breakpoints: Breakpoints,
+        stop_on_entry: bool,
+    ) -> Self {
+        Self {
+            frontend: Box::new(frontend),
+            breakpoints,
+            step: Step::Run,
+            stop_on_entry,
+            quit: false,
+            files: CanonicalPaths::default(),
+        }
+    }
+
+    /// Whether the front end ended the program (the run then fails with a "session ended" error).
+    pub fn quit_requested(&self) -> bool {
+        self.quit
+    }
+
+    fn before_statement(&mut self, interpreter: &mut Interpreter, span: &Span) -> JResult<()> {
+        if self.quit {
+            return Err(session_ended());
+        }
+        let depth = interpreter.frames.len();
+        let mut reason = match self.step {
+            Step::Into => Some(StopReason::Step),
+            Step::Over(start) if depth <= start => Some(StopReason::Step),
+            Step::Out(start) if depth < start => Some(StopReason::Step),
+            _ => None,
+        };
+        if std::mem::take(&mut self.stop_on_entry) {
+            reason = Some(StopReason::Entry);
+        }
+        match self.frontend.running(interpreter, &mut self.breakpoints) {
+            Some(Interrupt::Quit) => {
+                self.quit = true;
+                return Err(session_ended());
+            }
+            Some(Interrupt::Pause) => reason = reason.or(Some(StopReason::Pause)),
+            None => {}
+        }
+        let file = self.files.of(span).unwrap_or_default();
+        if reason.is_none() && self.breakpoints.contains(&file, span.line) {
+            reason = Some(StopReason::Breakpoint);
+        }
+        let Some(reason) = reason else {
+            return Ok(());
+        };
+
+        let mut paused = Paused {
+            interpreter,
+            breakpoints: &mut self.breakpoints,
+            reason,
+            file,
+            span: span.clone(),
+        };
+        self.step = match self.frontend.stopped(&mut paused) {
+            Resume::Continue => Step::Run,
+            Resume::StepOver => Step::Over(depth),
+            Resume::StepIn => Step::Into,
+            Resume::StepOut => Step::Out(depth),
+            Resume::Quit => {
+                self.quit = true;
+                return Err(session_ended());
+            }
+        };
+        Ok(())
+    }
+}
+
+fn session_ended() -> Box<JError> {
+    JError::unsupported("Debugging session ended").into()
+}
+
+/// One running function, as shown in a stack trace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackFrame {
+    /// The function's name, or `<module>` for the top level.
+    pub function: String,
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The program while stopped: where it is, and its variables.
+pub struct Paused<'a> {
+    interpreter: &'a mut Interpreter,
+    pub breakpoints: &'a mut Breakpoints,
+    pub reason: StopReason,
+    /// Canonical path of the file stopped in; empty for code parsed without a file name.
+    pub file: PathBuf,
+    span: Span,
+}
+
+impl Paused<'_> {
+    pub fn line(&self) -> usize {
+        self.span.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.span.column
+    }
+
+    /// Running functions, innermost first; the last is the top level.
+    pub fn stack(&self) -> Vec<StackFrame> {
+        let trace = self.interpreter.traceback(&self.span);
+        trace
+            .into_iter()
+            .rev()
+            .map(|entry| StackFrame {
+                function: entry.function,
+                file: entry.file,
+                line: entry.line,
+                column: entry.column,
+            })
+            .collect()
+    }
+
+    /// Local variables of `frame` (0 is the innermost): its function's parameters and the
+    /// variables of its blocks, sorted by name. Variables it captured are not included, and the
+    /// top level's are globals.
+    pub fn locals(&self, frame: usize) -> Vec<(String, Value)> {
+        let frames = &self.interpreter.frames;
+        // The frame's scopes, and how many of them its function captured
+        let (scopes, captured) = match frame {
+            0 => (
+                &self.interpreter.locals,
+                frames.last().map_or(0, |frame| frame.captured),
+            ),
+            _ if frame <= frames.len() => {
+                let callee = frames.len() - frame;
+                let captured = match callee {
+                    0 => 0,
+                    _ => frames[callee - 1].captured,
+                };
+                (&frames[callee].caller_locals, captured)
+            }
+            _ => return Vec::new(),
+        };
+        let top_level = self.top_level_scope();
+        let scopes = scopes
+            .iter()
+            .skip(captured)
+            .rev()
+            .filter(|scope| !top_level.is_some_and(|top| std::rc::Rc::ptr_eq(scope, top)));
+        collect_variables(scopes)
+    }
+
+    /// Global variables and the program's top-level variables, sorted by name. Constants such
+    /// as `PI` are left out.
+    pub fn globals(&self) -> Vec<(String, Value)> {
+        let mut globals: HashMap<String, Value> = self
+            .interpreter
+            .globals
+            .iter()
+            .filter(|(name, _)| !self.interpreter.builtin_globals.contains(*name))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        if let Some(top_level) = self.top_level_scope() {
+            for (name, value) in top_level.borrow().iter() {
+                globals.insert(name.clone(), value.clone());
+            }
+        }
+        sorted(globals)
+    }
+
+    /// `static` variables, sorted by name.
+    pub fn statics(&self) -> Vec<(String, Value)> {
+        sorted(self.interpreter.statics.clone())
+    }
+
+    /// Evaluate `expression` (or run a statement) where the program stopped.
+    pub fn evaluate(&mut self, expression: &str) -> JResult<Value> {
+        // The line end lets an unfinished expression fail to parse instead of running on.
+        let node = match parse_source(&format!("{}\n", expression))? {
+            AstNode::Block(mut statements) if statements.len() == 1 => statements.remove(0),
+            other => other,
+        };
+        self.interpreter.eval_node(&node)
+    }
+
+    pub fn interpreter(&mut self) -> &mut Interpreter {
+        self.interpreter
+    }
+
+    /// The scope of the program's top-level variables: the outermost scope of the code that
+    /// made the first call.
+    fn top_level_scope(&self) -> Option<&Scope> {
+        let interpreter = &*self.interpreter;
+        match interpreter.frames.first() {
+            Some(frame) => frame.caller_locals.first(),
+            None => interpreter.locals.first(),
+        }
+    }
+}
+
+/// Variables of `scopes`, innermost first, so the innermost of two with the same name wins.
+fn collect_variables<'s>(scopes: impl Iterator<Item = &'s Scope>) -> Vec<(String, Value)> {
+    let mut variables = HashMap::new();
+    for scope in scopes {
+        for (name, value) in scope.borrow().iter() {
+            variables
+                .entry(name.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
+    sorted(variables)
+}
+
+fn sorted(variables: HashMap<String, Value>) -> Vec<(String, Value)> {
+    let mut variables: Vec<_> = variables.into_iter().collect();
+    variables.sort_by(|a, b| a.0.cmp(&b.0));
+    variables
+}
+
+impl Interpreter {
+    /// Report statements to `debugger` from now on.
+    pub fn set_debugger(&mut self, debugger: Debugger) {
+        self.debugger = Some(Box::new(debugger));
+    }
+
+    /// Remove the debugger, e.g. to ask it whether the session was quit.
+    pub fn take_debugger(&mut self) -> Option<Debugger> {
+        self.debugger.take().map(|debugger| *debugger)
+    }
+
+    /// Called by blocks before each statement: counts it for coverage and the profiler, and
+    /// lets the debugger stop there.
+    pub(super) fn before_statement(&mut self, statement: &AstNode) -> JResult<()> {
+        if self.coverage.is_none() && self.profiler.is_none() && self.debugger.is_none() {
+            return Ok(());
+        }
+        if !stops_at(statement) {
+            return Ok(());
+        }
+        let Some(span) = statement.first_span() else {
+            return Ok(());
+        };
+        if let Some(coverage) = self.coverage.as_mut() {
+            coverage.hit_line(span);
+        }
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.statement(span);
+        }
+        // Out of `self` while it runs, so stops and evaluations do not re-enter it
+        let Some(mut debugger) = self.debugger.take() else {
+            return Ok(());
+        };
+        let result = debugger.before_statement(self, span);
+        self.debugger = Some(debugger);
+        result
+    }
+}
@@ -0,0 +1,330 @@
+//! Wrapping decorators: `@timer`, `@profile`, `@trace`, `@log_call`, `@throttle`, `@debounce`,
+//! `@deprecated` and `@validate`.
+//!
+//! Each wraps a function in a [`Value::Decorated`]; calls go through [`Interpreter::call_decorated`],
+//! which runs the inner function and reports around it. Output goes through `out()`'s writer, so it
+//! is captured along with the program's own output.
+//!
+//! `@debounce` defers calls to the timer queue, which runs due calls whenever `sleep()` returns and
+//! drains (waiting on the clock) when the program or test finishes.
+
+use super::builtins::type_of_name;
+use super::*;
+use crate::error::JError;
+use std::time::{Duration, Instant};
+
+/// Accumulated `@profile` numbers for one function.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ProfileStats {
+    pub(crate) calls: u64,
+    pub(crate) total_secs: f64,
+    /// Calls currently on the stack; time is only added when the outermost one returns,
+    /// so recursion is not counted twice.
+    active: usize,
+}
+
+/// A deferred `@debounce` call waiting in the timer queue.
+#[derive(Debug, Clone)]
+pub(crate) struct PendingCall {
+    due: Duration,
+    debounce_id: usize,
+    func: Value,
+    args: Vec<Value>,
+    this_opt: Option<Value>,
+}
+
+/// What `@validate` checks before the body runs.
+#[derive(Debug, Clone)]
+pub(crate) enum Validation {
+    /// Declared `(type, name)` parameters of the decorated function.
+    ParamTypes(Vec<(String, String)>),
+    /// Called with the same arguments; `false` or a message string rejects the call.
+    Validator(Box<Value>),
+}
+
+impl Interpreter {
+    pub(crate) fn decorate(&mut self, func: Value, kind: Decoration) -> JResult<Value> {
+        let name = match &func {
+            Value::Function { name, .. } => name.clone(),
+            Value::Decorated { name, .. } => name.clone(),
+            Value::Memoized { .. } | Value::OnceCached { .. } => func.to_string(),
+            other => {
+                return Err(JError::invalid_argument(format!(
+                    "decorator expects a function, got {}",
+                    Self::value_type_name(other)
+                )).into())
+            }
+        };
+        if kind == Decoration::Profile {
+            self.profile_stats.entry(name.clone()).or_default();
+        }
+        Ok(Value::Decorated {
+            kind,
+            name,
+            inner: Box::new(func),
+        })
+    }
+
+    pub(crate) fn call_decorated(
+        &mut self,
+        kind: Decoration,
+        name: &str,
+        inner: Value,
+        args: &[Value],
+        this_opt: Option<Value>,
+    ) -> JResult<Value> {
+        match kind {
+            Decoration::Timer => {
+                let start = Instant::now();
+                let result = self.call_value_with_args(inner, args, this_opt);
+                let ms = start.elapsed().as_secs_f64() * 1000.0;
+                self.write_out(&format!("⏱ {} took {:.3} ms\n", name, ms));
+                result
+            }
+            Decoration::Profile => {
+                let stats = self.profile_stats.entry(name.to_string()).or_default();
+                stats.calls += 1;
+                stats.active += 1;
+                let start = Instant::now();
+                let result = self.call_value_with_args(inner, args, this_opt);
+                let elapsed = start.elapsed().as_secs_f64();
+                let stats = self.profile_stats.entry(name.to_string()).or_default();
+                stats.active -= 1;
+                if stats.active == 0 {
+                    stats.total_secs += elapsed;
+                }
+                result
+            }
+            Decoration::Trace => {
+                let indent = "  ".repeat(self.trace_depth);
+                let call = format!("{}({})", name, format_args_list(args));
+                self.write_out(&format!("{}→ {}\n", indent, call));
+                self.trace_depth += 1;
+                let result = self.call_value_with_args(inner, args, this_opt);
+                self.trace_depth -= 1;
+                match &result {
+                    Ok(v) => self.write_out(&format!("{}← {} = {}\n", indent, call, repr(v))),
+                    Err(e) => self.write_out(&format!("{}← {} failed: {}\n", indent, call, e)),
+                }
+                result
+            }
+            Decoration::LogCall => {
+                let start = Instant::now();
+                let result = self.call_value_with_args(inner, args, this_opt);
+                let ms = start.elapsed().as_secs_f64() * 1000.0;
+                let outcome = match &result {
+                    Ok(v) => format!("result={}", repr(v)),
+                    Err(e) => format!("error={:?}", e),
+                };
+                self.write_out(&format!(
+                    "call fn={} args=[{}] {} duration_ms={:.3}\n",
+                    name,
+                    format_args_list(args),
+                    outcome,
+                    ms
+                ));
+                result
+            }
+            Decoration::Throttle { id, interval } => {
+                let now = self.clock.now();
+                if let Some((last_run, last_result)) = self.throttle_state.get(&id) {
+                    if now < last_run.saturating_add(interval) {
+                        return Ok(last_result.clone());
+                    }
+                }
+                let result = self.call_value_with_args(inner, args, this_opt)?;
+                self.throttle_state.insert(id, (now, result.clone()));
+                Ok(result)
+            }
+            Decoration::Debounce { id, delay } => {
+                let due = self.clock.now().saturating_add(delay);
+                let pending = PendingCall {
+                    due,
+                    debounce_id: id,
+                    func: inner,
+                    args: args.to_vec(),
+                    this_opt,
+                };
+                // A newer call replaces the queued one and restarts the delay.
+                match self.timer_queue.iter_mut().find(|p| p.debounce_id == id) {
+                    Some(queued) => *queued = pending,
+                    None => self.timer_queue.push(pending),
+                }
+                Ok(Value::None)
+            }
+            Decoration::Deprecated { id } => {
+                let message = self.deprecations.get(&id).cloned().unwrap_or_default();
+                if self.deny_deprecated {
+                    return Err(Box::new(JError::deprecated(name, &message)));
+                }
+                // One warning per call site, e.g. once for a call inside a loop
+                let site = &self.call_site;
+                let place = match site.file.as_deref().or(self.program.as_deref()) {
+                    Some(file) => format!("{}:{}:{}", file, site.line, site.column),
+                    None => format!("line {}, column {}", site.line, site.column),
+                };
+                if self.deprecation_warned.insert((id, place.clone())) {
+                    let _ = std::io::stdout().flush();
+                    eprintln!(
+                        "⚠️  warning: '{}' is deprecated: {} (called at {})",
+                        name, message, place
+                    );
+                }
+                self.call_value_with_args(inner, args, this_opt)
+            }
+            Decoration::Validate { id } => {
+                match self.validations.get(&id).cloned() {
+                    Some(Validation::ParamTypes(params)) => {
+                        for ((declared, param), arg) in params.iter().zip(args) {
+                            if !matches_declared_type(declared, arg) {
+                                return Err(Box::new(JError::argument_type(
+                                    name,
+                                    param,
+                                    declared,
+                                    type_of_name(arg),
+                                )));
+                            }
+                        }
+                    }
+                    Some(Validation::Validator(check)) => {
+                        match self.call_value_with_args(*check, args, None)? {
+                            Value::Boolean(false) => {
+                                return Err(Box::new(JError::validation_failed(
+                                    name,
+                                    "validator returned false",
+                                )))
+                            }
+                            Value::String(reason) => {
+                                return Err(Box::new(JError::validation_failed(name, &reason)))
+                            }
+                            _ => {}
+                        }
+                    }
+                    None => {}
+                }
+                self.call_value_with_args(inner, args, this_opt)
+            }
+        }
+    }
+
+    /// Next queued call, earliest first; only if due by `now` unless `now` is `None`.
+    fn pop_timer(&mut self, now: Option<Duration>) -> Option<PendingCall> {
+        let (index, next) = self
+            .timer_queue
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, p)| p.due)?;
+        if now.is_some_and(|now| next.due > now) {
+            return None;
+        }
+        Some(self.timer_queue.remove(index))
+    }
+
+    /// Run every queued call whose delay has passed.
+    pub(crate) fn run_due_timers(&mut self) -> JResult<()> {
+        while let Some(call) = self.pop_timer(Some(self.clock.now())) {
+            self.call_value_with_args(call.func, &call.args, call.this_opt)?;
+        }
+        Ok(())
+    }
+
+    /// Run every queued call, waiting on the clock until each is due.
+    pub(crate) fn drain_timers(&mut self) -> JResult<()> {
+        while let Some(call) = self.pop_timer(None) {
+            let now = self.clock.now();
+            if call.due > now {
+                self.clock.sleep(call.due - now);
+            }
+            self.call_value_with_args(call.func, &call.args, call.this_opt)?;
+        }
+        Ok(())
+    }
+
+    /// `@profile` numbers so far: name -> {calls, total_ms, mean_ms}.
+    pub(crate) fn profile_report_value(&self) -> Value {
+        let report = self
+            .profile_stats
+            .iter()
+            .map(|(name, stats)| {
+                let total_ms = stats.total_secs * 1000.0;
+                let mean_ms = if stats.calls > 0 {
+                    total_ms / stats.calls as f64
+                } else {
+                    0.0
+                };
+                let mut entry = HashMap::new();
+                entry.insert("calls".to_string(), Value::Integer(stats.calls as i64));
+                entry.insert("total_ms".to_string(), Value::Float(total_ms));
+                entry.insert("mean_ms".to_string(), Value::Float(mean_ms));
+                (name.clone(), Value::Dict(entry))
+            })
+            .collect();
+        Value::Dict(report)
+    }
+
+    /// Table of `@profile` numbers, slowest first; `None` if no profiled function was called.
+    pub fn profile_summary(&self) -> Option<String> {
+        let mut rows: Vec<(&String, &ProfileStats)> = self
+            .profile_stats
+            .iter()
+            .filter(|(_, s)| s.calls > 0)
+            .collect();
+        if rows.is_empty() {
+            return None;
+        }
+        rows.sort_by(|a, b| b.1.total_secs.total_cmp(&a.1.total_secs).then(a.0.cmp(b.0)));
+        let width = rows.iter().map(|(n, _)| n.len()).max().unwrap_or(0).max(8);
+        let mut out = format!(
+            "📊 Profile\n  {:<width$} {:>10} {:>12} {:>12}\n",
+            "function", "calls", "total ms", "mean ms"
+        );
+        for (name, stats) in rows {
+            let total_ms = stats.total_secs * 1000.0;
+            out.push_str(&format!(
+                "  {:<width$} {:>10} {:>12.3} {:>12.3}\n",
+                name,
+                stats.calls,
+                total_ms,
+                total_ms / stats.calls as f64
+            ));
+        }
+        Some(out)
+    }
+}
+
+pub(crate) fn is_callable(v: &Value) -> bool {
+    matches!(
+        v,
+        Value::Function { .. }
+            | Value::Decorated { .. }
+            | Value::Memoized { .. }
+            | Value::OnceCached { .. }
+    )
+}
+
+/// Whether `value` satisfies a declared parameter type. `float` accepts ints and `num` either;
+/// `func` any callable; capitalized names are classes; `any` / `value` / `object` accept anything.
+fn matches_declared_type(declared: &str, value: &Value) -> bool {
+    match declared {
+        "any" | "value" | "object" => true,
+        "float" => matches!(value, Value::Float(_) | Value::Integer(_)),
+        "num" | "number" => matches!(value, Value::Float(_) | Value::Integer(_)),
+        "func" | "fn" | "function" | "callable" => is_callable(value),
+        class if class.starts_with(char::is_uppercase) => {
+            matches!(value, Value::Instance { class_name, .. } if class_name == class)
+        }
+        other => type_of_name(value) == other,
+    }
+}
+
+/// Strings quoted, everything else as displayed, so records stay unambiguous.
+fn repr(v: &Value) -> String {
+    match v {
+        Value::String(s) => format!("{:?}", s),
+        other => other.to_string(),
+    }
+}
+
+fn format_args_list(args: &[Value]) -> String {
+    args.iter().map(repr).collect::<Vec<_>>().join(", ")
+}
@@ -3,8 +3,331 @@ use crate::parser::AstNode;
 use std::collections::HashSet;
 
 impl Interpreter {
-    pub(super) fn eval_node(&mut self, node: &AstNode) -> Result<Value, String> {
+    /// Evaluate a node. Deep recursion never overflows the native stack: when headroom runs low,
+    /// evaluation continues on a fresh heap-allocated segment. Recursion depth is bounded
+    /// separately by the call depth limit (see [`Interpreter::set_max_call_depth`]).
+    pub(super) fn eval_node(&mut self, node: &AstNode) -> JResult<Value> {
+        let result = crate::with_stack_headroom(|| self.eval_node_inner(node));
+        result.map_err(|error| match node.span() {
+            Some(span) => self.locate_error(error, span),
+            None => error,
+        })
+    }
+
+    /// Nodes on the usual recursion path (calls, blocks, branches, operators). Kept small so
+    /// each level of Jade recursion uses little native stack, even in debug builds.
+    fn eval_node_inner(&mut self, node: &AstNode) -> JResult<Value> {
+        match node {
+            AstNode::Integer(..)
+            | AstNode::Float(..)
+            | AstNode::String(..)
+            | AstNode::StringInterpolation { .. }
+            | AstNode::Boolean(..)
+            | AstNode::Char(..)
+            | AstNode::Infinity(..)
+            | AstNode::Emoji(..)
+            | AstNode::Money(..)
+            | AstNode::Hex(..)
+            | AstNode::Date(..)
+            | AstNode::Time(..)
+            | AstNode::DateTime(..)
+            | AstNode::Tuple(..)
+            | AstNode::List(..)
+            | AstNode::Vector(..)
+            | AstNode::Matrix(..)
+            | AstNode::Dict(..)
+            | AstNode::Identifier(..) => super::eval_primitive::eval_primitive(self, node),
+            AstNode::Block(statements) => self.eval_block(statements),
+            AstNode::Expression(expr) => self.eval_node(expr),
+            AstNode::FunctionCall { name, args, span } => {
+                self.at_call_site(span, |this| this.call_function(name, args))
+            }
+            AstNode::Return(expr, _) => self.eval_return(expr),
+            AstNode::If {
+                condition,
+                then_branch,
+                else_branch,
+                span,
+            } => self.eval_if(condition, then_branch, else_branch, span),
+            AstNode::Match { expr, arms, span } => self.eval_match(expr, arms, span),
+            AstNode::Assignment { name, value, .. } => self.eval_assignment(name, value),
+            AstNode::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } => self.eval_binary_expr(left, operator, right),
+            AstNode::Unary { operator, operand, .. } => {
+                let val = self.eval_node(operand)?;
+                self.eval_unary_op(operator, &val)
+            }
+            _ => self.eval_other_node(node),
+        }
+    }
+
+    fn eval_binary_expr(
+        &mut self,
+        left: &AstNode,
+        operator: &BinaryOp,
+        right: &AstNode,
+    ) -> JResult<Value> {
+        // Short-circuit evaluation for And/Or
+        match operator {
+            BinaryOp::And => {
+                let left_val = self.eval_node(left)?;
+                match left_val {
+                    Value::Boolean(false) => Ok(Value::Boolean(false)),
+                    Value::Boolean(true) => {
+                        let right_val = self.eval_node(right)?;
+                        match right_val {
+                            Value::Boolean(b) => Ok(Value::Boolean(b)),
+                            _ => {
+                                Err(
+                                    JError::invalid_argument("And operator requires boolean operands").into()
+                                )
+                            }
+                        }
+                    }
+                    _ => Err(JError::invalid_argument("And operator requires boolean operands").into()),
+                }
+            }
+            BinaryOp::Or => {
+                let left_val = self.eval_node(left)?;
+                match left_val {
+                    Value::Boolean(true) => Ok(Value::Boolean(true)),
+                    Value::Boolean(false) => {
+                        let right_val = self.eval_node(right)?;
+                        match right_val {
+                            Value::Boolean(b) => Ok(Value::Boolean(b)),
+                            _ => {
+                                Err(
+                                    JError::invalid_argument("Or operator requires boolean operands").into()
+                                )
+                            }
+                        }
+                    }
+                    _ => Err(JError::invalid_argument("Or operator requires boolean operands").into()),
+                }
+            }
+            _ => {
+                // Normal evaluation for other operators
+                let left_val = self.eval_node(left)?;
+                let right_val = self.eval_node(right)?;
+                self.eval_binary_op(&left_val, operator, &right_val)
+            }
+        }
+    }
+
+    /// `object[index]` on already evaluated operands (also used by the bytecode VM).
+    pub(crate) fn index_value(obj_val: Value, idx_val: Value) -> JResult<Value> {
+        match (obj_val, idx_val) {
+            (
+                Value::Enum {
+                    name: enum_name,
+                    variants,
+                },
+                val,
+            ) => {
+                // Reverse lookup: directions[1]
+                for (key, variant_val) in variants.iter() {
+                    if variant_val == &val {
+                        return Ok(Value::EnumVariant {
+                            enum_name: enum_name.clone(),
+                            variant_name: key.clone(),
+                            value: Box::new(variant_val.clone()),
+                        });
+                    }
+                }
+                Err(JError::unsupported(format!("Value '{}' not found in enum '{}'", val, enum_name)).into())
+            }
+            (Value::List(list), Value::Integer(i)) => {
+                // CRITICAL FIX: Proper negative index handling with bounds checking
+                let idx = if i < 0 {
+                    let abs_i = i.unsigned_abs() as usize;
+                    if abs_i > list.len() {
+                        return Err(JError::out_of_range(format!(
+                            "Index {} out of bounds (length {})",
+                            i,
+                            list.len()
+                        )).into());
+                    }
+                    list.len() - abs_i
+                } else {
+                    i as usize
+                };
+
+                if idx < list.len() {
+                    Ok(list[idx].clone())
+                } else {
+                    Err(Box::new(
+                        JError::index_out_of_bounds(i, list.len(), 0, 0).unlocated(),
+                    ))
+                }
+            }
+            (Value::SmallVec { cap: _, elements }, Value::Integer(idx_val)) => {
+                let idx = if idx_val < 0 {
+                    let abs_i = idx_val.unsigned_abs() as usize;
+                    if abs_i > elements.len() {
+                        return Err(JError::out_of_range(format!(
+                            "Index {} out of bounds (length {})",
+                            idx_val,
+                            elements.len()
+                        )).into());
+                    }
+                    elements.len() - abs_i
+                } else {
+                    idx_val as usize
+                };
+                if idx < elements.len() {
+                    Ok(elements[idx].clone())
+                } else {
+                    Err(Box::new(
+                        JError::index_out_of_bounds(idx_val, elements.len(), 0, 0).unlocated(),
+                    ))
+                }
+            }
+            (Value::Grid(grid), Value::Integer(i)) => {
+                // CRITICAL FIX: Proper negative index handling
+                let idx = if i < 0 {
+                    let abs_i = i.unsigned_abs() as usize;
+                    if abs_i > grid.len() {
+                        return Err(JError::out_of_range(format!(
+                            "Grid row index {} out of bounds (rows {})",
+                            i,
+                            grid.len()
+                        )).into());
+                    }
+                    grid.len() - abs_i
+                } else {
+                    i as usize
+                };
+                if idx < grid.len() {
+                    Ok(Value::List(grid[idx].clone()))
+                } else {
+                    Err(JError::out_of_range(format!(
+                        "Grid row index {} out of bounds (rows {})",
+                        i,
+                        grid.len()
+                    )).into())
+                }
+            }
+            (Value::String(s), Value::Integer(i)) => {
+                let chars: Vec<char> = s.chars().collect();
+                // CRITICAL FIX: Proper negative index handling
+                let idx = if i < 0 {
+                    let abs_i = i.unsigned_abs() as usize;
+                    if abs_i > chars.len() {
+                        return Err(JError::out_of_range(format!(
+                            "String index {} out of bounds (length {})",
+                            i,
+                            chars.len()
+                        )).into());
+                    }
+                    chars.len() - abs_i
+                } else {
+                    i as usize
+                };
+
+                if idx < chars.len() {
+                    Ok(Value::Char(chars[idx]))
+                } else {
+                    Err(JError::out_of_range(format!(
+                        "String index {} out of bounds (length {})",
+                        i,
+                        chars.len()
+                    )).into())
+                }
+            }
+            (Value::RegexMatch(m), Value::Integer(i)) => {
+                if i == 0 {
+                    Ok(Value::String(m.text.clone()))
+                } else if i >= 1 && (i as usize) <= m.groups.len() {
+                    Ok(Value::String(m.groups[(i - 1) as usize].clone()))
+                } else {
+                    Ok(Value::None)
+                }
+            }
+            (Value::RegexMatch(m), Value::String(name)) => {
+                m.named.get(&name).cloned().map(Value::String).ok_or_else(|| {
+                    JError::unsupported(format!("No named group '{}' in match", name)).into()
+                })
+            }
+            (Value::Dict(dict), Value::String(key)) => {
+                if let Some(value) = dict.get(&key) {
+                    Ok(value.clone())
+                } else {
+                    Err(Box::new(JError::key_not_found(&key, 0, 0).unlocated()))
+                }
+            }
+            (Value::Dict(dict), Value::Integer(i)) => {
+                let key = i.to_string();
+                if let Some(value) = dict.get(&key) {
+                    Ok(value.clone())
+                } else {
+                    Err(Box::new(JError::key_not_found(&key, 0, 0).unlocated()))
+                }
+            }
+            (Value::Tuple(tuple), Value::Integer(i)) => {
+                // CRITICAL FIX: Proper negative index handling
+                let idx = if i < 0 {
+                    let abs_i = i.unsigned_abs() as usize;
+                    if abs_i > tuple.len() {
+                        return Err(JError::out_of_range(format!(
+                            "Tuple index {} out of bounds (length {})",
+                            i,
+                            tuple.len()
+                        )).into());
+                    }
+                    tuple.len() - abs_i
+                } else {
+                    i as usize
+                };
+
+                if idx < tuple.len() {
+                    Ok(tuple[idx].clone())
+                } else {
+                    Err(JError::out_of_range(format!(
+                        "Tuple index {} out of bounds (length {})",
+                        i,
+                        tuple.len()
+                    )).into())
+                }
+            }
+            (obj, idx) => Err(JError::unsupported(format!(
+                "Cannot index {} with {}",
+                match obj {
+                    Value::Integer(_) => "integer",
+                    Value::Float(_) => "float",
+                    Value::Boolean(_) => "boolean",
+                    Value::Char(_) => "char",
+                    Value::Function { .. } => "function",
+                    Value::Infinity(_) => "infinity",
+                    Value::Emoji(_) => "emoji",
+                    Value::Money(_, _) => "money",
+                    Value::Hex(_) => "hex",
+                    Value::Date(_) => "date",
+                    Value::Time(_) => "time",
+                    Value::DateTime(_) => "datetime",
+                    Value::Range(_, _, _) => "range",
+                    Value::Task(_) => "task",
+                    Value::Channel(_) => "channel",
+                    Value::None => "none",
+                    _ => "unknown",
+                },
+                match idx {
+                    Value::Integer(_) => "integer",
+                    Value::String(_) => "string",
+                    _ => "invalid index type",
+                }
+            )).into()),
+        }
+    }
+
+    /// Every other node kind. Out of line because this match has a very large stack frame.
+    #[inline(never)]
+    fn eval_other_node(&mut self, node: &AstNode) -> JResult<Value> {
         match node {
+            // Literals and hot-path nodes are dispatched by `eval_node_inner` before reaching here
             AstNode::Integer(..)
             | AstNode::Float(..)
             | AstNode::String(..)
@@ -23,7 +346,16 @@ impl Interpreter {
             | AstNode::Vector(..)
             | AstNode::Matrix(..)
             | AstNode::Dict(..)
-            | AstNode::Identifier(..) => return super::eval_primitive::eval_primitive(self, node),
+            | AstNode::Identifier(..)
+            | AstNode::Block(..)
+            | AstNode::Expression(..)
+            | AstNode::FunctionCall { .. }
+            | AstNode::Return(..)
+            | AstNode::If { .. }
+            | AstNode::Match { .. }
+            | AstNode::Assignment { .. }
+            | AstNode::Binary { .. }
+            | AstNode::Unary { .. } => self.eval_node_inner(node),
 
             AstNode::VarDeclaration {
                 var_type,
@@ -32,6 +364,7 @@ impl Interpreter {
                 immutable: _,
                 is_static,
                 type_modifier,
+                ..
             } => {
                 let mut val = self.eval_node(value)?;
 
@@ -98,7 +431,7 @@ impl Interpreter {
                                         Value::Float(f) => vector.push(f),
                                         _ => {
                                             return Err(
-                                                "Vector elements must be numeric".to_string()
+                                                JError::invalid_argument("Vector elements must be numeric").into()
                                             )
                                         }
                                     }
@@ -107,7 +440,7 @@ impl Interpreter {
                             }
                             _ => {
                                 return Err(
-                                    "Vector must be initialized with a list or vector".to_string()
+                                    JError::invalid_argument("Vector must be initialized with a list or vector").into()
                                 )
                             }
                         }
@@ -128,23 +461,21 @@ impl Interpreter {
                                                     Value::Float(f) => matrix_row.push(f),
                                                     _ => {
                                                         return Err(
-                                                            "Matrix elements must be numeric"
-                                                                .to_string(),
+                                                            JError::invalid_argument("Matrix elements must be numeric").into(),
                                                         )
                                                     }
                                                 }
                                             }
                                             matrix.push(matrix_row);
                                         }
-                                        _ => return Err("Matrix rows must be lists".to_string()),
+                                        _ => return Err(JError::invalid_argument("Matrix rows must be lists").into()),
                                     }
                                 }
                                 Value::Matrix(matrix)
                             }
                             _ => {
                                 return Err(
-                                    "Matrix must be initialized with a list of lists or matrix"
-                                        .to_string(),
+                                    JError::invalid_argument("Matrix must be initialized with a list of lists or matrix").into(),
                                 )
                             }
                         }
@@ -163,8 +494,7 @@ impl Interpreter {
                                         Value::Boolean(b) => b.to_string(),
                                         _ => {
                                             return Err(
-                                                "Set elements must be convertible to strings"
-                                                    .to_string(),
+                                                JError::invalid_argument("Set elements must be convertible to strings").into(),
                                             )
                                         }
                                     };
@@ -172,7 +502,7 @@ impl Interpreter {
                                 }
                                 Value::Set(set)
                             }
-                            _ => return Err("Set must be initialized with a list".to_string()),
+                            _ => return Err(JError::invalid_argument("Set must be initialized with a list").into()),
                         }
                     }
                     "counter" => {
@@ -198,8 +528,7 @@ impl Interpreter {
                                             Value::Float(f) => f.to_string(),
                                             Value::Boolean(b) => b.to_string(),
                                             _ => return Err(
-                                                "Counter elements must be convertible to strings"
-                                                    .to_string(),
+                                                JError::invalid_argument("Counter elements must be convertible to strings").into(),
                                             ),
                                         };
                                     *counter.entry(key).or_insert(0) += 1;
@@ -208,7 +537,7 @@ impl Interpreter {
                             }
                             _ => {
                                 return Err(
-                                    "Counter must be initialized with a string or list".to_string()
+                                    JError::invalid_argument("Counter must be initialized with a string or list").into()
                                 )
                             }
                         }
@@ -216,7 +545,7 @@ impl Interpreter {
                     "deque" => match val {
                         Value::Deque(_) => val,
                         Value::List(list) => Value::Deque(list),
-                        _ => return Err("Deque must be initialized with a list".to_string()),
+                        _ => return Err(JError::invalid_argument("Deque must be initialized with a list").into()),
                     },
                     "priorityq" => {
                         match val {
@@ -230,19 +559,18 @@ impl Interpreter {
                                             let priority = match &tuple[0] {
                                                 Value::Integer(i) => *i,
                                                 Value::Float(f) => *f as i64,
-                                                _ => return Err("Priority must be numeric".to_string()),
+                                                _ => return Err(JError::invalid_argument("Priority must be numeric").into()),
                                             };
                                             pq.push((priority, tuple[1].clone()));
                                         }
-                                        _ => return Err("Priority queue elements must be (priority, value) tuples".to_string()),
+                                        _ => return Err(JError::invalid_argument("Priority queue elements must be (priority, value) tuples").into()),
                                     }
                                 }
                                 Value::PriorityQ(pq)
                             }
                             _ => {
                                 return Err(
-                                    "Priority queue must be initialized with a list of tuples"
-                                        .to_string(),
+                                    JError::invalid_argument("Priority queue must be initialized with a list of tuples").into(),
                                 )
                             }
                         }
@@ -262,23 +590,23 @@ impl Interpreter {
                                                     Value::Tuple(tuple) if tuple.len() == 2 => {
                                                         let neighbor = match &tuple[0] {
                                                             Value::String(s) => s.clone(),
-                                                            _ => return Err("Graph neighbor must be a string".to_string()),
+                                                            _ => return Err(JError::invalid_argument("Graph neighbor must be a string").into()),
                                                         };
                                                         let weight = match &tuple[1] {
                                                             Value::Float(f) => *f,
                                                             Value::Integer(i) => *i as f64,
-                                                            _ => return Err("Graph weight must be numeric".to_string()),
+                                                            _ => return Err(JError::invalid_argument("Graph weight must be numeric").into()),
                                                         };
                                                         edge_list.push((neighbor, weight));
                                                     }
-                                                    _ => return Err("Graph edges must be (neighbor, weight) tuples".to_string()),
+                                                    _ => return Err(JError::invalid_argument("Graph edges must be (neighbor, weight) tuples").into()),
                                                 }
                                             }
                                             graph.insert(node, edge_list);
                                         }
                                         _ => {
                                             return Err(
-                                                "Graph node edges must be a list".to_string()
+                                                JError::invalid_argument("Graph node edges must be a list").into()
                                             )
                                         }
                                     }
@@ -287,7 +615,7 @@ impl Interpreter {
                             }
                             _ => {
                                 return Err(
-                                    "Graph must be initialized with a dictionary".to_string()
+                                    JError::invalid_argument("Graph must be initialized with a dictionary").into()
                                 )
                             }
                         }
@@ -307,7 +635,7 @@ impl Interpreter {
                                 match row {
                                     Value::List(cells) => grid.push(cells),
                                     _ => {
-                                        return Err("Grid must be a list of lists (2D)".to_string())
+                                        return Err(JError::invalid_argument("Grid must be a list of lists (2D)").into())
                                     }
                                 }
                             }
@@ -315,8 +643,7 @@ impl Interpreter {
                         }
                         _ => {
                             return Err(
-                                "Grid must be initialized with a list of lists or matrix literal"
-                                    .to_string(),
+                                JError::invalid_argument("Grid must be initialized with a list of lists or matrix literal").into(),
                             )
                         }
                     },
@@ -327,12 +654,12 @@ impl Interpreter {
                                 // Convert dict to tree: {value: ..., children: [...]}
                                 let value = dict
                                     .get("value")
-                                    .ok_or_else(|| "Tree must have 'value' field".to_string())?
+                                    .ok_or_else(|| JError::invalid_argument("Tree must have 'value' field"))?
                                     .clone();
                                 let children = match dict.get("children") {
                                     Some(Value::List(children_list)) => children_list.clone(),
                                     Some(_) => {
-                                        return Err("Tree 'children' must be a list".to_string())
+                                        return Err(JError::invalid_argument("Tree 'children' must be a list").into())
                                     }
                                     None => Vec::new(),
                                 };
@@ -342,7 +669,7 @@ impl Interpreter {
                                 }
                             }
                             _ => {
-                                return Err("Tree must be initialized with a dictionary".to_string())
+                                return Err(JError::invalid_argument("Tree must be initialized with a dictionary").into())
                             }
                         }
                     }
@@ -359,7 +686,7 @@ impl Interpreter {
                                     end: len,
                                 }
                             }
-                            _ => return Err("Span must be initialized with a list".to_string()),
+                            _ => return Err(JError::invalid_argument("Span must be initialized with a list").into()),
                         }
                     }
                     "mut_span" => match val {
@@ -372,7 +699,7 @@ impl Interpreter {
                                 end: len,
                             }
                         }
-                        _ => return Err("MutSpan must be initialized with a list".to_string()),
+                        _ => return Err(JError::invalid_argument("MutSpan must be initialized with a list").into()),
                     },
                     "chunk" => match val {
                         Value::Chunk { .. } => val,
@@ -381,7 +708,7 @@ impl Interpreter {
                             chunk_size: 1,
                             current_index: 0,
                         },
-                        _ => return Err("Chunk must be initialized with a list".to_string()),
+                        _ => return Err(JError::invalid_argument("Chunk must be initialized with a list").into()),
                     },
                     "sparse" => {
                         match val {
@@ -408,7 +735,7 @@ impl Interpreter {
                             }
                             _ => {
                                 return Err(
-                                    "Sparse must be initialized with a list or size".to_string()
+                                    JError::invalid_argument("Sparse must be initialized with a list or size").into()
                                 )
                             }
                         }
@@ -436,8 +763,7 @@ impl Interpreter {
                                 }
                             }
                             _ => {
-                                return Err("Ring must be initialized with capacity (int) or list"
-                                    .to_string())
+                                return Err(JError::invalid_argument("Ring must be initialized with capacity (int) or list").into())
                             }
                         }
                     }
@@ -456,6 +782,7 @@ impl Interpreter {
                 name,
                 backing_type: _,
                 variants,
+                ..
             } => {
                 let mut variant_map = HashMap::new();
                 let mut next_int_val = 1;
@@ -489,6 +816,7 @@ impl Interpreter {
                 methods,
                 static_fields,
                 static_methods,
+                ..
             } => {
                 let mut class_fields = HashMap::new();
                 let mut class_methods = HashMap::new();
@@ -527,6 +855,7 @@ impl Interpreter {
                             name: method_name.clone(),
                             params: param_names,
                             body: body.clone(),
+                            env: self.capture_env(),
                         };
                         class_methods.insert(method_name.clone(), func);
                     }
@@ -546,6 +875,7 @@ impl Interpreter {
                             name: method_name.clone(),
                             params: param_names,
                             body: body.clone(),
+                            env: self.capture_env(),
                         };
                         class_static_methods.insert(method_name.clone(), func);
                     }
@@ -578,34 +908,33 @@ impl Interpreter {
                     name: name.clone(),
                     params: param_names,
                     body: body.clone(),
+                    env: self.capture_env(),
                 };
 
                 // Apply decorators (bottom-to-top order)
                 for decorator in decorators.iter().rev() {
-                    func = self.apply_decorator(&decorator.name, &decorator.args, func)?;
+                    func = self.apply_decorator(&decorator.name, &decorator.args, func, params)?;
                 }
 
                 self.set_variable(name.clone(), func.clone());
                 Ok(func)
             }
 
-            AstNode::FunctionCall { name, args } => self.call_function(name, args),
-
-            AstNode::Call { callee, args } => {
+            AstNode::Call { callee, args, span } => self.at_call_site(span, |this| {
                 let (callee_val, this_opt) =
-                    if let AstNode::DotAccess { object, field } = callee.as_ref() {
-                        let receiver = self.eval_node(object)?;
-                        let val = self.get_property(&receiver, field)?;
+                    if let AstNode::DotAccess { object, field, .. } = callee.as_ref() {
+                        let receiver = this.eval_node(object)?;
+                        let val = this.get_property(&receiver, field)?;
                         let this_opt = match &receiver {
                             Value::Instance { .. } => Some(receiver),
                             _ => None,
                         };
                         (val, this_opt)
                     } else {
-                        (self.eval_node(callee)?, None)
+                        (this.eval_node(callee)?, None)
                     };
-                self.call_value(callee_val, args, this_opt)
-            }
+                this.call_value(callee_val, args, this_opt)
+            }),
 
             AstNode::BroadcastCall { callee, args } => {
                 let callee_val = self.eval_node(callee)?;
@@ -639,63 +968,6 @@ impl Interpreter {
                 }
             }
 
-            AstNode::Binary {
-                left,
-                operator,
-                right,
-            } => {
-                // Short-circuit evaluation for And/Or
-                match operator {
-                    BinaryOp::And => {
-                        let left_val = self.eval_node(left)?;
-                        match left_val {
-                            Value::Boolean(false) => Ok(Value::Boolean(false)),
-                            Value::Boolean(true) => {
-                                let right_val = self.eval_node(right)?;
-                                match right_val {
-                                    Value::Boolean(b) => Ok(Value::Boolean(b)),
-                                    _ => {
-                                        Err(
-                                            "And operator requires boolean operands".to_string()
-                                        )
-                                    }
-                                }
-                            }
-                            _ => Err("And operator requires boolean operands".to_string()),
-                        }
-                    }
-                    BinaryOp::Or => {
-                        let left_val = self.eval_node(left)?;
-                        match left_val {
-                            Value::Boolean(true) => Ok(Value::Boolean(true)),
-                            Value::Boolean(false) => {
-                                let right_val = self.eval_node(right)?;
-                                match right_val {
-                                    Value::Boolean(b) => Ok(Value::Boolean(b)),
-                                    _ => {
-                                        Err(
-                                            "Or operator requires boolean operands".to_string()
-                                        )
-                                    }
-                                }
-                            }
-                            _ => Err("Or operator requires boolean operands".to_string()),
-                        }
-                    }
-                    _ => {
-                        // Normal evaluation for other operators
-                        let left_val = self.eval_node(left)?;
-                        let right_val = self.eval_node(right)?;
-                        self.eval_binary_op(&left_val, operator, &right_val)
-                    }
-                }
-            }
-
-            AstNode::Unary { operator, operand } => {
-                let val = self.eval_node(operand)?;
-                self.eval_unary_op(operator, &val)
-            }
-
             AstNode::Pipeline { left, right } => {
                 let left_val = self.eval_node(left)?;
 
@@ -713,7 +985,7 @@ impl Interpreter {
                 Ok(result)
             }
 
-            AstNode::DotAccess { object, field } => {
+            AstNode::DotAccess { object, field, .. } => {
                 let obj_val = self.eval_node(object)?;
                 match obj_val {
                     Value::Enum {
@@ -738,10 +1010,10 @@ impl Interpreter {
                                     let vals: Vec<Value> = variants.values().cloned().collect();
                                     Ok(Value::List(vals))
                                 }
-                                _ => Err(format!(
+                                _ => Err(JError::unsupported(format!(
                                     "Unknown variant or method '{}' on enum '{}'",
                                     field, enum_name
-                                )),
+                                )).into()),
                             }
                         }
                     }
@@ -752,7 +1024,7 @@ impl Interpreter {
                     } => match field.as_str() {
                         "label" | "name" => Ok(Value::String(variant_name)),
                         "value" => Ok(*value),
-                        _ => Err(format!("Unknown property '{}' on enum variant", field)),
+                        _ => Err(JError::unsupported(format!("Unknown property '{}' on enum variant", field)).into()),
                     },
                     Value::Class {
                         name: class_name,
@@ -770,10 +1042,10 @@ impl Interpreter {
                         } else if let Some(v) = static_methods.get(field) {
                             Ok(v.clone())
                         } else {
-                            Err(format!(
+                            Err(JError::unsupported(format!(
                                 "Unknown static field or method '{}' on class '{}'",
                                 field, class_name
-                            ))
+                            )).into())
                         }
                     }
                     Value::Instance { class_name, fields } => {
@@ -789,7 +1061,7 @@ impl Interpreter {
                         "end" => Ok(Value::Integer(m.end as i64)),
                         "group" => Ok(Value::MatchGroup(m.clone())),
                         _ => m.named.get(field).cloned().map(Value::String).ok_or_else(|| {
-                            format!("Unknown match field '{}' (use text, start, end, group(n), or named group)", field)
+                            JError::unsupported(format!("Unknown match field '{}' (use text, start, end, group(n), or named group)", field)).into()
                         }),
                     },
                     Value::Dict(dict) => {
@@ -820,50 +1092,48 @@ impl Interpreter {
                                 }
                                 "has" => {
                                     Err(
-                                        "dict.has() requires a key argument - use dict.has(key)"
-                                            .to_string(),
+                                        JError::invalid_argument("dict.has() requires a key argument - use dict.has(key)").into(),
                                     )
                                 }
                                 "get" => {
                                     Err(
-                                        "dict.get() requires key and optional default arguments"
-                                            .to_string(),
+                                        JError::invalid_argument("dict.get() requires key and optional default arguments").into(),
                                     )
                                 }
                                 "remove" => {
-                                    Err("dict.remove() requires a key argument - use dict.remove(key)".to_string())
+                                    Err(JError::invalid_argument("dict.remove() requires a key argument - use dict.remove(key)").into())
                                 }
                                 "merge" => {
                                     Err(
-                                        "dict.merge() requires another dict argument".to_string()
+                                        JError::invalid_argument("dict.merge() requires another dict argument").into()
                                     )
                                 }
                                 "update" => {
                                     Err(
-                                        "dict.update() requires another dict argument".to_string()
+                                        JError::invalid_argument("dict.update() requires another dict argument").into()
                                     )
                                 }
                                 "clear" => {
-                                    Err("dict.clear() cannot be called as a method - use clear(dict)".to_string())
+                                    Err(JError::unsupported("dict.clear() cannot be called as a method - use clear(dict)").into())
                                 }
                                 "size" | "len" | "length" => Ok(Value::Integer(dict.len() as i64)),
-                                _ => Err(format!("Dictionary field '{}' not found", field)),
+                                _ => Err(JError::missing_key(format!("Dictionary field '{}' not found", field)).into()),
                             }
                         }
                     }
                     Value::Date(s) => {
                         let parts: Vec<&str> = s.trim().split('-').collect();
                         if parts.len() != 3 {
-                            return Err(format!("Invalid date format '{}'", s));
+                            return Err(JError::invalid_argument(format!("Invalid date format '{}'", s)).into());
                         }
-                        let y: i64 = parts[0].parse().map_err(|_| "Invalid year".to_string())?;
-                        let m: i64 = parts[1].parse().map_err(|_| "Invalid month".to_string())?;
-                        let d: i64 = parts[2].parse().map_err(|_| "Invalid day".to_string())?;
+                        let y: i64 = parts[0].parse().map_err(|_| JError::invalid_argument("Invalid year"))?;
+                        let m: i64 = parts[1].parse().map_err(|_| JError::invalid_argument("Invalid month"))?;
+                        let d: i64 = parts[2].parse().map_err(|_| JError::invalid_argument("Invalid day"))?;
                         match field.as_str() {
                             "year" => Ok(Value::Integer(y)),
                             "month" => Ok(Value::Integer(m)),
                             "day" => Ok(Value::Integer(d)),
-                            _ => Err(format!("Unknown date property '{}'", field)),
+                            _ => Err(JError::unsupported(format!("Unknown date property '{}'", field)).into()),
                         }
                     }
                     Value::Time(s) => {
@@ -871,16 +1141,16 @@ impl Interpreter {
                         let time_part = s.split('.').next().unwrap_or(s);
                         let parts: Vec<&str> = time_part.split(':').collect();
                         if parts.len() < 3 {
-                            return Err(format!("Invalid time format '{}'", s));
+                            return Err(JError::invalid_argument(format!("Invalid time format '{}'", s)).into());
                         }
-                        let h: i64 = parts[0].parse().map_err(|_| "Invalid hour".to_string())?;
-                        let min: i64 = parts[1].parse().map_err(|_| "Invalid minute".to_string())?;
-                        let sec: i64 = parts[2].parse().map_err(|_| "Invalid second".to_string())?;
+                        let h: i64 = parts[0].parse().map_err(|_| JError::invalid_argument("Invalid hour"))?;
+                        let min: i64 = parts[1].parse().map_err(|_| JError::invalid_argument("Invalid minute"))?;
+                        let sec: i64 = parts[2].parse().map_err(|_| JError::invalid_argument("Invalid second"))?;
                         match field.as_str() {
                             "hour" => Ok(Value::Integer(h)),
                             "minute" => Ok(Value::Integer(min)),
                             "second" => Ok(Value::Integer(sec)),
-                            _ => Err(format!("Unknown time property '{}'", field)),
+                            _ => Err(JError::unsupported(format!("Unknown time property '{}'", field)).into()),
                         }
                     }
                     Value::DateTime(s) => {
@@ -895,11 +1165,11 @@ impl Interpreter {
                         let d_parts: Vec<&str> = date_part.split('-').collect();
                         let t_parts: Vec<&str> = time_part.split(':').collect();
                         if d_parts.len() != 3 {
-                            return Err(format!("Invalid datetime date part '{}'", s));
+                            return Err(JError::invalid_argument(format!("Invalid datetime date part '{}'", s)).into());
                         }
-                        let y: i64 = d_parts[0].parse().map_err(|_| "Invalid year".to_string())?;
-                        let m: i64 = d_parts[1].parse().map_err(|_| "Invalid month".to_string())?;
-                        let day: i64 = d_parts[2].parse().map_err(|_| "Invalid day".to_string())?;
+                        let y: i64 = d_parts[0].parse().map_err(|_| JError::invalid_argument("Invalid year"))?;
+                        let m: i64 = d_parts[1].parse().map_err(|_| JError::invalid_argument("Invalid month"))?;
+                        let day: i64 = d_parts[2].parse().map_err(|_| JError::invalid_argument("Invalid day"))?;
                         let h: i64 = t_parts.get(0).and_then(|x| x.parse().ok()).unwrap_or(0);
                         let min: i64 = t_parts.get(1).and_then(|x| x.parse().ok()).unwrap_or(0);
                         let sec: i64 = t_parts.get(2).and_then(|x| x.parse().ok()).unwrap_or(0);
@@ -912,16 +1182,26 @@ impl Interpreter {
                             "second" => Ok(Value::Integer(sec)),
                             "date" => Ok(Value::Date(format!("{:04}-{:02}-{:02}", y, m, day))),
                             "time" => Ok(Value::Time(format!("{:02}:{:02}:{:02}", h, min, sec))),
-                            _ => Err(format!("Unknown datetime property '{}'", field)),
-                        }
-                    }
+                            _ => Err(JError::unsupported(format!("Unknown datetime property '{}'", field)).into()),
+                        }
+                    }
+                    Value::Error(error) => match field.as_str() {
+                        "message" => Ok(Value::String(error.message.clone())),
+                        "kind" => Ok(Value::String(format!("{:?}", error.kind))),
+                        "code" => Ok(Value::String(error.kind.code().to_string())),
+                        "line" => Ok(error.line.map_or(Value::None, |l| Value::Integer(l as i64))),
+                        "column" => Ok(error.column.map_or(Value::None, |c| Value::Integer(c as i64))),
+                        "file" => Ok(error.file.clone().map_or(Value::None, Value::String)),
+                        "trace" => Ok(Value::List(error.trace.iter().map(trace_entry_value).collect())),
+                        _ => Err(JError::unsupported(format!("Unknown error property '{}'", field)).into()),
+                    },
                     Value::Duration { total_seconds } => match field.as_str() {
                         "days" => Ok(Value::Integer(total_seconds / 86400)),
                         "hours" => Ok(Value::Integer(total_seconds / 3600)),
                         "minutes" => Ok(Value::Integer(total_seconds / 60)),
                         "seconds" => Ok(Value::Integer(total_seconds)),
                         "is_zero" => Ok(Value::Boolean(total_seconds == 0)),
-                        _ => Err(format!("Unknown interval property '{}'", field)),
+                        _ => Err(JError::unsupported(format!("Unknown interval property '{}'", field)).into()),
                     },
                     Value::List(list) => {
                         // List methods
@@ -930,11 +1210,11 @@ impl Interpreter {
                             "first" => list
                                 .first()
                                 .cloned()
-                                .ok_or_else(|| "List is empty".to_string()),
+                                .ok_or_else(|| JError::empty_collection("List is empty").into()),
                             "last" => list
                                 .last()
                                 .cloned()
-                                .ok_or_else(|| "List is empty".to_string()),
+                                .ok_or_else(|| JError::empty_collection("List is empty").into()),
                             "sum" => {
                                 let mut sum = 0.0;
                                 for item in list.iter() {
@@ -942,7 +1222,7 @@ impl Interpreter {
                                         Value::Integer(i) => sum += *i as f64,
                                         Value::Float(f) => sum += f,
                                         _ => {
-                                            return Err("sum() requires numeric values".to_string())
+                                            return Err(JError::invalid_argument("sum() requires numeric values").into())
                                         }
                                     }
                                 }
@@ -960,7 +1240,7 @@ impl Interpreter {
                                         Value::Float(f) => product *= f,
                                         _ => {
                                             return Err(
-                                                "product() requires numeric values".to_string()
+                                                JError::invalid_argument("product() requires numeric values").into()
                                             )
                                         }
                                     }
@@ -973,7 +1253,7 @@ impl Interpreter {
                             }
                             "min" => {
                                 if list.is_empty() {
-                                    return Err("min() requires non-empty list".to_string());
+                                    return Err(JError::empty_collection("min() requires non-empty list").into());
                                 }
                                 let mut min_val = list[0].clone();
                                 for item in list.iter().skip(1) {
@@ -1001,7 +1281,7 @@ impl Interpreter {
                             }
                             "max" => {
                                 if list.is_empty() {
-                                    return Err("max() requires non-empty list".to_string());
+                                    return Err(JError::empty_collection("max() requires non-empty list").into());
                                 }
                                 let mut max_val = list[0].clone();
                                 for item in list.iter().skip(1) {
@@ -1036,7 +1316,7 @@ impl Interpreter {
                                         Value::Float(f) => *f,
                                         _ => {
                                             return Err(
-                                                "scan_max() requires numeric list".to_string()
+                                                JError::invalid_argument("scan_max() requires numeric list").into()
                                             )
                                         }
                                     };
@@ -1070,7 +1350,7 @@ impl Interpreter {
                                         Value::Float(f) => running += f,
                                         _ => {
                                             return Err(
-                                                "scan_sum() requires numeric list".to_string()
+                                                JError::invalid_argument("scan_sum() requires numeric list").into()
                                             )
                                         }
                                     }
@@ -1101,8 +1381,7 @@ impl Interpreter {
                                         Value::Integer(i) => *i as f64,
                                         Value::Float(f) => *f,
                                         _ => {
-                                            return Err("scan_right_max() requires numeric list"
-                                                .to_string())
+                                            return Err(JError::invalid_argument("scan_right_max() requires numeric list").into())
                                         }
                                     };
                                     running_max =
@@ -1131,7 +1410,7 @@ impl Interpreter {
                             }
                             "mean" | "average" | "avg" => {
                                 if list.is_empty() {
-                                    return Err("mean() requires non-empty list".to_string());
+                                    return Err(JError::empty_collection("mean() requires non-empty list").into());
                                 }
                                 let mut sum = 0.0;
                                 for item in list.iter() {
@@ -1139,7 +1418,7 @@ impl Interpreter {
                                         Value::Integer(i) => sum += *i as f64,
                                         Value::Float(f) => sum += f,
                                         _ => {
-                                            return Err("mean() requires numeric values".to_string())
+                                            return Err(JError::invalid_argument("mean() requires numeric values").into())
                                         }
                                     }
                                 }
@@ -1147,7 +1426,7 @@ impl Interpreter {
                             }
                             "median" => {
                                 if list.is_empty() {
-                                    return Err("median() requires non-empty list".to_string());
+                                    return Err(JError::empty_collection("median() requires non-empty list").into());
                                 }
                                 let mut sorted = list.clone();
                                 sorted.sort_by(|a, b| match (a, b) {
@@ -1222,35 +1501,31 @@ impl Interpreter {
                             "empty" | "is_empty" => Ok(Value::Boolean(list.is_empty())),
                             "any" => {
                                 Err(
-                                    "list.any() requires a predicate function - use any(list, fn)"
-                                        .to_string(),
+                                    JError::invalid_argument("list.any() requires a predicate function - use any(list, fn)").into(),
                                 )
                             }
                             "all" => {
                                 Err(
-                                    "list.all() requires a predicate function - use all(list, fn)"
-                                        .to_string(),
+                                    JError::invalid_argument("list.all() requires a predicate function - use all(list, fn)").into(),
                                 )
                             }
                             "count" => {
                                 Err(
-                                    "list.count() requires an argument - use list.count(value)"
-                                        .to_string(),
+                                    JError::invalid_argument("list.count() requires an argument - use list.count(value)").into(),
                                 )
                             }
                             "contains" => {
-                                Err("list.contains() requires an argument - use list.contains(value)".to_string())
+                                Err(JError::invalid_argument("list.contains() requires an argument - use list.contains(value)").into())
                             }
                             "index" | "find" => {
                                 Err(
-                                    "list.index() requires an argument - use list.index(value)"
-                                        .to_string(),
+                                    JError::invalid_argument("list.index() requires an argument - use list.index(value)").into(),
                                 )
                             }
                             "join" => {
-                                Err("list.join() requires a separator argument - use list.join(sep)".to_string())
+                                Err(JError::invalid_argument("list.join() requires a separator argument - use list.join(sep)").into())
                             }
-                            _ => Err(format!("List method '{}' not found", field)),
+                            _ => Err(JError::unsupported(format!("List method '{}' not found", field)).into()),
                         }
                     }
                     Value::String(s) => {
@@ -1261,12 +1536,12 @@ impl Interpreter {
                                 .chars()
                                 .next()
                                 .map(Value::Char)
-                                .ok_or_else(|| "String is empty".to_string()),
+                                .ok_or_else(|| JError::empty_collection("String is empty").into()),
                             "last" => s
                                 .chars()
                                 .last()
                                 .map(Value::Char)
-                                .ok_or_else(|| "String is empty".to_string()),
+                                .ok_or_else(|| JError::empty_collection("String is empty").into()),
                             "empty" | "is_empty" => Ok(Value::Boolean(s.is_empty())),
                             "upper" | "uppercase" => Ok(Value::String(s.to_uppercase())),
                             "lower" | "lowercase" => Ok(Value::String(s.to_lowercase())),
@@ -1287,25 +1562,25 @@ impl Interpreter {
                                 Ok(Value::List(chars))
                             }
                             "split" => {
-                                Err("string.split() requires a separator argument - use string.split(sep)".to_string())
+                                Err(JError::invalid_argument("string.split() requires a separator argument - use string.split(sep)").into())
                             }
                             "contains" => {
-                                Err("string.contains() requires a substring argument - use string.contains(substr)".to_string())
+                                Err(JError::invalid_argument("string.contains() requires a substring argument - use string.contains(substr)").into())
                             }
                             "starts_with" | "startswith" => {
                                 Err(
-                                    "string.starts_with() requires a prefix argument".to_string()
+                                    JError::invalid_argument("string.starts_with() requires a prefix argument").into()
                                 )
                             }
                             "ends_with" | "endswith" => {
                                 Err(
-                                    "string.ends_with() requires a suffix argument".to_string()
+                                    JError::invalid_argument("string.ends_with() requires a suffix argument").into()
                                 )
                             }
                             "replace" => {
-                                Err("string.replace() requires old and new arguments - use string.replace(old, new)".to_string())
+                                Err(JError::invalid_argument("string.replace() requires old and new arguments - use string.replace(old, new)").into())
                             }
-                            _ => Err(format!("String method '{}' not found", field)),
+                            _ => Err(JError::unsupported(format!("String method '{}' not found", field)).into()),
                         }
                     }
                     Value::Counter(counter) => match field.as_str() {
@@ -1333,7 +1608,7 @@ impl Interpreter {
                             Ok(Value::Integer(total))
                         }
                         "len" | "length" | "size" => Ok(Value::Integer(counter.len() as i64)),
-                        _ => Err(format!("Counter method '{}' not found", field)),
+                        _ => Err(JError::unsupported(format!("Counter method '{}' not found", field)).into()),
                     },
                     Value::Interval(start, end) => {
                         match field.as_str() {
@@ -1343,20 +1618,18 @@ impl Interpreter {
                             "overlaps" => {
                                 // Returns a callable that checks if another interval overlaps
                                 Err(
-                                    "interval.overlaps() requires another interval argument"
-                                        .to_string(),
+                                    JError::invalid_argument("interval.overlaps() requires another interval argument").into(),
                                 )
                             }
                             "merge" => {
-                                Err("interval.merge() requires another interval argument"
-                                    .to_string())
+                                Err(JError::invalid_argument("interval.merge() requires another interval argument").into())
                             }
                             "contains" => {
                                 Err(
-                                    "interval.contains() requires a value argument".to_string()
+                                    JError::invalid_argument("interval.contains() requires a value argument").into()
                                 )
                             }
-                            _ => Err(format!("Interval method '{}' not found", field)),
+                            _ => Err(JError::unsupported(format!("Interval method '{}' not found", field)).into()),
                         }
                     }
                     Value::Grid(grid) => {
@@ -1373,14 +1646,14 @@ impl Interpreter {
                             "neighbors" => {
                                 Ok(Value::GridNeighbors(Box::new(Value::Grid(grid.clone()))))
                             }
-                            _ => Err(format!("Grid method '{}' not found", field)),
+                            _ => Err(JError::unsupported(format!("Grid method '{}' not found", field)).into()),
                         }
                     }
                     Value::Matrix(mat) => {
                         // Use get_property for Matrix to get all the accessor methods
                         self.get_property(&Value::Matrix(mat), field)
                     }
-                    _ => Err(format!(
+                    _ => Err(JError::unsupported(format!(
                         "Cannot access field '{}' on type {}",
                         field,
                         match obj_val {
@@ -1391,211 +1664,15 @@ impl Interpreter {
                             Value::Function { .. } => "function",
                             _ => "unknown",
                         }
-                    )),
+                    )).into()),
                 }
             }
 
-            AstNode::Index { object, index } => {
+            AstNode::Index { object, index, .. } => {
                 let obj_val = self.eval_node(object)?;
                 let idx_val = self.eval_node(index)?;
 
-                match (obj_val, idx_val) {
-                    (
-                        Value::Enum {
-                            name: enum_name,
-                            variants,
-                        },
-                        val,
-                    ) => {
-                        // Reverse lookup: directions[1]
-                        for (key, variant_val) in variants.iter() {
-                            if variant_val == &val {
-                                return Ok(Value::EnumVariant {
-                                    enum_name: enum_name.clone(),
-                                    variant_name: key.clone(),
-                                    value: Box::new(variant_val.clone()),
-                                });
-                            }
-                        }
-                        Err(format!("Value '{}' not found in enum '{}'", val, enum_name))
-                    }
-                    (Value::List(list), Value::Integer(i)) => {
-                        // CRITICAL FIX: Proper negative index handling with bounds checking
-                        let idx = if i < 0 {
-                            let abs_i = i.unsigned_abs() as usize;
-                            if abs_i > list.len() {
-                                return Err(format!(
-                                    "Index {} out of bounds (length {})",
-                                    i,
-                                    list.len()
-                                ));
-                            }
-                            list.len() - abs_i
-                        } else {
-                            i as usize
-                        };
-
-                        if idx < list.len() {
-                            Ok(list[idx].clone())
-                        } else {
-                            Err(JError::index_out_of_bounds(i, list.len(), 0, 0).to_string())
-                        }
-                    }
-                    (Value::SmallVec { cap: _, elements }, Value::Integer(idx_val)) => {
-                        let idx = if idx_val < 0 {
-                            let abs_i = idx_val.unsigned_abs() as usize;
-                            if abs_i > elements.len() {
-                                return Err(format!(
-                                    "Index {} out of bounds (length {})",
-                                    idx_val,
-                                    elements.len()
-                                ));
-                            }
-                            elements.len() - abs_i
-                        } else {
-                            idx_val as usize
-                        };
-                        if idx < elements.len() {
-                            Ok(elements[idx].clone())
-                        } else {
-                            Err(JError::index_out_of_bounds(idx_val, elements.len(), 0, 0).to_string())
-                        }
-                    }
-                    (Value::Grid(grid), Value::Integer(i)) => {
-                        // CRITICAL FIX: Proper negative index handling
-                        let idx = if i < 0 {
-                            let abs_i = i.unsigned_abs() as usize;
-                            if abs_i > grid.len() {
-                                return Err(format!(
-                                    "Grid row index {} out of bounds (rows {})",
-                                    i,
-                                    grid.len()
-                                ));
-                            }
-                            grid.len() - abs_i
-                        } else {
-                            i as usize
-                        };
-                        if idx < grid.len() {
-                            Ok(Value::List(grid[idx].clone()))
-                        } else {
-                            Err(format!(
-                                "Grid row index {} out of bounds (rows {})",
-                                i,
-                                grid.len()
-                            ))
-                        }
-                    }
-                    (Value::String(s), Value::Integer(i)) => {
-                        let chars: Vec<char> = s.chars().collect();
-                        // CRITICAL FIX: Proper negative index handling
-                        let idx = if i < 0 {
-                            let abs_i = i.unsigned_abs() as usize;
-                            if abs_i > chars.len() {
-                                return Err(format!(
-                                    "String index {} out of bounds (length {})",
-                                    i,
-                                    chars.len()
-                                ));
-                            }
-                            chars.len() - abs_i
-                        } else {
-                            i as usize
-                        };
-
-                        if idx < chars.len() {
-                            Ok(Value::Char(chars[idx]))
-                        } else {
-                            Err(format!(
-                                "String index {} out of bounds (length {})",
-                                i,
-                                chars.len()
-                            ))
-                        }
-                    }
-                    (Value::RegexMatch(m), Value::Integer(i)) => {
-                        if i == 0 {
-                            Ok(Value::String(m.text.clone()))
-                        } else if i >= 1 && (i as usize) <= m.groups.len() {
-                            Ok(Value::String(m.groups[(i - 1) as usize].clone()))
-                        } else {
-                            Ok(Value::None)
-                        }
-                    }
-                    (Value::RegexMatch(m), Value::String(name)) => {
-                        m.named.get(&name).cloned().map(Value::String).ok_or_else(|| {
-                            format!("No named group '{}' in match", name)
-                        })
-                    }
-                    (Value::Dict(dict), Value::String(key)) => {
-                        if let Some(value) = dict.get(&key) {
-                            Ok(value.clone())
-                        } else {
-                            Err(JError::key_not_found(&key, 0, 0).to_string())
-                        }
-                    }
-                    (Value::Dict(dict), Value::Integer(i)) => {
-                        let key = i.to_string();
-                        if let Some(value) = dict.get(&key) {
-                            Ok(value.clone())
-                        } else {
-                            Err(JError::key_not_found(&key, 0, 0).to_string())
-                        }
-                    }
-                    (Value::Tuple(tuple), Value::Integer(i)) => {
-                        // CRITICAL FIX: Proper negative index handling
-                        let idx = if i < 0 {
-                            let abs_i = i.unsigned_abs() as usize;
-                            if abs_i > tuple.len() {
-                                return Err(format!(
-                                    "Tuple index {} out of bounds (length {})",
-                                    i,
-                                    tuple.len()
-                                ));
-                            }
-                            tuple.len() - abs_i
-                        } else {
-                            i as usize
-                        };
-
-                        if idx < tuple.len() {
-                            Ok(tuple[idx].clone())
-                        } else {
-                            Err(format!(
-                                "Tuple index {} out of bounds (length {})",
-                                i,
-                                tuple.len()
-                            ))
-                        }
-                    }
-                    (obj, idx) => Err(format!(
-                        "Cannot index {} with {}",
-                        match obj {
-                            Value::Integer(_) => "integer",
-                            Value::Float(_) => "float",
-                            Value::Boolean(_) => "boolean",
-                            Value::Char(_) => "char",
-                            Value::Function { .. } => "function",
-                            Value::Infinity(_) => "infinity",
-                            Value::Emoji(_) => "emoji",
-                            Value::Money(_, _) => "money",
-                            Value::Hex(_) => "hex",
-                            Value::Date(_) => "date",
-                            Value::Time(_) => "time",
-                            Value::DateTime(_) => "datetime",
-                            Value::Range(_, _, _) => "range",
-                            Value::Task(_) => "task",
-                            Value::Channel(_) => "channel",
-                            Value::None => "none",
-                            _ => "unknown",
-                        },
-                        match idx {
-                            Value::Integer(_) => "integer",
-                            Value::String(_) => "string",
-                            _ => "invalid index type",
-                        }
-                    )),
-                }
+                Self::index_value(obj_val, idx_val)
             }
 
             AstNode::Slice {
@@ -1610,7 +1687,7 @@ impl Interpreter {
                 let start_idx = if let Some(start_node) = start {
                     match self.eval_node(start_node)? {
                         Value::Integer(i) => Some(i),
-                        _ => return Err("Slice start must be an integer".to_string()),
+                        _ => return Err(JError::invalid_argument("Slice start must be an integer").into()),
                     }
                 } else {
                     None
@@ -1619,7 +1696,7 @@ impl Interpreter {
                 let end_idx = if let Some(end_node) = end {
                     match self.eval_node(end_node)? {
                         Value::Integer(i) => Some(i),
-                        _ => return Err("Slice end must be an integer".to_string()),
+                        _ => return Err(JError::invalid_argument("Slice end must be an integer").into()),
                     }
                 } else {
                     None
@@ -1629,11 +1706,11 @@ impl Interpreter {
                     match self.eval_node(step_node)? {
                         Value::Integer(i) => {
                             if i == 0 {
-                                return Err("Slice step cannot be zero".to_string());
+                                return Err(JError::invalid_argument("Slice step cannot be zero").into());
                             }
                             i
                         }
-                        _ => return Err("Slice step must be an integer".to_string()),
+                        _ => return Err(JError::invalid_argument("Slice step must be an integer").into()),
                     }
                 } else {
                     1
@@ -1717,19 +1794,11 @@ impl Interpreter {
                         }
                         Ok(Value::Vector(result))
                     }
-                    _ => Err("Cannot slice this type".to_string()),
+                    _ => Err(JError::unsupported("Cannot slice this type").into()),
                 }
             }
 
-            AstNode::If {
-                condition,
-                then_branch,
-                else_branch,
-            } => self.eval_if(condition, then_branch, else_branch),
-
-            AstNode::Match { expr, arms } => self.eval_match(expr, arms),
-
-            AstNode::Cond { value, branches } => self.eval_cond(value, branches),
+            AstNode::Cond { value, branches, span } => self.eval_cond(value, branches, span),
 
             AstNode::When { value, branches } => self.eval_when(value, branches),
 
@@ -1768,15 +1837,15 @@ impl Interpreter {
                 let list_val = self.eval_node(target)?;
                 let list = match &list_val {
                     Value::List(l) => l.clone(),
-                    _ => return Err("borrow_split target must be a list".to_string()),
+                    _ => return Err(JError::invalid_argument("borrow_split target must be a list").into()),
                 };
                 let idx_val = self.eval_node(index)?;
                 let idx = match idx_val {
                     Value::Integer(i) if i >= 0 => i as usize,
-                    _ => return Err("borrow_split index must be a non-negative integer".to_string()),
+                    _ => return Err(JError::invalid_argument("borrow_split index must be a non-negative integer").into()),
                 };
                 if idx > list.len() {
-                    return Err("borrow_split index out of bounds".to_string());
+                    return Err(JError::out_of_range("borrow_split index out of bounds").into());
                 }
                 let mut left: Vec<Value> = list[..idx].to_vec();
                 let mut right: Vec<Value> = list[idx..].to_vec();
@@ -1792,7 +1861,7 @@ impl Interpreter {
                 }
                 self.pop_scope();
                 let merged: Vec<Value> = left.into_iter().chain(right).collect();
-                if let AstNode::Identifier(name) = target.as_ref() {
+                if let AstNode::Identifier(name, _) = target.as_ref() {
                     self.set_variable(name.clone(), Value::List(merged));
                 }
                 Ok(Value::None)
@@ -1800,7 +1869,7 @@ impl Interpreter {
 
             AstNode::Switch { expr, cases } => self.eval_switch(expr, cases),
 
-            AstNode::While { condition, body } => {
+            AstNode::While { condition, body, .. } => {
                 let mut last_val = Value::None;
 
                 loop {
@@ -1811,8 +1880,8 @@ impl Interpreter {
 
                     match self.eval_node(body) {
                         Ok(val) => last_val = val,
-                        Err(e) if e == "Break statement outside of loop" => break,
-                        Err(e) if e == "Continue statement outside of loop" => continue,
+                        Err(e) if e.message == "Break statement outside of loop" => break,
+                        Err(e) if e.message == "Continue statement outside of loop" => continue,
                         Err(e) => return Err(e),
                     }
                 }
@@ -1827,12 +1896,13 @@ impl Interpreter {
                 finally_block,
             } => self.eval_try_catch(try_block, catch_var, catch_block, finally_block),
 
-            AstNode::Throw(expr) => self.eval_throw(expr),
+            AstNode::Throw(expr, _) => self.eval_throw(expr),
 
             AstNode::For {
                 var,
                 iterable,
                 body,
+                ..
             } => {
                 let iterable_val = self.eval_node(iterable)?;
                 let mut last_val = Value::None;
@@ -1843,8 +1913,8 @@ impl Interpreter {
                             self.set_variable(var.clone(), item);
                             match self.eval_node(body) {
                                 Ok(val) => last_val = val,
-                                Err(e) if e == "Break statement outside of loop" => break,
-                                Err(e) if e == "Continue statement outside of loop" => continue,
+                                Err(e) if e.message == "Break statement outside of loop" => break,
+                                Err(e) if e.message == "Continue statement outside of loop" => continue,
                                 Err(e) => return Err(e),
                             }
                         }
@@ -1854,8 +1924,8 @@ impl Interpreter {
                             self.set_variable(var.clone(), Value::Char(ch));
                             match self.eval_node(body) {
                                 Ok(val) => last_val = val,
-                                Err(e) if e == "Break statement outside of loop" => break,
-                                Err(e) if e == "Continue statement outside of loop" => continue,
+                                Err(e) if e.message == "Break statement outside of loop" => break,
+                                Err(e) if e.message == "Continue statement outside of loop" => continue,
                                 Err(e) => return Err(e),
                             }
                         }
@@ -1866,8 +1936,8 @@ impl Interpreter {
                             self.set_variable(var.clone(), Value::String(key));
                             match self.eval_node(body) {
                                 Ok(val) => last_val = val,
-                                Err(e) if e == "Break statement outside of loop" => break,
-                                Err(e) if e == "Continue statement outside of loop" => continue,
+                                Err(e) if e.message == "Break statement outside of loop" => break,
+                                Err(e) if e.message == "Continue statement outside of loop" => continue,
                                 Err(e) => return Err(e),
                             }
                         }
@@ -1877,8 +1947,8 @@ impl Interpreter {
                             self.set_variable(var.clone(), item);
                             match self.eval_node(body) {
                                 Ok(val) => last_val = val,
-                                Err(e) if e == "Break statement outside of loop" => break,
-                                Err(e) if e == "Continue statement outside of loop" => continue,
+                                Err(e) if e.message == "Break statement outside of loop" => break,
+                                Err(e) if e.message == "Continue statement outside of loop" => continue,
                                 Err(e) => return Err(e),
                             }
                         }
@@ -1888,15 +1958,14 @@ impl Interpreter {
                             self.set_variable(var.clone(), Value::Float(item));
                             match self.eval_node(body) {
                                 Ok(val) => last_val = val,
-                                Err(e) if e == "Break statement outside of loop" => break,
-                                Err(e) if e == "Continue statement outside of loop" => continue,
+                                Err(e) if e.message == "Break statement outside of loop" => break,
+                                Err(e) if e.message == "Continue statement outside of loop" => continue,
                                 Err(e) => return Err(e),
                             }
                         }
                     }
                     _ => return Err(
-                        "Can only iterate over lists, strings, dictionaries, tuples, and vectors"
-                            .to_string(),
+                        JError::unsupported("Can only iterate over lists, strings, dictionaries, tuples, and vectors").into(),
                     ),
                 }
 
@@ -1935,7 +2004,7 @@ impl Interpreter {
                     }
                     _ => {
                         return Err(
-                            "Can only reverse iterate over lists, strings, and vectors".to_string()
+                            JError::unsupported("Can only reverse iterate over lists, strings, and vectors").into()
                         )
                     }
                 }
@@ -1957,7 +2026,7 @@ impl Interpreter {
                 match (start_val, step_val) {
                     (Value::Integer(s), Value::Integer(st)) => {
                         if st == 0 {
-                            return Err("Step cannot be zero".to_string());
+                            return Err(JError::invalid_argument("Step cannot be zero").into());
                         }
 
                         let mut current = s;
@@ -1981,7 +2050,7 @@ impl Interpreter {
                             }
                         }
                     }
-                    _ => return Err("ForStep requires integer start and step values".to_string()),
+                    _ => return Err(JError::invalid_argument("ForStep requires integer start and step values").into()),
                 }
 
                 Ok(last_val)
@@ -2033,8 +2102,7 @@ impl Interpreter {
                         }
                     }
                     _ => return Err(
-                        "Can only iterate over lists, strings, dictionaries, tuples, and vectors"
-                            .to_string(),
+                        JError::unsupported("Can only iterate over lists, strings, dictionaries, tuples, and vectors").into(),
                     ),
                 }
 
@@ -2048,7 +2116,7 @@ impl Interpreter {
             } => {
                 if vars.len() != iterables.len() {
                     return Err(
-                        "Number of variables must match number of iterables in zip".to_string()
+                        JError::invalid_argument("Number of variables must match number of iterables in zip").into()
                     );
                 }
 
@@ -2074,7 +2142,7 @@ impl Interpreter {
                         Value::Tuple(tuple) => lists.push(tuple),
                         _ => {
                             return Err(
-                                "Can only zip over lists, strings, vectors, and tuples".to_string()
+                                JError::unsupported("Can only zip over lists, strings, vectors, and tuples").into()
                             )
                         }
                     }
@@ -2129,8 +2197,7 @@ impl Interpreter {
                         }
                     }
                     _ => {
-                        return Err("Can only parallel iterate over lists, strings, and vectors"
-                            .to_string())
+                        return Err(JError::unsupported("Can only parallel iterate over lists, strings, and vectors").into())
                     }
                 }
 
@@ -2151,11 +2218,11 @@ impl Interpreter {
                 let chunk_size = match chunk_size_val {
                     Value::Integer(size) => {
                         if size <= 0 {
-                            return Err("Chunk size must be positive".to_string());
+                            return Err(JError::invalid_argument("Chunk size must be positive").into());
                         }
                         size as usize
                     }
-                    _ => return Err("Chunk size must be an integer".to_string()),
+                    _ => return Err(JError::invalid_argument("Chunk size must be an integer").into()),
                 };
 
                 match iterable_val {
@@ -2175,7 +2242,7 @@ impl Interpreter {
                         }
                     }
                     _ => {
-                        return Err("ForChunked can only iterate over lists and strings".to_string())
+                        return Err(JError::unsupported("ForChunked can only iterate over lists and strings").into())
                     }
                 }
 
@@ -2215,7 +2282,7 @@ impl Interpreter {
                     }
                     _ => {
                         return Err(
-                            "ForFiltered can only iterate over lists and strings".to_string()
+                            JError::unsupported("ForFiltered can only iterate over lists and strings").into()
                         )
                     }
                 }
@@ -2236,11 +2303,11 @@ impl Interpreter {
                 let window_size = match window_size_val {
                     Value::Integer(size) => {
                         if size <= 0 {
-                            return Err("Window size must be positive".to_string());
+                            return Err(JError::invalid_argument("Window size must be positive").into());
                         }
                         size as usize
                     }
-                    _ => return Err("Window size must be an integer".to_string()),
+                    _ => return Err(JError::invalid_argument("Window size must be an integer").into()),
                 };
 
                 match iterable_val {
@@ -2268,7 +2335,7 @@ impl Interpreter {
                     }
                     _ => {
                         return Err(
-                            "ForWindowed can only iterate over lists and strings".to_string()
+                            JError::unsupported("ForWindowed can only iterate over lists and strings").into()
                         )
                     }
                 }
@@ -2313,14 +2380,14 @@ impl Interpreter {
                             left = match self.get_variable(left_var)? {
                                 Value::Integer(i) => i,
                                 _ => {
-                                    return Err("Sweep left variable must be an integer".to_string())
+                                    return Err(JError::invalid_argument("Sweep left variable must be an integer").into())
                                 }
                             };
                             right = match self.get_variable(right_var)? {
                                 Value::Integer(i) => i,
                                 _ => {
                                     return Err(
-                                        "Sweep right variable must be an integer".to_string()
+                                        JError::invalid_argument("Sweep right variable must be an integer").into()
                                     )
                                 }
                             };
@@ -2335,7 +2402,7 @@ impl Interpreter {
                             }
                         }
                     }
-                    _ => return Err("Sweep loop requires a list".to_string()),
+                    _ => return Err(JError::invalid_argument("Sweep loop requires a list").into()),
                 }
 
                 Ok(last_val)
@@ -2366,7 +2433,7 @@ impl Interpreter {
                                 Value::Integer(i) => i,
                                 _ => {
                                     return Err(
-                                        "Shrink left variable must be an integer".to_string()
+                                        JError::invalid_argument("Shrink left variable must be an integer").into()
                                     )
                                 }
                             };
@@ -2374,13 +2441,13 @@ impl Interpreter {
                                 Value::Integer(i) => i,
                                 _ => {
                                     return Err(
-                                        "Shrink right variable must be an integer".to_string()
+                                        JError::invalid_argument("Shrink right variable must be an integer").into()
                                     )
                                 }
                             };
                         }
                     }
-                    _ => return Err("Shrink loop requires a list".to_string()),
+                    _ => return Err(JError::invalid_argument("Shrink loop requires a list").into()),
                 }
 
                 Ok(last_val)
@@ -2410,18 +2477,18 @@ impl Interpreter {
                             left = match self.get_variable(left_var)? {
                                 Value::Integer(i) => i,
                                 _ => {
-                                    return Err("Meet left variable must be an integer".to_string())
+                                    return Err(JError::invalid_argument("Meet left variable must be an integer").into())
                                 }
                             };
                             right = match self.get_variable(right_var)? {
                                 Value::Integer(i) => i,
                                 _ => {
-                                    return Err("Meet right variable must be an integer".to_string())
+                                    return Err(JError::invalid_argument("Meet right variable must be an integer").into())
                                 }
                             };
                         }
                     }
-                    _ => return Err("Meet loop requires a list".to_string()),
+                    _ => return Err(JError::invalid_argument("Meet loop requires a list").into()),
                 }
 
                 Ok(last_val)
@@ -2454,13 +2521,13 @@ impl Interpreter {
                             let new_lo = match self.get_variable(lo_var)? {
                                 Value::Integer(i) => i,
                                 _ => {
-                                    return Err("Binary lo variable must be an integer".to_string())
+                                    return Err(JError::invalid_argument("Binary lo variable must be an integer").into())
                                 }
                             };
                             let new_hi = match self.get_variable(hi_var)? {
                                 Value::Integer(i) => i,
                                 _ => {
-                                    return Err("Binary hi variable must be an integer".to_string())
+                                    return Err(JError::invalid_argument("Binary hi variable must be an integer").into())
                                 }
                             };
 
@@ -2487,13 +2554,13 @@ impl Interpreter {
                             let new_lo = match self.get_variable(lo_var)? {
                                 Value::Integer(i) => i,
                                 _ => {
-                                    return Err("Binary lo variable must be an integer".to_string())
+                                    return Err(JError::invalid_argument("Binary lo variable must be an integer").into())
                                 }
                             };
                             let new_hi = match self.get_variable(hi_var)? {
                                 Value::Integer(i) => i,
                                 _ => {
-                                    return Err("Binary hi variable must be an integer".to_string())
+                                    return Err(JError::invalid_argument("Binary hi variable must be an integer").into())
                                 }
                             };
 
@@ -2506,7 +2573,7 @@ impl Interpreter {
                             hi = new_hi;
                         }
                     }
-                    _ => return Err("Binary search loop requires a list or range".to_string()),
+                    _ => return Err(JError::invalid_argument("Binary search loop requires a list or range").into()),
                 }
 
                 // Execute else block if not found
@@ -2531,11 +2598,11 @@ impl Interpreter {
                     match self.eval_node(dim)? {
                         Value::Integer(size) => {
                             if size <= 0 {
-                                return Err("DP dimension must be positive".to_string());
+                                return Err(JError::invalid_argument("DP dimension must be positive").into());
                             }
                             dim_sizes.push(size as usize);
                         }
-                        _ => return Err("DP dimensions must be integers".to_string()),
+                        _ => return Err(JError::invalid_argument("DP dimensions must be integers").into()),
                     }
                 }
 
@@ -2555,7 +2622,7 @@ impl Interpreter {
                     }
                     self.set_variable(table_name.clone(), Value::List(table));
                 } else {
-                    return Err("DP loop currently supports 1D and 2D tables".to_string());
+                    return Err(JError::unsupported("DP loop currently supports 1D and 2D tables").into());
                 }
 
                 // Execute body
@@ -2576,7 +2643,7 @@ impl Interpreter {
                                 break;
                             }
                         }
-                        _ => return Err("while_nonzero requires an integer variable".to_string()),
+                        _ => return Err(JError::invalid_argument("while_nonzero requires an integer variable").into()),
                     }
 
                     last_val = self.eval_node(body)?;
@@ -2628,6 +2695,7 @@ impl Interpreter {
                 end,
                 inclusive,
                 step,
+                ..
             } => {
                 let start_val = self.eval_node(start)?;
                 let end_val = self.eval_node(end)?;
@@ -2638,14 +2706,14 @@ impl Interpreter {
                         let step_val = if let Some(step_node) = step {
                             match self.eval_node(step_node)? {
                                 Value::Integer(step) => step,
-                                _ => return Err("Range step must be an integer".to_string()),
+                                _ => return Err(JError::invalid_argument("Range step must be an integer").into()),
                             }
                         } else {
                             1
                         };
 
                         if step_val == 0 {
-                            return Err("Range step cannot be zero".to_string());
+                            return Err(JError::invalid_argument("Range step cannot be zero").into());
                         }
 
                         let mut current = s;
@@ -2663,12 +2731,10 @@ impl Interpreter {
 
                         Ok(Value::List(range))
                     }
-                    _ => Err("Range bounds must be integers".to_string()),
+                    _ => Err(JError::invalid_argument("Range bounds must be integers").into()),
                 }
             }
 
-            AstNode::Block(statements) => self.eval_block(statements),
-
             AstNode::Defer(_) => Ok(Value::None),
 
             AstNode::ConvergeLoop { body } => {
@@ -2685,16 +2751,10 @@ impl Interpreter {
                 }
             }
 
-            AstNode::Expression(expr) => self.eval_node(expr),
-
-            AstNode::Assignment { name, value } => self.eval_assignment(name, value),
-
             AstNode::DestructuringAssignment { targets, value } => {
                 self.eval_destructuring_assignment(targets, value)
             }
 
-            AstNode::Return(expr) => self.eval_return(expr),
-
             AstNode::TryExpression(expr) => {
                 // For now, just evaluate the expression
                 // In a full implementation, this would handle Result types
@@ -2710,6 +2770,7 @@ impl Interpreter {
                 name: "<lambda>".to_string(),
                 params: params.clone(),
                 body: body.clone(),
+                env: self.capture_env(),
             }),
 
             AstNode::ListComprehension {
@@ -2770,8 +2831,7 @@ impl Interpreter {
                     }
                     _ => {
                         return Err(
-                            "List comprehension can only iterate over lists, strings, and vectors"
-                                .to_string(),
+                            JError::unsupported("List comprehension can only iterate over lists, strings, and vectors").into(),
                         )
                     }
                 }
@@ -2810,8 +2870,7 @@ impl Interpreter {
                                     Value::String(s) => s,
                                     Value::Integer(i) => i.to_string(),
                                     _ => return Err(
-                                        "Dictionary comprehension keys must be strings or integers"
-                                            .to_string(),
+                                        JError::invalid_argument("Dictionary comprehension keys must be strings or integers").into(),
                                     ),
                                 };
 
@@ -2837,8 +2896,7 @@ impl Interpreter {
                                     Value::String(s) => s,
                                     Value::Integer(i) => i.to_string(),
                                     _ => return Err(
-                                        "Dictionary comprehension keys must be strings or integers"
-                                            .to_string(),
+                                        JError::invalid_argument("Dictionary comprehension keys must be strings or integers").into(),
                                     ),
                                 };
 
@@ -2847,8 +2905,7 @@ impl Interpreter {
                     }
                     _ => {
                         return Err(
-                            "Dictionary comprehension can only iterate over lists and strings"
-                                .to_string(),
+                            JError::unsupported("Dictionary comprehension can only iterate over lists and strings").into(),
                         )
                     }
                 }
@@ -2921,16 +2978,16 @@ impl Interpreter {
                                     Value::Float(f) => vec.push(f),
                                     _ => {
                                         return Err(
-                                            "Cannot convert non-numeric list to vector".to_string()
+                                            JError::unsupported("Cannot convert non-numeric list to vector").into()
                                         )
                                     }
                                 }
                             }
                             Value::Vector(vec)
                         }
-                        _ => return Err(format!("Cannot convert {} to vector", target_type)),
+                        _ => return Err(JError::unsupported(format!("Cannot convert {} to vector", target_type)).into()),
                     },
-                    _ => return Err(format!("Unsupported type conversion to {}", target_type)),
+                    _ => return Err(JError::unsupported(format!("Unsupported type conversion to {}", target_type)).into()),
                 };
 
                 // Create new variable with converted value (shadowing)
@@ -2938,14 +2995,14 @@ impl Interpreter {
                 Ok(converted_value)
             }
 
-            AstNode::Break => {
+            AstNode::Break(_) => {
                 // Break statement - should be handled by loop constructs
-                Err("Break statement outside of loop".to_string())
+                Err(JError::unsupported("Break statement outside of loop").into())
             }
 
-            AstNode::Continue => {
+            AstNode::Continue(_) => {
                 // Continue statement - should be handled by loop constructs
-                Err("Continue statement outside of loop".to_string())
+                Err(JError::unsupported("Continue statement outside of loop").into())
             }
 
             AstNode::Underscore => {
@@ -2954,35 +3011,35 @@ impl Interpreter {
             }
 
             // Advanced J features (not yet implemented)
-            AstNode::AutoFunction { .. } => Err("AutoFunction not yet implemented".to_string()),
+            AstNode::AutoFunction { .. } => Err(JError::unsupported("AutoFunction not yet implemented").into()),
 
-            AstNode::CheatBlock { .. } => Err("CheatBlock not yet implemented".to_string()),
+            AstNode::CheatBlock { .. } => Err(JError::unsupported("CheatBlock not yet implemented").into()),
 
-            AstNode::LiveVariable { .. } => Err("LiveVariable not yet implemented".to_string()),
+            AstNode::LiveVariable { .. } => Err(JError::unsupported("LiveVariable not yet implemented").into()),
 
-            AstNode::WhyExpression { .. } => Err("WhyExpression not yet implemented".to_string()),
+            AstNode::WhyExpression { .. } => Err(JError::unsupported("WhyExpression not yet implemented").into()),
 
             AstNode::BlendExpression { .. } => {
-                Err("BlendExpression not yet implemented".to_string())
+                Err(JError::unsupported("BlendExpression not yet implemented").into())
             }
 
-            AstNode::EchoVariable { .. } => Err("EchoVariable not yet implemented".to_string()),
+            AstNode::EchoVariable { .. } => Err(JError::unsupported("EchoVariable not yet implemented").into()),
 
-            AstNode::TraceBlock { .. } => Err("TraceBlock not yet implemented".to_string()),
+            AstNode::TraceBlock { .. } => Err(JError::unsupported("TraceBlock not yet implemented").into()),
 
             AstNode::GuardExpression { .. } => {
-                Err("GuardExpression not yet implemented".to_string())
+                Err(JError::unsupported("GuardExpression not yet implemented").into())
             }
 
-            AstNode::LensView { .. } => Err("LensView not yet implemented".to_string()),
+            AstNode::LensView { .. } => Err(JError::unsupported("LensView not yet implemented").into()),
 
             // Memory management
             AstNode::ArenaAllocation { .. } => {
-                Err("ArenaAllocation not yet implemented".to_string())
+                Err(JError::unsupported("ArenaAllocation not yet implemented").into())
             }
 
             AstNode::StackAllocation { .. } => {
-                Err("StackAllocation not yet implemented".to_string())
+                Err(JError::unsupported("StackAllocation not yet implemented").into())
             }
 
             // Concurrency
@@ -3014,7 +3071,7 @@ impl Interpreter {
                         // For now, just acknowledge the send
                         Ok(Value::Boolean(true))
                     }
-                    _ => Err("ChannelSend requires a Channel value".to_string()),
+                    _ => Err(JError::invalid_argument("ChannelSend requires a Channel value").into()),
                 }
             }
 
@@ -3028,30 +3085,35 @@ impl Interpreter {
                         // Full implementation would need a channel queue
                         Ok(Value::None)
                     }
-                    _ => Err("ChannelReceive requires a Channel value".to_string()),
+                    _ => Err(JError::invalid_argument("ChannelReceive requires a Channel value").into()),
                 }
             }
 
-            AstNode::ScopeBlock { .. } => Err("ScopeBlock not yet implemented".to_string()),
+            AstNode::ScopeBlock { .. } => Err(JError::unsupported("ScopeBlock not yet implemented").into()),
 
             // Testing
-            AstNode::TestCase { .. } => Err("TestCase not yet implemented".to_string()),
+            AstNode::TestCase { name, body } => self.eval_test_case(name, body),
 
-            AstNode::PropertyTest { .. } => Err("PropertyTest not yet implemented".to_string()),
+            AstNode::PropertyTest {
+                name,
+                var_type,
+                var_name,
+                body,
+            } => self.eval_property_test(name, var_type, var_name, body),
 
-            AstNode::Assertion { .. } => Err("Assertion not yet implemented".to_string()),
+            AstNode::Assertion { condition, message } => self.eval_assertion(condition, message),
 
             // Macros
             AstNode::MacroDefinition { .. } => {
-                Err("MacroDefinition not yet implemented".to_string())
+                Err(JError::unsupported("MacroDefinition not yet implemented").into())
             }
 
-            AstNode::MacroCall { .. } => Err("MacroCall not yet implemented".to_string()),
+            AstNode::MacroCall { .. } => Err(JError::unsupported("MacroCall not yet implemented").into()),
 
             // ===== NEW ADVANCED FEATURE HANDLERS =====
 
             // Traits
-            AstNode::TraitDeclaration { name, methods } => {
+            AstNode::TraitDeclaration { name, methods, .. } => {
                 // Convert methods to TraitMethod structs
                 let trait_methods: Vec<TraitMethod> = methods.iter().filter_map(|m| {
                     if let AstNode::FunctionDeclaration { name: method_name, params, return_type, body, .. } = m {
@@ -3085,6 +3147,7 @@ impl Interpreter {
                 params,
                 return_type: _,
                 body,
+                ..
             } => {
                 // Create an async function that can be awaited
                 // For now, it executes synchronously but returns a completed Future
@@ -3093,6 +3156,7 @@ impl Interpreter {
                     name: format!("async_{}", name),
                     params: param_names,
                     body: body.clone(),
+                    env: self.capture_env(),
                 };
 
                 self.set_variable(name.clone(), func);
@@ -3113,11 +3177,11 @@ impl Interpreter {
                             }
                         }
                         FutureState::Failed(err) => {
-                            return Err(format!("Future failed: {}", err));
+                            return Err(JError::unsupported(format!("Future failed: {}", err)).into());
                         }
                         _ => {
                             return Err(
-                                "Cannot await pending future (no async runtime)".to_string()
+                                JError::unsupported("Cannot await pending future (no async runtime)").into()
                             );
                         }
                     }
@@ -3128,7 +3192,7 @@ impl Interpreter {
             }
 
             // Module System
-            AstNode::ModuleDeclaration { name, body } => {
+            AstNode::ModuleDeclaration { name, body, .. } => {
                 // Create a new scope for the module
                 self.push_scope();
                 let result = self.eval_node(body)?;
@@ -3136,7 +3200,7 @@ impl Interpreter {
                 // Collect exports from module scope
                 let mut exports = HashMap::new();
                 if let Some(scope) = self.locals.last() {
-                    exports = scope.clone();
+                    exports = scope.borrow().clone();
                 }
 
                 self.pop_scope();
@@ -3152,7 +3216,11 @@ impl Interpreter {
                 Ok(result)
             }
 
-            AstNode::ImportStatement { module_path, items } => {
+            AstNode::ImportStatement {
+                module_path,
+                items,
+                ..
+            } => {
                 let path = module_path.join("/");
                 let module = self.load_module(&path)?;
 
@@ -3168,7 +3236,7 @@ impl Interpreter {
                             if let Some(value) = exports.get(item) {
                                 self.set_variable(item.clone(), value.clone());
                             } else {
-                                return Err(format!("Module {} does not export '{}'", path, item));
+                                return Err(JError::unsupported(format!("Module {} does not export '{}'", path, item)).into());
                             }
                         }
                     }
@@ -3191,6 +3259,7 @@ impl Interpreter {
                 params,
                 return_type: _,
                 body,
+                ..
             } => {
                 // For now, treat generic functions like regular functions
                 // In a full implementation, this would support type parameters
@@ -3199,6 +3268,7 @@ impl Interpreter {
                     name: name.clone(),
                     params: param_names,
                     body: body.clone(),
+                    env: self.capture_env(),
                 };
                 self.set_variable(name.clone(), func);
                 Ok(Value::None)
@@ -3213,6 +3283,7 @@ impl Interpreter {
                 methods,
                 static_fields,
                 static_methods,
+                ..
             } => {
                 // For now, treat generic classes like regular classes
                 // In a full implementation, this would support type parameters
@@ -3241,6 +3312,7 @@ impl Interpreter {
                             name: name.clone(),
                             params: param_names,
                             body: body.clone(),
+                            env: self.capture_env(),
                         };
                         method_map.insert(name.clone(), func);
                     }
@@ -3270,6 +3342,7 @@ impl Interpreter {
                             name: name.clone(),
                             params: param_names,
                             body: body.clone(),
+                            env: self.capture_env(),
                         };
                         static_method_map.insert(name.clone(), func);
                     }
@@ -3304,6 +3377,7 @@ impl Interpreter {
                     name: name.clone(),
                     params: param_names,
                     body: body.clone(),
+                    env: self.capture_env(),
                 };
                 self.set_variable(name.clone(), func);
                 Ok(Value::None)
@@ -3364,7 +3438,7 @@ impl Interpreter {
                 }
             }
             AstNode::RollbackBlock { retries: _, body } => self.eval_node(body),
-            AstNode::RetryKeyword => Err("retry only valid inside rollback".to_string()),
+            AstNode::RetryKeyword => Err(JError::unsupported("retry only valid inside rollback").into()),
             AstNode::RaceBlock { branches } => {
                 if branches.is_empty() {
                     Ok(Value::None)
@@ -3424,7 +3498,7 @@ impl Interpreter {
                     let window_size = if let Some(size_expr) = size {
                         match self.eval_node(size_expr)? {
                             Value::Integer(s) => s as usize,
-                            _ => return Err("Window size must be an integer".to_string()),
+                            _ => return Err(JError::invalid_argument("Window size must be an integer").into()),
                         }
                     } else {
                         1 // Default: growing window
@@ -3483,7 +3557,7 @@ impl Interpreter {
                     self.pop_scope();
                     Ok(last)
                 } else {
-                    Err("window loop requires a list".to_string())
+                    Err(JError::invalid_argument("window loop requires a list").into())
                 }
             }
             AstNode::SolverBlock {
@@ -3498,7 +3572,7 @@ impl Interpreter {
                 let end_val = self.eval_node(end)?;
                 match (start_val, end_val) {
                     (Value::Integer(s), Value::Integer(e)) => Ok(Value::Interval(s, e)),
-                    _ => Err("Interval requires integer start and end".to_string()),
+                    _ => Err(JError::invalid_argument("Interval requires integer start and end").into()),
                 }
             }
 
@@ -3510,20 +3584,22 @@ impl Interpreter {
                     for item in items {
                         // Call the key function with the item
                         let key_val = match self.eval_node(key_fn)? {
-                            Value::Function { params, body, .. } => {
+                            Value::Function { name, params, body, env } => {
                                 if params.len() != 1 {
                                     return Err(
-                                        "group_by key function must take exactly 1 parameter"
-                                            .to_string(),
+                                        JError::invalid_argument("group_by key function must take exactly 1 parameter").into(),
                                     );
                                 }
-                                self.push_scope();
-                                self.set_variable(params[0].clone(), item.clone());
-                                let result = self.eval_node(&body)?;
-                                self.pop_scope();
-                                result
+                                self.call_function_internal(
+                                    &name,
+                                    std::slice::from_ref(&item),
+                                    &params,
+                                    &body,
+                                    None,
+                                    &env,
+                                )?
                             }
-                            _ => return Err("group_by requires a function as key".to_string()),
+                            _ => return Err(JError::invalid_argument("group_by requires a function as key").into()),
                         };
 
                         // Convert key to string
@@ -3538,7 +3614,7 @@ impl Interpreter {
                     }
                     Ok(Value::Dict(result))
                 } else {
-                    Err("group_by requires a list".to_string())
+                    Err(JError::invalid_argument("group_by requires a list").into())
                 }
             }
 
@@ -3554,21 +3630,23 @@ impl Interpreter {
                     for item in items {
                         // Call the predicate function with the item
                         let pred_result = match self.eval_node(predicate)? {
-                            Value::Function { params, body, .. } => {
+                            Value::Function { name, params, body, env } => {
                                 if params.len() != 1 {
                                     return Err(
-                                        "partition predicate must take exactly 1 parameter"
-                                            .to_string(),
+                                        JError::invalid_argument("partition predicate must take exactly 1 parameter").into(),
                                     );
                                 }
-                                self.push_scope();
-                                self.set_variable(params[0].clone(), item.clone());
-                                let result = self.eval_node(&body)?;
-                                self.pop_scope();
-                                result
+                                self.call_function_internal(
+                                    &name,
+                                    std::slice::from_ref(&item),
+                                    &params,
+                                    &body,
+                                    None,
+                                    &env,
+                                )?
                             }
                             _ => {
-                                return Err("partition requires a function as predicate".to_string())
+                                return Err(JError::invalid_argument("partition requires a function as predicate").into())
                             }
                         };
 
@@ -3576,7 +3654,7 @@ impl Interpreter {
                             Value::Boolean(true) => true_items.push(item),
                             Value::Boolean(false) => false_items.push(item),
                             _ => {
-                                return Err("partition predicate must return a boolean".to_string())
+                                return Err(JError::invalid_argument("partition predicate must return a boolean").into())
                             }
                         }
                     }
@@ -3587,7 +3665,7 @@ impl Interpreter {
                         Value::List(false_items),
                     ]))
                 } else {
-                    Err("partition requires a list".to_string())
+                    Err(JError::invalid_argument("partition requires a list").into())
                 }
             }
 
@@ -3606,6 +3684,7 @@ impl Interpreter {
                     name: "<generator>".to_string(),
                     params: params.clone(),
                     body: body.clone(),
+                    env: self.capture_env(),
                 })
             }
 
@@ -3695,7 +3774,7 @@ impl Interpreter {
                         let key_str = match k {
                             Value::String(s) => s,
                             Value::Integer(i) => i.to_string(),
-                            _ => return Err("Veil get requires string or integer key".to_string()),
+                            _ => return Err(JError::invalid_argument("Veil get requires string or integer key").into()),
                         };
                         // In constant-time implementation, would scan entire dict
                         Ok(dict.get(&key_str).cloned().unwrap_or(Value::None))
@@ -3710,10 +3789,10 @@ impl Interpreter {
                             // In constant-time implementation, would scan entire list
                             Ok(list.get(index).cloned().unwrap_or(Value::None))
                         } else {
-                            Err("Veil get on list requires integer index".to_string())
+                            Err(JError::invalid_argument("Veil get on list requires integer index").into())
                         }
                     }
-                    _ => Err("Veil get requires dict or list".to_string()),
+                    _ => Err(JError::invalid_argument("Veil get requires dict or list").into()),
                 }
             }
 
@@ -3728,7 +3807,7 @@ impl Interpreter {
                         let key_str = match k {
                             Value::String(s) => s,
                             Value::Integer(i) => i.to_string(),
-                            _ => return Err("Veil set requires string or integer key".to_string()),
+                            _ => return Err(JError::invalid_argument("Veil set requires string or integer key").into()),
                         };
                         dict.insert(key_str, v);
                         Ok(Value::Dict(dict))
@@ -3745,7 +3824,7 @@ impl Interpreter {
                             }
                             Ok(Value::List(list))
                         } else {
-                            Err("Veil set on list requires integer index".to_string())
+                            Err(JError::invalid_argument("Veil set on list requires integer index").into())
                         }
                     }
                     Value::SmallVec { cap, mut elements } => {
@@ -3760,10 +3839,10 @@ impl Interpreter {
                             }
                             Ok(Value::SmallVec { cap, elements })
                         } else {
-                            Err("Veil set on smallvec requires integer index".to_string())
+                            Err(JError::invalid_argument("Veil set on smallvec requires integer index").into())
                         }
                     }
-                    _ => Err("Veil set requires dict or list".to_string()),
+                    _ => Err(JError::invalid_argument("Veil set requires dict or list").into()),
                 }
             }
 
@@ -3774,6 +3853,7 @@ impl Interpreter {
                     name: name.clone(),
                     params: params.iter().map(|(_, n)| n.clone()).collect(),
                     body: body.clone(),
+                    env: self.capture_env(),
                 })
             }
 
@@ -3795,3 +3875,17 @@ impl Interpreter {
         }
     }
 }
+
+/// `e.trace` entry: a dict with `function`, `file` (none for the program being run), `line` and
+/// `column`.
+fn trace_entry_value(entry: &crate::error::TraceEntry) -> Value {
+    let mut dict = HashMap::new();
+    dict.insert("function".to_string(), Value::String(entry.function.clone()));
+    dict.insert(
+        "file".to_string(),
+        entry.file.clone().map_or(Value::None, Value::String),
+    );
+    dict.insert("line".to_string(), Value::Integer(entry.line as i64));
+    dict.insert("column".to_string(), Value::Integer(entry.column as i64));
+    Value::Dict(dict)
+}
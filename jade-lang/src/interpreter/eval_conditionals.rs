@@ -1,6 +1,6 @@
 //! Evaluation of conditional AST nodes: if, match, cond, when, unless, either, guard, switch.
 
-use crate::parser::{AstNode, CondBranch, MatchArm, SwitchCase};
+use crate::parser::{AstNode, CondBranch, MatchArm, Span, SwitchCase};
 use super::*;
 
 impl Interpreter {
@@ -9,9 +9,14 @@ impl Interpreter {
         condition: &AstNode,
         then_branch: &AstNode,
         else_branch: &Option<Box<AstNode>>,
-    ) -> Result<Value, String> {
+        span: &Span,
+    ) -> JResult<Value> {
         let cond_val = self.eval_node(condition)?;
-        if self.is_truthy(&cond_val) {
+        let taken = self.is_truthy(&cond_val);
+        if self.coverage.is_some() {
+            self.cover_branch(span, usize::from(!taken), 2);
+        }
+        if taken {
             self.eval_node(then_branch)
         } else if let Some(else_node) = else_branch {
             self.eval_node(else_node)
@@ -20,9 +25,14 @@ impl Interpreter {
         }
     }
 
-    pub(super) fn eval_match(&mut self, expr: &AstNode, arms: &[MatchArm]) -> Result<Value, String> {
+    pub(super) fn eval_match(
+        &mut self,
+        expr: &AstNode,
+        arms: &[MatchArm],
+        span: &Span,
+    ) -> JResult<Value> {
         let expr_val = self.eval_node(expr)?;
-        for arm in arms {
+        for (index, arm) in arms.iter().enumerate() {
             if self.pattern_matches(&arm.pattern, &expr_val)? {
                 if let Some(guard) = &arm.guard {
                     let guard_val = self.eval_node(guard)?;
@@ -30,28 +40,40 @@ impl Interpreter {
                         continue;
                     }
                 }
+                if self.coverage.is_some() {
+                    self.cover_branch(span, index, arms.len());
+                }
                 return self.eval_node(&arm.body);
             }
         }
-        Err("No matching pattern found".to_string())
+        Err(JError::unsupported("No matching pattern found").into())
     }
 
     pub(super) fn eval_cond(
         &mut self,
         value: &AstNode,
         branches: &[CondBranch],
-    ) -> Result<Value, String> {
+        span: &Span,
+    ) -> JResult<Value> {
         let cond_value = self.eval_node(value)?;
         self.set_variable("_".to_string(), cond_value.clone());
-        for branch in branches {
-            if branch.is_else {
-                return self.eval_node(&branch.body);
-            }
-            let condition_result = self.eval_node(&branch.condition)?;
-            if self.is_truthy(&condition_result) {
+        // Falling through counts as one more branch when there is no `|> else`
+        let count = branches.len() + usize::from(!branches.iter().any(|b| b.is_else));
+        for (index, branch) in branches.iter().enumerate() {
+            let taken = branch.is_else || {
+                let condition_result = self.eval_node(&branch.condition)?;
+                self.is_truthy(&condition_result)
+            };
+            if taken {
+                if self.coverage.is_some() {
+                    self.cover_branch(span, index, count);
+                }
                 return self.eval_node(&branch.body);
             }
         }
+        if self.coverage.is_some() {
+            self.cover_branch(span, branches.len(), count);
+        }
         Ok(Value::None)
     }
 
@@ -59,7 +81,7 @@ impl Interpreter {
         &mut self,
         value: &AstNode,
         branches: &[CondBranch],
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         let cond_value = self.eval_node(value)?;
         self.set_variable("_".to_string(), cond_value.clone());
         for branch in branches {
@@ -79,7 +101,7 @@ impl Interpreter {
         condition: &AstNode,
         then_branch: &AstNode,
         else_branch: &Option<Box<AstNode>>,
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         let cond_val = self.eval_node(condition)?;
         if self.is_truthy(&cond_val) {
             self.eval_node(then_branch)
@@ -95,7 +117,7 @@ impl Interpreter {
         expr: &AstNode,
         true_body: &AstNode,
         false_body: &AstNode,
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         let val = self.eval_node(expr)?;
         if self.is_truthy(&val) {
             self.eval_node(true_body)
@@ -108,7 +130,7 @@ impl Interpreter {
         &mut self,
         condition: &AstNode,
         return_value: &AstNode,
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         let cond_val = self.eval_node(condition)?;
         if !self.is_truthy(&cond_val) {
             return self.eval_node(return_value);
@@ -120,7 +142,7 @@ impl Interpreter {
         &mut self,
         expr: &AstNode,
         cases: &[(SwitchCase, AstNode)],
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         let val = self.eval_node(expr)?;
         for (case, body) in cases {
             let matches = match case {
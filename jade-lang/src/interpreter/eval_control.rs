@@ -4,13 +4,14 @@ use crate::parser::AstNode;
 use super::*;
 
 impl Interpreter {
-    pub(super) fn eval_block(&mut self, statements: &[AstNode]) -> Result<Value, String> {
+    pub(super) fn eval_block(&mut self, statements: &[AstNode]) -> JResult<Value> {
         self.push_scope();
         self.defer_stack.push(Vec::new());
 
         let result = (|| {
             let mut last_val = Value::None;
             for stmt in statements {
+                self.before_statement(stmt)?;
                 if let AstNode::Defer(expr) = stmt {
                     if let Some(defer_vec) = self.defer_stack.last_mut() {
                         defer_vec.push((*expr.clone(), None));
@@ -34,7 +35,7 @@ impl Interpreter {
         result
     }
 
-    pub(super) fn eval_assignment(&mut self, name: &str, value: &AstNode) -> Result<Value, String> {
+    pub(super) fn eval_assignment(&mut self, name: &str, value: &AstNode) -> JResult<Value> {
         let val = self.eval_node(value)?;
         self.assign_variable(name, val.clone())?;
         Ok(val)
@@ -44,16 +45,16 @@ impl Interpreter {
         &mut self,
         targets: &[String],
         value: &AstNode,
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         let val = self.eval_node(value)?;
         match val {
             Value::Tuple(tuple) => {
                 if targets.len() != tuple.len() {
-                    return Err(format!(
+                    return Err(JError::unsupported(format!(
                         "Cannot destructure tuple of length {} into {} variables",
                         tuple.len(),
                         targets.len()
-                    ));
+                    )).into());
                 }
                 for (target, value) in targets.iter().zip(tuple.iter()) {
                     self.set_variable(target.clone(), value.clone());
@@ -62,11 +63,11 @@ impl Interpreter {
             }
             Value::List(list) => {
                 if targets.len() > list.len() {
-                    return Err(format!(
+                    return Err(JError::unsupported(format!(
                         "Cannot destructure list of length {} into {} variables",
                         list.len(),
                         targets.len()
-                    ));
+                    )).into());
                 }
                 for (i, target) in targets.iter().enumerate() {
                     if i < list.len() {
@@ -77,11 +78,11 @@ impl Interpreter {
                 }
                 Ok(Value::List(list))
             }
-            _ => Err("Can only destructure tuples and lists".to_string()),
+            _ => Err(JError::unsupported("Can only destructure tuples and lists").into()),
         }
     }
 
-    pub(super) fn eval_return(&mut self, expr: &Option<Box<AstNode>>) -> Result<Value, String> {
+    pub(super) fn eval_return(&mut self, expr: &Option<Box<AstNode>>) -> JResult<Value> {
         if let Some(expr) = expr {
             self.eval_node(expr)
         } else {
@@ -95,13 +96,13 @@ impl Interpreter {
         catch_var: &Option<String>,
         catch_block: &AstNode,
         finally_block: &Option<Box<AstNode>>,
-    ) -> Result<Value, String> {
+    ) -> JResult<Value> {
         let result = self.eval_node(try_block);
 
         let final_result = match result {
-            Err(error_msg) => {
+            Err(error) => {
                 if let Some(var_name) = catch_var {
-                    self.set_variable(var_name.clone(), Value::String(error_msg));
+                    self.set_variable(var_name.clone(), Value::Error(error));
                 }
                 self.eval_node(catch_block)?
             }
@@ -115,12 +116,14 @@ impl Interpreter {
         Ok(final_result)
     }
 
-    pub(super) fn eval_throw(&mut self, expr: &AstNode) -> Result<Value, String> {
+    pub(super) fn eval_throw(&mut self, expr: &AstNode) -> JResult<Value> {
         let error_val = self.eval_node(expr)?;
-        let error_msg = match error_val {
+        let message = match error_val {
+            // Rethrowing a caught error keeps its kind and location.
+            Value::Error(error) => return Err(error),
             Value::String(s) => s,
             other => format!("{}", other),
         };
-        Err(error_msg)
+        Err(Box::new(JError::new(ErrorKind::Thrown, message)))
     }
 }
@@ -6,7 +6,7 @@ use super::*;
 
 impl Interpreter {
     /// Main `out(...)` builtin: printing, tables, progress bar, animation, gradient, formatting.
-    pub(super) fn call_out(&mut self, args: &[AstNode]) -> Result<Value, String> {
+    pub(super) fn call_out(&mut self, args: &[AstNode]) -> JResult<Value> {
         if args.is_empty() {
             self.write_out_ln("");
             return Ok(Value::None);
@@ -113,7 +113,7 @@ impl Interpreter {
         &self,
         format_str: &str,
         vars: &HashMap<String, Value>,
-    ) -> Result<String, String> {
+    ) -> JResult<String> {
         let mut result = String::new();
         let mut i = 0;
         let chars: Vec<char> = format_str.chars().collect();
@@ -178,13 +178,13 @@ impl Interpreter {
         Ok(result)
     }
 
-    pub(super) fn print_table(&mut self, rows: &[Value]) -> Result<(), String> {
+    pub(super) fn print_table(&mut self, rows: &[Value]) -> JResult<()> {
         let mut table: Vec<Vec<String>> = Vec::new();
         for row in rows {
             if let Value::List(cells) = row {
                 table.push(cells.iter().map(|c| c.to_string()).collect());
             } else {
-                return Err("Table rows must be lists".to_string());
+                return Err(JError::invalid_argument("Table rows must be lists").into());
             }
         }
         if table.is_empty() {
@@ -228,13 +228,13 @@ impl Interpreter {
         &mut self,
         rows: &[Value],
         options: &HashMap<String, Value>,
-    ) -> Result<(), String> {
+    ) -> JResult<()> {
         let mut table: Vec<Vec<String>> = Vec::new();
         for row in rows {
             if let Value::List(cells) = row {
                 table.push(cells.iter().map(|c| c.to_string()).collect());
             } else {
-                return Err("Table rows must be lists".to_string());
+                return Err(JError::invalid_argument("Table rows must be lists").into());
             }
         }
         if table.is_empty() {
@@ -302,7 +302,7 @@ impl Interpreter {
         Ok(())
     }
 
-    pub(super) fn print_progress_bar(&mut self, percent: f64, width: usize, color: &str) -> Result<(), String> {
+    pub(super) fn print_progress_bar(&mut self, percent: f64, width: usize, color: &str) -> JResult<()> {
         let clamped_percent = percent.clamp(0.0, 100.0);
         let filled = ((clamped_percent / 100.0) * width as f64) as usize;
         let empty = width.saturating_sub(filled);
@@ -346,12 +346,12 @@ impl Interpreter {
         anim_type: &str,
         interval: f64,
         count: Option<usize>,
-    ) -> Result<(), String> {
+    ) -> JResult<()> {
         let frames: Vec<&str> = match anim_type {
             "spinner" => vec!["|", "/", "-", "\\"],
             "dots" => vec!["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
-            "bar" | "bounce" | "marquee" | "pulse" => return Err(format!("Animation type '{}' not fully implemented", anim_type)),
-            _ => return Err(format!("Unknown animation type: {}", anim_type)),
+            "bar" | "bounce" | "marquee" | "pulse" => return Err(JError::unsupported(format!("Animation type '{}' not fully implemented", anim_type)).into()),
+            _ => return Err(JError::unsupported(format!("Unknown animation type: {}", anim_type)).into()),
         };
         let max_iterations = count.unwrap_or(10);
         let mut iteration = 0;
@@ -370,9 +370,9 @@ impl Interpreter {
         Ok(())
     }
 
-    pub(super) fn print_gradient(&mut self, text: &str, gradient_colors: &[Value]) -> Result<(), String> {
+    pub(super) fn print_gradient(&mut self, text: &str, gradient_colors: &[Value]) -> JResult<()> {
         if gradient_colors.len() < 2 {
-            return Err("Gradient requires at least 2 colors".to_string());
+            return Err(JError::invalid_argument("Gradient requires at least 2 colors").into());
         }
         let mut colors: Vec<(u8, u8, u8)> = Vec::new();
         for color_val in gradient_colors {
@@ -390,7 +390,7 @@ impl Interpreter {
             }
         }
         if colors.len() < 2 {
-            return Err("Gradient colors must be hex strings".to_string());
+            return Err(JError::invalid_argument("Gradient colors must be hex strings").into());
         }
         let chars: Vec<char> = text.chars().collect();
         for (i, ch) in chars.iter().enumerate() {
@@ -5,7 +5,7 @@ use crate::parser::AstNode;
 use super::*;
 
 /// Evaluate primitive literals and identifier lookup.
-pub(super) fn eval_primitive(interpreter: &mut Interpreter, node: &AstNode) -> Result<Value, String> {
+pub(super) fn eval_primitive(interpreter: &mut Interpreter, node: &AstNode) -> JResult<Value> {
     match node {
             AstNode::Integer(i) => Ok(Value::Integer(*i)),
             AstNode::Float(f) => Ok(Value::Float(*f)),
@@ -52,7 +52,7 @@ pub(super) fn eval_primitive(interpreter: &mut Interpreter, node: &AstNode) -> R
                     match val {
                         Value::Integer(i) => vector.push(i as f64),
                         Value::Float(f) => vector.push(f),
-                        _ => return Err("Vector elements must be numeric".to_string()),
+                        _ => return Err(JError::invalid_argument("Vector elements must be numeric").into()),
                     }
                 }
                 Ok(Value::Vector(vector))
@@ -67,7 +67,7 @@ pub(super) fn eval_primitive(interpreter: &mut Interpreter, node: &AstNode) -> R
                         match val {
                             Value::Integer(i) => matrix_row.push(i as f64),
                             Value::Float(f) => matrix_row.push(f),
-                            _ => return Err("Matrix elements must be numeric".to_string()),
+                            _ => return Err(JError::invalid_argument("Matrix elements must be numeric").into()),
                         }
                     }
                     matrix.push(matrix_row);
@@ -82,7 +82,7 @@ pub(super) fn eval_primitive(interpreter: &mut Interpreter, node: &AstNode) -> R
                     let key = match interpreter.eval_node(key_node)? {
                         Value::String(s) => s,
                         Value::Integer(i) => i.to_string(),
-                        _ => return Err("Dictionary keys must be strings or integers".to_string()),
+                        _ => return Err(JError::invalid_argument("Dictionary keys must be strings or integers").into()),
                     };
                     let value = interpreter.eval_node(value_node)?;
                     dict.insert(key, value);
@@ -90,8 +90,8 @@ pub(super) fn eval_primitive(interpreter: &mut Interpreter, node: &AstNode) -> R
                 Ok(Value::Dict(dict))
             }
 
-            AstNode::Identifier(name) => interpreter.get_variable(name),
+            AstNode::Identifier(name, _) => interpreter.get_variable(name),
 
-            _ => Err("eval_primitive: expected primitive or identifier".to_string()),
+            _ => Err(JError::unsupported("eval_primitive: expected primitive or identifier").into()),
         }
 }
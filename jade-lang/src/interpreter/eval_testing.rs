@@ -0,0 +1,231 @@
+//! Evaluation of testing AST nodes: `test` and `property` blocks and `assert` statements.
+//!
+//! Test blocks are collected while a file runs instead of being executed; the
+//! `jade test` runner (see [`crate::testing`]) runs them afterwards, each in a fresh scope.
+//! A property body runs once per generated input; on failure the input is shrunk.
+
+use super::generators::{self, Rng};
+use super::*;
+
+/// Upper bound on re-runs spent shrinking one failing input.
+const MAX_SHRINK_RUNS: usize = 1000;
+
+/// A `test "name" { ... }` or `property "name" (type | var) { ... }` block collected while running a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestDefinition {
+    pub name: String,
+    pub body: AstNode,
+    /// `(type, variable)` for property tests; `None` for plain tests.
+    pub property_input: Option<(String, String)>,
+}
+
+impl Interpreter {
+    pub(super) fn eval_test_case(&mut self, name: &str, body: &AstNode) -> JResult<Value> {
+        self.tests.push(TestDefinition {
+            name: name.to_string(),
+            body: body.clone(),
+            property_input: None,
+        });
+        Ok(Value::None)
+    }
+
+    pub(super) fn eval_property_test(
+        &mut self,
+        name: &str,
+        var_type: &str,
+        var_name: &str,
+        body: &AstNode,
+    ) -> JResult<Value> {
+        if !generators::GENERATOR_TYPES.contains(&var_type) {
+            return Err(JError::unsupported(format!(
+                "property '{}': no generator for type '{}' (supported: {})",
+                name,
+                var_type,
+                generators::GENERATOR_TYPES.join(", ")
+            )).into());
+        }
+        self.tests.push(TestDefinition {
+            name: name.to_string(),
+            body: body.clone(),
+            property_input: Some((var_type.to_string(), var_name.to_string())),
+        });
+        Ok(Value::None)
+    }
+
+    pub(super) fn eval_assertion(
+        &mut self,
+        condition: &AstNode,
+        message: &Option<String>,
+    ) -> JResult<Value> {
+        // For comparisons, evaluate each side once so a failure can show both values.
+        let (passed, detail) = match condition {
+            AstNode::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } if matches!(
+                operator,
+                BinaryOp::Equal
+                    | BinaryOp::NotEqual
+                    | BinaryOp::Less
+                    | BinaryOp::Greater
+                    | BinaryOp::LessEqual
+                    | BinaryOp::GreaterEqual
+            ) =>
+            {
+                let left_val = self.eval_node(left)?;
+                let right_val = self.eval_node(right)?;
+                let result = self.eval_binary_op(&left_val, operator, &right_val)?;
+                let detail = format!("left: {}, right: {}", left_val, right_val);
+                (result, Some(detail))
+            }
+            _ => (self.eval_node(condition)?, None),
+        };
+
+        match passed {
+            Value::Boolean(true) => Ok(Value::None),
+            Value::Boolean(false) => {
+                let mut msg = match message {
+                    Some(m) => format!("Assertion failed: {}", m),
+                    None => "Assertion failed".to_string(),
+                };
+                if let Some(detail) = detail {
+                    msg.push_str(&format!(" ({})", detail));
+                }
+                Err(JError::assertion_failed(msg).into())
+            }
+            other => Err(JError::invalid_argument(format!(
+                "assert expects a boolean condition, got {}",
+                Self::value_type_name(&other)
+            )).into()),
+        }
+    }
+
+    /// Run a file's top-level statements directly in the global scope (unlike [`Interpreter::run`],
+    /// which wraps the program in a block scope) so its functions and globals stay visible to tests.
+    pub fn load_for_tests(&mut self, source: &str) -> JResult<()> {
+        let program = parse_source(source)?;
+        if let Some(coverage) = self.coverage.as_mut() {
+            coverage.register(&program);
+        }
+        let statements = match program {
+            AstNode::Block(statements) => statements,
+            other => vec![other],
+        };
+        for stmt in &statements {
+            self.before_statement(stmt)?;
+            self.eval_node(stmt)?;
+        }
+        Ok(())
+    }
+
+    /// Tests collected so far by `test` blocks, in source order.
+    pub fn collected_tests(&self) -> &[TestDefinition] {
+        &self.tests
+    }
+
+    /// Use a fixed seed for every property test (replays a reported failure).
+    pub fn set_property_seed(&mut self, seed: Option<u64>) {
+        self.property_seed = seed;
+    }
+
+    /// Number of generated inputs per property test.
+    pub fn set_property_cases(&mut self, cases: usize) {
+        self.property_cases = cases.max(1);
+    }
+
+    /// Run one collected test in a fresh scope. `Err` carries the failure message.
+    pub fn run_test(&mut self, test: &TestDefinition) -> JResult<()> {
+        match test.property_input {
+            Some((ref var_type, ref var_name)) => {
+                self.run_property(var_type, var_name, &test.body)
+            }
+            None => self.run_test_body(&test.body, None),
+        }
+    }
+
+    fn run_test_body(&mut self, body: &AstNode, input: Option<(&str, Value)>) -> JResult<()> {
+        // Calls left queued by an earlier failing test must not run inside this one
+        self.timer_queue.clear();
+        self.push_scope();
+        if let Some((var_name, value)) = input {
+            self.set_variable(var_name.to_string(), value);
+        }
+        let result = self.eval_node(body).and_then(|_| self.drain_timers());
+        self.pop_scope();
+        result.map(|_| ())
+    }
+
+    fn run_property(&mut self, var_type: &str, var_name: &str, body: &AstNode) -> JResult<()> {
+        let seed = self.property_seed.unwrap_or_else(generators::time_seed);
+        let mut gen = Rng::new(seed);
+        // Builtins like rand() inside the body replay with the same seed too.
+        self.rng = Rng::new(seed);
+        let cases = self.property_cases;
+
+        for case in 0..cases {
+            // Grow inputs over the run: early cases are small, later ones up to size 100.
+            let size = 1 + case * 100 / cases;
+            let input = generators::generate(var_type, &mut gen, size).map_err(JError::unsupported)?;
+            let Err(first_error) = self.run_test_body(body, Some((var_name, input.clone()))) else {
+                continue;
+            };
+
+            let (shrunk, error, steps) = self.shrink_failure(var_name, body, input.clone(), first_error);
+            let mut msg = format!(
+                "Property falsified after {} case(s) (seed {}; replay with `jade test --seed {}`)\n",
+                case + 1,
+                seed,
+                seed
+            );
+            msg.push_str(&format!("{} = {}", var_name, show_input(&shrunk)));
+            if steps > 0 {
+                msg.push_str(&format!(
+                    " (shrunk from {} in {} step(s))",
+                    show_input(&input),
+                    steps
+                ));
+            }
+            msg.push('\n');
+            msg.push_str(error.message.trim());
+            return Err(JError::assertion_failed(msg).into());
+        }
+        Ok(())
+    }
+
+    /// Greedily replace the failing input with the first simpler candidate that still fails.
+    fn shrink_failure(
+        &mut self,
+        var_name: &str,
+        body: &AstNode,
+        input: Value,
+        error: Box<JError>,
+    ) -> (Value, Box<JError>, usize) {
+        let (mut current, mut error, mut steps, mut runs) = (input, error, 0, 0);
+        'outer: loop {
+            for candidate in generators::shrink(&current) {
+                if runs >= MAX_SHRINK_RUNS {
+                    break 'outer;
+                }
+                runs += 1;
+                if let Err(e) = self.run_test_body(body, Some((var_name, candidate.clone()))) {
+                    current = candidate;
+                    error = e;
+                    steps += 1;
+                    continue 'outer;
+                }
+            }
+            break;
+        }
+        (current, error, steps)
+    }
+}
+
+/// Render a generated input so strings stay distinguishable (`""` vs nothing).
+fn show_input(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("{:?}", s),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,216 @@
+//! Seedable random source and value generators for `property` tests.
+//!
+//! The same [`Rng`] backs the `rand*` builtins, so `rand_seed(n)` makes both
+//! scripts and property runs reproducible.
+
+use super::Value;
+use std::collections::HashMap;
+
+/// SplitMix64: small, fast, and deterministic for a given seed.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Seed from the clock (default when no seed is given).
+    pub fn from_time() -> Self {
+        Self::new(time_seed())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform integer in `lo..=hi` (requires `lo <= hi`).
+    pub fn range_i64(&mut self, lo: i64, hi: i64) -> i64 {
+        let span = (hi as i128 - lo as i128 + 1) as u128;
+        (lo as i128 + (self.next_u64() as u128 % span) as i128) as i64
+    }
+
+    /// Uniform index in `0..n` (requires `n > 0`).
+    pub fn index(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+pub fn time_seed() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// Input types a `property` test can draw from.
+pub const GENERATOR_TYPES: &[&str] = &["int", "float", "str", "bool", "list", "dict"];
+
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 _-.,!?";
+
+/// Generate a value of `type_name`. `size` grows over the run so early cases are small.
+pub fn generate(type_name: &str, rng: &mut Rng, size: usize) -> Result<Value, String> {
+    let size = size.max(1);
+    let v = match type_name {
+        "int" => Value::Integer(gen_int(rng, size)),
+        "float" => {
+            // Mix in exact zero now and then; it is a common edge case.
+            if rng.index(10) == 0 {
+                Value::Float(0.0)
+            } else {
+                let bound = (size * 10) as f64;
+                Value::Float((rng.next_f64() * 2.0 - 1.0) * bound)
+            }
+        }
+        "bool" => Value::Boolean(rng.next_u64() & 1 == 1),
+        "str" => {
+            let len = rng.index(size + 1);
+            let s = (0..len)
+                .map(|_| ALPHABET[rng.index(ALPHABET.len())] as char)
+                .collect();
+            Value::String(s)
+        }
+        "list" => {
+            let len = rng.index(size + 1);
+            Value::List((0..len).map(|_| Value::Integer(gen_int(rng, size))).collect())
+        }
+        "dict" => {
+            let len = rng.index(size + 1);
+            let mut map = HashMap::new();
+            for _ in 0..len {
+                let key_len = 1 + rng.index(4);
+                let key: String = (0..key_len)
+                    .map(|_| (b'a' + rng.index(26) as u8) as char)
+                    .collect();
+                map.insert(key, Value::Integer(gen_int(rng, size)));
+            }
+            Value::Dict(map)
+        }
+        other => {
+            return Err(format!(
+                "No generator for type '{}' (supported: {})",
+                other,
+                GENERATOR_TYPES.join(", ")
+            ))
+        }
+    };
+    Ok(v)
+}
+
+fn gen_int(rng: &mut Rng, size: usize) -> i64 {
+    let bound = (size * 10) as i64;
+    match rng.index(10) {
+        0 => 0,
+        1 => [1, -1][rng.index(2)],
+        _ => rng.range_i64(-bound, bound),
+    }
+}
+
+/// Candidate simplifications of `value`, simplest first. Empty when nothing is smaller.
+pub fn shrink(value: &Value) -> Vec<Value> {
+    match value {
+        Value::Integer(n) => shrink_int(*n).into_iter().map(Value::Integer).collect(),
+        Value::Float(f) => {
+            let mut out = Vec::new();
+            if *f != 0.0 {
+                out.push(Value::Float(0.0));
+                if f.trunc() != *f {
+                    out.push(Value::Float(f.trunc()));
+                }
+                if f.abs() > 1.0 {
+                    out.push(Value::Float((f / 2.0).trunc()));
+                }
+            }
+            out
+        }
+        Value::Boolean(true) => vec![Value::Boolean(false)],
+        Value::String(s) => {
+            let chars: Vec<char> = s.chars().collect();
+            shrink_seq(&chars, |c| if *c != 'a' { vec!['a'] } else { Vec::new() })
+                .into_iter()
+                .map(|cs| Value::String(cs.into_iter().collect()))
+                .collect()
+        }
+        Value::List(items) => shrink_seq(items, shrink).into_iter().map(Value::List).collect(),
+        Value::Dict(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let mut out = Vec::new();
+            if !map.is_empty() {
+                out.push(Value::Dict(HashMap::new()));
+            }
+            for key in &keys {
+                let mut smaller = map.clone();
+                smaller.remove(*key);
+                out.push(Value::Dict(smaller));
+            }
+            for key in &keys {
+                for v in shrink(&map[*key]) {
+                    let mut smaller = map.clone();
+                    smaller.insert((*key).clone(), v);
+                    out.push(Value::Dict(smaller));
+                }
+            }
+            out
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn shrink_int(n: i64) -> Vec<i64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut out = vec![0];
+    if n < 0 && n != i64::MIN {
+        out.push(-n);
+    }
+    let half = n / 2;
+    if half != 0 {
+        out.push(half);
+    }
+    let step = n - n.signum();
+    if step != 0 && step != half {
+        out.push(step);
+    }
+    out
+}
+
+/// Shrink a sequence: empty, halves, drop one element, then shrink one element.
+fn shrink_seq<T: Clone>(items: &[T], shrink_item: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>> {
+    let mut out = Vec::new();
+    if items.is_empty() {
+        return out;
+    }
+    out.push(Vec::new());
+    if items.len() > 1 {
+        let mid = items.len() / 2;
+        out.push(items[..mid].to_vec());
+        out.push(items[mid..].to_vec());
+    }
+    for i in 0..items.len() {
+        let mut smaller = items.to_vec();
+        smaller.remove(i);
+        out.push(smaller);
+    }
+    for (i, item) in items.iter().enumerate() {
+        for simpler in shrink_item(item) {
+            let mut smaller = items.to_vec();
+            smaller[i] = simpler;
+            out.push(smaller);
+        }
+    }
+    out
+}
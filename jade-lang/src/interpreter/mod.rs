@@ -3,10 +3,13 @@
 //! Evaluates the AST produced by the parser in a single pass, with a global
 //! environment and local scopes for functions and blocks.
 
-use crate::error::JError;
-use crate::parser::{AstNode, BinaryOp, Pattern, UnaryOp};
+use crate::error::{ErrorKind, JError, JResult, TraceEntry};
+use crate::parser::{AstNode, BinaryOp, Pattern, Span, UnaryOp};
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::io::Write;
+use std::rc::Rc;
+use std::time::Duration;
 
 mod value;
 mod eval;
@@ -14,22 +17,46 @@ mod eval_conditionals;
 mod eval_control;
 mod eval_primitive;
 mod eval_out;
+mod eval_testing;
+mod decorators;
+mod clock;
+mod generators;
 mod call;
 mod builtins;
+mod debug;
+mod coverage;
+mod profiler;
 pub(crate) mod crypto;
+pub use builtins::{BUILTIN_METHODS, BUILTIN_NAMES};
 pub use value::*;
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use eval_testing::TestDefinition;
+pub use coverage::{Coverage, FileCoverage};
+pub use profiler::{FunctionProfile, LineProfile, Profiler};
+pub use debug::{
+    statement_lines, Breakpoints, Debugger, Frontend, Interrupt, Paused, Resume, StackFrame,
+    StopReason,
+};
+
+/// Default limit on nested function calls (`jade --max-depth` / [`Interpreter::set_max_call_depth`]).
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 10_000;
 
 pub struct Interpreter {
     globals: HashMap<String, Value>,
-    locals: Vec<HashMap<String, Value>>,
+    /// Names of the globals every interpreter starts with (recorded after `add_builtins`).
+    builtin_globals: HashSet<String>,
+    locals: Vec<Scope>, // innermost last; swapped for the callee's captured env on each call
     statics: HashMap<String, Value>, // Static variables
     call_depth: usize,               // Track recursion depth
+    max_call_depth: usize,           // Limit checked in call_function_internal
     defer_stack: Vec<Vec<(AstNode, Option<Value>)>>, // (expr, value for _) when block exits (LIFO)
     once_cache: HashMap<usize, Value>, // @once decorator cached results
     once_next_id: usize,
     // Module system
     module_cache: HashMap<String, Value>,
     module_search_paths: Vec<String>,
+    /// Modules packed into a `jade build --bundle` executable: module path -> (file path, source).
+    bundled_modules: HashMap<String, (String, String)>,
     // Trait system
     #[allow(dead_code)]
     trait_impls: HashMap<String, HashMap<String, Value>>, // type_name -> trait_name -> impl
@@ -40,28 +67,101 @@ pub struct Interpreter {
     resource_stack: Vec<(String, Value)>,       // (class_name, instance) for RAII cleanup
     /// When set, out() writes here instead of stdout (e.g. when capturing output).
     pub(crate) output_buffer: Option<Vec<u8>>,
-    /// Memoization caches: id -> (args_key -> result).
-    pub(crate) memo_caches: std::collections::HashMap<usize, std::collections::HashMap<String, Value>>,
+    /// Memoization caches: id -> (args_key -> result), optionally LRU-bounded.
+    pub(crate) memo_caches: std::collections::HashMap<usize, builtins::MemoCache>,
     pub(crate) next_memo_id: usize,
+    /// `@profile` numbers by function name (`profile_report()`, summary at exit).
+    pub(crate) profile_stats: HashMap<String, decorators::ProfileStats>,
+    /// Nesting of `@trace`d calls, for indentation.
+    pub(crate) trace_depth: usize,
+    /// Time source for `sleep()`, `@throttle` and `@debounce` ([`Interpreter::set_clock`]).
+    pub(crate) clock: Rc<dyn Clock>,
+    /// `@throttle` state by decorator id: (time of last real call, its result).
+    pub(crate) throttle_state: HashMap<usize, (Duration, Value)>,
+    /// Deferred `@debounce` calls, at most one per decorated function.
+    pub(crate) timer_queue: Vec<decorators::PendingCall>,
+    next_decorator_id: usize,
+    /// `@validate` checks by decorator id.
+    pub(crate) validations: HashMap<usize, decorators::Validation>,
+    /// `@deprecated` messages by decorator id.
+    pub(crate) deprecations: HashMap<usize, String>,
+    /// (decorator id, call site location) pairs already warned about.
+    pub(crate) deprecation_warned: HashSet<(usize, String)>,
+    /// Make `@deprecated` calls fail instead of warning (`--deny-deprecated`).
+    pub(crate) deny_deprecated: bool,
+    /// User functions currently executing, innermost last.
+    pub(crate) frames: Vec<Frame>,
+    /// Span of the innermost call expression being evaluated; recorded by the frame it pushes.
+    call_site: Span,
+    /// File that code parsed without a file name belongs to ([`Interpreter::set_program_path`]).
+    program: Option<String>,
     /// Arena pools for pool | name -> pool(); pool_id -> storage vec
     pub(crate) pools: std::collections::HashMap<usize, Vec<Value>>,
     #[allow(dead_code)]
     pub(crate) next_pool_id: usize,
+    /// `test` blocks collected during a run (executed by `jade test`, not by `jade run`).
+    tests: Vec<TestDefinition>,
+    /// Random source for `rand*` builtins and property test inputs (`rand_seed(n)` reseeds it).
+    pub(crate) rng: generators::Rng,
+    /// Fixed seed for property tests (`jade test --seed`); a fresh one per property when unset.
+    property_seed: Option<u64>,
+    /// Generated cases per property test.
+    property_cases: usize,
+    /// Native code for hot numeric functions ([`Interpreter::enable_native_jit`]).
+    #[cfg(feature = "jit")]
+    pub(crate) native: Option<Box<crate::jit::native::NativeJit>>,
+    /// Breakpoints and stepping ([`Interpreter::set_debugger`]); checked before each statement.
+    debugger: Option<Box<Debugger>>,
+    /// Statement and branch counts ([`Interpreter::set_coverage`]); updated as code runs.
+    coverage: Option<Box<Coverage>>,
+    /// Time per call and line ([`Interpreter::set_profiler`]); told of each call and statement.
+    profiler: Option<Box<Profiler>>,
+}
+
+/// A call to a user function in progress.
+pub(crate) struct Frame {
+    pub(crate) function: String,
+    /// Where it was called from.
+    pub(crate) call_site: Span,
+    /// The caller's local scopes, restored when the call returns.
+    caller_locals: Vec<Scope>,
+    /// How many scopes the function captured; its own scopes follow them.
+    captured: usize,
+}
+
+/// `program` is the file of spans that have none.
+fn trace_entry(function: &str, span: &Span, program: Option<&str>) -> TraceEntry {
+    TraceEntry {
+        function: function.to_string(),
+        file: span.file.as_deref().or(program).map(str::to_string),
+        line: span.line,
+        column: span.column,
+        source_line: None,
+    }
+}
+
+/// Lex and parse a whole program.
+pub fn parse_source(source: &str) -> JResult<AstNode> {
+    let tokens = crate::lexer::Lexer::new(source).tokenize()?;
+    crate::parser::Parser::new(tokens).parse()
 }
 
 impl Interpreter {
     pub fn new() -> Self {
         let mut interpreter = Self {
             globals: HashMap::new(),
+            builtin_globals: HashSet::new(),
             locals: Vec::new(),
             statics: HashMap::new(),
             call_depth: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
             defer_stack: Vec::new(),
             once_cache: HashMap::new(),
             once_next_id: 0,
             // Module system
             module_cache: HashMap::new(),
             module_search_paths: vec![".".to_string()],
+            bundled_modules: HashMap::new(),
             // Trait system
             trait_impls: HashMap::new(),
             // Async system
@@ -72,12 +172,35 @@ impl Interpreter {
             output_buffer: None,
             memo_caches: std::collections::HashMap::new(),
             next_memo_id: 0,
+            profile_stats: HashMap::new(),
+            trace_depth: 0,
+            clock: Rc::new(SystemClock::new()),
+            throttle_state: HashMap::new(),
+            timer_queue: Vec::new(),
+            next_decorator_id: 0,
+            validations: HashMap::new(),
+            deprecations: HashMap::new(),
+            deprecation_warned: HashSet::new(),
+            deny_deprecated: false,
+            frames: Vec::new(),
+            call_site: Span::default(),
+            program: None,
             pools: std::collections::HashMap::new(),
             next_pool_id: 0,
+            tests: Vec::new(),
+            rng: generators::Rng::from_time(),
+            property_seed: None,
+            property_cases: 100,
+            #[cfg(feature = "jit")]
+            native: None,
+            debugger: None,
+            coverage: None,
+            profiler: None,
         };
 
         // Add built-in functions
         interpreter.add_builtins();
+        interpreter.builtin_globals = interpreter.globals.keys().cloned().collect();
         interpreter
     }
 
@@ -99,7 +222,8 @@ impl Interpreter {
         decorator_name: &str,
         decorator_args: &[crate::parser::AstNode],
         func: Value,
-    ) -> Result<Value, String> {
+        params: &[(String, String)],
+    ) -> JResult<Value> {
         // Evaluate decorator arguments
         let mut args = Vec::new();
         for arg in decorator_args {
@@ -108,16 +232,16 @@ impl Interpreter {
 
         // Check if decorator is a built-in or user-defined function
         let decorator_func = self.globals.get(decorator_name).cloned();
-        if let Some(Value::Function { params, body, .. }) = decorator_func {
+        if let Some(Value::Function { params, body, env, .. }) = decorator_func {
             // Call decorator with function as argument
             let mut call_args = vec![func];
             call_args.extend(args);
-            return self.call_function_internal(decorator_name, &call_args, &params, &body, None);
+            return self.call_function_internal(decorator_name, &call_args, &params, &body, None, &env);
         }
 
         // Built-in decorators (from j.txt and jnew_features.txt)
         match decorator_name {
-            "memo" | "cache" => Ok(self.create_memoized_function(func)?),
+            "memo" | "cache" => Ok(self.create_memoized_function(func, &args)?),
             "timer" => Ok(self.create_timed_function(func)?),
             "log_call" => Ok(self.create_logged_function(func)?),
             "tco" => {
@@ -126,60 +250,38 @@ impl Interpreter {
             }
             "once" => Ok(self.create_once_function(func)?),
             "throttle" => {
-                let interval_secs = args
-                    .first()
-                    .and_then(|a| {
-                        if let Value::Float(f) = a {
-                            Some(*f)
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap_or(0.5);
-                Ok(self.create_throttled_function(func, interval_secs)?)
+                let interval = Self::decorator_seconds(&args, "@throttle", 0.5)?;
+                Ok(self.create_throttled_function(func, interval)?)
             }
             "debounce" => {
-                let delay_secs = args
-                    .first()
-                    .and_then(|a| {
-                        if let Value::Float(f) = a {
-                            Some(*f)
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap_or(0.3);
-                Ok(self.create_debounced_function(func, delay_secs)?)
+                let delay = Self::decorator_seconds(&args, "@debounce", 0.3)?;
+                Ok(self.create_debounced_function(func, delay)?)
             }
             "profile" => Ok(self.create_profiled_function(func)?),
             "trace" => Ok(self.create_trace_function(func)?),
-            "validate_args" | "validate" => {
-                // Schema-based validation - pass through for now; user can define validator fn
-                Ok(func)
-            }
+            "validate_args" | "validate" => Ok(self.create_validated_function(func, &args, params)?),
             "deprecated" => Ok(self.create_deprecated_function(func, &args)?),
-            _ => Err(format!("Decorator '{}' not found", decorator_name)),
+            _ => Err(JError::undefined_function(decorator_name, 0, 0)
+                .raised_as(&format!("Decorator '{}' not found", decorator_name))
+                .into()),
         }
     }
 
-    fn create_memoized_function(&mut self, func: Value) -> Result<Value, String> {
-        // For now, return the function as-is
-        // Full memoization would require a cache map
-        Ok(func)
+    /// `@memo` / `@cache`, or `@memo(max_size)` for an LRU-bounded cache.
+    fn create_memoized_function(&mut self, func: Value, args: &[Value]) -> JResult<Value> {
+        let max_size = builtins::parse_max_size(args.first(), "@memo")?;
+        self.memoize_value(func, max_size)
     }
 
-    fn create_timed_function(&mut self, func: Value) -> Result<Value, String> {
-        // For now, return the function as-is
-        // Full timing would require wrapping the call
-        Ok(func)
+    fn create_timed_function(&mut self, func: Value) -> JResult<Value> {
+        self.decorate(func, Decoration::Timer)
     }
 
-    fn create_logged_function(&mut self, func: Value) -> Result<Value, String> {
-        // For now, return the function as-is; full logging would wrap the call
-        Ok(func)
+    fn create_logged_function(&mut self, func: Value) -> JResult<Value> {
+        self.decorate(func, Decoration::LogCall)
     }
 
-    fn create_once_function(&mut self, func: Value) -> Result<Value, String> {
+    fn create_once_function(&mut self, func: Value) -> JResult<Value> {
         self.once_next_id += 1;
         let id = self.once_next_id;
         Ok(Value::OnceCached {
@@ -188,106 +290,284 @@ impl Interpreter {
         })
     }
 
-    fn create_throttled_function(
-        &mut self,
-        func: Value,
-        _interval_secs: f64,
-    ) -> Result<Value, String> {
-        // @throttle(sec): max one call per interval - stub
-        Ok(func)
+    /// Interval argument of `@throttle(sec)` / `@debounce(sec)`, or `default` when omitted.
+    fn decorator_seconds(args: &[Value], who: &str, default: f64) -> JResult<Duration> {
+        let seconds = match args.first() {
+            None => Ok(default),
+            Some(Value::Integer(n)) => Ok(*n as f64),
+            Some(Value::Float(f)) => Ok(*f),
+            Some(other) => Err(other),
+        };
+        match seconds {
+            Ok(seconds) => Duration::try_from_secs_f64(seconds).map_err(|_| {
+                JError::invalid_argument(format!(
+                    "{} expects a non-negative number of seconds that fits in a duration, got {}",
+                    who, seconds
+                ))
+                .into()
+            }),
+            Err(other) => Err(JError::invalid_argument(format!(
+                "{} expects a non-negative number of seconds, got {}",
+                who, other
+            )).into()),
+        }
     }
 
-    fn create_debounced_function(
-        &mut self,
-        func: Value,
-        _delay_secs: f64,
-    ) -> Result<Value, String> {
-        // @debounce(sec): delay until silence - stub
-        Ok(func)
+    fn create_throttled_function(&mut self, func: Value, interval: Duration) -> JResult<Value> {
+        self.next_decorator_id += 1;
+        let id = self.next_decorator_id;
+        self.decorate(func, Decoration::Throttle { id, interval })
+    }
+
+    fn create_debounced_function(&mut self, func: Value, delay: Duration) -> JResult<Value> {
+        self.next_decorator_id += 1;
+        let id = self.next_decorator_id;
+        self.decorate(func, Decoration::Debounce { id, delay })
     }
 
-    fn create_profiled_function(&mut self, func: Value) -> Result<Value, String> {
-        // @profile: time + call count - stub
-        Ok(func)
+    fn create_profiled_function(&mut self, func: Value) -> JResult<Value> {
+        self.decorate(func, Decoration::Profile)
     }
 
-    fn create_trace_function(&mut self, func: Value) -> Result<Value, String> {
-        // @trace: verbose entry/exit - stub
-        Ok(func)
+    fn create_trace_function(&mut self, func: Value) -> JResult<Value> {
+        self.decorate(func, Decoration::Trace)
     }
 
-    fn create_deprecated_function(&mut self, func: Value, args: &[Value]) -> Result<Value, String> {
-        // @deprecated("message") - stub; could warn on call
-        let _msg = args
+    fn create_deprecated_function(&mut self, func: Value, args: &[Value]) -> JResult<Value> {
+        let message = args
             .first()
             .map(|v| v.to_string())
             .unwrap_or_else(|| "deprecated".to_string());
-        Ok(func)
+        self.next_decorator_id += 1;
+        let id = self.next_decorator_id;
+        self.deprecations.insert(id, message);
+        self.decorate(func, Decoration::Deprecated { id })
+    }
+
+    /// `@validate` checks the declared parameter types; `@validate(check)` calls `check` instead.
+    fn create_validated_function(
+        &mut self,
+        func: Value,
+        args: &[Value],
+        params: &[(String, String)],
+    ) -> JResult<Value> {
+        let validation = match args.first() {
+            None => decorators::Validation::ParamTypes(params.to_vec()),
+            Some(validator) if decorators::is_callable(validator) => {
+                decorators::Validation::Validator(Box::new(validator.clone()))
+            }
+            Some(other) => {
+                return Err(JError::invalid_argument(format!(
+                    "@validate expects a validator function, got {}",
+                    Self::value_type_name(other)
+                )).into())
+            }
+        };
+        self.next_decorator_id += 1;
+        let id = self.next_decorator_id;
+        self.validations.insert(id, validation);
+        self.decorate(func, Decoration::Validate { id })
     }
 
     fn call_function_internal(
         &mut self,
-        _func_name: &str,
+        func_name: &str,
         args: &[Value],
         params: &[String],
         body: &AstNode,
         this_value: Option<Value>,
-    ) -> Result<Value, String> {
+        env: &Env,
+    ) -> JResult<Value> {
         // Check depth BEFORE incrementing to prevent stack overflow
-        if self.call_depth >= 50 {
-            return Err(JError::stack_overflow(self.call_depth).to_string());
+        if self.call_depth >= self.max_call_depth {
+            return Err(Box::new(JError::stack_overflow(self.call_depth)));
         }
 
         self.call_depth += 1;
 
-        // Push new scope
-        self.locals.push(HashMap::new());
+        // New frame on top of the function's defining scopes (not the caller's)
+        let mut frame = HashMap::new();
 
         // Bind this/self if method call
         if let Some(ref this_val) = this_value {
-            if let Some(scope) = self.locals.last_mut() {
-                scope.insert("this".to_string(), this_val.clone());
-                scope.insert("self".to_string(), this_val.clone());
-            }
+            frame.insert("this".to_string(), this_val.clone());
+            frame.insert("self".to_string(), this_val.clone());
         }
 
         // Bind parameters
-        for (i, param) in params.iter().enumerate() {
-            if i < args.len() {
-                if let Some(scope) = self.locals.last_mut() {
-                    scope.insert(param.clone(), args[i].clone());
-                }
-            }
+        for (param, arg) in params.iter().zip(args) {
+            frame.insert(param.clone(), arg.clone());
         }
 
+        let caller_locals = std::mem::replace(&mut self.locals, env.0.clone());
+        self.frames.push(Frame {
+            function: func_name.to_string(),
+            call_site: self.call_site.clone(),
+            caller_locals,
+            captured: env.0.len(),
+        });
+        self.locals.push(Rc::new(RefCell::new(frame)));
+
         // Execute body
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.enter(func_name, false);
+        }
         let result = self.eval_node(body);
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.exit(true);
+        }
 
-        // Pop scope and decrement call depth
-        self.locals.pop();
+        // Restore the caller's scopes and decrement call depth
+        let frame = self.frames.pop().expect("the call's frame is still on the stack");
+        let locals = std::mem::replace(&mut self.locals, frame.caller_locals);
+        for scope in locals.into_iter().skip(frame.captured).rev() {
+            Self::release_scope(scope);
+        }
         self.call_depth -= 1;
 
         result
     }
 
-    pub fn run(&mut self, source: &str) -> Result<(), String> {
-        // Tokenize
-        let mut lexer = crate::lexer::Lexer::new(source);
-        let tokens = lexer
-            .tokenize()
-            .map_err(|e| format!("Lexer error: {}", e))?;
+    pub fn run(&mut self, source: &str) -> JResult<()> {
+        let ast = parse_source(source)?;
+        self.run_ast(&ast)
+    }
+
+    /// Run an already parsed program (see [`parse_source`]).
+    pub fn run_ast(&mut self, ast: &AstNode) -> JResult<()> {
+        // Interpret, then run any calls still waiting on a @debounce delay
+        self.eval_node(ast)?;
+        self.drain_timers()
+
+    }
+
+    /// Full report for `error`, raised while running `source` with this interpreter: adds the
+    /// offending source lines, for the error and each traceback entry. Errors raised in an
+    /// imported module quote that module's file.
+    pub fn describe_error(&self, error: &JError, source: &str) -> JError {
+        let mut error = error.clone();
+        let mut modules: HashMap<String, Option<String>> = HashMap::new();
+        let mut line_of = |file: Option<&str>, line: usize| {
+            let text = match file.filter(|&file| Some(file) != self.program.as_deref()) {
+                Some(file) => modules
+                    .entry(file.to_string())
+                    .or_insert_with(|| self.module_source(file))
+                    .as_deref()?,
+                None => source,
+            };
+            text.lines().nth(line.checked_sub(1)?).map(str::to_string)
+        };
+        for entry in &mut error.trace {
+            entry.source_line = line_of(entry.file.as_deref(), entry.line);
+        }
+        if let Some(line) = error.line {
+            error.source_line = line_of(error.file.as_deref(), line);
+        }
+        error
+    }
+
+    /// Locate an error at `span`, the innermost spanned node it propagated through, and record
+    /// the traceback. Errors that already have a location keep it.
+    pub(crate) fn locate_error(&self, mut error: Box<JError>, span: &Span) -> Box<JError> {
+        if error.line.is_none() && span.line > 0 && error.kind != ErrorKind::AlreadyFormatted {
+            error.line = Some(span.line);
+            error.column = Some(span.column);
+            error.file = span.file.as_deref().map(str::to_string);
+            error.trace = self.traceback(span);
+        }
+        error
+    }
+
+    /// Where each running function is: the call site of the next frame, or `span` for the
+    /// innermost one.
+    fn traceback(&self, span: &Span) -> Vec<TraceEntry> {
+        let mut function = "<module>";
+        let mut trace = Vec::with_capacity(self.frames.len() + 1);
+        for frame in &self.frames {
+            trace.push(trace_entry(function, &frame.call_site, self.program.as_deref()));
+            function = &frame.function;
+        }
+        trace.push(trace_entry(function, span, self.program.as_deref()));
+        trace
+    }
+
+    /// Evaluate `f` with `span` as the call site of the frames it pushes.
+    fn at_call_site<T>(&mut self, span: &Span, f: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = std::mem::replace(&mut self.call_site, span.clone());
+        let result = f(self);
+        self.call_site = outer;
+        result
+    }
+
+    fn module_source(&self, file: &str) -> Option<String> {
+        self.bundled_modules
+            .values()
+            .find(|(path, _)| path == file)
+            .map(|(_, source)| source.clone())
+            .or_else(|| std::fs::read_to_string(file).ok())
+    }
 
-        // Parse
-        let mut parser = crate::parser::Parser::new(tokens);
-        let ast = parser.parse().map_err(|e| format!("Parser error: {}", e))?;
+    /// Limit nested function calls; deeper calls fail with a stack overflow error.
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.max_call_depth = depth.max(1);
+    }
 
-        // Interpret
-        self.eval_node(&ast)
-            .map_err(|e| format!("Runtime error: {}", e))?;
+    pub(crate) fn max_call_depth(&self) -> usize {
+        self.max_call_depth
+    }
 
+    /// Compile user functions to native code once they have been called `threshold` times with
+    /// the same argument types, if they only do `int`/`float`/`bool` work.
+    #[cfg(feature = "jit")]
+    pub fn enable_native_jit(&mut self, threshold: u64) -> JResult<()> {
+        let native =
+            crate::jit::native::NativeJit::new(threshold.max(1)).map_err(JError::unsupported)?;
+        self.native = Some(Box::new(native));
         Ok(())
     }
 
+    /// Compile `name` on its next call instead of waiting for it to get hot.
+    #[cfg(feature = "jit")]
+    pub fn mark_hot(&mut self, name: &str) -> JResult<()> {
+        match self.native.as_mut() {
+            Some(native) => {
+                native.mark_hot(name);
+                Ok(())
+            }
+            None => Err(JError::unsupported("Native code is not enabled").into()),
+        }
+    }
+
+    /// Names of the functions that have native code, sorted.
+    #[cfg(feature = "jit")]
+    pub fn native_functions(&self) -> Vec<String> {
+        self.native
+            .as_ref()
+            .map(|native| native.compiled_functions())
+            .unwrap_or_default()
+    }
+
+    /// Name `path` as the file of the program being run, for traceback frames in code parsed
+    /// without a file name.
+    pub fn set_program_path(&mut self, path: &str) {
+        self.program = Some(path.to_string());
+    }
+
+    pub(crate) fn program_path(&self) -> Option<&str> {
+        self.program.as_deref()
+    }
+
+    /// Make calls to `@deprecated` functions fail instead of printing a warning.
+    pub fn set_deny_deprecated(&mut self, deny: bool) {
+        self.deny_deprecated = deny;
+    }
+
+    /// Replace the time source, e.g. with a [`ManualClock`] so `sleep()` and `@debounce`
+    /// delays advance virtual time instead of blocking.
+    pub fn set_clock(&mut self, clock: Rc<dyn Clock>) {
+        self.clock = clock;
+    }
+
     /// Capture out() to an internal buffer instead of stdout.
     pub fn set_output_capture(&mut self, capture: bool) {
         self.output_buffer = if capture {
@@ -314,6 +594,17 @@ impl Interpreter {
         self.module_search_paths.push(path.into());
     }
 
+    /// Serve `import` of `module_path` from `source` instead of the file system (bundled programs).
+    pub fn add_bundled_module(
+        &mut self,
+        module_path: impl Into<String>,
+        file_path: impl Into<String>,
+        source: impl Into<String>,
+    ) {
+        self.bundled_modules
+            .insert(module_path.into(), (file_path.into(), source.into()));
+    }
+
     /// List global variable names (for REPL .vars). Sorted.
     pub fn global_names(&self) -> Vec<String> {
         let mut names: Vec<String> = self.globals.keys().cloned().collect();
@@ -345,8 +636,9 @@ impl Interpreter {
         }
     }
 
-    pub fn evaluate(&mut self, node: &AstNode) -> Result<String, String> {
+    pub fn evaluate(&mut self, node: &AstNode) -> JResult<String> {
         let value = self.eval_node(node)?;
+        self.drain_timers()?;
         match value {
             Value::None => Ok(String::new()),
             _ => Ok(value.to_string()),
@@ -0,0 +1,341 @@
+//! Profiling: with a [`Profiler`] installed, the interpreter reports every call, builtin call
+//! and statement, and the time between two reports is charged to the innermost call and to the
+//! line it is running. `jade run --profile` prints the slowest functions and lines and writes
+//! the time per call stack in the folded format that flamegraph tools read.
+//!
+//! Builtins are leaf frames: calls made while evaluating a builtin's arguments (or by a builtin
+//! like `map`) are stacked under the function that called the builtin. Lines are statements as
+//! the debugger sees them, located by [`crate::parser::AstNode::first_span`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::Interpreter;
+use crate::parser::Span;
+
+/// Name of the frame at the bottom of every stack: the program's top-level code.
+const ROOT: &str = "<module>";
+
+/// Time spent in one function or builtin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionProfile {
+    pub name: String,
+    pub calls: u64,
+    /// Time in the function itself, not in functions it called.
+    pub self_time: Duration,
+    /// Time from call to return; recursive calls are counted once. A builtin's is its self
+    /// time, since the calls in its arguments are the caller's.
+    pub total_time: Duration,
+}
+
+/// Time spent on one source line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineProfile {
+    pub file: String,
+    pub line: usize,
+    /// Times a statement starting on the line ran.
+    pub hits: u64,
+    /// Time in statements of the line, including builtins they called but not user functions.
+    pub time: Duration,
+}
+
+/// One call stack, as a node of the tree of all stacks seen.
+#[derive(Debug)]
+struct StackNode {
+    name: String,
+    parent: Option<usize>,
+    children: HashMap<String, usize>,
+    time: Duration,
+}
+
+/// A call in progress.
+#[derive(Debug)]
+struct Call {
+    node: usize,
+    builtin: bool,
+    entered: Instant,
+    /// Time charged to this call while it was the innermost one.
+    self_time: Duration,
+    /// Line of the statement running in this call.
+    line: Option<(Arc<str>, usize)>,
+}
+
+#[derive(Debug, Default)]
+struct Calls {
+    calls: u64,
+    total_time: Duration,
+    /// Calls currently on the stack; time is only added when the outermost one returns.
+    active: usize,
+}
+
+#[derive(Debug, Default)]
+struct LineTime {
+    hits: u64,
+    time: Duration,
+}
+
+/// Time per call stack, function and line, installed with [`Interpreter::set_profiler`].
+#[derive(Debug)]
+pub struct Profiler {
+    /// File that code parsed without a file name belongs to.
+    program: Arc<str>,
+    stacks: Vec<StackNode>,
+    calls: Vec<Call>,
+    functions: HashMap<String, Calls>,
+    lines: HashMap<(Arc<str>, usize), LineTime>,
+    /// When time was last charged.
+    last: Instant,
+}
+
+impl Profiler {
+    /// A profiler for a run of `program`, starting the clock now.
+    pub fn new(program: &str) -> Self {
+        let now = Instant::now();
+        let mut profiler = Self {
+            program: Arc::from(program),
+            stacks: vec![StackNode {
+                name: ROOT.to_string(),
+                parent: None,
+                children: HashMap::new(),
+                time: Duration::ZERO,
+            }],
+            calls: Vec::new(),
+            functions: HashMap::new(),
+            lines: HashMap::new(),
+            last: now,
+        };
+        profiler.push(0, false, now);
+        profiler
+    }
+
+    /// A call to the function or builtin `name` starts.
+    pub(super) fn enter(&mut self, name: &str, builtin: bool) {
+        let now = Instant::now();
+        self.charge(now);
+        let parent = self.calls.iter().rev().find(|call| !call.builtin);
+        let parent = parent.map_or(0, |call| call.node);
+        let node = match self.stacks[parent].children.get(name) {
+            Some(&node) => node,
+            None => {
+                let node = self.stacks.len();
+                self.stacks.push(StackNode {
+                    name: name.to_string(),
+                    parent: Some(parent),
+                    children: HashMap::new(),
+                    time: Duration::ZERO,
+                });
+                self.stacks[parent].children.insert(name.to_string(), node);
+                node
+            }
+        };
+        self.push(node, builtin, now);
+    }
+
+    /// The innermost call returns. A builtin lookup that found no builtin is not `counted`: it
+    /// is dropped and its time goes to the caller.
+    pub(super) fn exit(&mut self, counted: bool) {
+        let now = Instant::now();
+        if counted {
+            self.charge(now);
+        }
+        let Some(call) = self.calls.pop() else {
+            return;
+        };
+        let name = &self.stacks[call.node].name;
+        let function = self.functions.entry(name.clone()).or_default();
+        function.active -= 1;
+        if counted {
+            function.calls += 1;
+            if call.builtin {
+                function.total_time += call.self_time;
+            } else if function.active == 0 {
+                function.total_time += now - call.entered;
+            }
+        }
+    }
+
+    /// The statement starting at `span` is about to run.
+    pub(super) fn statement(&mut self, span: &Span) {
+        self.charge(Instant::now());
+        let file = span.file.clone().unwrap_or_else(|| self.program.clone());
+        let key = (file, span.line);
+        self.lines.entry(key.clone()).or_default().hits += 1;
+        if let Some(call) = self.calls.iter_mut().rev().find(|call| !call.builtin) {
+            call.line = Some(key);
+        }
+    }
+
+    /// End every call still open, e.g. after the program failed, and stop the clock.
+    pub fn finish(&mut self) {
+        while !self.calls.is_empty() {
+            self.exit(true);
+        }
+    }
+
+    /// Functions and builtins by time spent in them, slowest first.
+    pub fn functions(&self) -> Vec<FunctionProfile> {
+        let mut self_times: HashMap<&str, Duration> = HashMap::new();
+        for node in &self.stacks {
+            *self_times.entry(&node.name).or_default() += node.time;
+        }
+        let mut functions: Vec<_> = self
+            .functions
+            .iter()
+            .filter(|(_, function)| function.calls > 0)
+            .map(|(name, function)| FunctionProfile {
+                name: name.clone(),
+                calls: function.calls,
+                self_time: self_times.get(name.as_str()).copied().unwrap_or_default(),
+                total_time: function.total_time,
+            })
+            .collect();
+        functions.sort_by(|a, b| b.self_time.cmp(&a.self_time).then(a.name.cmp(&b.name)));
+        functions
+    }
+
+    /// Lines by time spent on them, slowest first.
+    pub fn lines(&self) -> Vec<LineProfile> {
+        let mut lines: Vec<_> = self
+            .lines
+            .iter()
+            .map(|((file, line), time)| LineProfile {
+                file: file.to_string(),
+                line: *line,
+                hits: time.hits,
+                time: time.time,
+            })
+            .collect();
+        lines.sort_by(|a, b| {
+            b.time
+                .cmp(&a.time)
+                .then(a.file.cmp(&b.file))
+                .then(a.line.cmp(&b.line))
+        });
+        lines
+    }
+
+    /// Every call stack seen, as `outer;inner;innermost` names, with the time spent in its
+    /// innermost call. Sorted by stack.
+    pub fn stacks(&self) -> Vec<(String, Duration)> {
+        let mut stacks: Vec<_> = (0..self.stacks.len())
+            .map(|node| (self.stack_path(node), self.stacks[node].time))
+            .collect();
+        stacks.sort();
+        stacks
+    }
+
+    /// Time per call stack in the folded format of `flamegraph.pl` and `inferno`: one
+    /// `stack microseconds` line per stack that took at least a microsecond.
+    pub fn folded(&self) -> String {
+        let stacks = self.stacks().into_iter();
+        let stacks = stacks.filter(|(_, time)| time.as_micros() > 0);
+        stacks
+            .map(|(stack, time)| format!("{} {}\n", stack, time.as_micros()))
+            .collect()
+    }
+
+    /// Tables of the `top` slowest functions and lines.
+    pub fn summary(&self, top: usize) -> String {
+        let functions = self.functions();
+        let total = functions
+            .iter()
+            .find(|function| function.name == ROOT)
+            .map_or(Duration::ZERO, |root| root.total_time);
+        let functions = &functions[..functions.len().min(top)];
+        let lines = self.lines();
+        let lines: Vec<_> = lines
+            .iter()
+            .take(top)
+            .map(|line| (format!("{}:{}", line.file, line.line), line))
+            .collect();
+
+        let width = functions.iter().map(|function| function.name.len());
+        let width = width.chain(lines.iter().map(|(place, _)| place.len()));
+        let width = width.max().unwrap_or(0).max(8);
+        let mut out = format!("🔥 Profile: {:.3} ms\n", ms(total));
+        out.push_str(&format!(
+            "  {:<width$} {:>10} {:>12} {:>12}\n",
+            "function", "calls", "self ms", "total ms"
+        ));
+        for function in functions {
+            out.push_str(&format!(
+                "  {:<width$} {:>10} {:>12.3} {:>12.3}\n",
+                function.name,
+                function.calls,
+                ms(function.self_time),
+                ms(function.total_time)
+            ));
+        }
+        out.push_str(&format!(
+            "  {:<width$} {:>10} {:>12}\n",
+            "line", "hits", "self ms"
+        ));
+        for (place, line) in lines {
+            out.push_str(&format!(
+                "  {:<width$} {:>10} {:>12.3}\n",
+                place,
+                line.hits,
+                ms(line.time)
+            ));
+        }
+        out
+    }
+
+    fn push(&mut self, node: usize, builtin: bool, now: Instant) {
+        let name = &self.stacks[node].name;
+        self.functions.entry(name.clone()).or_default().active += 1;
+        self.calls.push(Call {
+            node,
+            builtin,
+            entered: now,
+            self_time: Duration::ZERO,
+            line: None,
+        });
+    }
+
+    /// Charge the time since the last report to the innermost call and the line it runs.
+    fn charge(&mut self, now: Instant) {
+        let elapsed = now - self.last;
+        self.last = now;
+        let Some(call) = self.calls.last_mut() else {
+            return;
+        };
+        call.self_time += elapsed;
+        self.stacks[call.node].time += elapsed;
+        if let Some(line) = self.calls.iter().rev().find_map(|call| call.line.as_ref()) {
+            if let Some(time) = self.lines.get_mut(line) {
+                time.time += elapsed;
+            }
+        }
+    }
+
+    fn stack_path(&self, node: usize) -> String {
+        let mut names = Vec::new();
+        let mut next = Some(node);
+        while let Some(node) = next {
+            names.push(self.stacks[node].name.as_str());
+            next = self.stacks[node].parent;
+        }
+        names.reverse();
+        names.join(";")
+    }
+}
+
+fn ms(time: Duration) -> f64 {
+    time.as_secs_f64() * 1000.0
+}
+
+impl Interpreter {
+    /// Report calls and statements to `profiler` from now on.
+    pub fn set_profiler(&mut self, profiler: Profiler) {
+        self.profiler = Some(Box::new(profiler));
+    }
+
+    /// Remove the profiler, ending the calls still open in it.
+    pub fn take_profiler(&mut self) -> Option<Profiler> {
+        let mut profiler = *self.profiler.take()?;
+        profiler.finish();
+        Some(profiler)
+    }
+}
@@ -1,6 +1,10 @@
+use crate::error::JError;
 use crate::parser::AstNode;
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
 
 /// Control flow for return/break/continue unwinding (reserved for future use).
 #[allow(dead_code)]
@@ -30,6 +34,27 @@ pub enum FutureState {
     Failed(String),
 }
 
+/// Behavior added by an instrumenting decorator (see [`Value::Decorated`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decoration {
+    /// `@timer`: print the elapsed time of every call.
+    Timer,
+    /// `@profile`: accumulate call counts and time for `profile_report()`.
+    Profile,
+    /// `@trace`: print indented entry and exit lines.
+    Trace,
+    /// `@log_call`: write one structured record per call.
+    LogCall,
+    /// `@throttle(sec)`: run at most once per interval; calls in between return the last result.
+    Throttle { id: usize, interval: Duration },
+    /// `@debounce(sec)`: queue the call and run only the last of a burst once `delay` passes quietly.
+    Debounce { id: usize, delay: Duration },
+    /// `@deprecated("msg")`: warn once per call site, or fail under `--deny-deprecated`.
+    Deprecated { id: usize },
+    /// `@validate` / `@validate(check)`: reject bad arguments before the body runs.
+    Validate { id: usize },
+}
+
 /// Trie node for prefix tree (autocomplete, spell-check).
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct TrieNode {
@@ -59,6 +84,57 @@ impl PartialEq for CompiledRegex {
     }
 }
 
+/// One block or call scope. Shared so a closure sees later updates to the variables it captured.
+pub type Scope = Rc<RefCell<HashMap<String, Value>>>;
+
+/// Scopes a function captured where it was defined (outermost first). Globals are not included.
+///
+/// A function stored in a scope it captured keeps that scope alive through this `Rc`. The
+/// interpreter breaks that cycle when the scope leaves the stack and nothing outside it holds
+/// it (see `Interpreter::release_scope`); a scope whose closure escaped and is dropped later
+/// still leaks.
+#[derive(Clone, Default)]
+pub struct Env(pub(crate) Vec<Scope>);
+
+impl Env {
+    pub fn scopes(&self) -> &[Scope] {
+        &self.0
+    }
+}
+
+/// Shallow: a scope can hold the function that captured it.
+impl std::fmt::Debug for Env {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Env({} scopes)", self.0.len())
+    }
+}
+
+/// Two environments are equal when they share the same scopes.
+impl PartialEq for Env {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self.0.iter().zip(&other.0).all(|(a, b)| Rc::ptr_eq(a, b))
+    }
+}
+
+impl Value {
+    /// How many closures reachable from this value captured `scope`. Only walks the variants
+    /// that usually hold functions; anything else counts as none, which keeps the scope alive.
+    pub(crate) fn captures_of(&self, scope: &Scope) -> usize {
+        match self {
+            Value::Function { env, .. } => env.0.iter().filter(|s| Rc::ptr_eq(s, scope)).count(),
+            Value::List(items) | Value::Tuple(items) => {
+                items.iter().map(|v| v.captures_of(scope)).sum()
+            }
+            Value::Dict(map) => map.values().map(|v| v.captures_of(scope)).sum(),
+            Value::Memoized { inner, .. }
+            | Value::OnceCached { inner, .. }
+            | Value::Decorated { inner, .. } => inner.captures_of(scope),
+            _ => 0,
+        }
+    }
+}
+
 /// Match result from regex find: full text, start/end, and capture groups.
 #[derive(Debug, Clone, PartialEq)]
 pub struct RegexMatch {
@@ -154,6 +230,8 @@ pub enum Value {
         name: String,
         params: Vec<String>,
         body: Box<AstNode>,
+        /// Captured defining environment (lexical scope).
+        env: Env,
     },
     Infinity(bool), // true for +inf, false for -inf
     Emoji(String),
@@ -247,6 +325,12 @@ pub enum Value {
         id: usize,
         inner: Box<Value>,
     },
+    /// Function wrapped by an instrumenting decorator; `name` is the decorated function's name.
+    Decorated {
+        kind: Decoration,
+        name: String,
+        inner: Box<Value>,
+    },
     /// Compiled regex (only when "regex" feature is enabled).
     #[cfg(feature = "regex")]
     Regex(Box<CompiledRegex>),
@@ -254,6 +338,9 @@ pub enum Value {
     RegexMatch(Box<RegexMatch>),
     /// Callable that returns match group by index (from m.group(n)).
     MatchGroup(Box<RegexMatch>),
+    /// Error caught by `try`/`catch`: `e.message`, `e.kind`, `e.line`, `e.column`, `e.file`,
+    /// `e.trace`.
+    Error(Box<JError>),
     /// Bound method: receiver + method name, for obj.method(args) dispatch.
     BoundMethod {
         receiver: Box<Value>,
@@ -620,10 +707,12 @@ impl fmt::Display for Value {
             Value::UnionFind { parent, .. } => write!(f, "<union_find size={}>", parent.len()),
             Value::Trie(_) => write!(f, "<trie>"),
             Value::Memoized { id, .. } => write!(f, "<memoized {}>", id),
+            Value::Decorated { name, .. } => write!(f, "<function {}>", name),
             #[cfg(feature = "regex")]
             Value::Regex(r) => write!(f, "regex({:?})", r.pattern),
             Value::RegexMatch(m) => write!(f, "<match {}..{} {:?}>", m.start, m.end, m.text),
             Value::MatchGroup(_) => write!(f, "<match.group>"),
+            Value::Error(e) => write!(f, "{}", e.message),
             Value::BoundMethod { receiver, method } => {
                 write!(f, "<bound {} on {}>", method, receiver)
             }
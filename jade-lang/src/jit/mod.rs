@@ -9,10 +9,18 @@ use std::path::PathBuf;
 use std::sync::mpsc;
 use std::time::SystemTime;
 
+use crate::error::{JError, JResult};
 use crate::interpreter::Interpreter;
 use crate::lexer::Lexer;
 use crate::parser::Parser;
 
+#[cfg(feature = "jit")]
+pub(crate) mod native;
+
+/// Calls after which a numeric function is compiled to native code.
+#[cfg(feature = "jit")]
+pub const DEFAULT_HOT_THRESHOLD: u64 = 100;
+
 #[allow(dead_code)]
 pub struct JitCompiler {
     interpreter: Interpreter,
@@ -23,8 +31,14 @@ pub struct JitCompiler {
 #[allow(dead_code)]
 impl JitCompiler {
     pub fn new() -> Self {
+        #[allow(unused_mut)]
+        let mut interpreter = Interpreter::new();
+        #[cfg(feature = "jit")]
+        if let Err(e) = interpreter.enable_native_jit(DEFAULT_HOT_THRESHOLD) {
+            eprintln!("Warning: {}; running without native code", e);
+        }
         Self {
-            interpreter: Interpreter::new(),
+            interpreter,
             file_cache: HashMap::new(),
             hot_reload: false,
         }
@@ -35,7 +49,32 @@ impl JitCompiler {
         self.interpreter.add_module_search_path(path);
     }
 
-    pub fn run_file(&mut self, file_path: PathBuf, hot_reload: bool) -> Result<(), String> {
+    /// Limit nested function calls (see [`Interpreter::set_max_call_depth`]).
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.interpreter.set_max_call_depth(depth);
+    }
+
+    /// Name the program file in tracebacks (see [`Interpreter::set_program_path`]).
+    pub fn set_program_path(&mut self, path: &str) {
+        self.interpreter.set_program_path(path);
+    }
+
+    /// Make `@deprecated` calls fail (see [`Interpreter::set_deny_deprecated`]).
+    pub fn set_deny_deprecated(&mut self, deny: bool) {
+        self.interpreter.set_deny_deprecated(deny);
+    }
+
+    /// `@profile` summary from the last run (see [`Interpreter::profile_summary`]).
+    pub fn profile_summary(&self) -> Option<String> {
+        self.interpreter.profile_summary()
+    }
+
+    /// Report a [`JitCompiler::run_file`] error (see [`Interpreter::describe_error`]).
+    pub fn describe_error(&self, error: &JError, source: &str) -> JError {
+        self.interpreter.describe_error(error, source)
+    }
+
+    pub fn run_file(&mut self, file_path: PathBuf, hot_reload: bool) -> JResult<()> {
         self.hot_reload = hot_reload;
 
         // Initial execution
@@ -43,25 +82,25 @@ impl JitCompiler {
 
         if hot_reload {
             #[cfg(feature = "jit")]
-            self.start_hot_reload(file_path)?;
+            self.start_hot_reload(file_path).map_err(JError::io_error)?;
             #[cfg(not(feature = "jit"))]
-            return Err("Hot reload requires the 'jit' feature to be enabled".to_string());
+            return Err(JError::unsupported("Hot reload requires the 'jit' feature to be enabled").into());
         }
 
         Ok(())
     }
 
-    fn execute_file(&mut self, file_path: &PathBuf) -> Result<(), String> {
+    fn execute_file(&mut self, file_path: &PathBuf) -> JResult<()> {
         let content =
-            fs::read_to_string(file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+            fs::read_to_string(file_path).map_err(|e| JError::io_error(format!("Failed to read file: {}", e)))?;
 
         // Check if file has changed
         let metadata =
-            fs::metadata(file_path).map_err(|e| format!("Failed to get file metadata: {}", e))?;
+            fs::metadata(file_path).map_err(|e| JError::io_error(format!("Failed to get file metadata: {}", e)))?;
 
         let modified = metadata
             .modified()
-            .map_err(|e| format!("Failed to get modification time: {}", e))?;
+            .map_err(|e| JError::io_error(format!("Failed to get modification time: {}", e)))?;
 
         // Check cache
         if let Some((cached_time, cached_content)) = self.file_cache.get(file_path) {
@@ -73,15 +112,11 @@ impl JitCompiler {
 
         // Tokenize
         let mut lexer = Lexer::new(&content);
-        let tokens = lexer
-            .tokenize()
-            .map_err(|e| format!("Lexer error in {}: {}", file_path.display(), e))?;
+        let tokens = lexer.tokenize()?;
 
         // Parse
         let mut parser = Parser::new(tokens);
-        let ast = parser
-            .parse()
-            .map_err(|e| format!("Parser error in {}: {}", file_path.display(), e))?;
+        let ast = parser.parse()?;
 
         // JIT compile and execute
         match self.interpreter.evaluate(&ast) {
@@ -95,7 +130,7 @@ impl JitCompiler {
                     .insert(file_path.clone(), (modified, content));
             }
             Err(e) => {
-                eprintln!("Runtime error: {}", e);
+                eprintln!("Runtime error: {}", e.message);
                 return Err(e);
             }
         }
@@ -135,7 +170,7 @@ impl JitCompiler {
                         {
                             println!("\nFile changed: {}", path.display());
                             if let Err(e) = self.execute_file(path) {
-                                eprintln!("Hot reload failed: {}", e);
+                                eprintln!("Hot reload failed: {}", e.message);
                             }
                             break;
                         }
@@ -152,33 +187,30 @@ impl JitCompiler {
         Ok(())
     }
 
+    /// Compile `source` to a `.jdc` image (see [`crate::bytecode`]).
     pub fn compile_to_bytecode(&mut self, source: &str) -> Result<Vec<u8>, String> {
-        // This would compile to bytecode for faster execution
-        // For now, we'll just return a placeholder
-
-        let mut lexer = Lexer::new(source);
-        let tokens = lexer
-            .tokenize()
-            .map_err(|e| format!("Lexer error: {}", e))?;
-
-        let mut parser = Parser::new(tokens);
-        let _ast = parser.parse().map_err(|e| format!("Parser error: {}", e))?;
-
-        // TODO: Implement bytecode generation
-        Ok(vec![0x4A, 0x49, 0x54]) // "JIT" in bytes as placeholder
+        let ast = crate::interpreter::parse_source(source).map_err(|e| e.to_string())?;
+        let mut program = crate::bytecode::compile(&ast).map_err(|e| e.to_string())?;
+        program.source_hash = crate::bytecode::source_hash(source);
+        Ok(program.serialize())
     }
 
-    pub fn optimize_hot_path(&mut self, function_name: &str) -> Result<(), String> {
-        // This would optimize frequently called functions
-        println!("🚀 Optimizing hot path: {}", function_name);
-
-        // TODO: Implement JIT optimization
-        // - Profile function calls
-        // - Inline small functions
-        // - Optimize loops
-        // - Specialize for common types
+    /// Compile `function_name` to native code on its next call instead of waiting for it to
+    /// get hot (see [`Interpreter::enable_native_jit`]).
+    pub fn optimize_hot_path(&mut self, function_name: &str) -> JResult<()> {
+        #[cfg(feature = "jit")]
+        return self.interpreter.mark_hot(function_name);
+        #[cfg(not(feature = "jit"))]
+        return Err(JError::invalid_argument(format!(
+            "Cannot optimize '{}': native code requires the 'jit' feature",
+            function_name
+        )).into());
+    }
 
-        Ok(())
+    /// Functions running as native code (see [`Interpreter::native_functions`]).
+    #[cfg(feature = "jit")]
+    pub fn native_functions(&self) -> Vec<String> {
+        self.interpreter.native_functions()
     }
 }
 
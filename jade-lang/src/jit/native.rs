@@ -0,0 +1,930 @@
+//! Native code for hot numeric functions, built with Cranelift.
+//!
+//! The interpreter counts calls per function name. Once a function crosses the threshold it is
+//! compiled for the argument types of that call, provided its body only uses `int`/`float`/`bool`
+//! locals, arithmetic, comparisons, `if`, `while`, `for` over a range and calls to itself.
+//! Native code never reports errors itself: whenever the interpreter would fail (overflow,
+//! division by zero, the call depth limit, ...) it bails out and the call is run again on the
+//! interpreter. That is safe because such functions have no side effects.
+
+use std::collections::HashMap;
+
+use cranelift::codegen::ir::FuncRef;
+use cranelift::codegen::Context;
+use cranelift::prelude::*;
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, Linkage, Module};
+
+use crate::parser::{AstNode, BinaryOp, UnaryOp};
+
+/// Recursion budget of a single native entry; deeper calls bail out to the interpreter.
+const MAX_NATIVE_DEPTH: usize = 4096;
+
+/// Bail-outs after which a compiled specialization is dropped for good.
+const MAX_BAILS: u32 = 8;
+
+/// Declared types whose declarations need no conversion by the interpreter.
+const PLAIN_TYPES: &[&str] = &["int", "float", "bool", "any"];
+
+/// Runtime type of a native value; arguments and results are passed as `i64` words.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Ty {
+    Int,
+    Float,
+    Bool,
+}
+
+impl Ty {
+    fn clif(self) -> Type {
+        match self {
+            Ty::Int => types::I64,
+            Ty::Float => types::F64,
+            Ty::Bool => types::I8,
+        }
+    }
+
+    /// Type and word of an interpreter value, if it can be passed to native code.
+    pub(crate) fn of(value: &crate::interpreter::Value) -> Option<(Ty, i64)> {
+        use crate::interpreter::Value;
+        match value {
+            Value::Integer(i) => Some((Ty::Int, *i)),
+            Value::Float(f) => Some((Ty::Float, f.to_bits() as i64)),
+            Value::Boolean(b) => Some((Ty::Bool, *b as i64)),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn value(self, word: i64) -> crate::interpreter::Value {
+        use crate::interpreter::Value;
+        match self {
+            Ty::Int => Value::Integer(word),
+            Ty::Float => Value::Float(f64::from_bits(word as u64)),
+            Ty::Bool => Value::Boolean(word != 0),
+        }
+    }
+}
+
+/// `(args, recursion budget, status) -> result`; a nonzero status means "bailed out".
+type Entry = unsafe extern "C" fn(*const i64, i64, *mut i64) -> i64;
+
+struct Specialization {
+    params: Vec<String>,
+    body: AstNode,
+    arg_types: Vec<Ty>,
+    ret: Ty,
+    /// Calls itself by name; only valid while the name still refers to this function.
+    recursive: bool,
+    entry: Entry,
+    bails: u32,
+}
+
+#[derive(Default)]
+struct FunctionState {
+    calls: u64,
+    specializations: Vec<Specialization>,
+    /// Argument types this function could not be compiled for.
+    rejected: Vec<Vec<Ty>>,
+}
+
+pub(crate) struct NativeJit {
+    module: JITModule,
+    ctx: Context,
+    threshold: u64,
+    functions: HashMap<String, FunctionState>,
+    next_symbol: usize,
+}
+
+impl NativeJit {
+    pub(crate) fn new(threshold: u64) -> Result<Self, String> {
+        let builder = JITBuilder::new(default_libcall_names())
+            .map_err(|e| format!("Failed to set up the native JIT: {}", e))?;
+        let module = JITModule::new(builder);
+        let ctx = module.make_context();
+        Ok(Self {
+            module,
+            ctx,
+            threshold,
+            functions: HashMap::new(),
+            next_symbol: 0,
+        })
+    }
+
+    /// Count a call to `name`; true once it is hot enough to compile.
+    pub(crate) fn record_call(&mut self, name: &str) -> bool {
+        if !self.functions.contains_key(name) {
+            self.functions
+                .insert(name.to_string(), FunctionState::default());
+        }
+        let state = self
+            .functions
+            .get_mut(name)
+            .expect("function state was just inserted");
+        state.calls += 1;
+        state.calls >= self.threshold
+    }
+
+    /// Treat `name` as hot from its next call on.
+    pub(crate) fn mark_hot(&mut self, name: &str) {
+        let threshold = self.threshold;
+        let state = self.functions.entry(name.to_string()).or_default();
+        state.calls = state.calls.max(threshold);
+    }
+
+    /// Names of functions with at least one compiled specialization, sorted.
+    pub(crate) fn compiled_functions(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .functions
+            .iter()
+            .filter(|(_, state)| !state.specializations.is_empty())
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Whether `name` was already found uncompilable for these argument types.
+    pub(crate) fn is_rejected(&self, name: &str, arg_types: &[Ty]) -> bool {
+        self.functions
+            .get(name)
+            .is_some_and(|state| state.rejected.iter().any(|types| types == arg_types))
+    }
+
+    /// Specialization of `name` for this function and argument types, as (index, whether it
+    /// calls itself by `name`).
+    pub(crate) fn find(
+        &self,
+        name: &str,
+        params: &[String],
+        body: &AstNode,
+        arg_types: &[Ty],
+    ) -> Option<(usize, bool)> {
+        let state = self.functions.get(name)?;
+        state
+            .specializations
+            .iter()
+            .position(|s| s.arg_types == arg_types && s.params == params && s.body == *body)
+            .map(|index| (index, state.specializations[index].recursive))
+    }
+
+    /// Run a specialization found by [`NativeJit::find`] on argument words; `None` when it
+    /// bailed out and the call has to be interpreted.
+    pub(crate) fn run(
+        &mut self,
+        name: &str,
+        index: usize,
+        args: &[i64],
+        depth_left: usize,
+    ) -> Option<crate::interpreter::Value> {
+        let state = self.functions.get_mut(name)?;
+        let spec = &mut state.specializations[index];
+        let budget = depth_left.min(MAX_NATIVE_DEPTH) as i64;
+        let mut status = 0i64;
+        // SAFETY: `entry` was compiled for exactly these argument words (see `find`).
+        let result = crate::with_stack_headroom(|| unsafe {
+            (spec.entry)(args.as_ptr(), budget, &mut status)
+        });
+        if status == 0 {
+            return Some(spec.ret.value(result));
+        }
+        spec.bails += 1;
+        if spec.bails >= MAX_BAILS {
+            let types = spec.arg_types.clone();
+            state.specializations.remove(index);
+            state.rejected.push(types);
+        }
+        None
+    }
+
+    /// Compile `name` for `arg_types`; returns the index of the new specialization. Calls to
+    /// `name` inside `body` are compiled as recursion only if `self_calls` (the caller checked
+    /// that the name refers to this function there). On failure the types are remembered as
+    /// rejected.
+    pub(crate) fn compile(
+        &mut self,
+        name: &str,
+        params: &[String],
+        body: &AstNode,
+        arg_types: &[Ty],
+        self_calls: bool,
+    ) -> Result<usize, String> {
+        let result = self.compile_specialization(name, params, body, arg_types, self_calls);
+        let state = self.functions.entry(name.to_string()).or_default();
+        match result {
+            Ok(spec) => {
+                state.specializations.push(spec);
+                Ok(state.specializations.len() - 1)
+            }
+            Err(e) => {
+                state.rejected.push(arg_types.to_vec());
+                Err(e)
+            }
+        }
+    }
+
+    fn compile_specialization(
+        &mut self,
+        name: &str,
+        params: &[String],
+        body: &AstNode,
+        arg_types: &[Ty],
+        self_calls: bool,
+    ) -> Result<Specialization, String> {
+        if params.len() != arg_types.len() {
+            return Err(format!(
+                "'{}' called with the wrong number of arguments",
+                name
+            ));
+        }
+        let word = types::I64;
+        let mut sig = self.module.make_signature();
+        sig.params
+            .extend(std::iter::repeat_n(AbiParam::new(word), params.len() + 2));
+        sig.returns.push(AbiParam::new(word));
+        self.next_symbol += 1;
+        let symbol = format!("jade_{}_{}", name, self.next_symbol);
+        let func_id = self
+            .module
+            .declare_function(&symbol, Linkage::Local, &sig)
+            .map_err(|e| e.to_string())?;
+
+        // The result type of a recursive call is only known once the body is typed, so
+        // recursive functions are tried with each return type in turn.
+        let mut ret = None;
+        let mut recursive = false;
+        let mut result = Err(GenError::NeedsReturnType);
+        for assumed in [None, Some(Ty::Int), Some(Ty::Float), Some(Ty::Bool)] {
+            self.module.clear_context(&mut self.ctx);
+            self.ctx.func.signature = sig.clone();
+            let self_ref = self
+                .module
+                .declare_func_in_func(func_id, &mut self.ctx.func);
+            // A fresh builder context each time: a failed attempt leaves its context dirty.
+            let mut builder_ctx = FunctionBuilderContext::new();
+            let builder = FunctionBuilder::new(&mut self.ctx.func, &mut builder_ctx);
+            let self_ref = self_calls.then_some(self_ref);
+            let gen = FunctionGen::new(builder, name, arg_types, assumed, self_ref);
+            result = gen.finish(params, body);
+            match result {
+                Ok((ty, calls_self)) if assumed.is_none() || assumed == Some(ty) => {
+                    ret = Some(ty);
+                    recursive = calls_self;
+                    break;
+                }
+                // Without recursion the assumption plays no part, so retrying cannot help.
+                Err(GenError::Unsupported(_)) if assumed.is_none() => break,
+                _ => {}
+            }
+        }
+        let ret = match (ret, result) {
+            (Some(ret), _) => ret,
+            (None, Err(GenError::Unsupported(reason))) => return Err(reason),
+            _ => return Err(format!("could not infer the result type of '{}'", name)),
+        };
+        self.module
+            .define_function(func_id, &mut self.ctx)
+            .map_err(|e| format!("Native compilation of '{}' failed: {:?}", name, e))?;
+
+        // Entry point for the interpreter: unpacks the argument array.
+        self.module.clear_context(&mut self.ctx);
+        let mut entry_sig = self.module.make_signature();
+        entry_sig.params.extend([AbiParam::new(word); 3]);
+        entry_sig.returns.push(AbiParam::new(word));
+        let entry_id = self
+            .module
+            .declare_function(&format!("{}_entry", symbol), Linkage::Local, &entry_sig)
+            .map_err(|e| e.to_string())?;
+        self.ctx.func.signature = entry_sig;
+        let callee = self
+            .module
+            .declare_func_in_func(func_id, &mut self.ctx.func);
+        {
+            let mut builder_ctx = FunctionBuilderContext::new();
+            let mut b = FunctionBuilder::new(&mut self.ctx.func, &mut builder_ctx);
+            let block = b.create_block();
+            b.append_block_params_for_function_params(block);
+            b.switch_to_block(block);
+            b.seal_block(block);
+            let [args, budget, status] = b.block_params(block)[..] else {
+                unreachable!("entry signature has three parameters");
+            };
+            let mut call_args: Vec<Value> = (0..params.len())
+                .map(|i| {
+                    b.ins()
+                        .load(word, MemFlags::trusted(), args, (i * 8) as i32)
+                })
+                .collect();
+            call_args.extend([budget, status]);
+            let call = b.ins().call(callee, &call_args);
+            let result = b.inst_results(call)[0];
+            b.ins().return_(&[result]);
+            b.finalize();
+        }
+        self.module
+            .define_function(entry_id, &mut self.ctx)
+            .map_err(|e| format!("Native compilation of '{}' failed: {:?}", name, e))?;
+        self.module.clear_context(&mut self.ctx);
+        self.module
+            .finalize_definitions()
+            .map_err(|e| format!("Native compilation of '{}' failed: {}", name, e))?;
+
+        let code = self.module.get_finalized_function(entry_id);
+        // SAFETY: `code` was just compiled with the `Entry` signature.
+        let entry = unsafe { std::mem::transmute::<*const u8, Entry>(code) };
+        Ok(Specialization {
+            params: params.to_vec(),
+            body: body.clone(),
+            arg_types: arg_types.to_vec(),
+            ret,
+            recursive,
+            entry,
+            bails: 0,
+        })
+    }
+}
+
+enum GenError {
+    /// The function uses something native code cannot express.
+    Unsupported(String),
+    /// A recursive call needs the (not yet known) return type.
+    NeedsReturnType,
+}
+
+fn unsupported<T>(what: impl Into<String>) -> Result<T, GenError> {
+    Err(GenError::Unsupported(what.into()))
+}
+
+#[derive(Clone, Copy)]
+enum Binding {
+    Local(Variable, Ty),
+    /// A `for` variable after its loop; the interpreter only defines it if the loop ran.
+    Poisoned,
+}
+
+/// Typed value; `None` for statements without a usable value (loops, mismatched `if` arms).
+type Typed = Option<(Value, Ty)>;
+
+struct FunctionGen<'a> {
+    b: FunctionBuilder<'a>,
+    name: &'a str,
+    arg_types: &'a [Ty],
+    ret: Option<Ty>,
+    /// `None` when calls to `name` may not refer to this function.
+    self_ref: Option<FuncRef>,
+    calls_self: bool,
+    scopes: Vec<HashMap<String, Binding>>,
+    vars: usize,
+    /// (continue target, break target) of enclosing loops.
+    loops: Vec<(Block, Block)>,
+    bail: Block,
+    budget: Value,
+    status: Value,
+}
+
+impl<'a> FunctionGen<'a> {
+    fn new(
+        mut b: FunctionBuilder<'a>,
+        name: &'a str,
+        arg_types: &'a [Ty],
+        ret: Option<Ty>,
+        self_ref: Option<FuncRef>,
+    ) -> Self {
+        let entry = b.create_block();
+        b.append_block_params_for_function_params(entry);
+        b.switch_to_block(entry);
+        let words = b.block_params(entry).to_vec();
+        let bail = b.create_block();
+        Self {
+            b,
+            name,
+            arg_types,
+            ret,
+            self_ref,
+            calls_self: false,
+            scopes: vec![HashMap::new()],
+            vars: 0,
+            loops: Vec::new(),
+            bail,
+            budget: words[arg_types.len()],
+            status: words[arg_types.len() + 1],
+        }
+    }
+
+    /// Emit the whole function; returns its result type and whether it calls itself.
+    fn finish(mut self, params: &[String], body: &AstNode) -> Result<(Ty, bool), GenError> {
+        let entry = self.b.current_block().expect("entry block");
+        let words = self.b.block_params(entry).to_vec();
+        for ((param, ty), word) in params.iter().zip(self.arg_types).zip(words) {
+            let value = self.unpack(word, *ty);
+            self.declare(param, value, *ty);
+        }
+        let out_of_depth = self
+            .b
+            .ins()
+            .icmp_imm(IntCC::SignedLessThanOrEqual, self.budget, 0);
+        self.bail_if(out_of_depth);
+
+        let Some((value, ty)) = self.node(body)? else {
+            return unsupported(format!("'{}' has no numeric result", self.name));
+        };
+        let word = self.pack(value, ty);
+        self.b.ins().return_(&[word]);
+
+        self.b.switch_to_block(self.bail);
+        let one = self.b.ins().iconst(types::I64, 1);
+        self.b.ins().store(MemFlags::trusted(), one, self.status, 0);
+        let zero = self.b.ins().iconst(types::I64, 0);
+        self.b.ins().return_(&[zero]);
+        self.b.seal_all_blocks();
+        let calls_self = self.calls_self;
+        self.b.finalize();
+        Ok((ty, calls_self))
+    }
+
+    fn unpack(&mut self, word: Value, ty: Ty) -> Value {
+        match ty {
+            Ty::Int => word,
+            Ty::Float => self.b.ins().bitcast(types::F64, MemFlags::new(), word),
+            Ty::Bool => self.b.ins().icmp_imm(IntCC::NotEqual, word, 0),
+        }
+    }
+
+    fn pack(&mut self, value: Value, ty: Ty) -> Value {
+        match ty {
+            Ty::Int => value,
+            Ty::Float => self.b.ins().bitcast(types::I64, MemFlags::new(), value),
+            Ty::Bool => self.b.ins().uextend(types::I64, value),
+        }
+    }
+
+    /// Leave native code when `cond` holds.
+    fn bail_if(&mut self, cond: Value) {
+        let next = self.b.create_block();
+        self.b.ins().brif(cond, self.bail, &[], next, &[]);
+        self.b.switch_to_block(next);
+    }
+
+    fn declare(&mut self, name: &str, value: Value, ty: Ty) {
+        let var = Variable::new(self.vars);
+        self.vars += 1;
+        self.b.declare_var(var, ty.clif());
+        self.b.def_var(var, value);
+        let scope = self.scopes.last_mut().expect("function scope");
+        scope.insert(name.to_string(), Binding::Local(var, ty));
+    }
+
+    fn lookup(&self, name: &str) -> Result<(Variable, Ty), GenError> {
+        match self.scopes.iter().rev().find_map(|scope| scope.get(name)) {
+            Some(Binding::Local(var, ty)) => Ok((*var, *ty)),
+            Some(Binding::Poisoned) => {
+                unsupported(format!("loop variable '{}' read after its loop", name))
+            }
+            None => unsupported(format!("non-local name '{}'", name)),
+        }
+    }
+
+    fn typed(&mut self, node: &AstNode) -> Result<(Value, Ty), GenError> {
+        match self.node(node)? {
+            Some(typed) => Ok(typed),
+            None => unsupported("expression without a numeric value"),
+        }
+    }
+
+    fn node(&mut self, node: &AstNode) -> Result<Typed, GenError> {
+        Ok(match node {
+            AstNode::Integer(i) => Some((self.b.ins().iconst(types::I64, *i), Ty::Int)),
+            AstNode::Float(f) => Some((self.b.ins().f64const(*f), Ty::Float)),
+            AstNode::Boolean(b) => Some((self.b.ins().iconst(types::I8, *b as i64), Ty::Bool)),
+            AstNode::Identifier(name, _) => {
+                let (var, ty) = self.lookup(name)?;
+                Some((self.b.use_var(var), ty))
+            }
+            AstNode::Expression(inner) => self.node(inner)?,
+            AstNode::Return(Some(inner), _) => self.node(inner)?,
+            AstNode::Block(statements) => {
+                self.scopes.push(HashMap::new());
+                let mut last = None;
+                for statement in statements {
+                    last = self.node(statement)?;
+                }
+                self.scopes.pop();
+                last
+            }
+            AstNode::VarDeclaration {
+                var_type,
+                name,
+                value,
+                is_static,
+                type_modifier,
+                ..
+            } => {
+                if *is_static
+                    || type_modifier.is_some()
+                    || !PLAIN_TYPES.contains(&var_type.as_str())
+                {
+                    return unsupported(format!("declaration of '{}'", name));
+                }
+                let (value, ty) = self.typed(value)?;
+                self.declare(name, value, ty);
+                Some((value, ty))
+            }
+            AstNode::Assignment { name, value, .. } => {
+                let (value, ty) = self.typed(value)?;
+                let (var, var_ty) = self.lookup(name)?;
+                if ty != var_ty {
+                    return unsupported(format!("'{}' changes type", name));
+                }
+                self.b.def_var(var, value);
+                Some((value, ty))
+            }
+            AstNode::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } => Some(self.binary(left, operator, right)?),
+            AstNode::Unary { operator, operand, .. } => {
+                let (value, ty) = self.typed(operand)?;
+                Some(match (operator, ty) {
+                    (UnaryOp::Minus, Ty::Int) => {
+                        let min = self.b.ins().icmp_imm(IntCC::Equal, value, i64::MIN);
+                        self.bail_if(min);
+                        (self.b.ins().ineg(value), Ty::Int)
+                    }
+                    (UnaryOp::Minus, Ty::Float) => (self.b.ins().fneg(value), Ty::Float),
+                    (UnaryOp::Not, Ty::Bool) => (self.b.ins().bxor_imm(value, 1), Ty::Bool),
+                    (UnaryOp::BitwiseNot, Ty::Int) => (self.b.ins().bnot(value), Ty::Int),
+                    _ => return unsupported(format!("{:?} on {:?}", operator, ty)),
+                })
+            }
+            AstNode::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => self.if_expr(condition, then_branch, else_branch.as_deref())?,
+            AstNode::While { condition, body, .. } => {
+                let header = self.b.create_block();
+                let body_block = self.b.create_block();
+                let exit = self.b.create_block();
+                self.b.ins().jump(header, &[]);
+                self.b.switch_to_block(header);
+                let cond = self.condition(condition)?;
+                self.b.ins().brif(cond, body_block, &[], exit, &[]);
+                self.b.switch_to_block(body_block);
+                self.loops.push((header, exit));
+                self.node(body)?;
+                self.loops.pop();
+                self.b.ins().jump(header, &[]);
+                self.b.switch_to_block(exit);
+                None
+            }
+            AstNode::For {
+                var,
+                iterable,
+                body,
+                ..
+            } => {
+                self.for_range(var, iterable, body)?;
+                None
+            }
+            AstNode::Break(_) | AstNode::Continue(_) => {
+                let Some(&(next, exit)) = self.loops.last() else {
+                    return unsupported("break or continue outside a loop");
+                };
+                let target = if matches!(node, AstNode::Break(_)) {
+                    exit
+                } else {
+                    next
+                };
+                self.b.ins().jump(target, &[]);
+                // Anything after the jump is dead but still needs a block.
+                let dead = self.b.create_block();
+                self.b.switch_to_block(dead);
+                None
+            }
+            AstNode::FunctionCall { name, args, .. } if name == self.name => {
+                Some(self.self_call(args)?)
+            }
+            other => return unsupported(format!("{:?}", std::mem::discriminant(other))),
+        })
+    }
+
+    /// Truthiness of a condition, as an `i8`.
+    fn condition(&mut self, node: &AstNode) -> Result<Value, GenError> {
+        let (value, ty) = self.typed(node)?;
+        Ok(match ty {
+            Ty::Bool => value,
+            Ty::Int => self.b.ins().icmp_imm(IntCC::NotEqual, value, 0),
+            Ty::Float => {
+                let zero = self.b.ins().f64const(0.0);
+                self.b.ins().fcmp(FloatCC::NotEqual, value, zero)
+            }
+        })
+    }
+
+    fn if_expr(
+        &mut self,
+        condition: &AstNode,
+        then_branch: &AstNode,
+        else_branch: Option<&AstNode>,
+    ) -> Result<Typed, GenError> {
+        let cond = self.condition(condition)?;
+        let then_block = self.b.create_block();
+        let else_block = self.b.create_block();
+        let merge = self.b.create_block();
+        self.b.ins().brif(cond, then_block, &[], else_block, &[]);
+
+        // The result goes through a variable so its type can be decided after the first arm.
+        self.b.switch_to_block(then_block);
+        let then_value = self.node(then_branch)?;
+        let result = then_value.map(|(value, ty)| {
+            let var = Variable::new(self.vars);
+            self.vars += 1;
+            self.b.declare_var(var, ty.clif());
+            self.b.def_var(var, value);
+            (var, ty)
+        });
+        self.b.ins().jump(merge, &[]);
+
+        self.b.switch_to_block(else_block);
+        let else_value = match else_branch {
+            Some(node) => self.node(node)?,
+            None => None,
+        };
+        let merged = match (result, else_value) {
+            (Some((var, ty)), Some((value, else_ty))) if ty == else_ty => {
+                self.b.def_var(var, value);
+                Some((var, ty))
+            }
+            _ => None,
+        };
+        self.b.ins().jump(merge, &[]);
+
+        self.b.switch_to_block(merge);
+        Ok(merged.map(|(var, ty)| (self.b.use_var(var), ty)))
+    }
+
+    /// `for var in start..end` (optionally inclusive, with a step) without building the list.
+    fn for_range(&mut self, var: &str, iterable: &AstNode, body: &AstNode) -> Result<(), GenError> {
+        let AstNode::Range {
+            start,
+            end,
+            inclusive,
+            step,
+            ..
+        } = iterable
+        else {
+            return unsupported("for over anything but a range");
+        };
+        let (start, start_ty) = self.typed(start)?;
+        let (end, end_ty) = self.typed(end)?;
+        let step = match step {
+            Some(step) => self.typed(step)?,
+            None => (self.b.ins().iconst(types::I64, 1), Ty::Int),
+        };
+        if (start_ty, end_ty, step.1) != (Ty::Int, Ty::Int, Ty::Int) {
+            return unsupported("range with non-integer bounds");
+        }
+        let step = step.0;
+        let zero_step = self.b.ins().icmp_imm(IntCC::Equal, step, 0);
+        self.bail_if(zero_step);
+
+        let current = Variable::new(self.vars);
+        self.vars += 1;
+        self.b.declare_var(current, types::I64);
+        self.b.def_var(current, start);
+
+        let header = self.b.create_block();
+        let body_block = self.b.create_block();
+        let next = self.b.create_block();
+        let exit = self.b.create_block();
+        self.b.ins().jump(header, &[]);
+
+        self.b.switch_to_block(header);
+        let at = self.b.use_var(current);
+        let upward = self.b.ins().icmp_imm(IntCC::SignedGreaterThan, step, 0);
+        let below = self.b.ins().icmp(IntCC::SignedLessThan, at, end);
+        let above = self.b.ins().icmp(IntCC::SignedGreaterThan, at, end);
+        let before_end = self.b.ins().select(upward, below, above);
+        let keep_going = if *inclusive {
+            let at_end = self.b.ins().icmp(IntCC::Equal, at, end);
+            self.b.ins().bor(before_end, at_end)
+        } else {
+            before_end
+        };
+        self.b.ins().brif(keep_going, body_block, &[], exit, &[]);
+
+        self.b.switch_to_block(body_block);
+        // The interpreter assigns the loop variable in the enclosing scope.
+        let existing = self.scopes.last().and_then(|scope| scope.get(var)).copied();
+        match existing {
+            Some(Binding::Local(loop_var, Ty::Int)) => self.b.def_var(loop_var, at),
+            Some(Binding::Local(..)) => return unsupported(format!("'{}' changes type", var)),
+            _ => self.declare(var, at, Ty::Int),
+        }
+        self.loops.push((next, exit));
+        self.node(body)?;
+        self.loops.pop();
+        self.b.ins().jump(next, &[]);
+
+        self.b.switch_to_block(next);
+        let at = self.b.use_var(current);
+        let (advanced, overflow) = self.b.ins().sadd_overflow(at, step);
+        self.bail_if(overflow);
+        self.b.def_var(current, advanced);
+        self.b.ins().jump(header, &[]);
+
+        self.b.switch_to_block(exit);
+        if !matches!(existing, Some(Binding::Local(..))) {
+            let scope = self.scopes.last_mut().expect("function scope");
+            scope.insert(var.to_string(), Binding::Poisoned);
+        }
+        Ok(())
+    }
+
+    fn self_call(&mut self, args: &[AstNode]) -> Result<(Value, Ty), GenError> {
+        let Some(self_ref) = self.self_ref else {
+            return unsupported(format!(
+                "call to '{}' from a scope where it means something else",
+                self.name
+            ));
+        };
+        if self
+            .scopes
+            .iter()
+            .any(|scope| scope.contains_key(self.name))
+        {
+            return unsupported(format!("'{}' is shadowed by a local", self.name));
+        }
+        if args.len() != self.arg_types.len() {
+            return unsupported(format!(
+                "'{}' called with the wrong number of arguments",
+                self.name
+            ));
+        }
+        let ret = self.ret.ok_or(GenError::NeedsReturnType)?;
+        let mut words = Vec::with_capacity(args.len() + 2);
+        for (arg, expected) in args.iter().zip(self.arg_types) {
+            let (value, ty) = self.typed(arg)?;
+            if ty != *expected {
+                return unsupported(format!("'{}' called with other argument types", self.name));
+            }
+            words.push(self.pack(value, ty));
+        }
+        let budget = self.b.ins().iadd_imm(self.budget, -1);
+        words.extend([budget, self.status]);
+        let call = self.b.ins().call(self_ref, &words);
+        self.calls_self = true;
+        let result = self.b.inst_results(call)[0];
+        let status = self
+            .b
+            .ins()
+            .load(types::I64, MemFlags::trusted(), self.status, 0);
+        self.bail_if(status);
+        Ok((self.unpack(result, ret), ret))
+    }
+
+    fn binary(
+        &mut self,
+        left: &AstNode,
+        op: &BinaryOp,
+        right: &AstNode,
+    ) -> Result<(Value, Ty), GenError> {
+        if matches!(op, BinaryOp::And | BinaryOp::Or) {
+            return self.logical(left, op, right);
+        }
+        let (a, a_ty) = self.typed(left)?;
+        let (b, b_ty) = self.typed(right)?;
+        use BinaryOp::*;
+        Ok(match (a_ty, b_ty) {
+            (Ty::Int, Ty::Int) => match op {
+                Add | Subtract | Multiply => {
+                    let (value, overflow) = match op {
+                        Add => self.b.ins().sadd_overflow(a, b),
+                        Subtract => self.b.ins().ssub_overflow(a, b),
+                        _ => self.b.ins().smul_overflow(a, b),
+                    };
+                    self.bail_if(overflow);
+                    (value, Ty::Int)
+                }
+                Divide | Modulo => {
+                    let zero = self.b.ins().icmp_imm(IntCC::Equal, b, 0);
+                    self.bail_if(zero);
+                    let min = self.b.ins().icmp_imm(IntCC::Equal, a, i64::MIN);
+                    let minus_one = self.b.ins().icmp_imm(IntCC::Equal, b, -1);
+                    let overflow = self.b.ins().band(min, minus_one);
+                    if matches!(op, Divide) {
+                        self.bail_if(overflow);
+                        (self.b.ins().sdiv(a, b), Ty::Int)
+                    } else {
+                        // i64::MIN % -1 is 0; dividing by 1 instead gives that without trapping.
+                        let one = self.b.ins().iconst(types::I64, 1);
+                        let divisor = self.b.ins().select(overflow, one, b);
+                        (self.b.ins().srem(a, divisor), Ty::Int)
+                    }
+                }
+                Equal => (self.b.ins().icmp(IntCC::Equal, a, b), Ty::Bool),
+                Less => (self.b.ins().icmp(IntCC::SignedLessThan, a, b), Ty::Bool),
+                Greater => (self.b.ins().icmp(IntCC::SignedGreaterThan, a, b), Ty::Bool),
+                LessEqual => (
+                    self.b.ins().icmp(IntCC::SignedLessThanOrEqual, a, b),
+                    Ty::Bool,
+                ),
+                GreaterEqual => (
+                    self.b.ins().icmp(IntCC::SignedGreaterThanOrEqual, a, b),
+                    Ty::Bool,
+                ),
+                BitwiseAnd => (self.b.ins().band(a, b), Ty::Int),
+                BitwiseOr => (self.b.ins().bor(a, b), Ty::Int),
+                BitwiseXor => (self.b.ins().bxor(a, b), Ty::Int),
+                LeftShift | RightShift => {
+                    let out_of_range =
+                        self.b
+                            .ins()
+                            .icmp_imm(IntCC::UnsignedGreaterThanOrEqual, b, 64);
+                    self.bail_if(out_of_range);
+                    if matches!(op, LeftShift) {
+                        (self.b.ins().ishl(a, b), Ty::Int)
+                    } else {
+                        (self.b.ins().sshr(a, b), Ty::Int)
+                    }
+                }
+                _ => return unsupported(format!("{:?} on integers", op)),
+            },
+            (Ty::Bool, Ty::Bool) if matches!(op, Equal) => {
+                (self.b.ins().icmp(IntCC::Equal, a, b), Ty::Bool)
+            }
+            (Ty::Float, Ty::Float) | (Ty::Int, Ty::Float) | (Ty::Float, Ty::Int) => {
+                let mixed = a_ty != b_ty;
+                if matches!(op, Divide) && b_ty == Ty::Int {
+                    let zero = self.b.ins().icmp_imm(IntCC::Equal, b, 0);
+                    self.bail_if(zero);
+                }
+                let a = self.widen(a, a_ty);
+                let b = self.widen(b, b_ty);
+                if matches!(op, Divide) && a_ty == Ty::Int {
+                    let zero = self.b.ins().f64const(0.0);
+                    let is_zero = self.b.ins().fcmp(FloatCC::Equal, b, zero);
+                    self.bail_if(is_zero);
+                }
+                match op {
+                    Add => (self.b.ins().fadd(a, b), Ty::Float),
+                    Subtract => (self.b.ins().fsub(a, b), Ty::Float),
+                    Multiply => (self.b.ins().fmul(a, b), Ty::Float),
+                    Divide => (self.b.ins().fdiv(a, b), Ty::Float),
+                    // Mixed equality in the interpreter is approximate.
+                    Equal if !mixed => (self.b.ins().fcmp(FloatCC::Equal, a, b), Ty::Bool),
+                    Less => (self.b.ins().fcmp(FloatCC::LessThan, a, b), Ty::Bool),
+                    Greater => (self.b.ins().fcmp(FloatCC::GreaterThan, a, b), Ty::Bool),
+                    LessEqual => (self.b.ins().fcmp(FloatCC::LessThanOrEqual, a, b), Ty::Bool),
+                    GreaterEqual => (
+                        self.b.ins().fcmp(FloatCC::GreaterThanOrEqual, a, b),
+                        Ty::Bool,
+                    ),
+                    _ => return unsupported(format!("{:?} on floats", op)),
+                }
+            }
+            _ => return unsupported(format!("{:?} on {:?} and {:?}", op, a_ty, b_ty)),
+        })
+    }
+
+    fn widen(&mut self, value: Value, ty: Ty) -> Value {
+        match ty {
+            Ty::Int => self.b.ins().fcvt_from_sint(types::F64, value),
+            _ => value,
+        }
+    }
+
+    /// Short-circuiting `and`/`or`; both operands must be bools, as in the interpreter.
+    fn logical(
+        &mut self,
+        left: &AstNode,
+        op: &BinaryOp,
+        right: &AstNode,
+    ) -> Result<(Value, Ty), GenError> {
+        let (a, a_ty) = self.typed(left)?;
+        if a_ty != Ty::Bool {
+            return unsupported("and/or on non-bool operands");
+        }
+        let result = Variable::new(self.vars);
+        self.vars += 1;
+        self.b.declare_var(result, types::I8);
+        self.b.def_var(result, a);
+        let rhs = self.b.create_block();
+        let merge = self.b.create_block();
+        if matches!(op, BinaryOp::And) {
+            self.b.ins().brif(a, rhs, &[], merge, &[]);
+        } else {
+            self.b.ins().brif(a, merge, &[], rhs, &[]);
+        }
+        self.b.switch_to_block(rhs);
+        let (b, b_ty) = self.typed(right)?;
+        if b_ty != Ty::Bool {
+            return unsupported("and/or on non-bool operands");
+        }
+        self.b.def_var(result, b);
+        self.b.ins().jump(merge, &[]);
+        self.b.switch_to_block(merge);
+        Ok((self.b.use_var(result), Ty::Bool))
+    }
+}
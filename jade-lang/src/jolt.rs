@@ -1,5 +1,6 @@
 //! Jolt package manager: project init, dependencies, scripts, publish.
 
+use crate::lint::LintLevel;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -36,6 +37,9 @@ pub struct JoltManifest {
     pub main: Option<String>,
     pub bin: Option<HashMap<String, String>>,
     pub files: Vec<String>,
+    /// `[lint]`: level per `jade lint` rule, e.g. `unused-variable = "allow"`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub lint: HashMap<String, LintLevel>,
 }
 
 /// Manifest file names (in order of preference).
@@ -58,6 +62,7 @@ impl Default for JoltManifest {
             main: Some("main.jdl".to_string()),
             bin: None,
             files: vec!["*.jdl".to_string(), "README.md".to_string()],
+            lint: HashMap::new(),
         }
     }
 }
@@ -3,7 +3,7 @@
 //! Converts source text into a stream of tokens (keywords, literals, operators, etc.)
 //! with line/column tracking for error reporting.
 
-use crate::error::JError;
+use crate::error::{ErrorKind, JError, JResult};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
@@ -237,6 +237,7 @@ pub enum TokenType {
     // Special
     Newline,
     Eof,
+    Comment, // `# ...` or `// ...`, only kept by `tokenize_with_comments`
 
     // Range operators
     Range,          // ..
@@ -327,6 +328,9 @@ pub struct Token {
     pub lexeme: String,
     pub line: usize,
     pub column: usize,
+    /// Text of the `##` or `///` doc comment on the lines right above this token, if it is
+    /// the first token after them; the parser attaches it to declarations.
+    pub doc: Option<String>,
 }
 
 impl Token {
@@ -336,10 +340,24 @@ impl Token {
             lexeme,
             line,
             column,
+            doc: None,
         }
     }
 }
 
+/// The text of a doc comment (`## text` or `/// text`), or `None` for other comments.
+/// Comments of three or more `#` (or four or more `/`) are rulers, not docs.
+fn doc_comment_text(comment: &str) -> Option<&str> {
+    let text = match comment.strip_prefix("///") {
+        Some(text) => text,
+        None => comment.strip_prefix("##")?,
+    };
+    if text.starts_with(['#', '/']) {
+        return None;
+    }
+    Some(text.strip_prefix(' ').unwrap_or(text).trim_end())
+}
+
 pub struct Lexer {
     input: Vec<char>,
     current: usize,
@@ -359,8 +377,36 @@ impl Lexer {
         }
     }
 
-    pub fn tokenize(&mut self) -> Result<Vec<Token>, String> {
-        let mut tokens = Vec::new();
+    pub fn tokenize(&mut self) -> JResult<Vec<Token>> {
+        let (tokens, errors) = self.scan_tokens(false, false);
+        match errors.into_iter().next() {
+            Some(error) => Err(Box::new(error)),
+            None => Ok(tokens),
+        }
+    }
+
+    /// Like [`Lexer::tokenize`], but keeps each `#` and `//` comment as a
+    /// [`TokenType::Comment`] token whose lexeme is the comment text, for tools that rewrite
+    /// source (e.g. `jade fmt`). The parser does not accept comment tokens.
+    pub fn tokenize_with_comments(&mut self) -> JResult<Vec<Token>> {
+        let (tokens, errors) = self.scan_tokens(false, true);
+        match errors.into_iter().next() {
+            Some(error) => Err(Box::new(error)),
+            None => Ok(tokens),
+        }
+    }
+
+    /// Like [`Lexer::tokenize`], but skips invalid input and carries on, so that every lexical
+    /// error is reported (e.g. by `jade check`).
+    pub fn tokenize_recovering(&mut self) -> (Vec<Token>, Vec<JError>) {
+        self.scan_tokens(true, false)
+    }
+
+    fn scan_tokens(&mut self, recover: bool, keep_comments: bool) -> (Vec<Token>, Vec<JError>) {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut errors = Vec::new();
+        // Doc comment lines so far, and the line of the last one
+        let mut doc: Option<(String, usize)> = None;
 
         while !self.is_at_end() {
             self.skip_whitespace();
@@ -373,11 +419,63 @@ impl Lexer {
             let start_line = self.line;
             let start_column = self.column;
 
-            if let Some(token_type) = self.scan_token()? {
-                let lexeme = self.get_lexeme_from_current();
-                tokens.push(Token::new(token_type, lexeme, start_line, start_column));
+            match self.scan_token() {
+                Ok(Some(TokenType::Comment)) => {
+                    let lexeme = self.get_lexeme_from_current();
+                    let starts_line = tokens.last().is_none_or(|t| t.line < start_line);
+                    doc = match doc_comment_text(&lexeme) {
+                        Some(text) if starts_line => match doc.take() {
+                            Some((mut lines, line)) if line + 1 == start_line => {
+                                lines.push('\n');
+                                lines.push_str(text);
+                                Some((lines, start_line))
+                            }
+                            _ => Some((text.to_string(), start_line)),
+                        },
+                        _ => None,
+                    };
+                    if keep_comments {
+                        tokens.push(Token::new(
+                            TokenType::Comment,
+                            lexeme,
+                            start_line,
+                            start_column,
+                        ));
+                    }
+                }
+                Ok(Some(token_type)) => {
+                    let lexeme = self.get_lexeme_from_current();
+                    let mut token = Token::new(token_type, lexeme, start_line, start_column);
+                    if token.token_type != TokenType::Newline {
+                        token.doc = doc
+                            .take()
+                            .filter(|(_, line)| line + 1 == start_line)
+                            .map(|(lines, _)| lines);
+                    }
+                    tokens.push(token);
+                }
+                // None => skip whitespace
+                Ok(None) => {}
+                Err(error) => {
+                    if self.line != start_line {
+                        // An unterminated string swallowed the rest of the input; end the
+                        // error at the end of its first line and resume on the next one.
+                        self.current = self.start;
+                        self.line = start_line;
+                        self.column = start_column;
+                        while !self.is_at_end() && self.peek() != '\n' {
+                            self.advance();
+                        }
+                    }
+                    errors.push(self.locate(*error, start_line, start_column));
+                    if !recover {
+                        return (tokens, errors);
+                    }
+                    if self.current == self.start {
+                        self.advance();
+                    }
+                }
             }
-            // None => skip whitespace/comments
         }
 
         tokens.push(Token::new(
@@ -386,10 +484,25 @@ impl Lexer {
             self.line,
             self.column,
         ));
-        Ok(tokens)
+        (tokens, errors)
+    }
+
+    /// Point an error at the text that raised it, from where its token starts to where the
+    /// lexer stopped.
+    fn locate(&self, error: JError, line: usize, column: usize) -> JError {
+        let error = match error.kind {
+            ErrorKind::UnexpectedCharacter
+            | ErrorKind::UnterminatedString
+            | ErrorKind::InvalidNumber
+            | ErrorKind::InvalidEscape => error,
+            _ => JError::lexical(ErrorKind::UnexpectedCharacter, error.message),
+        };
+        error
+            .with_location(line, column)
+            .with_end(self.line, self.column)
     }
 
-    fn scan_token(&mut self) -> Result<Option<TokenType>, String> {
+    fn scan_token(&mut self) -> JResult<Option<TokenType>> {
         let c = self.advance();
 
         match c {
@@ -422,7 +535,7 @@ impl Lexer {
                     while self.peek() != '\n' && !self.is_at_end() {
                         self.advance();
                     }
-                    Ok(None)
+                    Ok(Some(TokenType::Comment))
                 } else {
                     Ok(Some(TokenType::Divide))
                 }
@@ -528,7 +641,7 @@ impl Lexer {
                     while self.peek() != '\n' && !self.is_at_end() {
                         self.advance();
                     }
-                    Ok(None)
+                    Ok(Some(TokenType::Comment))
                 }
             }
 
@@ -565,7 +678,7 @@ impl Lexer {
             }
 
             _ => Err(JError::new(
-                crate::error::ErrorKind::UnexpectedCharacter,
+                ErrorKind::UnexpectedCharacter,
                 format!("Unexpected character '{}'", c),
             )
             .with_location(self.line, self.column)
@@ -573,11 +686,11 @@ impl Lexer {
             .with_solution(
                 "Remove this character or check if you meant to use a different symbol".to_string(),
             )
-            .to_string()),
+            .into()),
         }
     }
 
-    fn scan_string(&mut self) -> Result<Option<TokenType>, String> {
+    fn scan_string(&mut self) -> JResult<Option<TokenType>> {
         let mut value = String::new();
 
         while self.peek() != '"' && !self.is_at_end() {
@@ -627,7 +740,7 @@ impl Lexer {
                             };
                             value.push_str(ansi_code);
                         } else {
-                            return Err("Unterminated color escape sequence".to_string());
+                            return Err(JError::lexical(ErrorKind::InvalidEscape, "Unterminated color escape sequence").into());
                         }
                     }
                     // Emoji shortcuts: \emoji{fire}, \emoji{heart}, etc.
@@ -663,7 +776,7 @@ impl Lexer {
                                 };
                                 value.push_str(emoji);
                             } else {
-                                return Err("Unterminated emoji escape sequence".to_string());
+                                return Err(JError::lexical(ErrorKind::InvalidEscape, "Unterminated emoji escape sequence").into());
                             }
                         } else {
                             value.push('e'); // not an emoji escape
@@ -679,7 +792,7 @@ impl Lexer {
                             if self.peek().is_ascii_hexdigit() {
                                 hex.push(self.advance());
                             } else {
-                                return Err("Invalid Unicode escape sequence".to_string());
+                                return Err(JError::lexical(ErrorKind::InvalidEscape, "Invalid Unicode escape sequence").into());
                             }
                         }
                         if self.peek() == '}' {
@@ -688,13 +801,13 @@ impl Lexer {
                                 if let Some(ch) = char::from_u32(code_point) {
                                     value.push(ch);
                                 } else {
-                                    return Err("Invalid Unicode code point".to_string());
+                                    return Err(JError::lexical(ErrorKind::InvalidEscape, "Invalid Unicode code point").into());
                                 }
                             } else {
-                                return Err("Invalid Unicode escape sequence".to_string());
+                                return Err(JError::lexical(ErrorKind::InvalidEscape, "Invalid Unicode escape sequence").into());
                             }
                         } else {
-                            return Err("Unterminated Unicode escape sequence".to_string());
+                            return Err(JError::lexical(ErrorKind::InvalidEscape, "Unterminated Unicode escape sequence").into());
                         }
                     }
                     // Hex escape: \xHH
@@ -704,13 +817,13 @@ impl Lexer {
                             if self.peek().is_ascii_hexdigit() {
                                 hex.push(self.advance());
                             } else {
-                                return Err("Invalid hex escape sequence".to_string());
+                                return Err(JError::lexical(ErrorKind::InvalidEscape, "Invalid hex escape sequence").into());
                             }
                         }
                         if let Ok(byte) = u8::from_str_radix(&hex, 16) {
                             value.push(byte as char);
                         } else {
-                            return Err("Invalid hex escape sequence".to_string());
+                            return Err(JError::lexical(ErrorKind::InvalidEscape, "Invalid hex escape sequence").into());
                         }
                     }
                     c => value.push(c),
@@ -721,29 +834,29 @@ impl Lexer {
         }
 
         if self.is_at_end() {
-            return Err(JError::unterminated_string(self.line, self.column).to_string());
+            return Err(JError::unterminated_string(self.line, self.column).into());
         }
 
         self.advance(); // closing quote
         Ok(Some(TokenType::String(value)))
     }
 
-    fn scan_char(&mut self) -> Result<Option<TokenType>, String> {
+    fn scan_char(&mut self) -> JResult<Option<TokenType>> {
         if self.is_at_end() {
-            return Err("Unterminated character literal".to_string());
+            return Err(JError::lexical(ErrorKind::UnexpectedCharacter, "Unterminated character literal").into());
         }
 
         let c = self.advance();
 
         if self.peek() != '\'' {
-            return Err("Character literal must contain exactly one character".to_string());
+            return Err(JError::lexical(ErrorKind::UnexpectedCharacter, "Character literal must contain exactly one character").into());
         }
 
         self.advance(); // closing quote
         Ok(Some(TokenType::Char(c)))
     }
 
-    fn scan_number_or_date_time(&mut self) -> Result<Option<TokenType>, String> {
+    fn scan_number_or_date_time(&mut self) -> JResult<Option<TokenType>> {
         // Check if this might be a date (YYYY-MM-DD format)
         if self.current + 9 < self.input.len()
             && self.input[self.current + 4] == '-'
@@ -776,22 +889,22 @@ impl Lexer {
             let value: f64 = self
                 .get_lexeme_from_current()
                 .parse()
-                .map_err(|_| "Invalid float literal")?;
+                .map_err(|_| JError::lexical(ErrorKind::InvalidNumber, "Invalid float literal"))?;
             Ok(Some(TokenType::Float(value)))
         } else {
             let value: i64 = self
                 .get_lexeme_from_current()
                 .parse()
-                .map_err(|_| "Invalid integer literal")?;
+                .map_err(|_| JError::lexical(ErrorKind::InvalidNumber, "Invalid integer literal"))?;
             Ok(Some(TokenType::Integer(value)))
         }
     }
 
-    fn scan_date_time(&mut self) -> Result<Option<TokenType>, String> {
+    fn scan_date_time(&mut self) -> JResult<Option<TokenType>> {
         // Scan YYYY-MM-DD
         for _ in 0..10 {
             if self.is_at_end() {
-                return Err("Invalid date format".to_string());
+                return Err(JError::lexical(ErrorKind::InvalidNumber, "Invalid date format").into());
             }
             self.advance();
         }
@@ -803,7 +916,7 @@ impl Lexer {
             // Scan HH:MM:SS
             for _ in 0..8 {
                 if self.is_at_end() {
-                    return Err("Invalid datetime format".to_string());
+                    return Err(JError::lexical(ErrorKind::InvalidNumber, "Invalid datetime format").into());
                 }
                 self.advance();
             }
@@ -816,11 +929,11 @@ impl Lexer {
         }
     }
 
-    fn scan_time(&mut self) -> Result<Option<TokenType>, String> {
+    fn scan_time(&mut self) -> JResult<Option<TokenType>> {
         // Scan HH:MM:SS
         for _ in 0..8 {
             if self.is_at_end() {
-                return Err("Invalid time format".to_string());
+                return Err(JError::lexical(ErrorKind::InvalidNumber, "Invalid time format").into());
             }
             self.advance();
         }
@@ -829,7 +942,7 @@ impl Lexer {
         Ok(Some(TokenType::Time(time)))
     }
 
-    fn scan_money(&mut self, currency_char: char) -> Result<Option<TokenType>, String> {
+    fn scan_money(&mut self, currency_char: char) -> JResult<Option<TokenType>> {
         let currency_symbol = currency_char.to_string();
 
         // Skip whitespace after currency symbol
@@ -859,18 +972,18 @@ impl Lexer {
         }
 
         if !has_digits {
-            return Err("Invalid money format - no digits found".to_string());
+            return Err(JError::lexical(ErrorKind::InvalidNumber, "Invalid money format - no digits found").into());
         }
 
         let amount_str = self.input[self.start + 1..self.current]
             .iter()
             .collect::<String>();
-        let amount: f64 = amount_str.parse().map_err(|_| "Invalid money amount")?;
+        let amount: f64 = amount_str.parse().map_err(|_| JError::lexical(ErrorKind::InvalidNumber, "Invalid money amount"))?;
 
         Ok(Some(TokenType::Money(currency_symbol, amount)))
     }
 
-    fn scan_identifier_or_keyword(&mut self) -> Result<Option<TokenType>, String> {
+    fn scan_identifier_or_keyword(&mut self) -> JResult<Option<TokenType>> {
         while self.is_alphanumeric(self.peek()) {
             self.advance();
         }
@@ -1106,7 +1219,7 @@ impl Lexer {
         Ok(Some(token_type))
     }
 
-    fn scan_hex_color(&mut self) -> Result<Option<TokenType>, String> {
+    fn scan_hex_color(&mut self) -> JResult<Option<TokenType>> {
         let mut hex = String::from("#");
 
         while self.is_hex_digit(self.peek()) && hex.len() < 9 {
@@ -1115,7 +1228,7 @@ impl Lexer {
         }
 
         if hex.len() != 4 && hex.len() != 7 && hex.len() != 9 {
-            return Err("Invalid hex color format".to_string());
+            return Err(JError::lexical(ErrorKind::InvalidNumber, "Invalid hex color format").into());
         }
 
         Ok(Some(TokenType::Hex(hex)))
@@ -1,26 +1,47 @@
 //! Jade Programming Language — core library.
 //!
 //! This crate provides the lexer, parser, interpreter, and tooling for the Jade language.
-//! The `jade` binary uses this library for REPL, run, build, check, and jolt commands.
+//! The `jade` binary uses this library for REPL, run, build, check, test, and jolt commands.
 
+pub mod bundle;
+pub mod bytecode;
 pub mod compiler;
+pub mod debugger;
+pub mod diagnostics;
+pub mod doc;
 pub mod error;
+pub mod formatter;
 pub mod interpreter;
 pub mod jit;
 pub mod jolt;
 pub mod lexer;
+pub mod lint;
+pub mod lsp;
 pub mod parser;
 pub mod repl;
 pub mod runtime;
+pub mod testing;
 
 // Re-export main types for consumers of the library
-pub use error::JError;
+pub use error::{JError, JResult};
 pub use interpreter::Interpreter;
 pub use lexer::{Lexer, Token, TokenType};
 pub use parser::Parser;
 
+/// Native stack headroom kept free by [`with_stack_headroom`].
+const STACK_RED_ZONE: usize = 2 * 1024 * 1024;
+/// Size of each heap-allocated stack segment.
+const STACK_GROWTH: usize = 16 * 1024 * 1024;
+
+/// Run `f`, first switching to a fresh heap-allocated stack segment if the native stack is
+/// nearly exhausted. The parser and interpreter recurse through this, so deeply nested or
+/// recursive programs cannot overflow the stack of whatever thread runs them.
+pub(crate) fn with_stack_headroom<R>(f: impl FnOnce() -> R) -> R {
+    stacker::maybe_grow(STACK_RED_ZONE, STACK_GROWTH, f)
+}
+
 /// Run Jade source and return captured output as a string.
-pub fn run_source_to_string(source: &str) -> Result<String, String> {
+pub fn run_source_to_string(source: &str) -> JResult<String> {
     let source = source.replace("\r\n", "\n").replace('\r', "\n");
     let mut interpreter = Interpreter::new();
     interpreter.set_output_capture(true);
@@ -0,0 +1,766 @@
+//! Static checks for `jade lint`: unused variables and imports, shadowed variables, unreachable
+//! code, assignments to immutable variables and calls to unknown functions, found by walking the
+//! AST without running the program.
+//!
+//! Scopes follow the interpreter's: the top level is the global scope, and every block, function
+//! body, loop and catch clause opens a local one. Top-level variables are a module's exports, so
+//! only locals are reported as unused or shadowed.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{find_similar_names, JResult};
+use crate::interpreter::{Interpreter, BUILTIN_NAMES};
+use crate::lexer::Lexer;
+use crate::parser::{AstNode, Parser, Pattern, Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    UnusedVariable,
+    UnusedImport,
+    ShadowedVariable,
+    UnreachableCode,
+    ImmutableAssignment,
+    UnknownFunction,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 6] = [
+        Rule::UnusedVariable,
+        Rule::UnusedImport,
+        Rule::ShadowedVariable,
+        Rule::UnreachableCode,
+        Rule::ImmutableAssignment,
+        Rule::UnknownFunction,
+    ];
+
+    /// Name in reports and in the `[lint]` table of `jade.toml`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::UnusedVariable => "unused-variable",
+            Rule::UnusedImport => "unused-import",
+            Rule::ShadowedVariable => "shadowed-variable",
+            Rule::UnreachableCode => "unreachable-code",
+            Rule::ImmutableAssignment => "immutable-assignment",
+            Rule::UnknownFunction => "unknown-function",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Rule> {
+        Rule::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+
+    /// Mistakes that fail or misbehave at runtime are errors; the rest are warnings.
+    pub fn default_level(self) -> LintLevel {
+        match self {
+            Rule::ImmutableAssignment | Rule::UnknownFunction => LintLevel::Deny,
+            _ => LintLevel::Warn,
+        }
+    }
+}
+
+/// How a rule's findings are treated: ignored, reported, or reported as errors that make
+/// `jade lint` exit with status 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+/// The level of every rule: its default unless the project's `[lint]` table sets it.
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    levels: HashMap<Rule, LintLevel>,
+}
+
+impl LintConfig {
+    /// Levels from a manifest's `[lint]` table; unknown rule names are an error.
+    pub fn from_table(table: &HashMap<String, LintLevel>) -> Result<Self, String> {
+        let mut config = Self::default();
+        for (name, level) in table {
+            let rule = Rule::from_name(name).ok_or_else(|| {
+                let names: Vec<_> = Rule::ALL.iter().map(|rule| rule.name()).collect();
+                format!(
+                    "Unknown lint rule '{}' in [lint]; expected one of: {}",
+                    name,
+                    names.join(", ")
+                )
+            })?;
+            config.levels.insert(rule, *level);
+        }
+        Ok(config)
+    }
+
+    pub fn level(&self, rule: Rule) -> LintLevel {
+        self.levels
+            .get(&rule)
+            .copied()
+            .unwrap_or_else(|| rule.default_level())
+    }
+}
+
+/// One finding, located like a [`crate::error::JError`].
+#[derive(Debug, Clone)]
+pub struct Lint {
+    pub rule: Rule,
+    pub level: LintLevel,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub source_line: Option<String>,
+    pub tip: Option<String>,
+    pub similar_names: Vec<String>,
+}
+
+impl Lint {
+    /// This finding as a JSON diagnostic object, with the fields of `jade check --format json`;
+    /// `code` is the rule name.
+    #[cfg(feature = "serde_json")]
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "severity": if self.level == LintLevel::Deny { "error" } else { "warning" },
+            "code": self.rule.name(),
+            "message": self.message,
+            "file": self.file,
+            "line": self.line,
+            "column": self.column,
+            "tip": self.tip,
+            "similar_names": self.similar_names,
+        })
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (emoji, severity) = match self.level {
+            LintLevel::Deny => ("❌", "ERROR"),
+            _ => ("⚠️", "WARNING"),
+        };
+        write!(
+            f,
+            "{} LINT {}[{}]: {}",
+            emoji,
+            severity,
+            self.rule.name(),
+            self.message
+        )?;
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            match &self.file {
+                Some(file) => write!(f, " ({}, line {}, col {})", file, line, column)?,
+                None => write!(f, " (line {}, col {})", line, column)?,
+            }
+            writeln!(f)?;
+            if let Some(source) = &self.source_line {
+                writeln!(f, "  {} │ {}", line, source)?;
+                writeln!(
+                    f,
+                    "  {} │ {}^",
+                    " ".repeat(line.to_string().len()),
+                    " ".repeat(column.saturating_sub(1))
+                )?;
+            }
+        } else {
+            writeln!(f)?;
+        }
+        if !self.similar_names.is_empty() {
+            writeln!(f, "  💡 Did you mean: {}", self.similar_names.join(", "))?;
+        }
+        if let Some(tip) = &self.tip {
+            writeln!(f, "  💡 {}", tip)?;
+        }
+        Ok(())
+    }
+}
+
+/// Every finding in `source` whose rule is not allowed by `config`, in source order. `path` is
+/// used in reports and to resolve `import`s as `jade run` does; a syntax error is returned as
+/// the error.
+pub fn lint_source(source: &str, path: Option<&Path>, config: &LintConfig) -> JResult<Vec<Lint>> {
+    let tokens = Lexer::new(source).tokenize()?;
+    let program = Parser::new(tokens).parse()?;
+    let statements = match &program {
+        AstNode::Block(statements) => statements.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+
+    let mut resolver = Interpreter::new();
+    if let Some(path) = path {
+        for search_path in crate::testing::module_search_paths(path) {
+            resolver.add_module_search_path(search_path);
+        }
+    }
+    let mut known = HashSet::new();
+    let mut callables = HashSet::new();
+    let mut imports = Vec::new();
+    let mut all_imports_resolved = true;
+    for statement in statements {
+        declared_names(statement, &mut known);
+        callable_names(statement, &mut callables);
+        if let AstNode::ImportStatement {
+            module_path,
+            items,
+            span,
+        } = statement
+        {
+            let exports = if items.is_empty() {
+                let seen = &mut HashSet::new();
+                let exports = module_exports(&resolver, module_path, seen, &mut callables);
+                all_imports_resolved &= exports.is_some();
+                exports.unwrap_or_default()
+            } else {
+                HashSet::new()
+            };
+            known.extend(exports.iter().cloned());
+            imports.push((module_path.join("."), items.clone(), exports, span.clone()));
+        }
+    }
+
+    let mut linter = Linter {
+        config,
+        file: path.map(|path| path.display().to_string()),
+        lines: source.lines().collect(),
+        known: all_imports_resolved.then_some(known),
+        callables,
+        scopes: vec![Scope::default()],
+        globals: HashMap::new(),
+        referenced: HashSet::new(),
+        lints: Vec::new(),
+    };
+    for statement in statements {
+        if let AstNode::VarDeclaration {
+            name,
+            immutable,
+            span,
+            ..
+        } = statement
+        {
+            linter
+                .globals
+                .entry(name.clone())
+                .or_insert((*immutable, span.clone()));
+        }
+    }
+    linter.block(statements);
+
+    for (module, items, exports, span) in imports {
+        if items.is_empty() {
+            if !exports.is_empty() && exports.is_disjoint(&linter.referenced) {
+                linter.report(
+                    Rule::UnusedImport,
+                    Some(&span),
+                    format!("Nothing imported from '{}' is used", module),
+                );
+            }
+        } else {
+            let unused: Vec<_> = items
+                .iter()
+                .filter(|item| !linter.referenced.contains(*item))
+                .collect();
+            for item in unused {
+                linter.report(
+                    Rule::UnusedImport,
+                    Some(&span),
+                    format!("'{}' is imported from '{}' but never used", item, module),
+                );
+            }
+        }
+    }
+
+    let mut lints = linter.lints;
+    lints.sort_by_key(|lint| (lint.line, lint.column));
+    Ok(lints)
+}
+
+struct Variable {
+    name: String,
+    /// `None` for declarations without a position (parameters, most loop variables), which are
+    /// never reported.
+    span: Option<Span>,
+    immutable: bool,
+    check_unused: bool,
+}
+
+#[derive(Default)]
+struct Scope {
+    variables: Vec<Variable>,
+    /// Names read anywhere while this scope was open.
+    referenced: HashSet<String>,
+}
+
+struct Linter<'a> {
+    config: &'a LintConfig,
+    file: Option<String>,
+    lines: Vec<&'a str>,
+    /// Every name the program declares or imports; `None` when an import could not be
+    /// resolved, so unknown functions cannot be told apart from imported ones.
+    known: Option<HashSet<String>>,
+    /// Declared functions and classes and imported names, suggested for unknown functions.
+    callables: HashSet<String>,
+    /// Innermost last; the first is the global scope.
+    scopes: Vec<Scope>,
+    /// Top-level declarations, visible to functions declared before them.
+    globals: HashMap<String, (bool, Span)>,
+    referenced: HashSet<String>,
+    lints: Vec<Lint>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, rule: Rule, span: Option<&Span>, message: String) -> Option<&mut Lint> {
+        let level = self.config.level(rule);
+        if level == LintLevel::Allow {
+            return None;
+        }
+        let position = span.filter(|span| span.line > 0);
+        self.lints.push(Lint {
+            rule,
+            level,
+            message,
+            file: self.file.clone(),
+            line: position.map(|span| span.line),
+            column: position.map(|span| span.column),
+            source_line: position
+                .and_then(|span| self.lines.get(span.line - 1))
+                .map(|line| line.to_string()),
+            tip: None,
+            similar_names: Vec::new(),
+        });
+        self.lints.last_mut()
+    }
+
+    /// Statements in order, reporting the first one after a `return`, `break`, `continue` or
+    /// `panic` that always runs.
+    fn block(&mut self, statements: &[AstNode]) {
+        let mut diverged = false;
+        for statement in statements {
+            if diverged {
+                self.report(
+                    Rule::UnreachableCode,
+                    statement.first_span(),
+                    "Unreachable code".to_string(),
+                );
+                diverged = false;
+                self.node(statement);
+                continue;
+            }
+            self.node(statement);
+            diverged = diverges(statement);
+        }
+    }
+
+    fn node(&mut self, node: &AstNode) {
+        match node {
+            AstNode::Identifier(name, _) | AstNode::TypeConversion { name, .. } => {
+                self.reference(name)
+            }
+            AstNode::FunctionCall { name, args, span } => {
+                self.reference(name);
+                self.check_known(name, span);
+                args.iter().for_each(|arg| self.node(arg));
+            }
+            AstNode::VarDeclaration {
+                name,
+                value,
+                immutable,
+                span,
+                ..
+            } => {
+                self.node(value);
+                self.declare(name, Some(span), *immutable, true);
+            }
+            AstNode::Assignment { name, value, span } => {
+                self.node(value);
+                self.assign(name, span);
+            }
+            AstNode::DestructuringAssignment { targets, value } => {
+                self.node(value);
+                for target in targets {
+                    self.declare(target, None, false, false);
+                }
+            }
+            AstNode::Block(statements) => {
+                self.scopes.push(Scope::default());
+                self.block(statements);
+                self.close_scope();
+            }
+            AstNode::FunctionDeclaration {
+                params,
+                body,
+                decorators,
+                ..
+            } => {
+                for decorator in decorators {
+                    decorator.args.iter().for_each(|arg| self.node(arg));
+                }
+                let params: Vec<&String> = params.iter().map(|(_, name)| name).collect();
+                self.function(&params, body);
+            }
+            AstNode::AsyncFunction { params, body, .. }
+            | AstNode::GenericFunction { params, body, .. }
+            | AstNode::AutoFunction { params, body, .. }
+            | AstNode::MemoVarDeclaration { params, body, .. } => {
+                let params: Vec<&String> = params.iter().map(|(_, name)| name).collect();
+                self.function(&params, body);
+            }
+            AstNode::Lambda { params, body } | AstNode::Generator { params, body } => {
+                self.function(&params.iter().collect::<Vec<_>>(), body);
+            }
+            AstNode::For {
+                var,
+                iterable,
+                body,
+                span,
+            } => {
+                self.node(iterable);
+                self.scopes.push(Scope::default());
+                self.declare(var, Some(span), false, true);
+                self.node(body);
+                self.close_scope();
+            }
+            AstNode::TryCatch {
+                try_block,
+                catch_var,
+                catch_block,
+                finally_block,
+            } => {
+                self.node(try_block);
+                self.scopes.push(Scope::default());
+                if let Some(var) = catch_var {
+                    self.declare(var, None, false, false);
+                }
+                self.node(catch_block);
+                self.close_scope();
+                if let Some(finally_block) = finally_block {
+                    self.node(finally_block);
+                }
+            }
+            AstNode::Match { expr, arms, .. } => {
+                self.node(expr);
+                for arm in arms {
+                    self.scopes.push(Scope::default());
+                    let mut bindings = Vec::new();
+                    pattern_bindings(&arm.pattern, &mut bindings);
+                    for name in bindings {
+                        self.declare(name, None, false, false);
+                    }
+                    let mut literals = Vec::new();
+                    arm.pattern.literals(&mut literals);
+                    literals.into_iter().for_each(|literal| self.node(literal));
+                    if let Some(guard) = &arm.guard {
+                        self.node(guard);
+                    }
+                    self.node(&arm.body);
+                    self.close_scope();
+                }
+            }
+            _ => {
+                let loop_vars = loop_variables(node);
+                let opens_scope = !loop_vars.is_empty();
+                if opens_scope {
+                    self.scopes.push(Scope::default());
+                    for name in loop_vars {
+                        self.declare(name, None, false, false);
+                    }
+                }
+                for child in node.children() {
+                    self.node(child);
+                }
+                if opens_scope {
+                    self.close_scope();
+                }
+            }
+        }
+    }
+
+    /// A function or lambda body, in a scope holding its parameters.
+    fn function(&mut self, params: &[&String], body: &AstNode) {
+        self.scopes.push(Scope::default());
+        for param in params {
+            self.declare(param, None, false, false);
+        }
+        self.node(body);
+        self.close_scope();
+    }
+
+    fn declare(&mut self, name: &str, span: Option<&Span>, immutable: bool, check_unused: bool) {
+        if self.scopes.len() > 1 && !name.starts_with('_') {
+            let (current, enclosing) = self.scopes.split_last().expect("a scope is open");
+            let shadowed = enclosing[1..]
+                .iter()
+                .rev()
+                .find_map(|scope| scope.variables.iter().find(|v| v.name == name));
+            let redeclared = current.variables.iter().any(|v| v.name == name);
+            if let (Some(shadowed), false, Some(_)) = (shadowed, redeclared, span) {
+                let message = match &shadowed.span {
+                    Some(outer) => format!(
+                        "'{}' shadows the variable declared on line {}",
+                        name, outer.line
+                    ),
+                    None => format!("'{}' shadows a parameter or loop variable", name),
+                };
+                if let Some(lint) = self.report(Rule::ShadowedVariable, span, message) {
+                    lint.tip = Some(format!(
+                        "Assign with '{} = ...' to update the outer variable, or rename this one",
+                        name
+                    ));
+                }
+            }
+        }
+        let check_unused = check_unused && self.scopes.len() > 1 && !name.starts_with('_');
+        self.scopes
+            .last_mut()
+            .expect("a scope is open")
+            .variables
+            .push(Variable {
+                name: name.to_string(),
+                span: span.cloned(),
+                immutable,
+                check_unused,
+            });
+    }
+
+    fn reference(&mut self, name: &str) {
+        self.referenced.insert(name.to_string());
+        for scope in &mut self.scopes {
+            scope.referenced.insert(name.to_string());
+        }
+    }
+
+    fn assign(&mut self, name: &str, span: &Span) {
+        let declared = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.variables.iter().rev().find(|v| v.name == name))
+            .map(|v| (v.immutable, v.span.clone()))
+            .or_else(|| {
+                let (immutable, span) = self.globals.get(name)?;
+                Some((*immutable, Some(span.clone())))
+            });
+        if let Some((true, declaration)) = declared {
+            let line = declaration.map_or(0, |span| span.line);
+            let message = format!(
+                "Cannot assign to '{}': it is declared immutable on line {}",
+                name, line
+            );
+            if let Some(lint) = self.report(Rule::ImmutableAssignment, Some(span), message) {
+                lint.tip = Some("Drop the '!' from its declaration to make it mutable".to_string());
+            }
+        }
+    }
+
+    fn check_known(&mut self, name: &str, span: &Span) {
+        let Some(known) = &self.known else {
+            return;
+        };
+        if known.contains(name) || BUILTIN_NAMES.contains(&name) {
+            return;
+        }
+        let mut candidates: Vec<&str> = self.callables.iter().map(String::as_str).collect();
+        candidates.extend(BUILTIN_NAMES);
+        let similar = find_similar_names(name, &candidates);
+        let message = format!("Unknown function '{}'", name);
+        if let Some(lint) = self.report(Rule::UnknownFunction, Some(span), message) {
+            lint.similar_names = similar;
+        }
+    }
+
+    /// Leave the innermost scope, reporting its variables that were never read.
+    fn close_scope(&mut self) {
+        let scope = self.scopes.pop().expect("a scope is open");
+        for variable in scope.variables {
+            if variable.check_unused && !scope.referenced.contains(&variable.name) {
+                let message = format!("Variable '{}' is never used", variable.name);
+                let span = variable.span.as_ref();
+                if let Some(lint) = self.report(Rule::UnusedVariable, span, message) {
+                    lint.tip = Some(format!(
+                        "Remove it, or name it '_{}' if it is unused on purpose",
+                        variable.name
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Whether control never continues past `node`.
+fn diverges(node: &AstNode) -> bool {
+    match node {
+        AstNode::Return(..) | AstNode::Break(_) | AstNode::Continue(_) | AstNode::Throw(..) => true,
+        AstNode::Expression(inner) => diverges(inner),
+        AstNode::Block(statements) => statements.iter().any(diverges),
+        AstNode::If {
+            then_branch,
+            else_branch: Some(else_branch),
+            ..
+        } => diverges(then_branch) && diverges(else_branch),
+        _ => false,
+    }
+}
+
+/// Variables a loop or comprehension other than a plain `for` binds for its body.
+fn loop_variables(node: &AstNode) -> Vec<&String> {
+    match node {
+        AstNode::ForIndexed {
+            index_var,
+            value_var,
+            ..
+        } => vec![index_var, value_var],
+        AstNode::ForReverse { var, .. }
+        | AstNode::ForStep { var, .. }
+        | AstNode::ForParallel { var, .. }
+        | AstNode::ForChunked { var, .. }
+        | AstNode::ForFiltered { var, .. }
+        | AstNode::ForWindowed { var, .. }
+        | AstNode::WindowLoop { var, .. }
+        | AstNode::ListComprehension { var, .. }
+        | AstNode::DictComprehension { var, .. } => vec![var],
+        AstNode::ForZip { vars, .. } => vars.iter().collect(),
+        AstNode::SweepLoop {
+            left_var,
+            right_var,
+            ..
+        }
+        | AstNode::ShrinkLoop {
+            left_var,
+            right_var,
+            ..
+        }
+        | AstNode::MeetLoop {
+            left_var,
+            right_var,
+            ..
+        } => vec![left_var, right_var],
+        AstNode::BinarySearchLoop { lo_var, hi_var, .. } => vec![lo_var, hi_var],
+        _ => Vec::new(),
+    }
+}
+
+fn pattern_bindings<'a>(pattern: &'a Pattern, names: &mut Vec<&'a String>) {
+    match pattern {
+        Pattern::Identifier(name) => names.push(name),
+        Pattern::List(items) | Pattern::Tuple(items) => {
+            items.iter().for_each(|item| pattern_bindings(item, names))
+        }
+        Pattern::Literal(_) | Pattern::Range { .. } | Pattern::Wildcard => {}
+    }
+}
+
+/// Every name declared in `node` or the nodes inside it: variables, functions, types,
+/// parameters, loop, catch and pattern variables, and imported items.
+fn declared_names(node: &AstNode, names: &mut HashSet<String>) {
+    match node {
+        AstNode::VarDeclaration { name, .. }
+        | AstNode::Assignment { name, .. }
+        | AstNode::ClassDeclaration { name, .. }
+        | AstNode::GenericClass { name, .. }
+        | AstNode::EnumDeclaration { name, .. }
+        | AstNode::TraitDeclaration { name, .. }
+        | AstNode::LiveVariable { name, .. }
+        | AstNode::EchoVariable { name, .. }
+        | AstNode::StackAllocation { name, .. }
+        | AstNode::GhostDeclaration { name, .. }
+        | AstNode::ComponentDecl { name, .. }
+        | AstNode::TaskDecl { name, .. }
+        | AstNode::For { var: name, .. } => {
+            names.insert(name.clone());
+        }
+        AstNode::FunctionDeclaration { name, params, .. }
+        | AstNode::AsyncFunction { name, params, .. }
+        | AstNode::GenericFunction { name, params, .. }
+        | AstNode::AutoFunction { name, params, .. }
+        | AstNode::MemoVarDeclaration { name, params, .. }
+        | AstNode::WarpTemplate { name, params, .. } => {
+            names.insert(name.clone());
+            names.extend(params.iter().map(|(_, param)| param.clone()));
+        }
+        AstNode::MacroDefinition { name, params, .. } => {
+            names.insert(name.clone());
+            names.extend(params.iter().cloned());
+        }
+        AstNode::Lambda { params, .. } | AstNode::Generator { params, .. } => {
+            names.extend(params.iter().cloned())
+        }
+        AstNode::DestructuringAssignment { targets, .. } => names.extend(targets.iter().cloned()),
+        AstNode::TryCatch {
+            catch_var: Some(name),
+            ..
+        } => {
+            names.insert(name.clone());
+        }
+        AstNode::ImportStatement { items, .. } => names.extend(items.iter().cloned()),
+        AstNode::Match { arms, .. } => {
+            let mut bindings = Vec::new();
+            for arm in arms {
+                pattern_bindings(&arm.pattern, &mut bindings);
+            }
+            names.extend(bindings.into_iter().cloned());
+        }
+        _ => names.extend(loop_variables(node).into_iter().cloned()),
+    }
+    for child in node.children() {
+        declared_names(child, names);
+    }
+}
+
+/// Names `node` declares that can be called: functions, classes, enums, macros and imported
+/// items.
+fn callable_names(node: &AstNode, names: &mut HashSet<String>) {
+    match node {
+        AstNode::FunctionDeclaration { name, .. }
+        | AstNode::AsyncFunction { name, .. }
+        | AstNode::GenericFunction { name, .. }
+        | AstNode::AutoFunction { name, .. }
+        | AstNode::MemoVarDeclaration { name, .. }
+        | AstNode::WarpTemplate { name, .. }
+        | AstNode::MacroDefinition { name, .. }
+        | AstNode::ClassDeclaration { name, .. }
+        | AstNode::GenericClass { name, .. }
+        | AstNode::EnumDeclaration { name, .. } => {
+            names.insert(name.clone());
+        }
+        AstNode::ImportStatement { items, .. } => names.extend(items.iter().cloned()),
+        _ => {}
+    }
+    for child in node.children() {
+        callable_names(child, names);
+    }
+}
+
+/// Names an `import` of every export of `module_path` brings in, or `None` if the module (or
+/// a module it imports everything from) cannot be found or parsed. The functions and classes
+/// among them are added to `callables`.
+fn module_exports(
+    resolver: &Interpreter,
+    module_path: &[String],
+    seen: &mut HashSet<String>,
+    callables: &mut HashSet<String>,
+) -> Option<HashSet<String>> {
+    let path = resolver.resolve_module_path(&module_path.join("/")).ok()?;
+    if !seen.insert(path.clone()) {
+        return Some(HashSet::new());
+    }
+    let source = std::fs::read_to_string(&path).ok()?;
+    let program = Parser::new(Lexer::new(&source).tokenize().ok()?)
+        .parse()
+        .ok()?;
+    let mut exports = HashSet::new();
+    declared_names(&program, &mut exports);
+    callable_names(&program, callables);
+    for statement in program.children() {
+        if let AstNode::ImportStatement {
+            module_path, items, ..
+        } = statement
+        {
+            if items.is_empty() {
+                exports.extend(module_exports(resolver, module_path, seen, callables)?);
+            }
+        }
+    }
+    Some(exports)
+}
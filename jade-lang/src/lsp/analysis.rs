@@ -0,0 +1,588 @@
+//! What the language server knows about one document: the declarations in its tokens, and
+//! lookups of the name at a position. Declarations are found in the token stream, which has
+//! positions and survives syntax errors, rather than in the AST.
+
+use std::path::{Path, PathBuf};
+
+use crate::interpreter::{BUILTIN_METHODS, BUILTIN_NAMES};
+use crate::lexer::{Lexer, Token, TokenType};
+
+/// A 1-based (line, column) position, in characters, as the lexer counts them.
+pub type Pos = (usize, usize);
+
+/// Past the end of any document.
+const END: Pos = (usize::MAX, usize::MAX);
+
+/// Keywords offered by completion.
+const KEYWORDS: &[&str] = &[
+    "fn", "class", "enum", "trait", "if", "else", "for", "in", "while", "match", "return", "break",
+    "continue", "try", "catch", "finally", "panic", "import", "test", "assert", "true", "false",
+    "static",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Class,
+    Enum,
+    Trait,
+    Variable,
+    Parameter,
+}
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Where the name itself is.
+    pub pos: Pos,
+    /// The whole declaration, from its keyword to the end of its body.
+    pub range: (Pos, Pos),
+    /// The block the name is declared in (a function's body for parameters).
+    pub block: (Pos, Pos),
+    /// Variables are only visible from their declaration on.
+    pub visible_from: Pos,
+    /// Signature of functions and types; declared type of variables and parameters.
+    pub detail: String,
+}
+
+pub struct Analysis {
+    tokens: Vec<Token>,
+    lines: Vec<String>,
+    pub symbols: Vec<Symbol>,
+    /// Module paths of `import` statements, e.g. `["lib", "util"]`.
+    pub imports: Vec<Vec<String>>,
+}
+
+/// What a name at a position refers to.
+pub enum Target<'a> {
+    Symbol(&'a Symbol),
+    Builtin(&'a str),
+    /// A builtin method, with the value kinds that have it.
+    Method(&'a str, Vec<&'static str>),
+}
+
+/// One completion candidate.
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Symbol(SymbolKind),
+    Builtin,
+    Method,
+    Keyword,
+}
+
+impl Analysis {
+    pub fn new(source: &str) -> Self {
+        let (tokens, _) = Lexer::new(source).tokenize_recovering();
+        let mut analysis = Self {
+            tokens,
+            lines: source.lines().map(str::to_string).collect(),
+            symbols: Vec::new(),
+            imports: Vec::new(),
+        };
+        analysis.collect_declarations();
+        analysis
+    }
+
+    fn collect_declarations(&mut self) {
+        let closers = self.matching_braces();
+        let mut enclosing = Vec::with_capacity(self.tokens.len());
+        let mut open = Vec::new();
+        for (i, token) in self.tokens.iter().enumerate() {
+            if token.token_type == TokenType::RightBrace {
+                open.pop();
+            }
+            enclosing.push(open.last().copied());
+            if token.token_type == TokenType::LeftBrace {
+                open.push(i);
+            }
+        }
+        let block_of = |brace: Option<usize>| match brace {
+            Some(b) => (
+                self.start(b),
+                closers[b].map_or(END, |close| self.end(close)),
+            ),
+            None => ((1, 1), END),
+        };
+        // The block opened by the first `{` after `from` at the same nesting level.
+        let body_after = |from: usize| {
+            (from..self.tokens.len())
+                .take_while(|&k| enclosing[k] == enclosing[from])
+                .find(|&k| self.tokens[k].token_type == TokenType::LeftBrace)
+        };
+
+        let mut symbols = Vec::new();
+        let mut imports = Vec::new();
+        for (i, &brace) in enclosing.iter().enumerate() {
+            let block = block_of(brace);
+            match &self.tokens[i].token_type {
+                TokenType::Fn => {
+                    let mut j = i + 1;
+                    if !self.is(j, &TokenType::Pipe) {
+                        j += 1; // return type
+                    }
+                    if !self.is(j, &TokenType::Pipe) {
+                        continue;
+                    }
+                    let Some(name) = self.name_at(j + 1) else {
+                        continue;
+                    };
+                    let Some(close) = self.matching_paren(j + 2) else {
+                        continue;
+                    };
+                    let body = match self.tokens.get(close + 2) {
+                        Some(t) if t.token_type == TokenType::LeftBrace => {
+                            block_of(Some(close + 2))
+                        }
+                        _ => (self.start(close), (self.tokens[close].line, usize::MAX)),
+                    };
+                    symbols.push(Symbol {
+                        name,
+                        kind: SymbolKind::Function,
+                        pos: self.start(j + 1),
+                        range: (self.start(i), body.1),
+                        block,
+                        visible_from: block.0,
+                        detail: self.text(i, close),
+                    });
+                    // Parameters: `type | name`, separated by commas
+                    for k in j + 3..close {
+                        if self.is(k, &TokenType::Pipe) {
+                            if let Some(param) = self.name_at(k + 1) {
+                                symbols.push(Symbol {
+                                    name: param,
+                                    kind: SymbolKind::Parameter,
+                                    pos: self.start(k + 1),
+                                    range: (self.start(k - 1), self.end(k + 1)),
+                                    block: body,
+                                    visible_from: body.0,
+                                    detail: self.tokens[k - 1].lexeme.clone(),
+                                });
+                            }
+                        }
+                    }
+                }
+                TokenType::Class | TokenType::EnumKeyword | TokenType::Trait => {
+                    if !self.is(i + 1, &TokenType::Pipe) {
+                        continue;
+                    }
+                    let Some(name) = self.name_at(i + 2) else {
+                        continue;
+                    };
+                    let kind = match self.tokens[i].token_type {
+                        TokenType::Class => SymbolKind::Class,
+                        TokenType::EnumKeyword => SymbolKind::Enum,
+                        _ => SymbolKind::Trait,
+                    };
+                    let (detail_end, end) = match body_after(i) {
+                        Some(brace) => (brace - 1, block_of(Some(brace)).1),
+                        None => (i + 2, self.end(i + 2)),
+                    };
+                    symbols.push(Symbol {
+                        name,
+                        kind,
+                        pos: self.start(i + 2),
+                        range: (self.start(i), end),
+                        block,
+                        visible_from: block.0,
+                        detail: self.text(i, detail_end),
+                    });
+                }
+                // `type: name = value` at the start of a statement
+                TokenType::Colon if i > 0 && self.is(i + 2, &TokenType::Assign) => {
+                    let Some(name) = self.name_at(i + 1) else {
+                        continue;
+                    };
+                    let mut first = i - 1;
+                    while first > 0
+                        && matches!(
+                            self.tokens[first - 1].token_type,
+                            TokenType::Static
+                                | TokenType::Exclamation
+                                | TokenType::Untrusted
+                                | TokenType::Secret
+                                | TokenType::Canary
+                                | TokenType::Enc
+                        )
+                    {
+                        first -= 1;
+                    }
+                    let at_statement_start = first == 0
+                        || matches!(
+                            self.tokens[first - 1].token_type,
+                            TokenType::Newline
+                                | TokenType::Semicolon
+                                | TokenType::LeftBrace
+                                | TokenType::RightBrace
+                        );
+                    if !at_statement_start
+                        || !self.tokens[i - 1].lexeme.chars().all(char::is_alphanumeric)
+                    {
+                        continue;
+                    }
+                    let pos = self.start(i + 1);
+                    symbols.push(Symbol {
+                        name,
+                        kind: SymbolKind::Variable,
+                        pos,
+                        range: (self.start(first), (pos.0, usize::MAX)),
+                        block,
+                        visible_from: pos,
+                        detail: self.tokens[i - 1].lexeme.clone(),
+                    });
+                }
+                // Loop variables and the error bound by `catch`, scoped to the body
+                TokenType::For | TokenType::Catch => {
+                    let Some(body) = body_after(i) else {
+                        continue;
+                    };
+                    let is_for = self.tokens[i].token_type == TokenType::For;
+                    for k in i + 1..body {
+                        match &self.tokens[k].token_type {
+                            TokenType::Identifier(name) => symbols.push(Symbol {
+                                name: name.clone(),
+                                kind: SymbolKind::Variable,
+                                pos: self.start(k),
+                                range: (self.start(k), self.end(k)),
+                                block: block_of(Some(body)),
+                                visible_from: self.start(k),
+                                detail: if is_for { "loop variable" } else { "error" }.to_string(),
+                            }),
+                            TokenType::Comma => {}
+                            _ => break,
+                        }
+                    }
+                }
+                TokenType::Import => {
+                    let mut path = Vec::new();
+                    let mut k = i + 1;
+                    while let Some(part) = self.name_at(k) {
+                        path.push(part);
+                        if !self.is(k + 1, &TokenType::Dot) {
+                            break;
+                        }
+                        k += 2;
+                    }
+                    if !path.is_empty() {
+                        imports.push(path);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.symbols = symbols;
+        self.imports = imports;
+    }
+
+    /// Functions, classes, enums and traits, each with the declarations nested in it.
+    pub fn outline(&self) -> Vec<(&Symbol, Vec<&Symbol>)> {
+        let declarations: Vec<&Symbol> = self
+            .symbols
+            .iter()
+            .filter(|s| !matches!(s.kind, SymbolKind::Variable | SymbolKind::Parameter))
+            .collect();
+        let inside = |inner: &Symbol, outer: &Symbol| {
+            !std::ptr::eq(inner, outer)
+                && outer.range.0 <= inner.range.0
+                && inner.range.1 <= outer.range.1
+        };
+        declarations
+            .iter()
+            .filter(|s| !declarations.iter().any(|outer| inside(s, outer)))
+            .map(|&outer| {
+                let children = declarations
+                    .iter()
+                    .copied()
+                    .filter(|s| inside(s, outer))
+                    .collect();
+                (outer, children)
+            })
+            .collect()
+    }
+
+    /// The name at `pos` and what it refers to.
+    pub fn lookup(&self, pos: Pos) -> Option<(&Token, Target<'_>)> {
+        let index = self.token_at(pos)?;
+        let token = &self.tokens[index];
+        let TokenType::Identifier(name) = &token.token_type else {
+            return None;
+        };
+        let after_dot = index > 0 && self.is(index - 1, &TokenType::Dot);
+        if after_dot {
+            if let Some(method) = self
+                .symbols
+                .iter()
+                .find(|s| s.kind == SymbolKind::Function && s.name == *name)
+            {
+                return Some((token, Target::Symbol(method)));
+            }
+            let kinds = methods_named(name);
+            return (!kinds.is_empty()).then(|| (token, Target::Method(name, kinds)));
+        }
+        if let Some(symbol) = self.resolve(name, token_start(token)) {
+            return Some((token, Target::Symbol(symbol)));
+        }
+        BUILTIN_NAMES
+            .iter()
+            .find(|builtin| **builtin == name.as_str())
+            .map(|builtin| (token, Target::Builtin(builtin)))
+    }
+
+    /// The identifier at `pos`, if any.
+    pub fn identifier_at(&self, pos: Pos) -> Option<&str> {
+        match &self.tokens[self.token_at(pos)?].token_type {
+            TokenType::Identifier(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The declaration `name` refers to at `pos`: the innermost visible one, else one at the top
+    /// level (e.g. a global assigned after the function using it).
+    pub fn resolve(&self, name: &str, pos: Pos) -> Option<&Symbol> {
+        let named = || self.symbols.iter().filter(move |s| s.name == name);
+        named()
+            .filter(|s| s.block.0 <= pos && pos <= s.block.1 && s.visible_from <= pos)
+            .max_by_key(|s| (s.block.0, s.pos))
+            .or_else(|| named().find(|s| s.block == ((1, 1), END)))
+    }
+
+    /// Completions at `pos`: methods after `.`, otherwise visible declarations, builtins and
+    /// keywords, starting with the partial name before the cursor.
+    pub fn completions(&self, pos: Pos) -> Vec<Completion> {
+        let line = self
+            .lines
+            .get(pos.0.wrapping_sub(1))
+            .map_or("", String::as_str);
+        let before: String = line.chars().take(pos.1.saturating_sub(1)).collect();
+        let prefix: String = before
+            .chars()
+            .rev()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        let before_prefix = &before[..before.len() - prefix.len()];
+
+        let mut items = Vec::new();
+        if let Some(receiver) = before_prefix.strip_suffix('.') {
+            for (label, detail) in self.members(receiver, pos) {
+                items.push(Completion {
+                    label,
+                    kind: CompletionKind::Method,
+                    detail,
+                });
+            }
+        } else {
+            let mut seen = std::collections::HashSet::new();
+            let visible = self
+                .symbols
+                .iter()
+                .filter(|s| s.block.0 <= pos && pos <= s.block.1 && s.visible_from <= pos);
+            for symbol in visible.rev() {
+                if seen.insert(symbol.name.clone()) {
+                    items.push(Completion {
+                        label: symbol.name.clone(),
+                        kind: CompletionKind::Symbol(symbol.kind),
+                        detail: symbol.detail.clone(),
+                    });
+                }
+            }
+            for builtin in BUILTIN_NAMES.iter().filter(|b| seen.insert(b.to_string())) {
+                items.push(Completion {
+                    label: builtin.to_string(),
+                    kind: CompletionKind::Builtin,
+                    detail: "builtin".to_string(),
+                });
+            }
+            for keyword in KEYWORDS.iter().filter(|k| seen.insert(k.to_string())) {
+                items.push(Completion {
+                    label: keyword.to_string(),
+                    kind: CompletionKind::Keyword,
+                    detail: "keyword".to_string(),
+                });
+            }
+        }
+        items.retain(|item| item.label.starts_with(&prefix));
+        items
+    }
+
+    /// Methods available on the expression ending `receiver` (the text before the `.`): a
+    /// class's methods, the builtin methods for a variable's declared type, or every method.
+    fn members(&self, receiver: &str, pos: Pos) -> Vec<(String, String)> {
+        let receiver = receiver.trim_end();
+        let name: String = receiver
+            .chars()
+            .rev()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        let kind = if receiver.ends_with('"') {
+            Some("string")
+        } else if receiver.ends_with(']') {
+            Some("list")
+        } else {
+            match self.resolve(&name, pos) {
+                Some(class) if class.kind == SymbolKind::Class => {
+                    return self
+                        .symbols
+                        .iter()
+                        .filter(|s| {
+                            s.kind == SymbolKind::Function
+                                && class.range.0 < s.range.0
+                                && s.range.1 <= class.range.1
+                        })
+                        .map(|s| (s.name.clone(), s.detail.clone()))
+                        .collect();
+                }
+                Some(variable) => Some(method_kind(&variable.detail)),
+                None => None,
+            }
+        };
+        let mut members: Vec<(String, String)> = Vec::new();
+        for (value_kind, methods) in BUILTIN_METHODS {
+            if kind.is_some_and(|kind| kind != *value_kind) {
+                continue;
+            }
+            for method in methods.iter() {
+                if !members.iter().any(|(m, _)| m == method) {
+                    members.push((method.to_string(), format!("{} method", value_kind)));
+                }
+            }
+        }
+        members
+    }
+
+    /// Files that the document's imports may refer to, relative to its directory `dir`: a local
+    /// module or an installed package.
+    pub fn import_candidates(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for path in &self.imports {
+            files.push(dir.join(path.join("/")).with_extension("jdl"));
+            files.push(dir.join(".jade/packages").join(&path[0]).join("main.jdl"));
+        }
+        files
+    }
+
+    fn token_at(&self, pos: Pos) -> Option<usize> {
+        self.tokens.iter().position(|t| {
+            let start = token_start(t);
+            t.line == pos.0 && start.1 <= pos.1 && pos.1 <= self.end_of(t).1
+        })
+    }
+
+    fn is(&self, index: usize, token_type: &TokenType) -> bool {
+        self.tokens
+            .get(index)
+            .is_some_and(|t| t.token_type == *token_type)
+    }
+
+    fn name_at(&self, index: usize) -> Option<String> {
+        match self.tokens.get(index).map(|t| &t.token_type) {
+            Some(TokenType::Identifier(name)) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    fn start(&self, index: usize) -> Pos {
+        token_start(&self.tokens[index])
+    }
+
+    fn end(&self, index: usize) -> Pos {
+        self.end_of(&self.tokens[index])
+    }
+
+    /// Just past the token, where it ends on its first line.
+    fn end_of(&self, token: &Token) -> Pos {
+        (
+            token.line,
+            token.column + token.lexeme.chars().count().max(1),
+        )
+    }
+
+    /// Source text from the start of token `from` to the end of token `to`, on `from`'s line.
+    fn text(&self, from: usize, to: usize) -> String {
+        let (line, column) = self.start(from);
+        let end = if self.tokens[to].line == line {
+            self.end(to).1
+        } else {
+            usize::MAX
+        };
+        let text = self.lines.get(line - 1).map_or("", String::as_str);
+        text.chars()
+            .skip(column - 1)
+            .take(end.saturating_sub(column))
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    /// For each `{`, the index of its `}`.
+    fn matching_braces(&self) -> Vec<Option<usize>> {
+        let mut closers = vec![None; self.tokens.len()];
+        let mut open = Vec::new();
+        for (i, token) in self.tokens.iter().enumerate() {
+            match token.token_type {
+                TokenType::LeftBrace => open.push(i),
+                TokenType::RightBrace => {
+                    if let Some(start) = open.pop() {
+                        closers[start] = Some(i);
+                    }
+                }
+                _ => {}
+            }
+        }
+        closers
+    }
+
+    /// The `)` closing the `(` at `open`.
+    fn matching_paren(&self, open: usize) -> Option<usize> {
+        if !self.is(open, &TokenType::LeftParen) {
+            return None;
+        }
+        let mut depth = 0;
+        for (i, token) in self.tokens.iter().enumerate().skip(open) {
+            match token.token_type {
+                TokenType::LeftParen => depth += 1,
+                TokenType::RightParen => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                TokenType::LeftBrace | TokenType::Eof => return None,
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+fn token_start(token: &Token) -> Pos {
+    (token.line, token.column)
+}
+
+/// The [`BUILTIN_METHODS`] kind for values of a declared type.
+fn method_kind(declared: &str) -> &str {
+    match declared {
+        "str" => "string",
+        other => other,
+    }
+}
+
+/// Value kinds that have a builtin method `name`.
+fn methods_named(name: &str) -> Vec<&'static str> {
+    BUILTIN_METHODS
+        .iter()
+        .filter(|(_, methods)| methods.contains(&name))
+        .map(|(kind, _)| *kind)
+        .collect()
+}
@@ -0,0 +1,463 @@
+//! `jade lsp`: a Language Server Protocol server over stdio. Diagnostics come from
+//! [`crate::diagnostics`], as for `jade check`; document symbols, go-to-definition, hover and
+//! completion from [`analysis`]; formatting from [`crate::formatter`], as for `jade fmt`.
+//!
+//! Documents are synced in full on every change. Positions are converted between the lexer's
+//! 1-based character columns and LSP's 0-based UTF-16 offsets at this boundary.
+
+pub mod analysis;
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value as Json};
+
+use crate::diagnostics::check_source;
+use crate::error::JError;
+use crate::formatter;
+use analysis::{Analysis, CompletionKind, Pos, Symbol, SymbolKind, Target};
+
+/// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Open documents by URI, and what the client asked of the server's lifecycle.
+#[derive(Default)]
+pub struct Server {
+    documents: HashMap<String, String>,
+    shutdown: bool,
+    exit: bool,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the client sent `exit`; the process should then stop.
+    pub fn exited(&self) -> bool {
+        self.exit
+    }
+
+    /// Exit status once the client sent `exit`: 0 if it asked for `shutdown` first.
+    pub fn exit_code(&self) -> i32 {
+        if self.shutdown {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Handle one JSON-RPC message. Returns the messages to send back: the response to a
+    /// request, and notifications such as published diagnostics.
+    pub fn handle(&mut self, message: &Json) -> Vec<Json> {
+        let Some(method) = message["method"].as_str() else {
+            return Vec::new(); // a response to a request of ours; we send none
+        };
+        let params = &message["params"];
+        let id = message.get("id").cloned();
+        let result = match method {
+            "initialize" => Ok(capabilities()),
+            "initialized" | "$/cancelRequest" | "$/setTrace" => return Vec::new(),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Json::Null)
+            }
+            "exit" => {
+                self.exit = true;
+                return Vec::new();
+            }
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                return self.update(document["uri"].as_str(), document["text"].as_str());
+            }
+            "textDocument/didChange" => {
+                // Full sync: the last change holds the whole text
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                return self.update(params["textDocument"]["uri"].as_str(), text);
+            }
+            "textDocument/didClose" => {
+                let Some(uri) = params["textDocument"]["uri"].as_str() else {
+                    return Vec::new();
+                };
+                self.documents.remove(uri);
+                return vec![publish_diagnostics(uri, Vec::new())];
+            }
+            "textDocument/documentSymbol" => self.with_document(params, |text, analysis, _| {
+                let symbols: Vec<Json> = analysis
+                    .outline()
+                    .into_iter()
+                    .map(|(symbol, children)| {
+                        let mut outer = document_symbol(text, symbol);
+                        outer["children"] = children
+                            .into_iter()
+                            .map(|child| document_symbol(text, child))
+                            .collect();
+                        outer
+                    })
+                    .collect();
+                Json::from(symbols)
+            }),
+            "textDocument/definition" => self.with_document(params, |text, analysis, uri| {
+                let pos = from_lsp(text, &params["position"]);
+                match analysis.lookup(pos) {
+                    Some((_, Target::Symbol(symbol))) => location(uri, text, symbol),
+                    // Not declared here: look in the imported modules
+                    _ => analysis
+                        .identifier_at(pos)
+                        .and_then(|name| imported_symbol(uri, name, analysis))
+                        .unwrap_or(Json::Null),
+                }
+            }),
+            "textDocument/hover" => self.with_document(params, |text, analysis, _| {
+                let pos = from_lsp(text, &params["position"]);
+                let Some((token, target)) = analysis.lookup(pos) else {
+                    return Json::Null;
+                };
+                let contents = match target {
+                    Target::Symbol(symbol) => describe(symbol),
+                    Target::Builtin(name) => {
+                        format!("```jade\n{}(...)\n```\nBuiltin function", name)
+                    }
+                    Target::Method(name, kinds) => format!(
+                        "```jade\n.{}(...)\n```\nBuiltin method of {}",
+                        name,
+                        kinds.join(", ")
+                    ),
+                };
+                let start = (token.line, token.column);
+                let end = (token.line, token.column + token.lexeme.chars().count());
+                json!({
+                    "contents": { "kind": "markdown", "value": contents },
+                    "range": { "start": to_lsp(text, start), "end": to_lsp(text, end) },
+                })
+            }),
+            "textDocument/completion" => self.with_document(params, |text, analysis, _| {
+                let pos = from_lsp(text, &params["position"]);
+                let items: Vec<Json> = analysis
+                    .completions(pos)
+                    .into_iter()
+                    .map(|item| {
+                        json!({
+                            "label": item.label,
+                            "kind": completion_kind(item.kind),
+                            "detail": item.detail,
+                        })
+                    })
+                    .collect();
+                Json::from(items)
+            }),
+            "textDocument/formatting" => self.with_document(params, |text, _, _| {
+                match formatter::format_source(text) {
+                    Ok(formatted) if formatted != text => json!([{
+                        "range": { "start": { "line": 0, "character": 0 }, "end": document_end(text) },
+                        "newText": formatted,
+                    }]),
+                    _ => json!([]),
+                }
+            }),
+            _ if id.is_none() => return Vec::new(),
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {}", method))),
+        };
+        let Some(id) = id else {
+            return Vec::new();
+        };
+        vec![match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        }]
+    }
+
+    /// Store a document's new text and publish its diagnostics.
+    fn update(&mut self, uri: Option<&str>, text: Option<&str>) -> Vec<Json> {
+        let (Some(uri), Some(text)) = (uri, text) else {
+            return Vec::new();
+        };
+        let diagnostics = check_source(text, None)
+            .iter()
+            .map(|error| diagnostic(text, error))
+            .collect();
+        self.documents.insert(uri.to_string(), text.to_string());
+        vec![publish_diagnostics(uri, diagnostics)]
+    }
+
+    /// Answer a request about an open document.
+    fn with_document(
+        &self,
+        params: &Json,
+        answer: impl FnOnce(&str, &Analysis, &str) -> Json,
+    ) -> Result<Json, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match self.documents.get(uri) {
+            Some(text) => Ok(answer(text, &Analysis::new(text), uri)),
+            None => Err((INVALID_PARAMS, format!("Document is not open: {}", uri))),
+        }
+    }
+}
+
+/// Serve LSP over stdin and stdout until the client sends `exit`; returns the exit status.
+pub fn run_stdio() -> io::Result<i32> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    run(stdin.lock(), stdout.lock())
+}
+
+/// Serve LSP over `reader` and `writer` until `exit` or end of input.
+pub fn run(mut reader: impl BufRead, mut writer: impl Write) -> io::Result<i32> {
+    let mut server = Server::new();
+    while let Some(body) = read_message(&mut reader)? {
+        let replies = match serde_json::from_str::<Json>(&body) {
+            Ok(message) => server.handle(&message),
+            Err(e) => vec![json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": PARSE_ERROR, "message": e.to_string() },
+            })],
+        };
+        for reply in replies {
+            write_message(&mut writer, &reply)?;
+        }
+        if server.exited() {
+            return Ok(server.exit_code());
+        }
+    }
+    Ok(server.exit_code())
+}
+
+/// Read one `Content-Length` framed message body; `None` at end of input.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let mut body = vec![0; length.unwrap_or(0)];
+    reader.read_exact(&mut body)?;
+    Ok(Some(String::from_utf8_lossy(&body).into_owned()))
+}
+
+/// Write one message with its `Content-Length` header.
+pub fn write_message(writer: &mut impl Write, message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+fn capabilities() -> Json {
+    json!({
+        "capabilities": {
+            "textDocumentSync": 1,
+            "documentSymbolProvider": true,
+            "definitionProvider": true,
+            "hoverProvider": true,
+            "completionProvider": { "triggerCharacters": ["."] },
+            "documentFormattingProvider": true,
+        },
+        "serverInfo": { "name": "jade", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Json>) -> Json {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+fn diagnostic(text: &str, error: &JError) -> Json {
+    let start = (error.line.unwrap_or(1), error.column.unwrap_or(1));
+    // Underline at least one character
+    let end = match (error.end_line, error.end_column) {
+        (Some(line), Some(column)) if (line, column) > start => (line, column),
+        _ => (start.0, start.1 + 1),
+    };
+    let mut message = error.message.clone();
+    if let Some(tip) = &error.tip {
+        message.push_str("\n💡 ");
+        message.push_str(tip);
+    }
+    json!({
+        "range": { "start": to_lsp(text, start), "end": to_lsp(text, end) },
+        "severity": 1,
+        "code": error.kind.code(),
+        "source": "jade",
+        "message": message,
+    })
+}
+
+fn document_symbol(text: &str, symbol: &Symbol) -> Json {
+    let kind = match symbol.kind {
+        SymbolKind::Class => 5,
+        SymbolKind::Enum => 10,
+        SymbolKind::Trait => 11,
+        _ => 12, // Function
+    };
+    json!({
+        "name": symbol.name,
+        "detail": symbol.detail,
+        "kind": kind,
+        "range": range(text, symbol.range),
+        "selectionRange": range(text, name_range(symbol)),
+    })
+}
+
+fn completion_kind(kind: CompletionKind) -> u8 {
+    match kind {
+        CompletionKind::Method => 2,
+        CompletionKind::Builtin | CompletionKind::Symbol(SymbolKind::Function) => 3,
+        CompletionKind::Symbol(SymbolKind::Class) => 7,
+        CompletionKind::Symbol(SymbolKind::Trait) => 8,
+        CompletionKind::Symbol(SymbolKind::Enum) => 13,
+        CompletionKind::Keyword => 14,
+        CompletionKind::Symbol(_) => 6, // Variable
+    }
+}
+
+/// Hover text for a declaration.
+fn describe(symbol: &Symbol) -> String {
+    match symbol.kind {
+        SymbolKind::Variable | SymbolKind::Parameter => {
+            let what = if symbol.kind == SymbolKind::Parameter {
+                "parameter"
+            } else {
+                "variable"
+            };
+            format!("```jade\n{}: {}\n```\n{}", symbol.detail, symbol.name, what)
+        }
+        _ => format!("```jade\n{}\n```", symbol.detail),
+    }
+}
+
+fn location(uri: &str, text: &str, symbol: &Symbol) -> Json {
+    json!({ "uri": uri, "range": range(text, name_range(symbol)) })
+}
+
+/// Where `name` is declared at the top level of one of the document's imports.
+fn imported_symbol(uri: &str, name: &str, analysis: &Analysis) -> Option<Json> {
+    let dir = uri_to_path(uri)?.parent()?.to_path_buf();
+    analysis
+        .import_candidates(&dir)
+        .into_iter()
+        .find_map(|file| {
+            let text = std::fs::read_to_string(&file).ok()?;
+            let module = Analysis::new(&text);
+            let symbol = module
+                .symbols
+                .iter()
+                .find(|s| s.name == name && s.block.0 == (1, 1))?;
+            Some(location(&path_to_uri(&file), &text, symbol))
+        })
+}
+
+fn name_range(symbol: &Symbol) -> (Pos, Pos) {
+    let (line, column) = symbol.pos;
+    (symbol.pos, (line, column + symbol.name.chars().count()))
+}
+
+fn range(text: &str, (start, end): (Pos, Pos)) -> Json {
+    json!({ "start": to_lsp(text, start), "end": to_lsp(text, end) })
+}
+
+/// An LSP position (0-based line, UTF-16 offset) as a lexer position.
+fn from_lsp(text: &str, position: &Json) -> Pos {
+    let line = position["line"].as_u64().unwrap_or(0) as usize;
+    let character = position["character"].as_u64().unwrap_or(0) as usize;
+    let mut units = 0;
+    let mut column = 1;
+    for c in text.lines().nth(line).unwrap_or_default().chars() {
+        if units >= character {
+            break;
+        }
+        units += c.len_utf16();
+        column += 1;
+    }
+    (line + 1, column)
+}
+
+/// A lexer position as an LSP position, clamped to the end of its line and of the document.
+fn to_lsp(text: &str, (line, column): Pos) -> Json {
+    let lines: Vec<&str> = text.lines().collect();
+    let (line, column) = if line > lines.len() {
+        (lines.len().max(1), usize::MAX)
+    } else {
+        (line.max(1), column)
+    };
+    let character: usize = lines
+        .get(line - 1)
+        .unwrap_or(&"")
+        .chars()
+        .take(column.saturating_sub(1))
+        .map(char::len_utf16)
+        .sum();
+    json!({ "line": line - 1, "character": character })
+}
+
+/// The LSP position just past the last character of `text`.
+fn document_end(text: &str) -> Json {
+    let last_line = text.rsplit('\n').next().unwrap_or_default();
+    json!({
+        "line": text.matches('\n').count(),
+        "character": last_line.chars().map(char::len_utf16).sum::<usize>(),
+    })
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    Some(PathBuf::from(
+        String::from_utf8_lossy(&decoded).into_owned(),
+    ))
+}
+
+fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
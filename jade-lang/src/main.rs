@@ -1,19 +1,24 @@
 //! Jade Programming Language — CLI binary.
 //!
-//! Delegates to the library crate for REPL, run, build, check, and jolt commands.
+//! Delegates to the library crate for REPL, run, build, check, test, and jolt commands.
 
 use clap::{Arg, Command};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use j_lang::compiler::AotCompiler;
-use j_lang::error::JError;
 #[cfg(not(feature = "jit"))]
-use j_lang::interpreter::Interpreter;
+use j_lang::bytecode;
+use j_lang::bundle::Bundle;
+use j_lang::compiler::{AotCompiler, Emit};
+use j_lang::diagnostics;
+use j_lang::doc;
+use j_lang::formatter;
+use j_lang::interpreter::{Coverage, Interpreter, Profiler};
+use j_lang::interpreter::DEFAULT_MAX_CALL_DEPTH;
 use j_lang::jolt::JoltManager;
-use j_lang::lexer::Lexer;
-use j_lang::parser::Parser;
+use j_lang::lint::{self, LintConfig, LintLevel};
 use j_lang::repl::Repl;
+use j_lang::testing;
 
 #[cfg(feature = "jit")]
 use j_lang::jit::JitCompiler;
@@ -50,8 +55,93 @@ fn read_stdin_or_exit() -> String {
     s
 }
 
-/// Run a single .jdl file (or stdin when file is "-") on the main thread.
-fn run_single_file(file: &str) {
+/// `--max-depth`, for the commands that run Jade code.
+fn max_depth_arg() -> Arg {
+    Arg::new("max-depth")
+        .long("max-depth")
+        .help("Maximum nested function calls before a stack overflow error (default: 10000)")
+        .value_name("N")
+        .value_parser(clap::value_parser!(usize))
+}
+
+/// `--deny-deprecated`, for the commands that run Jade code.
+fn deny_deprecated_arg() -> Arg {
+    Arg::new("deny-deprecated")
+        .long("deny-deprecated")
+        .action(clap::ArgAction::SetTrue)
+        .help("Treat calls to @deprecated functions as errors instead of warnings")
+}
+
+/// `--no-bytecode`, for the commands that run a program.
+fn no_bytecode_arg() -> Arg {
+    Arg::new("no-bytecode")
+        .long("no-bytecode")
+        .action(clap::ArgAction::SetTrue)
+        .help("Run on the tree-walking interpreter, without compiling or using the .jdc cache")
+}
+
+/// Call depth limit from `--max-depth`, or the interpreter default.
+fn max_call_depth(matches: &clap::ArgMatches) -> usize {
+    matches
+        .get_one::<usize>("max-depth")
+        .copied()
+        .unwrap_or(DEFAULT_MAX_CALL_DEPTH)
+}
+
+/// Print the `@profile` summary (if any profiled function ran) to stderr at exit.
+fn print_profile_summary(summary: Option<String>) {
+    if let Some(summary) = summary {
+        let _ = std::io::stdout().flush();
+        eprint!("{}", summary);
+    }
+}
+
+/// Whether `--deny-deprecated` (global flag) was given.
+fn deny_deprecated(matches: &clap::ArgMatches) -> bool {
+    matches.get_flag("deny-deprecated")
+}
+
+fn use_bytecode(matches: &clap::ArgMatches) -> bool {
+    !matches.get_flag("no-bytecode")
+}
+
+/// `jade run --profile`: run `file` on the tree-walking interpreter with a [`Profiler`], then
+/// print the slowest functions and lines to stderr and write the folded stacks for a flamegraph.
+fn run_profiled(file: &str, matches: &clap::ArgMatches, search_paths: Vec<String>) {
+    let source = read_file_or_exit(file);
+    let mut interpreter = Interpreter::new();
+    interpreter.set_max_call_depth(max_call_depth(matches));
+    interpreter.set_deny_deprecated(deny_deprecated(matches));
+    interpreter.set_program_path(file);
+    for path in search_paths {
+        interpreter.add_module_search_path(path);
+    }
+    interpreter.set_profiler(Profiler::new(file));
+    let result = interpreter.run(&source);
+    let profiler = interpreter.take_profiler().expect("the profiler is still installed");
+    let result = result.map_err(|e| interpreter.describe_error(&e, &source));
+    print_profile_summary(interpreter.profile_summary());
+
+    let top = matches.get_one::<usize>("profile-top").copied().unwrap_or(10);
+    eprint!("{}", profiler.summary(top));
+    let out = matches.get_one::<String>("profile-out").unwrap();
+    match std::fs::write(out, profiler.folded()) {
+        Ok(()) => eprintln!("🔥 Wrote folded stacks to {} (flamegraph.pl or inferno-flamegraph)", out),
+        Err(e) => {
+            eprintln!("❌ Failed to write {}: {}", out, e);
+            std::process::exit(1);
+        }
+    }
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Run a single .jdl file (or stdin when file is "-") on the main thread. `jit` builds run
+/// files through [`JitCompiler`] instead of bytecode, so hot functions get native code.
+#[cfg_attr(feature = "jit", allow(unused_variables))]
+fn run_single_file(file: &str, max_depth: usize, deny_deprecated: bool, bytecode: bool) {
     let (source, parent) = if file == "-" {
         (read_stdin_or_exit(), std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")))
     } else {
@@ -67,67 +157,143 @@ fn run_single_file(file: &str) {
     #[cfg(feature = "jit")]
     let ok = if file == "-" {
         let mut interp = Interpreter::new();
+        interp.set_max_call_depth(max_depth);
+        interp.set_deny_deprecated(deny_deprecated);
         if !parent_str.is_empty() {
             interp.add_module_search_path(&parent_str);
             if has_packages {
                 interp.add_module_search_path(&packages_str);
             }
         }
-        interp.run(&source)
+        let result = interp.run(&source).map_err(|e| interp.describe_error(&e, &source));
+        print_profile_summary(interp.profile_summary());
+        result
     } else {
         let mut jit = JitCompiler::new();
+        jit.set_max_call_depth(max_depth);
+        jit.set_deny_deprecated(deny_deprecated);
+        jit.set_program_path(file);
         if !parent_str.is_empty() {
             jit.add_module_search_path(&parent_str);
             if has_packages {
                 jit.add_module_search_path(&packages_str);
             }
         }
-        jit.run_file(PathBuf::from(file), false)
+        let result = jit
+            .run_file(PathBuf::from(file), false)
+            .map_err(|e| jit.describe_error(&e, &source));
+        print_profile_summary(jit.profile_summary());
+        result
     };
 
     #[cfg(not(feature = "jit"))]
     let ok = {
         let mut interpreter = Interpreter::new();
+        interpreter.set_max_call_depth(max_depth);
+        interpreter.set_deny_deprecated(deny_deprecated);
+        if file != "-" {
+            interpreter.set_program_path(file);
+        }
         if !parent_str.is_empty() {
             interpreter.add_module_search_path(&parent_str);
             if has_packages {
                 interpreter.add_module_search_path(&packages_str);
             }
         }
-        interpreter.run(&source)
+        let result = if bytecode && file != "-" {
+            bytecode::run_file_cached(Path::new(file), &source, &mut interpreter)
+        } else {
+            interpreter.run(&source)
+        }
+        .map_err(|e| interpreter.describe_error(&e, &source));
+        print_profile_summary(interpreter.profile_summary());
+        result
     };
 
     if let Err(e) = ok {
-        eprintln!("{}", JError::from_interpreter_message(&e));
+        eprintln!("{}", e);
         std::process::exit(1);
     }
     let _ = std::io::stdout().flush();
 }
 
+/// Run the program appended by `jade build --bundle`, if this executable carries one.
+fn run_bundled_program() {
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    let bundle = match Bundle::from_executable(&exe) {
+        Ok(Some(bundle)) => bundle,
+        Ok(None) => return,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+    let result = bundle.run();
+    let _ = std::io::stdout().flush();
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    std::process::exit(0);
+}
+
+/// `jade build --bundle`: a copy of this executable with the program and its imports appended.
+fn build_bundle(file: &str, output: Option<&String>) {
+    println!("📦 Bundling {}", file);
+    let path = Path::new(file);
+    let output = match output {
+        Some(output) => output.clone(),
+        None => path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "output".to_string()),
+    };
+    let output = if cfg!(windows) && !output.ends_with(".exe") {
+        format!("{}.exe", output)
+    } else {
+        output
+    };
+    let result = Bundle::collect(path).and_then(|bundle| {
+        for file in bundle.modules.values() {
+            println!("  + {}", file.path);
+        }
+        let exe = std::env::current_exe()
+            .map_err(|e| format!("Cannot locate the jade executable: {}", e))?;
+        bundle.write_executable(&exe, Path::new(&output))
+    });
+    if let Err(e) = result {
+        eprintln!("❌ Bundling failed: {}", e);
+        std::process::exit(1);
+    }
+    println!("✅ Bundle created: {}", output);
+}
+
 fn main() {
+    run_bundled_program();
     let matches = clap_matches();
     // Run "jade file.jdl" on the main thread so output appears in Cursor/VS Code terminal.
     if matches.get_one::<String>("file").is_some() && matches.subcommand().is_none() {
         let file = matches.get_one::<String>("file").unwrap();
-        run_single_file(file);
+        run_single_file(
+            file,
+            max_call_depth(&matches),
+            deny_deprecated(&matches),
+            use_bytecode(&matches),
+        );
         return;
     }
-    // REPL and other commands use a larger stack (avoid overflow on Windows).
-    const STACK_SIZE: usize = 8 * 1024 * 1024; // 8 MiB
-    let result = std::thread::Builder::new()
-        .stack_size(STACK_SIZE)
-        .spawn(run_cli)
-        .expect("failed to spawn CLI thread")
-        .join();
-    if let Err(e) = result {
-        std::panic::resume_unwind(e);
-    }
+    run_cli();
 }
 
 fn clap_matches() -> clap::ArgMatches {
     Command::new("jade")
         .version(env!("CARGO_PKG_VERSION"))
         .about("Jade Programming Language - Interpreter / JIT / AOT Compiler")
+        .arg(max_depth_arg())
+        .arg(deny_deprecated_arg())
+        .arg(no_bytecode_arg())
         .subcommand(Command::new("repl").about("Start interactive REPL"))
         .subcommand(Command::new("idle").about("Jade IDLE - interactive shell (like Python IDLE)"))
         .subcommand(
@@ -138,6 +304,30 @@ fn clap_matches() -> clap::ArgMatches {
                         .help("J source file to run")
                         .required(true)
                         .index(1),
+                )
+                .arg(max_depth_arg())
+                .arg(deny_deprecated_arg())
+                .arg(no_bytecode_arg())
+                .arg(
+                    Arg::new("profile")
+                        .long("profile")
+                        .help("Time functions and lines, print the slowest and write folded stacks")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("profile-out")
+                        .long("profile-out")
+                        .help("Where to write the folded stacks (default: profile.folded)")
+                        .value_name("FILE")
+                        .default_value("profile.folded"),
+                )
+                .arg(
+                    Arg::new("profile-top")
+                        .long("profile-top")
+                        .help("Rows in the tables of slowest functions and lines (default: 10)")
+                        .value_name("N")
+                        .default_value("10")
+                        .value_parser(clap::value_parser!(usize)),
                 ),
         )
         .subcommand(
@@ -161,18 +351,173 @@ fn clap_matches() -> clap::ArgMatches {
                         .long("output")
                         .help("Output binary name")
                         .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("emit")
+                        .long("emit")
+                        .help("Build with this backend and keep its source next to the binary")
+                        .value_name("KIND")
+                        .value_parser(["c", "llvm"]),
+                )
+                .arg(
+                    Arg::new("bundle")
+                        .long("bundle")
+                        .help("Package the interpreter, the script and its imports into one executable")
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with_all(["release", "emit"]),
                 ),
         )
         .subcommand(
             Command::new("check")
-                .about("Check Jade file for syntax errors")
+                .about("Check Jade files for syntax errors")
                 .arg(
                     Arg::new("file")
-                        .help("Jade source file to check")
+                        .help("Jade source files to check")
+                        .required(true)
+                        .num_args(1..)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help("Output format for diagnostics")
+                        .value_parser(["text", "json"])
+                        .default_value("text"),
+                ),
+        )
+        .subcommand(
+            Command::new("explain")
+                .about("Explain an error code, e.g. J0303")
+                .arg(
+                    Arg::new("code")
+                        .help("Error code from a diagnostic")
                         .required(true)
                         .index(1),
                 ),
         )
+        .subcommand(
+            Command::new("lsp").about("Run the language server on stdin/stdout, for editors"),
+        )
+        .subcommand(
+            Command::new("debug")
+                .about("Run a Jade file under the step debugger")
+                .arg(
+                    Arg::new("file")
+                        .help("Jade source file to debug")
+                        .required_unless_present("dap")
+                        .index(1),
+                )
+                .arg(max_depth_arg())
+                .arg(
+                    Arg::new("dap")
+                        .long("dap")
+                        .help("Serve the Debug Adapter Protocol on stdin/stdout, for editors")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("fmt")
+                .about("Format Jade files in place")
+                .arg(
+                    Arg::new("path")
+                        .help("Files or directories to format (default: current directory)")
+                        .num_args(1..)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("check")
+                        .long("check")
+                        .help("List files that are not formatted instead of rewriting them")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("lint")
+                .about("Report likely mistakes in Jade files without running them")
+                .arg(
+                    Arg::new("path")
+                        .help("Files or directories to lint (default: current directory)")
+                        .num_args(1..)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help("Output format for findings")
+                        .value_parser(["text", "json"])
+                        .default_value("text"),
+                ),
+        )
+        .subcommand(
+            Command::new("doc")
+                .about("Generate API documentation from doc comments")
+                .arg(
+                    Arg::new("path")
+                        .help("Project, package directory or file to document (default: current directory)")
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help("Output format")
+                        .value_parser(["html", "markdown"])
+                        .default_value("html"),
+                )
+                .arg(
+                    Arg::new("out")
+                        .long("out")
+                        .short('o')
+                        .help("Directory to write the documentation to")
+                        .value_name("DIR")
+                        .default_value("docs/api"),
+                ),
+        )
+        .subcommand(
+            Command::new("test")
+                .about("Run test blocks in .jdl files")
+                .arg(
+                    Arg::new("path")
+                        .help("File or directory to search for tests (default: current directory)")
+                        .required(false)
+                        .index(1),
+                )
+                .arg(max_depth_arg())
+                .arg(deny_deprecated_arg())
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .help("Seed for property tests (replays a reported failure)")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(u64)),
+                )
+                .arg(
+                    Arg::new("cases")
+                        .long("cases")
+                        .help("Generated inputs per property test (default: 100)")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("coverage")
+                        .long("coverage")
+                        .help("Record line and branch coverage and write an LCOV report")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("coverage-out")
+                        .long("coverage-out")
+                        .help("Where to write the LCOV report (default: coverage/lcov.info)")
+                        .value_name("FILE")
+                        .default_value("coverage/lcov.info"),
+                )
+                .arg(
+                    Arg::new("coverage-min")
+                        .long("coverage-min")
+                        .help("Fail when less than this percentage of lines is covered")
+                        .value_name("PERCENT")
+                        .value_parser(clap::value_parser!(f64)),
+                ),
+        )
         .arg(
             Arg::new("file")
                 .index(1)
@@ -265,33 +610,63 @@ fn run_cli() {
             let packages_str = packages_path.to_string_lossy().to_string();
             let has_packages = packages_path.exists();
 
+            if sub_matches.get_flag("profile") {
+                let mut search_paths = Vec::new();
+                if !parent_str.is_empty() {
+                    search_paths.push(parent_str);
+                    if has_packages {
+                        search_paths.push(packages_str);
+                    }
+                }
+                run_profiled(&file, sub_matches, search_paths);
+                return;
+            }
+
             #[cfg(feature = "jit")]
             let ok = {
                 let mut jit = JitCompiler::new();
+                jit.set_max_call_depth(max_call_depth(sub_matches));
+                jit.set_deny_deprecated(deny_deprecated(sub_matches));
+                jit.set_program_path(&file);
                 if !parent_str.is_empty() {
                     jit.add_module_search_path(&parent_str);
                     if has_packages {
                         jit.add_module_search_path(&packages_str);
                     }
                 }
-                jit.run_file(file_path, false)
+                let result = jit.run_file(file_path, false).map_err(|e| {
+                    let source = std::fs::read_to_string(&file).unwrap_or_default();
+                    jit.describe_error(&e, &source)
+                });
+                print_profile_summary(jit.profile_summary());
+                result
             };
 
             #[cfg(not(feature = "jit"))]
             let ok = {
                 let source = read_file_or_exit(&file);
                 let mut interpreter = Interpreter::new();
+                interpreter.set_max_call_depth(max_call_depth(sub_matches));
+                interpreter.set_deny_deprecated(deny_deprecated(sub_matches));
+                interpreter.set_program_path(&file);
                 if !parent_str.is_empty() {
                     interpreter.add_module_search_path(&parent_str);
                     if has_packages {
                         interpreter.add_module_search_path(&packages_str);
                     }
                 }
-                interpreter.run(&source)
+                let result = if use_bytecode(sub_matches) {
+                    bytecode::run_file_cached(&file_path, &source, &mut interpreter)
+                } else {
+                    interpreter.run(&source)
+                }
+                .map_err(|e| interpreter.describe_error(&e, &source));
+                print_profile_summary(interpreter.profile_summary());
+                result
             };
 
             if let Err(e) = ok {
-                eprintln!("{}", JError::from_interpreter_message(&e));
+                eprintln!("{}", e);
                 std::process::exit(1);
             }
             let _ = std::io::stdout().flush();
@@ -304,19 +679,19 @@ fn run_cli() {
             let file = require_arg(sub_matches, "file", "No file specified");
             let release = sub_matches.get_flag("release");
             let output = sub_matches.get_one::<String>("output");
-            let mode = if release { "release" } else { "debug" };
-            let use_llvm = cfg!(feature = "llvm");
-
-            if use_llvm {
-                println!("🔨 Compiling {} in {} mode with LLVM", file, mode);
-            } else {
-                println!(
-                    "🔨 Compiling {} in {} mode (fallback - no LLVM)",
-                    file, mode
-                );
+            if sub_matches.get_flag("bundle") {
+                build_bundle(&file, output);
+                return;
             }
+            let mode = if release { "release" } else { "debug" };
+            println!("🔨 Compiling {} in {} mode", file, mode);
 
             let mut compiler = AotCompiler::new();
+            compiler.set_emit(match sub_matches.get_one::<String>("emit").map(String::as_str) {
+                Some("c") => Some(Emit::C),
+                Some("llvm") => Some(Emit::Llvm),
+                _ => None,
+            });
             if let Err(e) =
                 compiler.compile_file(PathBuf::from(&file), release, output.map(String::as_str))
             {
@@ -324,31 +699,290 @@ fn run_cli() {
                 std::process::exit(1);
             }
             println!("✅ Compilation successful!");
-            if !use_llvm {
-                println!("💡 For LLVM support, install Visual Studio Build Tools and rebuild with: cargo build --features llvm");
-            }
         }
         Some(("check", sub_matches)) => {
+            let files: Vec<String> = sub_matches
+                .get_many::<String>("file")
+                .map(|files| files.cloned().collect())
+                .unwrap_or_default();
+            let json = sub_matches.get_one::<String>("format").map(String::as_str) == Some("json");
+
+            let mut diagnostics = Vec::new();
+            for file in &files {
+                if !json {
+                    println!("🔍 Checking {}", file);
+                }
+                let source = read_file_or_exit(file);
+                diagnostics.extend(diagnostics::check_source(&source, Some(file)));
+            }
+
+            if json {
+                let report: Vec<_> = diagnostics.iter().map(diagnostics::to_json).collect();
+                println!("{}", serde_json::Value::Array(report));
+            } else if diagnostics.is_empty() {
+                println!("✅ No syntax errors found");
+            } else {
+                for error in &diagnostics {
+                    eprintln!("{}", error);
+                }
+                eprintln!("❌ {} error(s) found", diagnostics.len());
+            }
+            if !diagnostics.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Some(("explain", sub_matches)) => {
+            let code = require_arg(sub_matches, "code", "No error code specified");
+            match j_lang::error::explain(&code) {
+                Some(explanation) => println!("{}", explanation),
+                None => {
+                    eprintln!("❌ Unknown error code: {}", code);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(("lsp", _)) => match j_lang::lsp::run_stdio() {
+            Ok(code) => std::process::exit(code),
+            Err(e) => {
+                eprintln!("❌ Language server I/O error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Some(("debug", sub_matches)) if sub_matches.get_flag("dap") => {
+            match j_lang::debugger::dap::run_stdio() {
+                Ok(code) => std::process::exit(code),
+                Err(e) => {
+                    eprintln!("❌ Debug adapter I/O error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(("debug", sub_matches)) => {
             let file = require_arg(sub_matches, "file", "No file specified");
-            println!("🔍 Checking {}", file);
-            let source = read_file_or_exit(&file);
-
-            let mut lexer = Lexer::new(&source);
-            match lexer.tokenize() {
-                Ok(tokens) => {
-                    let mut parser = Parser::new(tokens);
-                    match parser.parse() {
-                        Ok(_) => println!("✅ No syntax errors found"),
-                        Err(e) => {
-                            eprintln!("{}", JError::from_interpreter_message(&e));
-                            std::process::exit(1);
+            let code = j_lang::debugger::run_console(Path::new(&file), max_call_depth(sub_matches));
+            std::process::exit(code);
+        }
+        Some(("fmt", sub_matches)) => {
+            let check = sub_matches.get_flag("check");
+            let paths: Vec<String> = sub_matches
+                .get_many::<String>("path")
+                .map(|paths| paths.cloned().collect())
+                .unwrap_or_else(|| vec![".".to_string()]);
+            let files: Vec<PathBuf> = paths
+                .iter()
+                .flat_map(|path| testing::discover_source_files(Path::new(path)))
+                .collect();
+
+            let mut unformatted = 0;
+            let mut failed = false;
+            for file in &files {
+                let name = file.display().to_string();
+                let source = read_file_or_exit(&name);
+                match formatter::format_source(&source) {
+                    Ok(formatted) if formatted == source => {}
+                    Ok(formatted) => {
+                        unformatted += 1;
+                        if check {
+                            println!("Would reformat: {}", name);
+                        } else if let Err(e) = std::fs::write(file, formatted) {
+                            eprintln!("❌ Error writing {}: {}", name, e);
+                            failed = true;
+                        } else {
+                            println!("Formatted {}", name);
+                        }
+                    }
+                    Err(mut error) => {
+                        if error.file.is_none() {
+                            error.file = Some(name.clone());
                         }
+                        eprintln!("{}", error);
+                        failed = true;
                     }
                 }
-                Err(e) => {
-                    eprintln!("{}", JError::from_interpreter_message(&format!("Lexer error: {}", e)));
-                    std::process::exit(1);
+            }
+            if check && unformatted > 0 {
+                eprintln!("❌ {} of {} file(s) would be reformatted", unformatted, files.len());
+            } else if check && !failed {
+                println!("✅ {} file(s) already formatted", files.len());
+            } else if !failed {
+                println!("✅ {} of {} file(s) reformatted", unformatted, files.len());
+            }
+            if failed || (check && unformatted > 0) {
+                std::process::exit(1);
+            }
+        }
+        Some(("doc", sub_matches)) => {
+            let root = sub_matches
+                .get_one::<String>("path")
+                .map(String::as_str)
+                .unwrap_or(".");
+            let format = match sub_matches.get_one::<String>("format").map(String::as_str) {
+                Some("markdown") => doc::Format::Markdown,
+                _ => doc::Format::Html,
+            };
+            let out = Path::new(sub_matches.get_one::<String>("out").unwrap());
+            let (project, errors) = doc::document_project(Path::new(root));
+            if !errors.is_empty() {
+                let failed = errors.len();
+                for (file, mut error) in errors {
+                    if error.file.is_none() {
+                        error.file = Some(file.display().to_string());
+                    }
+                    eprintln!("{}", error);
+                }
+                // Nothing is written, so a broken file never leaves half a site behind
+                eprintln!("❌ {} file(s) failed to parse; no documentation written", failed);
+                std::process::exit(1);
+            }
+            if let Err(e) = doc::write_docs(&project, out, format) {
+                eprintln!("❌ Error writing documentation to {}: {}", out.display(), e);
+                std::process::exit(1);
+            }
+            println!(
+                "📚 Documented {} module(s) in {}",
+                project.pages.len(),
+                out.display()
+            );
+        }
+        Some(("lint", sub_matches)) => {
+            let json = sub_matches.get_one::<String>("format").map(String::as_str) == Some("json");
+            let paths: Vec<String> = sub_matches
+                .get_many::<String>("path")
+                .map(|paths| paths.cloned().collect())
+                .unwrap_or_else(|| vec![".".to_string()]);
+            let files: Vec<PathBuf> = paths
+                .iter()
+                .flat_map(|path| testing::discover_source_files(Path::new(path)))
+                .collect();
+            let config = match JoltManager::find_manifest_path(Path::new(".")) {
+                Some(manifest) => JoltManager::read_manifest(&manifest)
+                    .and_then(|manifest| LintConfig::from_table(&manifest.lint)),
+                None => Ok(LintConfig::default()),
+            };
+            let config = config.unwrap_or_else(|e| {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            });
+
+            let mut report = Vec::new();
+            let (mut warnings, mut errors) = (0, 0);
+            for file in &files {
+                let name = file.display().to_string();
+                let source = read_file_or_exit(&name);
+                match lint::lint_source(&source, Some(file), &config) {
+                    Ok(lints) => {
+                        for finding in lints {
+                            if finding.level == LintLevel::Deny {
+                                errors += 1;
+                            } else {
+                                warnings += 1;
+                            }
+                            if json {
+                                report.push(finding.to_json());
+                            } else {
+                                eprintln!("{}", finding);
+                            }
+                        }
+                    }
+                    Err(mut error) => {
+                        let mut syntax_errors = diagnostics::check_source(&source, Some(&name));
+                        if syntax_errors.is_empty() {
+                            error.file = Some(name.clone());
+                            syntax_errors.push(*error);
+                        }
+                        for error in syntax_errors {
+                            errors += 1;
+                            if json {
+                                report.push(diagnostics::to_json(&error));
+                            } else {
+                                eprintln!("{}", error);
+                            }
+                        }
+                    }
+                }
+            }
+
+            if json {
+                println!("{}", serde_json::Value::Array(report));
+            } else if warnings + errors == 0 {
+                println!("✅ No problems found in {} file(s)", files.len());
+            } else {
+                eprintln!(
+                    "{} {} warning(s), {} error(s) in {} file(s)",
+                    if errors > 0 { "❌" } else { "⚠️" },
+                    warnings,
+                    errors,
+                    files.len()
+                );
+            }
+            if errors > 0 {
+                std::process::exit(1);
+            }
+        }
+        Some(("test", sub_matches)) => {
+            let path = sub_matches
+                .get_one::<String>("path")
+                .cloned()
+                .unwrap_or_else(|| ".".to_string());
+            let root = PathBuf::from(&path);
+            if !root.exists() {
+                eprintln!("❌ Path not found: {}", path);
+                std::process::exit(1);
+            }
+            let files = testing::discover_test_files(&root);
+            if files.is_empty() {
+                println!("⚠️  No tests found in {}", path);
+                return;
+            }
+            let mut options = testing::TestOptions {
+                seed: sub_matches.get_one::<u64>("seed").copied(),
+                max_depth: max_call_depth(sub_matches),
+                deny_deprecated: deny_deprecated(sub_matches),
+                coverage: sub_matches.get_flag("coverage")
+                    || sub_matches.contains_id("coverage-min"),
+                ..Default::default()
+            };
+            if let Some(&cases) = sub_matches.get_one::<usize>("cases") {
+                options.cases = cases;
+            }
+            let mut reports: Vec<_> = files
+                .iter()
+                .map(|f| testing::run_test_file(f, &options))
+                .collect();
+            let ok = testing::print_report(&reports);
+            if options.coverage {
+                let mut coverage = Coverage::default();
+                for report in &mut reports {
+                    coverage.merge(report.coverage.take().unwrap_or_default());
                 }
+                testing::print_coverage(&coverage);
+                let out = PathBuf::from(sub_matches.get_one::<String>("coverage-out").unwrap());
+                let written = out
+                    .parent()
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|_| std::fs::write(&out, coverage.to_lcov()));
+                match written {
+                    Ok(()) => println!("📝 Wrote LCOV report to {}", out.display()),
+                    Err(e) => {
+                        eprintln!("❌ Failed to write {}: {}", out.display(), e);
+                        std::process::exit(1);
+                    }
+                }
+                if let Some(&min) = sub_matches.get_one::<f64>("coverage-min") {
+                    let (hit, found) = coverage.line_totals();
+                    let percent = testing::coverage_percent(hit, found).unwrap_or(100.0);
+                    if percent < min {
+                        eprintln!(
+                            "❌ Line coverage {:.1}% is below the required {}%",
+                            percent, min
+                        );
+                        std::process::exit(1);
+                    }
+                }
+            }
+            if !ok {
+                std::process::exit(1);
             }
         }
         Some(("jolt", sub_matches)) => {
@@ -3,9 +3,24 @@
 //! Builds an AST from the token stream produced by the lexer. Entry point is
 //! [`Parser::parse`], which returns a list of top-level statements.
 
-use crate::error::JError;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::error::{ErrorKind, JError, JResult};
 use crate::lexer::{Token, TokenType};
 
+/// Where a node starts in the source: 1-based line and column, and the file for code parsed
+/// from an imported module (`None` for the program being run). Line 0 means unknown.
+///
+/// Spans are part of AST equality; to compare code laid out differently, parse it with
+/// [`Parser::without_spans`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub file: Option<Arc<str>>,
+    pub line: usize,
+    pub column: usize,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ClassField {
     pub name: String,
@@ -14,6 +29,8 @@ pub struct ClassField {
     pub is_public: bool,
     pub is_readonly: bool,
     pub is_static: bool,
+    /// The `##` / `///` doc comment above the field.
+    pub doc: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -42,7 +59,7 @@ pub enum AstNode {
     Matrix(Vec<Vec<AstNode>>), // 2D matrix literal
 
     // Variables and declarations
-    Identifier(String),
+    Identifier(String, Span),
     VarDeclaration {
         var_type: String,
         name: String,
@@ -50,6 +67,7 @@ pub enum AstNode {
         immutable: bool,
         is_static: bool,
         type_modifier: Option<String>, // "untrusted" | "secret" | "canary"
+        span: Span,                    // of the name
     },
     TypeConversion {
         target_type: String,
@@ -63,15 +81,18 @@ pub enum AstNode {
         return_type: Option<String>,
         body: Box<AstNode>,
         decorators: Vec<Decorator>, // @decorator syntax
+        doc: Option<String>,        // `##` / `///` comment above the declaration
     },
     FunctionCall {
         name: String,
         args: Vec<AstNode>,
+        span: Span,
     },
     /// Call on any expression (e.g. instance.method(args) or Class.new(args))
     Call {
         callee: Box<AstNode>,
         args: Vec<AstNode>,
+        span: Span,
     },
     /// Broadcast call: fn.(list, scalar) applies fn element-wise (from jnew_features)
     BroadcastCall {
@@ -88,14 +109,17 @@ pub enum AstNode {
         condition: Box<AstNode>,
         then_branch: Box<AstNode>,
         else_branch: Option<Box<AstNode>>,
+        span: Span,
     },
     Match {
         expr: Box<AstNode>,
         arms: Vec<MatchArm>,
+        span: Span,
     },
     Cond {
         value: Box<AstNode>,
         branches: Vec<CondBranch>,
+        span: Span,
     },
     When {
         value: Box<AstNode>,
@@ -122,11 +146,13 @@ pub enum AstNode {
     While {
         condition: Box<AstNode>,
         body: Box<AstNode>,
+        span: Span, // of `while`
     },
     For {
         var: String,
         iterable: Box<AstNode>,
         body: Box<AstNode>,
+        span: Span,
     },
     ForIndexed {
         index_var: String,
@@ -140,10 +166,12 @@ pub enum AstNode {
         left: Box<AstNode>,
         operator: BinaryOp,
         right: Box<AstNode>,
+        span: Span,
     },
     Unary {
         operator: UnaryOp,
         operand: Box<AstNode>,
+        span: Span,
     },
     Pipeline {
         left: Box<AstNode>,
@@ -152,10 +180,12 @@ pub enum AstNode {
     Index {
         object: Box<AstNode>,
         index: Box<AstNode>,
+        span: Span,
     },
     DotAccess {
         object: Box<AstNode>,
         field: String,
+        span: Span,
     },
     Slice {
         object: Box<AstNode>,
@@ -170,14 +200,15 @@ pub enum AstNode {
     Assignment {
         name: String,
         value: Box<AstNode>,
+        span: Span,
     },
     DestructuringAssignment {
         targets: Vec<String>,
         value: Box<AstNode>,
     },
-    Return(Option<Box<AstNode>>),
-    Break,
-    Continue,
+    Return(Option<Box<AstNode>>, Span), // span of `return`
+    Break(Span),
+    Continue(Span),
     Defer(Box<AstNode>),
     ConvergeLoop {
         body: Box<AstNode>,
@@ -191,7 +222,7 @@ pub enum AstNode {
         catch_block: Box<AstNode>,
         finally_block: Option<Box<AstNode>>,
     },
-    Throw(Box<AstNode>),
+    Throw(Box<AstNode>, Span), // span of `panic`
 
     // Ranges
     Range {
@@ -199,6 +230,7 @@ pub enum AstNode {
         end: Box<AstNode>,
         inclusive: bool,
         step: Option<Box<AstNode>>,
+        span: Span,
     },
 
     // J-specific constructs
@@ -206,6 +238,7 @@ pub enum AstNode {
         name: String,
         backing_type: Option<String>,
         variants: Vec<(String, Option<AstNode>)>,
+        doc: Option<String>,
     },
     ClassDeclaration {
         name: String,
@@ -216,6 +249,7 @@ pub enum AstNode {
         methods: Vec<AstNode>, // FunctionDeclaration nodes
         static_fields: Vec<ClassField>,
         static_methods: Vec<AstNode>,
+        doc: Option<String>,
     },
 
     // Anonymous variable
@@ -422,6 +456,7 @@ pub enum AstNode {
     TraitDeclaration {
         name: String,
         methods: Vec<AstNode>, // Method signatures (FunctionDeclaration without body)
+        doc: Option<String>,
     },
 
     // Async/Await
@@ -430,6 +465,7 @@ pub enum AstNode {
         params: Vec<(String, String)>,
         return_type: Option<String>,
         body: Box<AstNode>,
+        doc: Option<String>,
     },
     AwaitExpression {
         expr: Box<AstNode>,
@@ -439,10 +475,12 @@ pub enum AstNode {
     ModuleDeclaration {
         name: String,
         body: Box<AstNode>,
+        doc: Option<String>,
     },
     ImportStatement {
         module_path: Vec<String>, // e.g., ["std", "io"]
         items: Vec<String>,       // specific items to import, empty = import all
+        span: Span,
     },
     UseStatement {
         path: Vec<String>,
@@ -455,6 +493,7 @@ pub enum AstNode {
         params: Vec<(String, String)>,
         return_type: Option<String>,
         body: Box<AstNode>,
+        doc: Option<String>,
     },
     GenericClass {
         name: String,
@@ -465,6 +504,7 @@ pub enum AstNode {
         methods: Vec<AstNode>,
         static_fields: Vec<ClassField>,
         static_methods: Vec<AstNode>,
+        doc: Option<String>,
     },
 
     // jnew_features: extensions, loops, security, enterprise, tooling
@@ -671,6 +711,8 @@ pub struct CondBranch {
     pub is_else: bool,
 }
 
+// Literal cases hold their node inline, as `Pattern` does
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum SwitchCase {
     Range { start: Box<AstNode>, end: Box<AstNode> },
@@ -684,7 +726,7 @@ pub struct Decorator {
     pub args: Vec<AstNode>, // Arguments for parameterized decorators like @retry(3)
 }
 
-#[allow(dead_code)]
+#[allow(dead_code, clippy::large_enum_variant)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum Pattern {
     Literal(AstNode),
@@ -732,14 +774,561 @@ pub enum UnaryOp {
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    /// File recorded in spans ([`Parser::set_file`]).
+    file: Option<Arc<str>>,
+    /// Errors recovered from so far, when parsing with [`Parser::parse_recovering`].
+    errors: Option<Vec<JError>>,
+    /// Record positions in spans; off, every span is `Span::default()`.
+    spans: bool,
+}
+
+impl AstNode {
+    /// Source position of nodes that can fail at runtime (names, operators, calls, indexing,
+    /// assignments, loops and ranges).
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            AstNode::Identifier(_, span)
+            | AstNode::FunctionCall { span, .. }
+            | AstNode::Call { span, .. }
+            | AstNode::For { span, .. }
+            | AstNode::If { span, .. }
+            | AstNode::Match { span, .. }
+            | AstNode::Cond { span, .. }
+            | AstNode::While { span, .. }
+            | AstNode::Return(_, span)
+            | AstNode::Break(span)
+            | AstNode::Continue(span)
+            | AstNode::Throw(_, span)
+            | AstNode::Binary { span, .. }
+            | AstNode::Unary { span, .. }
+            | AstNode::Index { span, .. }
+            | AstNode::DotAccess { span, .. }
+            | AstNode::Assignment { span, .. }
+            | AstNode::Range { span, .. } => Some(span),
+            _ => None,
+        }
+    }
+
+    /// The doc comment of a declaration: the `##` or `///` lines right above it.
+    pub fn doc(&self) -> Option<&str> {
+        match self {
+            AstNode::FunctionDeclaration { doc, .. }
+            | AstNode::EnumDeclaration { doc, .. }
+            | AstNode::ClassDeclaration { doc, .. }
+            | AstNode::TraitDeclaration { doc, .. }
+            | AstNode::AsyncFunction { doc, .. }
+            | AstNode::ModuleDeclaration { doc, .. }
+            | AstNode::GenericFunction { doc, .. }
+            | AstNode::GenericClass { doc, .. } => doc.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Attach a doc comment to a declaration; other nodes have nowhere to keep one.
+    fn set_doc(&mut self, text: String) {
+        if let AstNode::FunctionDeclaration { doc, .. }
+        | AstNode::EnumDeclaration { doc, .. }
+        | AstNode::ClassDeclaration { doc, .. }
+        | AstNode::TraitDeclaration { doc, .. }
+        | AstNode::AsyncFunction { doc, .. }
+        | AstNode::ModuleDeclaration { doc, .. }
+        | AstNode::GenericFunction { doc, .. }
+        | AstNode::GenericClass { doc, .. } = self
+        {
+            *doc = Some(text);
+        }
+    }
+
+    /// The earliest position recorded in this node or the nodes inside it: roughly where a
+    /// statement starts, for nodes that have no span of their own.
+    pub fn first_span(&self) -> Option<&Span> {
+        let own = match self {
+            AstNode::VarDeclaration { span, .. } | AstNode::ImportStatement { span, .. } => {
+                Some(span)
+            }
+            _ => self.span(),
+        };
+        own.into_iter()
+            .chain(self.children().into_iter().filter_map(AstNode::first_span))
+            .filter(|span| span.line > 0)
+            .min_by_key(|span| (span.line, span.column))
+    }
+
+    /// The nodes directly inside this one, in field order: operands, bodies and arguments, and
+    /// the expressions held by match arms, cond branches, switch cases, class field defaults
+    /// and decorator arguments.
+    pub fn children(&self) -> Vec<&AstNode> {
+        let mut children = Vec::new();
+        match self {
+            AstNode::StringInterpolation { parts } => {
+                children.extend(parts);
+            }
+            AstNode::List(items)
+            | AstNode::Tuple(items)
+            | AstNode::Vector(items)
+            | AstNode::Block(items) => {
+                children.extend(items);
+            }
+            AstNode::Dict(items) => {
+                children.extend(items.iter().flat_map(|(key, value)| [key, value]));
+            }
+            AstNode::Matrix(items) => {
+                children.extend(items.iter().flatten());
+            }
+            AstNode::VarDeclaration { value, .. }
+            | AstNode::Assignment { value, .. }
+            | AstNode::DestructuringAssignment { value, .. }
+            | AstNode::LiveVariable { value, .. }
+            | AstNode::StackAllocation { value, .. }
+            | AstNode::Yield { value }
+            | AstNode::GhostDeclaration { value, .. } => children.push(value),
+            AstNode::FunctionDeclaration {
+                body, decorators, ..
+            } => {
+                children.push(body);
+                children.extend(decorators.iter().flat_map(|decorator| &decorator.args));
+            }
+            AstNode::FunctionCall { args, .. } | AstNode::MacroCall { args, .. } => {
+                children.extend(args);
+            }
+            AstNode::Call { callee, args, .. } | AstNode::BroadcastCall { callee, args } => {
+                children.push(callee);
+                children.extend(args);
+            }
+            AstNode::Lambda { body, .. }
+            | AstNode::ConvergeLoop { body }
+            | AstNode::WhileNonzero { body, .. }
+            | AstNode::WhileMatchLoop { body, .. }
+            | AstNode::AutoFunction { body, .. }
+            | AstNode::CheatBlock { body }
+            | AstNode::TraceBlock { body }
+            | AstNode::TightBlock { body }
+            | AstNode::TaskSpawn { body }
+            | AstNode::TestCase { body, .. }
+            | AstNode::PropertyTest { body, .. }
+            | AstNode::MacroDefinition { body, .. }
+            | AstNode::AsyncFunction { body, .. }
+            | AstNode::ModuleDeclaration { body, .. }
+            | AstNode::GenericFunction { body, .. }
+            | AstNode::MemoVarDeclaration { body, .. }
+            | AstNode::RollbackBlock { body, .. }
+            | AstNode::RetryBlock { body, .. }
+            | AstNode::SecureBlock { body }
+            | AstNode::TaskDecl { body, .. }
+            | AstNode::Generator { body, .. }
+            | AstNode::VeilBlock { body }
+            | AstNode::WarpTemplate { body, .. } => children.push(body),
+            AstNode::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            }
+            | AstNode::Unless {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                children.push(condition);
+                children.push(then_branch);
+                children.extend(else_branch.as_deref());
+            }
+            AstNode::Match { expr, arms, .. } => {
+                children.push(expr);
+                for arm in arms {
+                    arm.pattern.literals(&mut children);
+                    children.extend(&arm.guard);
+                    children.push(&arm.body);
+                }
+            }
+            AstNode::Cond { value, branches, .. } | AstNode::When { value, branches } => {
+                children.push(value);
+                children.extend(
+                    branches
+                        .iter()
+                        .flat_map(|branch| [&branch.condition, &branch.body]),
+                );
+            }
+            AstNode::Either {
+                expr,
+                true_body,
+                false_body,
+            } => children.extend([&**expr, &**true_body, &**false_body]),
+            AstNode::GuardReturn {
+                condition,
+                return_value,
+            } => children.extend([&**condition, &**return_value]),
+            AstNode::Switch { expr, cases } => {
+                children.push(expr);
+                for (case, body) in cases {
+                    match case {
+                        SwitchCase::Range { start, end } => children.extend([&**start, &**end]),
+                        SwitchCase::Literal(value) => children.push(value),
+                        SwitchCase::Else => {}
+                    }
+                    children.push(body);
+                }
+            }
+            AstNode::While { condition, body, .. } => children.extend([&**condition, &**body]),
+            AstNode::For { iterable, body, .. }
+            | AstNode::ForIndexed { iterable, body, .. }
+            | AstNode::ForReverse { iterable, body, .. }
+            | AstNode::SweepLoop { iterable, body, .. }
+            | AstNode::ShrinkLoop { iterable, body, .. }
+            | AstNode::MeetLoop { iterable, body, .. } => children.extend([&**iterable, &**body]),
+            AstNode::Binary { left, right, .. } | AstNode::Pipeline { left, right } => {
+                children.extend([&**left, &**right])
+            }
+            AstNode::Unary { operand, .. } => children.push(operand),
+            AstNode::Index { object, index, .. } => children.extend([&**object, &**index]),
+            AstNode::DotAccess { object, .. } => children.push(object),
+            AstNode::Slice {
+                object,
+                start,
+                end,
+                step,
+            } => {
+                children.push(object);
+                children.extend(start.as_deref());
+                children.extend(end.as_deref());
+                children.extend(step.as_deref());
+            }
+            AstNode::Expression(inner)
+            | AstNode::Defer(inner)
+            | AstNode::TryExpression(inner)
+            | AstNode::Throw(inner, _) => children.push(inner),
+            AstNode::Return(value, _) => {
+                children.extend(value.as_deref());
+            }
+            AstNode::TryCatch {
+                try_block,
+                catch_block,
+                finally_block,
+                ..
+            } => {
+                children.push(try_block);
+                children.push(catch_block);
+                children.extend(finally_block.as_deref());
+            }
+            AstNode::Range {
+                start, end, step, ..
+            } => {
+                children.push(start);
+                children.push(end);
+                children.extend(step.as_deref());
+            }
+            AstNode::EnumDeclaration { variants, .. } => {
+                children.extend(variants.iter().filter_map(|(_, value)| value.as_ref()));
+            }
+            AstNode::ClassDeclaration {
+                fields,
+                methods,
+                static_fields,
+                static_methods,
+                ..
+            }
+            | AstNode::GenericClass {
+                fields,
+                methods,
+                static_fields,
+                static_methods,
+                ..
+            } => {
+                children.extend(
+                    fields
+                        .iter()
+                        .filter_map(|field| field.default_value.as_ref()),
+                );
+                children.extend(methods);
+                children.extend(
+                    static_fields
+                        .iter()
+                        .filter_map(|field| field.default_value.as_ref()),
+                );
+                children.extend(static_methods);
+            }
+            AstNode::ForStep {
+                start,
+                step,
+                condition,
+                body,
+                ..
+            } => {
+                children.push(start);
+                children.push(step);
+                children.extend(condition.as_deref());
+                children.push(body);
+            }
+            AstNode::ForZip {
+                iterables, body, ..
+            } => {
+                children.extend(iterables);
+                children.push(body);
+            }
+            AstNode::ForParallel {
+                iterable,
+                body,
+                workers,
+                ..
+            } => {
+                children.push(iterable);
+                children.push(body);
+                children.extend(workers.as_deref());
+            }
+            AstNode::ForChunked {
+                iterable,
+                chunk_size,
+                body,
+                ..
+            } => children.extend([&**iterable, &**chunk_size, &**body]),
+            AstNode::ForFiltered {
+                iterable,
+                filter,
+                body,
+                ..
+            } => children.extend([&**iterable, &**filter, &**body]),
+            AstNode::ForWindowed {
+                iterable,
+                window_size,
+                body,
+                ..
+            } => children.extend([&**iterable, &**window_size, &**body]),
+            AstNode::BinarySearchLoop {
+                range,
+                body,
+                else_block,
+                ..
+            } => {
+                children.push(range);
+                children.push(body);
+                children.extend(else_block.as_deref());
+            }
+            AstNode::DpLoop {
+                dimensions,
+                init_value,
+                body,
+                ..
+            } => {
+                children.extend(dimensions);
+                children.push(init_value);
+                children.push(body);
+            }
+            AstNode::WhileChange { init, body, .. } => children.extend([&**init, &**body]),
+            AstNode::WhyExpression { expr }
+            | AstNode::ArenaAllocation { expr, .. }
+            | AstNode::AwaitExpression { expr } => children.push(expr),
+            AstNode::BlendExpression { branches } => {
+                children.extend(branches.iter().map(|(_, expr)| expr));
+            }
+            AstNode::EchoVariable { dependency, .. } => children.push(dependency),
+            AstNode::GuardExpression {
+                pre_conditions,
+                post_conditions,
+                body,
+            } => {
+                children.extend(pre_conditions);
+                children.extend(post_conditions);
+                children.push(body);
+            }
+            AstNode::LensView { target, transform } => children.extend([&**target, &**transform]),
+            AstNode::BorrowSplit {
+                target,
+                index,
+                body,
+                ..
+            } => children.extend([&**target, &**index, &**body]),
+            AstNode::ChannelSend { channel, value } => children.extend([&**channel, &**value]),
+            AstNode::ChannelReceive { channel } => children.push(channel),
+            AstNode::ScopeBlock { workers, body } => {
+                children.extend(workers.as_deref());
+                children.push(body);
+            }
+            AstNode::Assertion { condition, .. } => children.push(condition),
+            AstNode::TraitDeclaration { methods, .. }
+            | AstNode::ExtendType { methods, .. }
+            | AstNode::ContractDecl { methods, .. } => {
+                children.extend(methods);
+            }
+            AstNode::FuzzLoop {
+                range_opt,
+                condition,
+                body,
+                else_body,
+                ..
+            } => {
+                children.extend(range_opt.as_deref());
+                children.push(condition);
+                children.push(body);
+                children.extend(else_body.as_deref());
+            }
+            AstNode::WithinLoop {
+                duration_expr,
+                iterable,
+                body,
+                else_body,
+                ..
+            } => {
+                children.push(duration_expr);
+                children.extend(iterable.as_deref());
+                children.push(body);
+                children.extend(else_body.as_deref());
+            }
+            AstNode::RaceBlock { branches } => {
+                children.extend(branches.iter().map(|(_, expr)| expr));
+            }
+            AstNode::ComponentDecl {
+                fields, methods, ..
+            } => {
+                children.extend(
+                    fields
+                        .iter()
+                        .filter_map(|field| field.default_value.as_ref()),
+                );
+                children.extend(methods);
+            }
+            AstNode::EnvSchema { fields, .. } => {
+                children.extend(fields.iter().filter_map(|(_, _, default)| default.as_ref()));
+            }
+            AstNode::WindowLoop {
+                iterable,
+                size,
+                shrink_condition,
+                body,
+                ..
+            } => {
+                children.push(iterable);
+                children.extend(size.as_deref());
+                children.extend(shrink_condition.as_deref());
+                children.push(body);
+            }
+            AstNode::IntervalLiteral { start, end } => children.extend([&**start, &**end]),
+            AstNode::GroupBy { collection, key_fn } => children.extend([&**collection, &**key_fn]),
+            AstNode::Partition {
+                collection,
+                predicate,
+            } => children.extend([&**collection, &**predicate]),
+            AstNode::FloodLoop { start, body } => children.extend([&**start, &**body]),
+            AstNode::SolverBlock { options, body, .. } => {
+                children.extend(options.iter().map(|(_, expr)| expr));
+                children.push(body);
+            }
+            AstNode::DeferAttach { resource, cleanup } => {
+                children.extend([&**resource, &**cleanup])
+            }
+            AstNode::ListComprehension {
+                expr,
+                iterable,
+                condition,
+                ..
+            } => {
+                children.push(expr);
+                children.push(iterable);
+                children.extend(condition.as_deref());
+            }
+            AstNode::DictComprehension {
+                key_expr,
+                value_expr,
+                iterable,
+                condition,
+                ..
+            } => {
+                children.push(key_expr);
+                children.push(value_expr);
+                children.push(iterable);
+                children.extend(condition.as_deref());
+            }
+            AstNode::FlowBlock { bindings } => {
+                children.extend(bindings.iter().map(|(_, _, expr)| expr));
+            }
+            AstNode::ProbeDeclaration { hooks, .. } => {
+                children.extend(hooks.iter().map(|(_, expr)| expr));
+            }
+            AstNode::FuseHint { target } => children.push(target),
+            AstNode::FusePipeline { stages } => {
+                children.extend(stages);
+            }
+            AstNode::VeilGet { collection, key } => children.extend([&**collection, &**key]),
+            AstNode::VeilSet {
+                collection,
+                key,
+                value,
+            } => children.extend([&**collection, &**key, &**value]),
+            _ => {}
+        }
+        children
+    }
+}
+
+impl Pattern {
+    /// The literal and range-bound expressions in this pattern.
+    pub fn literals<'a>(&'a self, out: &mut Vec<&'a AstNode>) {
+        match self {
+            Pattern::Literal(value) => out.push(value),
+            Pattern::Range { start, end } => out.extend([start, end]),
+            Pattern::List(items) | Pattern::Tuple(items) => {
+                items.iter().for_each(|item| item.literals(out))
+            }
+            Pattern::Identifier(_) | Pattern::Wildcard => {}
+        }
+    }
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, current: 0 }
+        Self {
+            tokens,
+            current: 0,
+            file: None,
+            errors: None,
+            spans: true,
+        }
+    }
+
+    /// A parser that leaves every span unknown, so trees parsed from the same code laid out
+    /// differently compare equal (the formatter's round-trip check).
+    pub fn without_spans(tokens: Vec<Token>) -> Self {
+        Self {
+            spans: false,
+            ..Self::new(tokens)
+        }
     }
 
-    pub fn parse(&mut self) -> Result<AstNode, String> {
+    /// Record `file` in the spans of parsed nodes (for imported modules).
+    pub fn set_file(&mut self, file: &str) {
+        self.file = Some(Arc::from(file));
+    }
+
+    fn span_of(&self, token: &Token) -> Span {
+        if !self.spans {
+            return Span::default();
+        }
+        Span {
+            file: self.file.clone(),
+            line: token.line,
+            column: token.column,
+        }
+    }
+
+    /// Span of the token just consumed.
+    fn previous_span(&self) -> Span {
+        self.span_of(self.previous())
+    }
+
+    /// Span of the next token.
+    fn peek_span(&self) -> Span {
+        self.span_of(self.peek())
+    }
+
+    pub fn parse(&mut self) -> JResult<AstNode> {
+        let statements = self.parse_statements()?;
+        Ok(AstNode::Block(
+            statements.into_iter().map(|(statement, _)| statement).collect(),
+        ))
+    }
+
+    /// Parse the whole program into its top-level statements, each with the range of token
+    /// indices it was parsed from (not counting the newline or `;` after it).
+    pub fn parse_statements(&mut self) -> JResult<Vec<(AstNode, Range<usize>)>> {
         let mut statements = Vec::new();
 
         while !self.is_at_end() {
@@ -747,13 +1336,111 @@ impl Parser {
                 continue; // Skip newlines
             }
 
-            statements.push(self.statement()?);
+            let start = self.current;
+            match self.statement() {
+                Ok(statement) => {
+                    let mut end = self.current;
+                    while end > start
+                        && matches!(
+                            self.tokens[end - 1].token_type,
+                            TokenType::Newline | TokenType::Semicolon
+                        )
+                    {
+                        end -= 1;
+                    }
+                    statements.push((statement, start..end));
+                }
+                Err(error) => self.recover(error, start)?,
+            }
         }
 
-        Ok(AstNode::Block(statements))
+        Ok(statements)
+    }
+
+    /// Parse the whole program, skipping to the next statement after each syntax error so that
+    /// all of them are reported. The tree holds the statements that did parse.
+    pub fn parse_recovering(&mut self) -> (AstNode, Vec<JError>) {
+        self.errors = Some(Vec::new());
+        let ast = self.parse().unwrap_or(AstNode::Block(Vec::new()));
+        (ast, self.errors.take().unwrap_or_default())
+    }
+
+    /// Handle an error from the statement starting at token `start`: record it and skip to the
+    /// next statement when recovering, otherwise fail with it.
+    fn recover(&mut self, error: Box<JError>, start: usize) -> JResult<()> {
+        let error = self.locate(error);
+        let Some(errors) = self.errors.as_mut() else {
+            return Err(error);
+        };
+        errors.push(*error);
+        if self.current == start && !self.is_at_end() {
+            self.advance();
+        }
+        self.synchronize();
+        Ok(())
+    }
+
+    /// Skip to the end of the current line, or to the `}` closing the enclosing block, passing
+    /// over any brackets opened on the way.
+    fn synchronize(&mut self) {
+        let mut depth = 0usize;
+        while !self.is_at_end() {
+            match self.peek().token_type {
+                TokenType::LeftBrace | TokenType::LeftParen | TokenType::LeftBracket => depth += 1,
+                TokenType::RightBrace if depth == 0 => return,
+                TokenType::RightBrace | TokenType::RightParen | TokenType::RightBracket => {
+                    depth = depth.saturating_sub(1)
+                }
+                TokenType::Newline if depth == 0 => {
+                    self.advance();
+                    return;
+                }
+                _ => {}
+            }
+            self.advance();
+        }
     }
 
-    fn statement(&mut self) -> Result<AstNode, String> {
+    /// Point a syntax error at the token the parser stopped on, unless it already has a
+    /// location, and mark where that token ends.
+    fn locate(&self, error: Box<JError>) -> Box<JError> {
+        let mut error = match error.kind {
+            ErrorKind::UnexpectedCharacter
+            | ErrorKind::UnterminatedString
+            | ErrorKind::InvalidNumber
+            | ErrorKind::InvalidEscape
+            | ErrorKind::UnexpectedToken
+            | ErrorKind::ExpectedToken
+            | ErrorKind::InvalidSyntax
+            | ErrorKind::MissingOperand => error,
+            _ => Box::new(JError::syntax(ErrorKind::UnexpectedToken, error.message)),
+        };
+        let token = self.peek();
+        if error.line.is_none() {
+            error.line = Some(token.line);
+            error.column = Some(token.column);
+        }
+        let at = |t: &&Token| error.line == Some(t.line) && error.column == Some(t.column);
+        if let Some(token) = self.tokens[..=self.current.min(self.tokens.len() - 1)].iter().rev().find(at) {
+            error.end_line = Some(token.line);
+            error.end_column = Some(token.column + token.lexeme.chars().count().max(1));
+        }
+        if error.file.is_none() {
+            error.file = self.file.as_deref().map(str::to_string);
+        }
+        error
+    }
+
+    fn statement(&mut self) -> JResult<AstNode> {
+        let doc = self.peek().doc.clone();
+        let mut statement = crate::with_stack_headroom(|| self.statement_inner())?;
+        if let Some(doc) = doc {
+            statement.set_doc(doc);
+        }
+        Ok(statement)
+    }
+
+    fn statement_inner(&mut self) -> JResult<AstNode> {
         // Execute command: j; -> filename
         if self.match_token(&TokenType::Execute) {
             return self.execute_statement();
@@ -776,6 +1463,10 @@ impl Parser {
         if self.is_type_token() && self.check_ahead(&TokenType::Colon) {
             return self.var_declaration();
         }
+        // Immutable declaration: !type: name = value
+        if self.check(&TokenType::Exclamation) && self.check_ahead_n(2, &TokenType::Colon) {
+            return self.var_declaration();
+        }
 
         // Enum declaration: enum | name { variant = value, ... }
         if self.match_token(&TokenType::EnumKeyword) {
@@ -950,8 +1641,13 @@ impl Parser {
                     if matches!(
                         self.tokens[self.current].token_type,
                         TokenType::Identifier(_)
+                            | TokenType::MemoVar
+                            | TokenType::Inline
+                            | TokenType::Hot
+                            | TokenType::Cold
+                            | TokenType::Trace
                     ) {
-                        self.advance(); // consume decorator name
+                        self.advance(); // consume decorator name (same names as parse_decorator)
                     }
                     if self.current < self.tokens.len() && self.check(&TokenType::LeftParen) {
                         self.advance(); // consume (
@@ -1072,6 +1768,20 @@ impl Parser {
             return self.throw_statement();
         }
 
+        // Testing: test "name" { ... } and assert cond, "message"
+        if self.check(&TokenType::Test) && self.check_ahead(&TokenType::String(String::new())) {
+            self.advance(); // consume 'test'
+            return self.test_case();
+        }
+
+        if self.check(&TokenType::Property) && self.check_ahead(&TokenType::String(String::new())) {
+            self.advance(); // consume 'property'
+            return self.property_test();
+        }
+        if self.match_token(&TokenType::Assert) {
+            return self.assertion();
+        }
+
         // FOR LOOPS - Check this BEFORE expression statement
         if self.match_token(&TokenType::For)
             || (matches!(self.peek().token_type, TokenType::Identifier(_))
@@ -1091,11 +1801,11 @@ impl Parser {
         }
 
         if self.match_token(&TokenType::Break) {
-            return Ok(AstNode::Break);
+            return Ok(AstNode::Break(self.previous_span()));
         }
 
         if self.match_token(&TokenType::Continue) {
-            return Ok(AstNode::Continue);
+            return Ok(AstNode::Continue(self.previous_span()));
         }
 
         if self.match_token(&TokenType::Defer) {
@@ -1160,7 +1870,7 @@ impl Parser {
         self.expression_statement()
     }
 
-    fn type_conversion_statement(&mut self) -> Result<AstNode, String> {
+    fn type_conversion_statement(&mut self) -> JResult<AstNode> {
         // Parse "type * variable" format
         let target_type = match &self.advance().token_type {
             TokenType::Str => "str".to_string(),
@@ -1171,7 +1881,7 @@ impl Parser {
             TokenType::Vec => "vec".to_string(),
             TokenType::Mat => "mat".to_string(),
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected type name in type conversion".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected type name in type conversion").into()),
         };
 
         self.consume(
@@ -1181,20 +1891,20 @@ impl Parser {
 
         let name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected variable name after '*' in type conversion".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected variable name after '*' in type conversion").into()),
         };
 
         Ok(AstNode::TypeConversion { target_type, name })
     }
 
-    fn execute_statement(&mut self) -> Result<AstNode, String> {
+    fn execute_statement(&mut self) -> JResult<AstNode> {
         self.consume(&TokenType::Arrow, "Expected '->' after 'j;'")?;
 
         let token = self.advance().clone();
         let mut filename = match &token.token_type {
             TokenType::Identifier(name) => name.clone(),
             TokenType::String(filename) => filename.clone(),
-            _ => return Err("Expected filename after 'j; ->'".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected filename after 'j; ->'").into()),
         };
 
         // Handle path separators and extensions
@@ -1207,7 +1917,7 @@ impl Parser {
                         filename.push('/');
                         filename.push_str(name);
                     }
-                    _ => return Err("Expected identifier after '/' in path".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected identifier after '/' in path").into()),
                 }
             } else if self.match_token(&TokenType::Dot) {
                 // Handle file extension (.jdl)
@@ -1217,7 +1927,7 @@ impl Parser {
                         filename.push('.');
                         filename.push_str(ext);
                     }
-                    _ => return Err("Expected file extension after '.'".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected file extension after '.'").into()),
                 }
             } else {
                 break;
@@ -1227,7 +1937,7 @@ impl Parser {
         Ok(AstNode::ExecuteFile { filename })
     }
 
-    fn var_declaration(&mut self) -> Result<AstNode, String> {
+    fn var_declaration(&mut self) -> JResult<AstNode> {
         let mut immutable = false;
         let mut is_static = false;
         let mut type_modifier: Option<String> = None;
@@ -1289,11 +1999,12 @@ impl Parser {
             TokenType::Identifier(name) => name.clone(),
             TokenType::Enc => "enc".to_string(),
             TokenType::Secret => "secret".to_string(),
-            _ => return Err("Expected type name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected type name").into()),
         };
 
         self.consume(&TokenType::Colon, "Expected ':' after type")?;
 
+        let span = self.peek_span();
         let name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
             TokenType::Data => "data".to_string(), // allow 'data' as variable name
@@ -1312,10 +2023,11 @@ impl Parser {
             immutable,
             is_static,
             type_modifier,
+            span,
         })
     }
 
-    fn parse_decorator(&mut self) -> Result<Decorator, String> {
+    fn parse_decorator(&mut self) -> JResult<Decorator> {
         self.consume(&TokenType::At, "Expected '@' for decorator")?;
 
         let name = match &self.advance().token_type {
@@ -1324,7 +2036,8 @@ impl Parser {
             TokenType::Inline => "inline".to_string(),
             TokenType::Hot => "hot".to_string(),
             TokenType::Cold => "cold".to_string(),
-            _ => return Err("Expected decorator name after '@'".to_string()),
+            TokenType::Trace => "trace".to_string(),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected decorator name after '@'").into()),
         };
 
         let args = if self.check(&TokenType::LeftParen) {
@@ -1350,7 +2063,7 @@ impl Parser {
         Ok(Decorator { name, args })
     }
 
-    fn function_declaration(&mut self) -> Result<AstNode, String> {
+    fn function_declaration(&mut self) -> JResult<AstNode> {
         // Parse decorators (can be multiple, applied bottom-to-top)
         let mut decorators = Vec::new();
         while self.check(&TokenType::At) {
@@ -1376,7 +2089,7 @@ impl Parser {
         let name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
             TokenType::Test => "test".to_string(),
-            _ => return Err("Expected function name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected function name").into()),
         };
 
         self.consume(&TokenType::LeftParen, "Expected '(' after function name")?;
@@ -1398,14 +2111,14 @@ impl Parser {
                     TokenType::Counter => "counter".to_string(),
                     TokenType::CharType => "char".to_string(),
                     TokenType::Identifier(name) => name.clone(),
-                    _ => return Err("Expected parameter type".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected parameter type").into()),
                 };
 
                 self.consume(&TokenType::Pipe, "Expected '|' after parameter type")?;
 
                 let param_name = match &self.advance().token_type {
                     TokenType::Identifier(name) => name.clone(),
-                    _ => return Err("Expected parameter name".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected parameter name").into()),
                 };
                 params.push((param_type, param_name));
 
@@ -1430,12 +2143,14 @@ impl Parser {
             return_type,
             body: Box::new(body),
             decorators,
+            doc: None,
         })
     }
 
-    fn if_statement(&mut self) -> Result<AstNode, String> {
+    fn if_statement(&mut self) -> JResult<AstNode> {
         // Consume "if" when entering from "else if" (when called from else branch it is not yet consumed)
         self.match_token(&TokenType::If);
+        let span = self.previous_span();
         let condition = self.expression()?;
         while self.match_token(&TokenType::Newline) {}
         // Allow "if cond : expr" (colon then single expression) for one-liner style
@@ -1467,10 +2182,12 @@ impl Parser {
             condition: Box::new(condition),
             then_branch,
             else_branch,
+            span,
         })
     }
 
-    fn match_statement(&mut self) -> Result<AstNode, String> {
+    fn match_statement(&mut self) -> JResult<AstNode> {
+        let span = self.previous_span();
         let expr = self.expression()?;
 
         self.consume(&TokenType::LeftBrace, "Expected '{' after match expression")?;
@@ -1516,11 +2233,13 @@ impl Parser {
         Ok(AstNode::Match {
             expr: Box::new(expr),
             arms,
+            span,
         })
     }
 
-    fn cond_statement(&mut self) -> Result<AstNode, String> {
+    fn cond_statement(&mut self) -> JResult<AstNode> {
         // cond (value) { |> condition : result ... }
+        let span = self.previous_span();
         self.consume(&TokenType::LeftParen, "Expected '(' after 'cond'")?;
         let value = self.expression()?;
         self.consume(&TokenType::RightParen, "Expected ')' after cond value")?;
@@ -1573,16 +2292,17 @@ impl Parser {
         self.consume(&TokenType::RightBrace, "Expected '}' after cond branches")?;
 
         if branches.is_empty() {
-            return Err("cond must have at least one branch".to_string());
+            return Err(JError::syntax(ErrorKind::UnexpectedToken, "cond must have at least one branch").into());
         }
 
         Ok(AstNode::Cond {
             value: Box::new(value),
             branches,
+            span,
         })
     }
 
-    fn when_statement(&mut self) -> Result<AstNode, String> {
+    fn when_statement(&mut self) -> JResult<AstNode> {
         // when (value) { |> cond : body ... } — identical to cond, switch-style name
         self.consume(&TokenType::LeftParen, "Expected '(' after 'when'")?;
         let value = self.expression()?;
@@ -1616,7 +2336,7 @@ impl Parser {
         }
         self.consume(&TokenType::RightBrace, "Expected '}' after when branches")?;
         if branches.is_empty() {
-            return Err("when must have at least one branch".to_string());
+            return Err(JError::syntax(ErrorKind::UnexpectedToken, "when must have at least one branch").into());
         }
         Ok(AstNode::When {
             value: Box::new(value),
@@ -1624,7 +2344,7 @@ impl Parser {
         })
     }
 
-    fn unless_statement(&mut self) -> Result<AstNode, String> {
+    fn unless_statement(&mut self) -> JResult<AstNode> {
         // unless cond : body [else body]
         let condition = self.expression()?;
         while self.match_token(&TokenType::Newline) {}
@@ -1650,6 +2370,7 @@ impl Parser {
         // unless cond : then else other  =>  if !cond : then else other
         let not_cond = AstNode::Unary {
             operator: crate::parser::UnaryOp::Not,
+            span: condition.span().cloned().unwrap_or_default(),
             operand: Box::new(condition),
         };
         Ok(AstNode::Unless {
@@ -1659,7 +2380,7 @@ impl Parser {
         })
     }
 
-    fn either_statement(&mut self) -> Result<AstNode, String> {
+    fn either_statement(&mut self) -> JResult<AstNode> {
         // either expr { |> true : body1 |> false : body2 }
         let expr = self.expression()?;
         self.consume(&TokenType::LeftBrace, "Expected '{' after either expression")?;
@@ -1678,7 +2399,7 @@ impl Parser {
                 false
             };
             if !is_true && !is_false {
-                return Err("either branches must be |> true : body or |> false : body".to_string());
+                return Err(JError::syntax(ErrorKind::UnexpectedToken, "either branches must be |> true : body or |> false : body").into());
             }
             self.consume(&TokenType::Colon, "Expected ':' after true/false")?;
             let body = if self.check(&TokenType::LeftBrace) {
@@ -1688,20 +2409,20 @@ impl Parser {
             };
             if is_true {
                 if true_body.is_some() {
-                    return Err("either: duplicate 'true' branch".to_string());
+                    return Err(JError::syntax(ErrorKind::UnexpectedToken, "either: duplicate 'true' branch").into());
                 }
                 true_body = Some(body);
             } else {
                 if false_body.is_some() {
-                    return Err("either: duplicate 'false' branch".to_string());
+                    return Err(JError::syntax(ErrorKind::UnexpectedToken, "either: duplicate 'false' branch").into());
                 }
                 false_body = Some(body);
             }
             self.match_token(&TokenType::Newline);
         }
         self.consume(&TokenType::RightBrace, "Expected '}' after either branches")?;
-        let true_body = true_body.ok_or("either must have |> true : body".to_string())?;
-        let false_body = false_body.ok_or("either must have |> false : body".to_string())?;
+        let true_body = true_body.ok_or_else(|| JError::syntax(ErrorKind::UnexpectedToken, "either must have |> true : body"))?;
+        let false_body = false_body.ok_or_else(|| JError::syntax(ErrorKind::UnexpectedToken, "either must have |> false : body"))?;
         Ok(AstNode::Either {
             expr: Box::new(expr),
             true_body: Box::new(true_body),
@@ -1709,11 +2430,12 @@ impl Parser {
         })
     }
 
-    fn guard_return_statement(&mut self) -> Result<AstNode, String> {
+    fn guard_return_statement(&mut self) -> JResult<AstNode> {
         // guard condition : return value
         let condition = self.expression()?;
         self.consume(&TokenType::Colon, "Expected ':' after guard condition")?;
         let return_value = if self.match_token(&TokenType::Return) {
+            let span = self.previous_span();
             let val = if self.check(&TokenType::LeftBrace) || self.check(&TokenType::Newline)
                 || self.check(&TokenType::RightBrace)
             {
@@ -1721,7 +2443,7 @@ impl Parser {
             } else {
                 Some(self.expression()?)
             };
-            AstNode::Return(val.map(Box::new))
+            AstNode::Return(val.map(Box::new), span)
         } else {
             self.expression()?
         };
@@ -1731,7 +2453,7 @@ impl Parser {
         })
     }
 
-    fn switch_statement(&mut self) -> Result<AstNode, String> {
+    fn switch_statement(&mut self) -> JResult<AstNode> {
         // switch expr { start..end : body, literal : body, else : body }
         let expr = self.expression()?;
         self.consume(&TokenType::LeftBrace, "Expected '{' after switch expression")?;
@@ -1784,7 +2506,7 @@ impl Parser {
         })
     }
 
-    fn tight_statement(&mut self) -> Result<AstNode, String> {
+    fn tight_statement(&mut self) -> JResult<AstNode> {
         // tight { ... } — stack-only scope (interpreter may enforce no heap escape)
         let body = self.block()?;
         Ok(AstNode::TightBlock {
@@ -1792,7 +2514,7 @@ impl Parser {
         })
     }
 
-    fn borrow_split_statement(&mut self) -> Result<AstNode, String> {
+    fn borrow_split_statement(&mut self) -> JResult<AstNode> {
         // borrow_split(expr, index) | left_var, right_var { body }
         self.consume(&TokenType::LeftParen, "Expected '(' after 'borrow_split'")?;
         let target = self.expression()?;
@@ -1802,12 +2524,12 @@ impl Parser {
         self.consume(&TokenType::Pipeline, "Expected '|>' after borrow_split(...)")?;
         let left_var = match &self.advance().token_type {
             TokenType::Identifier(s) => s.clone(),
-            _ => return Err("Expected left variable name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected left variable name").into()),
         };
         self.consume(&TokenType::Comma, "Expected ',' between variable names")?;
         let right_var = match &self.advance().token_type {
             TokenType::Identifier(s) => s.clone(),
-            _ => return Err("Expected right variable name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected right variable name").into()),
         };
         let body = self.block()?;
         Ok(AstNode::BorrowSplit {
@@ -1819,7 +2541,8 @@ impl Parser {
         })
     }
 
-    fn while_statement(&mut self) -> Result<AstNode, String> {
+    fn while_statement(&mut self) -> JResult<AstNode> {
+        let span = self.previous_span();
         let condition = self.expression()?;
         let body = if self.check(&TokenType::LeftBrace) {
             self.block()?
@@ -1830,10 +2553,11 @@ impl Parser {
         Ok(AstNode::While {
             condition: Box::new(condition),
             body: Box::new(body),
+            span,
         })
     }
 
-    fn try_catch_statement(&mut self) -> Result<AstNode, String> {
+    fn try_catch_statement(&mut self) -> JResult<AstNode> {
         // Parse try block
         let try_block = self.block()?;
 
@@ -1868,13 +2592,14 @@ impl Parser {
         })
     }
 
-    fn throw_statement(&mut self) -> Result<AstNode, String> {
+    fn throw_statement(&mut self) -> JResult<AstNode> {
         // panic "error message" or panic(expr)
+        let span = self.previous_span();
         let error_expr = self.expression()?;
-        Ok(AstNode::Throw(Box::new(error_expr)))
+        Ok(AstNode::Throw(Box::new(error_expr), span))
     }
 
-    fn for_statement(&mut self) -> Result<AstNode, String> {
+    fn for_statement(&mut self) -> JResult<AstNode> {
         // Handle different for loop patterns:
         // 1. i in nums { ... }
         // 2. (i, v) in nums { ... }
@@ -1887,14 +2612,14 @@ impl Parser {
             // Indexed iteration: (i, v) in nums
             let index_var = match &self.advance().token_type {
                 TokenType::Identifier(name) => name.clone(),
-                _ => return Err("Expected index variable name".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected index variable name").into()),
             };
 
             self.consume(&TokenType::Comma, "Expected ',' after index variable")?;
 
             let value_var = match &self.advance().token_type {
                 TokenType::Identifier(name) => name.clone(),
-                _ => return Err("Expected value variable name".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected value variable name").into()),
             };
 
             self.consume(&TokenType::RightParen, "Expected ')' after variables")?;
@@ -1926,13 +2651,14 @@ impl Parser {
                 // Named: i in nums
                 let var_name = match &self.advance().token_type {
                     TokenType::Identifier(name) => name.clone(),
-                    _ => return Err("Expected variable name in for loop".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected variable name in for loop").into()),
                 };
 
                 self.consume(&TokenType::In, "Expected 'in' after for loop variable")?;
                 var_name
             };
 
+            let span = self.peek_span();
             let iterable = self.expression()?;
 
             // Check for enhanced for loop variants after iterable
@@ -1968,7 +2694,7 @@ impl Parser {
 
             // Check for enhanced for loop variants in function calls
             let enhanced_loop = match &iterable {
-                AstNode::FunctionCall { name, args } => {
+                AstNode::FunctionCall { name, args, .. } => {
                     match name.as_str() {
                         "reverse" => {
                             if args.len() == 1 {
@@ -2056,24 +2782,26 @@ impl Parser {
                     var,
                     iterable: Box::new(iterable),
                     body: Box::new(body),
+                    span,
                 })
             }
         }
     }
 
-    fn return_statement(&mut self) -> Result<AstNode, String> {
+    fn return_statement(&mut self) -> JResult<AstNode> {
+        let span = self.previous_span();
         let value = if self.check(&TokenType::Newline) || self.is_at_end() {
             None
         } else {
             Some(Box::new(self.expression()?))
         };
 
-        Ok(AstNode::Return(value))
+        Ok(AstNode::Return(value, span))
     }
 
-    fn yield_statement(&mut self) -> Result<AstNode, String> {
+    fn yield_statement(&mut self) -> JResult<AstNode> {
         let value = if self.check(&TokenType::Newline) || self.is_at_end() {
-            return Err("yield requires a value".to_string());
+            return Err(JError::syntax(ErrorKind::UnexpectedToken, "yield requires a value").into());
         } else {
             Box::new(self.expression()?)
         };
@@ -2081,10 +2809,11 @@ impl Parser {
         Ok(AstNode::Yield { value })
     }
 
-    fn assignment_statement(&mut self) -> Result<AstNode, String> {
+    fn assignment_statement(&mut self) -> JResult<AstNode> {
+        let span = self.peek_span();
         let name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected variable name in assignment".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected variable name in assignment").into()),
         };
 
         self.consume(&TokenType::Assign, "Expected '=' in assignment")?;
@@ -2094,10 +2823,11 @@ impl Parser {
         Ok(AstNode::Assignment {
             name,
             value: Box::new(value),
+            span,
         })
     }
 
-    fn destructuring_assignment(&mut self) -> Result<AstNode, String> {
+    fn destructuring_assignment(&mut self) -> JResult<AstNode> {
         self.consume(
             &TokenType::LeftParen,
             "Expected '(' in destructuring assignment",
@@ -2109,7 +2839,7 @@ impl Parser {
                 let target = match &self.advance().token_type {
                     TokenType::Identifier(name) => name.clone(),
                     _ => {
-                        return Err("Expected variable name in destructuring assignment".to_string())
+                        return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected variable name in destructuring assignment").into())
                     }
                 };
                 targets.push(target);
@@ -2136,16 +2866,17 @@ impl Parser {
         })
     }
 
-    fn expression_statement(&mut self) -> Result<AstNode, String> {
+    fn expression_statement(&mut self) -> JResult<AstNode> {
         let expr = self.expression()?;
         Ok(AstNode::Expression(Box::new(expr)))
     }
 
-    fn expression(&mut self) -> Result<AstNode, String> {
-        self.pipeline()
+    fn expression(&mut self) -> JResult<AstNode> {
+        // Deeply nested source must not overflow the native stack
+        crate::with_stack_headroom(|| self.pipeline())
     }
 
-    fn pipeline(&mut self) -> Result<AstNode, String> {
+    fn pipeline(&mut self) -> JResult<AstNode> {
         let mut expr = self.arrow_lambda()?;
 
         while self.match_token(&TokenType::Pipeline) {
@@ -2159,7 +2890,7 @@ impl Parser {
         Ok(expr)
     }
 
-    fn arrow_lambda(&mut self) -> Result<AstNode, String> {
+    fn arrow_lambda(&mut self) -> JResult<AstNode> {
         // Check for arrow lambda: x => expr or (x, y) => expr
         let start_pos = self.current;
 
@@ -2213,137 +2944,154 @@ impl Parser {
         self.or()
     }
 
-    fn or(&mut self) -> Result<AstNode, String> {
+    fn or(&mut self) -> JResult<AstNode> {
         let mut expr = self.and()?;
 
         while self.match_token(&TokenType::Or) {
+            let span = self.previous_span();
             let operator = BinaryOp::Or;
             let right = self.and()?;
             expr = AstNode::Binary {
                 left: Box::new(expr),
                 operator,
                 right: Box::new(right),
+                span,
             };
         }
 
         Ok(expr)
     }
 
-    fn and(&mut self) -> Result<AstNode, String> {
+    fn and(&mut self) -> JResult<AstNode> {
         let mut expr = self.bitwise_or()?;
 
         while self.match_token(&TokenType::And) {
+            let span = self.previous_span();
             let operator = BinaryOp::And;
             let right = self.bitwise_or()?;
             expr = AstNode::Binary {
                 left: Box::new(expr),
                 operator,
                 right: Box::new(right),
+                span,
             };
         }
 
         Ok(expr)
     }
 
-    fn bitwise_or(&mut self) -> Result<AstNode, String> {
+    fn bitwise_or(&mut self) -> JResult<AstNode> {
         let mut expr = self.bitwise_xor()?;
 
         while self.match_token(&TokenType::Pipe) {
+            let span = self.previous_span();
             let operator = BinaryOp::BitwiseOr;
             let right = self.bitwise_xor()?;
             expr = AstNode::Binary {
                 left: Box::new(expr),
                 operator,
                 right: Box::new(right),
+                span,
             };
         }
 
         Ok(expr)
     }
 
-    fn bitwise_xor(&mut self) -> Result<AstNode, String> {
+    fn bitwise_xor(&mut self) -> JResult<AstNode> {
         let mut expr = self.bitwise_and()?;
 
         while self.match_token(&TokenType::Caret) {
+            let span = self.previous_span();
             let operator = BinaryOp::BitwiseXor;
             let right = self.bitwise_and()?;
             expr = AstNode::Binary {
                 left: Box::new(expr),
                 operator,
                 right: Box::new(right),
+                span,
             };
         }
 
         Ok(expr)
     }
 
-    fn bitwise_and(&mut self) -> Result<AstNode, String> {
+    fn bitwise_and(&mut self) -> JResult<AstNode> {
         let mut expr = self.equality()?;
 
         while self.match_token(&TokenType::Ampersand) {
+            let span = self.previous_span();
             let operator = BinaryOp::BitwiseAnd;
             let right = self.equality()?;
             expr = AstNode::Binary {
                 left: Box::new(expr),
                 operator,
                 right: Box::new(right),
+                span,
             };
         }
 
         Ok(expr)
     }
 
-    fn equality(&mut self) -> Result<AstNode, String> {
+    fn equality(&mut self) -> JResult<AstNode> {
         let mut expr = self.comparison()?;
 
         while let Some(op) = self.match_equality_op() {
+            let span = self.previous_span();
             let right = self.comparison()?;
             expr = AstNode::Binary {
                 left: Box::new(expr),
                 operator: op,
                 right: Box::new(right),
+                span,
             };
         }
 
         Ok(expr)
     }
 
-    fn comparison(&mut self) -> Result<AstNode, String> {
+    fn comparison(&mut self) -> JResult<AstNode> {
         let mut expr = self.bitwise_shift()?;
 
         while let Some(op) = self.match_comparison_op() {
+            let span = self.previous_span();
             let right = self.bitwise_shift()?;
             expr = AstNode::Binary {
                 left: Box::new(expr),
                 operator: op,
                 right: Box::new(right),
+                span,
             };
         }
 
         Ok(expr)
     }
 
-    fn bitwise_shift(&mut self) -> Result<AstNode, String> {
+    fn bitwise_shift(&mut self) -> JResult<AstNode> {
         let mut expr = self.range_expr()?;
 
         while let Some(op) = self.match_shift_op() {
+            let span = self.previous_span();
             let right = self.range_expr()?;
             expr = AstNode::Binary {
                 left: Box::new(expr),
                 operator: op,
                 right: Box::new(right),
+                span,
             };
         }
 
         Ok(expr)
     }
 
-    fn range_expr(&mut self) -> Result<AstNode, String> {
+    fn range_expr(&mut self) -> JResult<AstNode> {
         let mut expr = self.term()?;
 
         // Check for range operators
         if self.match_token(&TokenType::Range) || self.match_token(&TokenType::RangeExclusive) {
             let inclusive = self.previous().token_type == TokenType::Range;
+            let span = self.previous_span();
 
             let end = if self.is_at_end()
                 || self.check(&TokenType::By)
@@ -2370,55 +3118,62 @@ impl Parser {
                 end: Box::new(end),
                 inclusive,
                 step,
+                span,
             };
         }
 
         Ok(expr)
     }
 
-    fn term(&mut self) -> Result<AstNode, String> {
+    fn term(&mut self) -> JResult<AstNode> {
         let mut expr = self.factor()?;
 
         while let Some(op) = self.match_term_op() {
+            let span = self.previous_span();
             let right = self.factor()?;
             expr = AstNode::Binary {
                 left: Box::new(expr),
                 operator: op,
                 right: Box::new(right),
+                span,
             };
         }
 
         Ok(expr)
     }
 
-    fn factor(&mut self) -> Result<AstNode, String> {
+    fn factor(&mut self) -> JResult<AstNode> {
         let mut expr = self.unary()?;
 
         while let Some(op) = self.match_factor_op() {
+            let span = self.previous_span();
             let right = self.unary()?;
             expr = AstNode::Binary {
                 left: Box::new(expr),
                 operator: op,
                 right: Box::new(right),
+                span,
             };
         }
 
         Ok(expr)
     }
 
-    fn unary(&mut self) -> Result<AstNode, String> {
+    fn unary(&mut self) -> JResult<AstNode> {
         if let Some(op) = self.match_unary_op() {
+            let span = self.previous_span();
             let expr = self.unary()?;
             return Ok(AstNode::Unary {
                 operator: op,
                 operand: Box::new(expr),
+                span,
             });
         }
 
         self.postfix()
     }
 
-    fn postfix(&mut self) -> Result<AstNode, String> {
+    fn postfix(&mut self) -> JResult<AstNode> {
         let mut expr = self.primary()?;
 
         loop {
@@ -2427,6 +3182,7 @@ impl Parser {
                 expr = AstNode::TryExpression(Box::new(expr));
             } else if self.match_token(&TokenType::LeftParen) {
                 // Function call
+                let paren = self.previous_span();
                 let mut args = Vec::new();
                 if !self.check(&TokenType::RightParen) {
                     loop {
@@ -2438,12 +3194,14 @@ impl Parser {
                 }
                 self.consume(&TokenType::RightParen, "Expected ')' after arguments")?;
 
-                if let AstNode::Identifier(name) = expr {
-                    expr = AstNode::FunctionCall { name, args };
+                if let AstNode::Identifier(name, span) = expr {
+                    expr = AstNode::FunctionCall { name, args, span };
                 } else {
+                    let span = expr.span().cloned().unwrap_or(paren);
                     expr = AstNode::Call {
                         callee: Box::new(expr),
                         args,
+                        span,
                     };
                 }
             } else if self.match_token(&TokenType::Dot) {
@@ -2459,13 +3217,18 @@ impl Parser {
                         resource: Box::new(expr),
                         cleanup: Box::new(cleanup),
                     };
-                } else if let TokenType::Identifier(name) = &self.peek().token_type {
+                } else if let Some(field) = match &self.peek().token_type {
+                    TokenType::Identifier(name) => Some(name.clone()),
+                    TokenType::Trace => Some("trace".to_string()), // e.trace on caught errors
+                    _ => None,
+                } {
                     // Dot access: expr.field
-                    let field = name.clone();
+                    let span = self.peek_span();
                     self.advance();
                     expr = AstNode::DotAccess {
                         object: Box::new(expr),
                         field,
+                        span,
                     };
                 } else if self.check(&TokenType::LeftParen) {
                     // Broadcast call: expr.(args)
@@ -2488,11 +3251,11 @@ impl Parser {
                         args,
                     };
                 } else {
-                    return Err("Expected field name or '(' after '.'".to_string());
+                    return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected field name or '(' after '.'").into());
                 }
             } else if self.match_token(&TokenType::LeftBracket) {
                 // Check if this is slicing (contains ..) or indexing
-                let _start_pos = self.current;
+                let span = self.previous_span();
 
                 // Look ahead to see if we have a slice pattern
                 let mut has_slice = false;
@@ -2555,6 +3318,7 @@ impl Parser {
                     expr = AstNode::Index {
                         object: Box::new(expr),
                         index: Box::new(index),
+                        span,
                     };
                 }
             } else {
@@ -2565,7 +3329,8 @@ impl Parser {
         Ok(expr)
     }
 
-    fn primary(&mut self) -> Result<AstNode, String> {
+    fn primary(&mut self) -> JResult<AstNode> {
+        let span = self.peek_span();
         match &self.advance().token_type {
             TokenType::Boolean(b) => Ok(AstNode::Boolean(*b)),
             TokenType::Integer(i) => Ok(AstNode::Integer(*i)),
@@ -2587,14 +3352,14 @@ impl Parser {
             TokenType::Time(time) => Ok(AstNode::Time(time.clone())),
             TokenType::DateTime(datetime) => Ok(AstNode::DateTime(datetime.clone())),
             TokenType::Infinity(positive) => Ok(AstNode::Infinity(*positive)),
-            TokenType::Identifier(name) => Ok(AstNode::Identifier(name.clone())),
-            TokenType::DateType => Ok(AstNode::Identifier("date".to_string())),
-            TokenType::TimeType => Ok(AstNode::Identifier("time".to_string())),
-            TokenType::DateTimeType => Ok(AstNode::Identifier("datetime".to_string())),
-            TokenType::IntervalType => Ok(AstNode::Identifier("interval".to_string())),
-            TokenType::Data => Ok(AstNode::Identifier("data".to_string())), // contextual keyword as identifier
-            TokenType::This => Ok(AstNode::Identifier("this".to_string())),
-            TokenType::Self_ => Ok(AstNode::Identifier("this".to_string())),
+            TokenType::Identifier(name) => Ok(AstNode::Identifier(name.clone(), span)),
+            TokenType::DateType => Ok(AstNode::Identifier("date".to_string(), span)),
+            TokenType::TimeType => Ok(AstNode::Identifier("time".to_string(), span)),
+            TokenType::DateTimeType => Ok(AstNode::Identifier("datetime".to_string(), span)),
+            TokenType::IntervalType => Ok(AstNode::Identifier("interval".to_string(), span)),
+            TokenType::Data => Ok(AstNode::Identifier("data".to_string(), span)), // contextual keyword as identifier
+            TokenType::This => Ok(AstNode::Identifier("this".to_string(), span)),
+            TokenType::Self_ => Ok(AstNode::Identifier("this".to_string(), span)),
             TokenType::Underscore => Ok(AstNode::Underscore),
 
             TokenType::Pipe => {
@@ -2604,7 +3369,7 @@ impl Parser {
                     loop {
                         let param = match &self.advance().token_type {
                             TokenType::Identifier(name) => name.clone(),
-                            _ => return Err("Expected parameter name in lambda".to_string()),
+                            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected parameter name in lambda").into()),
                         };
                         params.push(param);
                         if !self.match_token(&TokenType::Comma) {
@@ -2631,7 +3396,7 @@ impl Parser {
                     loop {
                         let param = match &self.advance().token_type {
                             TokenType::Identifier(name) => name.clone(),
-                            _ => return Err("Expected parameter name in lambda".to_string()),
+                            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected parameter name in lambda").into()),
                         };
                         params.push(param);
                         if !self.match_token(&TokenType::Comma) {
@@ -2696,7 +3461,7 @@ impl Parser {
                             TokenType::Identifier(name) => name.clone(),
                             _ => {
                                 return Err(
-                                    "Expected variable name in list comprehension".to_string()
+                                    JError::syntax(ErrorKind::ExpectedToken, "Expected variable name in list comprehension").into()
                                 )
                             }
                         };
@@ -2775,7 +3540,7 @@ impl Parser {
                             TokenType::Identifier(name) => name.clone(),
                             _ => {
                                 return Err(
-                                    "Expected variable name in dict comprehension".to_string()
+                                    JError::syntax(ErrorKind::ExpectedToken, "Expected variable name in dict comprehension").into()
                                 )
                             }
                         };
@@ -2836,25 +3601,44 @@ impl Parser {
             _ => {
                 let idx = self.current.wrapping_sub(1);
                 let token = self.tokens.get(idx);
-                let msg = token
-                    .map(|t| {
+                let error = match token {
+                    Some(t) => JError::new(
+                        ErrorKind::UnexpectedToken,
                         format!(
                             "Unexpected token '{:?}' at line {}, column {}",
                             t.token_type, t.line, t.column
-                        )
-                    })
-                    .unwrap_or_else(|| "Unexpected token".to_string());
-                Err(msg)
+                        ),
+                    )
+                    .with_location(t.line, t.column),
+                    None => JError::new(ErrorKind::UnexpectedToken, "Unexpected token".to_string()),
+                }
+                .with_tip("Check brackets, parentheses, and statement syntax.".to_string())
+                .with_solution("See the Jade syntax guide for the correct form.".to_string());
+                // Leave a line end or closing bracket for the enclosing construct, so that
+                // error recovery resumes at the right place.
+                if token.is_some_and(|t| {
+                    matches!(
+                        t.token_type,
+                        TokenType::Newline
+                            | TokenType::Eof
+                            | TokenType::RightBrace
+                            | TokenType::RightParen
+                            | TokenType::RightBracket
+                    )
+                }) {
+                    self.current = idx;
+                }
+                Err(Box::new(error))
             }
         }
     }
 
-    fn enum_declaration(&mut self) -> Result<AstNode, String> {
+    fn enum_declaration(&mut self) -> JResult<AstNode> {
         self.consume(&TokenType::Pipe, "Expected '|' after 'enum'")?;
 
         let name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected enum name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected enum name").into()),
         };
 
         let backing_type = if self.match_token(&TokenType::Colon) {
@@ -2864,7 +3648,7 @@ impl Parser {
                 TokenType::Int => "int".to_string(),
                 TokenType::FloatType => "float".to_string(),
                 TokenType::Bool => "bool".to_string(),
-                _ => return Err("Expected type name after ':' for enum backing type".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected type name after ':' for enum backing type").into()),
             };
             Some(type_name)
         } else {
@@ -2884,7 +3668,7 @@ impl Parser {
 
             let variant_name = match &self.advance().token_type {
                 TokenType::Identifier(name) => name.clone(),
-                _ => return Err("Expected variant name".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected variant name").into()),
             };
 
             let value = if self.match_token(&TokenType::Assign) {
@@ -2907,22 +3691,23 @@ impl Parser {
             name,
             backing_type,
             variants,
+            doc: None,
         })
     }
 
-    fn class_declaration(&mut self, class_type: Option<String>) -> Result<AstNode, String> {
+    fn class_declaration(&mut self, class_type: Option<String>) -> JResult<AstNode> {
         self.consume(&TokenType::Pipe, "Expected '|' after 'class'")?;
 
         let name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected class name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected class name").into()),
         };
 
         // Check for parent class: class | Child : Parent
         let parent = if self.match_token(&TokenType::Colon) {
             match &self.advance().token_type {
                 TokenType::Identifier(parent_name) => Some(parent_name.clone()),
-                _ => return Err("Expected parent class name after ':'".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected parent class name after ':'").into()),
             }
         } else {
             None
@@ -2933,7 +3718,7 @@ impl Parser {
         while self.match_token(&TokenType::Plus) {
             match &self.advance().token_type {
                 TokenType::Identifier(trait_name) => traits.push(trait_name.clone()),
-                _ => return Err("Expected trait name after '+'".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected trait name after '+'").into()),
             }
         }
 
@@ -2951,6 +3736,7 @@ impl Parser {
             if self.match_token(&TokenType::Newline) {
                 continue;
             }
+            let doc = self.peek().doc.clone();
 
             // Check for static keyword
             let is_static = self.match_token(&TokenType::Static);
@@ -2968,7 +3754,10 @@ impl Parser {
             // Check if it's a method (fn) or field (type)
             if self.check(&TokenType::Fn) {
                 self.advance(); // consume fn
-                let method = self.function_declaration()?;
+                let mut method = self.function_declaration()?;
+                if let Some(doc) = doc {
+                    method.set_doc(doc);
+                }
                 if is_static {
                     static_methods.push(method);
                 } else {
@@ -2985,14 +3774,14 @@ impl Parser {
                     TokenType::List => "list".to_string(),
                     TokenType::Dict => "dict".to_string(),
                     TokenType::Identifier(name) => name,
-                    _ => return Err("Expected type for field".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected type for field").into()),
                 };
 
                 self.consume(&TokenType::Pipe, "Expected '|' after field type")?;
 
                 let field_name = match &self.advance().token_type {
                     TokenType::Identifier(name) => name.clone(),
-                    _ => return Err("Expected field name".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected field name").into()),
                 };
 
                 let default_value = if self.match_token(&TokenType::Arrow) {
@@ -3008,6 +3797,7 @@ impl Parser {
                     is_public,
                     is_readonly: false,
                     is_static,
+                    doc,
                 };
 
                 if is_static {
@@ -3016,7 +3806,7 @@ impl Parser {
                     fields.push(field);
                 }
             } else {
-                return Err("Expected field or method declaration in class".to_string());
+                return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected field or method declaration in class").into());
             }
 
             self.match_token(&TokenType::Newline);
@@ -3033,14 +3823,15 @@ impl Parser {
             methods,
             static_fields,
             static_methods,
+            doc: None,
         })
     }
 
-    fn extend_type_declaration(&mut self) -> Result<AstNode, String> {
+    fn extend_type_declaration(&mut self) -> JResult<AstNode> {
         self.consume(&TokenType::Pipe, "Expected '|' after 'extend'")?;
         match &self.advance().token_type {
             TokenType::Identifier(t) if t == "type" => {}
-            _ => return Err("Expected 'type'".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected 'type'").into()),
         }
         self.consume(&TokenType::Pipe, "Expected '|'")?;
         let target_type = match &self.advance().token_type {
@@ -3048,7 +3839,7 @@ impl Parser {
             TokenType::Int => "int".to_string(),
             TokenType::Str => "str".to_string(),
             TokenType::List => "list".to_string(),
-            _ => return Err("Expected type name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected type name").into()),
         };
         self.consume(&TokenType::LeftBrace, "Expected '{'")?;
         let mut methods = Vec::new();
@@ -3069,16 +3860,16 @@ impl Parser {
         })
     }
 
-    fn phantom_declaration(&mut self) -> Result<AstNode, String> {
+    fn phantom_declaration(&mut self) -> JResult<AstNode> {
         self.consume(&TokenType::Pipe, "Expected '|' after 'phantom'")?;
         let name = match &self.advance().token_type {
             TokenType::Identifier(n) => n.clone(),
-            _ => return Err("Expected phantom tag name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected phantom tag name").into()),
         };
         Ok(AstNode::PhantomDecl { name })
     }
 
-    fn secure_block(&mut self) -> Result<AstNode, String> {
+    fn secure_block(&mut self) -> JResult<AstNode> {
         while self.match_token(&TokenType::Newline) {}
         let body = self.block()?;
         Ok(AstNode::SecureBlock {
@@ -3086,7 +3877,109 @@ impl Parser {
         })
     }
 
-    fn rollback_block(&mut self) -> Result<AstNode, String> {
+    fn test_case(&mut self) -> JResult<AstNode> {
+        // test "name" { body }
+        let name = match self.peek().token_type.clone() {
+            TokenType::String(s) => {
+                self.advance();
+                s
+            }
+            _ => return Err(self.error_expected("test name string")),
+        };
+        while self.match_token(&TokenType::Newline) {}
+        let body = self.block()?;
+        Ok(AstNode::TestCase {
+            name,
+            body: Box::new(body),
+        })
+    }
+
+    fn property_test(&mut self) -> JResult<AstNode> {
+        // property "name" (int | x) { body }
+        let name = match self.peek().token_type.clone() {
+            TokenType::String(s) => {
+                self.advance();
+                s
+            }
+            _ => return Err(self.error_expected("property name string")),
+        };
+        self.consume(&TokenType::LeftParen, "Expected '(' after property name")?;
+        let var_type = match &self.advance().token_type {
+            TokenType::Int => "int".to_string(),
+            TokenType::FloatType => "float".to_string(),
+            TokenType::Str => "str".to_string(),
+            TokenType::Bool => "bool".to_string(),
+            TokenType::List => "list".to_string(),
+            TokenType::Dict => "dict".to_string(),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected property input type (int, float, str, bool, list, dict)").into()),
+        };
+        self.consume(&TokenType::Pipe, "Expected '|' after property input type")?;
+        let var_name = match self.peek().token_type.clone() {
+            TokenType::Identifier(name) => {
+                self.advance();
+                name
+            }
+            _ => return Err(self.error_expected("property input name")),
+        };
+        self.consume(&TokenType::RightParen, "Expected ')' after property input")?;
+        while self.match_token(&TokenType::Newline) {}
+        let body = self.block()?;
+        Ok(AstNode::PropertyTest {
+            name,
+            var_type,
+            var_name,
+            body: Box::new(body),
+        })
+    }
+
+    fn assertion(&mut self) -> JResult<AstNode> {
+        // assert cond  |  assert cond, "message"  |  assert(cond, "message")
+        let span = self.previous_span();
+        if self.match_token(&TokenType::LeftParen) {
+            let mut args = Vec::new();
+            if !self.check(&TokenType::RightParen) {
+                loop {
+                    args.push(self.expression()?);
+                    if !self.match_token(&TokenType::Comma) {
+                        break;
+                    }
+                }
+            }
+            self.consume(&TokenType::RightParen, "Expected ')' after assert arguments")?;
+            return Ok(match args.as_slice() {
+                [condition] => AstNode::Assertion {
+                    condition: Box::new(condition.clone()),
+                    message: None,
+                },
+                [condition, AstNode::String(message)] => AstNode::Assertion {
+                    condition: Box::new(condition.clone()),
+                    message: Some(message.clone()),
+                },
+                // Computed messages (or bad arity) go through the assert() builtin
+                _ => AstNode::FunctionCall {
+                    name: "assert".to_string(),
+                    args,
+                    span,
+                },
+            });
+        }
+
+        let condition = self.expression()?;
+        let message = if self.match_token(&TokenType::Comma) {
+            match &self.advance().token_type {
+                TokenType::String(s) => Some(s.clone()),
+                _ => return Err(self.error_expected("assertion message string")),
+            }
+        } else {
+            None
+        };
+        Ok(AstNode::Assertion {
+            condition: Box::new(condition),
+            message,
+        })
+    }
+
+    fn rollback_block(&mut self) -> JResult<AstNode> {
         while self.match_token(&TokenType::Newline) {}
         let retries = if self.check(&TokenType::LeftParen) {
             self.advance();
@@ -3119,7 +4012,7 @@ impl Parser {
         })
     }
 
-    fn race_block(&mut self) -> Result<AstNode, String> {
+    fn race_block(&mut self) -> JResult<AstNode> {
         while self.match_token(&TokenType::Newline) {}
         self.consume(&TokenType::LeftBrace, "Expected '{' after 'race'")?;
         let mut branches = Vec::new();
@@ -3130,7 +4023,7 @@ impl Parser {
             let label = match &self.advance().token_type {
                 TokenType::String(s) => s.clone(),
                 TokenType::Identifier(s) => s.clone(),
-                _ => return Err("Expected branch label".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected branch label").into()),
             };
             self.consume(&TokenType::Colon, "Expected ':'")?;
             let expr = self.expression()?;
@@ -3140,7 +4033,7 @@ impl Parser {
         Ok(AstNode::RaceBlock { branches })
     }
 
-    fn retry_block(&mut self) -> Result<AstNode, String> {
+    fn retry_block(&mut self) -> JResult<AstNode> {
         let (attempts, backoff, jitter) = if self.check(&TokenType::LeftParen) {
             self.advance();
             let mut a = 4u32;
@@ -3189,17 +4082,17 @@ impl Parser {
         })
     }
 
-    fn fuzz_loop(&mut self) -> Result<AstNode, String> {
+    fn fuzz_loop(&mut self) -> JResult<AstNode> {
         let var_type = match &self.advance().token_type {
             TokenType::Int => "int".to_string(),
             TokenType::Str => "str".to_string(),
             TokenType::Identifier(t) => t.clone(),
-            _ => return Err("Expected type in fuzz".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected type in fuzz").into()),
         };
         self.consume(&TokenType::Pipe, "Expected '|'")?;
         let var_name = match &self.advance().token_type {
             TokenType::Identifier(n) => n.clone(),
-            _ => return Err("Expected variable name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected variable name").into()),
         };
         let range_opt = if self.match_token(&TokenType::In) {
             Some(Box::new(self.expression()?))
@@ -3238,12 +4131,12 @@ impl Parser {
         })
     }
 
-    fn within_loop(&mut self) -> Result<AstNode, String> {
+    fn within_loop(&mut self) -> JResult<AstNode> {
         let duration_expr = Box::new(self.expression()?);
         let (loop_var, iterable) = if self.match_token(&TokenType::Pipe) {
             let var = match &self.advance().token_type {
                 TokenType::Identifier(n) => n.clone(),
-                _ => return Err("Expected loop variable".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected loop variable").into()),
             };
             self.consume(&TokenType::In, "Expected 'in'")?;
             let iter = self.expression()?;
@@ -3266,11 +4159,11 @@ impl Parser {
         })
     }
 
-    fn component_declaration(&mut self) -> Result<AstNode, String> {
+    fn component_declaration(&mut self) -> JResult<AstNode> {
         self.consume(&TokenType::Pipe, "Expected '|'")?;
         let name = match &self.advance().token_type {
             TokenType::Identifier(n) => n.clone(),
-            _ => return Err("Expected component name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected component name").into()),
         };
         self.consume(&TokenType::LeftBrace, "Expected '{'")?;
         let mut deps = Vec::new();
@@ -3288,7 +4181,7 @@ impl Parser {
                 self.consume(&TokenType::Pipe, "Expected '|'")?;
                 let fn_name = match &self.advance().token_type {
                     TokenType::Identifier(n) => n.clone(),
-                    _ => return Err("Expected field name".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected field name").into()),
                 };
                 deps.push((ft, fn_name));
             } else {
@@ -3304,11 +4197,11 @@ impl Parser {
         })
     }
 
-    fn contract_declaration(&mut self) -> Result<AstNode, String> {
+    fn contract_declaration(&mut self) -> JResult<AstNode> {
         self.consume(&TokenType::Pipe, "Expected '|'")?;
         let name = match &self.advance().token_type {
             TokenType::Identifier(n) => n.clone(),
-            _ => return Err("Expected contract name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected contract name").into()),
         };
         self.consume(&TokenType::LeftBrace, "Expected '{'")?;
         let mut methods = Vec::new();
@@ -3327,7 +4220,7 @@ impl Parser {
         Ok(AstNode::ContractDecl { name, methods })
     }
 
-    fn workspace_block(&mut self) -> Result<AstNode, String> {
+    fn workspace_block(&mut self) -> JResult<AstNode> {
         self.consume(&TokenType::LeftBrace, "Expected '{' after 'workspace'")?;
         let mut members = Vec::new();
         let mut rules = Vec::new();
@@ -3367,20 +4260,20 @@ impl Parser {
         Ok(AstNode::WorkspaceBlock { members, rules })
     }
 
-    fn task_declaration(&mut self) -> Result<AstNode, String> {
+    fn task_declaration(&mut self) -> JResult<AstNode> {
         self.consume(&TokenType::Pipe, "Expected '|'")?;
         let name = match &self.advance().token_type {
             TokenType::Identifier(n) => n.clone(),
-            _ => return Err("Expected task name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected task name").into()),
         };
         let needs: Vec<String> = if self.check(&TokenType::LeftParen) {
             self.advance();
             if let TokenType::Identifier(n) = &self.advance().token_type {
                 if n != "needs" {
-                    return Err("Expected 'needs'".to_string());
+                    return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected 'needs'").into());
                 }
             } else {
-                return Err("Expected 'needs'".to_string());
+                return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected 'needs'").into());
             }
             self.consume(&TokenType::Colon, "Expected ':'")?;
             let mut n = Vec::new();
@@ -3406,11 +4299,11 @@ impl Parser {
         })
     }
 
-    fn env_schema(&mut self) -> Result<AstNode, String> {
+    fn env_schema(&mut self) -> JResult<AstNode> {
         self.consume(&TokenType::Pipe, "Expected '|'")?;
         let name = match &self.advance().token_type {
             TokenType::Identifier(n) => n.clone(),
-            _ => return Err("Expected env schema name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected env schema name").into()),
         };
         self.consume(&TokenType::LeftBrace, "Expected '{'")?;
         let mut fields = Vec::new();
@@ -3423,12 +4316,12 @@ impl Parser {
                 TokenType::Int => "int".to_string(),
                 TokenType::Bool => "bool".to_string(),
                 TokenType::Identifier(x) => x.clone(),
-                _ => return Err("Expected type".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected type").into()),
             };
             self.consume(&TokenType::Pipe, "Expected '|'")?;
             let n = match &self.advance().token_type {
                 TokenType::Identifier(x) => x.clone(),
-                _ => return Err("Expected field name".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected field name").into()),
             };
             let default = if self.match_token(&TokenType::Arrow) {
                 Some(self.expression()?)
@@ -3441,7 +4334,7 @@ impl Parser {
         Ok(AstNode::EnvSchema { name, fields })
     }
 
-    fn flood_loop(&mut self) -> Result<AstNode, String> {
+    fn flood_loop(&mut self) -> JResult<AstNode> {
         let start = Box::new(self.expression()?);
         let body = self.block()?;
         Ok(AstNode::FloodLoop {
@@ -3450,10 +4343,10 @@ impl Parser {
         })
     }
 
-    fn window_loop(&mut self) -> Result<AstNode, String> {
+    fn window_loop(&mut self) -> JResult<AstNode> {
         let var = match &self.peek().token_type {
             TokenType::Identifier(n) => n.clone(),
-            _ => return Err("Expected variable name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected variable name").into()),
         };
         self.advance();
         self.consume(&TokenType::In, "Expected 'in'")?;
@@ -3478,7 +4371,7 @@ impl Parser {
                         self.consume(&TokenType::Colon, "Expected ':' after 'shrink_if'")?;
                         shrink_condition = Some(Box::new(self.expression()?));
                     }
-                    _ => return Err(format!("Unknown window option: {}", keyword)),
+                    _ => return Err(JError::syntax(ErrorKind::UnexpectedToken, format!("Unknown window option: {}", keyword)).into()),
                 }
             }
             self.consume(&TokenType::RightParen, "Expected ')'")?;
@@ -3494,17 +4387,17 @@ impl Parser {
         })
     }
 
-    fn solver_block(&mut self) -> Result<AstNode, String> {
+    fn solver_block(&mut self) -> JResult<AstNode> {
         let name = match &self.advance().token_type {
             TokenType::Identifier(n) => n.clone(),
-            _ => return Err("Expected solver name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected solver name").into()),
         };
         self.consume(&TokenType::LeftParen, "Expected '('")?;
         let mut options = Vec::new();
         while !self.check(&TokenType::RightParen) {
             let k = match &self.advance().token_type {
                 TokenType::Identifier(x) => x.clone(),
-                _ => return Err("Expected option name".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected option name").into()),
             };
             self.consume(&TokenType::Colon, "Expected ':'")?;
             options.push((k, self.expression()?));
@@ -3519,18 +4412,18 @@ impl Parser {
         })
     }
 
-    fn memo_var_declaration(&mut self) -> Result<AstNode, String> {
+    fn memo_var_declaration(&mut self) -> JResult<AstNode> {
         self.advance(); // consume memo
         let var_type = match &self.advance().token_type {
             TokenType::Int => "int".to_string(),
             TokenType::Str => "str".to_string(),
             TokenType::Identifier(t) => t.clone(),
-            _ => return Err("Expected type".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected type").into()),
         };
         self.consume(&TokenType::Pipe, "Expected '|'")?;
         let name = match &self.advance().token_type {
             TokenType::Identifier(n) => n.clone(),
-            _ => return Err("Expected variable name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected variable name").into()),
         };
         self.consume(&TokenType::LeftParen, "Expected '('")?;
         let mut params = Vec::new();
@@ -3539,12 +4432,12 @@ impl Parser {
                 TokenType::Int => "int".to_string(),
                 TokenType::Str => "str".to_string(),
                 TokenType::Identifier(x) => x.clone(),
-                _ => return Err("Expected param type".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected param type").into()),
             };
             self.consume(&TokenType::Pipe, "Expected '|'")?;
             let p = match &self.advance().token_type {
                 TokenType::Identifier(x) => x.clone(),
-                _ => return Err("Expected param name".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected param name").into()),
             };
             params.push((t, p));
             self.match_token(&TokenType::Comma);
@@ -3560,13 +4453,14 @@ impl Parser {
         })
     }
 
-    fn lambda_statement(&mut self) -> Result<AstNode, String> {
+    fn lambda_statement(&mut self) -> JResult<AstNode> {
         // Lambda function: fn | name > params { body }
         self.consume(&TokenType::Pipe, "Expected '|' after 'fn'")?;
 
+        let span = self.peek_span();
         let name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected function name in lambda".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected function name in lambda").into()),
         };
 
         self.consume(&TokenType::Greater, "Expected '>' in lambda")?;
@@ -3576,7 +4470,7 @@ impl Parser {
             loop {
                 let param = match &self.advance().token_type {
                     TokenType::Identifier(name) => name.clone(),
-                    _ => return Err("Expected parameter name in lambda".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected parameter name in lambda").into()),
                 };
                 params.push(param);
                 if !self.match_token(&TokenType::Comma) {
@@ -3603,10 +4497,11 @@ impl Parser {
         Ok(AstNode::Assignment {
             name,
             value: Box::new(lambda),
+            span,
         })
     }
 
-    fn pattern(&mut self) -> Result<Pattern, String> {
+    fn pattern(&mut self) -> JResult<Pattern> {
         let token = self.advance();
         match &token.token_type {
             TokenType::Integer(i) => Ok(Pattern::Literal(AstNode::Integer(*i))),
@@ -3616,14 +4511,14 @@ impl Parser {
             TokenType::Underscore => Ok(Pattern::Wildcard),
             TokenType::Identifier(name) if name == "_" => Ok(Pattern::Wildcard),
             TokenType::Identifier(name) => Ok(Pattern::Identifier(name.clone())),
-            _ => Err(format!(
+            _ => Err(JError::syntax(ErrorKind::UnexpectedToken, format!(
                 "Invalid pattern: {:?} at line {}",
                 token.token_type, token.line
-            )),
+            )).into()),
         }
     }
 
-    fn block(&mut self) -> Result<AstNode, String> {
+    fn block(&mut self) -> JResult<AstNode> {
         self.consume(&TokenType::LeftBrace, "Expected '{'")?;
 
         let mut statements = Vec::new();
@@ -3631,7 +4526,11 @@ impl Parser {
             if self.match_token(&TokenType::Newline) {
                 continue;
             }
-            statements.push(self.statement()?);
+            let start = self.current;
+            match self.statement() {
+                Ok(statement) => statements.push(statement),
+                Err(error) => self.recover(error, start)?,
+            }
         }
 
         self.consume(&TokenType::RightBrace, "Expected '}'")?;
@@ -3665,12 +4564,12 @@ impl Parser {
         }
     }
 
-    fn dict_key(&mut self) -> Result<AstNode, String> {
+    fn dict_key(&mut self) -> JResult<AstNode> {
         let key_token = self.advance().clone(); // Consume the key token first
         match &key_token.token_type {
             TokenType::Identifier(name) => Ok(AstNode::String(name.clone())),
             TokenType::String(s) => Ok(AstNode::String(s.clone())),
-            _ => Err("Dictionary keys must be identifiers or string literals".to_string()),
+            _ => Err(JError::syntax(ErrorKind::UnexpectedToken, "Dictionary keys must be identifiers or string literals").into()),
         }
     }
 
@@ -3745,20 +4644,20 @@ impl Parser {
     }
 
     // Advanced algorithm loop parsing functions
-    fn parse_sweep_loop(&mut self) -> Result<AstNode, String> {
+    fn parse_sweep_loop(&mut self) -> JResult<AstNode> {
         // sweep (left, right) in nums { ... }
         self.consume(&TokenType::LeftParen, "Expected '(' after 'sweep'")?;
 
         let left_var = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected left variable name in sweep loop".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected left variable name in sweep loop").into()),
         };
 
         self.consume(&TokenType::Comma, "Expected ',' between sweep variables")?;
 
         let right_var = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected right variable name in sweep loop".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected right variable name in sweep loop").into()),
         };
 
         self.consume(&TokenType::RightParen, "Expected ')' after sweep variables")?;
@@ -3775,20 +4674,20 @@ impl Parser {
         })
     }
 
-    fn parse_shrink_loop(&mut self) -> Result<AstNode, String> {
+    fn parse_shrink_loop(&mut self) -> JResult<AstNode> {
         // shrink (left, right) in nums { ... }
         self.consume(&TokenType::LeftParen, "Expected '(' after 'shrink'")?;
 
         let left_var = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected left variable name in shrink loop".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected left variable name in shrink loop").into()),
         };
 
         self.consume(&TokenType::Comma, "Expected ',' between shrink variables")?;
 
         let right_var = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected right variable name in shrink loop".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected right variable name in shrink loop").into()),
         };
 
         self.consume(
@@ -3808,20 +4707,20 @@ impl Parser {
         })
     }
 
-    fn parse_meet_loop(&mut self) -> Result<AstNode, String> {
+    fn parse_meet_loop(&mut self) -> JResult<AstNode> {
         // meet (left, right) in nums { ... }
         self.consume(&TokenType::LeftParen, "Expected '(' after 'meet'")?;
 
         let left_var = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected left variable name in meet loop".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected left variable name in meet loop").into()),
         };
 
         self.consume(&TokenType::Comma, "Expected ',' between meet variables")?;
 
         let right_var = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected right variable name in meet loop".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected right variable name in meet loop").into()),
         };
 
         self.consume(&TokenType::RightParen, "Expected ')' after meet variables")?;
@@ -3838,20 +4737,20 @@ impl Parser {
         })
     }
 
-    fn parse_binary_search_loop(&mut self) -> Result<AstNode, String> {
+    fn parse_binary_search_loop(&mut self) -> JResult<AstNode> {
         // binary (lo, hi) in range { ... } else { ... }
         self.consume(&TokenType::LeftParen, "Expected '(' after 'binary'")?;
 
         let lo_var = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected lo variable name in binary loop".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected lo variable name in binary loop").into()),
         };
 
         self.consume(&TokenType::Comma, "Expected ',' between binary variables")?;
 
         let hi_var = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected hi variable name in binary loop".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected hi variable name in binary loop").into()),
         };
 
         self.consume(
@@ -3878,11 +4777,11 @@ impl Parser {
         })
     }
 
-    fn parse_dp_loop(&mut self) -> Result<AstNode, String> {
+    fn parse_dp_loop(&mut self) -> JResult<AstNode> {
         // dp table[n][m] = 0 { ... }
         let table_name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected table name in dp loop".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected table name in dp loop").into()),
         };
 
         // Parse dimensions: [n][m]
@@ -3894,7 +4793,7 @@ impl Parser {
         }
 
         if dimensions.is_empty() {
-            return Err("DP loop requires at least one dimension".to_string());
+            return Err(JError::syntax(ErrorKind::UnexpectedToken, "DP loop requires at least one dimension").into());
         }
 
         self.consume(&TokenType::Assign, "Expected '=' after dp dimensions")?;
@@ -3910,11 +4809,11 @@ impl Parser {
         })
     }
 
-    fn parse_while_nonzero(&mut self) -> Result<AstNode, String> {
+    fn parse_while_nonzero(&mut self) -> JResult<AstNode> {
         // while_nonzero var { ... }
         let var = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected variable name in while_nonzero".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected variable name in while_nonzero").into()),
         };
 
         let body = self.block()?;
@@ -3925,11 +4824,11 @@ impl Parser {
         })
     }
 
-    fn parse_while_change(&mut self) -> Result<AstNode, String> {
+    fn parse_while_change(&mut self) -> JResult<AstNode> {
         // while_change var = init { ... }
         let var = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected variable name in while_change".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected variable name in while_change").into()),
         };
 
         self.consume(
@@ -3947,11 +4846,11 @@ impl Parser {
         })
     }
 
-    fn parse_while_match(&mut self) -> Result<AstNode, String> {
+    fn parse_while_match(&mut self) -> JResult<AstNode> {
         // while_match var { ... }
         let var = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected variable name in while_match".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected variable name in while_match").into()),
         };
 
         let body = self.block()?;
@@ -3981,22 +4880,24 @@ impl Parser {
         &self.tokens[self.current - 1]
     }
 
-    fn consume(&mut self, token_type: &TokenType, _message: &str) -> Result<&Token, String> {
+    fn consume(&mut self, token_type: &TokenType, _message: &str) -> JResult<&Token> {
         if self.check(token_type) {
             Ok(self.advance())
         } else {
             let current_token = self.peek();
             let expected = format!("{:?}", token_type);
             let got = format!("{:?}", current_token.token_type);
-            Err(
-                JError::unexpected_token(&expected, &got, current_token.line, current_token.column)
-                    .to_string(),
-            )
+            Err(Box::new(JError::unexpected_token(
+                &expected,
+                &got,
+                current_token.line,
+                current_token.column,
+            )))
         }
     }
 
     // Enhanced error helpers
-    fn error_expected(&self, expected: &str) -> String {
+    fn error_expected(&self, expected: &str) -> Box<JError> {
         let current = self.peek();
         let got = format!("{:?}", current.token_type);
         JError::parser_error(
@@ -4006,33 +4907,33 @@ impl Parser {
             current.line,
             current.column,
         )
-        .to_string()
+        .into()
     }
 
     #[allow(dead_code)]
-    fn error_unexpected(&self, context: &str) -> String {
+    fn error_unexpected(&self, context: &str) -> Box<JError> {
         let current = self.peek();
         JError::new(
-            crate::error::ErrorKind::UnexpectedToken,
+            ErrorKind::UnexpectedToken,
             format!("Unexpected token in {}", context),
         )
         .with_location(current.line, current.column)
         .with_tip(format!("Token {:?} is not valid here", current.token_type))
         .with_context(context.to_string())
-        .to_string()
+        .into()
     }
 
     #[allow(dead_code)]
-    fn error_invalid_syntax(&self, context: &str, suggestion: &str) -> String {
+    fn error_invalid_syntax(&self, context: &str, suggestion: &str) -> Box<JError> {
         let current = self.peek();
         JError::new(
-            crate::error::ErrorKind::InvalidSyntax,
+            ErrorKind::InvalidSyntax,
             format!("Invalid syntax in {}", context),
         )
         .with_location(current.line, current.column)
         .with_tip(suggestion.to_string())
         .with_context(context.to_string())
-        .to_string()
+        .into()
     }
 
     fn is_type_token(&self) -> bool {
@@ -4157,13 +5058,24 @@ impl Parser {
         }
     }
 
-    fn parse_string_interpolation(&mut self, s: &str) -> Result<AstNode, String> {
+    fn parse_string_interpolation(&mut self, s: &str) -> JResult<AstNode> {
         let mut parts = Vec::new();
         let mut current = String::new();
         let mut chars = s.chars().peekable();
+        // Spans inside the literal count from the character after its opening quote.
+        let literal = self.previous_span();
+        let total = s.chars().count();
+        let span_at = |remaining: usize| match literal.line {
+            0 => Span::default(),
+            _ => Span {
+                column: literal.column + 1 + total - remaining,
+                ..literal.clone()
+            },
+        };
 
         while let Some(ch) = chars.next() {
             if ch == '$' {
+                let span = span_at(chars.clone().count());
                 // Add current string part if not empty
                 if !current.is_empty() {
                     parts.push(AstNode::String(current.clone()));
@@ -4181,7 +5093,7 @@ impl Parser {
                 }
 
                 if !var_name.is_empty() {
-                    parts.push(AstNode::Identifier(var_name));
+                    parts.push(AstNode::Identifier(var_name, span));
                 }
             } else if ch == '{' {
                 // Add current string part if not empty
@@ -4191,6 +5103,7 @@ impl Parser {
                 }
 
                 // Parse expression inside braces
+                let start = span_at(chars.clone().count());
                 let mut expr = String::new();
                 let mut brace_count = 1;
 
@@ -4209,10 +5122,20 @@ impl Parser {
                 if !expr.is_empty() {
                     // Parse the expression
                     let mut expr_lexer = crate::lexer::Lexer::new(&expr);
-                    let expr_tokens = expr_lexer
+                    let mut expr_tokens = expr_lexer
                         .tokenize()
-                        .map_err(|e| format!("Error tokenizing interpolated expression: {}", e))?;
+                        .map_err(|e| JError::syntax(ErrorKind::UnexpectedToken, format!("Error tokenizing interpolated expression: {}", e)))?;
+                    if start.line > 0 {
+                        for token in &mut expr_tokens {
+                            if token.line == 1 {
+                                token.column += start.column - 1;
+                            }
+                            token.line += start.line - 1;
+                        }
+                    }
                     let mut expr_parser = Parser::new(expr_tokens);
+                    expr_parser.file = self.file.clone();
+                    expr_parser.spans = self.spans;
                     let expr_ast = expr_parser.expression()?;
                     parts.push(expr_ast);
                 }
@@ -4231,12 +5154,12 @@ impl Parser {
 
     // ===== NEW ADVANCED FEATURE PARSERS =====
 
-    fn trait_declaration(&mut self) -> Result<AstNode, String> {
+    fn trait_declaration(&mut self) -> JResult<AstNode> {
         self.consume(&TokenType::Pipe, "Expected '|' after 'trait'")?;
 
         let name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected trait name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected trait name").into()),
         };
 
         self.consume(&TokenType::LeftBrace, "Expected '{' after trait name")?;
@@ -4249,11 +5172,15 @@ impl Parser {
             }
 
             // Parse method signature (fn without body)
+            let doc = self.peek().doc.clone();
             if self.match_token(&TokenType::Fn) {
-                let method = self.function_declaration()?;
+                let mut method = self.function_declaration()?;
+                if let Some(doc) = doc {
+                    method.set_doc(doc);
+                }
                 methods.push(method);
             } else {
-                return Err("Expected method declaration in trait".to_string());
+                return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected method declaration in trait").into());
             }
 
             self.match_token(&TokenType::Newline);
@@ -4261,10 +5188,14 @@ impl Parser {
 
         self.consume(&TokenType::RightBrace, "Expected '}' after trait body")?;
 
-        Ok(AstNode::TraitDeclaration { name, methods })
+        Ok(AstNode::TraitDeclaration {
+            name,
+            methods,
+            doc: None,
+        })
     }
 
-    fn async_function_declaration(&mut self) -> Result<AstNode, String> {
+    fn async_function_declaration(&mut self) -> JResult<AstNode> {
         // async fn | name (params) > body
         self.consume(&TokenType::Fn, "Expected 'fn' after 'async'")?;
         self.consume(&TokenType::Pipe, "Expected '|' after 'async fn'")?;
@@ -4272,7 +5203,7 @@ impl Parser {
         let name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
             TokenType::Test => "test".to_string(), // Handle 'test' keyword as function name
-            _ => return Err("Expected function name after 'async fn |'".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected function name after 'async fn |'").into()),
         };
 
         self.consume(&TokenType::LeftParen, "Expected '(' after function name")?;
@@ -4286,14 +5217,14 @@ impl Parser {
                     TokenType::FloatType => "float".to_string(),
                     TokenType::Bool => "bool".to_string(),
                     TokenType::Identifier(name) => name.clone(),
-                    _ => return Err("Expected parameter type".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected parameter type").into()),
                 };
 
                 self.consume(&TokenType::Pipe, "Expected '|' after parameter type")?;
 
                 let param_name = match &self.advance().token_type {
                     TokenType::Identifier(name) => name.clone(),
-                    _ => return Err("Expected parameter name".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected parameter name").into()),
                 };
 
                 params.push((param_type, param_name));
@@ -4319,10 +5250,11 @@ impl Parser {
             params,
             return_type: None, // For now, async functions don't specify return type
             body: Box::new(body),
+            doc: None,
         })
     }
 
-    fn await_expression(&mut self) -> Result<AstNode, String> {
+    fn await_expression(&mut self) -> JResult<AstNode> {
         // await expression
         let expr = self.primary()?;
         Ok(AstNode::AwaitExpression {
@@ -4330,13 +5262,13 @@ impl Parser {
         })
     }
 
-    fn module_declaration(&mut self) -> Result<AstNode, String> {
+    fn module_declaration(&mut self) -> JResult<AstNode> {
         // module | name { body }
         self.consume(&TokenType::Pipe, "Expected '|' after 'module'")?;
 
         let name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected module name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected module name").into()),
         };
 
         self.consume(&TokenType::LeftBrace, "Expected '{' after module name")?;
@@ -4354,17 +5286,19 @@ impl Parser {
         Ok(AstNode::ModuleDeclaration {
             name,
             body: Box::new(AstNode::Block(statements)),
+            doc: None,
         })
     }
 
-    fn import_statement(&mut self) -> Result<AstNode, String> {
+    fn import_statement(&mut self) -> JResult<AstNode> {
         // import std.io or import std.io.{read, write}
+        let span = self.previous_span();
         let mut module_path = Vec::new();
 
         loop {
             match &self.advance().token_type {
                 TokenType::Identifier(name) => module_path.push(name.clone()),
-                _ => return Err("Expected module path".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected module path").into()),
             }
 
             if !self.match_token(&TokenType::Dot) {
@@ -4379,7 +5313,7 @@ impl Parser {
             loop {
                 match &self.advance().token_type {
                     TokenType::Identifier(name) => items.push(name.clone()),
-                    _ => return Err("Expected item name".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected item name").into()),
                 }
 
                 if !self.match_token(&TokenType::Comma) {
@@ -4393,17 +5327,21 @@ impl Parser {
             Vec::new() // Import all
         };
 
-        Ok(AstNode::ImportStatement { module_path, items })
+        Ok(AstNode::ImportStatement {
+            module_path,
+            items,
+            span,
+        })
     }
 
-    fn use_statement(&mut self) -> Result<AstNode, String> {
+    fn use_statement(&mut self) -> JResult<AstNode> {
         // use std.io.read
         let mut path = Vec::new();
 
         loop {
             match &self.advance().token_type {
                 TokenType::Identifier(name) => path.push(name.clone()),
-                _ => return Err("Expected path component".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected path component").into()),
             }
 
             if !self.match_token(&TokenType::Dot) {
@@ -4415,13 +5353,13 @@ impl Parser {
     }
 
     #[allow(dead_code)]
-    fn generic_function_declaration(&mut self) -> Result<AstNode, String> {
+    fn generic_function_declaration(&mut self) -> JResult<AstNode> {
         // fn | name<T, U> (params) > body
         self.consume(&TokenType::Pipe, "Expected '|' after 'fn'")?;
 
         let name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected function name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected function name").into()),
         };
 
         // Parse type parameters: <T, U>
@@ -4431,7 +5369,7 @@ impl Parser {
         loop {
             match &self.advance().token_type {
                 TokenType::Identifier(name) => type_params.push(name.clone()),
-                _ => return Err("Expected type parameter name".to_string()),
+                _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected type parameter name").into()),
             }
 
             if !self.match_token(&TokenType::Comma) {
@@ -4451,14 +5389,14 @@ impl Parser {
                     TokenType::FloatType => "float".to_string(),
                     TokenType::Bool => "bool".to_string(),
                     TokenType::Identifier(name) => name.clone(),
-                    _ => return Err("Expected parameter type".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected parameter type").into()),
                 };
 
                 self.consume(&TokenType::Pipe, "Expected '|' after parameter type")?;
 
                 let param_name = match &self.advance().token_type {
                     TokenType::Identifier(name) => name.clone(),
-                    _ => return Err("Expected parameter name".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected parameter name").into()),
                 };
 
                 params.push((param_type, param_name));
@@ -4498,16 +5436,17 @@ impl Parser {
             params,
             return_type,
             body: Box::new(body),
+            doc: None,
         })
     }
 
-    fn macro_definition(&mut self) -> Result<AstNode, String> {
+    fn macro_definition(&mut self) -> JResult<AstNode> {
         // macro | name (params) > body
         self.consume(&TokenType::Pipe, "Expected '|' after 'macro'")?;
 
         let name = match &self.advance().token_type {
             TokenType::Identifier(name) => name.clone(),
-            _ => return Err("Expected macro name".to_string()),
+            _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected macro name").into()),
         };
 
         self.consume(&TokenType::LeftParen, "Expected '(' after macro name")?;
@@ -4517,7 +5456,7 @@ impl Parser {
             loop {
                 match &self.advance().token_type {
                     TokenType::Identifier(name) => params.push(name.clone()),
-                    _ => return Err("Expected parameter name".to_string()),
+                    _ => return Err(JError::syntax(ErrorKind::ExpectedToken, "Expected parameter name").into()),
                 }
 
                 if !self.match_token(&TokenType::Comma) {
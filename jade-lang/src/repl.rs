@@ -1,6 +1,6 @@
 //! Interactive REPL for the Jade language.
 
-use crate::error::JError;
+use crate::error::{JError, JResult};
 use crate::interpreter::{Interpreter, Value};
 use crate::lexer::Lexer;
 use crate::parser::Parser;
@@ -10,12 +10,14 @@ use std::path::Path;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-fn value_preview(v: &Value, max_len: usize) -> String {
+/// `v` as printed by `out()`, cut to about `max_len` characters. Also used by `jade debug`.
+pub(crate) fn value_preview(v: &Value, max_len: usize) -> String {
     let s = format!("{}", v);
-    if s.len() <= max_len {
+    if s.chars().count() <= max_len {
         s
     } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
+        let kept: String = s.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", kept)
     }
 }
 
@@ -123,7 +125,7 @@ impl Repl {
                         println!("{}", result);
                     }
                 }
-                Err(e) => eprintln!("{}", JError::from_interpreter_message(&e)),
+                Err(e) => eprintln!("{}", self.interpreter.describe_error(&e, &input)),
             }
         }
     }
@@ -188,34 +190,35 @@ impl Repl {
         !stack.is_empty()
     }
 
-    fn load_file(&mut self, path: &str) -> Result<(), String> {
-        let source = std::fs::read_to_string(path)
-            .map_err(|e| format!("Could not read file: {}", e))?;
-        let mut lexer = Lexer::new(&source);
-        let tokens = lexer.tokenize().map_err(|e| format!("Lexer: {}", e))?;
-        let mut parser = Parser::new(tokens);
-        let ast = parser.parse().map_err(|e| format!("Parser: {}", e))?;
-        self.interpreter
-            .evaluate(&ast)
-            .map_err(|e| format!("Runtime: {}", e))?;
+    fn load_file(&mut self, path: &str) -> JResult<()> {
+        let source = read_source(path)?;
+        Self::run_source(&mut self.interpreter, &source)?;
         println!("Loaded {}", path);
         Ok(())
     }
 
-    fn evaluate(&mut self, input: &str) -> Result<String, String> {
+    /// Run `source` with `interpreter`, describing an error with its source line.
+    fn run_source(interpreter: &mut Interpreter, source: &str) -> JResult<String> {
+        let ast = crate::interpreter::parse_source(source)?;
+        interpreter
+            .evaluate(&ast)
+            .map_err(|e| interpreter.describe_error(&e, source).into())
+    }
+
+    fn evaluate(&mut self, input: &str) -> JResult<String> {
         let mut lexer = Lexer::new(input);
-        let tokens = lexer.tokenize().map_err(|e| format!("{}", e))?;
+        let tokens = lexer.tokenize()?;
 
         let mut parser = Parser::new(tokens);
-        let ast = parser.parse().map_err(|e| format!("{}", e))?;
+        let ast = parser.parse()?;
 
-        let result = self.interpreter.evaluate(&ast).map_err(|e| format!("{}", e))?;
+        let result = self.interpreter.evaluate(&ast)?;
 
         Ok(result)
     }
 
-    fn print_error(&self, _prefix: &str, msg: &str) {
-        eprintln!("{}", JError::from_interpreter_message(msg));
+    fn print_error(&self, error: &JError) {
+        eprintln!("{}", error);
     }
 
     /// Handle built-in and dot-commands. Returns Some if handled (or exit), None to evaluate as Jade.
@@ -262,28 +265,28 @@ impl Repl {
         if let Some(rest) = input.strip_prefix(".load ") {
             let path = rest.trim().trim_matches('"');
             if let Err(e) = self.load_file(path) {
-                self.print_error("Load", &e);
+                self.print_error(&e);
             }
             return Some(CommandResult::Handled);
         }
         if let Some(rest) = input.strip_prefix(".run ") {
             let path = rest.trim().trim_matches('"');
             if let Err(e) = self.run_file(path) {
-                self.print_error("Run", &e);
+                self.print_error(&e);
             }
             return Some(CommandResult::Handled);
         }
         if let Some(rest) = input.strip_prefix(".save ") {
             let path = rest.trim().trim_matches('"');
             if let Err(e) = self.save_history(path) {
-                self.print_error("Save", &e);
+                self.print_error(&e);
             }
             return Some(CommandResult::Handled);
         }
         if let Some(rest) = input.strip_prefix(".cd ") {
             let path = rest.trim().trim_matches('"');
             if let Err(e) = env::set_current_dir(Path::new(path)) {
-                self.print_error("cd", &e.to_string());
+                self.print_error(&io_error(path, e));
             }
             return Some(CommandResult::Handled);
         }
@@ -300,7 +303,7 @@ impl Repl {
         if let Some(rest) = input.strip_prefix(".cat ") {
             let path = rest.trim().trim_matches('"');
             if let Err(e) = self.cat_file(path) {
-                self.print_error("cat", &e);
+                self.print_error(&e);
             }
             return Some(CommandResult::Handled);
         }
@@ -328,21 +331,15 @@ impl Repl {
         }
     }
 
-    fn run_file(&self, path: &str) -> Result<(), String> {
-        let source = std::fs::read_to_string(path)
-            .map_err(|e| format!("Could not read file: {}", e))?;
-        let mut lexer = Lexer::new(&source);
-        let tokens = lexer.tokenize().map_err(|e| format!("Lexer: {}", e))?;
-        let mut parser = Parser::new(tokens);
-        let ast = parser.parse().map_err(|e| format!("Parser: {}", e))?;
-        let mut interp = Interpreter::new();
-        interp.evaluate(&ast).map_err(|e| format!("Runtime: {}", e))?;
+    fn run_file(&self, path: &str) -> JResult<()> {
+        let source = read_source(path)?;
+        Self::run_source(&mut Interpreter::new(), &source)?;
         Ok(())
     }
 
-    fn save_history(&self, path: &str) -> Result<(), String> {
+    fn save_history(&self, path: &str) -> JResult<()> {
         let content = self.history.join("\n");
-        std::fs::write(path, content).map_err(|e| format!("Could not write: {}", e))?;
+        std::fs::write(path, content).map_err(|e| io_error(path, e))?;
         println!("Saved {} lines to {}", self.history.len(), path);
         Ok(())
     }
@@ -366,9 +363,8 @@ impl Repl {
         }
     }
 
-    fn cat_file(&self, path: &str) -> Result<(), String> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| format!("{}", e))?;
+    fn cat_file(&self, path: &str) -> JResult<()> {
+        let content = read_source(path)?;
         print!("{}", content);
         Ok(())
     }
@@ -442,3 +438,16 @@ impl Default for Repl {
         Self::new()
     }
 }
+
+fn read_source(path: &str) -> JResult<String> {
+    std::fs::read_to_string(path).map_err(|e| io_error(path, e))
+}
+
+/// A failed file operation on `path`: a missing file, or any other I/O error.
+fn io_error(path: &str, error: io::Error) -> Box<JError> {
+    let error = match error.kind() {
+        io::ErrorKind::NotFound => JError::file_not_found(path),
+        _ => JError::io_error(format!("{}: {}", path, error)),
+    };
+    Box::new(error)
+}
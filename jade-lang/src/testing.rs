@@ -0,0 +1,332 @@
+//! Test runner for `jade test`: discovers `.jdl` files, runs their `test` blocks, reports results.
+//!
+//! A file is loaded once (top-level code runs so functions and globals exist), then every
+//! collected test runs in a fresh scope with its `out()` output captured. With coverage on,
+//! each file's line and branch counts come back in its report.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::interpreter::{Coverage, Interpreter, DEFAULT_MAX_CALL_DEPTH};
+use crate::lexer::{Lexer, TokenType};
+
+/// Result of running a single test.
+#[derive(Debug, Clone)]
+pub struct TestOutcome {
+    pub name: String,
+    /// Failure message; `None` when the test passed.
+    pub failure: Option<String>,
+    /// Output written by `out()` while the test ran.
+    pub output: String,
+    pub duration: Duration,
+}
+
+impl TestOutcome {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Results for one source file.
+#[derive(Debug, Clone)]
+pub struct FileReport {
+    pub path: String,
+    /// Set when the file could not be lexed, parsed, or its top level failed; no tests ran.
+    pub load_error: Option<String>,
+    pub outcomes: Vec<TestOutcome>,
+    /// Counts for the file and the modules it imported, when [`TestOptions::coverage`] is set.
+    pub coverage: Option<Coverage>,
+}
+
+impl FileReport {
+    pub fn passed(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.passed()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.outcomes.iter().filter(|o| !o.passed()).count()
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.load_error.is_none() && self.failed() == 0
+    }
+}
+
+/// Settings shared by every file in a `jade test` run.
+#[derive(Debug, Clone)]
+pub struct TestOptions {
+    /// Fixed seed for property tests; a fresh seed per property when `None`.
+    pub seed: Option<u64>,
+    /// Generated inputs per property test.
+    pub cases: usize,
+    /// Nested call limit for each file's interpreter.
+    pub max_depth: usize,
+    /// Fail calls to `@deprecated` functions instead of warning.
+    pub deny_deprecated: bool,
+    /// Record line and branch coverage while loading the file and running its tests.
+    pub coverage: bool,
+}
+
+impl Default for TestOptions {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            cases: 100,
+            max_depth: DEFAULT_MAX_CALL_DEPTH,
+            deny_deprecated: false,
+            coverage: false,
+        }
+    }
+}
+
+/// Directories never searched for tests (build output, VCS, installed packages).
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "dist"];
+
+/// Collect `.jdl` files under `root` (or `root` itself if it is a file) that declare tests.
+/// Hidden directories such as `.git` and `.jade/packages` are skipped. Sorted for stable output.
+pub fn discover_test_files(root: &Path) -> Vec<PathBuf> {
+    if root.is_file() {
+        return vec![root.to_path_buf()];
+    }
+    let mut files = discover_source_files(root);
+    files.retain(|p| {
+        fs::read_to_string(p)
+            .map(|source| contains_tests(&source))
+            .unwrap_or(false)
+    });
+    files
+}
+
+/// Every `.jdl` file under `root` (or `root` itself if it is a file), skipping the same
+/// directories as test discovery. Sorted for stable output.
+pub fn discover_source_files(root: &Path) -> Vec<PathBuf> {
+    if root.is_file() {
+        return vec![root.to_path_buf()];
+    }
+    let mut files = Vec::new();
+    collect_jdl_files(root, &mut files);
+    files.sort();
+    files
+}
+
+fn collect_jdl_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            collect_jdl_files(&path, out);
+        } else if path.extension().map(|e| e == "jdl").unwrap_or(false) {
+            out.push(path);
+        }
+    }
+}
+
+/// True if the source has a `test "..."` or `property "..."` block. Files that do not lex are
+/// skipped during discovery so unrelated scripts are never executed.
+fn contains_tests(source: &str) -> bool {
+    let Ok(tokens) = Lexer::new(source).tokenize() else {
+        return false;
+    };
+    tokens.windows(2).any(|w| {
+        matches!(w[0].token_type, TokenType::Test | TokenType::Property)
+            && matches!(w[1].token_type, TokenType::String(_))
+    })
+}
+
+/// Module search paths for a file: its directory plus `.jade/packages` when present (as `jade run`).
+pub(crate) fn module_search_paths(path: &Path) -> Vec<String> {
+    let parent = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+    let mut paths = Vec::new();
+    let parent_str = parent.to_string_lossy().to_string();
+    if !parent_str.is_empty() {
+        paths.push(parent_str);
+        let packages = parent.join(".jade").join("packages");
+        if packages.exists() {
+            paths.push(packages.to_string_lossy().to_string());
+        }
+    }
+    paths
+}
+
+/// Run every test declared in `source`. `label` is used as the report path.
+pub fn run_test_source(
+    source: &str,
+    label: &str,
+    search_paths: &[String],
+    options: &TestOptions,
+) -> FileReport {
+    let source = source.replace("\r\n", "\n").replace('\r', "\n");
+    let mut interpreter = Interpreter::new();
+    interpreter.set_property_seed(options.seed);
+    interpreter.set_property_cases(options.cases);
+    interpreter.set_max_call_depth(options.max_depth);
+    interpreter.set_deny_deprecated(options.deny_deprecated);
+    interpreter.set_program_path(label);
+    for path in search_paths {
+        interpreter.add_module_search_path(path.clone());
+    }
+    if options.coverage {
+        interpreter.set_coverage(Coverage::new(label));
+    }
+
+    // Top-level output is not part of any test; capture and drop it.
+    interpreter.set_output_capture(true);
+    let loaded = interpreter.load_for_tests(&source);
+    interpreter.take_captured_output();
+    let mut report = FileReport {
+        path: label.to_string(),
+        load_error: None,
+        outcomes: Vec::new(),
+        coverage: None,
+    };
+    if let Err(e) = loaded {
+        report.load_error = Some(e.message);
+        report.coverage = interpreter.take_coverage();
+        return report;
+    }
+
+    let tests = interpreter.collected_tests().to_vec();
+    for test in &tests {
+        interpreter.set_output_capture(true);
+        let start = Instant::now();
+        let result = interpreter.run_test(test);
+        let duration = start.elapsed();
+        report.outcomes.push(TestOutcome {
+            name: test.name.clone(),
+            failure: result.err().map(|e| e.message),
+            output: interpreter.take_captured_output().unwrap_or_default(),
+            duration,
+        });
+    }
+    report.coverage = interpreter.take_coverage();
+    report
+}
+
+/// Read and run the tests in one file.
+pub fn run_test_file(path: &Path, options: &TestOptions) -> FileReport {
+    let label = path.to_string_lossy().to_string();
+    match fs::read_to_string(path) {
+        Ok(source) => run_test_source(&source, &label, &module_search_paths(path), options),
+        Err(e) => FileReport {
+            path: label,
+            load_error: Some(format!("Error reading file: {}", e)),
+            outcomes: Vec::new(),
+            coverage: None,
+        },
+    }
+}
+
+fn print_indented(text: &str, indent: &str) {
+    for line in text.trim().lines() {
+        println!("{}{}", indent, line);
+    }
+}
+
+/// Print per-test results and a summary. Returns true when every file loaded and every test passed.
+pub fn print_report(reports: &[FileReport]) -> bool {
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut broken_files = 0;
+
+    for report in reports {
+        println!("🧪 {}", report.path);
+        if let Some(ref e) = report.load_error {
+            broken_files += 1;
+            println!("  ❌ could not load file");
+            print_indented(e, "     ");
+            continue;
+        }
+        for outcome in &report.outcomes {
+            let ms = outcome.duration.as_secs_f64() * 1000.0;
+            match outcome.failure {
+                None => println!("  ✅ {} ({:.1} ms)", outcome.name, ms),
+                Some(ref msg) => {
+                    println!("  ❌ {} ({:.1} ms)", outcome.name, ms);
+                    print_indented(msg, "     ");
+                    if !outcome.output.trim().is_empty() {
+                        println!("     output:");
+                        print_indented(&outcome.output, "       ");
+                    }
+                }
+            }
+        }
+        passed += report.passed();
+        failed += report.failed();
+    }
+
+    let ok = failed == 0 && broken_files == 0;
+    println!();
+    println!(
+        "Test result: {}. {} passed; {} failed; {} file(s){}",
+        if ok { "ok" } else { "FAILED" },
+        passed,
+        failed,
+        reports.len(),
+        if broken_files > 0 {
+            format!("; {} failed to load", broken_files)
+        } else {
+            String::new()
+        }
+    );
+    ok
+}
+
+/// Print lines and branches covered per file and in total. Paths are shown relative to the
+/// current directory when they are inside it.
+pub fn print_coverage(coverage: &Coverage) {
+    let cwd = std::env::current_dir().ok();
+    let rows: Vec<_> = coverage
+        .files()
+        .iter()
+        .map(|(path, file)| {
+            let shown = cwd
+                .as_ref()
+                .and_then(|cwd| path.strip_prefix(cwd).ok())
+                .unwrap_or(path);
+            let lines = ratio(file.lines_hit(), file.lines_found());
+            let branches = ratio(file.branches_hit(), file.branches_found());
+            (shown.display().to_string(), lines, branches)
+        })
+        .collect();
+    let (lines_hit, lines_found) = coverage.line_totals();
+    let (branches_hit, branches_found) = coverage.branch_totals();
+    let total = (
+        "Total".to_string(),
+        ratio(lines_hit, lines_found),
+        ratio(branches_hit, branches_found),
+    );
+    let width = rows.iter().chain([&total]).map(|row| row.0.len()).max().unwrap_or(0);
+    let lines_width = rows.iter().chain([&total]).map(|row| row.1.len()).max().unwrap_or(0);
+
+    println!();
+    println!("📊 Coverage");
+    for (name, lines, branches) in rows.iter().chain([&total]) {
+        println!(
+            "  {:<width$}  lines {:<lines_width$}  branches {}",
+            name,
+            lines,
+            branches,
+            width = width,
+            lines_width = lines_width
+        );
+    }
+}
+
+/// Share of lines (or branches) covered, as a percentage; `None` when there are none.
+pub fn coverage_percent(hit: usize, found: usize) -> Option<f64> {
+    (found > 0).then(|| hit as f64 * 100.0 / found as f64)
+}
+
+fn ratio(hit: usize, found: usize) -> String {
+    match coverage_percent(hit, found) {
+        Some(percent) => format!("{}/{} ({:.1}%)", hit, found, percent),
+        None => "-".to_string(),
+    }
+}
@@ -0,0 +1,220 @@
+//! `jade build`: lowering to LLVM IR or C and the native binaries they produce.
+
+mod common;
+
+use common::scratch_dir;
+use j_lang::compiler::{AotCompiler, Emit};
+use j_lang::interpreter::parse_source;
+use j_lang::run_source_to_string;
+use std::process::Command;
+
+const PROGRAM: &str = r#"fn | fib ( int | n ) > {
+  if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+}
+fn | echo_n ( str | s, int | n ) > {
+  if n == 0 { "" } else { s + echo_n(s, n - 1) }
+}
+fn | odd_sum ( list | xs ) > {
+  int: acc = 0
+  for x in xs {
+    if x % 2 == 0 { continue }
+    acc = acc + x
+  }
+  acc
+}
+list: squares = []
+for i in 1..5 {
+  squares = push(squares, i * i)
+}
+int: k = 0
+while true {
+  k = k + 1
+  if k >= 3 and not false { break }
+}
+out(squares, odd_sum(squares))
+str: ab = echo_n("ab", 3)
+out("fib {fib(20)} k {k} {ab}")
+out(0.1 + 0.2, 7 / 2, 7 / 2.0, -7 % 3, 2 ** 10, 3 == 3.0)
+out(squares[-1], len("héllo"), squares.len(), ["x", "y"].push("z"))
+list: rows = []
+rows = push(rows, [1.5, 2.0])
+out("rows {rows}", 1 << 4, true == false)"#;
+
+/// Dynamically typed code only the C backend can compile.
+const DYNAMIC_PROGRAM: &str = r#"fn | describe ( str | x ) > {
+  "value {x}"
+}
+list: mixed = [1, "two", 3.5, true, [4, 5]]
+for m in mixed {
+  out(describe(m))
+}
+dict: d = {name: "jade", "version": 3}
+out(d.get("name"), d.get("missing", 0), d.has("version"), d["version"] + 1)
+for c in "héllo" {
+  out(c)
+}
+str: x = 5
+x = "now a string"
+out(x + 1, mixed[-1] == [4, 5], 2 ** -1, 1..3)
+list: rows = []
+rows = push(rows, [1, "a"])
+rows = push(rows, [22, "bbb"])
+out(rows)"#;
+
+fn lower(source: &str) -> Result<String, String> {
+    AotCompiler::new().generate_llvm_ir(&parse_source(source).unwrap())
+}
+
+fn toolchain_available() -> bool {
+    ["llc", "opt", "gcc"]
+        .iter()
+        .all(|tool| Command::new(tool).arg("--version").output().is_ok())
+}
+
+fn c_compiler_available() -> bool {
+    Command::new("cc").arg("--version").output().is_ok()
+}
+
+/// Build `source` and run the binary, returning (exit code, stdout, stderr).
+fn build_and_run(name: &str, source: &str, release: bool) -> (i32, String, String) {
+    build_and_run_with(name, source, release, None)
+}
+
+fn build_and_run_with(
+    name: &str,
+    source: &str,
+    release: bool,
+    emit: Option<Emit>,
+) -> (i32, String, String) {
+    let dir = scratch_dir(&format!("aot-{}", name), &[("main.jdl", source)]);
+    let file = dir.join("main.jdl");
+    let binary = dir.join("main");
+    let mut compiler = AotCompiler::new();
+    compiler.set_emit(emit);
+    compiler.compile_file(file, release, binary.to_str()).unwrap();
+    if emit == Some(Emit::C) {
+        assert!(dir.join("main.c").exists(), "--emit c keeps the C source");
+    }
+    let output = Command::new(&binary).output().unwrap();
+    (
+        output.status.code().unwrap_or(-1),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn binary_output_matches_the_interpreter() {
+    if !toolchain_available() {
+        eprintln!("skipping: llc, opt or gcc not found");
+        return;
+    }
+    let expected = run_source_to_string(PROGRAM).unwrap();
+    for release in [false, true] {
+        let (code, stdout, _) = build_and_run(&format!("program-{}", release), PROGRAM, release);
+        assert_eq!(code, 0);
+        assert_eq!(stdout, expected);
+    }
+}
+
+#[test]
+fn runtime_errors_exit_with_the_interpreter_message() {
+    if !toolchain_available() {
+        eprintln!("skipping: llc, opt or gcc not found");
+        return;
+    }
+    let (code, stdout, stderr) = build_and_run(
+        "overflow",
+        "int: big = 9223372036854775807\nout(\"before\")\nout(big + 1)",
+        false,
+    );
+    assert_eq!(code, 1);
+    assert_eq!(stdout, "before\n");
+    assert_eq!(
+        stderr.trim(),
+        "Runtime error: Integer overflow: 9223372036854775807 + 1"
+    );
+
+    let (code, _, stderr) = build_and_run("index", "list: xs = [1]\nout(xs[5])", false);
+    assert_eq!(code, 1);
+    assert!(
+        stderr.contains("Index 5 is out of bounds for list of length 1"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn unsupported_programs_get_a_diagnostic() {
+    let ir = lower("out(1 + 2)").unwrap();
+    assert!(ir.contains("define i32 @main()"));
+    assert!(!ir.contains("Hello from J!"));
+
+    for (source, expected) in [
+        ("dict: d = {\"a\": 1}", "Dict is not supported"),
+        ("any: f = |x| x * 2", "Lambda is not supported"),
+        ("out(1 != 2)", "operator NotEqual on int and int"),
+        ("list: xs = [1, \"a\"]", "a list mixing int and str items"),
+        (
+            "int: x = 1\nx = \"s\"",
+            "variable 'x' holds int and cannot be assigned str",
+        ),
+        (
+            "fn | total ( list | xs ) > { 0 }\nout(total([1]))",
+            "name of a builtin",
+        ),
+        (
+            "fn | f ( int | n ) > { [n] + f(n - 1) }\nout(f(3))",
+            "recursive function 'f'",
+        ),
+    ] {
+        let err = lower(source).unwrap_err();
+        assert!(
+            err.starts_with("`jade build` cannot compile this program"),
+            "{}",
+            err
+        );
+        assert!(err.contains(expected), "{:?}: {}", source, err);
+    }
+}
+
+#[test]
+fn c_backend_output_matches_the_interpreter() {
+    if !c_compiler_available() {
+        eprintln!("skipping: cc not found");
+        return;
+    }
+    for (name, source) in [("typed", PROGRAM), ("dynamic", DYNAMIC_PROGRAM)] {
+        let expected = run_source_to_string(source).unwrap();
+        for release in [false, true] {
+            let name_release = format!("c-{}-{}", name, release);
+            let (code, stdout, _) =
+                build_and_run_with(&name_release, source, release, Some(Emit::C));
+            assert_eq!(code, 0);
+            assert_eq!(stdout, expected, "{}", name);
+        }
+    }
+}
+
+#[test]
+fn c_backend_reports_runtime_errors_and_unsupported_code() {
+    if c_compiler_available() {
+        let (code, stdout, stderr) = build_and_run_with(
+            "c-missing-key",
+            "dict: d = {\"a\": 1}\nout(d[\"a\"])\nout(d[\"b\"])",
+            false,
+            Some(Emit::C),
+        );
+        assert_eq!(code, 1);
+        assert_eq!(stdout, "1\n");
+        assert_eq!(stderr.trim(), "Runtime error: Key 'b' not found in dictionary");
+    }
+
+    let ast = parse_source("any: f = |x| x * 2").unwrap();
+    let err = AotCompiler::new().generate_c(&ast).unwrap_err();
+    assert!(
+        err.starts_with("`jade build` cannot compile this program: Lambda is not supported"),
+        "{}",
+        err
+    );
+}
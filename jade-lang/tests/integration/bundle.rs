@@ -0,0 +1,92 @@
+//! `jade build --bundle`: collecting a program's imports and running the packed executable.
+
+mod common;
+
+use common::{scratch_dir, ScratchDir};
+use j_lang::bundle::Bundle;
+use j_lang::error::ErrorKind;
+use std::fs;
+use std::process::Command;
+
+/// A project whose entry point imports a local module and a package that imports it too.
+fn project(name: &str) -> ScratchDir {
+    scratch_dir(
+        &format!("bundle-{}", name),
+        &[
+            (
+                "main.jdl",
+                "import util.text\nimport greet\nout(shout(\"bundled\"))\nout(hello(\"world\"))\n",
+            ),
+            ("util/text.jdl", "fn | shout ( str | s ) > { s + \"!\" }\n"),
+            (
+                ".jade/packages/greet/main.jdl",
+                "import util.text\nfn | hello ( str | name ) > { shout(\"hello \" + name) }\n",
+            ),
+        ],
+    )
+}
+
+#[test]
+fn collect_resolves_imports_transitively() {
+    let dir = project("collect");
+    let bundle = Bundle::collect(&dir.join("main.jdl")).unwrap();
+    let modules: Vec<&str> = bundle.modules.keys().map(String::as_str).collect();
+    assert_eq!(modules, ["greet", "util/text"]);
+    assert!(bundle.modules["greet"].path.ends_with("greet/main.jdl"));
+    assert_eq!(Bundle::deserialize(&bundle.serialize()).unwrap(), bundle);
+
+    fs::write(dir.join("broken.jdl"), "import util.missing\n").unwrap();
+    let err = Bundle::collect(&dir.join("broken.jdl")).unwrap_err();
+    assert!(err.contains("Module not found: util/missing"), "{}", err);
+}
+
+#[test]
+fn bundled_errors_keep_their_kind_and_traceback() {
+    let dir = project("error");
+    fs::write(
+        dir.join("util/ratio.jdl"),
+        "fn | ratio ( int | a, int | b ) > { a / b }\n",
+    )
+    .unwrap();
+    fs::write(dir.join("fail.jdl"), "import util.ratio\nout(ratio(1, 0))\n").unwrap();
+    let bundle = Bundle::collect(&dir.join("fail.jdl")).unwrap();
+    drop(dir);
+
+    let error = bundle.run().unwrap_err();
+    assert_eq!(error.kind, ErrorKind::DivisionByZero);
+    let frames: Vec<_> = error.trace.iter().map(|e| e.function.as_str()).collect();
+    assert_eq!(frames, ["<module>", "ratio"]);
+    assert_eq!(
+        error.trace[1].source_line.as_deref(),
+        Some("fn | ratio ( int | a, int | b ) > { a / b }")
+    );
+}
+
+#[test]
+fn bundled_executable_runs_without_the_sources() {
+    let dir = project("exe");
+    let binary = dir.with_extension("bin");
+    let status = Command::new(env!("CARGO_BIN_EXE_jade"))
+        .args(["build", "--bundle", "-o"])
+        .arg(&binary)
+        .arg(dir.join("main.jdl"))
+        .output()
+        .unwrap();
+    assert!(
+        status.status.success(),
+        "{}",
+        String::from_utf8_lossy(&status.stderr)
+    );
+    drop(dir);
+
+    let output = Command::new(&binary)
+        .current_dir(std::env::temp_dir())
+        .output()
+        .unwrap();
+    let _ = fs::remove_file(&binary);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "bundled!\nhello world!\n"
+    );
+}
@@ -0,0 +1,135 @@
+//! Bytecode compiler, VM and the `.jdc` cache used by `jade run`.
+
+mod common;
+
+use common::scratch_dir;
+use j_lang::bytecode::{self, CompileError, Op, Program};
+use j_lang::interpreter::parse_source;
+use j_lang::error::ErrorKind;
+use j_lang::{run_source_to_string, Interpreter, JResult};
+use std::fs;
+
+const PROGRAM: &str = r#"fn | fib ( int | n ) > {
+  if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+}
+fn | sum_odd ( list | xs ) > {
+  int: acc = 0
+  for x in xs {
+    if x % 2 == 0 { continue }
+    acc = acc + x
+  }
+  acc
+}
+int: k = 0
+while true {
+  k = k + 1
+  if k >= 3 and not false { break }
+}
+list: xs = [1, 2, 3, 4, 5]
+out("fib {fib(15)} odd {sum_odd(xs)} k {k}")
+out(xs[-1], len(xs), xs[0] * 10)
+for c in "ab" { out(c) }"#;
+
+fn compile(source: &str) -> Result<Program, CompileError> {
+    bytecode::compile(&parse_source(source).unwrap())
+}
+
+fn run_vm(program: &Program, max_depth: usize) -> JResult<String> {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_max_call_depth(max_depth);
+    interpreter.set_output_capture(true);
+    bytecode::run_program(program, &mut interpreter)?;
+    Ok(interpreter.take_captured_output().unwrap_or_default())
+}
+
+#[test]
+fn vm_output_matches_the_interpreter() {
+    let program = compile(PROGRAM).unwrap();
+    let vm = run_vm(&program, 10_000).unwrap();
+    assert_eq!(vm, run_source_to_string(PROGRAM).unwrap());
+    assert_eq!(vm, "fib 610 odd 9 k 3\n5 5 10\na\nb\n");
+}
+
+#[test]
+fn jdc_round_trips_and_rejects_damaged_files() {
+    let mut program = compile(PROGRAM).unwrap();
+    program.source_hash = bytecode::source_hash(PROGRAM);
+    let bytes = program.serialize();
+    assert!(bytes.starts_with(bytecode::MAGIC));
+    assert_eq!(Program::deserialize(&bytes).unwrap(), program);
+    assert!(Program::deserialize(&bytes[..bytes.len() - 3]).is_err());
+    assert!(Program::deserialize(b"not bytecode").is_err());
+}
+
+#[test]
+fn jdc_that_would_underflow_the_stack_is_rejected() {
+    let source = "out(1)";
+    let mut program = compile(source).unwrap();
+    program.source_hash = bytecode::source_hash(source);
+    let valid = program.functions[program.main as usize].clone();
+    // `out(1)` has no jumps, so inserting at the start leaves the code otherwise intact
+    for op in [Op::Swap, Op::Pop, Op::MakeList(3), Op::Concat(2)] {
+        let main = &mut program.functions[program.main as usize];
+        *main = valid.clone();
+        main.code.insert(0, op);
+        main.positions.insert(0, (1, 1));
+        let bytes = program.serialize();
+        let error = Program::deserialize(&bytes).unwrap_err();
+        assert!(error.contains("pops an empty stack"), "{:?}: {}", op, error);
+
+        // A cache like that is recompiled instead of run
+        let dir = scratch_dir("bytecode-underflow", &[]);
+        let path = dir.join("main.jdl");
+        let cache = bytecode::cache_path(&path);
+        fs::create_dir_all(cache.parent().unwrap()).unwrap();
+        fs::write(&cache, &bytes).unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_output_capture(true);
+        bytecode::run_file_cached(&path, source, &mut interpreter).unwrap();
+        assert_eq!(interpreter.take_captured_output().unwrap(), "1\n");
+    }
+}
+
+#[test]
+fn vm_respects_the_call_depth_limit() {
+    let program = compile(
+        r#"fn | down ( int | n ) > { if n == 0 { 0 } else { 1 + down(n - 1) } }
+out(down(50000))"#,
+    )
+    .unwrap();
+    // Frames are on the heap, so only the configured limit stops deep recursion.
+    assert_eq!(run_vm(&program, 100_000).unwrap().trim(), "50000");
+    let err = run_vm(&program, 100).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::StackOverflow);
+}
+
+#[test]
+fn unsupported_programs_run_on_the_interpreter() {
+    let source = "any: double = |x| x * 2\nout(double(21))";
+    assert!(matches!(compile(source), Err(CompileError::Unsupported(_))));
+
+    let dir = scratch_dir("bytecode-fallback", &[]);
+    let path = dir.join("lambda.jdl");
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output_capture(true);
+    bytecode::run_file_cached(&path, source, &mut interpreter).unwrap();
+    assert_eq!(interpreter.take_captured_output().unwrap().trim(), "42");
+    assert!(!bytecode::cache_path(&path).exists());
+}
+
+#[test]
+fn run_file_cached_refreshes_the_cache_when_the_source_changes() {
+    let dir = scratch_dir("bytecode-cache", &[]);
+    let path = dir.join("main.jdl");
+    let cache = bytecode::cache_path(&path);
+    assert_eq!(cache, dir.join(".jade").join("cache").join("main.jdc"));
+
+    for (source, expected) in [("out(1 + 1)", "2"), ("out(2 * 3)", "6"), ("out(2 * 3)", "6")] {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_output_capture(true);
+        bytecode::run_file_cached(&path, source, &mut interpreter).unwrap();
+        assert_eq!(interpreter.take_captured_output().unwrap().trim(), expected);
+        let cached = Program::deserialize(&fs::read(&cache).unwrap()).unwrap();
+        assert_eq!(cached.source_hash, bytecode::source_hash(source));
+    }
+}
@@ -0,0 +1,86 @@
+//! Lexical closures: captured variables outlive their defining call.
+
+use j_lang::interpreter::{parse_source, Value};
+use j_lang::parser::AstNode;
+use j_lang::{run_source_to_string, Interpreter};
+use std::rc::Rc;
+
+fn run(source: &str) -> String {
+    run_source_to_string(source).unwrap()
+}
+
+#[test]
+fn counter_keeps_its_own_state() {
+    let out = run(r#"fn | make_counter () > {
+  int: count = 0
+  fn | tick () > {
+    count = count + 1
+    count
+  }
+  return tick
+}
+any: a = make_counter()
+any: b = make_counter()
+a()
+a()
+out(a())
+out(b())"#);
+    assert_eq!(out.trim(), "3\n1");
+}
+
+#[test]
+fn compose_and_curry() {
+    let out = run(r#"fn | compose_fns ( func | f, func | g ) > {
+  return |x| f(g(x))
+}
+fn | curry_add ( int | a ) > {
+  return |b| |c| a + b + c
+}
+any: h = compose_fns(|x| x + 1, |x| x * 2)
+out(h(5))
+out(curry_add(1)(2)(3))"#);
+    assert_eq!(out.trim(), "11\n6");
+}
+
+#[test]
+fn callee_does_not_see_caller_locals() {
+    let out = run(r#"int: x = 10
+fn | read_x () > { return x }
+fn | shadow () > {
+  int: x = 99
+  return read_x()
+}
+out(shadow())"#);
+    assert_eq!(out.trim(), "10");
+}
+
+#[test]
+fn local_functions_do_not_keep_their_call_alive() {
+    let mut interp = Interpreter::new();
+    // Top-level statements one at a time, so `w` lands in the globals.
+    let Ok(AstNode::Block(statements)) = parse_source(
+        r#"fn | make () > {
+  fn | work ( int | n ) > {
+    fn | helper ( int | k ) > { return k + 1 }
+    return helper(n)
+  }
+  return work
+}
+any: w = make()"#,
+    ) else {
+        panic!("the program parses to a block");
+    };
+    for statement in &statements {
+        interp.evaluate(statement).unwrap();
+    }
+    let Some(Value::Function { env, .. }) = interp.get_global("w") else {
+        panic!("w is not a function");
+    };
+    let defining = env.scopes().last().expect("work captured make's scopes").clone();
+    let before = Rc::strong_count(&defining);
+    for _ in 0..50 {
+        assert_eq!(interp.evaluate(&parse_source("w(1)").unwrap()).unwrap(), "2");
+    }
+    // Each call's scopes hold `helper`, which captured them and `defining`.
+    assert_eq!(Rc::strong_count(&defining), before);
+}
@@ -0,0 +1,43 @@
+//! Fixtures shared by the integration tests.
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// A directory under the system temp dir, removed when dropped.
+pub struct ScratchDir(PathBuf);
+
+/// Fresh `jade-<name>-<pid>` directory holding `files` (path relative to it, contents). `name`
+/// must be unique within the test binary, since its tests run in parallel. The path is
+/// canonical, so paths built from it match the ones reports name.
+pub fn scratch_dir(name: &str, files: &[(&str, &str)]) -> ScratchDir {
+    let dir = std::env::temp_dir().join(format!("jade-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for (path, contents) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    ScratchDir(dir.canonicalize().unwrap())
+}
+
+impl Deref for ScratchDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for ScratchDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,168 @@
+//! `jade test --coverage`: statement and branch counts recorded by the interpreter across the
+//! test file and the modules it imports, merged over files and written as LCOV.
+
+mod common;
+
+use common::{scratch_dir, ScratchDir};
+use j_lang::interpreter::Coverage;
+use j_lang::testing::{run_test_file, run_test_source, TestOptions};
+use std::collections::BTreeMap;
+use std::fs;
+use std::process::Command;
+
+const RULES: &str = "fn | grade ( int | score ) > {
+  if score >= 50 {
+    out(\"pass\")
+  } else {
+    out(\"fail\")
+  }
+  score >= 50
+}
+
+fn | kind ( int | n ) > {
+  match n {
+    0: \"zero\"
+    _: \"other\"
+  }
+}
+
+fn | bucket ( int | n ) > {
+  cond (n) {
+    |> _ < 10 : \"small\"
+    |> _ < 100 : \"medium\"
+  }
+}
+
+fn | unused ( ) > {
+  out(\"never\")
+}
+";
+
+const RULES_TEST: &str = "import rules
+
+test \"grades\" {
+  assert grade(70)
+  assert kind(0) == \"zero\"
+  bucket(500)
+}
+";
+
+fn package(name: &str) -> ScratchDir {
+    scratch_dir(
+        &format!("coverage-{}", name),
+        &[("rules.jdl", RULES), ("rules_test.jdl", RULES_TEST)],
+    )
+}
+
+fn with_coverage() -> TestOptions {
+    TestOptions {
+        coverage: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn imported_modules_count_statements_and_branches() {
+    let dir = package("module");
+    let report = run_test_file(&dir.join("rules_test.jdl"), &with_coverage());
+    assert!(report.is_ok(), "{:?}", report);
+    let coverage = report.coverage.unwrap();
+    let rules = &coverage.files()[&dir.join("rules.jdl")];
+
+    // Declarations are not statements; the `else` arm and `unused` never ran
+    let lines: Vec<_> = rules
+        .lines
+        .iter()
+        .map(|(&line, &count)| (line, count))
+        .collect();
+    let expected = [(2, 1), (3, 1), (5, 0), (7, 1), (11, 1), (18, 1), (25, 0)];
+    assert_eq!(lines, expected);
+    let expected = BTreeMap::from([
+        ((2, 3), vec![1, 0]),
+        ((11, 3), vec![1, 0]),
+        // The last branch is falling through both conditions
+        ((18, 3), vec![0, 0, 1]),
+    ]);
+    assert_eq!(rules.branches, expected);
+    assert_eq!((rules.lines_hit(), rules.lines_found()), (5, 7));
+    assert_eq!((rules.branches_hit(), rules.branches_found()), (3, 7));
+
+    let test_file = &coverage.files()[&dir.join("rules_test.jdl")];
+    assert_eq!(test_file.lines.get(&4), Some(&1));
+}
+
+#[test]
+fn runs_merge_and_write_lcov() {
+    let source = "fn | polarity ( int | n ) > {
+  if n < 0 : \"-\" else : \"+\"
+}
+
+test \"negative\" { assert polarity(-1) == \"-\" }
+";
+    let run = || {
+        let report = run_test_source(source, "polarity.jdl", &[], &with_coverage());
+        assert!(report.is_ok(), "{:?}", report);
+        report.coverage.unwrap()
+    };
+    let mut coverage = Coverage::default();
+    coverage.merge(run());
+    coverage.merge(run());
+
+    assert_eq!(coverage.line_totals(), (2, 2));
+    assert_eq!(coverage.branch_totals(), (1, 2));
+    assert_eq!(
+        coverage.to_lcov(),
+        "TN:\nSF:polarity.jdl\nBRDA:2,0,0,2\nBRDA:2,0,1,0\nBRF:2\nBRH:1\nDA:2,2\nDA:5,2\nLF:2\nLH:2\nend_of_record\n"
+    );
+}
+
+#[test]
+fn cli_writes_the_report_and_enforces_the_minimum() {
+    let dir = package("cli");
+    let jade = |min: &str| {
+        Command::new(env!("CARGO_BIN_EXE_jade"))
+            .args([
+                "test",
+                "--coverage-min",
+                min,
+                "--coverage-out",
+                "out/lcov.info",
+            ])
+            .current_dir(&dir)
+            .output()
+            .unwrap()
+    };
+
+    let passing = jade("50");
+    let stdout = String::from_utf8_lossy(&passing.stdout);
+    assert!(passing.status.success(), "{}", stdout);
+    let row = stdout
+        .lines()
+        .find(|line| line.trim_start().starts_with("rules.jdl "));
+    let row: Vec<_> = row.unwrap().split_whitespace().collect();
+    assert_eq!(
+        row,
+        [
+            "rules.jdl",
+            "lines",
+            "5/7",
+            "(71.4%)",
+            "branches",
+            "3/7",
+            "(42.9%)"
+        ]
+    );
+    assert!(stdout.contains("Total"), "{}", stdout);
+    let lcov = fs::read_to_string(dir.join("out/lcov.info")).unwrap();
+    assert!(
+        lcov.contains(&format!("SF:{}\n", dir.join("rules.jdl").display())),
+        "{}",
+        lcov
+    );
+    assert!(lcov.contains("DA:25,0\n"), "{}", lcov);
+
+    let failing = jade("90");
+    assert!(!failing.status.success());
+    let stderr = String::from_utf8_lossy(&failing.stderr);
+    assert!(stderr.contains("below the required 90%"), "{}", stderr);
+}
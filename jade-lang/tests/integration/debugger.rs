@@ -0,0 +1,273 @@
+//! `jade debug`: breakpoints, stepping and inspection from the terminal and over the Debug
+//! Adapter Protocol, and the interpreter's debugger hook underneath both.
+
+mod common;
+
+use common::{scratch_dir, ScratchDir};
+use j_lang::interpreter::{
+    statement_lines, Breakpoints, Debugger, Frontend, Interpreter, Paused, Resume,
+};
+use j_lang::lexer::Lexer;
+use j_lang::lsp::{read_message, write_message};
+use j_lang::parser::Parser;
+use serde_json::{json, Value as Json};
+use std::cell::RefCell;
+use std::io::{BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::rc::Rc;
+
+const PROGRAM: &str = "static int: calls = 0
+
+fn int | plus ( int | a, int | b ) > {
+  int: total = a + b
+  calls = calls + 1
+  return total
+}
+
+int: x = plus(1, 2)
+out(x)
+";
+
+/// A directory holding `PROGRAM` as `main.jdl`.
+fn program_dir(name: &str) -> ScratchDir {
+    scratch_dir(&format!("debug-{}", name), &[("main.jdl", PROGRAM)])
+}
+
+/// Line, function and local names at each stop.
+type Stops = Vec<(usize, String, Vec<String>)>;
+
+/// Records where the program stopped and what was in scope, stepping into every call.
+struct Recorder(Rc<RefCell<Stops>>);
+
+impl Frontend for Recorder {
+    fn stopped(&mut self, paused: &mut Paused<'_>) -> Resume {
+        let function = paused.stack()[0].function.clone();
+        let locals = paused.locals(0).into_iter().map(|(name, _)| name).collect();
+        self.0.borrow_mut().push((paused.line(), function, locals));
+        Resume::StepIn
+    }
+}
+
+#[test]
+fn statement_lines_skip_declarations_and_blank_lines() {
+    let tokens = Lexer::new(PROGRAM).tokenize().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let lines: Vec<usize> = statement_lines(&program).into_iter().collect();
+    assert_eq!(lines, vec![1, 4, 5, 6, 9, 10]);
+}
+
+#[test]
+fn stepping_in_visits_every_statement_with_its_locals() {
+    let dir = program_dir("step");
+    let path = dir.join("main.jdl");
+    let tokens = Lexer::new(PROGRAM).tokenize().unwrap();
+    let mut parser = Parser::new(tokens);
+    parser.set_file(&path.to_string_lossy());
+    let program = parser.parse().unwrap();
+
+    let stops = Rc::new(RefCell::new(Vec::new()));
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output_capture(true);
+    interpreter.set_debugger(Debugger::new(
+        Recorder(stops.clone()),
+        Breakpoints::new(),
+        true,
+    ));
+    interpreter.run_ast(&program).unwrap();
+
+    let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        *stops.borrow(),
+        vec![
+            (1, "<module>".to_string(), names(&[])),
+            (9, "<module>".to_string(), names(&[])),
+            (4, "plus".to_string(), names(&["a", "b"])),
+            (5, "plus".to_string(), names(&["a", "b", "total"])),
+            (6, "plus".to_string(), names(&["a", "b", "total"])),
+            (10, "<module>".to_string(), names(&[])),
+        ]
+    );
+    assert_eq!(interpreter.take_captured_output().as_deref(), Some("3\n"));
+}
+
+/// Records the names `globals` lists at each stop.
+struct GlobalNames(Rc<RefCell<Vec<Vec<String>>>>);
+
+impl Frontend for GlobalNames {
+    fn stopped(&mut self, paused: &mut Paused<'_>) -> Resume {
+        let names = paused.globals().into_iter().map(|(name, _)| name).collect();
+        self.0.borrow_mut().push(names);
+        Resume::Continue
+    }
+}
+
+#[test]
+fn globals_leave_out_the_ones_every_program_starts_with() {
+    let dir = program_dir("globals");
+    let path = dir.join("main.jdl");
+    let tokens = Lexer::new(PROGRAM).tokenize().unwrap();
+    let mut parser = Parser::new(tokens);
+    parser.set_file(&path.to_string_lossy());
+    let program = parser.parse().unwrap();
+
+    let stops = Rc::new(RefCell::new(Vec::new()));
+    let mut breakpoints = Breakpoints::new();
+    breakpoints.set(&path, [10]);
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output_capture(true);
+    interpreter.set_debugger(Debugger::new(GlobalNames(stops.clone()), breakpoints, false));
+    interpreter.run_ast(&program).unwrap();
+    assert_eq!(*stops.borrow(), vec![vec!["plus".to_string(), "x".to_string()]]);
+}
+
+#[test]
+fn console_stops_at_breakpoints_and_prints_values() {
+    let dir = program_dir("console");
+    let path = dir.join("main.jdl");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_jade"))
+        .args(["debug", &path.to_string_lossy()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"break 2\ncontinue\nlocals\nprint a * 10\nnext\n\nstatics\nbt\ncontinue\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(output.status.code(), Some(0));
+    // Line 2 is blank, so the breakpoint moves to the next statement.
+    assert!(stdout.contains(&format!("● Breakpoint at {}:4", path.display())));
+    assert!(stdout.contains("main.jdl:4 in plus (breakpoint)"));
+    assert!(stdout.contains("  a = 1\n  b = 2\n"));
+    assert!(stdout.contains("(jade-debug) 10\n"));
+    // An empty line repeats `next`.
+    assert!(stdout.contains("main.jdl:6 in plus (step)"));
+    assert!(stdout.contains("  calls = 1\n"));
+    assert!(stdout.contains("#1 <module> at"));
+    assert!(stdout.contains("3\n✅ Program finished"));
+}
+
+/// A client talking to `jade debug --dap`.
+struct Session {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    seq: u64,
+    /// Events received so far, in order.
+    events: Vec<Json>,
+}
+
+impl Session {
+    fn start() -> Session {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_jade"))
+            .args(["debug", "--dap"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Session {
+            child,
+            stdin,
+            stdout,
+            seq: 0,
+            events: Vec::new(),
+        }
+    }
+
+    /// Send a request and return its response, keeping the events that came first.
+    fn request(&mut self, command: &str, arguments: Json) -> Json {
+        self.seq += 1;
+        let request =
+            json!({"seq": self.seq, "type": "request", "command": command, "arguments": arguments});
+        write_message(&mut self.stdin, &request).unwrap();
+        loop {
+            let message = self.receive();
+            if message["type"] == "response" {
+                assert_eq!(message["request_seq"], self.seq);
+                return message;
+            }
+            self.events.push(message);
+        }
+    }
+
+    /// Receive messages until the event named `name`.
+    fn wait_for(&mut self, name: &str) -> Json {
+        loop {
+            let message = self.receive();
+            assert_eq!(message["type"], "event", "unexpected {}", message);
+            self.events.push(message.clone());
+            if message["event"] == name {
+                return message;
+            }
+        }
+    }
+
+    fn receive(&mut self) -> Json {
+        let body = read_message(&mut self.stdout)
+            .unwrap()
+            .expect("the adapter exited");
+        serde_json::from_str(&body).unwrap()
+    }
+}
+
+#[test]
+fn dap_session_sets_breakpoints_and_inspects_the_stopped_program() {
+    let dir = program_dir("dap");
+    let path = dir.join("main.jdl");
+    let mut session = Session::start();
+    let capabilities = session.request("initialize", json!({"adapterID": "jade"}));
+    assert_eq!(
+        capabilities["body"]["supportsConfigurationDoneRequest"],
+        true
+    );
+    session.wait_for("initialized");
+    assert_eq!(
+        session.request("launch", json!({"program": path}))["success"],
+        true
+    );
+    let breakpoints = session.request(
+        "setBreakpoints",
+        json!({"source": {"path": path}, "breakpoints": [{"line": 5}, {"line": 12}]}),
+    );
+    let breakpoints = &breakpoints["body"]["breakpoints"];
+    assert_eq!(breakpoints[0], json!({"line": 5, "verified": true}));
+    assert_eq!(breakpoints[1]["verified"], false);
+    session.request("configurationDone", json!({}));
+
+    let stopped = session.wait_for("stopped");
+    assert_eq!(stopped["body"]["reason"], "breakpoint");
+    let trace = session.request("stackTrace", json!({"threadId": 1}));
+    let frames = &trace["body"]["stackFrames"];
+    assert_eq!(frames[0]["name"], "plus");
+    assert_eq!(frames[0]["line"], 5);
+    assert_eq!(frames[0]["source"]["path"], json!(path));
+    assert_eq!(frames[1]["line"], 9);
+    let variables = session.request("variables", json!({"variablesReference": 3}));
+    let variables = &variables["body"]["variables"];
+    assert_eq!(variables[2]["name"], "total");
+    assert_eq!(variables[2]["value"], "3");
+    let result = session.request("evaluate", json!({"expression": "total + 1", "frameId": 1}));
+    assert_eq!(result["body"]["result"], "4");
+    let error = session.request("evaluate", json!({"expression": "nope(", "frameId": 1}));
+    assert_eq!(error["success"], false);
+
+    session.request("continue", json!({"threadId": 1}));
+    let exited = session.wait_for("exited");
+    assert_eq!(exited["body"]["exitCode"], 0);
+    session.wait_for("terminated");
+    let output: Vec<&Json> = session
+        .events
+        .iter()
+        .filter(|event| event["event"] == "output")
+        .collect();
+    assert_eq!(output[0]["body"]["output"], "3\n");
+    session.request("disconnect", json!({}));
+    assert!(session.child.wait().unwrap().success());
+}
@@ -0,0 +1,246 @@
+//! Built-in decorators: caching, instrumentation and timing.
+
+mod common;
+
+use common::scratch_dir;
+use j_lang::error::ErrorKind;
+use j_lang::interpreter::ManualClock;
+use j_lang::{run_source_to_string, Interpreter};
+use std::rc::Rc;
+
+fn run(source: &str) -> String {
+    run_source_to_string(source).unwrap()
+}
+
+#[test]
+fn memo_makes_recursive_fib_linear() {
+    let out = run(r#"@memo
+fn | fib ( int | n ) > {
+  if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+}
+out(fib(90))
+dict: info = cache_info(fib)
+out(info["misses"])"#);
+    assert_eq!(out.trim(), "2880067194370816120\n91");
+}
+
+#[test]
+fn memo_with_max_size_evicts_least_recently_used() {
+    let out = run(r#"@memo(2)
+fn | square ( int | n ) > { n * n }
+square(1)
+square(2)
+square(1)
+square(3)
+square(2)
+dict: info = cache_info(square)
+out(info["size"])
+out(info["hits"])
+out(info["misses"])
+cache_clear(square)
+dict: cleared = cache_info(square)
+out(cleared["size"])"#);
+    // square(3) evicts 2 (1 was used more recently), so square(2) misses again
+    assert_eq!(out.trim(), "2\n1\n4\n0");
+}
+
+#[test]
+fn memo_keys_tell_nested_values_apart() {
+    let out = run(r#"@memo
+fn | first ( list | xs ) > { xs[0] + xs[0] }
+out(first([1]))
+out(first(["1"]))
+@memo
+fn | doubled ( dict | d ) > { d["k"][0] + d["k"][0] }
+out(doubled({"k": [2]}))
+out(doubled({"k": ["2"]}))
+out(doubled({"k": [2]}))
+dict: info = cache_info(doubled)
+out(info["hits"])"#);
+    assert_eq!(out.trim(), "2\n11\n4\n22\n4\n1");
+}
+
+#[test]
+fn memoize_builtin_wraps_a_function() {
+    let out = run(r#"fn | plus_one ( int | n ) > { n + 1 }
+any: cached = memoize(plus_one)
+out(cached(1))
+out(cached(1))
+dict: info = cache_info(cached)
+out(info["hits"])"#);
+    assert_eq!(out.trim(), "2\n2\n1");
+}
+
+#[test]
+fn trace_prints_indented_entry_and_exit() {
+    let out = run(r#"@trace
+fn | fact ( int | n ) > {
+  if n < 2 { 1 } else { n * fact(n - 1) }
+}
+fact(3)"#);
+    assert_eq!(
+        out.trim(),
+        "→ fact(3)\n  → fact(2)\n    → fact(1)\n    ← fact(1) = 1\n  ← fact(2) = 2\n← fact(3) = 6"
+    );
+}
+
+#[test]
+fn log_call_and_timer_write_through_output_capture() {
+    let out = run(r#"@log_call
+fn | tag ( str | who, int | n ) > { len(who) + n }
+tag("ab", 2)
+@timer
+fn | idle ( ) > { 0 }
+idle()"#);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(
+        lines[0].starts_with(r#"call fn=tag args=["ab", 2] result=4 duration_ms="#),
+        "{}",
+        out
+    );
+    assert!(lines[1].starts_with("⏱ idle took ") && lines[1].ends_with(" ms"), "{}", out);
+}
+
+#[test]
+fn profile_counts_every_call() {
+    let out = run(r#"@profile
+fn | fibp ( int | n ) > {
+  if n < 2 { n } else { fibp(n - 1) + fibp(n - 2) }
+}
+fibp(10)
+fibp(1)
+dict: report = profile_report()
+dict: stats = report["fibp"]
+out(stats["calls"])
+out(stats["total_ms"] >= 0.0)"#);
+    assert_eq!(out.trim(), "178\ntrue");
+}
+
+/// Run with a virtual clock: `sleep()` advances it instantly.
+fn run_with_manual_clock(source: &str) -> String {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_clock(Rc::new(ManualClock::new()));
+    interpreter.set_output_capture(true);
+    interpreter.run(source).unwrap();
+    interpreter.take_captured_output().unwrap_or_default()
+}
+
+#[test]
+fn throttle_drops_calls_within_the_interval() {
+    let out = run_with_manual_clock(r#"@throttle(10)
+fn | ping ( int | n ) > { out(n) n * 10 }
+out(ping(1))
+out(ping(2))
+sleep(9)
+out(ping(3))
+sleep(1)
+out(ping(4))"#);
+    assert_eq!(out.trim(), "1\n10\n10\n10\n4\n40");
+}
+
+#[test]
+fn debounce_runs_only_the_last_call_of_a_burst() {
+    let out = run_with_manual_clock(r#"@debounce(60)
+fn | save ( int | n ) > { out(n) }
+save(1)
+save(2)
+sleep(30)
+save(3)
+sleep(59)
+out("quiet")
+sleep(1)
+save(4)
+out("end")"#);
+    // 4 is still pending when the program ends; the timer queue drains it
+    assert_eq!(out.trim(), "quiet\n3\nend\n4");
+}
+
+#[test]
+fn intervals_that_do_not_fit_a_duration_are_rejected() {
+    for decorator in ["throttle", "debounce"] {
+        let source = format!(
+            "float: big = 100000000000000000000.0\n@{}(big)\nfn | ping ( ) > {{ 1 }}\nping()",
+            decorator
+        );
+        let err = run_source_to_string(&source).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidArgument, "{}", err);
+        assert!(err.message.contains(&format!("@{}", decorator)), "{}", err);
+    }
+    // Just under the limit is accepted; the deadline saturates instead of overflowing
+    let out = run_with_manual_clock(
+        "@throttle(18000000000000000000.0)
+fn | ping ( int | n ) > { n }
+out(ping(1))
+sleep(5)
+out(ping(2))",
+    );
+    assert_eq!(out.trim(), "1\n1");
+}
+
+#[test]
+fn deprecated_warns_but_runs_unless_denied() {
+    let source = r#"@deprecated("use area2")
+fn | area ( int | w, int | h ) > { w * h }
+out(area(2, 3))"#;
+    assert_eq!(run(source).trim(), "6");
+
+    let mut interpreter = Interpreter::new();
+    interpreter.set_deny_deprecated(true);
+    interpreter.set_output_capture(true);
+    let err = interpreter.run(source).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Deprecated);
+    assert!(err.message.contains("'area' is deprecated: use area2"), "{}", err);
+}
+
+#[test]
+fn deprecated_warns_once_per_call_site() {
+    let source = r#"@deprecated("use area2")
+fn | area ( int | w, int | h ) > { w * h }
+for i in [1, 2, 3] {
+  area(i, i)
+}
+out(area(2, 3))"#;
+    let dir = scratch_dir("deprecated", &[("main.jdl", source)]);
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_jade"))
+        .args(["run", "main.jdl"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    let warnings: Vec<_> = stderr.lines().filter(|l| l.contains("deprecated")).collect();
+    assert_eq!(warnings.len(), 2, "{}", stderr);
+    assert!(warnings[0].ends_with("(called at main.jdl:4:3)"), "{}", stderr);
+    assert!(warnings[1].ends_with("(called at main.jdl:6:5)"), "{}", stderr);
+}
+
+#[test]
+fn validate_checks_declared_parameter_types() {
+    let ok = run(r#"@validate
+fn | scale ( int | n, float | f ) > { n * f }
+out(scale(2, 1.5))
+out(scale(2, 3))"#);
+    assert_eq!(ok.trim(), "3\n6");
+
+    let err = run_source_to_string(
+        r#"@validate
+fn | scale ( int | n ) > { n }
+scale("x")"#,
+    )
+    .unwrap_err();
+    assert_eq!(err.kind, ErrorKind::TypeError);
+    assert!(err.message.contains("Argument 'n' of 'scale' expected int, got str"), "{}", err);
+}
+
+#[test]
+fn validate_with_a_validator_function() {
+    let err = run_source_to_string(
+        r#"fn | positive ( int | n ) > { if n > 0 { true } else { "n must be positive" } }
+@validate(positive)
+fn | root ( int | n ) > { n }
+out(root(4))
+root(-1)"#,
+    )
+    .unwrap_err();
+    assert!(err.message.contains("failed validation: n must be positive"), "{}", err);
+}
@@ -0,0 +1,75 @@
+//! `jade check`: reporting every lexical and syntax error in a file, as text or JSON.
+
+mod common;
+
+use common::scratch_dir;
+use j_lang::diagnostics::check_source;
+use j_lang::error::ErrorKind;
+use std::process::Command;
+
+const BROKEN: &str =
+    "int: y = (4 +\nout(y)\nstr: s = \"abc\nfn | f ( int | a ) > { a + }\nout(1)\n";
+
+#[test]
+fn parser_recovers_after_each_error() {
+    let errors = check_source(BROKEN, Some("broken.jdl"));
+    let found: Vec<_> = errors
+        .iter()
+        .map(|e| (e.kind.clone(), e.line, e.column))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (ErrorKind::UnexpectedToken, Some(1), Some(14)),
+            (ErrorKind::UnterminatedString, Some(3), Some(10)),
+            (ErrorKind::UnexpectedToken, Some(4), Some(28)),
+        ]
+    );
+    assert!(errors
+        .iter()
+        .all(|e| e.file.as_deref() == Some("broken.jdl")));
+    assert_eq!(
+        (errors[1].end_line, errors[1].end_column),
+        (Some(3), Some(14))
+    );
+    assert_eq!(
+        errors[2].source_line.as_deref(),
+        Some("fn | f ( int | a ) > { a + }")
+    );
+}
+
+#[test]
+fn valid_source_has_no_diagnostics() {
+    let source = "fn | f ( int | a ) > { a + 1 }\nout(f(2))\n";
+    assert!(check_source(source, None).is_empty());
+}
+
+#[test]
+fn check_prints_json_diagnostics() {
+    let dir = scratch_dir("check", &[("broken.jdl", BROKEN)]);
+    let file = dir.join("broken.jdl");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_jade"))
+        .args(["check", "--format", "json"])
+        .arg(&file)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let diagnostics = report.as_array().unwrap();
+    assert_eq!(diagnostics.len(), 3);
+    let first = &diagnostics[0];
+    assert_eq!(first["severity"], "error");
+    assert_eq!(first["code"], "J0201");
+    assert_eq!(first["kind"], "UnexpectedToken");
+    assert_eq!(first["file"], file.to_string_lossy().as_ref());
+    assert_eq!(
+        (first["line"].as_u64(), first["column"].as_u64()),
+        (Some(1), Some(14))
+    );
+    assert_eq!(
+        (first["end_line"].as_u64(), first["end_column"].as_u64()),
+        (Some(1), Some(15))
+    );
+    assert!(first["tip"].is_string() && first["solution"].is_string());
+}
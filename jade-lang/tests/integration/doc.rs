@@ -0,0 +1,200 @@
+//! `jade doc`: doc comments attached to declarations by the lexer and parser, the signatures
+//! and members listed for them, and the HTML and Markdown pages written for a project.
+
+mod common;
+
+use common::scratch_dir;
+use j_lang::doc::{document_source, Item, ItemKind};
+use j_lang::lexer::{Lexer, TokenType};
+use j_lang::parser::{AstNode, Parser};
+use std::fs;
+use std::process::Command;
+
+const SOURCE: &str = "## A point on the plane.
+## Coordinates are `int`.
+class | Point {
+  ## Horizontal position.
+  int | x -> 0
+  int | y -> 0
+
+  /// Distance from the origin, squared.
+  fn int | norm ( ) > {
+    return this.x * this.x + this.y * this.y
+  }
+}
+
+## Detached from what follows by a blank line.
+
+enum | Color : int {
+  Red = 1
+  Green
+}
+
+### A ruler, not a doc comment
+fn | _helper ( ) > { return 1 }
+
+## Area of a rectangle.
+@memo
+fn int | area ( int | w, int | h ) > {
+  int: a = w * h ## not a doc comment either
+  return a
+}
+
+## Math helpers.
+module | mathx {
+  ## Twice `n`.
+  fn int | twice ( int | n ) > { return n * 2 }
+}
+";
+
+fn summary(item: &Item) -> (ItemKind, &str, &str, Option<&str>) {
+    (
+        item.kind,
+        item.name.as_str(),
+        item.signature.as_str(),
+        item.doc.as_deref(),
+    )
+}
+
+#[test]
+fn doc_comments_attach_to_the_declaration_below() {
+    let tokens = Lexer::new(SOURCE).tokenize().unwrap();
+    let AstNode::Block(statements) = Parser::new(tokens).parse().unwrap() else {
+        panic!("expected a block");
+    };
+    let docs: Vec<Option<&str>> = statements.iter().map(AstNode::doc).collect();
+    assert_eq!(
+        docs,
+        vec![
+            Some("A point on the plane.\nCoordinates are `int`."),
+            None,
+            None,
+            Some("Area of a rectangle."),
+            Some("Math helpers."),
+        ]
+    );
+    // The comment itself is not a token; the text rides on the first token after it.
+    let tokens = Lexer::new("## doc\nfn | f ( ) > { }\n").tokenize().unwrap();
+    assert_eq!(tokens[0].token_type, TokenType::Newline);
+    assert_eq!(tokens[1].token_type, TokenType::Fn);
+    assert_eq!(tokens[1].doc.as_deref(), Some("doc"));
+}
+
+#[test]
+fn items_list_signatures_and_members() {
+    let items = document_source(SOURCE).unwrap();
+    let top: Vec<_> = items.iter().map(summary).collect();
+    assert_eq!(
+        top,
+        vec![
+            (
+                ItemKind::Class,
+                "Point",
+                "class | Point",
+                Some("A point on the plane.\nCoordinates are `int`.")
+            ),
+            (ItemKind::Enum, "Color", "enum | Color : int", None),
+            (
+                ItemKind::Function,
+                "area",
+                "@memo\nfn int | area ( int | w, int | h )",
+                Some("Area of a rectangle.")
+            ),
+            (
+                ItemKind::Module,
+                "mathx",
+                "module | mathx",
+                Some("Math helpers.")
+            ),
+        ]
+    );
+
+    let point: Vec<_> = items[0].members.iter().map(summary).collect();
+    assert_eq!(
+        point,
+        vec![
+            (
+                ItemKind::Field,
+                "x",
+                "int | x -> 0",
+                Some("Horizontal position.")
+            ),
+            (ItemKind::Field, "y", "int | y -> 0", None),
+            (
+                ItemKind::Method,
+                "norm",
+                "fn int | norm ( )",
+                Some("Distance from the origin, squared.")
+            ),
+        ]
+    );
+    let variants: Vec<&str> = items[1]
+        .members
+        .iter()
+        .map(|v| v.signature.as_str())
+        .collect();
+    assert_eq!(variants, vec!["Red = 1", "Green"]);
+    assert_eq!(
+        summary(&items[3].members[0]),
+        (
+            ItemKind::Function,
+            "twice",
+            "fn int | twice ( int | n )",
+            Some("Twice `n`.")
+        )
+    );
+}
+
+#[test]
+fn writes_html_and_markdown_pages_for_a_package() {
+    let dir = scratch_dir(
+        "doc",
+        &[
+            (
+                "jade.toml",
+                "name = \"geometry\"\nversion = \"0.2.0\"\ndescription = \"Shapes & areas\"\n\
+                 dependencies = {}\ndev_dependencies = {}\nscripts = {}\nkeywords = []\nfiles = []\n",
+            ),
+            ("lib/shapes.jdl", SOURCE),
+            ("main.jdl", "out(\"no declarations\")\n"),
+        ],
+    );
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_jade"))
+            .current_dir(&dir)
+            .arg("doc")
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&[]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Documented 1 module(s)"));
+    let index = fs::read_to_string(dir.join("docs/api/index.html")).unwrap();
+    assert!(index.contains("<h1>geometry 0.2.0</h1>"));
+    assert!(index.contains("<p class=\"lead\">Shapes &amp; areas</p>"));
+    assert!(index.contains("<a href=\"lib.shapes.html#area\"><code>fn area</code></a>"));
+    let page = fs::read_to_string(dir.join("docs/api/lib.shapes.html")).unwrap();
+    assert!(page.contains("<link rel=\"stylesheet\" href=\"styles.css\" />"));
+    assert!(page.contains("<h3 id=\"Point.norm\">fn norm</h3>"));
+    assert!(page.contains("<p>Twice <code>n</code>.</p>"));
+    assert!(!page.contains("_helper"));
+    assert!(dir.join("docs/api/styles.css").exists());
+
+    let output = run(&["--format", "markdown", "--out", "md"]);
+    assert_eq!(output.status.code(), Some(0));
+    let page = fs::read_to_string(dir.join("md/lib.shapes.md")).unwrap();
+    assert!(page.starts_with("# lib.shapes\n\n`lib/shapes.jdl`\n"));
+    assert!(page.contains("## fn area\n\n```jade\n@memo\nfn int | area ( int | w, int | h )\n```\n\nArea of a rectangle.\n"));
+    assert!(page.contains("- `Red = 1`\n- `Green`\n"));
+
+    fs::write(dir.join("broken.jdl"), "fn | f ( > {\n").unwrap();
+    let output = run(&["--out", "partial"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("broken.jdl"), "{}", stderr);
+    assert!(stderr.contains("no documentation written"), "{}", stderr);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Documented"));
+    assert!(!dir.join("partial").exists());
+}
@@ -0,0 +1,150 @@
+//! Runtime errors point at the expression that raised them, in the program or in a module.
+
+mod common;
+
+use common::scratch_dir;
+use j_lang::bytecode;
+use j_lang::error::ErrorKind;
+use j_lang::interpreter::parse_source;
+use j_lang::Interpreter;
+
+/// Module whose `ratio` divides by zero on line 2 when `b` is 0.
+const RATIO: &str = "fn | ratio ( int | a, int | b ) > {\n  a / b\n}\n";
+
+fn run_error(interpreter: &mut Interpreter, source: &str) -> j_lang::JError {
+    let e = interpreter.run(source).unwrap_err();
+    interpreter.describe_error(&e, source)
+}
+
+#[test]
+fn undefined_variable_points_at_identifier() {
+    let source = "int: total = 4\nout(total + missing)\n";
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output_capture(true);
+    let error = run_error(&mut interpreter, source);
+    assert_eq!(error.kind, ErrorKind::UndefinedVariable);
+    assert_eq!((error.line, error.column), (Some(2), Some(13)));
+    assert_eq!(error.source_line.as_deref(), Some("out(total + missing)"));
+    assert_eq!(error.file, None);
+    assert!(error.to_string().contains("(line 2, col 13)"));
+}
+
+#[test]
+fn error_inside_module_points_into_its_file() {
+    let dir = scratch_dir("spans", &[("lib/util.jdl", RATIO)]);
+    let module = dir.join("lib/util.jdl");
+
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output_capture(true);
+    interpreter.add_module_search_path(dir.to_string_lossy().to_string());
+    let error = run_error(&mut interpreter, "import lib.util\nout(ratio(1, 0))\n");
+    assert_eq!(error.kind, ErrorKind::DivisionByZero);
+    assert_eq!(
+        error.file.as_deref(),
+        Some(module.to_string_lossy().as_ref())
+    );
+    assert_eq!((error.line, error.column), (Some(2), Some(5)));
+    assert_eq!(error.source_line.as_deref(), Some("  a / b"));
+}
+
+#[test]
+fn caught_errors_do_not_keep_their_location() {
+    let source = "list: xs = [1, 2]\ntry { xs[5] } catch e { out(e) }\nout(xs[5])\n";
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output_capture(true);
+    let error = run_error(&mut interpreter, source);
+    assert_eq!(error.kind, ErrorKind::IndexOutOfBounds);
+    assert_eq!((error.line, error.column), (Some(3), Some(7)));
+    assert_eq!(
+        interpreter.take_captured_output().as_deref(),
+        Some("Index 5 is out of bounds for list of length 2\n")
+    );
+}
+
+#[test]
+fn bytecode_errors_are_located() {
+    let source = "int: n = 0\nout(10 / n)\n";
+    let program = bytecode::compile(&parse_source(source).unwrap()).unwrap();
+    let program = bytecode::Program::deserialize(&program.serialize()).unwrap();
+    let mut interpreter = Interpreter::new();
+    let e = bytecode::run_program(&program, &mut interpreter).unwrap_err();
+    let error = interpreter.describe_error(&e, source);
+    assert_eq!(error.kind, ErrorKind::DivisionByZero);
+    assert_eq!((error.line, error.column), (Some(2), Some(8)));
+}
+
+#[test]
+fn traceback_follows_calls_into_modules() {
+    let dir = scratch_dir("trace", &[("lib/util.jdl", RATIO)]);
+    let module = dir.join("lib/util.jdl").to_string_lossy().to_string();
+
+    let source = "import lib.util\nfn | report ( int | n ) > { out(ratio(1, n)) }\nreport(0)\n";
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output_capture(true);
+    interpreter.add_module_search_path(dir.to_string_lossy().to_string());
+    let error = run_error(&mut interpreter, source);
+    let trace: Vec<_> = error
+        .trace
+        .iter()
+        .map(|e| (e.function.as_str(), e.file.as_deref(), e.line))
+        .collect();
+    assert_eq!(
+        trace,
+        [
+            ("<module>", None, 3),
+            ("report", None, 2),
+            ("ratio", Some(module.as_str()), 2)
+        ]
+    );
+    assert_eq!(error.trace[2].source_line.as_deref(), Some("  a / b"));
+    let report = error.to_string();
+    assert!(report.contains("Traceback (most recent call last):\n  File \"<main>\", line 3, in <module>\n    report(0)\n"), "{}", report);
+}
+
+#[test]
+fn traceback_names_the_program_file() {
+    let source = "fn | ratio ( int | a, int | b ) > { a / b }\nout(ratio(1, 0))\n";
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output_capture(true);
+    interpreter.set_program_path("main.jdl");
+    let error = run_error(&mut interpreter, source);
+    let report = error.to_string();
+    assert!(report.contains("  File \"main.jdl\", line 2, in <module>\n    out(ratio(1, 0))\n  File \"main.jdl\", line 1, in ratio\n"), "{}", report);
+}
+
+#[test]
+fn caught_errors_expose_the_trace() {
+    let source = "fn | pick ( list | xs ) > { xs[9] }\ntry { pick([]) } catch e {\n  for frame in e.trace { out(frame[\"function\"], frame[\"line\"]) }\n}\n";
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output_capture(true);
+    interpreter.run(source).unwrap();
+    assert_eq!(
+        interpreter.take_captured_output().as_deref(),
+        Some("<module> 2\npick 1\n")
+    );
+}
+
+#[test]
+fn bytecode_errors_have_a_traceback() {
+    let source = "fn | g ( int | n ) > { 10 / n }\nfn | h ( int | n ) > { g(n) }\nout(h(0))\n";
+    let program = bytecode::compile(&parse_source(source).unwrap()).unwrap();
+    let mut interpreter = Interpreter::new();
+    let error = bytecode::run_program(&program, &mut interpreter).unwrap_err();
+    let trace: Vec<_> = error
+        .trace
+        .iter()
+        .map(|e| (e.function.as_str(), e.line))
+        .collect();
+    assert_eq!(trace, [("<module>", 3), ("h", 2), ("g", 1)]);
+}
+
+#[test]
+fn spans_take_part_in_ast_equality() {
+    let parse = |source: &str| {
+        let tokens = j_lang::Lexer::new(source).tokenize().unwrap();
+        j_lang::Parser::without_spans(tokens).parse().unwrap()
+    };
+    let (tight, spaced) = ("out(1+x)\n", "\n\nout( 1 + x )\n");
+    assert_ne!(parse_source(tight).unwrap(), parse_source(spaced).unwrap());
+    assert_eq!(parse(tight), parse(spaced));
+}
@@ -0,0 +1,117 @@
+//! `jade fmt`: canonical layout that keeps comments, blank lines, literal spellings and the
+//! program itself.
+
+mod common;
+
+use common::scratch_dir;
+use j_lang::formatter::format_source;
+use j_lang::lexer::Lexer;
+use j_lang::parser::{AstNode, Parser};
+use std::fs;
+use std::process::Command;
+
+const MESSY: &str = "# header
+int:   x=3   # trailing
+list: xs=[1,2,  3]
+
+
+fn int | add(int|a,int|b)>{
+    # inside
+    return a+b  // sum
+}
+for i in 0..3 { out(i) }
+unless x > 2 { out(\"small\") } else { out(\"big\") }
+";
+
+const FORMATTED: &str = "# header
+int: x = 3 # trailing
+list: xs = [1, 2, 3]
+
+fn int | add ( int | a, int | b ) > {
+  # inside
+  return a + b // sum
+}
+for i in 0..3 {
+  out(i)
+}
+unless x > 2 {
+  out(\"small\")
+} else {
+  out(\"big\")
+}
+";
+
+fn parse(source: &str) -> AstNode {
+    let tokens = Lexer::new(source).tokenize().unwrap();
+    Parser::new(tokens).parse().unwrap()
+}
+
+#[test]
+fn formats_to_the_canonical_layout() {
+    assert_eq!(format_source(MESSY).unwrap(), FORMATTED);
+    assert_eq!(format_source(FORMATTED).unwrap(), FORMATTED);
+    assert_eq!(format_source("").unwrap(), "");
+}
+
+#[test]
+fn keeps_literal_spellings() {
+    let source = "out(1.50, \"tab\\there\", \"{x} and $x\")\n";
+    assert_eq!(format_source(source).unwrap(), source);
+}
+
+#[test]
+fn fixtures_keep_their_meaning_and_are_stable() {
+    let mut fixtures: Vec<_> = fs::read_dir("tests/fixtures")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jdl"))
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty());
+    for path in fixtures {
+        let source = fs::read_to_string(&path).unwrap();
+        let formatted = format_source(&source).unwrap();
+        assert!(parse(&source) == parse(&formatted), "{}", path.display());
+        assert_eq!(
+            format_source(&formatted).unwrap(),
+            formatted,
+            "{}",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn rejects_syntax_errors() {
+    assert!(format_source("int: y = (4 +\n").is_err());
+}
+
+#[test]
+fn check_reports_without_writing() {
+    let dir = scratch_dir("fmt", &[("messy.jdl", MESSY), ("tidy.jdl", FORMATTED)]);
+    let messy = dir.join("messy.jdl");
+
+    let check = |path: &std::path::Path| {
+        Command::new(env!("CARGO_BIN_EXE_jade"))
+            .args(["fmt", "--check"])
+            .arg(path)
+            .output()
+            .unwrap()
+    };
+    let output = check(&dir);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("messy.jdl") && !stdout.contains("tidy.jdl"));
+    assert_eq!(fs::read_to_string(&messy).unwrap(), MESSY);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_jade"))
+        .arg("fmt")
+        .arg(dir.as_os_str())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1 of 2 file(s) reformatted"), "{}", stdout);
+    assert_eq!(fs::read_to_string(&messy).unwrap(), FORMATTED);
+    assert_eq!(check(&dir).status.code(), Some(0));
+}
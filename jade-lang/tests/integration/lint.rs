@@ -0,0 +1,201 @@
+//! `jade lint`: static checks found without running the program, their levels from the
+//! manifest's `[lint]` table, and the command's exit status and JSON output.
+
+mod common;
+
+use common::scratch_dir;
+use j_lang::jolt::{JoltManager, JoltManifest};
+use j_lang::lexer::Lexer;
+use j_lang::lint::{lint_source, Lint, LintConfig, LintLevel, Rule};
+use j_lang::parser::{AstNode, Parser};
+use std::collections::HashMap;
+use std::process::Command;
+
+const SOURCE: &str = "!int: limit = 10
+
+fn int | add ( int | a, int | b ) > {
+  int: unused = 3
+  int: total = a + b
+  if total > 3 {
+    int: total = 1
+    out(total)
+  }
+  return total
+  out(\"after\")
+}
+
+fn | bump ( ) > {
+  limit = limit + 1
+  int: _ignored = 0
+  out(lenght([1]))
+}
+out(add(1, 2))
+bump()
+";
+
+fn lint(source: &str) -> Vec<Lint> {
+    lint_source(source, None, &LintConfig::default()).unwrap()
+}
+
+fn found(lints: &[Lint]) -> Vec<(Rule, usize)> {
+    lints
+        .iter()
+        .map(|lint| (lint.rule, lint.line.unwrap()))
+        .collect()
+}
+
+#[test]
+fn reports_each_rule_where_it_happens() {
+    let lints = lint(SOURCE);
+    assert_eq!(
+        found(&lints),
+        vec![
+            (Rule::UnusedVariable, 4),
+            (Rule::ShadowedVariable, 7),
+            (Rule::UnreachableCode, 11),
+            (Rule::ImmutableAssignment, 15),
+            (Rule::UnknownFunction, 17),
+        ]
+    );
+    assert_eq!(
+        lints[1].message,
+        "'total' shadows the variable declared on line 5"
+    );
+    assert_eq!(lints[4].similar_names, vec!["len".to_string()]);
+    assert_eq!(lints[3].level, LintLevel::Deny);
+    assert_eq!(lints[0].level, LintLevel::Warn);
+}
+
+#[test]
+fn reports_imports_whose_names_are_never_used() {
+    let dir = scratch_dir(
+        "lint-imports",
+        &[
+            ("shapes.jdl", "fn int | area ( int | w ) > { return w * w }\n"),
+            ("colors.jdl", "str: red = \"#f00\"\n"),
+        ],
+    );
+    let main = dir.join("main.jdl");
+    let source = "import shapes\nimport colors\nout(area(3))\n";
+
+    let lints = lint_source(source, Some(&main), &LintConfig::default()).unwrap();
+    assert_eq!(found(&lints), vec![(Rule::UnusedImport, 2)]);
+    assert_eq!(lints[0].message, "Nothing imported from 'colors' is used");
+
+    // Names from an unresolved module are unknown, so calls are not reported.
+    let lints = lint_source(
+        "import missing\nout(area(3))\n",
+        Some(&main),
+        &LintConfig::default(),
+    );
+    assert!(lints.unwrap().is_empty());
+}
+
+#[test]
+fn globals_and_underscored_locals_are_not_unused() {
+    let source = "int: exported = 1
+fn | f ( int | _skip ) > {
+  for _ in 0..2 { out(\"x\") }
+  try { out(1) } catch e { out(e) }
+}
+f(1)
+";
+    assert!(lint(source).is_empty());
+}
+
+#[test]
+fn unknown_functions_are_compared_with_callable_names_only() {
+    let source = "fn | prin ( int | n, int | n2 ) > { out(n + n2) }
+int: prn = 1
+prnt(prn)
+prin(1, 2)
+";
+    let lints = lint(source);
+    assert_eq!(found(&lints), vec![(Rule::UnknownFunction, 3)]);
+    let similar = &lints[0].similar_names;
+    assert!(similar.contains(&"prin".to_string()), "{:?}", similar);
+    assert!(
+        !similar.iter().any(|name| ["n", "n2", "prn"].contains(&name.as_str())),
+        "{:?}",
+        similar
+    );
+}
+
+#[test]
+fn manifest_levels_override_the_defaults() {
+    let manifest: JoltManifest = toml::from_str(
+        "name = \"demo\"
+version = \"0.1.0\"
+dependencies = {}
+dev_dependencies = {}
+scripts = {}
+keywords = []
+files = []
+
+[lint]
+unused-variable = \"allow\"
+unknown-function = \"warn\"
+",
+    )
+    .unwrap();
+    let config = LintConfig::from_table(&manifest.lint).unwrap();
+    assert_eq!(config.level(Rule::UnusedVariable), LintLevel::Allow);
+    assert_eq!(config.level(Rule::UnknownFunction), LintLevel::Warn);
+    assert_eq!(config.level(Rule::ImmutableAssignment), LintLevel::Deny);
+
+    let lints = lint_source(SOURCE, None, &config).unwrap();
+    assert!(!lints.iter().any(|lint| lint.rule == Rule::UnusedVariable));
+    let unknown = lints.iter().find(|lint| lint.rule == Rule::UnknownFunction);
+    assert_eq!(unknown.unwrap().level, LintLevel::Warn);
+
+    let table = HashMap::from([("unused-var".to_string(), LintLevel::Allow)]);
+    let error = LintConfig::from_table(&table).unwrap_err();
+    assert!(error.contains("Unknown lint rule 'unused-var'"));
+}
+
+#[test]
+fn bang_before_a_type_declares_an_immutable_variable() {
+    let tokens = Lexer::new("!int: x = 3\n").tokenize().unwrap();
+    let AstNode::Block(statements) = Parser::new(tokens).parse().unwrap() else {
+        panic!("expected a block");
+    };
+    assert!(matches!(
+        &statements[0],
+        AstNode::VarDeclaration { name, immutable: true, .. } if name == "x"
+    ));
+}
+
+#[test]
+fn exit_status_follows_the_configured_levels() {
+    let dir = scratch_dir("lint-cli", &[("main.jdl", SOURCE)]);
+    let run = |format: &str| {
+        Command::new(env!("CARGO_BIN_EXE_jade"))
+            .current_dir(&dir)
+            .args(["lint", "--format", format, "main.jdl"])
+            .output()
+            .unwrap()
+    };
+
+    let output = run("json");
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let findings = json.as_array().unwrap();
+    assert_eq!(findings.len(), 5);
+    assert_eq!(findings[3]["code"], "immutable-assignment");
+    assert_eq!(findings[3]["severity"], "error");
+    assert_eq!(findings[3]["line"], 15);
+
+    let manifest = JoltManifest {
+        lint: HashMap::from([
+            ("immutable-assignment".to_string(), LintLevel::Warn),
+            ("unknown-function".to_string(), LintLevel::Allow),
+        ]),
+        ..JoltManifest::default()
+    };
+    JoltManager::write_manifest(&dir.join("jade.toml"), &manifest).unwrap();
+    let output = run("text");
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("LINT WARNING[immutable-assignment]"));
+    assert!(!stderr.contains("unknown-function"));
+}
@@ -0,0 +1,333 @@
+//! `jade lsp`: diagnostics, document symbols, go-to-definition, hover, completion and
+//! formatting over JSON-RPC.
+
+use j_lang::interpreter::{BUILTIN_METHODS, BUILTIN_NAMES};
+use j_lang::lsp::{read_message, Server};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{BufReader, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+const URI: &str = "file:///project/main.jdl";
+
+const SOURCE: &str = "class | Point {
+  fn | norm ( ) > { 1 }
+}
+fn int | add ( int | a, int | b ) > {
+  int: total = a + b
+  return total
+}
+list: xs = [1, 2]
+out(add(1, 2))
+";
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn at(line: u64, character: u64) -> Value {
+    json!({ "textDocument": { "uri": URI }, "position": { "line": line, "character": character } })
+}
+
+fn open(server: &mut Server, text: &str) -> Vec<Value> {
+    server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": URI, "languageId": "jade", "version": 1, "text": text } },
+    }))
+}
+
+fn result(server: &mut Server, message: Value) -> Value {
+    server.handle(&message).remove(0)["result"].take()
+}
+
+#[test]
+fn publishes_diagnostics_with_codes_and_ranges() {
+    let mut server = Server::new();
+    let published = open(&mut server, "out(1)\nint: y = (4 + ])\n");
+    let params = &published[0]["params"];
+    assert_eq!(params["uri"], URI);
+    let diagnostic = &params["diagnostics"][0];
+    assert_eq!(diagnostic["code"], "J0201");
+    assert_eq!(diagnostic["source"], "jade");
+    assert_eq!(
+        diagnostic["range"],
+        json!({ "start": { "line": 1, "character": 14 }, "end": { "line": 1, "character": 15 } })
+    );
+
+    let fixed = open(&mut server, SOURCE);
+    assert_eq!(fixed[0]["params"]["diagnostics"], json!([]));
+}
+
+#[test]
+fn answers_symbol_definition_hover_and_completion_requests() {
+    let mut server = Server::new();
+    open(&mut server, SOURCE);
+
+    let symbols = result(
+        &mut server,
+        request(
+            1,
+            "textDocument/documentSymbol",
+            json!({ "textDocument": { "uri": URI } }),
+        ),
+    );
+    let names: Vec<_> = symbols
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| (s["name"].as_str().unwrap(), s["kind"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(names, [("Point", 5), ("add", 12)]);
+    assert_eq!(symbols[0]["children"][0]["name"], "norm");
+
+    // `total` in `return total` goes to its declaration
+    let definition = result(
+        &mut server,
+        request(2, "textDocument/definition", at(5, 10)),
+    );
+    assert_eq!(definition["uri"], URI);
+    assert_eq!(
+        definition["range"]["start"],
+        json!({ "line": 4, "character": 7 })
+    );
+
+    let hover = result(&mut server, request(3, "textDocument/hover", at(8, 5)));
+    let text = hover["contents"]["value"].as_str().unwrap();
+    assert!(
+        text.contains("fn int | add ( int | a, int | b )"),
+        "{}",
+        text
+    );
+    let builtin = result(&mut server, request(4, "textDocument/hover", at(8, 1)));
+    assert!(builtin["contents"]["value"]
+        .as_str()
+        .unwrap()
+        .contains("Builtin function"));
+
+    // Inside `add`: its locals and parameters, then builtins
+    let completions = result(&mut server, request(5, "textDocument/completion", at(5, 9)));
+    let labels: Vec<_> = completions
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["label"].as_str().unwrap())
+        .collect();
+    assert!(
+        labels.starts_with(&["total", "b", "a", "add", "Point"]),
+        "{:?}",
+        labels
+    );
+    assert!(labels.contains(&"len") && !labels.contains(&"xs"));
+
+    let unknown = server.handle(&request(6, "textDocument/rename", at(0, 0)));
+    assert_eq!(unknown[0]["error"]["code"], -32601);
+}
+
+#[test]
+fn completes_methods_after_a_dot() {
+    let mut server = Server::new();
+    open(&mut server, "list: xs = [1, 2]\nxs.ap\n");
+    let completions = result(&mut server, request(1, "textDocument/completion", at(1, 5)));
+    let labels: Vec<_> = completions
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["label"].as_str().unwrap())
+        .collect();
+    assert_eq!(labels, ["append"]);
+}
+
+#[test]
+fn builtin_table_is_sorted_for_dispatch() {
+    // Calls look names up by binary search, so an out-of-order entry would stop dispatching
+    for pair in BUILTIN_NAMES.windows(2) {
+        assert!(pair[0] < pair[1], "{:?}", pair);
+    }
+    for (kind, methods) in BUILTIN_METHODS {
+        assert!(methods.windows(2).all(|pair| pair[0] < pair[1]), "{}", kind);
+    }
+}
+
+/// String literals in the patterns of the match arms in `block`, which starts at the match's
+/// `{`. Only patterns directly in the match count, not strings in arm bodies or nested matches.
+fn arm_patterns(block: &str) -> Vec<String> {
+    let chars: Vec<char> = block.chars().collect();
+    let mut names = Vec::new();
+    let mut depth = 0usize;
+    let mut in_pattern = true;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '\'' if chars.get(i + 1) == Some(&'\\') => {
+                i += 3;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+            }
+            '\'' if chars.get(i + 2) == Some(&'\'') => i += 2,
+            '"' => {
+                let mut literal = String::new();
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    literal.push(chars[i]);
+                    i += 1;
+                }
+                if depth == 1 && in_pattern {
+                    names.push(literal);
+                }
+            }
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+                if depth == 1 && chars[i] == '}' {
+                    in_pattern = true;
+                }
+            }
+            ',' if depth == 1 => in_pattern = true,
+            '=' if depth == 1 && chars.get(i + 1) == Some(&'>') => in_pattern = false,
+            _ => {}
+        }
+        i += 1;
+    }
+    names
+}
+
+#[test]
+fn builtin_tables_match_the_dispatch_arms() {
+    let interpreter = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/interpreter");
+    let mut sources: Vec<_> = fs::read_dir(interpreter.join("builtins"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    sources.push(interpreter.join("call.rs"));
+
+    // Names handled by a `match name { .. }` in a dispatcher
+    let mut names = BTreeSet::new();
+    for path in &sources {
+        let source = fs::read_to_string(path).unwrap();
+        for (at, _) in source.match_indices("match name {") {
+            names.extend(arm_patterns(&source[at + "match name ".len()..]));
+        }
+    }
+    let table: BTreeSet<String> = BUILTIN_NAMES.iter().map(|name| name.to_string()).collect();
+    assert_eq!(
+        names.difference(&table).collect::<Vec<_>>(),
+        Vec::<&String>::new(),
+        "dispatched but missing from BUILTIN_NAMES"
+    );
+    assert_eq!(
+        table.difference(&names).collect::<Vec<_>>(),
+        Vec::<&String>::new(),
+        "in BUILTIN_NAMES but never dispatched"
+    );
+
+    // Methods handled by the `match method { .. }` of each `call_<kind>_method`
+    let call = fs::read_to_string(interpreter.join("call.rs")).unwrap();
+    let mut methods = BTreeMap::new();
+    for (at, _) in call.match_indices("fn call_") {
+        let rest = &call[at + "fn call_".len()..];
+        let Some(kind) = rest.split('(').next().and_then(|f| f.strip_suffix("_method")) else {
+            continue;
+        };
+        let body_end = rest.find("\n    fn ").unwrap_or(rest.len());
+        if let Some(at) = rest[..body_end].find("match method {") {
+            let handled: BTreeSet<_> = arm_patterns(&rest[at + "match method ".len()..])
+                .into_iter()
+                .collect();
+            methods.insert(kind.to_string(), handled);
+        }
+    }
+    let table: BTreeMap<_, _> = BUILTIN_METHODS
+        .iter()
+        .map(|(kind, list)| {
+            let list: BTreeSet<_> = list.iter().map(|method| method.to_string()).collect();
+            (kind.to_string(), list)
+        })
+        .collect();
+    assert_eq!(table, methods);
+}
+
+#[test]
+fn formats_the_whole_document() {
+    let mut server = Server::new();
+    open(&mut server, "int:   x=3\nout(x)");
+    let params = json!({ "textDocument": { "uri": URI }, "options": { "tabSize": 2 } });
+    let edits = result(&mut server, request(1, "textDocument/formatting", params.clone()));
+    assert_eq!(
+        edits,
+        json!([{
+            "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 1, "character": 6 } },
+            "newText": "int: x = 3\nout(x)\n",
+        }])
+    );
+
+    open(&mut server, "int: x = 3\nout(x)\n");
+    let unchanged = result(&mut server, request(2, "textDocument/formatting", params));
+    assert_eq!(unchanged, json!([]));
+}
+
+#[test]
+fn serves_a_session_over_stdio() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_jade"))
+        .arg("lsp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut input = Vec::new();
+    for message in [
+        request(1, "initialize", json!({})),
+        json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": URI, "languageId": "jade", "version": 1, "text": "out(\n" } },
+        }),
+        request(2, "shutdown", Value::Null),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+    ] {
+        let body = message.to_string();
+        write!(input, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+    }
+    child.stdin.take().unwrap().write_all(&input).unwrap();
+
+    let mut output = BufReader::new(child.stdout.take().unwrap());
+    let mut replies = Vec::new();
+    while let Some(body) = read_message(&mut output).unwrap() {
+        replies.push(serde_json::from_str::<Value>(&body).unwrap());
+    }
+    assert_eq!(child.wait().unwrap().code(), Some(0));
+
+    assert_eq!(replies.len(), 3);
+    assert_eq!(
+        replies[0]["result"]["capabilities"]["definitionProvider"],
+        true
+    );
+    assert_eq!(replies[1]["method"], "textDocument/publishDiagnostics");
+    assert_eq!(
+        replies[1]["params"]["diagnostics"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        replies[2],
+        json!({ "jsonrpc": "2.0", "id": 2, "result": null })
+    );
+}
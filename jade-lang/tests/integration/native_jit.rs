@@ -0,0 +1,87 @@
+//! Native code for hot numeric functions (`jit` feature).
+
+use j_lang::error::ErrorKind;
+use j_lang::{Interpreter, JResult};
+
+/// Run `source` with native code enabled after `threshold` calls; returns (output, native functions).
+fn run_native(source: &str, threshold: u64) -> JResult<(String, Vec<String>)> {
+    let mut interpreter = Interpreter::new();
+    interpreter.enable_native_jit(threshold)?;
+    interpreter.set_output_capture(true);
+    interpreter.run(source)?;
+    let output = interpreter.take_captured_output().unwrap_or_default();
+    Ok((output, interpreter.native_functions()))
+}
+
+#[test]
+fn hot_numeric_kernels_run_as_native_code() {
+    let source = r#"fn | fib ( int | n ) > {
+  if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+}
+fn | sum_squares ( int | n ) > {
+  int: total = 0
+  for i in 1..n { total = total + i * i }
+  total
+}
+fn | climb ( int | n ) > {
+  int: a = 1
+  int: b = 1
+  int: i = 2
+  while i <= n {
+    int: c = a + b
+    a = b
+    b = c
+    i = i + 1
+  }
+  b
+}
+fn | ratio ( float | total, int | count ) > { total / count }
+int: acc = 0
+for k in 0..<20 { acc = acc + sum_squares(k) + climb(k) }
+out(fib(20), acc, ratio(7.5, 3))"#;
+    let (output, native) = run_native(source, 5).unwrap();
+    assert_eq!(output, j_lang::run_source_to_string(source).unwrap());
+    assert_eq!(output.trim(), "6765 31010 2.5");
+    assert_eq!(native, ["climb", "fib", "sum_squares"]);
+}
+
+#[test]
+fn failed_type_guards_fall_back_to_the_interpreter() {
+    let source = r#"fn | twice ( int | x ) > { x + x }
+for k in 0..<10 { twice(k) }
+out(twice(21), twice(1.25), twice("ab"))"#;
+    let (output, native) = run_native(source, 3).unwrap();
+    assert_eq!(output.trim(), "42 2.5 abab");
+    assert_eq!(native, ["twice"]);
+}
+
+#[test]
+fn runtime_errors_are_reported_by_the_interpreter() {
+    let square = "fn | square ( int | x ) > { x * x }\nfor k in 0..<10 { square(k) }\n";
+    let err = run_native(&format!("{}square(5000000000)", square), 3).unwrap_err();
+    assert!(err.message.contains("Integer overflow"), "{}", err);
+
+    let deep = r#"fn | down ( int | n ) > { if n == 0 { 0 } else { 1 + down(n - 1) } }
+for k in 0..<10 { down(k) }
+out(down(50))
+down(500)"#;
+    let mut interpreter = Interpreter::new();
+    interpreter.enable_native_jit(3).unwrap();
+    interpreter.set_max_call_depth(100);
+    interpreter.set_output_capture(true);
+    let err = interpreter.run(deep).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::StackOverflow);
+    assert_eq!(interpreter.take_captured_output().unwrap().trim(), "50");
+}
+
+#[test]
+fn functions_with_side_effects_stay_interpreted() {
+    let source = r#"fn | shout ( int | n ) > {
+  out(n)
+  n
+}
+for k in 0..<5 { shout(k) }"#;
+    let (output, native) = run_native(source, 2).unwrap();
+    assert_eq!(output, "0\n1\n2\n3\n4\n");
+    assert!(native.is_empty());
+}
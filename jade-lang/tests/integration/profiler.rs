@@ -0,0 +1,159 @@
+//! `jade run --profile`: calls, builtin leaf frames and statements timed by the interpreter's
+//! profiler, and the tables and folded stacks the CLI writes from them.
+
+mod common;
+
+use common::scratch_dir;
+use j_lang::interpreter::{Interpreter, Profiler};
+use std::fs;
+use std::process::Command;
+use std::time::Duration;
+
+const PROGRAM: &str = "fn | fib ( int | n ) > {
+  if n < 2 : n else : fib(n - 1) + fib(n - 2)
+}
+
+fn | twice ( int | n ) > {
+  n * 2
+}
+
+int: a = fib(10)
+out(twice(a))
+";
+
+fn profile(source: &str) -> Profiler {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output_capture(true);
+    interpreter.set_profiler(Profiler::new("main.jdl"));
+    interpreter.run(source).unwrap();
+    interpreter.take_profiler().unwrap()
+}
+
+#[test]
+fn calls_are_counted_per_function_and_builtin() {
+    let profiler = profile(PROGRAM);
+    let mut calls: Vec<_> = profiler
+        .functions()
+        .into_iter()
+        .map(|function| (function.name, function.calls))
+        .collect();
+    calls.sort();
+    let expected = [("<module>", 1), ("fib", 177), ("out", 1), ("twice", 1)];
+    let expected: Vec<_> = expected
+        .map(|(name, calls)| (name.to_string(), calls))
+        .into();
+    assert_eq!(calls, expected);
+
+    let fib = profiler
+        .functions()
+        .into_iter()
+        .find(|f| f.name == "fib")
+        .unwrap();
+    let module = profiler
+        .functions()
+        .into_iter()
+        .find(|f| f.name == "<module>")
+        .unwrap();
+    // Recursive calls are inside the outermost one, so they add no total time of their own
+    assert!(fib.self_time <= fib.total_time && fib.total_time <= module.total_time);
+
+    let mut lines: Vec<_> = profiler
+        .lines()
+        .into_iter()
+        .map(|line| (line.file, line.line, line.hits))
+        .collect();
+    lines.sort();
+    let expected = [(2, 177), (6, 1), (9, 1), (10, 1)];
+    let expected: Vec<_> = expected
+        .map(|(line, hits)| ("main.jdl".to_string(), line, hits))
+        .into();
+    assert_eq!(lines, expected);
+}
+
+#[test]
+fn builtins_are_leaf_frames_and_stacks_add_up() {
+    let profiler = profile(PROGRAM);
+    let stacks = profiler.stacks();
+    let names: Vec<_> = stacks.iter().map(|(stack, _)| stack.as_str()).collect();
+    // `twice` runs while `out` evaluates its argument, but is stacked under the caller
+    assert!(names.contains(&"<module>;out"), "{:?}", names);
+    assert!(names.contains(&"<module>;twice"), "{:?}", names);
+    assert!(
+        !names.iter().any(|stack| stack.contains("out;")),
+        "{:?}",
+        names
+    );
+    let deepest = format!("<module>{}", ";fib".repeat(10));
+    assert!(names.contains(&deepest.as_str()), "{:?}", names);
+    assert!(
+        !names.contains(&format!("{};fib", deepest).as_str()),
+        "{:?}",
+        names
+    );
+
+    // `out` is not charged for `twice`, which ran in its argument
+    let out = profiler
+        .functions()
+        .into_iter()
+        .find(|f| f.name == "out")
+        .unwrap();
+    assert_eq!(out.total_time, out.self_time);
+
+    // Every moment of the run is charged to exactly one stack
+    let charged: Duration = stacks.iter().map(|(_, time)| *time).sum();
+    let module = profiler
+        .functions()
+        .into_iter()
+        .find(|f| f.name == "<module>")
+        .unwrap();
+    assert_eq!(charged, module.total_time);
+}
+
+#[test]
+fn cli_prints_the_slowest_functions_and_writes_folded_stacks() {
+    let dir = scratch_dir("profile-cli", &[("main.jdl", PROGRAM)]);
+    let output = Command::new(env!("CARGO_BIN_EXE_jade"))
+        .args([
+            "run",
+            "--profile",
+            "--profile-top",
+            "3",
+            "--profile-out",
+            "fib.folded",
+        ])
+        .arg("main.jdl")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "110\n");
+
+    let rows: Vec<Vec<&str>> = stderr
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    let header = rows
+        .iter()
+        .position(|row| row.first() == Some(&"function"))
+        .unwrap();
+    assert_eq!(
+        rows[header],
+        ["function", "calls", "self", "ms", "total", "ms"]
+    );
+    assert_eq!(rows[header + 1][..2], ["fib", "177"], "{}", stderr);
+    let lines = rows
+        .iter()
+        .position(|row| row.first() == Some(&"line"))
+        .unwrap();
+    assert_eq!(lines - header, 4, "three functions are listed\n{}", stderr);
+    assert_eq!(rows[lines + 1][..2], ["main.jdl:2", "177"], "{}", stderr);
+
+    let folded = fs::read_to_string(dir.join("fib.folded")).unwrap();
+    assert!(folded.contains("<module>;fib;fib "), "{}", folded);
+    for line in folded.lines() {
+        let (stack, micros) = line.rsplit_once(' ').unwrap();
+        assert!(stack.starts_with("<module>"), "{}", line);
+        assert!(micros.parse::<u64>().unwrap() > 0, "{}", line);
+    }
+}
@@ -0,0 +1,31 @@
+//! Deep recursion: configurable call depth limit, no native stack overflow.
+
+use j_lang::error::ErrorKind;
+use j_lang::{Interpreter, JResult};
+
+fn run_with_depth(source: &str, depth: usize) -> JResult<String> {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_max_call_depth(depth);
+    interpreter.set_output_capture(true);
+    interpreter.run(source)?;
+    Ok(interpreter.take_captured_output().unwrap_or_default())
+}
+
+const COUNT_DOWN: &str = r#"fn | depth_of ( int | n ) > {
+  if n == 0 { 0 } else { 1 + depth_of(n - 1) }
+}
+out(depth_of(5000))"#;
+
+#[test]
+fn default_limit_allows_deep_recursion_on_a_small_thread() {
+    // Test threads have small native stacks; evaluation must grow onto the heap.
+    let out = run_with_depth(COUNT_DOWN, j_lang::interpreter::DEFAULT_MAX_CALL_DEPTH).unwrap();
+    assert_eq!(out.trim(), "5000");
+}
+
+#[test]
+fn exceeding_the_limit_is_a_stack_overflow_error() {
+    let err = run_with_depth(COUNT_DOWN, 100).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::StackOverflow);
+    assert!(err.message.contains("depth: 100"), "{}", err);
+}
@@ -0,0 +1,79 @@
+//! `test` blocks, `assert` statements, and the `jade test` runner.
+
+use j_lang::testing::{run_test_source, FileReport, TestOptions};
+
+fn run_tests(source: &str) -> FileReport {
+    run_tests_with(source, TestOptions::default())
+}
+
+fn run_tests_with(source: &str, options: TestOptions) -> FileReport {
+    run_test_source(source, "inline.jdl", &[], &options)
+}
+
+#[test]
+fn passing_and_failing_tests_are_counted() {
+    let report = run_tests(
+        r#"fn | plus ( int | a, int | b ) > { return a + b }
+
+test "adds" {
+  assert plus(2, 3) == 5
+}
+
+test "fails with message" {
+  out("debug line")
+  assert plus(1, 1) == 3, "math is broken"
+}
+"#,
+    );
+    assert!(report.load_error.is_none(), "{:?}", report.load_error);
+    assert_eq!(report.passed(), 1);
+    assert_eq!(report.failed(), 1);
+    let failed = &report.outcomes[1];
+    let msg = failed.failure.as_deref().unwrap();
+    assert!(msg.contains("math is broken"), "{}", msg);
+    assert!(msg.contains("left: 2, right: 3"), "{}", msg);
+    assert!(failed.output.contains("debug line"));
+}
+
+#[test]
+fn each_test_gets_a_fresh_scope() {
+    let report = run_tests(
+        r#"test "defines x" {
+  int: x = 1
+  assert x == 1
+}
+
+test "does not see x" {
+  assert x == 1
+}
+"#,
+    );
+    assert_eq!(report.passed(), 1);
+    assert_eq!(report.failed(), 1);
+}
+
+#[test]
+fn property_failure_is_shrunk_and_replayable() {
+    let source = r#"property "reverse twice" (list | xs) {
+  assert reverse(reverse(xs)) == xs
+}
+
+property "ints stay below 50" (int | n) {
+  assert n < 50
+}
+"#;
+    let options = TestOptions {
+        seed: Some(7),
+        cases: 200,
+        ..Default::default()
+    };
+    let report = run_tests_with(source, options.clone());
+    assert_eq!(report.passed(), 1);
+    let msg = report.outcomes[1].failure.clone().unwrap();
+    assert!(msg.contains("seed 7"), "{}", msg);
+    assert!(msg.contains("n = 50"), "{}", msg);
+
+    // Same seed, same counterexample
+    let again = run_tests_with(source, options);
+    assert_eq!(again.outcomes[1].failure.as_deref(), Some(msg.as_str()));
+}
@@ -0,0 +1,84 @@
+//! Runtime errors are structured `JError` values, in Rust and in `try`/`catch`.
+
+use j_lang::error::{explain, ErrorKind};
+use j_lang::{run_source_to_string, Interpreter};
+
+#[test]
+fn errors_carry_kind_location_and_traceback() {
+    let source = "fn | inner ( list | xs ) > { xs[3] }\nfn | outer ( ) > { inner([1]) }\nouter()\n";
+    let mut interpreter = Interpreter::new();
+    let error = interpreter.run(source).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::IndexOutOfBounds);
+    assert_eq!(
+        error.message,
+        "Index 3 is out of bounds for list of length 1"
+    );
+    assert_eq!((error.line, error.column), (Some(1), Some(32)));
+    let functions: Vec<&str> = error.trace.iter().map(|e| e.function.as_str()).collect();
+    assert_eq!(functions, ["<module>", "outer", "inner"]);
+}
+
+#[test]
+fn caught_errors_expose_their_fields() {
+    let out = run_source_to_string(
+        "dict: d = {\"a\": 1}\ntry { d[\"b\"] } catch e {\n  out(e.kind, e.code, e.line, e.column)\n  out(e)\n}\n",
+    )
+    .unwrap();
+    assert_eq!(out, "KeyNotFound J0305 2 8\nKey 'b' not found in dictionary\n");
+}
+
+#[test]
+fn panics_keep_their_message_and_rethrow_unchanged() {
+    let out = run_source_to_string(
+        "try { panic \"x is not defined yet\" } catch e { out(e.kind, e.message) }\n",
+    )
+    .unwrap();
+    assert_eq!(out, "Thrown x is not defined yet\n");
+
+    let mut interpreter = Interpreter::new();
+    let error = interpreter
+        .run("int: n = 0\ntry { out(1 / n) } catch e { panic e }\n")
+        .unwrap_err();
+    assert_eq!(error.kind, ErrorKind::DivisionByZero);
+    assert_eq!((error.line, error.column), (Some(2), Some(13)));
+}
+
+#[test]
+fn every_error_kind_has_a_stable_documented_code() {
+    let mut codes: Vec<&str> = ErrorKind::ALL.iter().map(ErrorKind::code).collect();
+    for (kind, code) in ErrorKind::ALL.iter().zip(&codes) {
+        assert_eq!(ErrorKind::from_code(code).as_ref(), Some(kind));
+        let entry = explain(code).unwrap_or_else(|| panic!("{} is not in the error index", code));
+        assert!(entry.starts_with(&format!("## {}: ", code)), "{}", entry);
+    }
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), ErrorKind::ALL.len());
+
+    assert_eq!(ErrorKind::DivisionByZero.code(), "J0303");
+    assert!(explain("j0303").unwrap().contains("out(10 / n)"));
+    assert_eq!(explain("J9999"), None);
+}
+
+#[test]
+fn reported_errors_show_their_code() {
+    let error = Interpreter::new().run("out(1 / 0)\n").unwrap_err();
+    assert!(error.to_string().contains("ERROR[J0303]: Cannot divide by zero"));
+}
+
+#[test]
+fn kinds_come_from_the_raise_site_not_the_message() {
+    let kind = |source: &str| {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_output_capture(true);
+        interpreter.run(source).unwrap_err().kind
+    };
+    assert_eq!(kind("gcd(4)\n"), ErrorKind::WrongArgumentCount);
+    assert_eq!(kind("gcd(4, \"x\")\n"), ErrorKind::InvalidArgument);
+    assert_eq!(kind("list: xs = [1]\nxs.frobnicate()\n"), ErrorKind::InvalidOperation);
+    // Text that reads like another kind's message does not change the kind
+    assert_eq!(
+        kind("assert(false, \"Function 'f' not found\")\n"),
+        ErrorKind::InvalidOperation
+    );
+}